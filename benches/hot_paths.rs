@@ -0,0 +1,131 @@
+//! Benchmarks for the user-facing hot paths
+//!
+//! Each benchmark runs the real `pais` binary against a generated fixture
+//! (40 skills, 500 history entries) in a temporary PAIS_DIR, so process
+//! startup and config loading are part of the measurement, just as they are
+//! when Claude Code invokes a hook.
+//!
+//! Budgets (release build, median) are documented in docs/performance.md:
+//!
+//! | Path | Budget |
+//! |------|--------|
+//! | hook dispatch | 50 ms |
+//! | context inject | 100 ms |
+//! | skill index | 250 ms |
+//! | history query | 150 ms |
+//!
+//! Run with `cargo bench`; `pais bench` checks the same paths against a live config.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use tempfile::TempDir;
+
+const SKILLS: usize = 40;
+const HISTORY_ENTRIES: usize = 500;
+
+struct Fixture {
+    _dir: TempDir,
+    root: PathBuf,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let dir = TempDir::new().expect("Failed to create fixture dir");
+        let root = dir.path().to_path_buf();
+        let skills = root.join("skills");
+        let history = root.join("history");
+
+        for i in 0..SKILLS {
+            let skill = skills.join(format!("skill-{:02}", i));
+            fs::create_dir_all(&skill).unwrap();
+            let tier = if i < 3 { "core" } else { "deferred" };
+            fs::write(
+                skill.join("SKILL.md"),
+                format!(
+                    "---\nname: skill-{i:02}\ndescription: Benchmark skill {i}. USE WHEN working on area {i}.\n\
+                     tier: {tier}\n---\n\n# Skill {i}\n\n{}\n",
+                    "Guidance paragraph for the benchmark skill.\n".repeat(20)
+                ),
+            )
+            .unwrap();
+        }
+
+        for i in 0..HISTORY_ENTRIES {
+            let day = history
+                .join(if i % 3 == 0 { "learnings" } else { "sessions" })
+                .join(format!("2026-01-{:02}", i % 28 + 1));
+            fs::create_dir_all(&day).unwrap();
+            fs::write(
+                day.join(format!("{:011x}.md", i)),
+                format!(
+                    "---\nid: {i:011x}\ntitle: Session {i}\ncategory: sessions\n\
+                     created_at: 2026-01-{:02}T10:00:00+0000\n---\n\n# Session {i}\n\n{}\n",
+                    i % 28 + 1,
+                    "Worked through a change to the session handling code.\n".repeat(10)
+                ),
+            )
+            .unwrap();
+        }
+
+        fs::create_dir_all(root.join("plugins")).unwrap();
+        fs::write(
+            root.join("pais.yaml"),
+            format!(
+                "log-level: off\npaths:\n  skills: {}\n  history: {}\n  plugins: {}\n\
+                 hooks:\n  history-enabled: false\n  ui-enabled: false\nobservability:\n  enabled: false\n",
+                skills.display(),
+                history.display(),
+                root.join("plugins").display()
+            ),
+        )
+        .unwrap();
+
+        Self { _dir: dir, root }
+    }
+
+    fn pais(&self, args: &[&str]) {
+        let status = Command::new(env!("CARGO_BIN_EXE_pais"))
+            .env("PAIS_DIR", &self.root)
+            .env("HOME", &self.root)
+            .env("XDG_CACHE_HOME", self.root.join("cache"))
+            .env("XDG_DATA_HOME", self.root.join("data"))
+            .arg("--config")
+            .arg(self.root.join("pais.yaml"))
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .expect("Failed to run pais");
+        assert!(status.success(), "pais {:?} failed: {}", args, status);
+    }
+}
+
+fn hot_paths(c: &mut Criterion) {
+    let fixture = Fixture::new();
+    // The index feeds context injection; build it once up front
+    fixture.pais(&["skill", "index"]);
+    assert!(fixture.root.join("skills").join("skill-index.yaml").exists());
+
+    let payload = r#"{"tool_name":"Bash","tool_input":{"command":"ls -la"},"session_id":"bench"}"#;
+
+    let mut group = c.benchmark_group("hot_paths");
+    group.sample_size(20).measurement_time(Duration::from_secs(5));
+
+    group.bench_function("hook_dispatch", |b| {
+        b.iter(|| fixture.pais(&["hook", "dispatch", "PreToolUse", "--payload", payload]))
+    });
+    group.bench_function("context_inject", |b| b.iter(|| fixture.pais(&["context", "inject"])));
+    group.bench_function("skill_index", |b| b.iter(|| fixture.pais(&["skill", "index"])));
+    group.bench_function("history_query", |b| {
+        b.iter(|| fixture.pais(&["history", "query", "session", "--limit", "10"]))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, hot_paths);
+criterion_main!(benches);
@@ -6,7 +6,7 @@ use eyre::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use super::traits::Trait;
 
@@ -38,6 +38,14 @@ pub struct Agent {
     /// Communication style examples
     #[serde(default)]
     pub communication_style: Vec<String>,
+
+    /// Skills (names or profiles) `pais agent run` includes by default
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skills: Vec<String>,
+
+    /// MCP servers (names or profiles) `pais agent run` enables by default
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mcp: Vec<String>,
 }
 
 impl Agent {
@@ -72,7 +80,11 @@ impl Agent {
 /// Agent loader for discovering and loading agents
 pub struct AgentLoader {
     agents_dir: std::path::PathBuf,
+    /// Team agent directories, as (team, directory)
+    team_dirs: Vec<(String, PathBuf)>,
     cache: HashMap<String, Agent>,
+    /// Team each team-provided agent came from
+    teams: HashMap<String, String>,
 }
 
 impl AgentLoader {
@@ -80,33 +92,35 @@ impl AgentLoader {
     pub fn new(agents_dir: std::path::PathBuf) -> Self {
         Self {
             agents_dir,
+            team_dirs: Vec::new(),
             cache: HashMap::new(),
+            teams: HashMap::new(),
         }
     }
 
-    /// Load all agents from the agents directory
-    pub fn load_all(&mut self) -> Result<Vec<Agent>> {
-        let mut agents = Vec::new();
+    /// Also load agents from team repos (see [`crate::team`]); agents in the
+    /// agents directory win over team agents of the same name
+    pub fn with_team_dirs(mut self, team_dirs: Vec<(String, PathBuf)>) -> Self {
+        self.team_dirs = team_dirs;
+        self
+    }
 
-        if !self.agents_dir.exists() {
-            return Ok(agents);
+    /// Load all agents from the agents directory (and team directories)
+    pub fn load_all(&mut self) -> Result<Vec<Agent>> {
+        let mut agents = self.load_dir(&self.agents_dir.clone())?;
+        for agent in &agents {
+            self.cache.insert(agent.name.clone(), agent.clone());
         }
 
-        let entries = fs::read_dir(&self.agents_dir)
-            .with_context(|| format!("Failed to read agents directory: {}", self.agents_dir.display()))?;
-
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.extension().map(|e| e == "yaml" || e == "yml").unwrap_or(false) {
-                match self.load_agent(&path) {
-                    Ok(agent) => {
-                        self.cache.insert(agent.name.clone(), agent.clone());
-                        agents.push(agent);
-                    }
-                    Err(e) => {
-                        log::warn!("Failed to load agent from {}: {}", path.display(), e);
-                    }
+        for (team, dir) in self.team_dirs.clone() {
+            for agent in self.load_dir(&dir)? {
+                if self.cache.contains_key(&agent.name) {
+                    log::debug!("Team '{}' agent '{}' is shadowed", team, agent.name);
+                    continue;
                 }
+                self.teams.insert(agent.name.clone(), team.clone());
+                self.cache.insert(agent.name.clone(), agent.clone());
+                agents.push(agent);
             }
         }
 
@@ -116,6 +130,48 @@ impl AgentLoader {
         Ok(agents)
     }
 
+    /// Agents defined in one directory
+    fn load_dir(&self, dir: &Path) -> Result<Vec<Agent>> {
+        let mut agents = Vec::new();
+        if !dir.exists() {
+            return Ok(agents);
+        }
+
+        let entries =
+            fs::read_dir(dir).with_context(|| format!("Failed to read agents directory: {}", dir.display()))?;
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().map(|e| e == "yaml" || e == "yml").unwrap_or(false))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            match self.load_agent(&path) {
+                Ok(agent) => agents.push(agent),
+                Err(e) => {
+                    log::warn!("Failed to load agent from {}: {}", path.display(), e);
+                }
+            }
+        }
+        Ok(agents)
+    }
+
+    /// File defining the agent `name`: in the agents directory, else in the
+    /// first team directory that has it
+    pub fn find(&self, name: &str) -> Option<PathBuf> {
+        let file = format!("{}.yaml", name);
+        std::iter::once(&self.agents_dir)
+            .chain(self.team_dirs.iter().map(|(_, dir)| dir))
+            .map(|dir| dir.join(&file))
+            .find(|path| path.exists())
+    }
+
+    /// Team an agent loaded by [`Self::load_all`] came from, if any
+    pub fn team_of(&self, name: &str) -> Option<&str> {
+        self.teams.get(name).map(String::as_str)
+    }
+
     /// Load a single agent from a file
     pub fn load_agent(&self, path: &Path) -> Result<Agent> {
         let content =
@@ -152,6 +208,8 @@ mod tests {
             prompt_prefix: None,
             history_category: Some("research".to_string()),
             communication_style: vec!["Direct".to_string(), "Questioning".to_string()],
+            skills: vec![],
+            mcp: vec![],
         };
 
         let prompt = agent.generate_prompt();
@@ -173,6 +231,8 @@ mod tests {
             prompt_prefix: Some("Custom prefix override".to_string()),
             history_category: None,
             communication_style: vec![],
+            skills: vec![],
+            mcp: vec![],
         };
 
         let prompt = agent.generate_prompt();
@@ -202,4 +262,26 @@ communication_style:
         assert_eq!(agent.traits.len(), 3);
         assert!(agent.traits.contains(&Trait::Enthusiastic));
     }
+
+    #[test]
+    fn test_team_agents_fill_in() {
+        let temp = tempfile::tempdir().unwrap();
+        let (mine, team) = (temp.path().join("agents"), temp.path().join("acme"));
+        fs::create_dir_all(&mine).unwrap();
+        fs::create_dir_all(&team).unwrap();
+        fs::write(mine.join("intern.yaml"), "name: intern\ndescription: mine\n").unwrap();
+        fs::write(team.join("intern.yaml"), "name: intern\ndescription: theirs\n").unwrap();
+        fs::write(team.join("reviewer.yaml"), "name: reviewer\ndescription: theirs\n").unwrap();
+
+        let mut loader = AgentLoader::new(mine).with_team_dirs(vec![("acme".to_string(), team.clone())]);
+        let agents = loader.load_all().unwrap();
+        assert_eq!(
+            agents.iter().map(|a| a.name.as_str()).collect::<Vec<_>>(),
+            ["intern", "reviewer"]
+        );
+        assert_eq!(loader.get("intern").unwrap().description, "mine");
+        assert_eq!(loader.team_of("intern"), None);
+        assert_eq!(loader.team_of("reviewer"), Some("acme"));
+        assert_eq!(loader.find("reviewer"), Some(team.join("reviewer.yaml")));
+    }
 }
@@ -6,4 +6,5 @@
 //! - Communication style
 
 pub mod loader;
+pub mod runner;
 pub mod traits;
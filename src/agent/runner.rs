@@ -0,0 +1,169 @@
+//! Headless agent runs
+//!
+//! `pais agent run` hands a task to `claude -p` with the agent's prompt and
+//! the selected skills appended to the system prompt. Skills are inlined
+//! rather than symlinked into `~/.claude/skills/` (as `pais session` does) so
+//! parallel runs with different skill sets don't disturb each other.
+
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::Command;
+
+use super::loader::Agent;
+
+/// One task for one agent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AgentTask {
+    pub agent: String,
+    pub task: String,
+}
+
+/// Parse a task list: one task per line, optionally prefixed with `agent:`.
+///
+/// The prefix only counts when it names a known agent, so tasks like
+/// "fix: flaky test" still go to `default_agent`. Blank lines and `#`
+/// comments are skipped.
+pub fn parse_tasks(content: &str, default_agent: Option<&str>, agents: &[String]) -> Result<Vec<AgentTask>> {
+    let mut tasks = Vec::new();
+    for (n, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let prefixed = line
+            .split_once(':')
+            .map(|(agent, task)| (agent.trim(), task.trim()))
+            .filter(|(agent, task)| !task.is_empty() && agents.iter().any(|a| a == agent));
+        let (agent, task) = match (prefixed, default_agent) {
+            (Some((agent, task)), _) => (agent, task),
+            (None, Some(agent)) => (agent, line),
+            (None, None) => eyre::bail!("Line {}: no agent given (write it as 'agent: task')", n + 1),
+        };
+        tasks.push(AgentTask {
+            agent: agent.to_string(),
+            task: task.to_string(),
+        });
+    }
+    Ok(tasks)
+}
+
+/// System prompt addition: the agent's prompt followed by each skill's SKILL.md
+pub fn system_prompt(agent: &Agent, skills: &[(String, String)]) -> String {
+    let mut prompt = agent.generate_prompt();
+    for (name, content) in skills {
+        prompt.push_str(&format!("\n\n<skill name=\"{}\">\n{}\n</skill>", name, content.trim()));
+    }
+    prompt
+}
+
+/// The `claude -p` invocation for a task
+///
+/// The task goes last, after `--`, so a task starting with `-` isn't read as a flag.
+pub fn command(task: &str, system_prompt: &str, launch_flags: Vec<OsString>, cwd: &Path) -> Command {
+    let mut cmd = Command::new("claude");
+    cmd.arg("-p")
+        .arg("--append-system-prompt")
+        .arg(system_prompt)
+        .args(["--output-format", "json"])
+        .args(launch_flags)
+        .arg("--")
+        .arg(task)
+        .current_dir(cwd);
+    cmd
+}
+
+/// The final message of `claude -p --output-format json`
+#[derive(Debug, Default, Deserialize)]
+pub struct ClaudeResult {
+    #[serde(default)]
+    pub result: String,
+    #[serde(default)]
+    pub is_error: bool,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub total_cost_usd: Option<f64>,
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+}
+
+/// Parse claude's stdout, treating anything that isn't the JSON result as plain text output
+pub fn parse_output(stdout: &str) -> ClaudeResult {
+    serde_json::from_str(stdout.trim()).unwrap_or_else(|_| ClaudeResult {
+        result: stdout.trim().to_string(),
+        ..Default::default()
+    })
+}
+
+/// Run a prepared command, returning its parsed result and whether it succeeded
+pub fn execute(mut cmd: Command) -> Result<(ClaudeResult, bool)> {
+    let output = cmd
+        .output()
+        .context("Failed to run claude (is Claude Code installed?)")?;
+    let mut result = parse_output(&String::from_utf8_lossy(&output.stdout));
+    if result.result.is_empty() && !output.status.success() {
+        result.result = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    }
+    let ok = output.status.success() && !result.is_error;
+    Ok((result, ok))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tasks() {
+        let agents = vec!["architect".to_string(), "intern".to_string()];
+        let content = "# review batch\narchitect: design the cache\n\nfix: flaky test\nintern:  write docs\n";
+
+        let tasks = parse_tasks(content, Some("intern"), &agents).unwrap();
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[0].agent, "architect");
+        assert_eq!(tasks[0].task, "design the cache");
+        assert_eq!(tasks[1].agent, "intern");
+        assert_eq!(tasks[1].task, "fix: flaky test");
+        assert_eq!(tasks[2].task, "write docs");
+
+        let err = parse_tasks(content, None, &agents).unwrap_err();
+        assert!(err.to_string().contains("Line 4"));
+    }
+
+    #[test]
+    fn test_command_ends_options_before_task() {
+        let cmd = command(
+            "--dangerously-skip-permissions rm -rf",
+            "prompt",
+            vec!["--model".into(), "opus".into()],
+            Path::new("."),
+        );
+        let args: Vec<_> = cmd.get_args().map(|a| a.to_string_lossy().into_owned()).collect();
+        assert_eq!(
+            args,
+            [
+                "-p",
+                "--append-system-prompt",
+                "prompt",
+                "--output-format",
+                "json",
+                "--model",
+                "opus",
+                "--",
+                "--dangerously-skip-permissions rm -rf"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_output() {
+        let json = r#"{"type":"result","is_error":false,"result":"Done.","session_id":"abc","total_cost_usd":0.02}"#;
+        let result = parse_output(json);
+        assert_eq!(result.result, "Done.");
+        assert_eq!(result.session_id.as_deref(), Some("abc"));
+        assert_eq!(result.total_cost_usd, Some(0.02));
+
+        assert_eq!(parse_output("plain text\n").result, "plain text");
+    }
+}
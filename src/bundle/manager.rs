@@ -2,19 +2,22 @@
 
 use colored::*;
 use eyre::{Context, Result};
+use indicatif::ProgressBar;
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use super::manifest::BundleManifest;
+use crate::presenter;
 
 /// A discovered bundle
 #[derive(Debug)]
 pub struct DiscoveredBundle {
     pub manifest: BundleManifest,
-    #[allow(dead_code)] // Used for future remote plugin installation
     pub path: PathBuf,
+    /// Team repo it came from (see [`crate::team`])
+    pub team: Option<String>,
 }
 
 /// Bundle manager for discovery and installation
@@ -22,6 +25,8 @@ pub struct BundleManager {
     pub bundles: HashMap<String, DiscoveredBundle>,
     pub bundles_dir: PathBuf,
     pub plugins_dir: PathBuf,
+    /// Team bundle directories, as (team, directory)
+    pub team_dirs: Vec<(String, PathBuf)>,
 }
 
 impl BundleManager {
@@ -30,20 +35,36 @@ impl BundleManager {
             bundles: HashMap::new(),
             bundles_dir,
             plugins_dir,
+            team_dirs: Vec::new(),
         }
     }
 
-    /// Discover all bundles in the bundles directory
+    /// Also discover bundles from team repos; bundles in the bundles
+    /// directory win over team bundles of the same name
+    pub fn with_team_dirs(mut self, team_dirs: Vec<(String, PathBuf)>) -> Self {
+        self.team_dirs = team_dirs;
+        self
+    }
+
+    /// Discover all bundles in the bundles directory (and team directories)
     pub fn discover(&mut self) -> Result<usize> {
         self.bundles.clear();
 
-        if !self.bundles_dir.exists() {
+        let mut count = self.discover_dir(&self.bundles_dir.clone(), None)?;
+        for (team, dir) in self.team_dirs.clone() {
+            count += self.discover_dir(&dir, Some(&team))?;
+        }
+        Ok(count)
+    }
+
+    fn discover_dir(&mut self, dir: &Path, team: Option<&str>) -> Result<usize> {
+        if !dir.exists() {
             return Ok(0);
         }
 
         let mut count = 0;
 
-        for entry in fs::read_dir(&self.bundles_dir).context("Failed to read bundles directory")? {
+        for entry in fs::read_dir(dir).context("Failed to read bundles directory")? {
             let entry = entry?;
             let path = entry.path();
 
@@ -59,7 +80,12 @@ impl BundleManager {
             match BundleManifest::load(&manifest_path) {
                 Ok(manifest) => {
                     let name = manifest.bundle.name.clone();
-                    self.bundles.insert(name, DiscoveredBundle { manifest, path });
+                    if team.is_some() && self.bundles.contains_key(&name) {
+                        log::debug!("Team bundle '{}' is shadowed", name);
+                        continue;
+                    }
+                    let team = team.map(String::from);
+                    self.bundles.insert(name, DiscoveredBundle { manifest, path, team });
                     count += 1;
                 }
                 Err(e) => {
@@ -81,8 +107,14 @@ impl BundleManager {
         self.bundles.values()
     }
 
-    /// Install a bundle
-    pub fn install(&self, name: &str, required_only: bool, skip_verify: bool) -> Result<InstallResult> {
+    /// Install a bundle, advancing `progress` once per plugin and post-install command
+    pub fn install(
+        &self,
+        name: &str,
+        required_only: bool,
+        skip_verify: bool,
+        progress: &ProgressBar,
+    ) -> Result<InstallResult> {
         let bundle = self
             .get(name)
             .ok_or_else(|| eyre::eyre!("Bundle not found: {}", name))?;
@@ -102,81 +134,109 @@ impl BundleManager {
         };
 
         let total = plugins_to_install.len();
+        progress.set_length((total + bundle.manifest.post_install.len()) as u64);
 
         for (index, (plugin_name, plugin_ref)) in plugins_to_install.iter().enumerate() {
             let step = index + 1;
             let required_str = if plugin_ref.required { "" } else { " (optional)" };
 
-            println!(
-                "\n[{}/{}] Installing {}{}...",
-                step,
-                total,
-                plugin_name.cyan(),
-                required_str.dimmed()
-            );
-
-            // Check if plugin is already installed
-            let plugin_path = self.plugins_dir.join(plugin_name);
-            if plugin_path.exists() {
-                println!("  {} Already installed", "→".blue());
-                result.skipped.push(plugin_name.to_string());
-                continue;
-            }
-
-            // For now, we only support local plugins
-            // Check if plugin exists in plugins directory or as a known location
-            if plugin_ref.source.is_some() {
-                println!("  {} Remote sources not yet supported", "⚠".yellow());
-                result.skipped.push(plugin_name.to_string());
-                continue;
-            }
-
-            // Try to find plugin in common locations
-            // For now, just report that it needs manual installation
-            println!("  {} Plugin not found. Install manually:", "!".yellow());
-            println!("    pais plugin install /path/to/{}", plugin_name);
-            result.failed.push(plugin_name.to_string());
+            progress.set_message(plugin_name.to_string());
+            progress.suspend(|| {
+                self.install_plugin(
+                    step,
+                    total,
+                    plugin_name,
+                    plugin_ref.source.is_some(),
+                    required_str,
+                    &mut result,
+                )
+            });
+            progress.inc(1);
         }
 
         // Run verification if not skipped
         if !skip_verify && !result.installed.is_empty() {
-            println!("\n{}", "Running verification...".bold());
+            progress.suspend(|| println!("\n{}", "Running verification...".bold()));
             for plugin_name in &result.installed {
+                progress.set_message(format!("verifying {}", plugin_name));
                 let verify_output = Command::new("pais").args(["plugin", "verify", plugin_name]).output();
 
-                match verify_output {
+                progress.suspend(|| match verify_output {
                     Ok(output) if output.status.success() => {
-                        println!("  {} {} verified", "✓".green(), plugin_name);
+                        println!("  {} {} verified", presenter::ok(), plugin_name);
                     }
                     _ => {
-                        println!("  {} {} verification failed", "✗".red(), plugin_name);
+                        println!("  {} {} verification failed", presenter::fail(), plugin_name);
                     }
-                }
+                });
             }
         }
 
         // Run post-install commands
         if !bundle.manifest.post_install.is_empty() {
-            println!("\n{}", "Post-install:".bold());
+            progress.suspend(|| println!("\n{}", "Post-install:".bold()));
             for cmd in &bundle.manifest.post_install {
                 let desc = cmd.description.as_deref().unwrap_or(&cmd.command);
-                print!("  - {}... ", desc);
+                progress.set_message(desc.to_string());
 
                 let output = Command::new("sh").arg("-c").arg(&cmd.command).output();
 
-                match output {
+                progress.suspend(|| match output {
                     Ok(o) if o.status.success() => {
-                        println!("{}", "done".green());
+                        println!("  - {}... {}", desc, "done".green());
                     }
                     _ => {
-                        println!("{}", "failed".red());
+                        println!("  - {}... {}", desc, "failed".red());
                     }
-                }
+                });
+                progress.inc(1);
             }
         }
 
+        progress.finish_and_clear();
         Ok(result)
     }
+
+    /// Report on and record one plugin of a bundle install
+    fn install_plugin(
+        &self,
+        step: usize,
+        total: usize,
+        plugin_name: &str,
+        has_source: bool,
+        required_str: &str,
+        result: &mut InstallResult,
+    ) {
+        println!(
+            "\n[{}/{}] Installing {}{}...",
+            step,
+            total,
+            plugin_name.cyan(),
+            required_str.dimmed()
+        );
+
+        // Check if plugin is already installed
+        let plugin_path = self.plugins_dir.join(plugin_name);
+        if plugin_path.exists() {
+            println!("  {} Already installed", presenter::arrow());
+            result.skipped.push(plugin_name.to_string());
+            return;
+        }
+
+        // For now, we only support local plugins
+        // Check if plugin exists in plugins directory or as a known location
+        if has_source {
+            println!("  {} Remote sources not yet supported", presenter::warn());
+            result.skipped.push(plugin_name.to_string());
+            return;
+        }
+
+        // Try to find plugin in common locations
+        // For now, just report that it needs manual installation
+        println!("  {} Plugin not found. Install manually:", "!".yellow());
+        println!("    pais plugin install /path/to/{}", plugin_name);
+        result.failed.push(plugin_name.to_string());
+    }
 }
 
 /// Result of bundle installation
@@ -255,6 +315,22 @@ plugins:
         assert!(manager.get("bundle-b").is_some());
     }
 
+    #[test]
+    fn test_team_bundles_are_shadowed() {
+        let temp = tempdir().unwrap();
+        let (mine, team) = (temp.path().join("bundles"), temp.path().join("team"));
+        create_test_bundle(&mine, "shared");
+        create_test_bundle(&team, "shared");
+        create_test_bundle(&team, "extra");
+
+        let mut manager = BundleManager::new(mine.clone(), temp.path().join("plugins"))
+            .with_team_dirs(vec![("acme".to_string(), team)]);
+        assert_eq!(manager.discover().unwrap(), 2);
+        assert!(manager.get("shared").unwrap().path.starts_with(&mine));
+        assert_eq!(manager.get("shared").unwrap().team, None);
+        assert_eq!(manager.get("extra").unwrap().team.as_deref(), Some("acme"));
+    }
+
     #[test]
     fn test_discover_empty_directory() {
         let temp = tempdir().unwrap();
@@ -0,0 +1,361 @@
+//! Git checkpoints of agent edits
+//!
+//! The checkpoint hook counts file modifications per session and, every N
+//! edits, snapshots the working tree of the session's repository. Snapshots
+//! never touch the branch, `HEAD` or the index:
+//!
+//! - `ref` mode writes a commit chain under `refs/pais/checkpoints/<session>`
+//!   (built from a throwaway index, so untracked files are included)
+//! - `stash` mode records a `git stash create` entry in the stash list
+//!   (tracked files only)
+//!
+//! `pais checkpoint list` shows them and `pais checkpoint restore` copies a
+//! checkpoint's files back into the working tree.
+
+use chrono::{DateTime, Local, TimeZone};
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::CheckpointMode;
+use crate::lock::FileLock;
+
+/// Ref namespace holding checkpoint chains
+pub const REF_PREFIX: &str = "refs/pais/checkpoints/";
+
+/// Subject prefix of every checkpoint commit and stash entry
+const SUBJECT_PREFIX: &str = "pais checkpoint";
+
+/// Identity checkpoint commits are made with (they record agent edits)
+const IDENTITY: (&str, &str) = ("pais", "pais@localhost");
+
+/// Edits not yet captured in a checkpoint, per session
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Pending {
+    /// File modifications since the last checkpoint
+    pub edits: usize,
+    /// Files touched by those modifications
+    pub files: Vec<String>,
+    /// Checkpoints taken so far in the session
+    #[serde(default)]
+    pub taken: usize,
+}
+
+/// Pending edits of all sessions
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PendingState {
+    #[serde(default)]
+    pub sessions: BTreeMap<String, Pending>,
+}
+
+/// Location of the pending-edit state (`~/.cache/pais/checkpoints.json`)
+pub fn state_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|d| d.join("pais").join("checkpoints.json"))
+}
+
+impl PendingState {
+    /// Load state, starting empty when the file is missing or unreadable
+    pub fn load(path: &Path) -> Self {
+        fs::read(path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        fs::write(path, serde_json::to_vec(self)?).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Load, change and save the pending state under a lock, returning what `change` returns
+pub fn update<T>(path: &Path, change: impl FnOnce(&mut PendingState) -> T) -> Result<T> {
+    let _lock = FileLock::acquire(path)?;
+    let mut state = PendingState::load(path);
+    let result = change(&mut state);
+    state.save(path)?;
+    Ok(result)
+}
+
+/// A checkpoint found in a repository
+#[derive(Debug, Clone, Serialize)]
+pub struct Checkpoint {
+    /// Full commit id
+    pub commit: String,
+    /// Session the checkpoint belongs to
+    pub session: String,
+    /// Position in the session (1 = first)
+    pub number: usize,
+    /// When it was taken
+    pub created: DateTime<Local>,
+    /// Files edited since the previous checkpoint
+    pub files: Vec<String>,
+    /// `ref` or `stash`
+    pub kind: &'static str,
+}
+
+impl Checkpoint {
+    /// Abbreviated commit id for display and `restore`
+    pub fn short_id(&self) -> &str {
+        &self.commit[..10.min(self.commit.len())]
+    }
+}
+
+/// Run git in `root`, returning trimmed stdout on success
+fn git(root: &Path, args: &[&str], env: &[(&str, &str)]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(root)
+        .envs(env.iter().copied())
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        eyre::bail!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Top level of the repository containing `dir`, if any
+pub fn repo_root(dir: &Path) -> Option<PathBuf> {
+    git(dir, &["rev-parse", "--show-toplevel"], &[])
+        .ok()
+        .filter(|root| !root.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Commit message for the `number`th checkpoint of `session`
+fn message(session: &str, number: usize, files: &[String]) -> String {
+    let mut message = format!(
+        "{} {} (session {})\n\nSession: {}\n",
+        SUBJECT_PREFIX,
+        number,
+        short(session),
+        session
+    );
+    for file in files {
+        message.push_str(&format!("File: {}\n", file));
+    }
+    message
+}
+
+fn short(session: &str) -> &str {
+    &session[..8.min(session.len())]
+}
+
+/// Snapshot the working tree of `root` as checkpoint `number` of `session`.
+///
+/// Returns the checkpoint commit, or `None` when nothing changed since the
+/// previous checkpoint (or, in stash mode, since `HEAD`).
+pub fn create(
+    root: &Path,
+    session: &str,
+    number: usize,
+    files: &[String],
+    mode: CheckpointMode,
+) -> Result<Option<String>> {
+    let message = message(session, number, files);
+    let identity = [
+        ("GIT_AUTHOR_NAME", IDENTITY.0),
+        ("GIT_AUTHOR_EMAIL", IDENTITY.1),
+        ("GIT_COMMITTER_NAME", IDENTITY.0),
+        ("GIT_COMMITTER_EMAIL", IDENTITY.1),
+    ];
+
+    match mode {
+        CheckpointMode::Stash => {
+            let commit = git(root, &["stash", "create", &message], &identity)?;
+            if commit.is_empty() {
+                return Ok(None);
+            }
+            let subject = message.lines().next().unwrap_or(SUBJECT_PREFIX);
+            git(root, &["stash", "store", "-m", subject, &commit], &identity)?;
+            Ok(Some(commit))
+        }
+        CheckpointMode::Ref => {
+            // Stage everything into a copy of the index so the real one is untouched
+            let index = PathBuf::from(git(root, &["rev-parse", "--git-path", "index"], &[])?);
+            let index = if index.is_absolute() { index } else { root.join(index) };
+            let temp = tempfile::tempdir().context("Failed to create temporary index")?;
+            let temp_index = temp.path().join("index");
+            if index.exists() {
+                fs::copy(&index, &temp_index).context("Failed to copy git index")?;
+            }
+            let temp_index = temp_index.to_string_lossy().to_string();
+            let env = [&identity[..], &[("GIT_INDEX_FILE", temp_index.as_str())]].concat();
+            git(root, &["add", "-A"], &env)?;
+            let tree = git(root, &["write-tree"], &env)?;
+
+            let reference = format!("{}{}", REF_PREFIX, session);
+            let parent = git(root, &["rev-parse", "--verify", "--quiet", &reference], &[])
+                .or_else(|_| git(root, &["rev-parse", "--verify", "--quiet", "HEAD"], &[]))
+                .ok();
+            if let Some(parent) = &parent
+                && git(root, &["rev-parse", &format!("{}^{{tree}}", parent)], &[])? == tree
+            {
+                return Ok(None);
+            }
+
+            let mut args = vec!["commit-tree", tree.as_str(), "-m", message.as_str()];
+            if let Some(parent) = &parent {
+                args.extend(["-p", parent.as_str()]);
+            }
+            let commit = git(root, &args, &env)?;
+            git(root, &["update-ref", &reference, &commit], &[])?;
+            Ok(Some(commit))
+        }
+    }
+}
+
+/// Parse `git log` output (`%H%x1f%ct%x1f%B%x1e` records) into checkpoints
+fn parse_log(output: &str, kind: &'static str) -> Vec<Checkpoint> {
+    output
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').splitn(3, '\x1f');
+            let commit = fields.next()?.to_string();
+            let created = Local.timestamp_opt(fields.next()?.parse().ok()?, 0).single()?;
+            let body = fields.next()?;
+            let subject = body.lines().next()?;
+            // Stash subjects are prefixed with "On <branch>: "
+            let number = subject
+                .split_once(SUBJECT_PREFIX)?
+                .1
+                .split_whitespace()
+                .next()?
+                .parse()
+                .ok()?;
+            let session = body.lines().find_map(|l| l.strip_prefix("Session: "))?.to_string();
+            let files = body
+                .lines()
+                .filter_map(|l| l.strip_prefix("File: "))
+                .map(String::from)
+                .collect();
+            Some(Checkpoint {
+                commit,
+                session,
+                number,
+                created,
+                files,
+                kind,
+            })
+        })
+        .collect()
+}
+
+const LOG_FORMAT: &str = "--format=%H%x1f%ct%x1f%B%x1e";
+
+/// All checkpoints in the repository at `root`, newest first
+pub fn list(root: &Path) -> Result<Vec<Checkpoint>> {
+    let mut checkpoints = Vec::new();
+
+    let refs = git(root, &["for-each-ref", "--format=%(refname)", REF_PREFIX], &[])?;
+    for reference in refs.lines().filter(|r| !r.is_empty()) {
+        let log = git(root, &["log", "--first-parent", LOG_FORMAT, reference], &[])?;
+        // The chain ends where it was forked from HEAD
+        let chain = parse_log(&log, "ref");
+        let session = reference.trim_start_matches(REF_PREFIX);
+        checkpoints.extend(chain.into_iter().take_while(|c| c.session == session));
+    }
+
+    // Stash commits carry the full message, which `stash list` abbreviates
+    let stashes = git(root, &["stash", "list", "--format=%H"], &[]).unwrap_or_default();
+    for commit in stashes.lines().filter(|c| !c.is_empty()) {
+        let log = git(root, &["log", "-1", LOG_FORMAT, commit], &[])?;
+        checkpoints.extend(parse_log(&log, "stash"));
+    }
+
+    checkpoints.sort_by(|a, b| b.created.cmp(&a.created).then(b.number.cmp(&a.number)));
+    Ok(checkpoints)
+}
+
+/// Overwrite the working tree's files with those of `commit`
+pub fn restore(root: &Path, commit: &str) -> Result<()> {
+    git(root, &["restore", "--source", commit, "--worktree", "--", "."], &[])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn repo() -> tempfile::TempDir {
+        let dir = tempdir().unwrap();
+        git(dir.path(), &["init", "-q"], &[]).unwrap();
+        fs::write(dir.path().join("a.txt"), "one\n").unwrap();
+        let env = [
+            ("GIT_AUTHOR_NAME", "t"),
+            ("GIT_AUTHOR_EMAIL", "t@t"),
+            ("GIT_COMMITTER_NAME", "t"),
+            ("GIT_COMMITTER_EMAIL", "t@t"),
+        ];
+        git(dir.path(), &["add", "-A"], &env).unwrap();
+        git(dir.path(), &["commit", "-q", "-m", "init"], &env).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_ref_checkpoints_leave_head_and_index_alone() {
+        let dir = repo();
+        let root = dir.path();
+        let head = git(root, &["rev-parse", "HEAD"], &[]).unwrap();
+
+        fs::write(root.join("a.txt"), "two\n").unwrap();
+        fs::write(root.join("new.txt"), "untracked\n").unwrap();
+        let files = vec!["a.txt".to_string(), "new.txt".to_string()];
+        let first = create(root, "session-1", 1, &files, CheckpointMode::Ref)
+            .unwrap()
+            .unwrap();
+        assert!(
+            create(root, "session-1", 2, &[], CheckpointMode::Ref)
+                .unwrap()
+                .is_none()
+        );
+
+        fs::write(root.join("a.txt"), "three\n").unwrap();
+        create(root, "session-1", 2, &files[..1], CheckpointMode::Ref)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(git(root, &["rev-parse", "HEAD"], &[]).unwrap(), head);
+        assert!(git(root, &["diff", "--cached", "--name-only"], &[]).unwrap().is_empty());
+
+        let checkpoints = list(root).unwrap();
+        assert_eq!(checkpoints.len(), 2);
+        assert_eq!(checkpoints[0].number, 2);
+        assert_eq!(checkpoints[1].commit, first);
+        assert_eq!(checkpoints[1].files, files);
+        assert_eq!(checkpoints[1].session, "session-1");
+
+        restore(root, &first).unwrap();
+        assert_eq!(fs::read_to_string(root.join("a.txt")).unwrap(), "two\n");
+    }
+
+    #[test]
+    fn test_stash_checkpoints() {
+        let dir = repo();
+        let root = dir.path();
+        assert!(create(root, "s2", 1, &[], CheckpointMode::Stash).unwrap().is_none());
+
+        fs::write(root.join("a.txt"), "edited\n").unwrap();
+        create(root, "s2", 1, &["a.txt".to_string()], CheckpointMode::Stash)
+            .unwrap()
+            .unwrap();
+        // The working tree keeps the edit
+        assert_eq!(fs::read_to_string(root.join("a.txt")).unwrap(), "edited\n");
+
+        let checkpoints = list(root).unwrap();
+        assert_eq!(checkpoints.len(), 1);
+        assert_eq!(checkpoints[0].kind, "stash");
+        assert_eq!(checkpoints[0].session, "s2");
+    }
+}
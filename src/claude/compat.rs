@@ -0,0 +1,280 @@
+//! Compatibility with different Claude Code releases
+//!
+//! Hook payloads and CLI flags have changed across Claude Code releases:
+//! older releases sent camelCase payload keys and put the final response in
+//! `response`, current ones send snake_case keys and a `transcript_path` to
+//! read it from. This module hides those differences:
+//!
+//! - [`normalize_payload`] rewrites known aliases to the current key names,
+//!   so handlers only ever look up one name.
+//! - [`response_source`] says where a Stop payload's final response lives.
+//! - [`launch_flags`] builds `claude` arguments the installed release accepts.
+//! - [`detect`] finds the installed version (cached per binary) and
+//!   [`support`] tells `pais doctor` whether it has been tested.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::UNIX_EPOCH;
+
+/// Oldest release PAIS has been tested against
+pub const TESTED_MIN: Version = Version::new(1, 0, 0);
+
+/// Newest release line (major.minor) PAIS has been tested against
+pub const TESTED_MAX: Version = Version::new(2, 1, u32::MAX);
+
+/// First release accepting `--strict-mcp-config`
+const STRICT_MCP_CONFIG_SINCE: Version = Version::new(1, 0, 50);
+
+/// Payload keys renamed since older releases: (old, current)
+const PAYLOAD_ALIASES: &[(&str, &str)] = &[
+    ("sessionId", "session_id"),
+    ("toolName", "tool_name"),
+    ("toolInput", "tool_input"),
+    ("toolResponse", "tool_response"),
+    ("transcriptPath", "transcript_path"),
+    ("hookEventName", "hook_event_name"),
+    ("stopReason", "stop_reason"),
+];
+
+/// A Claude Code release number
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl Version {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self { major, minor, patch }
+    }
+
+    /// Find the first `X.Y.Z` in `claude --version` output
+    ///
+    /// Accepts `2.1.3 (Claude Code)`, `claude 2.1.3` and pre-release builds
+    /// like `2.1.3-dev.20260101`.
+    pub fn parse(output: &str) -> Option<Self> {
+        output.split_whitespace().find_map(|word| {
+            let core = word.trim_start_matches('v').split(['-', '+']).next()?;
+            let mut parts = core.split('.').map(|p| p.parse::<u32>().ok());
+            let version = Self::new(parts.next()??, parts.next()??, parts.next()??);
+            parts.next().is_none().then_some(version)
+        })
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// How the installed release relates to the tested range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Support {
+    Tested,
+    /// Newer than anything tested; probably fine, but payloads may have changed
+    Newer,
+    /// Older than the oldest tested release
+    Older,
+}
+
+/// Classify `version` against the tested range
+pub fn support(version: Version) -> Support {
+    match (version.cmp(&TESTED_MIN), version.cmp(&TESTED_MAX)) {
+        (Ordering::Less, _) => Support::Older,
+        (_, Ordering::Greater) => Support::Newer,
+        _ => Support::Tested,
+    }
+}
+
+/// Rewrite old payload key names to the current ones, in place
+///
+/// A key already present under its current name wins over its alias.
+pub fn normalize_payload(payload: &mut Value) {
+    let Some(map) = payload.as_object_mut() else {
+        return;
+    };
+    for (old, current) in PAYLOAD_ALIASES {
+        if let Some(value) = map.remove(*old)
+            && !map.contains_key(*current)
+        {
+            log::debug!("Normalized legacy payload key {} -> {}", old, current);
+            map.insert(current.to_string(), value);
+        }
+    }
+}
+
+/// Where a Stop payload's final response can be found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResponseSource<'a> {
+    /// Older releases: the response text itself
+    Inline(&'a str),
+    /// Current releases: the session transcript (JSONL)
+    Transcript(&'a Path),
+}
+
+/// Locate the final response in a (normalized) Stop payload
+pub fn response_source(payload: &Value) -> Option<ResponseSource<'_>> {
+    if let Some(response) = payload.get("response").and_then(|v| v.as_str()) {
+        return Some(ResponseSource::Inline(response));
+    }
+    payload
+        .get("transcript_path")
+        .and_then(|v| v.as_str())
+        .map(|path| ResponseSource::Transcript(Path::new(path)))
+}
+
+/// Arguments for launching `claude` with an optional MCP config
+///
+/// With an unknown version, the flags of current releases are used.
+pub fn launch_flags(version: Option<Version>, mcp_config: Option<&Path>) -> Vec<OsString> {
+    let mut flags = Vec::new();
+
+    // Only load the MCP servers we specify
+    if version.is_none_or(|v| v >= STRICT_MCP_CONFIG_SINCE) {
+        flags.push("--strict-mcp-config".into());
+    } else {
+        log::warn!(
+            "Claude Code {} predates --strict-mcp-config; user MCP servers will load too",
+            version.map(|v| v.to_string()).unwrap_or_default()
+        );
+    }
+
+    if let Some(path) = mcp_config {
+        flags.push("--mcp-config".into());
+        flags.push(path.into());
+    }
+
+    flags
+}
+
+/// Version detected for one `claude` binary
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedVersion {
+    binary: PathBuf,
+    mtime: u64,
+    version: String,
+}
+
+fn cache_file() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("pais").join("claude-version.json"))
+}
+
+/// The installed Claude Code version, if `claude` is on PATH
+///
+/// `claude --version` takes a while to start, so the answer is cached until
+/// the binary changes.
+pub fn detect() -> Option<Version> {
+    let binary = which::which("claude").ok()?;
+    let resolved = binary.canonicalize().unwrap_or(binary);
+    let mtime = fs::metadata(&resolved)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let cache = cache_file();
+    if let Some(cached) = cache
+        .as_ref()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<CachedVersion>(&content).ok())
+        .filter(|c| c.binary == resolved && c.mtime == mtime)
+    {
+        return Version::parse(&cached.version);
+    }
+
+    let output = Command::new(&resolved).arg("--version").output().ok()?;
+    if !output.status.success() {
+        log::debug!("claude --version exited with {}", output.status);
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let version = Version::parse(&text);
+
+    if let Some(path) = cache {
+        let entry = CachedVersion {
+            binary: resolved,
+            mtime,
+            version: text,
+        };
+        let written = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, serde_json::to_string(&entry).unwrap_or_default()));
+        if let Err(e) = written {
+            log::debug!("Failed to cache Claude Code version: {}", e);
+        }
+    }
+
+    version
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_version_parse() {
+        assert_eq!(Version::parse("2.1.3 (Claude Code)"), Some(Version::new(2, 1, 3)));
+        assert_eq!(Version::parse("claude v1.0.71"), Some(Version::new(1, 0, 71)));
+        assert_eq!(
+            Version::parse("2.1.280-dev.20260921.t204017 (Claude Code)"),
+            Some(Version::new(2, 1, 280))
+        );
+        assert_eq!(Version::parse("1.2 beta"), None);
+        assert_eq!(Version::parse(""), None);
+    }
+
+    #[test]
+    fn test_support_range() {
+        assert_eq!(support(Version::new(2, 1, 500)), Support::Tested);
+        assert_eq!(support(Version::new(1, 0, 0)), Support::Tested);
+        assert_eq!(support(Version::new(2, 2, 0)), Support::Newer);
+        assert_eq!(support(Version::new(0, 2, 9)), Support::Older);
+    }
+
+    #[test]
+    fn test_normalize_legacy_payload() {
+        let mut payload = json!({
+            "sessionId": "abc",
+            "toolName": "Bash",
+            "tool_input": {"command": "ls"},
+            "toolInput": {"command": "stale"},
+            "response": "done"
+        });
+        normalize_payload(&mut payload);
+
+        assert_eq!(payload["session_id"], "abc");
+        assert_eq!(payload["tool_name"], "Bash");
+        // The current key wins over its alias
+        assert_eq!(payload["tool_input"]["command"], "ls");
+        assert!(payload.get("sessionId").is_none());
+        assert_eq!(response_source(&payload), Some(ResponseSource::Inline("done")));
+
+        let current = json!({"transcript_path": "/tmp/t.jsonl"});
+        assert_eq!(
+            response_source(&current),
+            Some(ResponseSource::Transcript(Path::new("/tmp/t.jsonl")))
+        );
+    }
+
+    #[test]
+    fn test_launch_flags_follow_version() {
+        let mcp = Path::new("/tmp/mcp.json");
+        let current = launch_flags(None, Some(mcp));
+        assert_eq!(current, vec!["--strict-mcp-config", "--mcp-config", "/tmp/mcp.json"]);
+
+        let old = launch_flags(Some(Version::new(1, 0, 10)), Some(mcp));
+        assert_eq!(old, vec!["--mcp-config", "/tmp/mcp.json"]);
+        assert!(launch_flags(Some(Version::new(2, 0, 0)), None) == vec!["--strict-mcp-config"]);
+    }
+}
@@ -0,0 +1,8 @@
+//! Claude Code integration
+//!
+//! Reads and edits Claude Code's own files (`settings.json`) so users don't
+//! have to hand-edit them to wire up PAIS, and smooths over differences
+//! between Claude Code releases.
+
+pub mod compat;
+pub mod settings;
@@ -0,0 +1,332 @@
+//! Register `pais hook dispatch` in Claude Code settings.json
+//!
+//! Claude Code reads hooks from `~/.claude/settings.json` (user) and
+//! `<project>/.claude/settings.json` (project):
+//! ```json
+//! {
+//!   "hooks": {
+//!     "PreToolUse": [
+//!       { "matcher": "*", "hooks": [{ "type": "command", "command": "pais hook dispatch PreToolUse" }] }
+//!     ],
+//!     "SessionStart": [
+//!       { "hooks": [{ "type": "command", "command": "pais hook dispatch SessionStart" }] }
+//!     ]
+//!   }
+//! }
+//! ```
+//!
+//! PAIS entries are recognized by their command prefix, so install is
+//! idempotent and uninstall leaves every other hook and setting untouched.
+
+use eyre::{Context, Result};
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::hook::HookEvent;
+
+/// Command prefix identifying hooks PAIS owns
+pub const DISPATCH_PREFIX: &str = "pais hook dispatch";
+
+/// Events PAIS registers, in settings order
+pub const EVENTS: &[HookEvent] = &[
+    HookEvent::PreToolUse,
+    HookEvent::PostToolUse,
+    HookEvent::PermissionRequest,
+    HookEvent::UserPromptSubmit,
+    HookEvent::Notification,
+    HookEvent::Stop,
+    HookEvent::SubagentStop,
+    HookEvent::PreCompact,
+    HookEvent::SessionStart,
+    HookEvent::SessionEnd,
+];
+
+/// Matcher for an event; only tool events take a tool-name matcher
+pub fn matcher(event: HookEvent) -> Option<&'static str> {
+    match event {
+        HookEvent::PreToolUse | HookEvent::PostToolUse | HookEvent::PermissionRequest => Some("*"),
+        _ => None,
+    }
+}
+
+pub fn dispatch_command(event: HookEvent) -> String {
+    format!("{} {}", DISPATCH_PREFIX, event)
+}
+
+fn is_pais_hook(hook: &Value) -> bool {
+    hook.get("command")
+        .and_then(|c| c.as_str())
+        .is_some_and(|c| c.trim_start().starts_with(DISPATCH_PREFIX))
+}
+
+/// Registration state of one event
+#[derive(Debug, Clone, Serialize)]
+pub struct EventHooks {
+    pub event: String,
+    /// Commands of PAIS hooks registered for this event
+    pub pais: Vec<String>,
+    /// Number of other (non-PAIS) hooks left alone
+    pub other: usize,
+}
+
+/// A Claude settings.json file loaded as raw JSON so unknown keys survive edits
+pub struct ClaudeSettings {
+    pub path: PathBuf,
+    pub value: Value,
+}
+
+impl ClaudeSettings {
+    /// User-level settings (`~/.claude/settings.json`)
+    pub fn user_path() -> Result<PathBuf> {
+        crate::config::Config::claude_settings_file().ok_or_else(|| eyre::eyre!("Could not determine home directory"))
+    }
+
+    /// Project-level settings (`<dir>/.claude/settings.json`)
+    pub fn project_path(dir: &Path) -> PathBuf {
+        dir.join(".claude").join("settings.json")
+    }
+
+    /// Load settings, treating a missing file as empty
+    pub fn load(path: &Path) -> Result<Self> {
+        let value = if path.exists() {
+            let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+            if content.trim().is_empty() {
+                json!({})
+            } else {
+                serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?
+            }
+        } else {
+            json!({})
+        };
+
+        if !value.is_object() {
+            eyre::bail!("{} is not a JSON object", path.display());
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            value,
+        })
+    }
+
+    /// Write settings back, keeping the previous file as `settings.json.bak`
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        if self.path.exists() {
+            let backup = self.path.with_extension("json.bak");
+            fs::copy(&self.path, &backup).with_context(|| format!("Failed to back up to {}", backup.display()))?;
+        }
+
+        let mut content = serde_json::to_string_pretty(&self.value)?;
+        content.push('\n');
+        fs::write(&self.path, content).with_context(|| format!("Failed to write {}", self.path.display()))?;
+        Ok(())
+    }
+
+    fn hooks_mut(&mut self) -> Result<&mut Map<String, Value>> {
+        let root = self.value.as_object_mut().expect("settings root is an object");
+        root.entry("hooks")
+            .or_insert_with(|| json!({}))
+            .as_object_mut()
+            .ok_or_else(|| eyre::eyre!("\"hooks\" in {} is not an object", self.path.display()))
+    }
+
+    /// Register dispatch hooks for `events`; returns the events that were added
+    pub fn install(&mut self, events: &[HookEvent]) -> Result<Vec<HookEvent>> {
+        let hooks = self.hooks_mut()?;
+        let mut added = Vec::new();
+
+        for &event in events {
+            let groups = hooks.entry(event.to_string()).or_insert_with(|| json!([]));
+            let Some(groups) = groups.as_array_mut() else {
+                eyre::bail!("hooks.{} is not an array", event);
+            };
+
+            let registered = groups
+                .iter()
+                .filter_map(|g| g.get("hooks").and_then(|h| h.as_array()))
+                .flatten()
+                .any(is_pais_hook);
+            if registered {
+                continue;
+            }
+
+            let mut group = Map::new();
+            if let Some(matcher) = matcher(event) {
+                group.insert("matcher".to_string(), json!(matcher));
+            }
+            group.insert(
+                "hooks".to_string(),
+                json!([{ "type": "command", "command": dispatch_command(event) }]),
+            );
+            groups.push(Value::Object(group));
+            added.push(event);
+        }
+
+        Ok(added)
+    }
+
+    /// Remove every PAIS dispatch hook; returns how many hook entries were removed
+    pub fn uninstall(&mut self) -> usize {
+        let root = self.value.as_object_mut().expect("settings root is an object");
+        let Some(hooks) = root.get_mut("hooks").and_then(|h| h.as_object_mut()) else {
+            return 0;
+        };
+
+        let mut removed = 0;
+        for groups in hooks.values_mut() {
+            let Some(groups) = groups.as_array_mut() else {
+                continue;
+            };
+            for group in groups.iter_mut() {
+                if let Some(entries) = group.get_mut("hooks").and_then(|h| h.as_array_mut()) {
+                    let before = entries.len();
+                    entries.retain(|h| !is_pais_hook(h));
+                    removed += before - entries.len();
+                }
+            }
+            groups.retain(|g| g.get("hooks").and_then(|h| h.as_array()).is_none_or(|h| !h.is_empty()));
+        }
+
+        hooks.retain(|_, groups| groups.as_array().is_none_or(|g| !g.is_empty()));
+        if hooks.is_empty() {
+            root.remove("hooks");
+        }
+
+        removed
+    }
+
+    /// Registration state for every event that has hooks or that PAIS registers
+    pub fn show(&self) -> Vec<EventHooks> {
+        let hooks = self.value.get("hooks").and_then(|h| h.as_object());
+
+        let mut names: Vec<String> = EVENTS.iter().map(|e| e.to_string()).collect();
+        if let Some(hooks) = hooks {
+            names.extend(hooks.keys().filter(|k| !names.contains(k)).cloned().collect::<Vec<_>>());
+        }
+
+        names
+            .into_iter()
+            .map(|event| {
+                let entries: Vec<&Value> = hooks
+                    .and_then(|h| h.get(&event))
+                    .and_then(|g| g.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|g| g.get("hooks").and_then(|h| h.as_array()))
+                    .flatten()
+                    .collect();
+                let pais: Vec<String> = entries
+                    .iter()
+                    .filter(|h| is_pais_hook(h))
+                    .filter_map(|h| h.get("command").and_then(|c| c.as_str()).map(String::from))
+                    .collect();
+                EventHooks {
+                    other: entries.len() - pais.len(),
+                    event,
+                    pais,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn settings(value: Value) -> ClaudeSettings {
+        ClaudeSettings {
+            path: PathBuf::from("settings.json"),
+            value,
+        }
+    }
+
+    #[test]
+    fn test_install_is_idempotent_and_keeps_other_hooks() {
+        let mut s = settings(json!({
+            "model": "opus",
+            "hooks": {
+                "PreToolUse": [
+                    { "matcher": "Bash", "hooks": [{ "type": "command", "command": "my-linter" }] }
+                ]
+            }
+        }));
+
+        let added = s.install(EVENTS).unwrap();
+        assert_eq!(added.len(), EVENTS.len());
+        assert_eq!(s.install(EVENTS).unwrap().len(), 0);
+
+        let pre = s.value["hooks"]["PreToolUse"].as_array().unwrap();
+        assert_eq!(pre.len(), 2);
+        assert_eq!(pre[0]["hooks"][0]["command"], "my-linter");
+        assert_eq!(pre[1]["matcher"], "*");
+        assert_eq!(pre[1]["hooks"][0]["command"], "pais hook dispatch PreToolUse");
+
+        // Non-tool events get no matcher
+        assert!(s.value["hooks"]["SessionStart"][0].get("matcher").is_none());
+        assert_eq!(s.value["model"], "opus");
+    }
+
+    #[test]
+    fn test_uninstall_removes_only_pais_hooks() {
+        let mut s = settings(json!({
+            "hooks": {
+                "PreToolUse": [
+                    { "matcher": "Bash", "hooks": [{ "type": "command", "command": "my-linter" }] }
+                ]
+            }
+        }));
+        s.install(EVENTS).unwrap();
+
+        assert_eq!(s.uninstall(), EVENTS.len());
+        let hooks = s.value["hooks"].as_object().unwrap();
+        assert_eq!(hooks.len(), 1);
+        assert_eq!(hooks["PreToolUse"][0]["hooks"][0]["command"], "my-linter");
+
+        // Nothing else left: the hooks key goes away entirely
+        let mut empty = settings(json!({}));
+        empty.install(&[HookEvent::Stop]).unwrap();
+        assert_eq!(empty.uninstall(), 1);
+        assert!(empty.value.get("hooks").is_none());
+    }
+
+    #[test]
+    fn test_show_reports_pais_and_other_hooks() {
+        let mut s = settings(json!({
+            "hooks": {
+                "Custom": [{ "hooks": [{ "type": "command", "command": "echo hi" }] }]
+            }
+        }));
+        s.install(&[HookEvent::Stop]).unwrap();
+
+        let state = s.show();
+        let stop = state.iter().find(|e| e.event == "Stop").unwrap();
+        assert_eq!(stop.pais, vec!["pais hook dispatch Stop"]);
+        let custom = state.iter().find(|e| e.event == "Custom").unwrap();
+        assert_eq!(custom.other, 1);
+        assert!(state.iter().find(|e| e.event == "SessionEnd").unwrap().pais.is_empty());
+    }
+
+    #[test]
+    fn test_load_save_roundtrip_with_backup() {
+        let temp = tempdir().unwrap();
+        let path = ClaudeSettings::project_path(temp.path());
+
+        let mut s = ClaudeSettings::load(&path).unwrap();
+        s.install(&[HookEvent::Stop]).unwrap();
+        s.save().unwrap();
+        assert!(!path.with_extension("json.bak").exists());
+
+        let mut s = ClaudeSettings::load(&path).unwrap();
+        s.uninstall();
+        s.save().unwrap();
+        assert!(path.with_extension("json.bak").exists());
+        assert_eq!(ClaudeSettings::load(&path).unwrap().value, json!({}));
+    }
+}
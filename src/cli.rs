@@ -1,7 +1,11 @@
 use clap::{Parser, Subcommand, ValueEnum};
+use clap_complete::ArgValueCandidates;
 use std::io::IsTerminal;
 use std::path::PathBuf;
 
+use crate::completion;
+use crate::provenance::ArtifactKind;
+
 /// Output format for commands
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
 pub enum OutputFormat {
@@ -13,13 +17,28 @@ pub enum OutputFormat {
     Yaml,
 }
 
+/// `--format` as given on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FormatArg {
+    /// Human-readable text
+    Text,
+    /// JSON format
+    Json,
+    /// YAML format
+    Yaml,
+    /// iCalendar (only for `history calendar`)
+    Ics,
+}
+
 impl OutputFormat {
     /// Resolve the effective output format.
-    /// If user specified a format, use it.
+    /// If user specified a format, use it (`ics` renders as text).
     /// Otherwise: TTY → Text, non-TTY (pipe) → Json
-    pub fn resolve(user_choice: Option<OutputFormat>) -> OutputFormat {
+    pub fn resolve(user_choice: Option<FormatArg>) -> OutputFormat {
         match user_choice {
-            Some(fmt) => fmt,
+            Some(FormatArg::Text | FormatArg::Ics) => OutputFormat::Text,
+            Some(FormatArg::Json) => OutputFormat::Json,
+            Some(FormatArg::Yaml) => OutputFormat::Yaml,
             None => {
                 if std::io::stdout().is_terminal() {
                     OutputFormat::Text
@@ -36,7 +55,7 @@ impl OutputFormat {
     name = "pais",
     about = "Personal AI System - A modular plugin system for Claude Code",
     version = env!("GIT_DESCRIBE"),
-    after_help = "Logs are written to: ~/.local/share/pais/logs/pais.log\n\nDocumentation: https://github.com/scottidler/pais"
+    after_help = "Logs are written to: ~/.local/share/pais/logs/pais.log (view with `pais logs tail`)\n\nDocumentation: https://github.com/scottidler/pais"
 )]
 pub struct Cli {
     /// Path to config file
@@ -51,6 +70,28 @@ pub struct Cli {
     #[arg(short, long, global = true, help = "Suppress non-error output")]
     pub quiet: bool,
 
+    /// Output format for all commands (default: text for TTY, json for pipes)
+    #[arg(
+        long,
+        short = 'o',
+        value_enum,
+        global = true,
+        help = "Output format: text, json, yaml (default: text for TTY, json for pipes)"
+    )]
+    pub format: Option<FormatArg>,
+
+    /// Fixed, stepping clock for reproducible IDs and timestamps (also PAIS_DETERMINISTIC=1)
+    #[arg(long, global = true, hide = true)]
+    pub deterministic: bool,
+
+    /// Don't use the network (same as network.offline in pais.yaml)
+    #[arg(
+        long,
+        global = true,
+        help = "Fail fast or use local alternatives instead of the network"
+    )]
+    pub offline: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -99,6 +140,12 @@ pub enum Commands {
         action: HistoryAction,
     },
 
+    /// Weekly reviews generated from history
+    Review {
+        #[command(subcommand)]
+        action: ReviewAction,
+    },
+
     /// Manage configuration
     Config {
         #[command(subcommand)]
@@ -117,6 +164,18 @@ pub enum Commands {
         action: SecurityAction,
     },
 
+    /// Manage Claude Code settings (hook registration)
+    Claude {
+        #[command(subcommand)]
+        action: ClaudeAction,
+    },
+
+    /// Policy rules evaluated against hook payloads
+    Policy {
+        #[command(subcommand)]
+        action: PolicyAction,
+    },
+
     /// Live event stream (tail events in real-time)
     Observe {
         /// Filter by event type (e.g., PreToolUse, SessionStart)
@@ -130,6 +189,26 @@ pub enum Commands {
         /// Include full payload in output
         #[arg(long)]
         payload: bool,
+
+        /// Replay past events from this time instead of tailing (2h, 2026-01-03, "2026-01-03 14:00", 14:00, RFC 3339)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// End of the replay window (defaults to now)
+        #[arg(long, requires = "since")]
+        until: Option<String>,
+
+        /// Replay speed relative to the original pace (e.g. 4x, 0.5)
+        #[arg(long, default_value = "1x", requires = "since")]
+        speed: String,
+
+        /// Print the replayed events at once instead of at their original pace
+        #[arg(long, requires = "since")]
+        no_delay: bool,
+
+        /// Never pipe a replay through $PAGER
+        #[arg(long, requires = "since")]
+        no_pager: bool,
     },
 
     /// Manage agent personalities
@@ -144,6 +223,18 @@ pub enum Commands {
         action: BundleAction,
     },
 
+    /// Subscribe to shared team repos of skills, agents and bundles
+    Team {
+        #[command(subcommand)]
+        action: TeamAction,
+    },
+
+    /// Serve the skill index to Claude over MCP
+    Mcp {
+        #[command(subcommand)]
+        action: McpAction,
+    },
+
     /// Generate images using AI models
     Image {
         #[command(subcommand)]
@@ -159,9 +250,11 @@ pub enum Commands {
     /// Run a plugin action directly
     Run {
         /// Plugin name
+        #[arg(add = ArgValueCandidates::new(completion::plugins))]
         plugin: String,
 
         /// Action to run
+        #[arg(add = ArgValueCandidates::new(completion::plugin_actions))]
         action: String,
 
         /// Action arguments
@@ -172,11 +265,11 @@ pub enum Commands {
     /// Launch Claude Code with dynamic MCP and skill configuration
     Session {
         /// MCP servers or profiles to load (comma-separated, profiles expand to their contents)
-        #[arg(short, long, value_delimiter = ',')]
+        #[arg(short, long, value_delimiter = ',', add = ArgValueCandidates::new(completion::session_mcps))]
         mcp: Option<Vec<String>>,
 
         /// Skills or profiles to load (comma-separated, profiles expand to their contents)
-        #[arg(short, long, value_delimiter = ',')]
+        #[arg(short, long, value_delimiter = ',', add = ArgValueCandidates::new(completion::session_skills))]
         skill: Option<Vec<String>>,
 
         /// List available MCPs, skills, and profiles
@@ -187,10 +280,6 @@ pub enum Commands {
         #[arg(long)]
         dry_run: bool,
 
-        /// Output format for --list (default: text for TTY, json for pipes)
-        #[arg(long, short = 'o', value_enum)]
-        format: Option<OutputFormat>,
-
         /// Additional arguments to pass to Claude
         #[arg(last = true)]
         claude_args: Vec<String>,
@@ -198,20 +287,108 @@ pub enum Commands {
 
     /// Show system status
     Status {
-        /// Output format (default: text for TTY, json for pipes)
-        #[arg(long, short = 'o', value_enum)]
-        format: Option<OutputFormat>,
+        /// Print a compact segment for shell prompts (starship, powerlevel10k) from cached state
+        #[arg(long)]
+        prompt: bool,
+
+        /// Show pais's own command timings and cache hit rates instead
+        #[arg(long, conflicts_with = "prompt")]
+        perf: bool,
+    },
+
+    /// Run tests shipped with plugins and skills
+    Test {
+        #[command(subcommand)]
+        action: TestAction,
     },
 
-    /// Sync skills to Claude Code (~/.claude/skills/)
+    /// Sync skills to Claude Code (~/.claude/skills/) or export them to another editor
     Sync {
         /// Show what would happen without making changes
         #[arg(long)]
         dry_run: bool,
 
-        /// Remove orphaned symlinks from Claude skills directory
+        /// Remove orphaned and stale skill links; report broken and shadowed skills
         #[arg(long)]
         clean: bool,
+
+        /// Keep running and relink skills as the skills and plugins directories change
+        #[arg(long, conflicts_with_all = ["clean", "dry_run"])]
+        watch: bool,
+
+        /// Editor to sync to (cursor/windsurf/vscode write rules and MCP config files)
+        #[arg(long, value_enum, default_value_t, conflicts_with = "clean")]
+        target: crate::sync::targets::Target,
+
+        /// Project directory to write editor files into (default: current directory)
+        #[arg(long)]
+        project: Option<PathBuf>,
+
+        /// Skills or profiles to export (default: all skills)
+        #[arg(short, long, value_delimiter = ',', add = ArgValueCandidates::new(completion::session_skills))]
+        skill: Option<Vec<String>>,
+
+        /// MCP servers or profiles to export (default: all servers)
+        #[arg(short, long, value_delimiter = ',', add = ArgValueCandidates::new(completion::session_mcps))]
+        mcp: Option<Vec<String>>,
+
+        #[command(subcommand)]
+        action: Option<SyncAction>,
+    },
+
+    /// Diagnostics for bug reports
+    Debug {
+        #[command(subcommand)]
+        action: DebugAction,
+    },
+
+    /// List and restore git checkpoints of agent edits
+    Checkpoint {
+        #[command(subcommand)]
+        action: CheckpointAction,
+    },
+
+    /// Where installed skills, plugins, agents and bundles came from
+    Provenance {
+        #[command(subcommand)]
+        action: ProvenanceAction,
+    },
+
+    /// Log of changes pais made to its own setup
+    State {
+        #[command(subcommand)]
+        action: StateAction,
+    },
+
+    /// Trust a workspace so its project config is honored (lists workspaces without a path)
+    Trust {
+        /// Directory to trust, along with everything below it
+        path: Option<PathBuf>,
+
+        /// Withdraw trust from the directory instead
+        #[arg(long, requires = "path")]
+        revoke: bool,
+    },
+
+    /// View the pais log file
+    Logs {
+        #[command(subcommand)]
+        action: LogsAction,
+    },
+
+    /// Delete cached data pais can rebuild
+    Gc {
+        /// Cached plugin builds that no installed plugin's current sources use
+        #[arg(long)]
+        build_cache: bool,
+
+        /// Delete every cached build, including current ones
+        #[arg(long, requires = "build_cache")]
+        all: bool,
+
+        /// Show what would be deleted without deleting it
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Upgrade PAIS configuration (run migrations)
@@ -225,40 +402,146 @@ pub enum Commands {
         status: bool,
     },
 
+    /// Time the hot paths against their performance budgets
+    #[command(hide = true)]
+    Bench {
+        /// Timed runs per path
+        #[arg(long, default_value = "20")]
+        iterations: usize,
+
+        /// Exit non-zero if any path's median is over budget
+        #[arg(long)]
+        check: bool,
+    },
+
     /// Generate shell completions
     Completions {
         /// Shell to generate completions for
         shell: clap_complete::Shell,
+
+        /// Emit a static script (flags and subcommands only, no live skill/plugin names)
+        #[arg(long = "static")]
+        static_only: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TestAction {
+    /// Run a plugin's declared test command (all plugins if no name given)
+    Plugin {
+        /// Plugin name
+        #[arg(add = ArgValueCandidates::new(completion::plugins))]
+        name: Option<String>,
+    },
+
+    /// Check a skill's example prompts route as expected (all skills if no name given)
+    Skill {
+        /// Skill name
+        #[arg(add = ArgValueCandidates::new(completion::skills))]
+        name: Option<String>,
+    },
+
+    /// Run all plugin and skill tests
+    All,
+}
+
+#[derive(Subcommand)]
+pub enum ClaudeAction {
+    /// Register pais hook dispatch in Claude Code settings.json
+    Hooks {
+        #[command(subcommand)]
+        action: ClaudeHooksAction,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ClaudeHooksAction {
+    /// Add `pais hook dispatch` entries for every hook event (idempotent)
+    Install {
+        /// Edit <dir>/.claude/settings.json instead of ~/.claude/settings.json
+        #[arg(long, value_name = "DIR", num_args = 0..=1, default_missing_value = ".")]
+        project: Option<PathBuf>,
+
+        /// Show the resulting settings without writing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Remove all `pais hook dispatch` entries, leaving other hooks untouched
+    Uninstall {
+        /// Edit <dir>/.claude/settings.json instead of ~/.claude/settings.json
+        #[arg(long, value_name = "DIR", num_args = 0..=1, default_missing_value = ".")]
+        project: Option<PathBuf>,
+
+        /// Show the resulting settings without writing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Show which hook events are registered
+    Show {
+        /// Read <dir>/.claude/settings.json instead of ~/.claude/settings.json
+        #[arg(long, value_name = "DIR", num_args = 0..=1, default_missing_value = ".")]
+        project: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SyncAction {
+    /// Sync skills, agents, config (and optionally history) with a remote
+    Remote {
+        /// Remote URL (git URL, s3://bucket/prefix, or ssh://host/path); overrides sync.remote
+        #[arg(long, short)]
+        remote: Option<String>,
+
+        /// Include history (overrides sync.include-history)
+        #[arg(long)]
+        history: bool,
+
+        /// Resolve conflicts by taking this side instead of stopping
+        #[arg(long, value_enum)]
+        prefer: Option<crate::sync::Prefer>,
+
+        /// Show what would change without writing anything
+        #[arg(long)]
+        dry_run: bool,
     },
 }
 
 #[derive(Subcommand)]
 pub enum PluginAction {
     /// List installed plugins
-    List {
-        /// Output format (default: text for TTY, json for pipes)
-        #[arg(long, short = 'o', value_enum)]
-        format: Option<OutputFormat>,
+    List,
+
+    /// Search the configured plugin registries
+    Search {
+        /// Matched against names, descriptions and keywords
+        term: String,
     },
 
     /// Install a plugin
     Install {
-        /// Plugin source (name, git URL, or local path)
+        /// Plugin source: a local path, a `pais plugin pack` archive, a git URL, or a registry name (`name` or `registry/name`)
         source: String,
 
+        /// Branch, tag or commit of a git source
+        #[arg(long = "ref", value_name = "REF")]
+        r#ref: Option<String>,
+
         /// Symlink for development (don't copy)
         #[arg(long)]
         dev: bool,
 
-        /// Overwrite existing installation
+        /// Overwrite an existing installation and install even if dependencies don't resolve
         #[arg(long)]
         force: bool,
     },
 
     /// Remove a plugin
     Remove {
-        /// Plugin name
-        name: String,
+        /// Plugin name; omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::plugins))]
+        name: Option<String>,
 
         /// Remove even if other plugins depend on it
         #[arg(long)]
@@ -267,14 +550,24 @@ pub enum PluginAction {
 
     /// Update a plugin
     Update {
-        /// Plugin name (or "all")
-        name: String,
+        /// Plugin name (or "all"); omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::plugins))]
+        name: Option<String>,
+
+        /// Move a git source to this branch, tag or commit (default: the one installed)
+        #[arg(long = "ref", value_name = "REF")]
+        r#ref: Option<String>,
+
+        /// Reinstall even if the source hasn't changed
+        #[arg(long)]
+        force: bool,
     },
 
     /// Show plugin details
     Info {
-        /// Plugin name
-        name: String,
+        /// Plugin name; omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::plugins))]
+        name: Option<String>,
     },
 
     /// Create a new plugin
@@ -282,7 +575,7 @@ pub enum PluginAction {
         /// Plugin name
         name: String,
 
-        /// Language (python or rust)
+        /// Language (python, rust, node or shell)
         #[arg(long, default_value = "python")]
         language: String,
 
@@ -297,18 +590,81 @@ pub enum PluginAction {
 
     /// Verify plugin installation
     Verify {
-        /// Plugin name
-        name: String,
-
-        /// Output format (default: text for TTY, json for pipes)
-        #[arg(long, short = 'o', value_enum)]
-        format: Option<OutputFormat>,
+        /// Plugin name; omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::plugins))]
+        name: Option<String>,
     },
 
     /// Show plugin installation guide
     InstallGuide {
-        /// Plugin name
-        name: String,
+        /// Plugin name; omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::plugins))]
+        name: Option<String>,
+    },
+
+    /// Run plugins' builds (cargo, uv, npm or custom), reusing cached Rust binaries
+    Build {
+        /// Plugin name, or `all` (the default) to build every plugin that has a build
+        #[arg(add = ArgValueCandidates::new(completion::plugins))]
+        name: Option<String>,
+    },
+
+    /// Link a plugin checkout, then re-check and rebuild it on every change
+    Dev {
+        /// Plugin directory
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Replay a sample of this hook event after each rebuild (e.g. pre-tool-use)
+        #[arg(long)]
+        event: Option<String>,
+
+        /// JSON file to replay instead of the built-in sample
+        #[arg(long, requires = "event")]
+        payload: Option<PathBuf>,
+    },
+
+    /// Show a plugin's resolved configuration and where each value comes from
+    Config {
+        /// Plugin name; omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::plugins))]
+        name: Option<String>,
+    },
+
+    /// Turn a plugin back on after `pais plugin disable`
+    Enable {
+        /// Plugin name; omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::plugins))]
+        name: Option<String>,
+    },
+
+    /// Turn a plugin off without uninstalling it: no hooks, no `pais run`
+    Disable {
+        /// Plugin name; omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::plugins))]
+        name: Option<String>,
+    },
+
+    /// Sign a plugin (writes plugin.sig), creating the signing key on first use
+    Sign {
+        /// Installed plugin name or plugin directory; omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::plugins))]
+        name: Option<String>,
+
+        /// Secret key to sign with (default: plugins.signing.key)
+        #[arg(long, value_name = "FILE")]
+        key: Option<PathBuf>,
+    },
+
+    /// Pack a plugin into a versioned .tar.gz that `pais plugin install` accepts
+    Pack {
+        /// Installed plugin name or plugin directory; omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::plugins))]
+        name: Option<String>,
+
+        /// Archive to write (default: <name>-<version>.tar.gz in the current directory)
+        #[arg(long)]
+        output: Option<PathBuf>,
     },
 }
 
@@ -316,10 +672,6 @@ pub enum PluginAction {
 pub enum SkillAction {
     /// List all skills (simple and plugin-based)
     List {
-        /// Output format (default: text for TTY, json for pipes)
-        #[arg(long, short = 'o', value_enum)]
-        format: Option<OutputFormat>,
-
         /// Show only simple skills (no plugin.yaml)
         #[arg(long)]
         simple: bool,
@@ -341,20 +693,23 @@ pub enum SkillAction {
 
     /// Show skill details
     Info {
-        /// Skill name
-        name: String,
+        /// Skill name; omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::skills))]
+        name: Option<String>,
     },
 
     /// Edit a skill in $EDITOR
     Edit {
-        /// Skill name
-        name: String,
+        /// Skill name; omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::skills))]
+        name: Option<String>,
     },
 
     /// Remove a skill
     Remove {
-        /// Skill name
-        name: String,
+        /// Skill name; omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::skills))]
+        name: Option<String>,
 
         /// Remove without confirmation
         #[arg(long)]
@@ -363,8 +718,9 @@ pub enum SkillAction {
 
     /// Validate SKILL.md format
     Validate {
-        /// Skill name (or "all" to validate all skills)
-        name: String,
+        /// Skill name (or "all" to validate all skills); omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::skills))]
+        name: Option<String>,
     },
 
     /// Scan directories for .pais/SKILL.md files
@@ -379,30 +735,151 @@ pub enum SkillAction {
         /// Register found skills (create symlinks in ~/.config/pais/skills/)
         #[arg(long)]
         register: bool,
-
-        /// Output format (default: text for TTY, json for pipes)
-        #[arg(long, short = 'o', value_enum)]
-        format: Option<OutputFormat>,
     },
 
     /// Generate skill index for context injection
-    Index {
-        /// Output format (default: text for TTY, json for pipes)
-        #[arg(long, short = 'o', value_enum)]
-        format: Option<OutputFormat>,
+    Index,
+
+    /// Check skills for frontmatter problems and overlapping triggers
+    Lint,
+
+    /// List names that exist as both a skill and a plugin, and which one is used
+    Conflicts,
+
+    /// Turn a skill into a Claude Code subagent (~/.claude/agents/<name>.md), kept current by `pais sync`
+    Promote {
+        /// Skill name; omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::skills))]
+        name: Option<String>,
+
+        /// Write a subagent definition (the only target so far)
+        #[arg(long, required = true)]
+        to_subagent: bool,
+
+        /// Replace an agent file pais didn't generate
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Preview a skill's minified body (what context inject uses over the token ceiling)
+    Minify {
+        /// Skill name; omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::skills))]
+        name: Option<String>,
+
+        /// How to shorten it (default: skills.compression.method)
+        #[arg(long, value_enum)]
+        method: Option<crate::config::CompressionMethod>,
+
+        /// Save the result as SKILL.min.md next to SKILL.md
+        #[arg(long)]
+        write: bool,
     },
 
     /// Show or list workflows for a skill
     Workflow {
-        /// Skill name
-        skill: String,
+        /// Skill name; omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::skills))]
+        skill: Option<String>,
 
         /// Workflow name/intent (if omitted, lists available workflows)
         workflow: Option<String>,
+    },
+
+    /// Publish a skill as a GitHub gist and print its install URL
+    Share {
+        /// Skill name; omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::skills))]
+        name: Option<String>,
+
+        /// Create a public gist (default: secret)
+        #[arg(long)]
+        public: bool,
+    },
+
+    /// Install a skill shared with `pais skill share`, or from a git repository
+    Install {
+        /// Gist URL or ID, or a git URL holding SKILL.md or a skills/ collection
+        source: String,
+
+        /// Branch, tag or commit to install from a git source (default: its default branch)
+        #[arg(long = "ref", value_name = "REF")]
+        r#ref: Option<String>,
+
+        /// Overwrite an existing skill with the same name
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// List skills installed from git or a gist whose source has a newer version or commit
+    Outdated,
+
+    /// Replace a skill installed from git or a gist with its source's current copy, after showing the changelog
+    #[command(alias = "update")]
+    Upgrade {
+        /// Skill name (or "all"); omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::skills))]
+        name: Option<String>,
+
+        /// Move a git source to this branch, tag or commit (default: the one installed)
+        #[arg(long = "ref", value_name = "REF")]
+        r#ref: Option<String>,
+
+        /// Reinstall even if the source hasn't changed
+        #[arg(long)]
+        force: bool,
+
+        /// Overwrite without asking
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
+
+    /// How often each skill was used, per week, and which went unused (candidates to prune)
+    Stats {
+        /// Number of weeks to include
+        #[arg(long, default_value = "12")]
+        weeks: usize,
+    },
+
+    /// Find skills by name, triggers, description and body, tolerating typos and abbreviations
+    Search {
+        /// Words to look for
+        query: String,
+
+        /// Max results
+        #[arg(long, default_value = "10")]
+        limit: usize,
+    },
+
+    /// Draft a SKILL.md from the history entries a query matches, or from entries by ID
+    Distill {
+        /// Full-text query (as for `pais history search`), or entry IDs separated by commas
+        #[arg(long = "from-history", value_name = "QUERY|IDS")]
+        from_history: String,
+
+        /// Skill name (default: from the query, else the first entry's title)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Category to search; repeat for several (default: skills.distill.categories)
+        #[arg(long)]
+        category: Vec<String>,
+
+        /// Max entries to draft from (default: skills.distill.max-entries)
+        #[arg(long)]
+        limit: Option<usize>,
 
-        /// Output format (default: text for TTY, json for pipes)
-        #[arg(long, short = 'o', value_enum)]
-        format: Option<OutputFormat>,
+        /// How to write the draft (default: skills.distill.method)
+        #[arg(long, value_enum)]
+        method: Option<crate::config::DistillMethod>,
+
+        /// Save the draft to the skills directory instead of printing it
+        #[arg(long)]
+        write: bool,
+
+        /// Overwrite a skill of the same name
+        #[arg(long, requires = "write")]
+        force: bool,
     },
 }
 
@@ -445,40 +922,225 @@ pub enum HistoryAction {
         #[arg(long)]
         since: Option<String>,
 
-        /// Output format (default: text for TTY, json for pipes)
-        #[arg(long, short = 'o', value_enum)]
-        format: Option<OutputFormat>,
+        /// Only search reviewed entries promoted by `pais history review`
+        #[arg(long)]
+        reviewed: bool,
     },
 
-    /// Show recent entries
-    Recent {
-        /// Category
+    /// Full-text search, ranked by relevance
+    Search {
+        /// Words, "phrases", OR/NOT, prefix*, and title:/tag:/category: filters
+        query: String,
+
+        /// Category to search
         #[arg(long)]
         category: Option<String>,
 
-        /// Number of entries
-        #[arg(long, default_value = "5")]
-        count: usize,
+        /// Max results
+        #[arg(long, default_value = "10")]
+        limit: usize,
     },
 
-    /// List available categories
-    Categories,
+    /// Entries closest in meaning to a query (embeddings from history.embeddings)
+    Similar {
+        /// What to look for, in your own words
+        query: String,
 
-    /// Show a specific history entry
-    Show {
-        /// Entry ID
-        id: String,
+        /// Category to search
+        #[arg(long)]
+        category: Option<String>,
+
+        /// Max results
+        #[arg(long, default_value = "10")]
+        limit: usize,
     },
 
-    /// Show event statistics
-    Stats {
-        /// Number of days to include
-        #[arg(long, default_value = "7")]
+    /// Step through auto-captured entries: keep, edit, recategorize or delete each
+    Review {
+        /// Only review this category
+        #[arg(long)]
+        category: Option<String>,
+
+        /// Print the queue instead of reviewing it
+        #[arg(long)]
+        list: bool,
+    },
+
+    /// Show recent entries
+    Recent {
+        /// Category
+        #[arg(long)]
+        category: Option<String>,
+
+        /// Number of entries
+        #[arg(long, default_value = "5")]
+        count: usize,
+    },
+
+    /// Print new entries as hooks write them, until interrupted
+    Watch {
+        /// Only entries in this category
+        #[arg(long)]
+        category: Option<String>,
+    },
+
+    /// List declared categories with entry counts
+    Categories {
+        /// Also show descriptions, retention, frontmatter and routing keywords
+        #[arg(long)]
+        describe: bool,
+    },
+
+    /// Delete entries older than their category's retention-days
+    Prune {
+        /// Only list what would be deleted
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Merge near-duplicate entries into their older copy
+    Dedupe {
+        /// Only this category (default: all but events)
+        #[arg(long)]
+        category: Option<String>,
+
+        /// Similarity (0-1) at which entries count as duplicates (default: history.dedupe.threshold)
+        #[arg(long)]
+        threshold: Option<f64>,
+
+        /// Only list the duplicates
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Move captured entries to the category the current routing rules pick
+    Recategorize {
+        /// Only list the moves
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Rebuild the SQLite index from the entries on disk
+    Reindex,
+
+    /// Backfill entries from sessions recorded before pais was capturing them
+    Import {
+        /// Summarize transcripts under ~/.claude/projects the way the Stop hook does
+        #[arg(long)]
+        claude_transcripts: bool,
+
+        /// Only sessions that ended on or after this date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Show what would be imported without writing entries
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Bundle entries, indexes, embeddings and the category schema into one archive
+    Pack {
+        /// Archive to write (default: pais-history-<date>.tar.gz)
+        output: Option<PathBuf>,
+    },
+
+    /// Restore a `history pack` archive into the history directory
+    Unpack {
+        /// Archive to read
+        archive: PathBuf,
+
+        /// Overwrite files whose content differs from the archive's
+        #[arg(long)]
+        force: bool,
+
+        /// Verify the archive and show what would be written
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Show a specific history entry
+    Show {
+        /// Entry ID
+        id: String,
+    },
+
+    /// Open an entry in $EDITOR (a changed category moves it)
+    Edit {
+        /// Entry ID or prefix
+        id: String,
+    },
+
+    /// Add or remove an entry's tags: +tag adds, -tag removes
+    Tag {
+        /// Entry ID or prefix
+        id: String,
+
+        /// Tag changes (+tag, -tag; a bare tag adds)
+        #[arg(required = true, allow_hyphen_values = true)]
+        tags: Vec<String>,
+    },
+
+    /// Link an entry to another; `show` lists it on both
+    Link {
+        /// Entry ID or prefix to link from
+        from: String,
+
+        /// Entry ID or prefix to link to
+        to: String,
+
+        /// Remove the link instead
+        #[arg(long)]
+        remove: bool,
+    },
+
+    /// Move an entry to another category
+    Move {
+        /// Entry ID or prefix
+        id: String,
+
+        /// Target category
+        #[arg(long)]
+        category: String,
+    },
+
+    /// Delete an entry
+    Delete {
+        /// Entry ID or prefix
+        id: String,
+
+        /// Don't ask for confirmation
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Activity report: entries per category over time, top tags, busiest projects, session lengths
+    Stats {
+        /// Number of days to include
+        #[arg(long, default_value = "7")]
         days: usize,
 
-        /// Output format (default: text for TTY, json for pipes)
-        #[arg(long, short = 'o', value_enum)]
-        format: Option<OutputFormat>,
+        /// Number of tags and projects to list
+        #[arg(long, default_value = "10")]
+        top: usize,
+    },
+
+    /// Tool calls timed by the hooks: slowest and most failing tools, or one session's calls
+    Tools {
+        /// Show this session's calls in order (ID or prefix)
+        #[arg(long)]
+        session: Option<String>,
+
+        /// Number of days to include in the per-tool totals
+        #[arg(long, default_value = "30")]
+        days: u32,
+
+        /// Order per-tool totals by
+        #[arg(long, value_enum, default_value_t)]
+        sort: crate::history::tools::ToolSort,
+
+        /// Max tools to show
+        #[arg(long, default_value = "20")]
+        limit: usize,
     },
 
     /// List raw event dates
@@ -487,38 +1149,119 @@ pub enum HistoryAction {
         #[arg(long, default_value = "10")]
         limit: usize,
     },
+
+    /// Export sessions as calendar events (iCalendar unless --format json, yaml or text)
+    Calendar {
+        /// Number of days to include
+        #[arg(long, default_value = "30")]
+        days: usize,
+
+        /// Write to a file instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Export entries to a Notion database (only new or changed entries are sent)
+    ExportNotion {
+        /// Notion database ID (default: publish.notion.database)
+        #[arg(long)]
+        database: Option<String>,
+
+        /// Only export this category
+        #[arg(long)]
+        category: Option<String>,
+
+        /// Show what would be created or updated without calling Notion
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Publish an entry to Slack, Linear or Jira
+    Publish {
+        /// Entry ID (or unique prefix)
+        id: String,
+
+        /// Where to publish
+        #[arg(long, value_enum)]
+        to: crate::history::publish::PublishTarget,
+
+        /// Print the request body instead of sending it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Audit stored entries for tokens, passwords and other secrets
+    ScanSecrets {
+        /// Only scan this category
+        #[arg(long)]
+        category: Option<String>,
+
+        /// Redact what was found, rewriting the entries in place
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// Commit entries to a git repo in the history directory and sync it with a remote
+    Sync {
+        /// Git URL to sync with; saved as the repo's origin (overrides history.sync.remote)
+        #[arg(long, short)]
+        remote: Option<String>,
+
+        /// For entries edited on both machines, take this side instead of stopping
+        #[arg(long, value_enum)]
+        prefer: Option<crate::sync::Prefer>,
+
+        /// Show what would be committed, pulled and pushed without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Merge a history directory copied from another machine into this one
+    Merge {
+        /// History directory to merge in
+        dir: PathBuf,
+
+        /// For entries that differ on the two sides, take this side instead of reporting them
+        #[arg(long, value_enum)]
+        prefer: Option<crate::sync::Prefer>,
+
+        /// Show what would be merged without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Encrypt the bodies of entries written before encryption was enabled
+    Encrypt {
+        /// Only rewrite this category
+        #[arg(long)]
+        category: Option<String>,
+
+        /// Decrypt every entry instead, e.g. before turning encryption off
+        #[arg(long)]
+        decrypt: bool,
+    },
 }
 
 #[derive(Subcommand)]
 pub enum AgentAction {
     /// List available agents
-    List {
-        /// Output format (default: text for TTY, json for pipes)
-        #[arg(long, short = 'o', value_enum)]
-        format: Option<OutputFormat>,
-    },
+    List,
 
     /// Show agent details
     Show {
-        /// Agent name
-        name: String,
-
-        /// Output format (default: text for TTY, json for pipes)
-        #[arg(long, short = 'o', value_enum)]
-        format: Option<OutputFormat>,
+        /// Agent name; omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::agents))]
+        name: Option<String>,
     },
 
     /// List available traits
-    Traits {
-        /// Output format (default: text for TTY, json for pipes)
-        #[arg(long, short = 'o', value_enum)]
-        format: Option<OutputFormat>,
-    },
+    Traits,
 
     /// Generate prompt for an agent
     Prompt {
-        /// Agent name
-        name: String,
+        /// Agent name; omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::agents))]
+        name: Option<String>,
     },
 
     /// Create a new agent from template
@@ -526,31 +1269,76 @@ pub enum AgentAction {
         /// Agent name
         name: String,
     },
+
+    /// Run a task headlessly (claude -p) as an agent and save the output to history
+    Run {
+        /// Agent name (the default agent for --parallel task lists)
+        #[arg(add = ArgValueCandidates::new(completion::agents))]
+        agent: Option<String>,
+
+        /// Task description
+        task: Option<String>,
+
+        /// Skills to include (names or profiles); defaults to the agent's `skills`
+        #[arg(short = 's', long = "skill")]
+        skill: Option<Vec<String>>,
+
+        /// MCP servers to enable (names or profiles); defaults to the agent's `mcp`
+        #[arg(short = 'm', long = "mcp")]
+        mcp: Option<Vec<String>>,
+
+        /// Run every task in FILE ("-" for stdin), one per line as "agent: task" or just "task"
+        #[arg(long, value_name = "FILE", conflicts_with = "task")]
+        parallel: Option<PathBuf>,
+
+        /// Tasks to run at once with --parallel
+        #[arg(short = 'j', long, default_value = "4", requires = "parallel")]
+        jobs: usize,
+
+        /// Print the claude invocations instead of running them
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Publish an agent as a GitHub gist and print its install URL
+    Share {
+        /// Agent name; omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::agents))]
+        name: Option<String>,
+
+        /// Create a public gist (default: secret)
+        #[arg(long)]
+        public: bool,
+    },
+
+    /// Install an agent shared with `pais agent share`
+    Install {
+        /// Gist URL or ID
+        source: String,
+
+        /// Overwrite an existing agent with the same name
+        #[arg(long)]
+        force: bool,
+    },
 }
 
 #[derive(Subcommand)]
 pub enum BundleAction {
     /// List available bundles
-    List {
-        /// Output format (default: text for TTY, json for pipes)
-        #[arg(long, short = 'o', value_enum)]
-        format: Option<OutputFormat>,
-    },
+    List,
 
     /// Show bundle details
     Show {
-        /// Bundle name
-        name: String,
-
-        /// Output format (default: text for TTY, json for pipes)
-        #[arg(long, short = 'o', value_enum)]
-        format: Option<OutputFormat>,
+        /// Bundle name; omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::bundles))]
+        name: Option<String>,
     },
 
     /// Install a bundle
     Install {
-        /// Bundle name
-        name: String,
+        /// Bundle name; omit to pick interactively
+        #[arg(add = ArgValueCandidates::new(completion::bundles))]
+        name: Option<String>,
 
         /// Install only required plugins (skip optional)
         #[arg(long)]
@@ -579,17 +1367,40 @@ pub enum ContextAction {
         /// Output raw content without system-reminder wrapper
         #[arg(long)]
         raw: bool,
+
+        /// Rebuild the context instead of using the cached copy
+        #[arg(long)]
+        no_cache: bool,
+    },
+
+    /// Remove cached context injections
+    ClearCache,
+
+    /// Print the last exchanges of this project's most recent session, for
+    /// picking up where it left off
+    Recent {
+        /// Number of prompt/reply exchanges
+        #[arg(long, default_value = "10")]
+        turns: usize,
+
+        /// Use this session (ID or prefix) instead of the most recent one
+        #[arg(long)]
+        session: Option<String>,
+
+        /// Longest prompt or reply kept, in characters
+        #[arg(long, default_value = "2000")]
+        max_chars: usize,
+
+        /// Output raw content without system-reminder wrapper
+        #[arg(long)]
+        raw: bool,
     },
 }
 
 #[derive(Subcommand)]
 pub enum ConfigAction {
     /// Show current configuration
-    Show {
-        /// Output format (default: text for TTY, json for pipes)
-        #[arg(long, short = 'o', value_enum)]
-        format: Option<OutputFormat>,
-    },
+    Show,
 
     /// Get a configuration value
     Get {
@@ -610,21 +1421,13 @@ pub enum ConfigAction {
 #[derive(Subcommand)]
 pub enum SecurityAction {
     /// Show security tiers and their actions
-    Tiers {
-        /// Output format (default: text for TTY, json for pipes)
-        #[arg(long, short = 'o', value_enum)]
-        format: Option<OutputFormat>,
-    },
+    Tiers,
 
     /// View security event log
     Log {
         /// Number of days to show
         #[arg(long, default_value = "7")]
         days: usize,
-
-        /// Output format (default: text for TTY, json for pipes)
-        #[arg(long, short = 'o', value_enum)]
-        format: Option<OutputFormat>,
     },
 
     /// Test a command against security patterns
@@ -634,6 +1437,171 @@ pub enum SecurityAction {
     },
 }
 
+#[derive(Subcommand)]
+pub enum DebugAction {
+    /// Write a diagnostic bundle (redacted config, log tail, command, versions)
+    Bundle {
+        /// File to write instead of a new bundle in ~/.local/share/pais/crashes/
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ReviewAction {
+    /// Summarize a week of history, security events and agent costs, with its action items
+    Generate {
+        /// Week to review: 0 (the default) for this week, 1 for last week, and so on
+        #[arg(
+            long,
+            value_name = "WEEKS_AGO",
+            num_args = 0..=1,
+            default_value_t = 0,
+            default_missing_value = "0"
+        )]
+        week: u32,
+
+        /// Print the review without storing it in history
+        #[arg(long)]
+        no_save: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CheckpointAction {
+    /// List checkpoints in a repository, newest first
+    List {
+        /// Only checkpoints of sessions whose ID starts with this
+        #[arg(long)]
+        session: Option<String>,
+
+        /// Repository to inspect (defaults to the current directory)
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+
+    /// Restore the working tree's files from a checkpoint
+    Restore {
+        /// Checkpoint commit (as shown by `pais checkpoint list`)
+        checkpoint: String,
+
+        /// Repository to restore (defaults to the current directory)
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ProvenanceAction {
+    /// List the source, commit, author and license of everything installed
+    Report {
+        /// Only report items of this kind
+        #[arg(long, value_enum)]
+        kind: Option<ArtifactKind>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum StateAction {
+    /// Show recorded installs, removals, syncs, config changes and migrations
+    Log {
+        /// Only show events of this type (e.g. plugin-installed, config-changed)
+        #[arg(long)]
+        event: Option<String>,
+
+        /// Show only the last N events
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+    },
+
+    /// Show the setup reconstructed from the log
+    Show,
+
+    /// Check the log's integrity and compare it with what's on disk
+    Verify,
+}
+
+#[derive(Subcommand)]
+pub enum McpAction {
+    /// Run the MCP server on stdin/stdout (launched by Claude Code)
+    Serve,
+}
+
+#[derive(Subcommand)]
+pub enum TeamAction {
+    /// Clone a team repo (read-only) and add its contents to discovery
+    Add {
+        /// Git URL of the team repo
+        url: String,
+
+        /// Team name (default: the repo name)
+        #[arg(long)]
+        name: Option<String>,
+    },
+
+    /// Pull the latest commit of one team, or all of them
+    Update {
+        /// Team name
+        name: Option<String>,
+    },
+
+    /// List subscribed teams and what they provide
+    List,
+
+    /// Unsubscribe from a team and delete its clone
+    Remove {
+        /// Team name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum LogsAction {
+    /// Show the last log lines (text or json log-format)
+    Tail {
+        /// Number of lines to show
+        #[arg(short = 'n', long, default_value = "50")]
+        lines: usize,
+
+        /// Only lines whose message, module or fields match this regex
+        #[arg(long)]
+        grep: Option<String>,
+
+        /// Minimum level to show
+        #[arg(long, value_parser = ["error", "warn", "info", "debug", "trace"])]
+        level: Option<String>,
+
+        /// Keep printing new lines as they are written
+        #[arg(short, long)]
+        follow: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PolicyAction {
+    /// Evaluate policy rules against a hook payload
+    Eval {
+        /// Payload JSON file (reads from stdin if omitted)
+        #[arg(long)]
+        payload: Option<PathBuf>,
+
+        /// Hook event to evaluate as
+        #[arg(long, short = 'e', default_value = "PreToolUse")]
+        event: String,
+
+        /// Project directory for .pais/policy.yaml (defaults to the payload's cwd)
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+    },
+
+    /// List policy rules in evaluation order
+    List {
+        /// Project directory for .pais/policy.yaml (defaults to current directory)
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum ImageAction {
     /// Generate an image using AI
@@ -655,7 +1623,7 @@ pub enum ImageAction {
         aspect_ratio: Option<String>,
 
         /// Output file path
-        #[arg(long, short = 'o')]
+        #[arg(long)]
         output: Option<PathBuf>,
 
         /// Remove background (requires REMOVEBG_API_KEY)
@@ -668,11 +1636,7 @@ pub enum ImageAction {
     },
 
     /// List available AI models
-    Models {
-        /// Output format (default: text for TTY, json for pipes)
-        #[arg(long, short = 'o', value_enum)]
-        format: Option<OutputFormat>,
-    },
+    Models,
 }
 
 #[derive(Subcommand)]
@@ -687,12 +1651,12 @@ pub enum DiagramAction {
         #[arg(short, long)]
         mermaid: Option<String>,
 
-        /// Output format (svg, png)
-        #[arg(long, short = 'f', default_value = "svg")]
-        format: String,
+        /// File format (svg, png)
+        #[arg(long = "file-format", short = 'f', default_value = "svg")]
+        file_format: String,
 
         /// Output file path (prints to stdout if omitted)
-        #[arg(long, short = 'o')]
+        #[arg(long)]
         output: Option<PathBuf>,
 
         /// Image width
@@ -734,12 +1698,12 @@ pub enum DiagramAction {
         #[arg(long, short = 'c')]
         config: Option<PathBuf>,
 
-        /// Output format (svg, png, mermaid)
-        #[arg(long, short = 'f', default_value = "svg")]
-        format: String,
+        /// File format (svg, png, mermaid)
+        #[arg(long = "file-format", short = 'f', default_value = "svg")]
+        file_format: String,
 
         /// Output file path
-        #[arg(long, short = 'o')]
+        #[arg(long)]
         output: Option<PathBuf>,
 
         /// Mermaid.ink server URL
@@ -753,12 +1717,12 @@ pub enum DiagramAction {
         #[arg(long, short = 'c')]
         config: Option<PathBuf>,
 
-        /// Output format (svg, png, mermaid)
-        #[arg(long, short = 'f', default_value = "svg")]
-        format: String,
+        /// File format (svg, png, mermaid)
+        #[arg(long = "file-format", short = 'f', default_value = "svg")]
+        file_format: String,
 
         /// Output file path
-        #[arg(long, short = 'o')]
+        #[arg(long)]
         output: Option<PathBuf>,
 
         /// Mermaid.ink server URL
@@ -772,12 +1736,12 @@ pub enum DiagramAction {
         #[arg(long, short = 'c')]
         config: Option<PathBuf>,
 
-        /// Output format (svg, png, mermaid)
-        #[arg(long, short = 'f', default_value = "svg")]
-        format: String,
+        /// File format (svg, png, mermaid)
+        #[arg(long = "file-format", short = 'f', default_value = "svg")]
+        file_format: String,
 
         /// Output file path
-        #[arg(long, short = 'o')]
+        #[arg(long)]
         output: Option<PathBuf>,
 
         /// Mermaid.ink server URL
@@ -791,12 +1755,12 @@ pub enum DiagramAction {
         #[arg(long, short = 'c')]
         config: Option<PathBuf>,
 
-        /// Output format (svg, png, mermaid)
-        #[arg(long, short = 'f', default_value = "svg")]
-        format: String,
+        /// File format (svg, png, mermaid)
+        #[arg(long = "file-format", short = 'f', default_value = "svg")]
+        file_format: String,
 
         /// Output file path
-        #[arg(long, short = 'o')]
+        #[arg(long)]
         output: Option<PathBuf>,
 
         /// Mermaid.ink server URL
@@ -810,12 +1774,12 @@ pub enum DiagramAction {
         #[arg(long, short = 'c')]
         config: Option<PathBuf>,
 
-        /// Output format (svg, png, mermaid)
-        #[arg(long, short = 'f', default_value = "svg")]
-        format: String,
+        /// File format (svg, png, mermaid)
+        #[arg(long = "file-format", short = 'f', default_value = "svg")]
+        file_format: String,
 
         /// Output file path
-        #[arg(long, short = 'o')]
+        #[arg(long)]
         output: Option<PathBuf>,
 
         /// Mermaid.ink server URL
@@ -837,12 +1801,12 @@ pub enum DiagramAction {
         #[arg(long, short = 'c')]
         config: Option<PathBuf>,
 
-        /// Output format (svg, png, mermaid)
-        #[arg(long, short = 'f', default_value = "svg")]
-        format: String,
+        /// File format (svg, png, mermaid)
+        #[arg(long = "file-format", short = 'f', default_value = "svg")]
+        file_format: String,
 
         /// Output file path
-        #[arg(long, short = 'o')]
+        #[arg(long)]
         output: Option<PathBuf>,
 
         /// Mermaid.ink server URL
@@ -860,12 +1824,12 @@ pub enum DiagramAction {
         #[arg(long, short = 'c')]
         config: Option<PathBuf>,
 
-        /// Output format (svg, png, mermaid)
-        #[arg(long, short = 'f', default_value = "svg")]
-        format: String,
+        /// File format (svg, png, mermaid)
+        #[arg(long = "file-format", short = 'f', default_value = "svg")]
+        file_format: String,
 
         /// Output file path
-        #[arg(long, short = 'o')]
+        #[arg(long)]
         output: Option<PathBuf>,
 
         /// Mermaid.ink server URL
@@ -874,9 +1838,16 @@ pub enum DiagramAction {
     },
 
     /// List available diagram types
-    Types {
-        /// Output format (default: text for TTY, json for pipes)
-        #[arg(long, short = 'o', value_enum)]
-        format: Option<OutputFormat>,
-    },
+    Types,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
 }
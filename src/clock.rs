@@ -0,0 +1,146 @@
+//! Where timestamps and IDs come from
+//!
+//! History entries, review marks, raw and observability events, security log
+//! lines and tool timings read the time through [`now`] and take entry IDs from
+//! [`new_id`] instead of the system clock, so the source can be swapped:
+//!
+//! - normally a [`SystemClock`] and [`HexMillis`] IDs (creation time in hex
+//!   milliseconds);
+//! - with `--deterministic` or `PAIS_DETERMINISTIC=1`, a [`SteppingClock`]
+//!   that starts at `PAIS_CLOCK_START` (RFC 3339, default
+//!   2024-01-01T00:00:00Z) and moves one second per reading. Every run then
+//!   produces the same IDs and timestamps in the same order, which keeps
+//!   replays and fixtures reproducible. Local dates follow `TZ`.
+//!
+//! Each process starts the stepping clock afresh, so hooks run as separate
+//! processes in deterministic mode get the same times; set a different
+//! `PAIS_CLOCK_START` per run when their entries must not collide.
+
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// Start of the deterministic clock unless `PAIS_CLOCK_START` says otherwise
+const DEFAULT_START: i64 = 1_704_067_200; // 2024-01-01T00:00:00Z
+
+/// A source of the current time
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Turns a creation time into an entry ID
+pub trait IdGenerator: Send + Sync {
+    fn id(&self, at: DateTime<Utc>) -> String;
+}
+
+/// The system clock
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that starts at a fixed time and advances a fixed step per reading
+pub struct SteppingClock {
+    start: DateTime<Utc>,
+    step: Duration,
+    readings: AtomicI64,
+}
+
+impl SteppingClock {
+    pub fn new(start: DateTime<Utc>, step: Duration) -> Self {
+        Self {
+            start,
+            step,
+            readings: AtomicI64::new(0),
+        }
+    }
+}
+
+impl Clock for SteppingClock {
+    fn now(&self) -> DateTime<Utc> {
+        let n = self.readings.fetch_add(1, Ordering::Relaxed);
+        self.start + self.step * n as i32
+    }
+}
+
+/// IDs are the creation time in hex milliseconds, so they sort by age
+pub struct HexMillis;
+
+impl IdGenerator for HexMillis {
+    fn id(&self, at: DateTime<Utc>) -> String {
+        format!("{:x}", at.timestamp_millis().max(0))
+    }
+}
+
+struct Source {
+    clock: Box<dyn Clock>,
+    ids: Box<dyn IdGenerator>,
+}
+
+static SOURCE: OnceLock<Source> = OnceLock::new();
+
+/// Use the stepping clock for this process when `deterministic` (or
+/// `PAIS_DETERMINISTIC`) is set; anything read before this used the system clock
+pub fn init(deterministic: bool) {
+    let deterministic = deterministic || std::env::var("PAIS_DETERMINISTIC").is_ok_and(|v| !v.is_empty() && v != "0");
+    if !deterministic {
+        return;
+    }
+    let start = std::env::var("PAIS_CLOCK_START")
+        .ok()
+        .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.timestamp_opt(DEFAULT_START, 0).unwrap());
+    set(SteppingClock::new(start, Duration::seconds(1)), HexMillis);
+}
+
+/// Replace the time and ID source; only the first call takes effect
+pub fn set(clock: impl Clock + 'static, ids: impl IdGenerator + 'static) {
+    let _ = SOURCE.set(Source {
+        clock: Box::new(clock),
+        ids: Box::new(ids),
+    });
+}
+
+fn source() -> &'static Source {
+    SOURCE.get_or_init(|| Source {
+        clock: Box::new(SystemClock),
+        ids: Box::new(HexMillis),
+    })
+}
+
+/// The current time
+pub fn now() -> DateTime<Utc> {
+    source().clock.now()
+}
+
+/// The current time in the local timezone
+pub fn now_local() -> DateTime<Local> {
+    now().with_timezone(&Local)
+}
+
+/// The ID of an entry created at `at`
+pub fn new_id(at: DateTime<Utc>) -> String {
+    source().ids.id(at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stepping_clock_repeats() {
+        let start = Utc.timestamp_opt(DEFAULT_START, 0).unwrap();
+        let run = || {
+            let clock = SteppingClock::new(start, Duration::seconds(1));
+            (0..3).map(|_| HexMillis.id(clock.now())).collect::<Vec<_>>()
+        };
+        let ids = run();
+        assert_eq!(ids, run());
+        assert_eq!(ids[0], "18cc251f400");
+        assert_eq!(ids[1], "18cc251f7e8");
+    }
+}
@@ -1,31 +1,88 @@
 //! Agent management commands
 
 use colored::*;
-use eyre::Result;
+use eyre::{Context, Result};
+use rayon::prelude::*;
 use serde::Serialize;
+use std::collections::HashMap;
 use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::Command;
 
-use crate::agent::loader::AgentLoader;
+use crate::agent::loader::{Agent, AgentLoader};
+use crate::agent::runner::{self, AgentTask};
 use crate::agent::traits::{Trait, TraitCategory};
+use crate::claude::compat;
 use crate::cli::{AgentAction, OutputFormat};
+use crate::commands::skill::print_shared;
+use crate::commands::{self, session};
 use crate::config::Config;
-
-pub fn run(action: AgentAction, config: &Config) -> Result<()> {
+use crate::error::{CliError, ErrorCode};
+use crate::history::categorize::Categories;
+use crate::history::encrypt::Encryption;
+use crate::history::redact::Redactor;
+use crate::history::weekly::COST_KEY;
+use crate::history::{HistoryEntry, HistoryStore};
+use crate::net;
+use crate::picker::pick_name;
+use crate::presenter;
+use crate::progress;
+use crate::provenance::{self, ArtifactKind, Provenance};
+use crate::share::{self, Artifact, ShareKind};
+use crate::state::{self, StateEvent};
+use crate::team;
+
+pub fn run(action: AgentAction, format: OutputFormat, config: &Config) -> Result<()> {
     match action {
-        AgentAction::List { format } => list_agents(OutputFormat::resolve(format), config),
-        AgentAction::Show { name, format } => show_agent(&name, OutputFormat::resolve(format), config),
-        AgentAction::Traits { format } => list_traits(OutputFormat::resolve(format)),
-        AgentAction::Prompt { name } => show_prompt(&name, config),
+        AgentAction::List => list_agents(format, config),
+        AgentAction::Show { name } => show_agent(&pick_agent(name, config)?, format, config),
+        AgentAction::Traits => list_traits(format),
+        AgentAction::Prompt { name } => show_prompt(&pick_agent(name, config)?, config),
         AgentAction::Create { name } => create_agent(&name, config),
+        AgentAction::Run {
+            agent,
+            task,
+            skill,
+            mcp,
+            parallel,
+            jobs,
+            dry_run,
+        } => {
+            let settings = RunSettings { skill, mcp, dry_run };
+            run_tasks(agent, task, parallel, jobs, &settings, format, config)
+        }
+        AgentAction::Share { name, public } => share_agent(&pick_agent(name, config)?, public, format, config),
+        AgentAction::Install { source, force } => install_agent(&source, force, config),
     }
 }
 
-fn list_agents(format: OutputFormat, config: &Config) -> Result<()> {
-    let agents_dir = Config::expand_path(&config.paths.skills)
+/// Directory holding agent YAML files (next to the skills directory)
+pub fn agents_dir(config: &Config) -> PathBuf {
+    Config::expand_path(&config.paths.skills)
         .parent()
         .unwrap_or(&config.paths.skills)
-        .join("agents");
-    let mut loader = AgentLoader::new(agents_dir.clone());
+        .join("agents")
+}
+
+/// Loader for the agents directory plus subscribed team repos
+fn loader(config: &Config) -> AgentLoader {
+    AgentLoader::new(agents_dir(config)).with_team_dirs(team::agent_dirs())
+}
+
+/// Use the given agent name, or pick one of the available agents interactively
+fn pick_agent(name: Option<String>, config: &Config) -> Result<String> {
+    pick_name(name, "agent", || agent_names(config))
+}
+
+/// Names of all agents
+pub fn agent_names(config: &Config) -> Result<Vec<String>> {
+    Ok(loader(config).load_all()?.iter().map(|a| a.name.clone()).collect())
+}
+
+fn list_agents(format: OutputFormat, config: &Config) -> Result<()> {
+    let agents_dir = agents_dir(config);
+    let mut loader = loader(config);
     let agents = loader.load_all()?;
 
     #[derive(Serialize)]
@@ -34,6 +91,8 @@ fn list_agents(format: OutputFormat, config: &Config) -> Result<()> {
         description: String,
         traits: Vec<String>,
         history_category: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        team: Option<String>,
     }
 
     let summaries: Vec<AgentSummary> = agents
@@ -43,6 +102,7 @@ fn list_agents(format: OutputFormat, config: &Config) -> Result<()> {
             description: a.description.clone(),
             traits: a.traits.iter().map(|t| t.to_string()).collect(),
             history_category: a.history_category.clone(),
+            team: loader.team_of(&a.name).map(String::from),
         })
         .collect();
 
@@ -59,7 +119,15 @@ fn list_agents(format: OutputFormat, config: &Config) -> Result<()> {
                 println!("  Create agents with: {}", "pais agent create <name>".cyan());
             } else {
                 for agent in &agents {
-                    println!("  {} {}", "●".green(), agent.name.bold());
+                    match loader.team_of(&agent.name) {
+                        Some(team) => println!(
+                            "  {} {} {}",
+                            "●".green(),
+                            agent.name.bold(),
+                            format!("(team:{})", team).dimmed()
+                        ),
+                        None => println!("  {} {}", "●".green(), agent.name.bold()),
+                    }
                     println!("    {}", agent.description.dimmed());
                     if !agent.traits.is_empty() {
                         let traits: Vec<String> = agent.traits.iter().map(|t| t.to_string()).collect();
@@ -78,18 +146,17 @@ fn list_agents(format: OutputFormat, config: &Config) -> Result<()> {
 }
 
 fn show_agent(name: &str, format: OutputFormat, config: &Config) -> Result<()> {
-    let agents_dir = Config::expand_path(&config.paths.skills)
-        .parent()
-        .unwrap_or(&config.paths.skills)
-        .join("agents");
-    let agent_path = agents_dir.join(format!("{}.yaml", name));
-
-    if !agent_path.exists() {
-        eprintln!("{} Agent '{}' not found at {}", "✗".red(), name, agent_path.display());
+    let loader = loader(config);
+    let Some(agent_path) = loader.find(name) else {
+        eprintln!(
+            "{} Agent '{}' not found in {}",
+            presenter::fail(),
+            name,
+            agents_dir(config).display()
+        );
         return Ok(());
-    }
+    };
 
-    let loader = AgentLoader::new(agents_dir);
     let agent = loader.load_agent(&agent_path)?;
 
     match format {
@@ -99,6 +166,14 @@ fn show_agent(name: &str, format: OutputFormat, config: &Config) -> Result<()> {
             println!("{} {}", "Agent:".bold(), agent.name.green().bold());
             println!();
             println!("{} {}", "Description:".bold(), agent.description);
+            if let Some(team) = team::list().into_iter().find(|t| agent_path.starts_with(&t.path)) {
+                println!(
+                    "{} team:{} {}",
+                    "Source:".bold(),
+                    team.name,
+                    team.url().unwrap_or_default().dimmed()
+                );
+            }
 
             if let Some(ref backstory) = agent.backstory {
                 println!();
@@ -133,6 +208,19 @@ fn show_agent(name: &str, format: OutputFormat, config: &Config) -> Result<()> {
                 println!();
                 println!("{} {}", "History Category:".bold(), cat.magenta());
             }
+
+            let (provenance, recorded) = commands::provenance::resolve(
+                &commands::provenance::load(),
+                ArtifactKind::Agent,
+                name,
+                &agent_path,
+                || (None, None),
+            );
+            println!();
+            println!("{}", "Provenance:".bold());
+            for (label, value) in commands::provenance::details(&provenance, recorded) {
+                println!("  {} {}", format!("{}:", label).dimmed(), value);
+            }
         }
     }
 
@@ -228,18 +316,12 @@ fn list_traits(format: OutputFormat) -> Result<()> {
 }
 
 fn show_prompt(name: &str, config: &Config) -> Result<()> {
-    let agents_dir = Config::expand_path(&config.paths.skills)
-        .parent()
-        .unwrap_or(&config.paths.skills)
-        .join("agents");
-    let agent_path = agents_dir.join(format!("{}.yaml", name));
-
-    if !agent_path.exists() {
-        eprintln!("{} Agent '{}' not found", "✗".red(), name);
+    let loader = loader(config);
+    let Some(agent_path) = loader.find(name) else {
+        eprintln!("{} Agent '{}' not found", presenter::fail(), name);
         return Ok(());
-    }
+    };
 
-    let loader = AgentLoader::new(agents_dir);
     let agent = loader.load_agent(&agent_path)?;
     let prompt = agent.generate_prompt();
 
@@ -249,10 +331,7 @@ fn show_prompt(name: &str, config: &Config) -> Result<()> {
 }
 
 fn create_agent(name: &str, config: &Config) -> Result<()> {
-    let agents_dir = Config::expand_path(&config.paths.skills)
-        .parent()
-        .unwrap_or(&config.paths.skills)
-        .join("agents");
+    let agents_dir = agents_dir(config);
     fs::create_dir_all(&agents_dir)?;
 
     let agent_path = agents_dir.join(format!("{}.yaml", name));
@@ -260,7 +339,7 @@ fn create_agent(name: &str, config: &Config) -> Result<()> {
     if agent_path.exists() {
         eprintln!(
             "{} Agent '{}' already exists at {}",
-            "✗".red(),
+            presenter::fail(),
             name,
             agent_path.display()
         );
@@ -287,6 +366,10 @@ traits:
 # Where this agent's outputs are categorized in history
 history_category: research
 
+# Skills and MCP servers (names or profiles) for 'pais agent run'
+# skills: [rust-coder]
+# mcp: [github]
+
 # Example communication style phrases
 communication_style:
   - "Let me analyze this systematically..."
@@ -297,7 +380,7 @@ communication_style:
 
     fs::write(&agent_path, template)?;
 
-    println!("{} Created agent template: {}", "✓".green(), agent_path.display());
+    println!("{} Created agent template: {}", presenter::ok(), agent_path.display());
     println!();
     println!("Next steps:");
     println!("  1. Edit {} to customize the agent", agent_path.display());
@@ -305,3 +388,350 @@ communication_style:
 
     Ok(())
 }
+
+/// Skill and MCP overrides and flags shared by every task of `pais agent run`
+struct RunSettings {
+    skill: Option<Vec<String>>,
+    mcp: Option<Vec<String>>,
+    dry_run: bool,
+}
+
+/// Outcome of one headless agent run
+#[derive(Serialize)]
+struct RunReport {
+    agent: String,
+    task: String,
+    success: bool,
+    output: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cost_usd: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_ms: Option<u64>,
+    /// History entry the output was saved as
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entry: Option<String>,
+}
+
+/// Run one task, or a task list with `--parallel`, through `claude -p`
+fn run_tasks(
+    agent: Option<String>,
+    task: Option<String>,
+    parallel: Option<PathBuf>,
+    jobs: usize,
+    settings: &RunSettings,
+    format: OutputFormat,
+    config: &Config,
+) -> Result<()> {
+    let agents: HashMap<String, Agent> = loader(config)
+        .load_all()?
+        .into_iter()
+        .map(|a| (a.name.clone(), a))
+        .collect();
+
+    let tasks = match parallel {
+        Some(file) => {
+            let content = if file.as_os_str() == "-" {
+                let mut buffer = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut buffer)
+                    .context("Failed to read tasks from stdin")?;
+                buffer
+            } else {
+                fs::read_to_string(&file).with_context(|| format!("Failed to read task list {}", file.display()))?
+            };
+            let names: Vec<String> = agents.keys().cloned().collect();
+            runner::parse_tasks(&content, agent.as_deref(), &names)
+                .map_err(|e| CliError::new(ErrorCode::InvalidInput, format!("{}", e)))?
+        }
+        None => {
+            let agent = pick_agent(agent, config)?;
+            let task = task.ok_or_else(|| {
+                CliError::new(ErrorCode::InvalidInput, "No task given")
+                    .with_hint(format!("pais agent run {} \"describe the task\"", agent))
+            })?;
+            vec![AgentTask { agent, task }]
+        }
+    };
+    if tasks.is_empty() {
+        return Err(CliError::new(ErrorCode::InvalidInput, "The task list is empty").into());
+    }
+
+    let cwd = std::env::current_dir().context("Failed to get current directory")?;
+    let version = compat::detect();
+    let mut commands = Vec::new();
+    for task in &tasks {
+        let agent = agents
+            .get(&task.agent)
+            .ok_or_else(|| CliError::not_found("Agent", &task.agent))?;
+        let skills = load_skills(settings.skill.as_ref().unwrap_or(&agent.skills), config)?;
+        let mcp = session::expand_names(settings.mcp.as_ref().unwrap_or(&agent.mcp), &config.mcp.profiles);
+        let flags = if mcp.is_empty() {
+            Vec::new()
+        } else {
+            let (path, _) = session::build_mcp_config(&mcp, config)?;
+            compat::launch_flags(version, Some(&path))
+        };
+        let prompt = runner::system_prompt(agent, &skills);
+        commands.push(runner::command(&task.task, &prompt, flags, &cwd));
+    }
+
+    if settings.dry_run {
+        return print_dry_run(&tasks, &commands, format);
+    }
+
+    let pb = progress::bar(format, tasks.len() as u64, "Running agents");
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs.max(1))
+        .build()
+        .context("Failed to start worker threads")?;
+    let results: Vec<Result<(runner::ClaudeResult, bool)>> = pool.install(|| {
+        commands
+            .into_par_iter()
+            .map(|cmd| {
+                let result = runner::execute(cmd);
+                pb.inc(1);
+                result
+            })
+            .collect()
+    });
+    pb.finish_and_clear();
+
+    let categories = Categories::from_config(&config.history);
+    let store = HistoryStore::new(Config::expand_path(&config.paths.history))
+        .with_redactor(Redactor::from_config(&config.history.redaction))
+        .with_categories(categories.clone())
+        .with_index(config.history.index.enabled)
+        .with_encryption(Encryption::from_config(&config.history.encryption));
+    let mut reports = Vec::new();
+    for (task, result) in tasks.into_iter().zip(results) {
+        let (result, success) = match result {
+            Ok(result) => result,
+            Err(e) => (
+                runner::ClaudeResult {
+                    result: format!("{:#}", e),
+                    is_error: true,
+                    ..Default::default()
+                },
+                false,
+            ),
+        };
+        let entry = if success {
+            let category = categories.resolve(agents[&task.agent].history_category.as_deref(), &result.result);
+            let mut entry = HistoryEntry::new(
+                &category,
+                &presenter::truncate(&task.task, 60),
+                &format!("## Task\n\n{}\n\n## Output\n\n{}\n", task.task, result.result.trim()),
+            )
+            .with_tag("agent-run")
+            .with_tag(&format!("agent:{}", task.agent))
+            .with_metadata("agent", &task.agent);
+            if let Some(session_id) = &result.session_id {
+                entry = entry.with_metadata("session_id", session_id);
+            }
+            if let Some(cost) = result.total_cost_usd {
+                entry = entry.with_metadata(COST_KEY, &format!("{:.4}", cost));
+            }
+            store.store(&entry)?;
+            Some(entry.id)
+        } else {
+            None
+        };
+        reports.push(RunReport {
+            agent: task.agent,
+            task: task.task,
+            success,
+            output: result.result,
+            session_id: result.session_id,
+            cost_usd: result.total_cost_usd,
+            duration_ms: result.duration_ms,
+            entry,
+        });
+    }
+
+    print_reports(&reports, format)?;
+    if reports.iter().any(|r| !r.success) {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// SKILL.md contents of the named skills (profiles expanded)
+fn load_skills(names: &[String], config: &Config) -> Result<Vec<(String, String)>> {
+    session::expand_names(names, &config.skills.profiles)
+        .into_iter()
+        .map(|name| {
+            let dir = session::find_skill_source(&name, config).ok_or_else(|| CliError::not_found("Skill", &name))?;
+            let path = dir.join("SKILL.md");
+            let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+            Ok((name, content))
+        })
+        .collect()
+}
+
+fn print_dry_run(tasks: &[AgentTask], commands: &[Command], format: OutputFormat) -> Result<()> {
+    #[derive(Serialize)]
+    struct Planned<'a> {
+        agent: &'a str,
+        task: &'a str,
+        command: Vec<String>,
+    }
+
+    let planned: Vec<Planned> = tasks
+        .iter()
+        .zip(commands)
+        .map(|(task, cmd)| Planned {
+            agent: &task.agent,
+            task: &task.task,
+            command: std::iter::once(cmd.get_program())
+                .chain(cmd.get_args())
+                .map(|a| a.to_string_lossy().to_string())
+                .collect(),
+        })
+        .collect();
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&planned)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&planned)?),
+        OutputFormat::Text => {
+            println!("{}", "Dry run - would run:".yellow());
+            for plan in &planned {
+                println!();
+                println!("  {} {}", plan.agent.bold(), plan.task);
+                let args: Vec<String> = plan
+                    .command
+                    .iter()
+                    .map(|a| presenter::truncate(&a.replace('\n', " "), 60))
+                    .collect();
+                println!("    {}", args.join(" ").dimmed());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_reports(reports: &[RunReport], format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Json => match reports {
+            [one] => println!("{}", serde_json::to_string_pretty(one)?),
+            _ => println!("{}", serde_json::to_string_pretty(reports)?),
+        },
+        OutputFormat::Yaml => match reports {
+            [one] => println!("{}", serde_yaml::to_string(one)?),
+            _ => println!("{}", serde_yaml::to_string(reports)?),
+        },
+        OutputFormat::Text => {
+            for report in reports {
+                if reports.len() > 1 {
+                    println!("{}", presenter::banner(&format!("{}: {}", report.agent, report.task)));
+                }
+                println!("{}", report.output.trim_end());
+                println!();
+
+                let mut footer = Vec::new();
+                if let Some(entry) = &report.entry {
+                    footer.push(format!("saved as {}", entry));
+                }
+                if let Some(cost) = report.cost_usd {
+                    footer.push(format!("${:.4}", cost));
+                }
+                if let Some(ms) = report.duration_ms {
+                    footer.push(format!("{:.1}s", ms as f64 / 1000.0));
+                }
+                let marker = if report.success { presenter::ok() } else { presenter::fail() };
+                println!("{} {} {}", marker, report.agent.bold(), footer.join(" · ").dimmed());
+                println!();
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Publish an agent as a gist
+fn share_agent(name: &str, public: bool, format: OutputFormat, config: &Config) -> Result<()> {
+    let agent_path = agents_dir(config).join(format!("{}.yaml", name));
+    if !agent_path.exists() {
+        return Err(CliError::not_found("Agent", name)
+            .with_hint("List agents with: pais agent list")
+            .into());
+    }
+    net::ensure_online(&config.network, "Sharing an agent")?;
+
+    let agent = AgentLoader::new(agents_dir(config)).load_agent(&agent_path)?;
+    let artifact = Artifact::from_file(ShareKind::Agent, name, &agent_path)?;
+    let token = share::github_token()?;
+
+    let spinner = progress::spinner(format, "Uploading agent".to_string());
+    let description = format!("pais agent: {} - {}", name, agent.description);
+    let shared = share::create_gist(&artifact, &description, public, &token);
+    spinner.finish_and_clear();
+
+    print_shared(&shared?, format)
+}
+
+/// Install an agent from a shared gist
+fn install_agent(source: &str, force: bool, config: &Config) -> Result<()> {
+    let id = share::parse_gist_id(source).ok_or_else(|| {
+        CliError::new(ErrorCode::InvalidInput, format!("Not a gist URL or ID: {}", source))
+            .with_hint("Install from a URL printed by: pais agent share <name>")
+    })?;
+    net::ensure_online(&config.network, "Installing from a gist")?;
+    let artifact = share::fetch_gist(&id)?;
+
+    if artifact.manifest.kind != ShareKind::Agent {
+        return Err(CliError::new(
+            ErrorCode::InvalidInput,
+            format!(
+                "Gist contains {} '{}', not an agent",
+                artifact.manifest.kind, artifact.manifest.name
+            ),
+        )
+        .with_hint(format!(
+            "Install it with: pais {} install {}",
+            artifact.manifest.kind, source
+        ))
+        .into());
+    }
+
+    let name = &artifact.manifest.name;
+    let agents_dir = agents_dir(config);
+    let agent_path = agents_dir.join(format!("{}.yaml", name));
+    if agent_path.exists() && !force {
+        return Err(
+            CliError::new(ErrorCode::Conflict, format!("Agent '{}' already exists", name))
+                .with_hint("Overwrite it with --force")
+                .into(),
+        );
+    }
+
+    // Agents are a single YAML file; store it under the canonical name
+    let content = artifact
+        .files
+        .values()
+        .next()
+        .ok_or_else(|| eyre::eyre!("Shared agent '{}' has no files", name))?;
+    fs::create_dir_all(&agents_dir)
+        .with_context(|| format!("Failed to create agents directory: {}", agents_dir.display()))?;
+    fs::write(&agent_path, content).with_context(|| format!("Failed to write {}", agent_path.display()))?;
+
+    if let Err(e) = AgentLoader::new(agents_dir).load_agent(&agent_path) {
+        log::warn!("Installed agent '{}' failed to load: {}", name, e);
+    }
+    state::record(StateEvent::AgentInstalled {
+        name: name.to_string(),
+        source: artifact
+            .origin
+            .as_ref()
+            .map_or_else(|| source.to_string(), |o| o.url.clone()),
+    });
+    if let Some(origin) = &artifact.origin
+        && let Err(e) = provenance::record(ArtifactKind::Agent, name, Provenance::from_gist(origin))
+    {
+        log::warn!("Failed to record provenance of agent '{}': {:#}", name, e);
+    }
+
+    println!("{} Installed agent: {}", presenter::ok(), name.cyan());
+    println!("  Path: {}", agent_path.display());
+    Ok(())
+}
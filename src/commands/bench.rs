@@ -0,0 +1,224 @@
+//! Hidden `pais bench` command
+//!
+//! Times the user-facing hot paths against the live config and compares each
+//! to its budget (see docs/performance.md). Hook dispatch and context
+//! injection run as child processes, because that is how Claude Code pays for
+//! them, startup included. The skill index and history query run in-process
+//! so nothing is written to the skills directory.
+//!
+//! Children get a copy of the config (`~/.cache/pais/bench/pais.yaml`) with
+//! logging, observability and history capture switched off, so benchmarking
+//! leaves no trace in the user's history.
+
+use colored::*;
+use eyre::{Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::cli::OutputFormat;
+use crate::config::{Config, LogLevel};
+use crate::history::HistoryStore;
+use crate::perf::{
+    CONTEXT_INJECT_BUDGET_MS, HISTORY_QUERY_BUDGET_MS, HOOK_DISPATCH_BUDGET_MS, SKILL_INDEX_BUDGET_MS, millis,
+    percentiles,
+};
+use crate::presenter;
+use crate::skill::indexer::generate_index;
+
+/// Harmless PreToolUse payload: exercises every handler without being blocked
+const DISPATCH_PAYLOAD: &str = r#"{"tool_name":"Bash","tool_input":{"command":"ls -la"},"session_id":"pais-bench"}"#;
+
+struct Case<'a> {
+    name: &'static str,
+    budget_ms: u64,
+    run: Box<dyn Fn() -> Result<()> + 'a>,
+}
+
+/// Timing for one hot path
+#[derive(Debug, Serialize)]
+struct BenchResult {
+    name: &'static str,
+    runs: usize,
+    median_ms: f64,
+    p95_ms: f64,
+    budget_ms: u64,
+    within_budget: bool,
+}
+
+pub fn run(iterations: usize, check: bool, format: OutputFormat, config: &Config) -> Result<()> {
+    let iterations = iterations.max(1);
+    let exe = std::env::current_exe().context("Failed to locate the pais binary")?;
+
+    // A fixed path, so the config snapshot hook dispatch keeps is reused across runs
+    let config_path = dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("pais")
+        .join("bench")
+        .join("pais.yaml");
+    if let Some(parent) = config_path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(&config_path, serde_yaml::to_string(&quiet_config(config))?)
+        .with_context(|| format!("Failed to write {}", config_path.display()))?;
+    let config_path = config_path.as_path();
+
+    let skills_dir = Config::expand_path(&config.paths.skills);
+    let store = HistoryStore::new(Config::expand_path(&config.paths.history));
+
+    let cases = vec![
+        Case {
+            name: "hook-dispatch",
+            budget_ms: HOOK_DISPATCH_BUDGET_MS,
+            run: Box::new(|| {
+                spawn(
+                    &exe,
+                    config_path,
+                    &["hook", "dispatch", "PreToolUse", "--payload", DISPATCH_PAYLOAD],
+                )
+            }),
+        },
+        Case {
+            name: "context-inject",
+            budget_ms: CONTEXT_INJECT_BUDGET_MS,
+            run: Box::new(|| spawn(&exe, config_path, &["context", "inject"])),
+        },
+        Case {
+            name: "skill-index",
+            budget_ms: SKILL_INDEX_BUDGET_MS,
+            run: Box::new(|| generate_index(&skills_dir).map(|_| ())),
+        },
+        Case {
+            name: "history-query",
+            budget_ms: HISTORY_QUERY_BUDGET_MS,
+            run: Box::new(|| store.query("session", None, None, 10).map(|_| ())),
+        },
+    ];
+
+    let mut results = Vec::new();
+    for case in &cases {
+        if format == OutputFormat::Text {
+            eprintln!("{} {} ({} runs)", presenter::arrow(), case.name, iterations);
+        }
+        results.push(measure(case, iterations)?);
+    }
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&results)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&results)?),
+        OutputFormat::Text => print_results(&results),
+    }
+
+    if check && results.iter().any(|r| !r.within_budget) {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// The live config with every side-effecting sink switched off
+fn quiet_config(config: &Config) -> Config {
+    let mut quiet = config.clone();
+    quiet.log_level = LogLevel::Off;
+    quiet.observability.enabled = false;
+    quiet.hooks.history_enabled = false;
+    quiet.hooks.ui_enabled = false;
+    quiet.perf.enabled = false;
+    quiet
+}
+
+fn spawn(exe: &Path, config_path: &Path, args: &[&str]) -> Result<()> {
+    let status = Command::new(exe)
+        .arg("--config")
+        .arg(config_path)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .with_context(|| format!("Failed to run pais {}", args.join(" ")))?;
+    if !status.success() {
+        eyre::bail!("pais {} exited with {}", args[..2].join(" "), status);
+    }
+    Ok(())
+}
+
+/// Run a case once to warm caches, then `iterations` timed runs
+fn measure(case: &Case, iterations: usize) -> Result<BenchResult> {
+    (case.run)().with_context(|| format!("{} failed", case.name))?;
+
+    let mut samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        (case.run)()?;
+        samples.push(start.elapsed());
+    }
+
+    let (median, p95) = percentiles(&mut samples);
+    Ok(BenchResult {
+        name: case.name,
+        runs: iterations,
+        median_ms: millis(median),
+        p95_ms: millis(p95),
+        budget_ms: case.budget_ms,
+        within_budget: median <= Duration::from_millis(case.budget_ms),
+    })
+}
+
+fn print_results(results: &[BenchResult]) {
+    println!();
+    println!(
+        "{:<16} {:>10} {:>10} {:>8}",
+        "PATH".bold(),
+        "MEDIAN".bold(),
+        "P95".bold(),
+        "BUDGET".bold()
+    );
+    for r in results {
+        let status = if r.within_budget { presenter::ok() } else { presenter::fail() };
+        println!(
+            "{:<16} {:>8.1}ms {:>8.1}ms {:>6}ms  {}",
+            r.name, r.median_ms, r.p95_ms, r.budget_ms, status
+        );
+    }
+
+    if cfg!(debug_assertions) {
+        println!();
+        println!(
+            "{}",
+            "Debug build: budgets assume a release build (cargo build --release)".yellow()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles() {
+        let mut samples: Vec<Duration> = (1..=20).rev().map(Duration::from_millis).collect();
+        let (median, p95) = percentiles(&mut samples);
+        assert_eq!(median, Duration::from_millis(11));
+        assert_eq!(p95, Duration::from_millis(19));
+
+        let mut one = vec![Duration::from_millis(7)];
+        assert_eq!(
+            percentiles(&mut one),
+            (Duration::from_millis(7), Duration::from_millis(7))
+        );
+    }
+
+    #[test]
+    fn test_quiet_config_disables_side_effects() {
+        let quiet = quiet_config(&Config::default());
+        assert_eq!(quiet.log_level, LogLevel::Off);
+        assert!(!quiet.observability.enabled);
+        assert!(!quiet.hooks.history_enabled);
+        assert!(!quiet.perf.enabled);
+        // The checks being timed stay on
+        assert!(quiet.hooks.security_enabled);
+    }
+}
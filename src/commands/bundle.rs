@@ -8,21 +8,50 @@ use std::path::PathBuf;
 
 use crate::bundle::manager::BundleManager;
 use crate::cli::{BundleAction, OutputFormat};
+use crate::commands;
 use crate::config::Config;
-
-pub fn run(action: BundleAction, config: &Config) -> Result<()> {
+use crate::error::CliError;
+use crate::picker::pick_name;
+use crate::presenter;
+use crate::progress;
+use crate::provenance::{self, ArtifactKind, Provenance};
+use crate::state::{self, StateEvent};
+use crate::team;
+
+pub fn run(action: BundleAction, format: OutputFormat, config: &Config) -> Result<()> {
     match action {
-        BundleAction::List { format } => list(OutputFormat::resolve(format), config),
-        BundleAction::Show { name, format } => show(&name, OutputFormat::resolve(format), config),
+        BundleAction::List => list(format, config),
+        BundleAction::Show { name } => show(&pick_bundle(name, config)?, format, config),
         BundleAction::Install {
             name,
             required_only,
             skip_verify,
-        } => install(&name, required_only, skip_verify, config),
+        } => install(&pick_bundle(name, config)?, required_only, skip_verify, format, config),
         BundleAction::New { name, path } => new(&name, path, config),
     }
 }
 
+/// Use the given bundle name, or pick one of the available bundles interactively
+fn pick_bundle(name: Option<String>, config: &Config) -> Result<String> {
+    pick_name(name, "bundle", || bundle_names(config))
+}
+
+/// Bundle manager over personal and team bundles
+fn manager(config: &Config) -> BundleManager {
+    BundleManager::new(
+        Config::pais_dir().join("bundles"),
+        Config::expand_path(&config.paths.plugins),
+    )
+    .with_team_dirs(team::bundle_dirs())
+}
+
+/// Names of all available bundles
+pub fn bundle_names(config: &Config) -> Result<Vec<String>> {
+    let mut manager = manager(config);
+    manager.discover()?;
+    Ok(manager.list().map(|b| b.manifest.bundle.name.clone()).collect())
+}
+
 #[derive(Serialize)]
 struct BundleInfo {
     name: String,
@@ -31,12 +60,14 @@ struct BundleInfo {
     plugin_count: usize,
     required_count: usize,
     optional_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    team: Option<String>,
 }
 
 fn list(format: OutputFormat, config: &Config) -> Result<()> {
     let bundles_dir = Config::pais_dir().join("bundles");
 
-    let mut manager = BundleManager::new(bundles_dir.clone(), Config::expand_path(&config.paths.plugins));
+    let mut manager = manager(config);
     manager.discover()?;
 
     let bundles: Vec<BundleInfo> = manager
@@ -51,6 +82,7 @@ fn list(format: OutputFormat, config: &Config) -> Result<()> {
                 plugin_count: required + optional,
                 required_count: required,
                 optional_count: optional,
+                team: b.team.clone(),
             }
         })
         .collect();
@@ -75,12 +107,18 @@ fn list(format: OutputFormat, config: &Config) -> Result<()> {
                 println!("  Bundles directory: {}", bundles_dir.display());
             } else {
                 for bundle in &bundles {
+                    let team = bundle
+                        .team
+                        .as_ref()
+                        .map(|t| format!(" (team:{})", t).cyan().to_string())
+                        .unwrap_or_default();
                     println!(
-                        "  {} {} - {} ({} plugins)",
+                        "  {} {} - {} ({} plugins){}",
                         bundle.name.green(),
                         format!("v{}", bundle.version).dimmed(),
                         bundle.description,
-                        bundle.plugin_count
+                        bundle.plugin_count,
+                        team
                     );
                 }
             }
@@ -102,6 +140,8 @@ struct BundleDetail {
     environment: Vec<EnvVar>,
     post_install: Vec<String>,
     conflicts: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    team: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -118,14 +158,12 @@ struct EnvVar {
 }
 
 fn show(name: &str, format: OutputFormat, config: &Config) -> Result<()> {
-    let bundles_dir = Config::pais_dir().join("bundles");
-
-    let mut manager = BundleManager::new(bundles_dir, Config::expand_path(&config.paths.plugins));
+    let mut manager = manager(config);
     manager.discover()?;
 
     let bundle = manager
         .get(name)
-        .ok_or_else(|| eyre::eyre!("Bundle not found: {}", name))?;
+        .ok_or_else(|| CliError::not_found("Bundle", name).with_hint("List bundles with: pais bundle list"))?;
 
     let detail = BundleDetail {
         name: bundle.manifest.bundle.name.clone(),
@@ -155,6 +193,7 @@ fn show(name: &str, format: OutputFormat, config: &Config) -> Result<()> {
             .collect(),
         post_install: bundle.manifest.post_install.iter().map(|c| c.command.clone()).collect(),
         conflicts: bundle.manifest.conflicts.clone(),
+        team: bundle.team.clone(),
     };
 
     match format {
@@ -178,6 +217,9 @@ fn show(name: &str, format: OutputFormat, config: &Config) -> Result<()> {
             if let Some(ref pais_ver) = detail.pais_version {
                 println!("  {} {}", "PAIS version:".dimmed(), pais_ver);
             }
+            if let Some(ref team) = detail.team {
+                println!("  {} team:{}", "Source:".dimmed(), team);
+            }
             println!();
 
             println!("{} ({}):", "Plugins".cyan(), detail.plugins.len());
@@ -211,24 +253,32 @@ fn show(name: &str, format: OutputFormat, config: &Config) -> Result<()> {
                 println!();
                 println!("{}: {}", "Conflicts with".red(), detail.conflicts.join(", "));
             }
+
+            let (provenance, recorded) = commands::provenance::resolve(
+                &commands::provenance::load(),
+                ArtifactKind::Bundle,
+                name,
+                &bundle.path,
+                || (detail.author.clone(), detail.license.clone()),
+            );
+            println!();
+            println!("{}:", "Provenance".cyan());
+            for (label, value) in commands::provenance::details(&provenance, recorded) {
+                println!("  {} {}", format!("{}:", label).dimmed(), value);
+            }
         }
     }
 
     Ok(())
 }
 
-fn install(name: &str, required_only: bool, skip_verify: bool, config: &Config) -> Result<()> {
-    let bundles_dir = Config::pais_dir().join("bundles");
-
-    let manager = BundleManager::new(bundles_dir, Config::expand_path(&config.paths.plugins));
-
-    // Need to re-discover since we moved manager
-    let mut manager = manager;
+fn install(name: &str, required_only: bool, skip_verify: bool, format: OutputFormat, config: &Config) -> Result<()> {
+    let mut manager = manager(config);
     manager.discover()?;
 
     println!(
         "{} Installing bundle: {}{}",
-        "→".blue(),
+        presenter::arrow(),
         name.cyan(),
         if required_only {
             " (required only)".dimmed().to_string()
@@ -237,8 +287,21 @@ fn install(name: &str, required_only: bool, skip_verify: bool, config: &Config)
         }
     );
 
-    let result = manager.install(name, required_only, skip_verify)?;
+    let progress = progress::bar(format, 0, name.to_string());
+    let result = manager.install(name, required_only, skip_verify, &progress)?;
     result.print_summary();
+    state::record(StateEvent::BundleInstalled {
+        name: name.to_string(),
+        plugins: result.installed.clone(),
+    });
+
+    if let Some(bundle) = manager.get(name) {
+        let info = &bundle.manifest.bundle;
+        let provenance = Provenance::from_dir(&bundle.path).with_declared(info.author.clone(), info.license.clone());
+        if let Err(e) = provenance::record(ArtifactKind::Bundle, name, provenance) {
+            log::warn!("Failed to record provenance of bundle '{}': {:#}", name, e);
+        }
+    }
 
     Ok(())
 }
@@ -247,7 +310,7 @@ fn new(name: &str, path: Option<PathBuf>, _config: &Config) -> Result<()> {
     let bundles_dir = Config::pais_dir().join("bundles");
     let output_path = path.unwrap_or_else(|| bundles_dir.join(name));
 
-    println!("{} Creating new bundle: {}", "→".blue(), name.cyan());
+    println!("{} Creating new bundle: {}", presenter::arrow(), name.cyan());
     println!("  Output: {}", output_path.display());
 
     if output_path.exists() {
@@ -260,7 +323,7 @@ fn new(name: &str, path: Option<PathBuf>, _config: &Config) -> Result<()> {
     let manifest_content = generate_bundle_manifest(name);
     fs::write(output_path.join("bundle.yaml"), manifest_content).context("Failed to write bundle.yaml")?;
 
-    println!("  {} Created bundle scaffold", "✓".green());
+    println!("  {} Created bundle scaffold", presenter::ok());
     println!();
     println!("  Next steps:");
     println!("    1. Edit {}/bundle.yaml", output_path.display());
@@ -0,0 +1,128 @@
+//! Git checkpoint commands
+//!
+//! Lists the checkpoints the checkpoint hook took and restores one into the
+//! working tree. See [`crate::checkpoint`].
+
+use colored::*;
+use eyre::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::checkpoint::{self, Checkpoint};
+use crate::cli::{CheckpointAction, OutputFormat};
+use crate::config::CheckpointMode;
+use crate::error::{CliError, ErrorCode};
+use crate::presenter::{self, Table};
+
+/// Session name of the snapshot taken before each restore
+const BACKUP_SESSION: &str = "pre-restore";
+
+/// Run a checkpoint subcommand
+pub fn run(action: CheckpointAction, format: OutputFormat) -> Result<()> {
+    match action {
+        CheckpointAction::List { session, path } => list(&repo(path)?, session.as_deref(), format),
+        CheckpointAction::Restore { checkpoint, path } => restore(&repo(path)?, &checkpoint, format),
+    }
+}
+
+/// Repository root for `--path` (or the current directory)
+fn repo(path: Option<PathBuf>) -> Result<PathBuf> {
+    let dir = match path {
+        Some(path) => path,
+        None => std::env::current_dir().context("Failed to get current directory")?,
+    };
+    checkpoint::repo_root(&dir).ok_or_else(|| {
+        CliError::new(
+            ErrorCode::InvalidInput,
+            format!("{} is not in a git repository", dir.display()),
+        )
+        .with_hint("Run from the repository the agent edited, or pass --path")
+        .into()
+    })
+}
+
+fn list(root: &Path, session: Option<&str>, format: OutputFormat) -> Result<()> {
+    let checkpoints: Vec<Checkpoint> = checkpoint::list(root)?
+        .into_iter()
+        .filter(|c| session.is_none_or(|s| c.session.starts_with(s)))
+        .collect();
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&checkpoints)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&checkpoints)?),
+        OutputFormat::Text => {
+            if checkpoints.is_empty() {
+                println!("{} No checkpoints in {}", presenter::info(), root.display());
+                println!("  Enable them with hooks.checkpoint.enabled: true in pais.yaml");
+                return Ok(());
+            }
+
+            let mut table = Table::new(&["CHECKPOINT", "SESSION", "#", "CREATED", "KIND", "FILES"]);
+            for c in &checkpoints {
+                table.add_row(vec![
+                    c.short_id().cyan(),
+                    c.session[..8.min(c.session.len())].normal(),
+                    c.number.to_string().normal(),
+                    c.created.format("%Y-%m-%d %H:%M:%S").to_string().dimmed(),
+                    c.kind.normal(),
+                    c.files.join(", ").normal(),
+                ]);
+            }
+            table.print();
+            println!();
+            println!(
+                "{} Restore one with {}",
+                presenter::arrow(),
+                "pais checkpoint restore <CHECKPOINT>".cyan()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn restore(root: &Path, id: &str, format: OutputFormat) -> Result<()> {
+    let checkpoints = checkpoint::list(root)?;
+    let matches: Vec<&Checkpoint> = checkpoints.iter().filter(|c| c.commit.starts_with(id)).collect();
+    let target = match matches.as_slice() {
+        [one] => one,
+        [] => return Err(CliError::not_found("Checkpoint", id).into()),
+        _ => {
+            return Err(
+                CliError::new(ErrorCode::InvalidInput, format!("Checkpoint '{}' is ambiguous", id))
+                    .with_hint("Use more characters of the checkpoint id")
+                    .into(),
+            );
+        }
+    };
+
+    // Snapshot the current state first so the restore can be undone
+    let number = checkpoints.iter().filter(|c| c.session == BACKUP_SESSION).count() + 1;
+    let backup = checkpoint::create(root, BACKUP_SESSION, number, &[], CheckpointMode::Ref)?;
+    checkpoint::restore(root, &target.commit)?;
+
+    match format {
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({"restored": target, "backup": backup}))?
+        ),
+        OutputFormat::Yaml => println!(
+            "{}",
+            serde_yaml::to_string(&serde_json::json!({"restored": target, "backup": backup}))?
+        ),
+        OutputFormat::Text => {
+            println!(
+                "{} Restored checkpoint {} ({} of session {})",
+                presenter::ok(),
+                target.short_id().cyan(),
+                target.number,
+                &target.session[..8.min(target.session.len())]
+            );
+            if let Some(backup) = backup {
+                println!(
+                    "  Previous working tree saved as {}; files created since the checkpoint were kept",
+                    backup[..10.min(backup.len())].cyan()
+                );
+            }
+        }
+    }
+    Ok(())
+}
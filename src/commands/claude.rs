@@ -0,0 +1,154 @@
+//! Claude Code settings commands
+
+use colored::*;
+use eyre::Result;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::claude::settings::{ClaudeSettings, EVENTS, EventHooks};
+use crate::cli::{ClaudeAction, ClaudeHooksAction, OutputFormat};
+use crate::config::Config;
+use crate::lock::FileLock;
+use crate::presenter;
+
+pub fn run(action: ClaudeAction, format: OutputFormat, _config: &Config) -> Result<()> {
+    match action {
+        ClaudeAction::Hooks { action } => match action {
+            ClaudeHooksAction::Install { project, dry_run } => install(project.as_deref(), dry_run),
+            ClaudeHooksAction::Uninstall { project, dry_run } => uninstall(project.as_deref(), dry_run),
+            ClaudeHooksAction::Show { project } => show(project.as_deref(), format),
+        },
+    }
+}
+
+/// Resolve the settings file for `--project [DIR]` or the user settings
+fn settings_path(project: Option<&Path>) -> Result<PathBuf> {
+    match project {
+        Some(dir) => Ok(ClaudeSettings::project_path(&Config::expand_path(dir))),
+        None => ClaudeSettings::user_path(),
+    }
+}
+
+fn print_dry_run(settings: &ClaudeSettings) -> Result<()> {
+    println!("{}", "Dry run - would write:".yellow());
+    println!("{}", serde_json::to_string_pretty(&settings.value)?);
+    Ok(())
+}
+
+fn install(project: Option<&Path>, dry_run: bool) -> Result<()> {
+    let path = settings_path(project)?;
+    let _lock = FileLock::acquire(&path)?;
+    let mut settings = ClaudeSettings::load(&path)?;
+    let added = settings.install(EVENTS)?;
+
+    if added.is_empty() {
+        println!(
+            "{} PAIS hooks already registered in {}",
+            presenter::ok(),
+            path.display()
+        );
+        return Ok(());
+    }
+
+    if dry_run {
+        return print_dry_run(&settings);
+    }
+
+    settings.save()?;
+    println!(
+        "{} Registered {} hook event(s) in {}",
+        presenter::ok(),
+        added.len(),
+        path.display()
+    );
+    for event in &added {
+        println!("  {} {}", "+".green(), event);
+    }
+    Ok(())
+}
+
+fn uninstall(project: Option<&Path>, dry_run: bool) -> Result<()> {
+    let path = settings_path(project)?;
+    if !path.exists() {
+        println!("{} No settings at {}", presenter::arrow(), path.display());
+        return Ok(());
+    }
+
+    let _lock = FileLock::acquire(&path)?;
+    let mut settings = ClaudeSettings::load(&path)?;
+    let removed = settings.uninstall();
+
+    if removed == 0 {
+        println!("{} No PAIS hooks registered in {}", presenter::arrow(), path.display());
+        return Ok(());
+    }
+
+    if dry_run {
+        return print_dry_run(&settings);
+    }
+
+    settings.save()?;
+    println!(
+        "{} Removed {} PAIS hook(s) from {}",
+        presenter::ok(),
+        removed,
+        path.display()
+    );
+    Ok(())
+}
+
+/// Whether PAIS registers a hook for this event
+fn is_managed(event: &str) -> bool {
+    EVENTS.iter().any(|e| e.to_string() == event)
+}
+
+#[derive(Serialize)]
+struct ShowOutput {
+    path: String,
+    exists: bool,
+    events: Vec<EventHooks>,
+}
+
+fn show(project: Option<&Path>, format: OutputFormat) -> Result<()> {
+    let path = settings_path(project)?;
+    let settings = ClaudeSettings::load(&path)?;
+    let output = ShowOutput {
+        path: path.display().to_string(),
+        exists: path.exists(),
+        events: settings.show(),
+    };
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&output)?),
+        OutputFormat::Text => {
+            println!("{} {}", "Claude settings:".bold(), output.path);
+            if !output.exists {
+                println!("  {}", "(file does not exist)".dimmed());
+            }
+            println!();
+            for event in &output.events {
+                let icon = if !event.pais.is_empty() {
+                    presenter::ok()
+                } else if is_managed(&event.event) {
+                    presenter::fail()
+                } else {
+                    "·".dimmed()
+                };
+                let other = if event.other > 0 {
+                    format!(" (+{} other)", event.other).dimmed().to_string()
+                } else {
+                    String::new()
+                };
+                println!("  {} {:<18}{}", icon, event.event, other);
+            }
+
+            if output.events.iter().any(|e| e.pais.is_empty() && is_managed(&e.event)) {
+                println!();
+                println!("  Register missing events with: {}", "pais claude hooks install".cyan());
+            }
+        }
+    }
+
+    Ok(())
+}
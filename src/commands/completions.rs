@@ -1,12 +1,28 @@
 use clap::CommandFactory;
-use clap_complete::generate;
+use clap_complete::env::{Bash, Elvish, EnvCompleter, Fish, Powershell, Zsh};
+use clap_complete::{Shell, generate};
 use eyre::Result;
 use std::io;
 
 use crate::cli::Cli;
+use crate::completion::COMPLETE_VAR;
 
-pub fn run(shell: clap_complete::Shell) -> Result<()> {
-    let mut cmd = Cli::command();
-    generate(shell, &mut cmd, "pais", &mut io::stdout());
+pub fn run(shell: Shell, static_only: bool) -> Result<()> {
+    if static_only {
+        generate(shell, &mut Cli::command(), "pais", &mut io::stdout());
+        return Ok(());
+    }
+
+    let completer: &dyn EnvCompleter = match shell {
+        Shell::Bash => &Bash,
+        Shell::Elvish => &Elvish,
+        Shell::Fish => &Fish,
+        Shell::PowerShell => &Powershell,
+        Shell::Zsh => &Zsh,
+        _ => eyre::bail!("Dynamic completions are not supported for {}; use --static", shell),
+    };
+
+    // The script re-invokes `pais` with COMPLETE set on every TAB (handled at the top of main)
+    completer.write_registration(COMPLETE_VAR, "pais", "pais", "pais", &mut io::stdout())?;
     Ok(())
 }
@@ -4,10 +4,14 @@ use std::fs;
 
 use crate::cli::{ConfigAction, OutputFormat};
 use crate::config::Config;
+use crate::lock::FileLock;
+use crate::presenter;
+use crate::skill::cache::content_hash;
+use crate::state::{self, StateEvent};
 
-pub fn run(action: ConfigAction, config: &Config) -> Result<()> {
+pub fn run(action: ConfigAction, format: OutputFormat, config: &Config) -> Result<()> {
     match action {
-        ConfigAction::Show { format } => show(OutputFormat::resolve(format), config),
+        ConfigAction::Show => show(format, config),
         ConfigAction::Get { key } => get(&key, config),
         ConfigAction::Set { key, value } => set(&key, &value, config),
     }
@@ -54,7 +58,7 @@ fn get(key: &str, config: &Config) -> Result<()> {
     match value {
         Some(v) => println!("{}", v),
         None => {
-            eprintln!("{} Unknown config key: {}", "✗".red(), key);
+            eprintln!("{} Unknown config key: {}", presenter::fail(), key);
             std::process::exit(1);
         }
     }
@@ -63,9 +67,20 @@ fn get(key: &str, config: &Config) -> Result<()> {
 }
 
 fn set(key: &str, value: &str, config: &Config) -> Result<()> {
-    println!("{} Setting {} = {}", "→".blue(), key.cyan(), value.green());
+    println!("{} Setting {} = {}", presenter::arrow(), key.cyan(), value.green());
 
-    let mut new_config = config.clone();
+    let config_path = Config::pais_dir().join("pais.yaml");
+    if let Some(parent) = config_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // Re-read under the lock so a concurrent `config set` isn't overwritten
+    let _lock = FileLock::acquire(&config_path)?;
+    let mut new_config = if config_path.exists() {
+        Config::load(Some(&config_path))?
+    } else {
+        config.clone()
+    };
 
     match key {
         "paths.plugins" => new_config.paths.plugins = value.into(),
@@ -84,15 +99,14 @@ fn set(key: &str, value: &str, config: &Config) -> Result<()> {
         }
     }
 
-    let config_path = Config::pais_dir().join("pais.yaml");
-    if let Some(parent) = config_path.parent() {
-        fs::create_dir_all(parent)?;
-    }
-
     let yaml_str = serde_yaml::to_string(&new_config).context("Failed to serialize config")?;
-    fs::write(&config_path, yaml_str).context("Failed to write config file")?;
+    fs::write(&config_path, &yaml_str).context("Failed to write config file")?;
+    state::record(StateEvent::ConfigChanged {
+        key: key.to_string(),
+        digest: content_hash(yaml_str.as_bytes()),
+    });
 
-    println!("  {} Saved to {}", "✓".green(), config_path.display());
+    println!("  {} Saved to {}", presenter::ok(), config_path.display());
 
     Ok(())
 }
@@ -16,22 +16,77 @@
 //! Skills are filtered based on what symlinks exist in `~/.claude/skills/`.
 //! This is set up by `pais session` before Claude Code launches.
 //! If no symlinks exist, all skills from the PAIS skills directory are shown.
+//!
+//! ## Usage-Based Budget
+//!
+//! Once the skill-usage hook has recorded triggers (see
+//! [`crate::skill::usage`]), deferred skills used in the last
+//! `skills.usage.recent-days` are listed in full and those unused for
+//! `skills.usage.demote-after-days` shrink to a one-line mention.
+//!
+//! ## Compression
+//!
+//! With `skills.compression.token-ceiling` set, the largest core skills are
+//! swapped for their minified form (see [`crate::skill::minify`]) until the
+//! injection fits.
+//!
+//! ## Caching
+//!
+//! The rendered skill and environment sections are cached (see
+//! [`crate::skill::context_cache`]) and rebuilt automatically when a skill
+//! file, the environment config or the filter changes. Use `--no-cache` to
+//! bypass it or `pais context clear-cache` to drop it.
+//!
+//! ## Project Environment
+//!
+//! With `environment.detect-project` (the default), the project the session
+//! starts in is described next to the environment section: languages,
+//! package managers, Make targets and CI (see [`crate::workspace`]).
+//!
+//! ## MCP
+//!
+//! With `skills.mcp: true` deferred skills are only named; their
+//! descriptions, triggers and bodies are served by `pais mcp serve` (see
+//! [`crate::mcp`]) and fetched by Claude when needed.
+//!
+//! ## Recent Exchanges
+//!
+//! `pais context recent` prints the last prompts and replies of the
+//! project's newest transcript (see [`crate::history::transcript`]), so a
+//! fresh session can continue where the last one stopped without resuming it.
 
+use chrono::{DateTime, Utc};
 use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
 use std::process::Command;
 
 use crate::cli::ContextAction;
-use crate::config::Config;
+use crate::config::{Config, SkillUsageConfig};
+use crate::history::transcript::{self, Exchange};
+use crate::mcp;
+use crate::perf;
+use crate::presenter;
+use crate::skill::context_cache::{self, CacheKey};
 use crate::skill::indexer::{SkillIndex, generate_index};
+use crate::skill::minify;
 use crate::skill::parser::SkillTier;
+use crate::skill::usage::{UsageStore, UsageTier, usage_path};
+use crate::workspace;
 
 /// Run a context subcommand
 pub fn run(action: ContextAction, config: &Config) -> Result<()> {
     match action {
-        ContextAction::Inject { raw } => inject_context(raw, config),
+        ContextAction::Inject { raw, no_cache } => inject_context(raw, no_cache, config),
+        ContextAction::ClearCache => clear_cache(),
+        ContextAction::Recent {
+            turns,
+            session,
+            max_chars,
+            raw,
+        } => recent(turns, session.as_deref(), max_chars, raw),
     }
 }
 
@@ -45,7 +100,7 @@ const FRONTMATTER_DELIMITER: &str = "---";
 const FRONTMATTER_DELIMITER_LEN: usize = 3;
 
 /// Extract skill body content (everything after frontmatter)
-fn extract_skill_body(content: &str) -> Option<String> {
+pub fn extract_skill_body(content: &str) -> Option<String> {
     if let Some(start) = content.find(FRONTMATTER_DELIMITER)
         && let Some(end) = content[start + FRONTMATTER_DELIMITER_LEN..].find(FRONTMATTER_DELIMITER)
     {
@@ -130,8 +185,52 @@ fn load_core_skills(
     core_skills
 }
 
+/// Usage tiers of deferred skills for the current injection
+struct UsageTiers {
+    store: UsageStore,
+    now: DateTime<Utc>,
+    config: SkillUsageConfig,
+}
+
+impl UsageTiers {
+    /// `None` when adaptive context is off or no usage has been recorded yet
+    fn load(config: &Config) -> Option<Self> {
+        let config = config.skills.usage.clone();
+        if !config.adaptive_context {
+            return None;
+        }
+        let store = UsageStore::load(&usage_path());
+        store.since?;
+        Some(Self {
+            store,
+            now: Utc::now(),
+            config,
+        })
+    }
+
+    fn tier(&self, skill: &str) -> UsageTier {
+        self.store.tier(skill, self.now, &self.config)
+    }
+
+    /// The tiers fully determine the rendered section, so key on them rather than raw timestamps
+    fn add_to_key(&self, key: &mut CacheKey) {
+        for name in self.store.skills.keys() {
+            key.add("usage", format!("{}={}", name, self.tier(name)));
+        }
+        key.add("usage-unused", self.store.unused_tier(self.now, &self.config));
+        key.add("usage-days", self.config.demote_after_days);
+    }
+}
+
 /// Generate deferred skills section from index, applying filter
-fn generate_deferred_skills_content(index: &SkillIndex, skill_filter: &Option<HashSet<String>>) -> Option<String> {
+///
+/// With usage tiers, recently used skills get their own table with full
+/// descriptions and stale ones are only named.
+fn generate_deferred_skills_content(
+    index: &SkillIndex,
+    skill_filter: &Option<HashSet<String>>,
+    usage: Option<&UsageTiers>,
+) -> Option<String> {
     // Get deferred skills, applying filter
     let mut deferred_entries: Vec<_> = index
         .skills
@@ -146,28 +245,65 @@ fn generate_deferred_skills_content(index: &SkillIndex, skill_filter: &Option<Ha
     // Sort alphabetically
     deferred_entries.sort_by_key(|s| &s.name);
 
+    let tier = |name: &str| usage.map(|u| u.tier(name)).unwrap_or(UsageTier::Normal);
+    let in_tier = |wanted: UsageTier| {
+        deferred_entries
+            .iter()
+            .filter(|e| tier(&e.name) == wanted)
+            .collect::<Vec<_>>()
+    };
+    let (recent, normal, stale) = (
+        in_tier(UsageTier::Recent),
+        in_tier(UsageTier::Normal),
+        in_tier(UsageTier::Stale),
+    );
+
+    let mut lines = Vec::new();
+
+    if !recent.is_empty() {
+        lines.push("## Recently Used Skills".to_string());
+        lines.push(String::new());
+        lines.push("| Skill | Description | Triggers |".to_string());
+        lines.push("|-------|-------------|----------|".to_string());
+        for entry in &recent {
+            let triggers = entry.triggers.join(", ");
+            let triggers_display = if triggers.is_empty() { "-".to_string() } else { triggers };
+            lines.push(format!(
+                "| **{}** | {} | {} |",
+                entry.name, entry.description, triggers_display
+            ));
+        }
+        lines.push(String::new());
+    }
+
     // Skills table
-    let mut lines = vec![
-        "## Available Skills".to_string(),
-        String::new(),
-        "| Skill | Description | Triggers |".to_string(),
-        "|-------|-------------|----------|".to_string(),
-    ];
+    if !normal.is_empty() {
+        lines.push("## Available Skills".to_string());
+        lines.push(String::new());
+        lines.push("| Skill | Description | Triggers |".to_string());
+        lines.push("|-------|-------------|----------|".to_string());
+
+        for entry in &normal {
+            let triggers = entry.triggers.join(", ");
+            let triggers_display = if triggers.is_empty() { "-".to_string() } else { triggers };
+            // Truncate description for table
+            let desc = presenter::truncate(&entry.description, 50);
+            lines.push(format!("| **{}** | {} | {} |", entry.name, desc, triggers_display));
+        }
+        lines.push(String::new());
+    }
 
-    for entry in &deferred_entries {
-        let triggers = entry.triggers.join(", ");
-        let triggers_display = if triggers.is_empty() { "-".to_string() } else { triggers };
-        // Truncate description for table
-        let desc = if entry.description.len() > 50 {
-            format!("{}...", &entry.description[..47])
-        } else {
-            entry.description.clone()
-        };
-        lines.push(format!("| **{}** | {} | {} |", entry.name, desc, triggers_display));
+    if let (false, Some(usage)) = (stale.is_empty(), usage) {
+        let names: Vec<_> = stale.iter().map(|e| format!("`{}`", e.name)).collect();
+        lines.push(format!(
+            "Also available (unused for {}+ days): {}",
+            usage.config.demote_after_days,
+            names.join(", ")
+        ));
+        lines.push(String::new());
     }
 
     // Routing instructions
-    lines.push(String::new());
     lines.push("## Routing Instructions".to_string());
     lines.push(String::new());
     lines.push("When a user request matches a skill's triggers:".to_string());
@@ -180,6 +316,40 @@ fn generate_deferred_skills_content(index: &SkillIndex, skill_filter: &Option<Ha
     Some(lines.join("\n"))
 }
 
+/// Deferred skills as names only, to be read from the pais MCP server
+fn generate_mcp_skills_content(index: &SkillIndex, skill_filter: &Option<HashSet<String>>) -> Option<String> {
+    let mut deferred_entries: Vec<_> = index
+        .skills
+        .values()
+        .filter(|s| s.tier == SkillTier::Deferred && should_include_skill(&s.name, skill_filter))
+        .collect();
+    if deferred_entries.is_empty() {
+        return None;
+    }
+    deferred_entries.sort_by_key(|s| &s.name);
+
+    let mut lines = vec![
+        "## Available Skills".to_string(),
+        String::new(),
+        "| Skill | Resource |".to_string(),
+        "|-------|----------|".to_string(),
+    ];
+    for entry in deferred_entries {
+        lines.push(format!("| **{}** | `{}` |", entry.name, mcp::skill_uri(&entry.name)));
+    }
+    lines.push(String::new());
+    lines.push("## Routing Instructions".to_string());
+    lines.push(String::new());
+    lines.push(format!(
+        "Descriptions and triggers are in the `{}` resource of the `pais` MCP server.",
+        mcp::INDEX_URI
+    ));
+    lines.push("When a user request matches a skill, read its resource and follow its instructions.".to_string());
+    lines.push(String::new());
+
+    Some(lines.join("\n"))
+}
+
 /// Check if a tool is available in PATH
 fn check_tool_available(tool: &str) -> Option<String> {
     // For tool preferences (like "eza --tree"), just check the first word
@@ -241,7 +411,11 @@ fn generate_environment_context(config: &Config) -> Option<String> {
 
         for (legacy, modern) in prefs {
             let available = check_tool_available(modern).is_some();
-            let status = if available { "✓" } else { "✗" };
+            let status = if available {
+                presenter::symbol("✓", "ok")
+            } else {
+                presenter::symbol("✗", "x")
+            };
             lines.push(format!("- `{}` instead of `{}` {}", modern, legacy, status));
         }
 
@@ -259,7 +433,11 @@ fn generate_environment_context(config: &Config) -> Option<String> {
 
         for (name, tool_config) in tools {
             let available = check_tool_available(name);
-            let status = if available.is_some() { "✓" } else { "✗" };
+            let status = if available.is_some() {
+                presenter::symbol("✓", "ok")
+            } else {
+                presenter::symbol("✗", "x")
+            };
             let desc = tool_config.description.as_deref().unwrap_or("");
             let github = tool_config
                 .github
@@ -278,25 +456,88 @@ fn generate_environment_context(config: &Config) -> Option<String> {
     Some(lines.join("\n"))
 }
 
-/// Inject skill context for SessionStart hook
-fn inject_context(raw: bool, config: &Config) -> Result<()> {
-    log::debug!("Injecting context (raw={})", raw);
+/// Everything `context inject` prints except the timestamp, cached between sessions
+#[derive(Debug, Serialize, Deserialize)]
+struct Injection {
+    total_skills: usize,
+    core_count: usize,
+    env_context: Option<String>,
+    core_skills: Vec<(String, String)>,
+    context_content: Option<String>,
+    /// Core skills replaced by their minified form to fit the token ceiling
+    compressed: Vec<String>,
+}
 
-    let skills_dir = Config::expand_path(&config.paths.skills);
-    log::debug!("Skills directory: {}", skills_dir.display());
+/// Cache key covering the skill files, the config that shapes the output and the filter
+fn injection_key(
+    skills_dir: &Path,
+    skill_filter: &Option<HashSet<String>>,
+    usage: Option<&UsageTiers>,
+    config: &Config,
+) -> String {
+    let env = &config.environment;
+    let mut key = CacheKey::new();
+    key.add_dir(skills_dir);
+    // Status marks and truncation depend on the charset
+    key.add("ascii", presenter::ascii());
 
-    let context_path = skills_dir.join("context-snippet.md");
+    if let Some(ref repos_dir) = env.repos_dir {
+        key.add("repos-dir", repos_dir.display());
+    }
+    let mut prefs: Vec<_> = env.tool_preferences.iter().collect();
+    prefs.sort();
+    for (legacy, modern) in prefs {
+        key.add("tool-preference", format!("{}={}", legacy, modern));
+    }
+    let mut tools: Vec<_> = env.tools.iter().collect();
+    tools.sort_by_key(|(name, _)| *name);
+    for (name, tool) in tools {
+        key.add("tool", format!("{}|{:?}|{:?}", name, tool.description, tool.github));
+    }
+    // Tool availability checks depend on what is on PATH
+    if !env.tool_preferences.is_empty() || !env.tools.is_empty() {
+        key.add("path", std::env::var("PATH").unwrap_or_default());
+    }
 
-    // Check for skill filter from ~/.claude/skills/ symlinks
-    let skill_filter = get_skill_filter();
-    if let Some(ref filter) = skill_filter {
-        log::info!("Skill filter from symlinks: {} skills", filter.len());
-    } else {
-        log::debug!("No skill filter - loading all skills");
+    match skill_filter {
+        Some(filter) => {
+            let mut names: Vec<_> = filter.iter().collect();
+            names.sort();
+            for name in names {
+                key.add("filter", name);
+            }
+        }
+        None => {
+            key.add("filter", "*");
+        }
+    }
+    if let Some(usage) = usage {
+        usage.add_to_key(&mut key);
+    }
+    key.add("mcp", config.skills.mcp);
+    let compression = &config.skills.compression;
+    if let Some(ceiling) = compression.token_ceiling {
+        key.add("token-ceiling", ceiling);
+        key.add("max-code-lines", compression.max_code_lines);
+        for section in &compression.drop_sections {
+            key.add("drop-section", section);
+        }
     }
 
+    key.finish()
+}
+
+/// Build the injection from the skills directory
+fn build_injection(
+    skills_dir: &Path,
+    skill_filter: &Option<HashSet<String>>,
+    usage: Option<&UsageTiers>,
+    config: &Config,
+) -> Result<Injection> {
+    let context_path = skills_dir.join("context-snippet.md");
+
     // Generate or load the index
-    let index = generate_index(&skills_dir).context("Failed to generate skill index")?;
+    let index = generate_index(skills_dir).context("Failed to generate skill index")?;
     log::debug!(
         "Index generated: {} skills ({} core, {} deferred)",
         index.total_skills,
@@ -305,7 +546,7 @@ fn inject_context(raw: bool, config: &Config) -> Result<()> {
     );
 
     // Load core-tier skills (Tier 0), applying filter
-    let core_skills = load_core_skills(&skills_dir, &index, &skill_filter);
+    let mut core_skills = load_core_skills(skills_dir, &index, skill_filter);
     log::debug!(
         "Loaded {} core skills: [{}]",
         core_skills.len(),
@@ -324,11 +565,14 @@ fn inject_context(raw: bool, config: &Config) -> Result<()> {
     );
 
     // Generate deferred skills content (Tier 1)
-    // If skill filter is active, generate dynamically to apply the filter
+    // If a skill filter or usage tiers apply, generate dynamically to honor them
     // Otherwise, use the static context-snippet.md if available
-    let context_content = if skill_filter.is_some() {
-        log::debug!("Generating filtered deferred skills content");
-        generate_deferred_skills_content(&index, &skill_filter)
+    let context_content = if config.skills.mcp {
+        log::debug!("Naming deferred skills for the MCP server");
+        generate_mcp_skills_content(&index, skill_filter)
+    } else if skill_filter.is_some() || usage.is_some() {
+        log::debug!("Generating filtered/usage-tiered deferred skills content");
+        generate_deferred_skills_content(&index, skill_filter, usage)
     } else if context_path.exists() {
         log::debug!("Loading deferred skills context from: {}", context_path.display());
         Some(
@@ -337,7 +581,199 @@ fn inject_context(raw: bool, config: &Config) -> Result<()> {
         )
     } else {
         log::debug!("Generating deferred skills content (no static file)");
-        generate_deferred_skills_content(&index, &skill_filter)
+        generate_deferred_skills_content(&index, skill_filter, usage)
+    };
+
+    // Minify the largest core skills if everything together is over the ceiling
+    let fixed = [&env_context, &context_content]
+        .iter()
+        .filter_map(|section| section.as_deref())
+        .map(minify::estimate_tokens)
+        .sum();
+    let compressed = minify::fit_to_ceiling(skills_dir, &mut core_skills, fixed, &config.skills.compression);
+    if !compressed.is_empty() {
+        log::info!(
+            "Compressed core skills to fit the token ceiling: {}",
+            compressed.join(", ")
+        );
+    }
+
+    Ok(Injection {
+        total_skills: index.total_skills,
+        core_count: index.core_count,
+        env_context,
+        core_skills,
+        context_content,
+        compressed,
+    })
+}
+
+/// Load the injection from the cache, or build and cache it
+fn cached_injection(
+    skills_dir: &Path,
+    skill_filter: &Option<HashSet<String>>,
+    no_cache: bool,
+    config: &Config,
+) -> Result<Injection> {
+    let usage = UsageTiers::load(config);
+    let cache_dir = match context_cache::cache_dir() {
+        Some(dir) if !no_cache => dir,
+        _ => return build_injection(skills_dir, skill_filter, usage.as_ref(), config),
+    };
+
+    let key = injection_key(skills_dir, skill_filter, usage.as_ref(), config);
+    let cached = context_cache::load(&cache_dir, &key);
+    perf::cache("context-injection", cached.is_some());
+    if let Some(injection) = cached {
+        log::debug!("Using cached context injection: {}", key);
+        return Ok(injection);
+    }
+
+    let injection = build_injection(skills_dir, skill_filter, usage.as_ref(), config)?;
+    if let Err(e) = context_cache::store(&cache_dir, &key, &injection) {
+        log::debug!("Failed to cache context injection: {}", e);
+    }
+    Ok(injection)
+}
+
+/// Remove all cached injections
+fn clear_cache() -> Result<()> {
+    let removed = match context_cache::cache_dir() {
+        Some(dir) => context_cache::clear(&dir)?,
+        None => 0,
+    };
+    println!("Removed {} cached context injection(s)", removed);
+    Ok(())
+}
+
+/// Cut `text` to `max` characters
+fn clip(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// The transcript to read and its exchanges: the given session, or the
+/// newest transcript for the current directory that has any
+fn find_recent(session: Option<&str>, turns: usize) -> Result<Option<(std::path::PathBuf, Vec<Exchange>)>> {
+    let Some(projects) = transcript::projects_dir() else {
+        return Ok(None);
+    };
+    let candidates = match session {
+        Some(id) => fs::read_dir(&projects)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .flat_map(|project| fs::read_dir(project.path()).into_iter().flatten().flatten())
+            .map(|e| e.path())
+            .filter(|p| {
+                p.extension().is_some_and(|e| e == "jsonl")
+                    && p.file_stem()
+                        .and_then(|s| s.to_str())
+                        .is_some_and(|s| s.starts_with(id))
+            })
+            .collect(),
+        None => {
+            let cwd = std::env::current_dir().context("Failed to get current directory")?;
+            transcript::project_transcripts(&projects, &cwd)
+        }
+    };
+
+    for path in candidates {
+        match transcript::recent_exchanges(&path, turns) {
+            Ok(exchanges) if !exchanges.is_empty() => return Ok(Some((path, exchanges))),
+            Ok(_) => {}
+            Err(e) => log::debug!("Skipping unreadable transcript {}: {}", path.display(), e),
+        }
+    }
+    Ok(None)
+}
+
+/// Print the last exchanges of the most recent session
+fn recent(turns: usize, session: Option<&str>, max_chars: usize, raw: bool) -> Result<()> {
+    let Some((path, exchanges)) = find_recent(session, turns)? else {
+        eprintln!("[PAIS] No previous session transcript found for this project.");
+        return Ok(());
+    };
+    let session_id = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+
+    if !raw {
+        println!("<system-reminder>");
+        println!("PAIS CONTEXT (Previous Session)");
+        println!();
+        let ended = fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .map(|t| {
+                chrono::DateTime::<chrono::Local>::from(t)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        println!(
+            "Last {} exchange(s) of session {} (last active {}). Continue from here if the user asks to pick up where you left off.",
+            exchanges.len(),
+            &session_id[..8.min(session_id.len())],
+            ended
+        );
+    }
+
+    for exchange in &exchanges {
+        println!();
+        println!("## User");
+        println!();
+        println!("{}", clip(&exchange.prompt, max_chars));
+        println!();
+        println!("## Claude");
+        println!();
+        if exchange.response.is_empty() {
+            println!("(no text reply)");
+        } else {
+            println!("{}", clip(&exchange.response, max_chars));
+        }
+        if !exchange.tools.is_empty() {
+            println!();
+            println!("Tools used: {}", exchange.tools.join(", "));
+        }
+    }
+
+    if !raw {
+        println!("</system-reminder>");
+    }
+    Ok(())
+}
+
+/// Inject skill context for SessionStart hook
+fn inject_context(raw: bool, no_cache: bool, config: &Config) -> Result<()> {
+    log::debug!("Injecting context (raw={}, no_cache={})", raw, no_cache);
+
+    let skills_dir = Config::expand_path(&config.paths.skills);
+    log::debug!("Skills directory: {}", skills_dir.display());
+
+    // Check for skill filter from ~/.claude/skills/ symlinks
+    let skill_filter = get_skill_filter();
+    if let Some(ref filter) = skill_filter {
+        log::info!("Skill filter from symlinks: {} skills", filter.len());
+    } else {
+        log::debug!("No skill filter - loading all skills");
+    }
+
+    let Injection {
+        total_skills,
+        core_count,
+        env_context,
+        core_skills,
+        context_content,
+        compressed,
+    } = cached_injection(&skills_dir, &skill_filter, no_cache, config)?;
+
+    // Per project, so cached separately from the injection
+    let project_context = if config.environment.detect_project {
+        std::env::current_dir()
+            .ok()
+            .and_then(|dir| workspace::project_context(&dir, no_cache))
+    } else {
+        None
     };
 
     // If neither exists, warn and exit
@@ -349,7 +785,7 @@ fn inject_context(raw: bool, config: &Config) -> Result<()> {
 
     if raw {
         // Output raw content without wrapper
-        if let Some(ref env) = env_context {
+        for env in [&env_context, &project_context].into_iter().flatten() {
             println!("{}", env);
             println!();
         }
@@ -372,35 +808,48 @@ fn inject_context(raw: bool, config: &Config) -> Result<()> {
         println!("<system-reminder>");
         println!("PAIS CONTEXT (Auto-loaded at Session Start)");
         println!();
-        println!("📅 Current Time: {}", get_local_timestamp());
+        println!(
+            "{}Current Time: {}",
+            presenter::symbol("📅 ", ""),
+            get_local_timestamp()
+        );
         if skill_filter.is_some() {
             println!(
-                "📦 Skills: {} loaded (filtered), {} core-tier",
-                loaded_total, loaded_core_count
+                "{}Skills: {} loaded (filtered), {} core-tier",
+                presenter::symbol("📦 ", ""),
+                loaded_total,
+                loaded_core_count
             );
         } else {
             println!(
-                "📦 Skills: {} total, {} core-tier",
-                index.total_skills, index.core_count
+                "{}Skills: {} total, {} core-tier",
+                presenter::symbol("📦 ", ""),
+                total_skills,
+                core_count
+            );
+        }
+        if !compressed.is_empty() {
+            println!(
+                "{}Condensed to fit the context budget: {}",
+                presenter::symbol("🗜️ ", ""),
+                compressed.join(", ")
             );
         }
 
-        // Environment context (if configured)
-        if let Some(ref env) = env_context {
-            println!();
-            println!("═══════════════════════════════════════════════════════════");
-            println!("                    ENVIRONMENT");
-            println!("═══════════════════════════════════════════════════════════");
+        // Environment context (if configured) and the current project
+        if env_context.is_some() || project_context.is_some() {
             println!();
-            println!("{}", env);
+            println!("{}", presenter::banner("ENVIRONMENT"));
+            for env in [&env_context, &project_context].into_iter().flatten() {
+                println!();
+                println!("{}", env);
+            }
         }
 
         // Core-tier skills (Tier 0) - full content loaded
         if !core_skills.is_empty() {
             println!();
-            println!("═══════════════════════════════════════════════════════════");
-            println!("                 CORE SKILLS (Tier 0)");
-            println!("═══════════════════════════════════════════════════════════");
+            println!("{}", presenter::banner("CORE SKILLS (Tier 0)"));
 
             for (name, body) in &core_skills {
                 println!();
@@ -417,9 +866,7 @@ fn inject_context(raw: bool, config: &Config) -> Result<()> {
         // Deferred skills (Tier 1) - only frontmatter/triggers
         if let Some(ref context) = context_content {
             println!();
-            println!("═══════════════════════════════════════════════════════════");
-            println!("              DEFERRED SKILLS (Tier 1)");
-            println!("═══════════════════════════════════════════════════════════");
+            println!("{}", presenter::banner("DEFERRED SKILLS (Tier 1)"));
             println!();
             println!("{}", context);
         }
@@ -429,13 +876,17 @@ fn inject_context(raw: bool, config: &Config) -> Result<()> {
         println!();
         if skill_filter.is_some() {
             println!(
-                "✅ PAIS context loaded ({} skills filtered, {} core-tier)",
-                loaded_total, loaded_core_count
+                "{}PAIS context loaded ({} skills filtered, {} core-tier)",
+                presenter::symbol("✅ ", ""),
+                loaded_total,
+                loaded_core_count
             );
         } else {
             println!(
-                "✅ PAIS context loaded ({} skills, {} core-tier)",
-                index.total_skills, index.core_count
+                "{}PAIS context loaded ({} skills, {} core-tier)",
+                presenter::symbol("✅ ", ""),
+                total_skills,
+                core_count
             );
         }
     }
@@ -455,6 +906,70 @@ mod tests {
         assert!(ts.contains(":"));
     }
 
+    #[test]
+    fn test_deferred_skills_content_by_usage() {
+        use crate::skill::indexer::SkillIndexEntry;
+
+        let entry = |name: &str| SkillIndexEntry {
+            name: name.to_string(),
+            path: format!("{}/SKILL.md", name),
+            description: format!(
+                "{} skill with a description long enough to be truncated in the table",
+                name
+            ),
+            triggers: vec![name.to_string()],
+            tier: SkillTier::Deferred,
+            version: None,
+            priority: None,
+            workflows: Vec::new(),
+        };
+        let index = SkillIndex {
+            generated: String::new(),
+            total_skills: 3,
+            core_count: 0,
+            deferred_count: 3,
+            skills: ["fresh", "steady", "dusty"]
+                .iter()
+                .map(|n| (n.to_string(), entry(n)))
+                .collect(),
+            collisions: Vec::new(),
+        };
+
+        // Without usage data every skill is a truncated table row
+        let plain = generate_deferred_skills_content(&index, &None, None).unwrap();
+        assert!(!plain.contains("Recently Used"));
+        assert_eq!(plain.matches("| **").count(), 3);
+
+        let now = Utc::now();
+        let mut store = UsageStore::default();
+        store.record("dusty", now - chrono::Duration::days(90));
+        store.record("steady", now - chrono::Duration::days(10));
+        store.record("fresh", now - chrono::Duration::hours(2));
+        let usage = UsageTiers {
+            store,
+            now,
+            config: SkillUsageConfig::default(),
+        };
+
+        let content = generate_deferred_skills_content(&index, &None, Some(&usage)).unwrap();
+        let recent = content.find("## Recently Used Skills").unwrap();
+        let available = content.find("## Available Skills").unwrap();
+        assert!(recent < available);
+        assert!(
+            content.contains("| **fresh** | fresh skill with a description long enough to be truncated in the table |")
+        );
+        assert!(content.contains("| **steady** |"));
+        assert!(!content.contains("| **dusty** |"));
+        assert!(content.contains("Also available (unused for 30+ days): `dusty`"));
+
+        // With the MCP server the descriptions stay out of the context
+        let filter = Some(HashSet::from(["fresh".to_string(), "dusty".to_string()]));
+        let names = generate_mcp_skills_content(&index, &filter).unwrap();
+        assert!(names.contains("| **fresh** | `pais://skills/fresh` |"));
+        assert!(!names.contains("steady"));
+        assert!(!names.contains("description long enough"));
+    }
+
     // === Skill filter tests ===
 
     #[test]
@@ -0,0 +1,44 @@
+//! Diagnostics command
+//!
+//! `pais debug bundle` writes the same diagnostic bundle a crash leaves behind
+//! (see [`crate::crash`]), for attaching to bug reports.
+
+use colored::*;
+use eyre::Result;
+use serde_json::json;
+use std::path::PathBuf;
+
+use crate::cli::{DebugAction, OutputFormat};
+use crate::crash::{self, Bundle};
+use crate::presenter;
+
+/// Run a debug subcommand
+pub fn run(action: DebugAction, format: OutputFormat) -> Result<()> {
+    match action {
+        DebugAction::Bundle { output } => bundle(output, format),
+    }
+}
+
+fn bundle(output: Option<PathBuf>, format: OutputFormat) -> Result<()> {
+    let bundle = Bundle::collect("requested".to_string(), true);
+    let path = match output {
+        Some(path) => {
+            bundle.write_to(&path)?;
+            path
+        }
+        None => bundle.write(&crash::crash_dir())?,
+    };
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&json!({ "path": path }))?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&json!({ "path": path }))?),
+        OutputFormat::Text => {
+            println!("{} Diagnostic bundle written to {}", presenter::ok(), path.display());
+            println!(
+                "  {}",
+                "Secrets and your home directory are redacted; review it before sharing".dimmed()
+            );
+        }
+    }
+    Ok(())
+}
@@ -9,14 +9,18 @@ use std::io::{self, Read, Write as IoWrite};
 use std::path::PathBuf;
 
 use crate::cli::{DiagramAction, OutputFormat};
-use crate::config::Config;
+use crate::config::{Config, NetworkConfig};
+use crate::net;
+use crate::presenter;
 
-pub fn run(action: DiagramAction, _config: &Config) -> Result<()> {
+pub fn run(action: DiagramAction, format: OutputFormat, config: &Config) -> Result<()> {
+    // The diagram subcommands' own `config` (a YAML file) shadows this one
+    let network = &config.network;
     match action {
         DiagramAction::Render {
             file,
             mermaid,
-            format,
+            file_format,
             output,
             width,
             height,
@@ -25,73 +29,108 @@ pub fn run(action: DiagramAction, _config: &Config) -> Result<()> {
             server,
             clipboard,
             open,
-        } => render(RenderArgs {
-            file,
-            mermaid,
-            format,
-            output,
-            width,
-            height,
-            scale,
-            background,
-            server,
-            clipboard,
-            open,
-        }),
+        } => render(
+            RenderArgs {
+                file,
+                mermaid,
+                format: file_format,
+                output,
+                width,
+                height,
+                scale,
+                background,
+                server,
+                clipboard,
+                open,
+            },
+            network,
+        ),
         DiagramAction::Flowchart {
             direction,
             config,
-            format,
+            file_format,
             output,
             server,
-        } => flowchart(&direction, config.as_ref(), &format, output.as_ref(), &server),
+        } => flowchart(
+            &direction,
+            config.as_ref(),
+            &file_format,
+            output.as_ref(),
+            &Renderer::new(&server, network),
+        ),
         DiagramAction::Sequence {
             config,
-            format,
+            file_format,
             output,
             server,
-        } => sequence(config.as_ref(), &format, output.as_ref(), &server),
+        } => sequence(
+            config.as_ref(),
+            &file_format,
+            output.as_ref(),
+            &Renderer::new(&server, network),
+        ),
         DiagramAction::Er {
             config,
-            format,
+            file_format,
             output,
             server,
-        } => er(config.as_ref(), &format, output.as_ref(), &server),
+        } => er(
+            config.as_ref(),
+            &file_format,
+            output.as_ref(),
+            &Renderer::new(&server, network),
+        ),
         DiagramAction::State {
             config,
-            format,
+            file_format,
             output,
             server,
-        } => state(config.as_ref(), &format, output.as_ref(), &server),
+        } => state(
+            config.as_ref(),
+            &file_format,
+            output.as_ref(),
+            &Renderer::new(&server, network),
+        ),
         DiagramAction::Mindmap {
             config,
-            format,
+            file_format,
             output,
             server,
-        } => mindmap(config.as_ref(), &format, output.as_ref(), &server),
+        } => mindmap(
+            config.as_ref(),
+            &file_format,
+            output.as_ref(),
+            &Renderer::new(&server, network),
+        ),
         DiagramAction::Pie {
             title,
             show_data,
             config,
-            format,
+            file_format,
             output,
             server,
         } => pie(
             title.as_deref(),
             show_data,
             config.as_ref(),
-            &format,
+            &file_format,
             output.as_ref(),
-            &server,
+            &Renderer::new(&server, network),
         ),
         DiagramAction::Journey {
             title,
             config,
-            format,
+            file_format,
             output,
             server,
-        } => journey(title.as_deref(), config.as_ref(), &format, output.as_ref(), &server),
-        DiagramAction::Types { format } => list_types(OutputFormat::resolve(format)),
+        } => journey(
+            title.as_deref(),
+            config.as_ref(),
+            &file_format,
+            output.as_ref(),
+            &Renderer::new(&server, network),
+        ),
+        DiagramAction::Types => list_types(format),
     }
 }
 
@@ -109,8 +148,9 @@ struct RenderArgs {
     open: bool,
 }
 
-fn render(args: RenderArgs) -> Result<()> {
+fn render(args: RenderArgs, network: &NetworkConfig) -> Result<()> {
     let script = get_script(args.file.as_ref(), args.mermaid.as_deref())?;
+    let renderer = Renderer::new(&args.server, network);
 
     let render_options = RenderOptions {
         width: args.width,
@@ -125,11 +165,11 @@ fn render(args: RenderArgs) -> Result<()> {
             output_text(&script, args.output.as_ref(), args.clipboard)?;
         }
         "svg" => {
-            let svg = render_svg(&script, &render_options, &args.server)?;
+            let svg = renderer.svg(&script, &render_options)?;
             output_text(&svg, args.output.as_ref(), args.clipboard)?;
         }
         "png" => {
-            let png = render_png(&script, &render_options, &args.server)?;
+            let png = renderer.png(&script, &render_options)?;
             output_binary(&png, args.output.as_ref())?;
         }
         _ => eyre::bail!("Unsupported format: {}. Use svg, png, or mermaid.", format),
@@ -165,31 +205,97 @@ fn get_script(file: Option<&PathBuf>, mermaid: Option<&str>) -> Result<String> {
     Ok(buffer)
 }
 
-fn render_svg(script: &str, options: &RenderOptions, server: &str) -> Result<String> {
-    let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
-    let client = MermaidClient::new(Some(server.to_string()));
+/// Where svg and png output comes from: the mermaid.ink server, or mermaid-cli
+/// (`mmdc`) when offline mode rules out a remote one
+struct Renderer<'a> {
+    server: &'a str,
+    local: bool,
+}
+
+impl<'a> Renderer<'a> {
+    fn new(server: &'a str, network: &NetworkConfig) -> Self {
+        Self {
+            server,
+            local: network.offline && !net::is_local(server),
+        }
+    }
 
-    rt.block_on(async { client.render_svg_from_script(script, options).await })
-        .map_err(|e| eyre::eyre!("Render failed: {}", e))
+    fn svg(&self, script: &str, options: &RenderOptions) -> Result<String> {
+        if self.local {
+            let svg = render_local(script, options, "svg")?;
+            return String::from_utf8(svg).context("mmdc wrote an SVG that isn't UTF-8");
+        }
+        let client = MermaidClient::new(Some(self.server.to_string()));
+
+        net::block_on(async {
+            client
+                .render_svg_from_script(script, options)
+                .await
+                .map_err(|e| eyre::eyre!("Render failed: {}", e))
+        })
+    }
+
+    fn png(&self, script: &str, options: &RenderOptions) -> Result<Vec<u8>> {
+        if self.local {
+            return render_local(script, options, "png");
+        }
+        let client = MermaidClient::new(Some(self.server.to_string()));
+
+        net::block_on(async {
+            client
+                .render_png_from_script(script, options)
+                .await
+                .map_err(|e| eyre::eyre!("Render failed: {}", e))
+        })
+    }
 }
 
-fn render_png(script: &str, options: &RenderOptions, server: &str) -> Result<Vec<u8>> {
-    let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
-    let client = MermaidClient::new(Some(server.to_string()));
+/// Render with mermaid-cli through a temporary `diagram.<extension>`
+fn render_local(script: &str, options: &RenderOptions, extension: &str) -> Result<Vec<u8>> {
+    use std::process::Command;
 
-    rt.block_on(async { client.render_png_from_script(script, options).await })
-        .map_err(|e| eyre::eyre!("Render failed: {}", e))
+    let mmdc = which::which("mmdc").map_err(|_| {
+        net::offline_error(&format!("Rendering {} with a remote server", extension)).with_hint(
+            "Install mermaid-cli (mmdc) to render locally, pass --server with a local renderer, or use -f mermaid",
+        )
+    })?;
+    log::info!("Offline: rendering with {}", mmdc.display());
+
+    let dir = tempfile::tempdir().context("Failed to create a temporary directory")?;
+    let input = dir.path().join("diagram.mmd");
+    let output = dir.path().join(format!("diagram.{}", extension));
+    fs::write(&input, script).context("Failed to write diagram source")?;
+
+    let mut command = Command::new(&mmdc);
+    command.arg("--quiet").arg("-i").arg(&input).arg("-o").arg(&output);
+    if let Some(width) = options.width {
+        command.arg("-w").arg(width.to_string());
+    }
+    if let Some(height) = options.height {
+        command.arg("-H").arg(height.to_string());
+    }
+    if let Some(scale) = options.scale {
+        command.arg("-s").arg(scale.to_string());
+    }
+    if let Some(background) = &options.background_color {
+        command.arg("-b").arg(background);
+    }
+    let result = command.output().context("Failed to run mmdc")?;
+    if !result.status.success() {
+        eyre::bail!("mmdc failed: {}", String::from_utf8_lossy(&result.stderr).trim());
+    }
+    fs::read(&output).context("mmdc did not write the rendered diagram")
 }
 
 fn output_text(content: &str, output: Option<&PathBuf>, clipboard: bool) -> Result<()> {
     if clipboard {
         copy_to_clipboard(content)?;
-        eprintln!("{} Copied to clipboard", "✓".green());
+        eprintln!("{} Copied to clipboard", presenter::ok());
     }
 
     if let Some(path) = output {
         fs::write(path, content).context("Failed to write output file")?;
-        eprintln!("{} Saved: {}", "✓".green(), path.display());
+        eprintln!("{} Saved: {}", presenter::ok(), path.display());
     } else if !clipboard {
         print!("{}", content);
     }
@@ -200,7 +306,7 @@ fn output_text(content: &str, output: Option<&PathBuf>, clipboard: bool) -> Resu
 fn output_binary(content: &[u8], output: Option<&PathBuf>) -> Result<()> {
     if let Some(path) = output {
         fs::write(path, content).context("Failed to write output file")?;
-        eprintln!("{} Saved: {}", "✓".green(), path.display());
+        eprintln!("{} Saved: {}", presenter::ok(), path.display());
     } else {
         io::stdout().write_all(content).context("Failed to write to stdout")?;
     }
@@ -255,7 +361,7 @@ fn open_file(path: &PathBuf) -> Result<()> {
     Ok(())
 }
 
-fn render_diagram<D: Diagram>(diagram: &D, format: &str, output: Option<&PathBuf>, server: &str) -> Result<()> {
+fn render_diagram<D: Diagram>(diagram: &D, format: &str, output: Option<&PathBuf>, renderer: &Renderer) -> Result<()> {
     let script = diagram.build_script();
 
     match format.to_lowercase().as_str() {
@@ -263,11 +369,11 @@ fn render_diagram<D: Diagram>(diagram: &D, format: &str, output: Option<&PathBuf
             output_text(&script, output, false)?;
         }
         "svg" => {
-            let svg = render_svg(&script, &RenderOptions::default(), server)?;
+            let svg = renderer.svg(&script, &RenderOptions::default())?;
             output_text(&svg, output, false)?;
         }
         "png" => {
-            let png = render_png(&script, &RenderOptions::default(), server)?;
+            let png = renderer.png(&script, &RenderOptions::default())?;
             output_binary(&png, output)?;
         }
         _ => eyre::bail!("Unsupported format: {}. Use svg, png, or mermaid.", format),
@@ -304,7 +410,7 @@ fn flowchart(
     config: Option<&PathBuf>,
     format: &str,
     output: Option<&PathBuf>,
-    server: &str,
+    renderer: &Renderer,
 ) -> Result<()> {
     let diagram: FlowChart = if let Some(path) = config {
         let content = fs::read_to_string(path).context("Failed to read config file")?;
@@ -320,7 +426,7 @@ fn flowchart(
 
         eprintln!(
             "{} No config provided, reading YAML from stdin (direction: {})",
-            "→".blue(),
+            presenter::arrow(),
             direction
         );
 
@@ -339,29 +445,29 @@ fn flowchart(
         diagram
     };
 
-    render_diagram(&diagram, format, output, server)
+    render_diagram(&diagram, format, output, renderer)
 }
 
-fn sequence(config: Option<&PathBuf>, format: &str, output: Option<&PathBuf>, server: &str) -> Result<()> {
+fn sequence(config: Option<&PathBuf>, format: &str, output: Option<&PathBuf>, renderer: &Renderer) -> Result<()> {
     let diagram: SequenceDiagram = load_config_or_stdin(config)?;
-    render_diagram(&diagram, format, output, server)
+    render_diagram(&diagram, format, output, renderer)
 }
 
-fn er(config: Option<&PathBuf>, format: &str, output: Option<&PathBuf>, server: &str) -> Result<()> {
+fn er(config: Option<&PathBuf>, format: &str, output: Option<&PathBuf>, renderer: &Renderer) -> Result<()> {
     let content = read_config_or_stdin(config)?;
     let diagram = ERDiagram::from_yaml(&content).map_err(|e| eyre::eyre!("Failed to parse config: {}", e))?;
-    render_diagram(&diagram, format, output, server)
+    render_diagram(&diagram, format, output, renderer)
 }
 
-fn state(config: Option<&PathBuf>, format: &str, output: Option<&PathBuf>, server: &str) -> Result<()> {
+fn state(config: Option<&PathBuf>, format: &str, output: Option<&PathBuf>, renderer: &Renderer) -> Result<()> {
     let diagram: StateDiagram = load_config_or_stdin(config)?;
-    render_diagram(&diagram, format, output, server)
+    render_diagram(&diagram, format, output, renderer)
 }
 
-fn mindmap(config: Option<&PathBuf>, format: &str, output: Option<&PathBuf>, server: &str) -> Result<()> {
+fn mindmap(config: Option<&PathBuf>, format: &str, output: Option<&PathBuf>, renderer: &Renderer) -> Result<()> {
     let content = read_config_or_stdin(config)?;
     let diagram = Mindmap::from_yaml(&content).map_err(|e| eyre::eyre!("Failed to parse config: {}", e))?;
-    render_diagram(&diagram, format, output, server)
+    render_diagram(&diagram, format, output, renderer)
 }
 
 fn pie(
@@ -370,7 +476,7 @@ fn pie(
     config: Option<&PathBuf>,
     format: &str,
     output: Option<&PathBuf>,
-    server: &str,
+    renderer: &Renderer,
 ) -> Result<()> {
     let mut diagram: PieChart = load_config_or_stdin(config)?;
 
@@ -381,7 +487,7 @@ fn pie(
         diagram.show_data = true;
     }
 
-    render_diagram(&diagram, format, output, server)
+    render_diagram(&diagram, format, output, renderer)
 }
 
 fn journey(
@@ -389,7 +495,7 @@ fn journey(
     config: Option<&PathBuf>,
     format: &str,
     output: Option<&PathBuf>,
-    server: &str,
+    renderer: &Renderer,
 ) -> Result<()> {
     let content = read_config_or_stdin(config)?;
     let mut diagram = Journey::from_yaml(&content).map_err(|e| eyre::eyre!("Failed to parse config: {}", e))?;
@@ -398,7 +504,7 @@ fn journey(
         diagram.title = Some(t.to_string());
     }
 
-    render_diagram(&diagram, format, output, server)
+    render_diagram(&diagram, format, output, renderer)
 }
 
 fn list_types(format: OutputFormat) -> Result<()> {
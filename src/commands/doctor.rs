@@ -5,11 +5,17 @@ use eyre::Result;
 use std::fs;
 use std::process::Command;
 
+use crate::claude::compat::{self, Support};
+use crate::commands::plugin::installed_plugins;
 use crate::config::Config;
+use crate::history::categorize::Categories;
+use crate::plugin::{self, health};
+use crate::presenter;
+use crate::skill::conflicts;
 
 pub fn run(config: &Config) -> Result<()> {
     println!("{}", "PAIS Doctor".bold());
-    println!("{}", "═".repeat(50));
+    println!("{}", presenter::symbol("═", "=").repeat(50));
     println!();
 
     let mut issues = 0;
@@ -17,9 +23,9 @@ pub fn run(config: &Config) -> Result<()> {
     // Check PAIS directory
     let pais_dir = Config::pais_dir();
     if pais_dir.exists() {
-        println!("{} PAIS directory: {}", "✓".green(), pais_dir.display());
+        println!("{} PAIS directory: {}", presenter::ok(), pais_dir.display());
     } else {
-        println!("{} PAIS directory missing: {}", "✗".red(), pais_dir.display());
+        println!("{} PAIS directory missing: {}", presenter::fail(), pais_dir.display());
         println!("  Run {} to create it", "pais init".cyan());
         issues += 1;
     }
@@ -27,9 +33,9 @@ pub fn run(config: &Config) -> Result<()> {
     // Check config file
     let config_file = pais_dir.join("pais.yaml");
     if config_file.exists() {
-        println!("{} Config file: {}", "✓".green(), config_file.display());
+        println!("{} Config file: {}", presenter::ok(), config_file.display());
     } else {
-        println!("{} Config file missing: {}", "✗".red(), config_file.display());
+        println!("{} Config file missing: {}", presenter::fail(), config_file.display());
         issues += 1;
     }
 
@@ -39,20 +45,50 @@ pub fn run(config: &Config) -> Result<()> {
         let count = count_plugins(&plugins_dir);
         println!(
             "{} Plugins directory: {} ({} plugins)",
-            "✓".green(),
+            presenter::ok(),
             plugins_dir.display(),
             count
         );
     } else {
-        println!("{} Plugins directory missing: {}", "⚠".yellow(), plugins_dir.display());
+        println!(
+            "{} Plugins directory missing: {}",
+            presenter::warn(),
+            plugins_dir.display()
+        );
+    }
+
+    match conflicts::find(config) {
+        Ok(conflicts) => {
+            for conflict in conflicts.iter().filter(|c| !c.resolved) {
+                println!(
+                    "{} Skill {} shadows the plugin of the same name",
+                    presenter::warn(),
+                    conflict.name
+                );
+                println!(
+                    "    Set {} to choose; see {}",
+                    format!("skills.resolve.{}", conflict.name).cyan(),
+                    "pais skill conflicts".cyan()
+                );
+            }
+        }
+        Err(e) => println!("{} Could not check for shadowed plugins: {:#}", presenter::warn(), e),
     }
 
     // Check history directory
     let history_dir = Config::expand_path(&config.paths.history);
     if history_dir.exists() {
-        println!("{} History directory: {}", "✓".green(), history_dir.display());
+        println!("{} History directory: {}", presenter::ok(), history_dir.display());
     } else {
-        println!("{} History directory missing: {}", "⚠".yellow(), history_dir.display());
+        println!(
+            "{} History directory missing: {}",
+            presenter::warn(),
+            history_dir.display()
+        );
+    }
+    for problem in Categories::from_config(&config.history).problems() {
+        println!("{} history.categories: {}", presenter::warn(), problem);
+        issues += 1;
     }
 
     println!();
@@ -62,30 +98,60 @@ pub fn run(config: &Config) -> Result<()> {
 
     // Check git
     if check_command("git", &["--version"]) {
-        println!("  {} git", "✓".green());
+        println!("  {} git", presenter::ok());
     } else {
-        println!("  {} git (required for plugin install)", "✗".red());
+        println!("  {} git (required for plugin install)", presenter::fail());
         issues += 1;
     }
 
     // Check Python/uv for Python plugins
     if check_command("uv", &["--version"]) {
-        println!("  {} uv (Python package manager)", "✓".green());
+        println!("  {} uv (Python package manager)", presenter::ok());
     } else if check_command("python3", &["--version"]) {
-        println!("  {} python3 (uv recommended for faster installs)", "⚠".yellow());
+        println!("  {} python3 (uv recommended for faster installs)", presenter::warn());
     } else {
-        println!("  {} python3/uv (needed for Python plugins)", "⚠".yellow());
+        println!("  {} python3/uv (needed for Python plugins)", presenter::warn());
     }
 
     // Check cargo for Rust plugins
     if check_command("cargo", &["--version"]) {
-        println!("  {} cargo (Rust build tool)", "✓".green());
+        println!("  {} cargo (Rust build tool)", presenter::ok());
     } else {
-        println!("  {} cargo (needed for Rust plugins)", "⚠".yellow());
+        println!("  {} cargo (needed for Rust plugins)", presenter::warn());
     }
 
     println!();
 
+    // Check what each installed plugin needs to run
+    let plugins = installed_plugins(config).unwrap_or_default();
+    if !plugins.is_empty() {
+        println!("{}", "Plugins:".bold());
+        for collision in plugin::collisions(&plugins) {
+            println!("  {} {}", presenter::fail(), collision);
+            println!(
+                "      {} {}",
+                presenter::arrow(),
+                "Remove or rename all but one: pais plugin remove <directory>".cyan()
+            );
+            issues += 1;
+        }
+        for plugin in &plugins {
+            let name = plugin.manifest.plugin.qualified_name();
+            let diagnoses = health::diagnose(plugin);
+            if diagnoses.is_empty() {
+                println!("  {} {}", presenter::ok(), name);
+                continue;
+            }
+            println!("  {} {}", presenter::fail(), name);
+            for diagnosis in diagnoses {
+                println!("      {}", diagnosis.problem);
+                println!("      {} {}", presenter::arrow(), diagnosis.fix.cyan());
+                issues += 1;
+            }
+        }
+        println!();
+    }
+
     // Check environment tools
     let env = &config.environment;
     let has_tool_config = !env.tool_preferences.is_empty() || !env.tools.is_empty();
@@ -101,9 +167,9 @@ pub fn run(config: &Config) -> Result<()> {
             for (legacy, modern) in prefs {
                 let binary = modern.split_whitespace().next().unwrap_or(modern);
                 if let Some(version) = get_command_version(binary) {
-                    println!("  {} {} → {} ({})", "✓".green(), legacy, modern, version.dimmed());
+                    println!("  {} {} → {} ({})", presenter::ok(), legacy, modern, version.dimmed());
                 } else {
-                    println!("  {} {} → {} (not found)", "✗".red(), legacy, modern);
+                    println!("  {} {} → {} (not found)", presenter::fail(), legacy, modern);
                     println!("    Fallback: {} is available", legacy);
                 }
             }
@@ -117,10 +183,10 @@ pub fn run(config: &Config) -> Result<()> {
             for (name, tool_config) in tools {
                 if let Some(version) = get_command_version(name) {
                     let desc = tool_config.description.as_deref().unwrap_or("");
-                    println!("  {} {} - {} ({})", "✓".green(), name, desc, version.dimmed());
+                    println!("  {} {} - {} ({})", presenter::ok(), name, desc, version.dimmed());
                 } else {
                     let desc = tool_config.description.as_deref().unwrap_or("custom tool");
-                    println!("  {} {} - {} (not found)", "✗".red(), name, desc);
+                    println!("  {} {} - {} (not found)", presenter::fail(), name, desc);
 
                     // Show install hint
                     if let Some(ref github) = tool_config.github {
@@ -146,9 +212,9 @@ pub fn run(config: &Config) -> Result<()> {
         println!("{}", "Repos Directory:".bold());
         if expanded.exists() {
             let count = count_repos(&expanded);
-            println!("  {} {} ({} repos)", "✓".green(), expanded.display(), count);
+            println!("  {} {} ({} repos)", presenter::ok(), expanded.display(), count);
         } else {
-            println!("  {} {} (does not exist)", "✗".red(), expanded.display());
+            println!("  {} {} (does not exist)", presenter::fail(), expanded.display());
             issues += 1;
         }
         println!();
@@ -173,34 +239,65 @@ pub fn run(config: &Config) -> Result<()> {
         }
     );
 
+    // Check the installed Claude Code release against the tested range
+    match compat::detect() {
+        Some(version) => match compat::support(version) {
+            Support::Tested => println!("  {} Claude Code {}", presenter::ok(), version),
+            Support::Newer => {
+                println!(
+                    "  {} Claude Code {} is newer than tested (up to {}.{}.x)",
+                    presenter::warn(),
+                    version,
+                    compat::TESTED_MAX.major,
+                    compat::TESTED_MAX.minor
+                );
+                println!("    Hook payloads may have changed; report anything odd with `pais debug bundle`");
+            }
+            Support::Older => {
+                println!(
+                    "  {} Claude Code {} is older than tested (from {})",
+                    presenter::warn(),
+                    version,
+                    compat::TESTED_MIN
+                );
+                println!("    Upgrade with {}", "claude update".cyan());
+            }
+        },
+        None => println!("  {} Claude Code not found on PATH", presenter::warn()),
+    }
+
     // Check Claude Code hooks file (global settings)
     if let Some(hooks_file) = Config::claude_settings_file() {
         if hooks_file.exists() {
             // Check if hooks are actually configured in the file
             if let Ok(content) = fs::read_to_string(&hooks_file) {
                 if content.contains("hooks") && content.contains("pais") {
-                    println!("  {} Claude Code hooks configured", "✓".green());
+                    println!("  {} Claude Code hooks configured", presenter::ok());
                 } else {
-                    println!("  {} Claude Code settings exists but no PAIS hooks", "⚠".yellow());
-                    println!("    Add hooks configuration to {}", Config::CLAUDE_SETTINGS_JSON.cyan());
+                    println!("  {} Claude Code settings exists but no PAIS hooks", presenter::warn());
+                    println!("    Run {} to register them", "pais claude hooks install".cyan());
                 }
             } else {
-                println!("  {} Claude Code hooks configured", "✓".green());
+                println!("  {} Claude Code hooks configured", presenter::ok());
             }
         } else {
-            println!("  {} Claude Code hooks not configured", "⚠".yellow());
-            println!("    Create {} to enable hooks", Config::CLAUDE_SETTINGS_JSON.cyan());
+            println!("  {} Claude Code hooks not configured", presenter::warn());
+            println!(
+                "    Run {} to create {}",
+                "pais claude hooks install".cyan(),
+                Config::CLAUDE_SETTINGS_JSON.cyan()
+            );
         }
     }
 
     println!();
 
     // Summary
-    println!("{}", "═".repeat(50));
+    println!("{}", presenter::symbol("═", "=").repeat(50));
     if issues == 0 {
-        println!("{} All checks passed!", "✓".green().bold());
+        println!("{} All checks passed!", presenter::ok().bold());
     } else {
-        println!("{} {} issue(s) found", "⚠".yellow().bold(), issues);
+        println!("{} {} issue(s) found", presenter::warn().bold(), issues);
     }
 
     Ok(())
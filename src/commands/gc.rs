@@ -0,0 +1,94 @@
+//! `pais gc`: delete cached data pais can rebuild
+
+use colored::*;
+use eyre::{Context, Result};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::cli::OutputFormat;
+use crate::commands::plugin::installed_plugins;
+use crate::config::Config;
+use crate::error::{CliError, ErrorCode};
+use crate::plugin::build;
+use crate::presenter;
+
+pub fn run(build_cache: bool, all: bool, dry_run: bool, format: OutputFormat, config: &Config) -> Result<()> {
+    if !build_cache {
+        return Err(CliError::new(ErrorCode::InvalidInput, "Nothing to collect")
+            .with_hint("Pass --build-cache")
+            .into());
+    }
+    gc_build_cache(all, dry_run, format, config)
+}
+
+/// A cached build that was (or would be) deleted
+#[derive(Debug, Serialize)]
+struct Removed {
+    plugin: String,
+    key: String,
+    path: PathBuf,
+    bytes: u64,
+}
+
+fn gc_build_cache(all: bool, dry_run: bool, format: OutputFormat, config: &Config) -> Result<()> {
+    let cache = build::cache_dir();
+    // Keys the installed plugins' current sources would build to
+    let current: HashSet<(String, String)> = if all {
+        HashSet::new()
+    } else {
+        installed_plugins(config)?
+            .iter()
+            .filter_map(|plugin| {
+                let name = plugin.path.file_name()?.to_str()?.to_string();
+                let key = build::cache_key(&plugin.path).ok()?;
+                Some((name, key))
+            })
+            .collect()
+    };
+
+    let mut removed = Vec::new();
+    for entry in build::entries(&cache) {
+        if current.contains(&(entry.plugin.clone(), entry.key.clone())) {
+            continue;
+        }
+        if !dry_run {
+            fs::remove_dir_all(&entry.path).with_context(|| format!("Failed to remove {}", entry.path.display()))?;
+            // Drop the plugin's directory once its last build is gone
+            if let Some(parent) = entry.path.parent() {
+                fs::remove_dir(parent).ok();
+            }
+        }
+        removed.push(Removed {
+            plugin: entry.plugin,
+            key: entry.key,
+            path: entry.path,
+            bytes: entry.bytes,
+        });
+    }
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&removed)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&removed)?),
+        OutputFormat::Text => {
+            for item in &removed {
+                println!(
+                    "  {} {} {}",
+                    if dry_run { "would remove".yellow() } else { "removed".red() },
+                    item.plugin,
+                    item.key.dimmed()
+                );
+            }
+            let bytes: u64 = removed.iter().map(|r| r.bytes).sum();
+            println!(
+                "{} {} {} cached build(s), {:.1} MB",
+                presenter::ok(),
+                if dry_run { "Would free" } else { "Freed" },
+                removed.len(),
+                bytes as f64 / 1_048_576.0
+            );
+        }
+    }
+    Ok(())
+}
@@ -1,35 +1,117 @@
-use chrono::NaiveDate;
+use chrono::{Local, NaiveDate, Utc};
 use colored::*;
 use eyre::{Context, Result};
 use serde::Serialize;
 use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
-use crate::cli::{HistoryAction, OutputFormat};
-use crate::config::Config;
-use crate::history::HistoryStore;
+use crate::cli::{FormatArg, HistoryAction, OutputFormat};
+use crate::clock;
+use crate::config::{CategoryConfig, Config, EmbeddingProvider};
+use crate::error::{CliError, ErrorCode};
+use crate::history::archive::{self, Archive};
+use crate::history::calendar;
 use crate::history::capture::EventCapture;
+use crate::history::categorize::{Categories, RESERVED_DIRS};
+use crate::history::dedupe::{self, Dedupe};
+use crate::history::embed::{self, Embedder};
+use crate::history::encrypt::{self, Encryption};
+use crate::history::index::{HistoryIndex, SearchHit};
+use crate::history::links;
+use crate::history::manage::{self, TagChanges};
+use crate::history::merge;
+use crate::history::notion::{self, ExportAction, ExportedPage, NotionClient, NotionState};
+use crate::history::publish::{self, PublishTarget};
+use crate::history::redact::{Finding, Redactor};
+use crate::history::review::{self, ReviewIndex};
+use crate::history::stats;
+use crate::history::sync::{HistoryRepo, SyncReport};
+use crate::history::tools::{ToolSort, ToolStore};
+use crate::history::transcript;
+use crate::history::watch::HistoryWatcher;
+use crate::history::{HistoryEntry, HistoryStore};
+use crate::hook::history::HistoryHandler;
+use crate::net;
+use crate::observability::flush_buffered;
+use crate::presenter::{self, Table};
+use crate::progress;
+use crate::secrets::SecretStore;
+use crate::sync::Prefer;
 
-pub fn run(action: HistoryAction, config: &Config) -> Result<()> {
+pub fn run(action: HistoryAction, format: OutputFormat, requested: Option<FormatArg>, config: &Config) -> Result<()> {
     match action {
         HistoryAction::Query {
             query,
             category,
             limit,
             since,
-            format,
+            reviewed,
         } => query_history(
             &query,
             category.as_deref(),
             limit,
             since.as_deref(),
-            OutputFormat::resolve(format),
+            reviewed,
+            format,
             config,
         ),
+        HistoryAction::Search { query, category, limit } => search(&query, category.as_deref(), limit, format, config),
+        HistoryAction::Similar { query, category, limit } => {
+            similar(&query, category.as_deref(), limit, format, config)
+        }
+        HistoryAction::Review { category, list } => review(category.as_deref(), list, format, config),
         HistoryAction::Recent { category, count } => recent(category.as_deref(), count, config),
-        HistoryAction::Categories => categories(config),
+        HistoryAction::Watch { category } => watch(category.as_deref(), format, config),
+        HistoryAction::Categories { describe } => categories(describe, format, config),
+        HistoryAction::Prune { dry_run } => prune(dry_run, format, config),
+        HistoryAction::Dedupe {
+            category,
+            threshold,
+            dry_run,
+        } => dedupe(category.as_deref(), threshold, dry_run, format, config),
+        HistoryAction::Recategorize { dry_run } => recategorize(dry_run, format, config),
+        HistoryAction::Reindex => reindex(format, config),
+        HistoryAction::Import {
+            claude_transcripts,
+            since,
+            dry_run,
+        } => import(claude_transcripts, since.as_deref(), dry_run, format, config),
+        HistoryAction::Pack { output } => pack(output, format, config),
+        HistoryAction::Unpack {
+            archive,
+            force,
+            dry_run,
+        } => unpack(&archive, force, dry_run, format, config),
         HistoryAction::Show { id } => show_entry(&id, config),
-        HistoryAction::Stats { days, format } => stats(days, OutputFormat::resolve(format), config),
+        HistoryAction::Edit { id } => edit_entry(&id, config),
+        HistoryAction::Tag { id, tags } => tag_entry(&id, &tags, format, config),
+        HistoryAction::Link { from, to, remove } => link_entry(&from, &to, remove, format, config),
+        HistoryAction::Move { id, category } => move_entry(&id, &category, config),
+        HistoryAction::Delete { id, force } => delete_entry(&id, force, config),
+        HistoryAction::Stats { days, top } => stats(days, top, format, config),
+        HistoryAction::Tools {
+            session,
+            days,
+            sort,
+            limit,
+        } => tools(session.as_deref(), days, sort, limit, format, config),
         HistoryAction::Events { limit } => list_events(limit, config),
+        HistoryAction::Calendar { days, output } => calendar(days, output.as_deref(), requested, config),
+        HistoryAction::ExportNotion {
+            database,
+            category,
+            dry_run,
+        } => export_notion(database, category.as_deref(), dry_run, format, config),
+        HistoryAction::Publish { id, to, dry_run } => publish(&id, to, dry_run, config),
+        HistoryAction::ScanSecrets { category, fix } => scan_secrets(category.as_deref(), fix, format, config),
+        HistoryAction::Sync {
+            remote,
+            prefer,
+            dry_run,
+        } => sync(remote.as_deref(), prefer, dry_run, format, config),
+        HistoryAction::Merge { dir, prefer, dry_run } => merge(&dir, prefer, dry_run, format, config),
+        HistoryAction::Encrypt { category, decrypt } => encrypt(category.as_deref(), decrypt, format, config),
     }
 }
 
@@ -47,11 +129,11 @@ fn query_history(
     category: Option<&str>,
     limit: usize,
     since: Option<&str>,
+    reviewed: bool,
     format: OutputFormat,
     config: &Config,
 ) -> Result<()> {
     let history_dir = Config::expand_path(&config.paths.history);
-    let store = HistoryStore::new(history_dir);
 
     // Parse since date if provided
     let since_date = since
@@ -59,7 +141,14 @@ fn query_history(
         .transpose()
         .context("Invalid date format (use YYYY-MM-DD)")?;
 
-    let entries = store.query(query, category, since_date, limit)?;
+    let entries = if reviewed {
+        ReviewIndex::new(&history_dir).query(query, category, since_date, limit)?
+    } else {
+        HistoryStore::new(history_dir)
+            .with_index(config.history.index.enabled)
+            .with_encryption(Encryption::from_config(&config.history.encryption))
+            .query(query, category, since_date, limit)?
+    };
 
     match format {
         OutputFormat::Json | OutputFormat::Yaml => {
@@ -101,9 +190,147 @@ fn query_history(
     Ok(())
 }
 
+#[derive(Serialize)]
+struct SearchHitOutput {
+    #[serde(flatten)]
+    entry: HistoryEntryOutput,
+    score: f64,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    snippet: String,
+}
+
+/// Full-text search over the index
+fn search(query: &str, category: Option<&str>, limit: usize, format: OutputFormat, config: &Config) -> Result<()> {
+    let history_dir = Config::expand_path(&config.paths.history);
+    let index = HistoryIndex::new(&history_dir);
+
+    // Without `history.index.enabled` nothing keeps the index current
+    if !config.history.index.enabled {
+        let pb = progress::spinner(format, "Indexing history entries...");
+        let rebuilt = index.rebuild();
+        pb.finish_and_clear();
+        rebuilt?;
+    }
+
+    let hits = index.search(query, category, limit)?;
+    print_hits(query, hits, false, format)
+}
+
+/// Semantic search: embed entries that changed since the last run, then rank
+/// them by similarity to the query
+fn similar(query: &str, category: Option<&str>, limit: usize, format: OutputFormat, config: &Config) -> Result<()> {
+    let embeddings = &config.history.embeddings;
+    let local = embeddings.provider == EmbeddingProvider::Ollama && net::is_local(&embeddings.ollama_url);
+    if config.network.offline && !local {
+        eprintln!(
+            "{} Offline: embeddings need the network, showing full-text matches instead",
+            presenter::warn()
+        );
+        return search(query, category, limit, format, config);
+    }
+
+    let history_dir = Config::expand_path(&config.paths.history);
+    let index = HistoryIndex::new(&history_dir);
+    let embedder = Embedder::new(embeddings, &config.network, &SecretStore::load())?;
+    let model = embedder.id();
+
+    if !config.history.index.enabled {
+        let pb = progress::spinner(format, "Indexing history entries...");
+        let rebuilt = index.rebuild();
+        pb.finish_and_clear();
+        rebuilt?;
+    }
+
+    let pending = index.pending_embeddings(&model)?;
+    if !pending.is_empty() {
+        log::info!("Embedding {} history entries with {}", pending.len(), model);
+        let pb = progress::bar(
+            format,
+            pending.len() as u64,
+            format!("Embedding entries with {}", model),
+        );
+        for batch in pending.chunks(embed::BATCH_SIZE) {
+            let texts: Vec<String> = batch.iter().map(|p| p.text.clone()).collect();
+            let vectors = match embedder.embed(&texts) {
+                Ok(vectors) => vectors,
+                Err(e) => {
+                    pb.finish_and_clear();
+                    return Err(e);
+                }
+            };
+            for (item, vector) in batch.iter().zip(&vectors) {
+                index.store_embedding(item, &model, vector)?;
+            }
+            pb.inc(batch.len() as u64);
+        }
+        pb.finish_and_clear();
+    }
+
+    let query_vector = embedder.embed(&[query.to_string()])?.pop().unwrap_or_default();
+    let hits = index.similar(&query_vector, &model, category, limit)?;
+    print_hits(query, hits, true, format)
+}
+
+/// Print ranked hits with their entries; `show_score` for similarity scores,
+/// which unlike BM25 read as a fraction
+fn print_hits(query: &str, hits: Vec<SearchHit>, show_score: bool, format: OutputFormat) -> Result<()> {
+    let hits: Vec<(SearchHit, HistoryEntry)> = hits
+        .into_iter()
+        .filter_map(|hit| {
+            let content = fs::read_to_string(&hit.path).ok()?;
+            let entry = HistoryEntry::from_markdown(&content, &hit.path).ok()?;
+            Some((hit, entry))
+        })
+        .collect();
+    match format {
+        OutputFormat::Json | OutputFormat::Yaml => {
+            let output: Vec<SearchHitOutput> = hits
+                .into_iter()
+                .map(|(hit, e)| SearchHitOutput {
+                    entry: HistoryEntryOutput {
+                        id: e.id,
+                        category: e.category,
+                        title: e.title,
+                        created_at: e.created_at.format("%Y-%m-%dT%H:%M:%S%z").to_string(),
+                        tags: e.tags,
+                    },
+                    score: hit.score,
+                    snippet: hit.snippet,
+                })
+                .collect();
+            match format {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+                OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&output)?),
+                _ => unreachable!(),
+            }
+        }
+        OutputFormat::Text => {
+            println!("{} {} matches for '{}':", "🔍".blue(), hits.len(), query.cyan());
+            println!();
+
+            if hits.is_empty() {
+                println!("  {}", "(no matches)".dimmed());
+            }
+            for (hit, entry) in &hits {
+                print_entry_summary(entry);
+                if show_score {
+                    println!("    similarity: {}", format!("{:.2}", hit.score).dimmed());
+                }
+                let snippet = hit.snippet.split_whitespace().collect::<Vec<_>>().join(" ");
+                if !snippet.is_empty() {
+                    println!("    {}", presenter::truncate(&snippet, 100).dimmed());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 fn recent(category: Option<&str>, count: usize, config: &Config) -> Result<()> {
     let history_dir = Config::expand_path(&config.paths.history);
-    let store = HistoryStore::new(history_dir);
+    let store = HistoryStore::new(history_dir)
+        .with_index(config.history.index.enabled)
+        .with_encryption(Encryption::from_config(&config.history.encryption));
 
     let entries = store.recent(category, count)?;
 
@@ -121,143 +348,1722 @@ fn recent(category: Option<&str>, count: usize, config: &Config) -> Result<()> {
     Ok(())
 }
 
-fn categories(config: &Config) -> Result<()> {
-    println!("{}", "History categories:".bold());
-    println!();
+/// Print entries as they are written until interrupted, one per line in JSON and YAML
+fn watch(category: Option<&str>, format: OutputFormat, config: &Config) -> Result<()> {
+    if let Some(category) = category {
+        Categories::from_config(&config.history).check(category)?;
+    }
+    let store = HistoryStore::new(Config::expand_path(&config.paths.history))
+        .with_encryption(Encryption::from_config(&config.history.encryption));
+    let mut watcher = HistoryWatcher::new(&store, category)?;
+
+    if format == OutputFormat::Text {
+        println!("{} Watching {}", presenter::arrow(), watcher.base().display());
+        println!("{}", "Press Ctrl-C to stop".dimmed());
+    }
+
+    loop {
+        for path in watcher.next_batch()? {
+            // Entries can be moved or deleted again before they are read
+            let entry = match store
+                .read(&path)
+                .and_then(|content| HistoryEntry::from_markdown(&content, &path))
+            {
+                Ok(entry) => entry,
+                Err(e) => {
+                    log::debug!("Skipping {}: {:#}", path.display(), e);
+                    continue;
+                }
+            };
+            match format {
+                OutputFormat::Json => println!("{}", serde_json::to_string(&entry)?),
+                OutputFormat::Yaml => print!("---\n{}", serde_yaml::to_string(&entry)?),
+                OutputFormat::Text => {
+                    print_entry_summary(&entry);
+                    if let Some(summary) = entry.metadata.get("summary") {
+                        println!("    {}", presenter::truncate(summary, 100).dimmed());
+                    }
+                }
+            }
+        }
+        io::stdout().flush()?;
+    }
+}
+
+/// What to do with an entry in the review queue
+#[derive(Clone, Copy)]
+enum ReviewAction {
+    Keep,
+    Edit,
+    Recategorize,
+    Delete,
+    Skip,
+    Quit,
+}
+
+impl ReviewAction {
+    const ALL: [ReviewAction; 6] = [
+        ReviewAction::Keep,
+        ReviewAction::Edit,
+        ReviewAction::Recategorize,
+        ReviewAction::Delete,
+        ReviewAction::Skip,
+        ReviewAction::Quit,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            ReviewAction::Keep => "Keep",
+            ReviewAction::Edit => "Edit, then keep",
+            ReviewAction::Recategorize => "Move to another category, then keep",
+            ReviewAction::Delete => "Delete",
+            ReviewAction::Skip => "Skip for now",
+            ReviewAction::Quit => "Quit",
+        }
+    }
+}
+
+/// Step through unreviewed entries (or list them with `--list`)
+fn review(category: Option<&str>, list: bool, format: OutputFormat, config: &Config) -> Result<()> {
+    use std::io::IsTerminal;
 
     let history_dir = Config::expand_path(&config.paths.history);
-    let store = HistoryStore::new(history_dir);
+    let store = HistoryStore::new(history_dir.clone())
+        .with_index(config.history.index.enabled)
+        .with_encryption(Encryption::from_config(&config.history.encryption));
+    let queue = review::queue(&store, category)?;
 
-    let cats = store.categories()?;
+    if list || format != OutputFormat::Text {
+        match format {
+            OutputFormat::Json | OutputFormat::Yaml => {
+                let output: Vec<HistoryEntryOutput> = queue
+                    .iter()
+                    .map(|(_, e)| HistoryEntryOutput {
+                        id: e.id.clone(),
+                        category: e.category.clone(),
+                        title: e.title.clone(),
+                        created_at: e.created_at.format("%Y-%m-%dT%H:%M:%S%z").to_string(),
+                        tags: e.tags.clone(),
+                    })
+                    .collect();
+                match format {
+                    OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+                    OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&output)?),
+                    _ => unreachable!(),
+                }
+            }
+            OutputFormat::Text => {
+                println!("{} {} entries awaiting review:", "📥".blue(), queue.len());
+                println!();
+                if queue.is_empty() {
+                    println!("  {}", "(queue is empty)".dimmed());
+                }
+                for (_, entry) in &queue {
+                    print_entry_summary(entry);
+                }
+            }
+        }
+        return Ok(());
+    }
 
-    if cats.is_empty() {
-        println!("  {}", "(no history yet)".dimmed());
+    if queue.is_empty() {
+        println!("{} Nothing to review", presenter::ok());
         return Ok(());
     }
+    if !std::io::stdin().is_terminal() || !std::io::stderr().is_terminal() {
+        return Err(
+            CliError::new(ErrorCode::InvalidInput, "Reviewing needs an interactive terminal")
+                .with_hint("Use `pais history review --list` to print the queue")
+                .into(),
+        );
+    }
+
+    let index = ReviewIndex::new(&history_dir);
+    let promote = &config.history.review.promote;
+    let theme = dialoguer::theme::ColorfulTheme::default();
+    let labels: Vec<&str> = ReviewAction::ALL.iter().map(|a| a.label()).collect();
+    let (mut kept, mut deleted, mut skipped) = (0, 0, 0);
+
+    let total = queue.len();
+    for (i, (path, entry)) in queue.into_iter().enumerate() {
+        println!();
+        println!(
+            "{} {}",
+            format!("[{}/{}]", i + 1, total).dimmed(),
+            "─".repeat(40).dimmed()
+        );
+        print_entry_summary(&entry);
+        println!();
+        let lines: Vec<&str> = entry.content.lines().collect();
+        for line in lines.iter().take(30) {
+            println!("    {}", line);
+        }
+        if lines.len() > 30 {
+            println!("    {}", format!("… {} more lines", lines.len() - 30).dimmed());
+        }
+        println!();
+
+        let choice = dialoguer::Select::with_theme(&theme)
+            .with_prompt("Review")
+            .items(&labels)
+            .default(0)
+            .interact_opt()?;
+        let action = choice.map_or(ReviewAction::Quit, |i| ReviewAction::ALL[i]);
+
+        let path = match action {
+            ReviewAction::Quit => break,
+            ReviewAction::Skip => {
+                skipped += 1;
+                continue;
+            }
+            ReviewAction::Delete => {
+                store.remove(&path)?;
+                index.remove(&entry.id)?;
+                println!("{} Deleted", presenter::ok());
+                deleted += 1;
+                continue;
+            }
+            ReviewAction::Keep => path,
+            ReviewAction::Edit => {
+                crate::commands::skill::open_in_editor(&path)?;
+                path
+            }
+            ReviewAction::Recategorize => {
+                let names = Categories::from_config(&config.history)
+                    .iter()
+                    .map(|c| c.name.clone())
+                    .filter(|name| *name != entry.category)
+                    .collect();
+                let target = crate::picker::pick_name(None, "category", || Ok(names))?;
+                review::recategorize(&store, &path, &target)?
+            }
+        };
 
-    for cat in cats {
-        let count = store.count(&cat)?;
-        println!("  {:15} ({} entries)", cat.cyan(), count);
+        let reviewed = review::mark_reviewed(&store, &path)?;
+        if promote.contains(&reviewed.category) {
+            index.promote(&reviewed, &path)?;
+            println!(
+                "{} Kept and added to the {} index",
+                presenter::ok(),
+                reviewed.category.cyan()
+            );
+        } else {
+            index.remove(&reviewed.id)?;
+            println!("{} Kept in {}", presenter::ok(), reviewed.category.cyan());
+        }
+        kept += 1;
     }
 
+    println!();
+    println!(
+        "{} Reviewed: {} kept, {} deleted, {} skipped",
+        presenter::info(),
+        kept,
+        deleted,
+        skipped
+    );
     Ok(())
 }
 
-fn print_entry_summary(entry: &crate::history::HistoryEntry) {
-    let date = entry.created_at.format("%Y-%m-%d %H:%M").to_string();
-    println!(
-        "  {} {} {} {}",
-        entry.id[..8.min(entry.id.len())].dimmed(),
-        entry.category.cyan(),
-        date.dimmed(),
-        entry.title.bold()
-    );
-    if !entry.tags.is_empty() {
-        println!("    tags: {}", entry.tags.join(", ").dimmed());
+/// A declared category, or a directory on disk that isn't one
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct CategoryOutput {
+    name: String,
+    entries: usize,
+    declared: bool,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    config: Option<CategoryConfig>,
+}
+
+fn categories(describe: bool, format: OutputFormat, config: &Config) -> Result<()> {
+    let store = HistoryStore::new(Config::expand_path(&config.paths.history));
+    let declared = Categories::from_config(&config.history);
+
+    let mut output = Vec::new();
+    for category in declared.iter() {
+        output.push(CategoryOutput {
+            name: category.name.clone(),
+            entries: store.count(&category.name)?,
+            declared: true,
+            config: describe.then(|| category.clone()),
+        });
+    }
+    for name in store.categories()? {
+        if !RESERVED_DIRS.contains(&name.as_str()) && declared.get(&name).is_none() {
+            output.push(CategoryOutput {
+                entries: store.count(&name)?,
+                name,
+                declared: false,
+                config: None,
+            });
+        }
+    }
+    let problems = declared.problems();
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&output)?),
+        OutputFormat::Text => {
+            println!("{}", "History categories:".bold());
+            println!();
+            for category in &output {
+                let name = if category.declared { category.name.cyan() } else { category.name.yellow() };
+                let mut notes = Vec::new();
+                if category.name == declared.default_category() {
+                    notes.push("default".to_string());
+                }
+                if !category.declared {
+                    notes.push("not declared".to_string());
+                }
+                let notes = if notes.is_empty() {
+                    String::new()
+                } else {
+                    format!(" [{}]", notes.join(", "))
+                };
+                println!("  {:15} ({} entries){}", name, category.entries, notes.dimmed());
+
+                let Some(config) = &category.config else { continue };
+                if !config.description.is_empty() {
+                    println!("    {}", config.description);
+                }
+                if let Some(days) = config.retention_days {
+                    println!("    {} {} days", "Retention:".dimmed(), days);
+                }
+                if !config.frontmatter.is_empty() {
+                    let fields: Vec<String> = config
+                        .frontmatter
+                        .iter()
+                        .map(|(k, v)| format!("{}: {}", k, v))
+                        .collect();
+                    println!("    {} {}", "Frontmatter:".dimmed(), fields.join(", "));
+                }
+                if let Some(template) = &config.template {
+                    let source = if template.contains('\n') { "inline" } else { template.as_str() };
+                    println!("    {} {}", "Template:".dimmed(), source);
+                }
+                if !config.route.keywords.is_empty() {
+                    println!(
+                        "    {} {} of: {}",
+                        "Routed by:".dimmed(),
+                        config.route.min_matches,
+                        presenter::truncate(&config.route.keywords.join(", "), 100)
+                    );
+                }
+                if !config.route.patterns.is_empty() {
+                    println!(
+                        "    {} {}",
+                        "Patterns:".dimmed(),
+                        presenter::truncate(&config.route.patterns.join("  "), 100)
+                    );
+                }
+                if !config.route.agents.is_empty() {
+                    println!("    {} {}", "Agents:".dimmed(), config.route.agents.join(", "));
+                }
+                println!();
+            }
+
+            if output.iter().any(|c| !c.declared) {
+                println!(
+                    "{} Directories not declared in history.categories are listed but nothing new is written to them",
+                    presenter::info()
+                );
+            }
+            for problem in &problems {
+                println!("{} history.categories: {}", presenter::warn(), problem);
+            }
+        }
     }
+
+    Ok(())
 }
 
-/// Show a specific history entry
-fn show_entry(id: &str, config: &Config) -> Result<()> {
-    let history_dir = Config::expand_path(&config.paths.history);
-    let store = HistoryStore::new(history_dir.clone());
+/// Delete entries past their category's retention
+fn prune(dry_run: bool, format: OutputFormat, config: &Config) -> Result<()> {
+    let store = HistoryStore::new(Config::expand_path(&config.paths.history)).with_index(config.history.index.enabled);
+    let today = Local::now().date_naive();
 
-    // Search all categories for the entry
-    let cats = store.categories()?;
+    #[derive(Serialize)]
+    struct Pruned {
+        category: String,
+        retention_days: u32,
+        entries: Vec<String>,
+    }
 
-    for cat in &cats {
-        let cat_path = history_dir.join(cat);
-        if !cat_path.exists() {
+    let mut pruned = Vec::new();
+    for category in Categories::from_config(&config.history).iter() {
+        let Some(days) = category.retention_days else {
             continue;
+        };
+        let before = today - chrono::Duration::days(days.into());
+        let paths = store.prune(&category.name, before, dry_run)?;
+        pruned.push(Pruned {
+            category: category.name.clone(),
+            retention_days: days,
+            entries: paths.iter().map(|p| p.display().to_string()).collect(),
+        });
+    }
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&pruned)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&pruned)?),
+        OutputFormat::Text => {
+            if pruned.is_empty() {
+                println!("{} No category sets retention-days", presenter::info());
+                return Ok(());
+            }
+            let verb = if dry_run { "Would delete" } else { "Deleted" };
+            for category in &pruned {
+                println!(
+                    "  {} {:15} {} {} entries older than {} days",
+                    if category.entries.is_empty() { presenter::ok() } else { presenter::arrow() },
+                    category.category.cyan(),
+                    verb,
+                    category.entries.len(),
+                    category.retention_days
+                );
+            }
         }
+    }
+    Ok(())
+}
+
+/// A duplicate `pais history dedupe` merged (or would merge)
+#[derive(Serialize)]
+struct DuplicateOutput {
+    id: String,
+    kept: String,
+    category: String,
+    title: String,
+    similarity: f64,
+}
+
+fn dedupe(
+    category: Option<&str>,
+    threshold: Option<f64>,
+    dry_run: bool,
+    format: OutputFormat,
+    config: &Config,
+) -> Result<()> {
+    let settings = &config.history.dedupe;
+    let threshold = threshold.unwrap_or(settings.threshold);
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(CliError::new(
+            ErrorCode::InvalidInput,
+            format!("Threshold must be between 0 and 1, got {}", threshold),
+        )
+        .into());
+    }
 
-        // Search date directories
-        for date_entry in fs::read_dir(&cat_path)? {
-            let date_entry = date_entry?;
-            let date_path = date_entry.path();
+    let store = HistoryStore::new(Config::expand_path(&config.paths.history))
+        .with_index(config.history.index.enabled)
+        .with_encryption(Encryption::from_config(&config.history.encryption));
+    let categories: Vec<String> = match category {
+        Some(name) => vec![name.to_string()],
+        None => store
+            .categories()?
+            .into_iter()
+            .filter(|name| {
+                !dedupe::SKIPPED_CATEGORIES.contains(&name.as_str()) && !RESERVED_DIRS.contains(&name.as_str())
+            })
+            .collect(),
+    };
 
-            if !date_path.is_dir() {
-                continue;
+    let pb = progress::spinner(format, "Comparing history entries...");
+    let mut found = Vec::new();
+    for name in &categories {
+        match dedupe::scan(&store, name, threshold, settings.window) {
+            Ok(duplicates) => found.extend(duplicates),
+            Err(e) => {
+                pb.finish_and_clear();
+                return Err(e);
             }
+        }
+    }
+    pb.finish_and_clear();
 
-            for file_entry in fs::read_dir(&date_path)? {
-                let file_entry = file_entry?;
-                let path = file_entry.path();
+    if !dry_run {
+        for duplicate in &found {
+            dedupe::fold(&store, &duplicate.kept, &duplicate.entry)?;
+            manage::delete(&store, &duplicate.path, &duplicate.entry.id)?;
+        }
+    }
 
-                if let Some(stem) = path.file_stem().and_then(|s| s.to_str())
-                    && (stem == id || stem.starts_with(id))
-                {
-                    // Found it!
-                    let content = fs::read_to_string(&path)?;
-                    println!("{}", content);
-                    return Ok(());
-                }
+    let output: Vec<DuplicateOutput> = found
+        .into_iter()
+        .map(|d| DuplicateOutput {
+            id: d.entry.id,
+            kept: d.kept_id,
+            category: d.entry.category,
+            title: d.entry.title,
+            similarity: (d.similarity * 100.0).round() / 100.0,
+        })
+        .collect();
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&output)?),
+        OutputFormat::Text => {
+            if output.is_empty() {
+                println!("{} No near-duplicates at {:.2}", presenter::ok(), threshold);
+                return Ok(());
+            }
+            let mut table = Table::new(&["ID", "INTO", "CATEGORY", "SIMILARITY", "TITLE"]);
+            for d in &output {
+                table.add_row(vec![
+                    d.id.yellow(),
+                    d.kept.normal(),
+                    d.category.cyan(),
+                    format!("{:.2}", d.similarity).normal(),
+                    presenter::truncate(&d.title, 50).normal(),
+                ]);
             }
+            table.print();
+            println!();
+            let verb = if dry_run { "Would merge" } else { "Merged" };
+            println!("{} {} {} duplicate(s)", presenter::info(), verb, output.len());
         }
     }
+    Ok(())
+}
 
-    eyre::bail!("Entry '{}' not found", id)
+/// An entry `pais history recategorize` moved (or would move)
+#[derive(Serialize)]
+struct RecategorizedOutput {
+    id: String,
+    title: String,
+    from: String,
+    to: String,
 }
 
-/// Show event statistics
-fn stats(days: usize, format: OutputFormat, config: &Config) -> Result<()> {
+fn recategorize(dry_run: bool, format: OutputFormat, config: &Config) -> Result<()> {
     let history_dir = Config::expand_path(&config.paths.history);
-    let capture = EventCapture::new(history_dir, true);
+    let categories = Categories::from_config(&config.history);
+    let handler = HistoryHandler::new(true, history_dir.clone()).with_categories(categories.clone());
+    let store = HistoryStore::new(history_dir)
+        .with_categories(categories)
+        .with_index(config.history.index.enabled)
+        .with_encryption(Encryption::from_config(&config.history.encryption));
 
-    let stats = capture.stats(days)?;
+    // Listed up front: moves change the tree being walked
+    let paths = store.paths(None, None)?.collect::<Result<Vec<_>>>()?;
+    let mut output = Vec::new();
+    for path in paths {
+        let entry = match store
+            .read(&path)
+            .and_then(|content| HistoryEntry::from_markdown(&content, &path))
+        {
+            Ok(entry) => entry,
+            Err(e) => {
+                log::warn!("Skipping {}: {:#}", path.display(), e);
+                continue;
+            }
+        };
+        let Some(category) = handler.reroute(&entry).filter(|c| *c != entry.category) else {
+            continue;
+        };
+        if !dry_run {
+            manage::move_to(&store, &path, &category)?;
+        }
+        output.push(RecategorizedOutput {
+            id: entry.id,
+            title: entry.title,
+            from: entry.category,
+            to: category,
+        });
+    }
 
     match format {
-        OutputFormat::Json => {
-            #[derive(Serialize)]
-            struct StatsOutput {
-                total: usize,
-                by_type: std::collections::HashMap<String, usize>,
-                days: usize,
-            }
-            let output = StatsOutput {
-                total: stats.total,
-                by_type: stats.by_type.clone(),
-                days,
-            };
-            println!("{}", serde_json::to_string_pretty(&output)?);
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&output)?),
+        OutputFormat::Text => {
+            if output.is_empty() {
+                println!(
+                    "{} Captured entries are where the current rules put them",
+                    presenter::ok()
+                );
+                return Ok(());
+            }
+            let mut table = Table::new(&["ID", "FROM", "TO", "TITLE"]);
+            for moved in &output {
+                table.add_row(vec![
+                    moved.id.yellow(),
+                    moved.from.normal(),
+                    moved.to.cyan(),
+                    presenter::truncate(&moved.title, 50).normal(),
+                ]);
+            }
+            table.print();
+            println!();
+            let verb = if dry_run { "Would move" } else { "Moved" };
+            println!("{} {} {} entry(s)", presenter::info(), verb, output.len());
         }
-        OutputFormat::Yaml => {
+    }
+    Ok(())
+}
+
+/// Rebuild the SQLite index
+fn reindex(format: OutputFormat, config: &Config) -> Result<()> {
+    let index = HistoryIndex::new(&Config::expand_path(&config.paths.history));
+
+    let pb = progress::spinner(format, "Indexing history entries...");
+    let count = index.rebuild();
+    pb.finish_and_clear();
+    let count = count?;
+
+    println!(
+        "{} Indexed {} entries in {}",
+        presenter::ok(),
+        count,
+        index.path().display()
+    );
+    if !config.history.index.enabled {
+        println!(
+            "  {} Set {} to keep it up to date and use it for lookups",
+            presenter::info(),
+            "history.index.enabled: true".cyan()
+        );
+    }
+    Ok(())
+}
+
+/// Write the history directory to a portable archive
+fn pack(output: Option<PathBuf>, format: OutputFormat, config: &Config) -> Result<()> {
+    let history_dir = Config::expand_path(&config.paths.history);
+    let output = output
+        .unwrap_or_else(|| PathBuf::from(format!("pais-history-{}.tar.gz", clock::now_local().format("%Y-%m-%d"))));
+
+    let pb = progress::spinner(format, "Packing history...");
+    let summary = archive::pack(&history_dir, &config.history, &output);
+    pb.finish_and_clear();
+    let summary = summary?;
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&summary)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&summary)?),
+        OutputFormat::Text => println!(
+            "{} Packed {} files and {} embeddings into {} ({} KB)",
+            presenter::ok(),
+            summary.files,
+            summary.embeddings,
+            summary.archive.display(),
+            summary.bytes.div_ceil(1024)
+        ),
+    }
+    Ok(())
+}
+
+/// Restore an archive written by `history pack`
+fn unpack(path: &Path, force: bool, dry_run: bool, format: OutputFormat, config: &Config) -> Result<()> {
+    let history_dir = Config::expand_path(&config.paths.history);
+    let archive = Archive::read(path)?;
+    let summary = archive.unpack(&history_dir, force, dry_run)?;
+    let undeclared = archive.undeclared_categories(&Categories::from_config(&config.history));
+
+    match format {
+        OutputFormat::Json | OutputFormat::Yaml => {
             #[derive(Serialize)]
-            struct StatsOutput {
-                total: usize,
-                by_type: std::collections::HashMap<String, usize>,
-                days: usize,
-            }
-            let output = StatsOutput {
-                total: stats.total,
-                by_type: stats.by_type.clone(),
-                days,
+            struct UnpackOutput<'a> {
+                dry_run: bool,
+                #[serde(flatten)]
+                summary: &'a archive::UnpackSummary,
+                #[serde(skip_serializing_if = "Vec::is_empty")]
+                undeclared_categories: Vec<&'a str>,
+            }
+            let output = UnpackOutput {
+                dry_run,
+                summary: &summary,
+                undeclared_categories: undeclared.iter().map(|c| c.name.as_str()).collect(),
             };
-            println!("{}", serde_yaml::to_string(&output)?);
+            match format {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+                _ => print!("{}", serde_yaml::to_string(&output)?),
+            }
         }
         OutputFormat::Text => {
-            println!("{} Event statistics (last {} days):", "📊".blue(), days);
-            println!();
-            println!("  Total events: {}", stats.total.to_string().bold());
-            println!();
-
-            if stats.by_type.is_empty() {
-                println!("  {}", "(no events captured)".dimmed());
-            } else {
-                println!("  By type:");
-                let mut types: Vec<_> = stats.by_type.iter().collect();
-                types.sort_by(|a, b| b.1.cmp(a.1));
-                for (event_type, count) in types {
-                    println!("    {:20} {}", event_type.cyan(), count);
-                }
+            let verb = if dry_run { "Would write" } else { "Wrote" };
+            println!(
+                "{} {} {} files ({} unchanged) from a pais {} archive",
+                presenter::ok(),
+                verb,
+                summary.written.len(),
+                summary.unchanged,
+                archive.manifest.pais_version
+            );
+            if summary.embeddings > 0 && !dry_run {
+                println!("  Rebuilt the index and restored {} embeddings", summary.embeddings);
+            }
+            for path in &summary.conflicts {
+                println!("{} {} differs locally; kept", presenter::warn(), path);
+            }
+            if !summary.conflicts.is_empty() {
+                println!("  Run with {} to take the archive's copies", "--force".cyan());
+            }
+            if !undeclared.is_empty() {
+                println!(
+                    "{} The archive's categories {} are not declared here; add them under history.categories:",
+                    presenter::warn(),
+                    undeclared
+                        .iter()
+                        .map(|c| c.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+                print!("{}", serde_yaml::to_string(&undeclared)?);
             }
         }
     }
-
     Ok(())
 }
 
-/// List available raw event dates
+/// Backfill entries from Claude Code transcripts
+fn import(
+    claude_transcripts: bool,
+    since: Option<&str>,
+    dry_run: bool,
+    format: OutputFormat,
+    config: &Config,
+) -> Result<()> {
+    if !claude_transcripts {
+        return Err(CliError::new(ErrorCode::InvalidInput, "Nothing to import")
+            .with_hint("Pass --claude-transcripts to import sessions from ~/.claude/projects")
+            .into());
+    }
+    let since = since
+        .map(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d"))
+        .transpose()
+        .context("Invalid date format (use YYYY-MM-DD)")?;
+    let history_dir = Config::expand_path(&config.paths.history);
+    let handler = HistoryHandler::new(true, history_dir.clone())
+        .with_redactor(Redactor::from_config(&config.history.redaction))
+        .with_categories(Categories::from_config(&config.history))
+        .with_review(config.history.review.enabled)
+        .with_index(config.history.index.enabled)
+        .with_encryption(Encryption::from_config(&config.history.encryption))
+        .with_dedupe(Dedupe::from_config(&config.history.dedupe));
+
+    // Sessions the Stop hook (or an earlier import) already captured
+    let store = HistoryStore::new(history_dir).with_index(config.history.index.enabled);
+    let mut captured = std::collections::HashSet::new();
+    for entry in store.entries(None, None)?.flatten() {
+        if entry.category != "events"
+            && let Some(session) = entry.metadata.get("session_id")
+        {
+            captured.insert(session.clone());
+        }
+    }
+
+    let transcripts = transcript::projects_dir()
+        .map(|dir| transcript::all_transcripts(&dir))
+        .unwrap_or_default();
+
+    #[derive(Serialize)]
+    struct Imported {
+        id: String,
+        category: String,
+        title: String,
+        created_at: String,
+        session_id: String,
+    }
+
+    let (mut imported, mut existing, mut empty) = (Vec::new(), 0, 0);
+    let pb = progress::bar(format, transcripts.len() as u64, "Importing transcripts");
+    for path in &transcripts {
+        pb.inc(1);
+        let session = match transcript::session_info(path) {
+            Ok(session) => session,
+            Err(e) => {
+                pb.suspend(|| println!("{} {}: {}", presenter::warn(), path.display(), e));
+                continue;
+            }
+        };
+        if since.is_some_and(|since| session.ended.is_none_or(|ended| ended.date_naive() < since)) {
+            continue;
+        }
+        if captured.contains(&session.session_id) {
+            existing += 1;
+            continue;
+        }
+        let Some(entry) = handler.transcript_entry(path, &session) else {
+            empty += 1;
+            continue;
+        };
+        if !dry_run {
+            handler
+                .import(&entry)
+                .with_context(|| format!("Failed to import {}", path.display()))?;
+        }
+        captured.insert(session.session_id.clone());
+        imported.push(Imported {
+            id: entry.id,
+            category: entry.category,
+            title: entry.title,
+            created_at: entry.created_at.format("%Y-%m-%d %H:%M").to_string(),
+            session_id: session.session_id,
+        });
+    }
+    pb.finish_and_clear();
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&imported)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&imported)?),
+        OutputFormat::Text => {
+            if !imported.is_empty() {
+                let mut table = Table::new(&["DATE", "CATEGORY", "TITLE"]);
+                for entry in &imported {
+                    table.add_row(vec![
+                        entry.created_at.dimmed(),
+                        entry.category.cyan(),
+                        presenter::truncate(&entry.title, 60).normal(),
+                    ]);
+                }
+                table.print();
+                println!();
+            }
+            println!(
+                "{} {} {} of {} transcripts ({} already in history, {} without a reply)",
+                presenter::ok(),
+                if dry_run { "Would import" } else { "Imported" },
+                imported.len(),
+                transcripts.len(),
+                existing,
+                empty
+            );
+        }
+    }
+    Ok(())
+}
+
+fn print_entry_summary(entry: &HistoryEntry) {
+    let date = entry.created_at.format("%Y-%m-%d %H:%M").to_string();
+    println!(
+        "  {} {} {} {}",
+        entry.id[..8.min(entry.id.len())].dimmed(),
+        entry.category.cyan(),
+        date.dimmed(),
+        entry.title.bold()
+    );
+    if !entry.tags.is_empty() {
+        println!("    tags: {}", entry.tags.join(", ").dimmed());
+    }
+}
+
+/// Show a specific history entry
+fn show_entry(id: &str, config: &Config) -> Result<()> {
+    let store = HistoryStore::new(Config::expand_path(&config.paths.history))
+        .with_index(config.history.index.enabled)
+        .with_encryption(Encryption::from_config(&config.history.encryption));
+    let path = store.find(id)?.ok_or_else(|| CliError::not_found("Entry", id))?;
+    let content = store.read(&path)?;
+    println!("{}", content);
+
+    let entry = HistoryEntry::from_markdown(&content, &path)?;
+    let links = links::links(&store, &entry)?;
+    if !links.related.is_empty() {
+        println!("{}", "Related:".bold());
+        for related in &links.related {
+            match &related.entry {
+                Some(entry) => print_entry_summary(entry),
+                None => println!("  {} {}", related.id.dimmed(), "(missing)".yellow()),
+            }
+        }
+    }
+    if !links.backlinks.is_empty() {
+        println!("{}", "Backlinks:".bold());
+        for entry in &links.backlinks {
+            print_entry_summary(entry);
+        }
+    }
+    Ok(())
+}
+
+/// The store and the file of the entry `id` refers to
+fn find_entry(id: &str, config: &Config) -> Result<(HistoryStore, PathBuf)> {
+    let store = HistoryStore::new(Config::expand_path(&config.paths.history))
+        .with_categories(Categories::from_config(&config.history))
+        .with_index(config.history.index.enabled)
+        .with_encryption(Encryption::from_config(&config.history.encryption));
+    let path = store.find(id)?.ok_or_else(|| CliError::not_found("Entry", id))?;
+    Ok((store, path))
+}
+
+fn edit_entry(id: &str, config: &Config) -> Result<()> {
+    let (store, path) = find_entry(id, config)?;
+    let original = store.read(&path)?;
+
+    // Edit a decrypted copy; the entry is only replaced once the edit parses
+    let draft = tempfile::Builder::new()
+        .prefix("pais-entry-")
+        .suffix(".md")
+        .tempfile()
+        .context("Failed to create a file to edit")?;
+    fs::write(draft.path(), &original).context("Failed to write the entry to edit")?;
+    crate::commands::skill::open_in_editor(draft.path())?;
+    let edited = fs::read_to_string(draft.path()).context("Failed to read the edited entry")?;
+
+    if edited == original {
+        println!("{} No changes", presenter::info());
+        return Ok(());
+    }
+    let target = manage::replace(&store, &path, &edited)?;
+    if target != path {
+        println!("{} Saved and moved to {}", presenter::ok(), target.display());
+    } else {
+        println!("{} Saved {}", presenter::ok(), target.display());
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct TagOutput {
+    id: String,
+    tags: Vec<String>,
+}
+
+fn tag_entry(id: &str, changes: &[String], format: OutputFormat, config: &Config) -> Result<()> {
+    let changes = TagChanges::parse(changes)?;
+    let (store, path) = find_entry(id, config)?;
+    let entry = manage::retag(&store, &path, &changes)?;
+
+    let output = TagOutput {
+        id: entry.id,
+        tags: entry.tags,
+    };
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&output)?),
+        OutputFormat::Text if output.tags.is_empty() => println!("{} {} has no tags", presenter::ok(), output.id),
+        OutputFormat::Text => println!("{} {}: {}", presenter::ok(), output.id, output.tags.join(", ").cyan()),
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct LinkOutput {
+    id: String,
+    related: Vec<String>,
+}
+
+fn link_entry(from: &str, to: &str, remove: bool, format: OutputFormat, config: &Config) -> Result<()> {
+    let (store, path) = find_entry(from, config)?;
+    let target = match store.get(to)? {
+        Some(target) => target.id,
+        // A dangling link can still be removed
+        None if remove => to.to_string(),
+        None => return Err(CliError::not_found("Entry", to).into()),
+    };
+    let before = HistoryEntry::from_markdown(&store.read(&path)?, &path)?;
+    let entry = links::link(&store, &path, &target, remove)?;
+    let changed = entry.related != before.related;
+
+    let output = LinkOutput {
+        id: entry.id,
+        related: entry.related,
+    };
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&output)?),
+        OutputFormat::Text if !changed && remove => {
+            println!("{} {} doesn't link to {}", presenter::info(), output.id, target)
+        }
+        OutputFormat::Text if !changed => println!("{} {} already links to {}", presenter::info(), output.id, target),
+        OutputFormat::Text if remove => println!("{} Unlinked {} from {}", presenter::ok(), output.id, target.cyan()),
+        OutputFormat::Text => println!("{} Linked {} to {}", presenter::ok(), output.id, target.cyan()),
+    }
+    Ok(())
+}
+
+fn move_entry(id: &str, category: &str, config: &Config) -> Result<()> {
+    Categories::from_config(&config.history).check(category)?;
+    let (store, path) = find_entry(id, config)?;
+    let entry = HistoryEntry::from_markdown(&store.read(&path)?, &path)?;
+    if entry.category == category {
+        println!("{} {} is already in {}", presenter::info(), entry.id, category.cyan());
+        return Ok(());
+    }
+
+    let target = manage::move_to(&store, &path, category)?;
+    println!(
+        "{} Moved {} from {} to {}",
+        presenter::ok(),
+        entry.id,
+        entry.category,
+        category.cyan()
+    );
+    log::debug!("Moved {} to {}", path.display(), target.display());
+    Ok(())
+}
+
+fn delete_entry(id: &str, force: bool, config: &Config) -> Result<()> {
+    let (store, path) = find_entry(id, config)?;
+    let entry = HistoryEntry::from_markdown(&store.read(&path)?, &path)?;
+
+    if !force {
+        print!("Delete '{}' ({}/{})? [y/N] ", entry.title, entry.category, entry.id);
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("Cancelled.");
+            return Ok(());
+        }
+    }
+
+    manage::delete(&store, &path, &entry.id)?;
+    println!("{} Deleted {}", presenter::ok(), entry.id);
+    Ok(())
+}
+
+/// Export sessions from raw events as iCalendar (or JSON/YAML, or a text table)
+///
+/// Unlike other commands this doesn't switch to JSON when piped: `> work.ics`
+/// and cron runs get iCalendar unless another format was asked for.
+fn calendar(days: usize, output: Option<&Path>, requested: Option<FormatArg>, config: &Config) -> Result<()> {
+    let history_dir = Config::expand_path(&config.paths.history);
+    // Events the batched file sink still holds belong in the logs read below
+    flush_buffered(&history_dir);
+    let capture = EventCapture::new(history_dir, true);
+    let today = clock::now_local().date_naive();
+
+    let mut events = Vec::new();
+    for i in (0..days).rev() {
+        let date = today - chrono::Duration::days(i as i64);
+        events.extend(capture.read_events(&date.format("%Y-%m-%d").to_string())?);
+    }
+    let spans = calendar::sessions(events);
+
+    let rendered = match requested {
+        Some(FormatArg::Json) => serde_json::to_string_pretty(&spans)?,
+        Some(FormatArg::Yaml) => serde_yaml::to_string(&spans)?,
+        Some(FormatArg::Text) => session_table(&spans),
+        Some(FormatArg::Ics) | None => calendar::to_ics(&spans, clock::now()),
+    };
+
+    match output {
+        Some(path) => {
+            fs::write(path, &rendered).with_context(|| format!("Failed to write {}", path.display()))?;
+            eprintln!(
+                "{} Wrote {} session(s) to {}",
+                presenter::ok(),
+                spans.len(),
+                path.display()
+            );
+        }
+        None => print!("{}", rendered),
+    }
+    Ok(())
+}
+
+/// Sessions as a table, one line per session in local time
+fn session_table(spans: &[calendar::SessionSpan]) -> String {
+    if spans.is_empty() {
+        return format!("  {}\n", "(no sessions)".dimmed());
+    }
+    let mut table = Table::new(&["START", "MINUTES", "EVENTS", "PROJECT"]);
+    for span in spans {
+        table.add_row(vec![
+            span.start
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+                .normal(),
+            span.duration_minutes().to_string().normal(),
+            span.events.to_string().normal(),
+            span.project().unwrap_or("-").cyan(),
+        ]);
+    }
+    let mut rendered = table.render(presenter::terminal_width()).join("\n");
+    rendered.push('\n');
+    rendered
+}
+
+/// Export new and changed entries to a Notion database
+fn export_notion(
+    database: Option<String>,
+    category: Option<&str>,
+    dry_run: bool,
+    format: OutputFormat,
+    config: &Config,
+) -> Result<()> {
+    let notion_config = &config.publish.notion;
+    let database = database.or_else(|| notion_config.database.clone()).ok_or_else(|| {
+        CliError::new(ErrorCode::InvalidInput, "No Notion database given")
+            .with_hint("Pass --database <id> or set publish.notion.database")
+    })?;
+
+    let store = HistoryStore::new(Config::expand_path(&config.paths.history))
+        .with_index(config.history.index.enabled)
+        .with_encryption(Encryption::from_config(&config.history.encryption));
+    let mut entries = store.entries(category, None)?.collect::<Result<Vec<_>>>()?;
+    entries.reverse();
+
+    let state_path = NotionState::path(&database);
+    let mut state = NotionState::load(&state_path)?;
+    let plan = notion::plan(&entries, &state);
+
+    let creates = plan.iter().filter(|(_, a)| *a == ExportAction::Create).count();
+    let updates = plan
+        .iter()
+        .filter(|(_, a)| matches!(a, ExportAction::Update(_)))
+        .count();
+    let unchanged = plan.len() - creates - updates;
+
+    if dry_run {
+        for (entry, action) in &plan {
+            match action {
+                ExportAction::Create => println!("  {} {} ({})", "+".green(), entry.title, entry.id),
+                ExportAction::Update(_) => println!("  {} {} ({})", "~".yellow(), entry.title, entry.id),
+                ExportAction::Unchanged => {}
+            }
+        }
+        println!(
+            "{} would create {}, update {}, skip {} unchanged",
+            "Dry run -".yellow(),
+            creates,
+            updates,
+            unchanged
+        );
+        return Ok(());
+    }
+
+    net::ensure_online(&config.network, "Exporting to Notion")?;
+    let client = NotionClient::new(SecretStore::load().require(&notion_config.token_secret)?);
+    let progress = progress::bar(format, (creates + updates) as u64, "Exporting to Notion".to_string());
+
+    let mut failed = 0;
+    for (entry, action) in plan {
+        let result = match action {
+            ExportAction::Unchanged => continue,
+            ExportAction::Create => client.create_page(&database, entry, notion_config),
+            ExportAction::Update(page_id) => client.update_page(&page_id, entry, notion_config).map(|_| page_id),
+        };
+        progress.inc(1);
+
+        match result {
+            Ok(page_id) => {
+                state.pages.insert(
+                    entry.id.clone(),
+                    ExportedPage {
+                        page_id,
+                        hash: notion::entry_hash(entry),
+                    },
+                );
+                // Save as we go so an interrupted export resumes where it stopped
+                state.save(&state_path)?;
+            }
+            Err(e) => {
+                failed += 1;
+                progress.suspend(|| eprintln!("{} {}: {:#}", presenter::fail(), entry.title, e));
+            }
+        }
+    }
+    progress.finish_and_clear();
+
+    println!(
+        "{} Notion export: {} created, {} updated, {} unchanged{}",
+        presenter::ok(),
+        creates,
+        updates,
+        unchanged,
+        if failed > 0 {
+            format!(", {} failed", failed).red().to_string()
+        } else {
+            String::new()
+        }
+    );
+    if failed > 0 {
+        eyre::bail!("{} entr(y/ies) failed to export", failed);
+    }
+    Ok(())
+}
+
+/// Push an entry to Slack, Linear or Jira
+fn publish(id: &str, target: PublishTarget, dry_run: bool, config: &Config) -> Result<()> {
+    let store = HistoryStore::new(Config::expand_path(&config.paths.history))
+        .with_index(config.history.index.enabled)
+        .with_encryption(Encryption::from_config(&config.history.encryption));
+    let entry = store.get(id)?.ok_or_else(|| CliError::not_found("Entry", id))?;
+    let body = publish::payload(target, &entry, &config.publish)?;
+
+    if dry_run {
+        println!("{} would send to {}:", "Dry run -".yellow(), target);
+        println!("{}", serde_json::to_string_pretty(&body)?);
+        return Ok(());
+    }
+
+    net::ensure_online(&config.network, &format!("Publishing to {}", target))?;
+    let link = publish::send(target, &body, &config.publish, &SecretStore::load())?;
+    println!("{} Published '{}' to {}", presenter::ok(), entry.title, target);
+    if let Some(link) = link {
+        println!("  {}", link.cyan());
+    }
+    Ok(())
+}
+
+/// Secrets found in one entry
+#[derive(Serialize)]
+struct EntrySecrets {
+    id: String,
+    path: String,
+    findings: Vec<Finding>,
+}
+
+/// Scan stored entries with the configured redaction rules, optionally redacting them
+fn scan_secrets(category: Option<&str>, fix: bool, format: OutputFormat, config: &Config) -> Result<()> {
+    // Audits run even with redaction disabled; a bad user pattern is an error here
+    let redactor = Redactor::new(&config.history.redaction).map_err(|e| {
+        CliError::new(ErrorCode::Config, format!("{:#}", e)).with_hint("Fix history.redaction in pais.yaml")
+    })?;
+    let store = HistoryStore::new(Config::expand_path(&config.paths.history)).with_index(config.history.index.enabled);
+
+    let mut scanned = 0;
+    let mut results = Vec::new();
+    for path in store.paths(category, None)? {
+        let path = path?;
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        scanned += 1;
+
+        let findings = redactor.scan(&content);
+        if findings.is_empty() {
+            continue;
+        }
+        if fix {
+            store.write(&path, &redactor.redact(&content).0)?;
+        }
+        results.push(EntrySecrets {
+            id: path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default()
+                .to_string(),
+            path: path.display().to_string(),
+            findings,
+        });
+    }
+    let total: usize = results.iter().map(|r| r.findings.len()).sum();
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&results)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&results)?),
+        OutputFormat::Text => {
+            if results.is_empty() {
+                println!("{} No secrets found in {} entries", presenter::ok(), scanned);
+                return Ok(());
+            }
+
+            let mut table = Table::new(&["ENTRY", "LINE", "RULE", "EXCERPT"]);
+            for entry in &results {
+                for finding in &entry.findings {
+                    table.add_row(vec![
+                        entry.id[..8.min(entry.id.len())].cyan(),
+                        finding.line.to_string().dimmed(),
+                        finding.rule.yellow(),
+                        finding.excerpt.normal(),
+                    ]);
+                }
+            }
+            table.print();
+            println!();
+
+            if fix {
+                println!(
+                    "{} Redacted {} secret(s) in {} of {} entries",
+                    presenter::ok(),
+                    total,
+                    results.len(),
+                    scanned
+                );
+            } else {
+                println!(
+                    "{} {} secret(s) in {} of {} entries; run with {} to redact them",
+                    presenter::warn(),
+                    total,
+                    results.len(),
+                    scanned,
+                    "--fix".cyan()
+                );
+            }
+        }
+    }
+
+    if !fix && !results.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct SyncPreview {
+    initialized: bool,
+    remote: Option<String>,
+    uncommitted: usize,
+    incoming: usize,
+    outgoing: usize,
+}
+
+/// Commit, merge and push the history repository
+fn sync(
+    remote: Option<&str>,
+    prefer: Option<Prefer>,
+    dry_run: bool,
+    format: OutputFormat,
+    config: &Config,
+) -> Result<()> {
+    let history_dir = Config::expand_path(&config.paths.history);
+    let repo = HistoryRepo::new(history_dir.clone(), &config.history.sync.branch);
+    let remote = remote.map(String::from).or_else(|| config.history.sync.remote.clone());
+
+    if dry_run {
+        let initialized = repo.exists();
+        let mut preview = SyncPreview {
+            initialized,
+            remote: remote.or_else(|| repo.remote()),
+            uncommitted: 0,
+            incoming: 0,
+            outgoing: 0,
+        };
+        if initialized {
+            preview.uncommitted = repo.pending()?;
+            let reachable = preview
+                .remote
+                .as_deref()
+                .is_some_and(|remote| !config.network.offline || net::is_local(remote));
+            if reachable && repo.fetch()? {
+                (preview.incoming, preview.outgoing) = repo.divergence()?;
+            }
+        }
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&preview)?),
+            OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&preview)?),
+            OutputFormat::Text => {
+                if !initialized {
+                    println!(
+                        "{} {} is not a git repo yet; it would be initialized",
+                        presenter::info(),
+                        history_dir.display()
+                    );
+                } else {
+                    println!("{} {} uncommitted change(s)", presenter::info(), preview.uncommitted);
+                    println!(
+                        "{} {} commit(s) to pull, {} to push",
+                        presenter::info(),
+                        preview.incoming,
+                        preview.outgoing
+                    );
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    let remote = remote.or_else(|| repo.remote()).ok_or_else(|| {
+        CliError::new(ErrorCode::Config, "No remote to sync history with")
+            .with_hint("Pass --remote <git-url> or set history.sync.remote in pais.yaml")
+    })?;
+    if repo.init()? {
+        log::info!("Initialized history repository in {}", history_dir.display());
+    }
+    repo.set_remote(&remote)?;
+
+    let message = format!("pais history sync {}", Local::now().format("%Y-%m-%d %H:%M"));
+    let mut report = SyncReport {
+        committed: repo.commit(&message)?,
+        ..Default::default()
+    };
+
+    // Offline, commit only; the next online sync pulls and pushes
+    if !config.network.offline || net::is_local(&remote) {
+        let pb = progress::spinner(format, format!("Syncing with {}...", remote));
+        let synced = pull_and_push(&repo, prefer, &mut report);
+        pb.finish_and_clear();
+        synced?;
+    }
+
+    // Pulled entries aren't in this machine's index yet
+    if report.pulled > 0 && config.history.index.enabled {
+        HistoryIndex::new(&history_dir).rebuild()?;
+    }
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&report)?),
+        OutputFormat::Text => {
+            println!("{} Committed {} local change(s)", presenter::ok(), report.committed);
+            if !report.pushed {
+                println!(
+                    "{} Offline: not pulling from or pushing to {}; sync again when online",
+                    presenter::info(),
+                    remote
+                );
+                return Ok(());
+            }
+            println!("{} Pulled {} file(s) from {}", presenter::ok(), report.pulled, remote);
+            for renamed in &report.renamed {
+                println!(
+                    "{} Same ID on both machines: stored the incoming {} as {}",
+                    presenter::warn(),
+                    renamed.from,
+                    renamed.to.cyan()
+                );
+            }
+            for path in &report.kept {
+                println!(
+                    "{} Kept {} (edited here or there, deleted on the other side)",
+                    presenter::warn(),
+                    path
+                );
+            }
+            for path in &report.resolved {
+                println!("{} Took the {} side of {}", presenter::warn(), side(prefer), path);
+            }
+            println!("{} Pushed to {}", presenter::ok(), remote);
+        }
+    }
+    Ok(())
+}
+
+fn pull_and_push(repo: &HistoryRepo, prefer: Option<Prefer>, report: &mut SyncReport) -> Result<()> {
+    if repo.fetch()? {
+        repo.merge(prefer, report)?;
+    }
+    repo.push()?;
+    report.pushed = true;
+    Ok(())
+}
+
+/// Merge another history directory into this one
+fn merge(dir: &Path, prefer: Option<Prefer>, dry_run: bool, format: OutputFormat, config: &Config) -> Result<()> {
+    let history_dir = Config::expand_path(&config.paths.history);
+    if !dir.is_dir() {
+        return Err(CliError::not_found("History directory", &dir.display().to_string()).into());
+    }
+    if dir.canonicalize()? == history_dir.canonicalize().unwrap_or_else(|_| history_dir.clone()) {
+        return Err(CliError::new(
+            ErrorCode::InvalidInput,
+            "Cannot merge the history directory into itself",
+        )
+        .into());
+    }
+    let store = HistoryStore::new(history_dir).with_index(config.history.index.enabled);
+
+    let pb = progress::spinner(format, format!("Merging {}...", dir.display()));
+    let report = merge::merge(&store, dir, prefer, dry_run);
+    pb.finish_and_clear();
+    let report = report?;
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&report)?),
+        OutputFormat::Text => {
+            let verb = if dry_run { "Would add" } else { "Added" };
+            println!("{} {} {} entry(ies)", presenter::ok(), verb, report.added.len());
+            println!(
+                "{} {} already here, {} duplicate(s) under another ID",
+                presenter::info(),
+                report.identical,
+                report.duplicates.len()
+            );
+            for renamed in &report.renamed {
+                println!(
+                    "{} Same ID on both machines: {} the incoming {} as {}",
+                    presenter::warn(),
+                    if dry_run { "would store" } else { "stored" },
+                    renamed.from,
+                    renamed.to.cyan()
+                );
+            }
+            for id in &report.resolved {
+                println!("{} Took the {} side of {}", presenter::warn(), side(prefer), id);
+            }
+            for id in &report.conflicts {
+                println!(
+                    "{} {} differs on the two sides; kept this one",
+                    presenter::fail(),
+                    id.cyan()
+                );
+            }
+            for path in &report.unreadable {
+                println!("{} Skipped unreadable {}", presenter::warn(), path.display());
+            }
+            if report.log_lines > 0 {
+                let verb = if dry_run { "Would append" } else { "Appended" };
+                println!(
+                    "{} {} {} raw event and security log line(s)",
+                    presenter::ok(),
+                    verb,
+                    report.log_lines
+                );
+            }
+        }
+    }
+
+    if !report.conflicts.is_empty() {
+        return Err(CliError::new(
+            ErrorCode::Conflict,
+            format!(
+                "{} entry(ies) differ between the two directories",
+                report.conflicts.len()
+            ),
+        )
+        .with_hint("Rerun with --prefer local or --prefer remote to pick a side")
+        .into());
+    }
+    Ok(())
+}
+
+fn side(prefer: Option<Prefer>) -> &'static str {
+    match prefer {
+        Some(Prefer::Remote) => "remote",
+        _ => "local",
+    }
+}
+
+#[derive(Serialize)]
+struct EncryptSummary {
+    entries: usize,
+    changed: usize,
+    decrypted: bool,
+}
+
+/// Encrypt (or decrypt) the bodies of stored entries in place
+fn encrypt(category: Option<&str>, decrypt: bool, format: OutputFormat, config: &Config) -> Result<()> {
+    let encryption = Encryption::from_config(&config.history.encryption);
+    if !decrypt && !encryption.enabled() {
+        return Err(CliError::new(ErrorCode::Config, "History encryption is not enabled")
+            .with_hint("Set history.encryption.enabled: true in pais.yaml")
+            .into());
+    }
+    let store = HistoryStore::new(Config::expand_path(&config.paths.history))
+        .with_index(config.history.index.enabled)
+        .with_encryption(encryption);
+
+    let pb = progress::spinner(format, "Rewriting entries...");
+    let mut summary = EncryptSummary {
+        entries: 0,
+        changed: 0,
+        decrypted: decrypt,
+    };
+    for path in store.paths(category, None)? {
+        let path = path?;
+        summary.entries += 1;
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let sealed = encrypt::is_sealed(&content);
+        let rewritten = match (decrypt, sealed) {
+            (true, true) => store.read(&path)?,
+            (false, false) => store.seal(&content)?,
+            _ => continue,
+        };
+        if rewritten != content {
+            store.write(&path, &rewritten)?;
+            summary.changed += 1;
+        }
+        pb.set_message(format!("{} entries rewritten", summary.changed));
+    }
+    pb.finish_and_clear();
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&summary)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&summary)?),
+        OutputFormat::Text => println!(
+            "{} {} {} of {} entries",
+            presenter::ok(),
+            if decrypt { "Decrypted" } else { "Encrypted" },
+            summary.changed,
+            summary.entries
+        ),
+    }
+    Ok(())
+}
+
+/// Activity report over the last `days` days
+fn stats(days: usize, top: usize, format: OutputFormat, config: &Config) -> Result<()> {
+    let history_dir = Config::expand_path(&config.paths.history);
+    flush_buffered(&history_dir);
+    let store = HistoryStore::new(history_dir.clone());
+    let capture = EventCapture::new(history_dir, true);
+    let report = stats::report(&store, &capture, days, clock::now_local().date_naive(), top)?;
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&report)?),
+        OutputFormat::Text => {
+            println!(
+                "{} Activity since {} ({} days)",
+                presenter::symbol("📊", "*").blue(),
+                report.since,
+                report.days
+            );
+            println!();
+
+            if report.categories.is_empty() {
+                println!("  {}", "(no entries)".dimmed());
+            } else {
+                let activity = match report.bucket_days {
+                    1 => "ACTIVITY (per day)".to_string(),
+                    n => format!("ACTIVITY (per {} days)", n),
+                };
+                let mut table = Table::new(&["CATEGORY", "ENTRIES", &activity]);
+                for category in &report.categories {
+                    table.add_row(vec![
+                        category.category.cyan(),
+                        category.total.to_string().normal(),
+                        stats::sparkline(&category.buckets, presenter::ascii()).green(),
+                    ]);
+                }
+                table.print();
+            }
+
+            if !report.top_tags.is_empty() {
+                println!();
+                let mut table = Table::new(&["TAG", "ENTRIES"]);
+                for tag in &report.top_tags {
+                    table.add_row(vec![tag.tag.cyan(), tag.count.to_string().normal()]);
+                }
+                table.print();
+            }
+
+            if !report.projects.is_empty() {
+                println!();
+                let mut table = Table::new(&["PROJECT", "SESSIONS", "TIME", "ENTRIES"]);
+                for project in &report.projects {
+                    table.add_row(vec![
+                        project.project.cyan(),
+                        project.sessions.to_string().normal(),
+                        if project.sessions == 0 {
+                            "-".dimmed()
+                        } else {
+                            format_ms(project.minutes * 60_000).normal()
+                        },
+                        project.entries.to_string().normal(),
+                    ]);
+                }
+                table.print();
+            }
+
+            println!();
+            if report.sessions.count == 0 {
+                println!("  {}", "(no sessions captured)".dimmed());
+            } else {
+                println!(
+                    "  {} session(s), {} on average, longest {}",
+                    report.sessions.count.to_string().bold(),
+                    format_ms((report.sessions.average_minutes * 60_000.0) as i64).bold(),
+                    format_ms(report.sessions.longest_minutes * 60_000)
+                );
+            }
+
+            let mut types: Vec<_> = report.events.by_type.iter().collect();
+            types.sort_by(|a, b| b.1.cmp(a.1));
+            let types: Vec<String> = types.iter().map(|(t, n)| format!("{} {}", t, n)).collect();
+            println!(
+                "  {} events{}",
+                report.events.total.to_string().bold(),
+                if types.is_empty() {
+                    String::new()
+                } else {
+                    format!(": {}", types.join(", ")).dimmed().to_string()
+                }
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// List available raw event dates
+/// Tool timing: one session's calls, or totals per tool
+fn tools(
+    session: Option<&str>,
+    days: u32,
+    sort: ToolSort,
+    limit: usize,
+    format: OutputFormat,
+    config: &Config,
+) -> Result<()> {
+    let store = ToolStore::new(&Config::expand_path(&config.paths.history));
+    if !store.exists() {
+        println!("{} No tool calls recorded yet", presenter::info());
+        if !config.history.tools.enabled {
+            println!("  Set {} to time them", "history.tools.enabled: true".cyan());
+        }
+        return Ok(());
+    }
+
+    if let Some(session) = session {
+        let uses = store.session(session)?;
+        if uses.is_empty() {
+            return Err(CliError::not_found("Session", session).into());
+        }
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&uses)?),
+            OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&uses)?),
+            OutputFormat::Text => {
+                let mut table = Table::new(&["TIME", "TOOL", "DETAIL", "DURATION", "RESULT"]);
+                for call in &uses {
+                    let result = match call.success {
+                        Some(true) => "ok".green(),
+                        Some(false) => presenter::truncate(call.error.as_deref().unwrap_or("failed"), 50).red(),
+                        None => "no result".dimmed(),
+                    };
+                    table.add_row(vec![
+                        call.started_at
+                            .or(call.ended_at)
+                            .map(|t| t.with_timezone(&Local).format("%H:%M:%S").to_string())
+                            .unwrap_or_default()
+                            .dimmed(),
+                        call.tool.cyan(),
+                        presenter::truncate(call.detail.as_deref().unwrap_or(""), 40).normal(),
+                        call.duration_ms().map(format_ms).unwrap_or_default().normal(),
+                        result,
+                    ]);
+                }
+                table.print();
+                let failed = uses.iter().filter(|u| u.success == Some(false)).count();
+                println!("\n  {} calls, {} failed", uses.len(), failed);
+            }
+        }
+        return Ok(());
+    }
+
+    let mut stats = store.stats(Utc::now() - chrono::Duration::days(days.into()))?;
+    sort.sort(&mut stats);
+    stats.truncate(limit);
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&stats)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&stats)?),
+        OutputFormat::Text => {
+            if stats.is_empty() {
+                println!("{} No tool calls in the last {} days", presenter::info(), days);
+                return Ok(());
+            }
+            let mut table = Table::new(&["TOOL", "CALLS", "FAILED", "AVG", "P95", "MAX"]);
+            for tool in &stats {
+                let failed = format!("{} ({:.0}%)", tool.failures, tool.failure_rate() * 100.0);
+                table.add_row(vec![
+                    tool.tool.cyan(),
+                    tool.calls.to_string().normal(),
+                    if tool.failures > 0 { failed.red() } else { failed.dimmed() },
+                    format_ms(tool.avg_ms as i64).normal(),
+                    format_ms(tool.p95_ms as i64).normal(),
+                    format_ms(tool.max_ms as i64).dimmed(),
+                ]);
+            }
+            table.print();
+        }
+    }
+    Ok(())
+}
+
+fn format_ms(ms: i64) -> String {
+    if ms < 1000 {
+        format!("{}ms", ms)
+    } else if ms < 60_000 {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    } else {
+        format!("{}m{:02}s", ms / 60_000, ms % 60_000 / 1000)
+    }
+}
+
 fn list_events(limit: usize, config: &Config) -> Result<()> {
     let history_dir = Config::expand_path(&config.paths.history);
     let capture = EventCapture::new(history_dir, true);
@@ -1,16 +1,24 @@
+use chrono::Local;
 use colored::*;
 use eyre::{Context, Result};
 use std::io::{self, Read};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
 
+use crate::claude::compat;
 use crate::cli::HookAction;
 use crate::config::Config;
-use crate::hook::history::HistoryHandler;
-use crate::hook::research::ResearchPathValidator;
-use crate::hook::security::SecurityValidator;
-use crate::hook::ui::UiHandler;
-use crate::hook::{HookEvent, HookHandler, HookResult};
+use crate::hook::registry::{HandlerContext, Registry};
+use crate::hook::{HookEvent, HookResult, context_output};
 use crate::observability::EventEmitter;
-use crate::plugin::PluginManager;
+use crate::perf;
+use crate::plugin::signing::Verifier;
+use crate::plugin::{PluginManager, PluginState};
+use crate::policy::PolicyEngine;
+use crate::presenter;
+use crate::prompt;
+use crate::trust;
 
 pub fn run(action: HookAction, config: &Config) -> Result<()> {
     match action {
@@ -39,19 +47,20 @@ fn dispatch(event: &str, payload: Option<&str>, config: &Config) -> Result<()> {
         }
     };
 
-    // Parse the payload
-    let payload: serde_json::Value = serde_json::from_str(&payload_str).context("Failed to parse payload JSON")?;
+    // Parse the payload, renaming keys older Claude Code releases used
+    let mut payload: serde_json::Value = serde_json::from_str(&payload_str).context("Failed to parse payload JSON")?;
+    compat::normalize_payload(&mut payload);
 
     // Parse event type
     let hook_event = match HookEvent::from_str(event) {
         Some(e) => e,
         None => {
             log::warn!("Unknown hook event: {}", event);
-            std::process::exit(0); // Unknown events are allowed
+            perf::exit(0); // Unknown events are allowed
         }
     };
 
-    log::info!("Dispatching hook event: {:?}", hook_event);
+    log::info!(event:? = hook_event; "Dispatching hook event: {:?}", hook_event);
     log::debug!("Payload: {}", payload);
 
     // Emit event to observability sinks (file, stdout, http)
@@ -59,27 +68,47 @@ fn dispatch(event: &str, payload: Option<&str>, config: &Config) -> Result<()> {
     let emitter = EventEmitter::new(config.observability.clone(), history_path.clone());
     emitter.emit(hook_event, &payload);
 
-    // Build handlers list
-    let security_enabled = config.hooks.security_enabled;
-    let history_enabled = config.hooks.history_enabled;
-    let ui_enabled = config.hooks.ui_enabled;
-    let research_enabled = config.hooks.research_enabled;
+    // Untrusted workspaces get no project overrides and stricter security tiers
+    let cwd = payload.get("cwd").and_then(|v| v.as_str()).map(Path::new);
+    let trusted = match cwd {
+        Some(cwd) => {
+            let (trust, new) = trust::check_workspace(&config.security.trust, cwd);
+            if new {
+                log::warn!("New untrusted workspace: {}", cwd.display());
+                eprintln!(
+                    "pais: {} is untrusted; project policy is ignored and security is stricter until `pais trust {}`",
+                    cwd.display(),
+                    cwd.display()
+                );
+            }
+            trust.is_trusted()
+        }
+        None => true,
+    };
+
+    // Policy rules: project overrides (from the payload's cwd) before global rules
+    let mut policy = PolicyEngine::new(&config.policy.rules).with_state_dir(&history_path);
+    if let Some(cwd) = cwd {
+        if trusted {
+            policy = policy.with_project_overrides(cwd);
+        } else {
+            log::debug!("Ignoring project policy in untrusted workspace {}", cwd.display());
+        }
+    }
 
+    let ctx = HandlerContext {
+        config,
+        history_path,
+        policy: Arc::new(policy),
+        trusted,
+    };
+    let handlers = Registry::builtin().build(hook_event, &ctx);
     log::debug!(
-        "Handler config: security={}, history={}, ui={}, research={}",
-        security_enabled,
-        history_enabled,
-        ui_enabled,
-        research_enabled
+        "Handlers for {}: {}",
+        hook_event,
+        handlers.iter().map(|h| h.name()).collect::<Vec<_>>().join(", ")
     );
 
-    let handlers: Vec<Box<dyn HookHandler>> = vec![
-        Box::new(SecurityValidator::new(security_enabled).with_log_path(history_path.clone())),
-        Box::new(ResearchPathValidator::new(research_enabled)),
-        Box::new(HistoryHandler::new(history_enabled, history_path)),
-        Box::new(UiHandler::new(ui_enabled)),
-    ];
-
     // Run all built-in handlers for this event
     for handler in &handlers {
         if handler.handles(hook_event) {
@@ -88,10 +117,11 @@ fn dispatch(event: &str, payload: Option<&str>, config: &Config) -> Result<()> {
 
             match &result {
                 HookResult::Block { message } => {
-                    log::warn!("Handler {} blocked: {}", handler.name(), message);
+                    log::warn!(event:? = hook_event, handler = handler.name(); "Handler {} blocked: {}", handler.name(), message);
+                    prompt::update(|state| state.record_block(Local::now().date_naive()));
                     // Print block message to stderr (Claude Code reads this)
                     eprintln!("{}", message);
-                    std::process::exit(result.exit_code());
+                    perf::exit(result.exit_code());
                 }
                 HookResult::Error { message } => {
                     log::error!("Hook error from {}: {}", handler.name(), message);
@@ -111,19 +141,24 @@ fn dispatch(event: &str, payload: Option<&str>, config: &Config) -> Result<()> {
     let plugins_dir = Config::expand_path(&config.paths.plugins);
     log::debug!("Checking plugin hooks in: {}", plugins_dir.display());
 
-    let mut plugin_manager = PluginManager::new(plugins_dir);
+    let mut plugin_manager = PluginManager::new(plugins_dir)
+        .with_verifier(Verifier::from_config(&config.plugins.signing))
+        .with_hook_timeout(Duration::from_secs(config.plugins.hook_timeout_secs))
+        .with_settings(config.plugins.settings.clone());
 
     if plugin_manager.discover().is_ok() {
         log::debug!("Found {} plugins with hooks", plugin_manager.plugins.len());
 
-        let plugin_results = plugin_manager.execute_hooks(hook_event, &payload);
+        let plugin_output = plugin_manager.execute_hooks(hook_event, &payload);
+        emitter.emit_plugin_runs(hook_event, &payload, &plugin_output.runs);
 
-        for result in plugin_results {
+        for result in plugin_output.results {
             match &result {
                 HookResult::Block { message } => {
                     log::warn!("Plugin hook blocked: {}", message);
+                    prompt::update(|state| state.record_block(Local::now().date_naive()));
                     eprintln!("{}", message);
-                    std::process::exit(result.exit_code());
+                    perf::exit(result.exit_code());
                 }
                 HookResult::Error { message } => {
                     log::error!("Plugin hook error: {}", message);
@@ -133,21 +168,20 @@ fn dispatch(event: &str, payload: Option<&str>, config: &Config) -> Result<()> {
                 }
             }
         }
+
+        if let Some(output) = context_output(hook_event, &plugin_output.context) {
+            log::info!("Injecting context from {} plugin hook(s)", plugin_output.context.len());
+            println!("{}", output);
+        } else if !plugin_output.context.is_empty() {
+            log::warn!("{} hooks can't add context; ignoring inject_context", hook_event);
+        }
     } else {
         log::debug!("No plugins discovered");
     }
 
     log::debug!("Hook dispatch complete, all handlers passed");
     // All handlers passed
-    std::process::exit(0);
-}
-
-/// Information about a built-in hook handler
-struct HandlerInfo {
-    name: &'static str,
-    description: &'static str,
-    events: &'static [&'static str],
-    enabled: bool,
+    perf::exit(0);
 }
 
 fn list(event_filter: Option<&str>, config: &Config) -> Result<()> {
@@ -161,48 +195,18 @@ fn list(event_filter: Option<&str>, config: &Config) -> Result<()> {
     {
         println!(
             "  {} Unknown event '{}', showing all handlers",
-            "⚠".yellow(),
+            presenter::warn(),
             filter_str
         );
         println!();
     }
 
     // Built-in handlers
-    let handlers = vec![
-        HandlerInfo {
-            name: "security",
-            description: "Blocks dangerous commands before execution",
-            events: &["PreToolUse"],
-            enabled: config.hooks.security_enabled,
-        },
-        HandlerInfo {
-            name: "history",
-            description: "Captures session lifecycle events",
-            events: &["SessionStart", "Stop", "SubagentStop", "SessionEnd"],
-            enabled: config.hooks.history_enabled,
-        },
-        HandlerInfo {
-            name: "ui",
-            description: "Updates terminal tab title",
-            events: &["UserPromptSubmit"],
-            enabled: config.hooks.ui_enabled,
-        },
-        HandlerInfo {
-            name: "research",
-            description: "Validates research directory path structure",
-            events: &["PreToolUse"],
-            enabled: config.hooks.research_enabled,
-        },
-    ];
-
-    // Filter handlers if event specified
-    let filtered_handlers: Vec<_> = handlers
+    let registry = Registry::builtin();
+    let filtered_handlers: Vec<_> = registry
+        .resolve(config)
         .into_iter()
-        .filter(|h| {
-            filter_event
-                .map(|e| h.events.contains(&e.to_string().as_str()))
-                .unwrap_or(true)
-        })
+        .filter(|h| filter_event.is_none_or(|e| h.registration.events.contains(&e)))
         .collect();
 
     // Print built-in handlers
@@ -216,9 +220,18 @@ fn list(event_filter: Option<&str>, config: &Config) -> Result<()> {
             let status = if handler.enabled { "●".green() } else { "○".bright_black() };
             let state = if handler.enabled { "enabled".green() } else { "disabled".bright_black() };
 
-            println!("    {} {} ({})", status, handler.name.bold(), state);
-            println!("      {}", handler.description.bright_black());
-            println!("      Events: {}", handler.events.join(", ").cyan());
+            let registration = handler.registration;
+            let events: Vec<String> = registration.events.iter().map(|e| e.to_string()).collect();
+
+            println!(
+                "    {} {} ({}, priority {})",
+                status,
+                registration.name.bold(),
+                state,
+                handler.priority
+            );
+            println!("      {}", registration.description.bright_black());
+            println!("      Events: {}", events.join(", ").cyan());
             println!();
         }
     }
@@ -245,11 +258,13 @@ fn list(event_filter: Option<&str>, config: &Config) -> Result<()> {
             println!("    (no plugins with hooks)");
         } else {
             for plugin in plugins_with_hooks {
+                let disabled = plugin.state == PluginState::Disabled;
                 println!(
-                    "    {} {} v{}",
-                    "●".green(),
+                    "    {} {} v{}{}",
+                    if disabled { "○".bright_black() } else { "●".green() },
                     plugin.manifest.plugin.name.bold(),
-                    plugin.manifest.plugin.version
+                    plugin.manifest.plugin.version,
+                    if disabled { " (disabled)".bright_black().to_string() } else { String::new() }
                 );
                 println!("      {}", plugin.manifest.plugin.description.bright_black());
 
@@ -1,5 +1,6 @@
 use colored::*;
 use eyre::{Context, Result};
+use indicatif::ProgressBar;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -7,6 +8,10 @@ use std::process::Command;
 
 use crate::cli::{ImageAction, OutputFormat};
 use crate::config::Config;
+use crate::net;
+use crate::presenter;
+use crate::progress;
+use crate::secrets::SecretStore;
 
 // Note: Config::pais_dir() is a static method that returns the PAIS directory
 
@@ -121,7 +126,7 @@ struct GenerateOptions<'a> {
     thumbnail: bool,
 }
 
-pub fn run(action: ImageAction, config: &Config) -> Result<()> {
+pub fn run(action: ImageAction, format: OutputFormat, config: &Config) -> Result<()> {
     match action {
         ImageAction::Generate {
             prompt,
@@ -141,17 +146,18 @@ pub fn run(action: ImageAction, config: &Config) -> Result<()> {
                 remove_bg,
                 thumbnail,
             };
-            generate(opts, config)
+            generate(opts, format, config)
         }
-        ImageAction::Models { format } => list_models(OutputFormat::resolve(format)),
+        ImageAction::Models => list_models(format),
     }
 }
 
-fn generate(opts: GenerateOptions, config: &Config) -> Result<()> {
+fn generate(opts: GenerateOptions, format: OutputFormat, config: &Config) -> Result<()> {
     let model: Model = opts.model.parse()?;
+    net::ensure_online(&config.network, &format!("Image generation with {}", model.name()))?;
 
-    // Get API key
-    let api_key = get_api_key(&model, config)?;
+    // Environment first, then ~/.config/pais/.env
+    let api_key = SecretStore::load().require(model.env_var())?;
 
     // Determine output path
     let output_path = opts.output.cloned().unwrap_or_else(|| {
@@ -165,26 +171,39 @@ fn generate(opts: GenerateOptions, config: &Config) -> Result<()> {
         fs::create_dir_all(parent).context("Failed to create output directory")?;
     }
 
-    println!("{} Generating image with {}...", "→".blue(), model.name().cyan());
+    let spinner = progress::spinner(format, format!("Generating image with {}...", model.name()));
+    if spinner.is_hidden() {
+        println!(
+            "{} Generating image with {}...",
+            presenter::arrow(),
+            model.name().cyan()
+        );
+    }
 
     // Generate based on model
-    match model {
-        Model::Gemini => {
-            let size = opts.size.unwrap_or("2K");
-            let aspect_ratio = opts.aspect_ratio.unwrap_or("16:9");
-            generate_gemini(opts.prompt, size, aspect_ratio, &output_path, &api_key)?;
-        }
-        Model::Flux => {
-            let aspect_ratio = opts.aspect_ratio.unwrap_or("16:9");
-            generate_flux(opts.prompt, aspect_ratio, &output_path, &api_key)?;
-        }
-        Model::OpenAi => {
-            let size = opts.size.unwrap_or("1024x1024");
-            generate_openai(opts.prompt, size, &output_path, &api_key)?;
-        }
-    }
+    let client = net::client(&config.network)?;
+    let generated = net::block_on(async {
+        let image = match model {
+            Model::Gemini => {
+                let size = opts.size.unwrap_or("2K");
+                let aspect_ratio = opts.aspect_ratio.unwrap_or("16:9");
+                generate_gemini(&client, opts.prompt, size, aspect_ratio, &api_key).await?
+            }
+            Model::Flux => {
+                let aspect_ratio = opts.aspect_ratio.unwrap_or("16:9");
+                generate_flux(&client, opts.prompt, aspect_ratio, &api_key, &spinner).await?
+            }
+            Model::OpenAi => {
+                let size = opts.size.unwrap_or("1024x1024");
+                generate_openai(&client, opts.prompt, size, &api_key).await?
+            }
+        };
+        fs::write(&output_path, image).context("Failed to write image file")
+    });
+    spinner.finish_and_clear();
+    generated?;
 
-    println!("{} Saved: {}", "✓".green(), output_path.display());
+    println!("{} Saved: {}", presenter::ok(), output_path.display());
 
     // Post-processing
     if opts.remove_bg || opts.thumbnail {
@@ -195,47 +214,19 @@ fn generate(opts: GenerateOptions, config: &Config) -> Result<()> {
         let thumb_path = output_path.with_extension("").to_string_lossy().to_string() + "-thumb.png";
         let thumb_path = PathBuf::from(thumb_path);
         add_background(&output_path, &thumb_path, "#0a0a0f")?;
-        println!("{} Thumbnail: {}", "✓".green(), thumb_path.display());
+        println!("{} Thumbnail: {}", presenter::ok(), thumb_path.display());
     }
 
     Ok(())
 }
 
-fn get_api_key(model: &Model, _config: &Config) -> Result<String> {
-    let env_var = model.env_var();
-
-    // Check environment variable first
-    if let Ok(key) = std::env::var(env_var) {
-        return Ok(key);
-    }
-
-    // Check ~/.config/pais/.env file
-    let pais_dir = Config::pais_dir();
-    let env_file = pais_dir.join(".env");
-    if env_file.exists() {
-        let content = fs::read_to_string(&env_file).context("Failed to read .env file")?;
-        for line in content.lines() {
-            let line = line.trim();
-            if line.starts_with('#') || line.is_empty() {
-                continue;
-            }
-            if let Some((key, value)) = line.split_once('=')
-                && key.trim() == env_var
-            {
-                let value = value.trim().trim_matches('"').trim_matches('\'');
-                return Ok(value.to_string());
-            }
-        }
-    }
-
-    eyre::bail!(
-        "Missing API key: {} not found in environment or {}",
-        env_var,
-        env_file.display()
-    )
-}
-
-fn generate_gemini(prompt: &str, _size: &str, aspect_ratio: &str, output: &PathBuf, api_key: &str) -> Result<()> {
+async fn generate_gemini(
+    client: &reqwest::Client,
+    prompt: &str,
+    _size: &str,
+    aspect_ratio: &str,
+    api_key: &str,
+) -> Result<Vec<u8>> {
     log::info!("Generating with Gemini, aspect_ratio={}", aspect_ratio);
 
     let request = GeminiRequest {
@@ -257,18 +248,7 @@ fn generate_gemini(prompt: &str, _size: &str, aspect_ratio: &str, output: &PathB
         api_key
     );
 
-    let request_body = serde_json::to_string(&request).context("Failed to serialize request")?;
-
-    let mut response = ureq::post(&url)
-        .header("Content-Type", "application/json")
-        .send(request_body.as_bytes())
-        .context("Failed to call Gemini API")?;
-
-    let response_body = response
-        .body_mut()
-        .read_to_string()
-        .context("Failed to read response")?;
-    let response: GeminiResponse = serde_json::from_str(&response_body).context("Failed to parse Gemini response")?;
+    let response: GeminiResponse = net::json(client.post(&url).json(&request), "Gemini API").await?;
 
     // Find image data in response
     let image_data = response
@@ -278,14 +258,16 @@ fn generate_gemini(prompt: &str, _size: &str, aspect_ratio: &str, output: &PathB
         .find_map(|p| p.inline_data.as_ref())
         .ok_or_else(|| eyre::eyre!("No image data in Gemini response"))?;
 
-    // Decode base64 and save
-    let decoded = base64_decode(&image_data.data)?;
-    fs::write(output, decoded).context("Failed to write image file")?;
-
-    Ok(())
+    base64_decode(&image_data.data)
 }
 
-fn generate_flux(prompt: &str, aspect_ratio: &str, output: &PathBuf, api_key: &str) -> Result<()> {
+async fn generate_flux(
+    client: &reqwest::Client,
+    prompt: &str,
+    aspect_ratio: &str,
+    api_key: &str,
+    progress: &ProgressBar,
+) -> Result<Vec<u8>> {
     log::info!("Generating with Flux, aspect_ratio={}", aspect_ratio);
 
     // Replicate API for Flux
@@ -299,60 +281,53 @@ fn generate_flux(prompt: &str, aspect_ratio: &str, output: &PathBuf, api_key: &s
         }
     });
 
-    let request_body = serde_json::to_string(&request).context("Failed to serialize request")?;
-
-    let mut response = ureq::post("https://api.replicate.com/v1/predictions")
-        .header("Authorization", &format!("Token {}", api_key))
-        .header("Content-Type", "application/json")
-        .send(request_body.as_bytes())
-        .context("Failed to call Replicate API")?;
-
-    let response_body = response
-        .body_mut()
-        .read_to_string()
-        .context("Failed to read response")?;
-    let response: serde_json::Value =
-        serde_json::from_str(&response_body).context("Failed to parse Replicate response")?;
+    let response: serde_json::Value = net::json(
+        client
+            .post("https://api.replicate.com/v1/predictions")
+            .header("Authorization", format!("Token {}", api_key))
+            .json(&request),
+        "Replicate API",
+    )
+    .await?;
 
     // Get prediction ID and poll for completion
     let prediction_id = response["id"]
         .as_str()
         .ok_or_else(|| eyre::eyre!("No prediction ID in response"))?;
 
-    let image_url = poll_replicate(prediction_id, api_key)?;
+    let image_url = net::deadline(
+        REPLICATE_POLL_LIMIT,
+        "Replicate prediction",
+        poll_replicate(client, prediction_id, api_key, progress),
+    )
+    .await?;
 
     // Download image
-    let image_data = ureq::get(&image_url)
-        .call()
-        .context("Failed to download image")?
-        .body_mut()
-        .read_to_vec()
-        .context("Failed to read image data")?;
-
-    fs::write(output, image_data).context("Failed to write image file")?;
-
-    Ok(())
+    net::bytes(client.get(&image_url), "image download").await
 }
 
-fn poll_replicate(prediction_id: &str, api_key: &str) -> Result<String> {
-    let url = format!("https://api.replicate.com/v1/predictions/{}", prediction_id);
+/// How long to wait for a Replicate prediction to finish
+const REPLICATE_POLL_LIMIT: std::time::Duration = std::time::Duration::from_secs(120);
 
-    for _ in 0..60 {
-        std::thread::sleep(std::time::Duration::from_secs(2));
+async fn poll_replicate(
+    client: &reqwest::Client,
+    prediction_id: &str,
+    api_key: &str,
+    progress: &ProgressBar,
+) -> Result<String> {
+    let url = format!("https://api.replicate.com/v1/predictions/{}", prediction_id);
 
-        let mut response = ureq::get(&url)
-            .header("Authorization", &format!("Token {}", api_key))
-            .call()
-            .context("Failed to poll Replicate")?;
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
 
-        let response_body = response
-            .body_mut()
-            .read_to_string()
-            .context("Failed to read response")?;
-        let response: serde_json::Value =
-            serde_json::from_str(&response_body).context("Failed to parse poll response")?;
+        let response: serde_json::Value = net::json(
+            client.get(&url).header("Authorization", format!("Token {}", api_key)),
+            "Replicate poll",
+        )
+        .await?;
 
         let status = response["status"].as_str().unwrap_or("");
+        progress.set_message(format!("Generating image with Flux ({})...", status));
 
         match status {
             "succeeded" => {
@@ -374,11 +349,9 @@ fn poll_replicate(prediction_id: &str, api_key: &str) -> Result<String> {
             _ => continue,
         }
     }
-
-    eyre::bail!("Replicate prediction timed out")
 }
 
-fn generate_openai(prompt: &str, size: &str, output: &PathBuf, api_key: &str) -> Result<()> {
+async fn generate_openai(client: &reqwest::Client, prompt: &str, size: &str, api_key: &str) -> Result<Vec<u8>> {
     log::info!("Generating with OpenAI, size={}", size);
 
     let request = serde_json::json!({
@@ -389,29 +362,20 @@ fn generate_openai(prompt: &str, size: &str, output: &PathBuf, api_key: &str) ->
         "response_format": "b64_json"
     });
 
-    let request_body = serde_json::to_string(&request).context("Failed to serialize request")?;
-
-    let mut response = ureq::post("https://api.openai.com/v1/images/generations")
-        .header("Authorization", &format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .send(request_body.as_bytes())
-        .context("Failed to call OpenAI API")?;
-
-    let response_body = response
-        .body_mut()
-        .read_to_string()
-        .context("Failed to read response")?;
-    let response: serde_json::Value =
-        serde_json::from_str(&response_body).context("Failed to parse OpenAI response")?;
+    let response: serde_json::Value = net::json(
+        client
+            .post("https://api.openai.com/v1/images/generations")
+            .header("Authorization", format!("Bearer {}", api_key))
+            .json(&request),
+        "OpenAI API",
+    )
+    .await?;
 
     let image_data = response["data"][0]["b64_json"]
         .as_str()
         .ok_or_else(|| eyre::eyre!("No image data in OpenAI response"))?;
 
-    let decoded = base64_decode(image_data)?;
-    fs::write(output, decoded).context("Failed to write image file")?;
-
-    Ok(())
+    base64_decode(image_data)
 }
 
 fn base64_decode(data: &str) -> Result<Vec<u8>> {
@@ -444,25 +408,11 @@ fn base64_decode(data: &str) -> Result<Vec<u8>> {
 }
 
 fn remove_background(image_path: &Path, _config: &Config) -> Result<()> {
-    let pais_dir = Config::pais_dir();
-    let api_key = std::env::var("REMOVEBG_API_KEY")
-        .or_else(|_| {
-            let env_file = pais_dir.join(".env");
-            if env_file.exists() {
-                let content = fs::read_to_string(&env_file)?;
-                for line in content.lines() {
-                    if let Some((key, value)) = line.split_once('=')
-                        && key.trim() == "REMOVEBG_API_KEY"
-                    {
-                        return Ok(value.trim().trim_matches('"').to_string());
-                    }
-                }
-            }
-            Err(eyre::eyre!("REMOVEBG_API_KEY not found"))
-        })
+    let api_key = SecretStore::load()
+        .require("REMOVEBG_API_KEY")
         .context("--remove-bg requires REMOVEBG_API_KEY")?;
 
-    println!("{} Removing background...", "→".blue());
+    println!("{} Removing background...", presenter::arrow());
 
     // Use curl for multipart form upload (simpler than implementing in Rust)
     let output = Command::new("curl")
@@ -488,7 +438,7 @@ fn remove_background(image_path: &Path, _config: &Config) -> Result<()> {
         eyre::bail!("Background removal failed: {}", stderr);
     }
 
-    println!("{} Background removed", "✓".green());
+    println!("{} Background removed", presenter::ok());
     Ok(())
 }
 
@@ -7,6 +7,9 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use crate::config::Config;
+use crate::history::capture::init_history_dirs;
+use crate::history::categorize::Categories;
+use crate::presenter;
 
 /// Default .gitignore content for PAIS configuration directory
 const DEFAULT_GITIGNORE: &str = r#"# Secrets
@@ -30,7 +33,7 @@ fn setup_claude_hooks(pais_dir: &Path) -> Result<()> {
 
     if !claude_skills.exists() {
         fs::create_dir_all(&claude_skills).context("Failed to create ~/.claude/skills")?;
-        println!("  {} Created ~/.claude/skills/", "✓".green());
+        println!("  {} Created ~/.claude/skills/", presenter::ok());
     }
 
     // Create a README in Claude skills directory pointing to PAIS
@@ -60,14 +63,17 @@ fn init_git_repo(pais_dir: &PathBuf) -> Result<bool> {
     let git_dir = pais_dir.join(".git");
 
     if git_dir.exists() {
-        println!("  {} Git repository already exists", "✓".green());
+        println!("  {} Git repository already exists", presenter::ok());
         return Ok(false);
     }
 
     // Check if git is available
     let git_check = Command::new("git").arg("--version").output();
     if git_check.is_err() {
-        println!("  {} Git not found, skipping repository initialization", "⚠".yellow());
+        println!(
+            "  {} Git not found, skipping repository initialization",
+            presenter::warn()
+        );
         return Ok(false);
     }
 
@@ -80,11 +86,11 @@ fn init_git_repo(pais_dir: &PathBuf) -> Result<bool> {
 
     if !init_result.status.success() {
         let stderr = String::from_utf8_lossy(&init_result.stderr);
-        println!("  {} Failed to initialize git: {}", "✗".red(), stderr);
+        println!("  {} Failed to initialize git: {}", presenter::fail(), stderr);
         return Ok(false);
     }
 
-    println!("  {} Initialized git repository", "✓".green());
+    println!("  {} Initialized git repository", presenter::ok());
 
     // Stage all files
     let add_result = Command::new("git")
@@ -95,7 +101,7 @@ fn init_git_repo(pais_dir: &PathBuf) -> Result<bool> {
 
     if !add_result.status.success() {
         let stderr = String::from_utf8_lossy(&add_result.stderr);
-        println!("  {} Failed to stage files: {}", "⚠".yellow(), stderr);
+        println!("  {} Failed to stage files: {}", presenter::warn(), stderr);
     }
 
     // Create initial commit
@@ -106,12 +112,16 @@ fn init_git_repo(pais_dir: &PathBuf) -> Result<bool> {
         .context("Failed to run git commit")?;
 
     if commit_result.status.success() {
-        println!("  {} Created initial commit", "✓".green());
+        println!("  {} Created initial commit", presenter::ok());
     } else {
         // This might fail if there's nothing to commit, which is fine
         let stderr = String::from_utf8_lossy(&commit_result.stderr);
         if !stderr.contains("nothing to commit") {
-            println!("  {} Could not create initial commit: {}", "⚠".yellow(), stderr.trim());
+            println!(
+                "  {} Could not create initial commit: {}",
+                presenter::warn(),
+                stderr.trim()
+            );
         }
     }
 
@@ -121,12 +131,16 @@ fn init_git_repo(pais_dir: &PathBuf) -> Result<bool> {
 pub fn run(path: Option<PathBuf>, force: bool, no_git: bool) -> Result<()> {
     let pais_dir = path.unwrap_or_else(Config::pais_dir);
 
-    println!("{} Initializing PAIS in {}", "→".blue(), pais_dir.display());
+    println!("{} Initializing PAIS in {}", presenter::arrow(), pais_dir.display());
 
     // Check if already initialized
     let config_file = pais_dir.join("pais.yaml");
     if config_file.exists() && !force {
-        println!("  {} PAIS already initialized at {}", "✓".green(), pais_dir.display());
+        println!(
+            "  {} PAIS already initialized at {}",
+            presenter::ok(),
+            pais_dir.display()
+        );
         println!("  Use {} to reinitialize", "--force".cyan());
         return Ok(());
     }
@@ -136,27 +150,24 @@ pub fn run(path: Option<PathBuf>, force: bool, no_git: bool) -> Result<()> {
     for dir in &dirs {
         let dir_path = pais_dir.join(dir);
         fs::create_dir_all(&dir_path).context(format!("Failed to create {}", dir))?;
-        println!("  {} Created {}/", "✓".green(), dir);
+        println!("  {} Created {}/", presenter::ok(), dir);
     }
 
-    // Create history subdirectories
-    let history_dirs = ["sessions", "decisions", "learnings", "errors"];
-    for dir in &history_dirs {
-        let dir_path = pais_dir.join("history").join(dir);
-        fs::create_dir_all(&dir_path).context(format!("Failed to create history/{}", dir))?;
-    }
-    println!("  {} Created history subdirectories", "✓".green());
-
     // Generate default config
     let config = Config::default();
+
+    // Create a directory per declared history category
+    init_history_dirs(&pais_dir.join("history"), &Categories::from_config(&config.history))?;
+    println!("  {} Created history subdirectories", presenter::ok());
+
     let yaml_str = serde_yaml::to_string(&config).context("Failed to serialize config")?;
     fs::write(&config_file, yaml_str).context("Failed to write pais.yaml")?;
-    println!("  {} Created pais.yaml", "✓".green());
+    println!("  {} Created pais.yaml", presenter::ok());
 
     // Create .gitignore
     let gitignore_path = pais_dir.join(".gitignore");
     fs::write(&gitignore_path, DEFAULT_GITIGNORE).context("Failed to write .gitignore")?;
-    println!("  {} Created .gitignore", "✓".green());
+    println!("  {} Created .gitignore", presenter::ok());
 
     // Initialize git repository (unless --no-git)
     if !no_git {
@@ -167,7 +178,7 @@ pub fn run(path: Option<PathBuf>, force: bool, no_git: bool) -> Result<()> {
     setup_claude_hooks(&pais_dir)?;
 
     println!();
-    println!("{} PAIS initialized!", "✓".green().bold());
+    println!("{} PAIS initialized!", presenter::ok().bold());
     println!();
     println!("Next steps:");
     println!("  1. Run {} to verify setup", "pais doctor".cyan());
@@ -0,0 +1,266 @@
+//! Log viewing command
+//!
+//! `pais logs tail` reads the log file backwards, so a large log costs only the
+//! lines shown, filters by level and regex, and with `--follow` polls for
+//! appended lines. Both `log-format: text` and `log-format: json` files work.
+
+use colored::*;
+use eyre::{Context, Result};
+use regex::Regex;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use crate::cli::{LogsAction, OutputFormat};
+use crate::error::{CliError, ErrorCode};
+use crate::history::transcript::ReverseLines;
+use crate::logging::{self, LogRecord};
+
+/// How often `--follow` checks the file for new lines
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Run a logs subcommand
+pub fn run(action: LogsAction, format: OutputFormat) -> Result<()> {
+    match action {
+        LogsAction::Tail {
+            lines,
+            grep,
+            level,
+            follow,
+        } => {
+            let filter = Filter::new(grep.as_deref(), level.as_deref())?;
+            tail(&logging::log_file(), lines, &filter, follow, format)
+        }
+    }
+}
+
+/// Which records to show
+struct Filter {
+    pattern: Option<Regex>,
+    /// Least severe level shown
+    level: Option<log::Level>,
+}
+
+impl Filter {
+    fn new(grep: Option<&str>, level: Option<&str>) -> Result<Self> {
+        let pattern = grep
+            .map(|g| {
+                Regex::new(g).map_err(|e| {
+                    CliError::new(ErrorCode::InvalidInput, format!("Invalid --grep pattern: {}", e))
+                        .with_hint("--grep takes a regular expression, e.g. 'hook|security'")
+                })
+            })
+            .transpose()?;
+        let level = level.and_then(|l| l.parse().ok());
+        Ok(Self { pattern, level })
+    }
+
+    fn matches(&self, record: &LogRecord) -> bool {
+        if let Some(min) = self.level
+            && record.level().is_none_or(|level| level > min)
+        {
+            return false;
+        }
+        match &self.pattern {
+            Some(re) => {
+                re.is_match(&record.message)
+                    || re.is_match(&record.module)
+                    || record
+                        .fields
+                        .iter()
+                        .any(|(key, value)| re.is_match(key) || re.is_match(&value.to_string()))
+            }
+            None => true,
+        }
+    }
+}
+
+fn tail(path: &Path, count: usize, filter: &Filter, follow: bool, format: OutputFormat) -> Result<()> {
+    let records = last_records(path, count, filter)?;
+
+    if follow {
+        for record in &records {
+            print_line(record, format)?;
+        }
+        return follow_file(path, filter, format);
+    }
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&records)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&records)?),
+        OutputFormat::Text => {
+            if records.is_empty() {
+                println!("{}", format!("No log lines in {}", path.display()).dimmed());
+            }
+            for record in &records {
+                print_record(record);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The last `count` matching records, oldest first
+fn last_records(path: &Path, count: usize, filter: &Filter) -> Result<Vec<LogRecord>> {
+    if count == 0 || !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let lines = ReverseLines::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut records = Vec::new();
+    // Lines of a multi-line text message, seen before (i.e. after) their header
+    let mut continuation: Vec<String> = Vec::new();
+
+    for line in lines {
+        let line = line.with_context(|| format!("Failed to read {}", path.display()))?;
+        let Some(mut record) = LogRecord::parse(&line) else {
+            continuation.push(line);
+            continue;
+        };
+        if !continuation.is_empty() {
+            continuation.reverse();
+            record.message.push('\n');
+            record.message.push_str(&continuation.join("\n"));
+            continuation.clear();
+        }
+        if filter.matches(&record) {
+            records.push(record);
+            if records.len() == count {
+                break;
+            }
+        }
+    }
+
+    records.reverse();
+    Ok(records)
+}
+
+/// Print lines appended to the log until interrupted
+fn follow_file(path: &Path, filter: &Filter, format: OutputFormat) -> Result<()> {
+    let mut offset = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let mut pending: Vec<u8> = Vec::new();
+    // Whether the last header line was shown, so its continuation lines are too
+    let mut showing = false;
+
+    loop {
+        let len = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        if len < offset {
+            // Truncated or replaced: start over from the top
+            offset = 0;
+            pending.clear();
+        }
+
+        if len > offset {
+            let mut file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+            file.seek(SeekFrom::Start(offset))?;
+            file.take(len - offset).read_to_end(&mut pending)?;
+            offset = len;
+
+            while let Some(newline) = pending.iter().position(|&b| b == b'\n') {
+                let raw: Vec<u8> = pending.drain(..=newline).collect();
+                let line = String::from_utf8_lossy(&raw);
+                let line = line.trim_end_matches(['\n', '\r']);
+
+                match LogRecord::parse(line) {
+                    Some(record) => {
+                        showing = filter.matches(&record);
+                        if showing {
+                            print_line(&record, format)?;
+                        }
+                    }
+                    None if showing && format == OutputFormat::Text => println!("{}", line),
+                    None => {}
+                }
+            }
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// One record per line, for streaming output
+fn print_line(record: &LogRecord, format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string(record)?),
+        OutputFormat::Yaml => print!("---\n{}", serde_yaml::to_string(record)?),
+        OutputFormat::Text => print_record(record),
+    }
+    Ok(())
+}
+
+fn print_record(record: &LogRecord) {
+    let level = format!("{:<5}", record.level);
+    let level = match record.level() {
+        Some(log::Level::Error) => level.red().bold(),
+        Some(log::Level::Warn) => level.yellow(),
+        Some(log::Level::Info) => level.green(),
+        _ => level.dimmed(),
+    };
+    let fields: String = record
+        .fields
+        .iter()
+        .map(|(key, value)| match value {
+            serde_json::Value::String(s) => format!(" {}={}", key, s),
+            other => format!(" {}={}", key, other),
+        })
+        .collect();
+
+    println!(
+        "{} {} {} {}{}",
+        record.timestamp.dimmed(),
+        level,
+        record.module.cyan(),
+        record.message,
+        fields.dimmed()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_last_records_filters_and_joins_continuations() {
+        let temp = tempdir().unwrap();
+        let path = temp.path().join("pais.log");
+        fs::write(
+            &path,
+            "[2026-01-05T10:00:00Z INFO  pais] Starting\n\
+             [2026-01-05T10:00:01Z WARN  pais::hook] Slow handler\n\
+             {\"timestamp\":\"2026-01-05T10:00:02.000Z\",\"level\":\"ERROR\",\"module\":\"pais::hook\",\"message\":\"Handler failed\",\"fields\":{\"handler\":\"security\"}}\n\
+             [2026-01-05T10:00:03Z DEBUG pais::config] Loaded\nfirst detail\nsecond detail\n",
+        )
+        .unwrap();
+
+        let all = Filter::new(None, None).unwrap();
+        let records = last_records(&path, 50, &all).unwrap();
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0].message, "Starting");
+        assert_eq!(records[3].message, "Loaded\nfirst detail\nsecond detail");
+
+        let last_two = last_records(&path, 2, &all).unwrap();
+        assert_eq!(last_two[0].message, "Handler failed");
+
+        let warnings = Filter::new(None, Some("warn")).unwrap();
+        let records = last_records(&path, 50, &warnings).unwrap();
+        assert_eq!(
+            records.iter().map(|r| r.message.as_str()).collect::<Vec<_>>(),
+            vec!["Slow handler", "Handler failed"]
+        );
+
+        // Field values are searched too
+        let grep = Filter::new(Some("secur"), None).unwrap();
+        let records = last_records(&path, 50, &grep).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].fields["handler"], "security");
+    }
+
+    #[test]
+    fn test_invalid_grep_is_input_error() {
+        let err = Filter::new(Some("("), None).err().unwrap();
+        assert!(err.to_string().contains("Invalid --grep pattern"));
+    }
+}
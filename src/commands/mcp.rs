@@ -0,0 +1,17 @@
+//! MCP server command
+//!
+//! `pais mcp serve` is started by Claude Code from `mcp.servers` in its
+//! settings; stdout carries the protocol, so nothing else may print there.
+
+use eyre::Result;
+
+use crate::cli::McpAction;
+use crate::config::Config;
+use crate::mcp;
+
+/// Run an mcp subcommand
+pub fn run(action: McpAction, config: &Config) -> Result<()> {
+    match action {
+        McpAction::Serve => mcp::serve(&Config::expand_path(&config.paths.skills)),
+    }
+}
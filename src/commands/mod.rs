@@ -1,20 +1,34 @@
 pub mod agent;
+pub mod bench;
 pub mod bundle;
+pub mod checkpoint;
+pub mod claude;
 pub mod completions;
 pub mod config;
 pub mod context;
+pub mod debug;
 pub mod diagram;
 pub mod doctor;
+pub mod gc;
 pub mod history;
 pub mod hook;
 pub mod image;
 pub mod init;
+pub mod logs;
+pub mod mcp;
 pub mod observe;
 pub mod plugin;
+pub mod policy;
+pub mod provenance;
+pub mod review;
 pub mod run;
 pub mod security;
 pub mod session;
 pub mod skill;
+pub mod state;
 pub mod status;
 pub mod sync;
+pub mod team;
+pub mod test;
+pub mod trust;
 pub mod upgrade;
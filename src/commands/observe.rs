@@ -1,24 +1,62 @@
 //! Live event observation command
 //!
-//! Tails the event log in real-time, similar to `tail -f`.
+//! Tails the event log in real-time, similar to `tail -f`, or replays a past
+//! window of events at (a multiple of) their original pace.
 
-use chrono::Local;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
 use colored::*;
 use eyre::{Context, Result};
+use lazy_regex::regex_captures;
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader, Seek, SeekFrom};
 use std::path::Path;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crate::config::Config;
-use crate::observability::Event;
+use crate::error::{CliError, ErrorCode};
+use crate::observability::{Event, flush_buffered};
+use crate::pager;
+
+/// Longest pause between two replayed events; longer idle stretches are skipped with a note
+const MAX_REPLAY_GAP: Duration = Duration::from_secs(5);
+
+/// How often the live tail moves buffered events into the day log
+const TAIL_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Options for replaying a past window of events
+#[derive(Debug, Clone)]
+pub struct Replay {
+    /// Start of the window
+    pub since: String,
+    /// End of the window (now when `None`)
+    pub until: Option<String>,
+    /// Speed multiplier ("4x", "0.5")
+    pub speed: String,
+    /// Sleep between events in proportion to their original spacing
+    pub delay: bool,
+    /// Page instant replays that overflow the terminal
+    pub pager: bool,
+}
 
 /// Run the observe command
-pub fn run(filter: Option<&str>, last: usize, include_payload: bool, config: &Config) -> Result<()> {
+pub fn run(
+    filter: Option<&str>,
+    last: usize,
+    include_payload: bool,
+    replay: Option<Replay>,
+    config: &Config,
+) -> Result<()> {
     let history_path = Config::expand_path(&config.paths.history);
     let events_dir = history_path.join("raw-events");
 
+    // Events the batched file sink still holds belong in the logs read below
+    flush_buffered(&history_path);
+
+    if let Some(replay) = replay {
+        return replay_events(&events_dir, filter, include_payload, &replay);
+    }
+
     println!("{} Observing events (Ctrl+C to stop)...", "👁".blue());
     if let Some(f) = filter {
         println!("  Filter: {}", f.cyan());
@@ -33,7 +71,7 @@ pub fn run(filter: Option<&str>, last: usize, include_payload: bool, config: &Co
     }
 
     // Now tail the current day's file
-    tail_events(&events_dir, filter, include_payload)?;
+    tail_events(&history_path, filter, include_payload)?;
 
     Ok(())
 }
@@ -79,8 +117,11 @@ fn show_recent_events(events_dir: &Path, count: usize, filter: Option<&str>, inc
     Ok(())
 }
 
-/// Tail the current day's log file
-fn tail_events(events_dir: &Path, filter: Option<&str>, include_payload: bool) -> Result<()> {
+/// Tail the current day's log file, flushing the batched file sink while idle
+/// so buffered events show up within a second
+fn tail_events(history_path: &Path, filter: Option<&str>, include_payload: bool) -> Result<()> {
+    let events_dir = history_path.join("raw-events");
+    let mut flushed = Instant::now();
     loop {
         let today = Local::now();
         let month_dir = events_dir.join(today.format("%Y-%m").to_string());
@@ -88,7 +129,8 @@ fn tail_events(events_dir: &Path, filter: Option<&str>, include_payload: bool) -
 
         if !log_file.exists() {
             // Wait for file to be created
-            thread::sleep(Duration::from_secs(1));
+            thread::sleep(TAIL_FLUSH_INTERVAL);
+            flush_buffered(history_path);
             continue;
         }
 
@@ -106,6 +148,10 @@ fn tail_events(events_dir: &Path, filter: Option<&str>, include_payload: bool) -
                 Ok(0) => {
                     // No new data, wait a bit
                     thread::sleep(Duration::from_millis(100));
+                    if flushed.elapsed() >= TAIL_FLUSH_INTERVAL {
+                        flush_buffered(history_path);
+                        flushed = Instant::now();
+                    }
 
                     // Check if we've crossed midnight
                     let now = Local::now();
@@ -140,14 +186,259 @@ fn tail_events(events_dir: &Path, filter: Option<&str>, include_payload: bool) -
     }
 }
 
-/// Print a single event
-fn print_event(event: &Event, include_payload: bool) {
-    println!("{}", event.format_display());
+/// Replay the events between `--since` and `--until`
+fn replay_events(events_dir: &Path, filter: Option<&str>, include_payload: bool, replay: &Replay) -> Result<()> {
+    let now = Local::now();
+    let since = parse_time(&replay.since, now)?;
+    let until = replay
+        .until
+        .as_deref()
+        .map(|u| parse_time(u, now))
+        .transpose()?
+        .unwrap_or(now);
+    if since > until {
+        return Err(CliError::new(ErrorCode::InvalidInput, "--since is after --until").into());
+    }
+    let speed = parse_speed(&replay.speed)?;
+
+    let events = events_between(events_dir, since, until, filter);
+    let window = format!(
+        "{} → {}",
+        since.format("%Y-%m-%d %H:%M:%S"),
+        until.format("%Y-%m-%d %H:%M:%S")
+    );
+    if events.is_empty() {
+        println!("No events between {}", window);
+        return Ok(());
+    }
+
+    if !replay.delay {
+        let mut lines = vec![format!("{} events, {}", events.len(), window).dimmed().to_string()];
+        for (_, event) in &events {
+            lines.extend(render_event(event, include_payload));
+        }
+        return if replay.pager {
+            pager::page(&lines)
+        } else {
+            lines.iter().for_each(|l| println!("{}", l));
+            Ok(())
+        };
+    }
+
+    println!(
+        "{} Replaying {} events, {} at {}x (Ctrl+C to stop)...",
+        "▶".blue(),
+        events.len(),
+        window,
+        speed
+    );
+    println!();
+
+    let mut previous: Option<DateTime<Local>> = None;
+    for (at, event) in &events {
+        if let Some(prev) = previous {
+            let original = (*at - prev).to_std().unwrap_or_default();
+            let scaled = original.div_f64(speed);
+            if scaled > MAX_REPLAY_GAP {
+                println!("{}", format!("… {} idle …", format_gap(original)).dimmed());
+            }
+            thread::sleep(scaled.min(MAX_REPLAY_GAP));
+        }
+        print_event(event, include_payload);
+        previous = Some(*at);
+    }
+
+    Ok(())
+}
+
+/// Events in `[since, until]` (matching `filter`), oldest first
+fn events_between(
+    events_dir: &Path,
+    since: DateTime<Local>,
+    until: DateTime<Local>,
+    filter: Option<&str>,
+) -> Vec<(DateTime<Local>, Event)> {
+    let mut events = Vec::new();
+    let mut day = since.date_naive();
+    while day <= until.date_naive() {
+        let log_file = events_dir
+            .join(day.format("%Y-%m").to_string())
+            .join(format!("{}.jsonl", day.format("%Y-%m-%d")));
+        if let Ok(content) = fs::read_to_string(&log_file) {
+            for event in content.lines().filter_map(|l| serde_json::from_str::<Event>(l).ok()) {
+                let Ok(at) = DateTime::parse_from_rfc3339(&event.timestamp) else {
+                    continue;
+                };
+                let at = at.with_timezone(&Local);
+                let matches = filter.is_none_or(|f| event.event_type.to_lowercase().contains(&f.to_lowercase()));
+                if matches && at >= since && at <= until {
+                    events.push((at, event));
+                }
+            }
+        }
+        let Some(next) = day.succ_opt() else { break };
+        day = next;
+    }
+    events.sort_by_key(|(at, _)| *at);
+    events
+}
+
+/// Parse a replay bound: a relative age (`90s`, `30m`, `2h`, `1d`, `1w`), `now`,
+/// RFC 3339, a local date or date-time, or a time of day today
+fn parse_time(s: &str, now: DateTime<Local>) -> Result<DateTime<Local>> {
+    let s = s.trim();
+    if s == "now" {
+        return Ok(now);
+    }
+    if let Some((_, n, unit)) = regex_captures!(r"^(\d+)\s*([smhdw])$", s) {
+        let n: i64 = n.parse().context("Relative time is too large")?;
+        let age = match unit {
+            "s" => chrono::Duration::seconds(n),
+            "m" => chrono::Duration::minutes(n),
+            "h" => chrono::Duration::hours(n),
+            "d" => chrono::Duration::days(n),
+            _ => chrono::Duration::weeks(n),
+        };
+        return Ok(now - age);
+    }
+    if let Ok(at) = DateTime::parse_from_rfc3339(s) {
+        return Ok(at.with_timezone(&Local));
+    }
+
+    let naive = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|f| NaiveDateTime::parse_from_str(s, f).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d")
+            .ok()
+            .map(|d| d.and_time(NaiveTime::MIN))
+    })
+    .or_else(|| {
+        ["%H:%M:%S", "%H:%M"]
+            .iter()
+            .find_map(|f| NaiveTime::parse_from_str(s, f).ok())
+            .map(|t| now.date_naive().and_time(t))
+    });
+    naive
+        .and_then(|n| Local.from_local_datetime(&n).earliest())
+        .ok_or_else(|| {
+            CliError::new(ErrorCode::InvalidInput, format!("Invalid time '{}'", s))
+                .with_hint("Use an age like 2h or 30m, a date like 2026-01-03, \"2026-01-03 14:00\", or 14:00")
+                .into()
+        })
+}
+
+/// Parse a replay speed such as `4x`, `0.5x` or `2`
+fn parse_speed(s: &str) -> Result<f64> {
+    s.trim()
+        .trim_end_matches(['x', 'X'])
+        .parse::<f64>()
+        .ok()
+        .filter(|speed| speed.is_finite() && *speed > 0.0)
+        .ok_or_else(|| {
+            CliError::new(ErrorCode::InvalidInput, format!("Invalid speed '{}'", s))
+                .with_hint("Use a positive multiplier like 4x or 0.5")
+                .into()
+        })
+}
+
+/// Human-readable length of an idle stretch
+fn format_gap(gap: Duration) -> String {
+    let secs = gap.as_secs();
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+/// Display lines for a single event
+fn render_event(event: &Event, include_payload: bool) -> Vec<String> {
+    let mut lines = vec![event.format_display()];
 
     if include_payload && let Some(ref payload) = event.payload {
         let pretty = serde_json::to_string_pretty(payload).unwrap_or_default();
-        for line in pretty.lines() {
-            println!("  {}", line.dimmed());
-        }
+        lines.extend(pretty.lines().map(|line| format!("  {}", line.dimmed())));
+    }
+    lines
+}
+
+/// Print a single event
+fn print_event(event: &Event, include_payload: bool) {
+    for line in render_event(event, include_payload) {
+        println!("{}", line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_time_and_speed() {
+        let now = Local.with_ymd_and_hms(2026, 1, 3, 15, 30, 0).unwrap();
+        assert_eq!(parse_time("2h", now).unwrap(), now - chrono::Duration::hours(2));
+        assert_eq!(parse_time("now", now).unwrap(), now);
+        assert_eq!(
+            parse_time("14:00", now).unwrap(),
+            Local.with_ymd_and_hms(2026, 1, 3, 14, 0, 0).unwrap()
+        );
+        assert_eq!(
+            parse_time("2026-01-02", now).unwrap(),
+            Local.with_ymd_and_hms(2026, 1, 2, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            parse_time("2026-01-02 09:15", now).unwrap(),
+            Local.with_ymd_and_hms(2026, 1, 2, 9, 15, 0).unwrap()
+        );
+        assert!(parse_time("yesterday-ish", now).is_err());
+
+        assert_eq!(parse_speed("4x").unwrap(), 4.0);
+        assert_eq!(parse_speed("0.5").unwrap(), 0.5);
+        assert!(parse_speed("0x").is_err());
+        assert!(parse_speed("fast").is_err());
+    }
+
+    #[test]
+    fn test_events_between_spans_days_and_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |day: &str, lines: &[(&str, &str)]| {
+            let month = dir.path().join(&day[..7]);
+            fs::create_dir_all(&month).unwrap();
+            let content: String = lines
+                .iter()
+                .map(|(ts, kind)| {
+                    format!(
+                        "{{\"timestamp\":\"{}\",\"local_time\":\"\",\"event_type\":\"{}\"}}\n",
+                        ts, kind
+                    )
+                })
+                .collect();
+            fs::write(month.join(format!("{}.jsonl", day)), content).unwrap();
+        };
+        write(
+            "2026-01-02",
+            &[
+                ("2026-01-02T12:00:00Z", "SessionStart"),
+                ("2026-01-02T12:00:05Z", "PreToolUse"),
+            ],
+        );
+        write("2026-01-03", &[("2026-01-03T12:00:00Z", "PreToolUse")]);
+
+        let since = DateTime::parse_from_rfc3339("2026-01-02T12:00:01Z")
+            .unwrap()
+            .with_timezone(&Local);
+        let until = DateTime::parse_from_rfc3339("2026-01-04T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Local);
+        let events = events_between(dir.path(), since, until, Some("pretool"));
+        assert_eq!(events.len(), 2);
+        assert!(events[0].0 < events[1].0);
+        assert!(events_between(dir.path(), since, until, Some("SessionStart")).is_empty());
     }
 }
@@ -1,86 +1,278 @@
+use chrono::{DateTime, Local};
 use colored::*;
 use eyre::{Context, Result};
 use serde::Serialize;
 use std::fs;
-use std::path::Path;
-use terminal_size::{Width, terminal_size};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use crate::cli::{OutputFormat, PluginAction};
+use crate::commands;
 use crate::config::Config;
+use crate::error::{CliError, ErrorCode};
+use crate::hook::HookEvent;
+use crate::observability::emitter::last_plugin_runs;
+use crate::picker::pick_name;
+use crate::plugin::build::{self, BuildOutcome};
+use crate::plugin::config as plugin_config;
+use crate::plugin::describe;
+use crate::plugin::dev::{self as plugin_dev, PluginWatcher};
+use crate::plugin::disabled::{self, DisabledPlugins};
+use crate::plugin::executor::{self, HookEnv};
+use crate::plugin::health::{self, Health};
 use crate::plugin::loader::load_plugin;
+use crate::plugin::lockfile;
+use crate::plugin::manifest::PluginManifest;
+use crate::plugin::pack;
+use crate::plugin::registry;
+use crate::plugin::resolve::{self, Problem};
+use crate::plugin::signing::{self, SecretKey, Verifier};
 use crate::plugin::verify::{has_checks, print_verification_result, read_verification_guide, verify_plugin};
+use crate::plugin::{HookOutcome, PluginManager};
+use crate::presenter::{self, Table};
+use crate::provenance::{self, ArtifactKind, Provenance};
+use crate::state::{self, StateEvent};
 
-pub fn run(action: PluginAction, config: &Config) -> Result<()> {
+pub fn run(action: PluginAction, format: OutputFormat, config: &Config) -> Result<()> {
     match action {
-        PluginAction::List { format } => list(OutputFormat::resolve(format), config),
-        PluginAction::Install { source, dev, force } => install(&source, dev, force, config),
-        PluginAction::Remove { name, force } => remove(&name, force, config),
-        PluginAction::Update { name } => update(&name, config),
-        PluginAction::Info { name } => info(&name, config),
+        PluginAction::List => list(format, config),
+        PluginAction::Search { term } => search(&term, format, config),
+        PluginAction::Install {
+            source,
+            r#ref,
+            dev,
+            force,
+        } => install(&source, r#ref.as_deref(), dev, force, config),
+        PluginAction::Remove { name, force } => remove(&pick_plugin(name, config)?, force, config),
+        PluginAction::Update { name, r#ref, force } => {
+            update(&pick_plugin(name, config)?, r#ref.as_deref(), force, config)
+        }
+        PluginAction::Info { name } => info(&pick_plugin(name, config)?, config),
         PluginAction::New {
             name,
             language,
             r#type,
             path,
         } => new(&name, &language, &r#type, path.as_ref(), config),
-        PluginAction::Verify { name, format } => verify(&name, OutputFormat::resolve(format), config),
-        PluginAction::InstallGuide { name } => install_guide(&name, config),
+        PluginAction::Verify { name } => verify(&pick_plugin(name, config)?, format, config),
+        PluginAction::InstallGuide { name } => install_guide(&pick_plugin(name, config)?, config),
+        PluginAction::Build { name } => build(name.as_deref(), config.network.offline, config),
+        PluginAction::Dev { path, event, payload } => dev(&path, event.as_deref(), payload.as_deref(), config),
+        PluginAction::Config { name } => show_config(&pick_plugin(name, config)?, format, config),
+        PluginAction::Enable { name } => set_enabled(&pick_plugin(name, config)?, true, config),
+        PluginAction::Disable { name } => set_enabled(&pick_plugin(name, config)?, false, config),
+        PluginAction::Sign { name, key } => sign(&pick_plugin(name, config)?, key.as_deref(), config),
+        PluginAction::Pack { name, output } => pack(&pick_plugin(name, config)?, output, format, config),
     }
 }
 
-/// Plugin info for serialization
-#[derive(Debug, Serialize)]
-struct PluginInfo {
-    name: String,
-    version: String,
-    description: String,
-    language: String,
-    path: String,
-}
-
-/// Get terminal width, defaulting to 80 if not available
-fn get_terminal_width() -> usize {
-    terminal_size().map(|(Width(w), _)| w as usize).unwrap_or(80)
+/// Link a plugin checkout, then re-check, rebuild and optionally replay a hook on every change
+fn dev(path: &Path, event: Option<&str>, payload: Option<&Path>, config: &Config) -> Result<()> {
+    let event = event
+        .map(|e| {
+            HookEvent::from_str(e).ok_or_else(|| {
+                CliError::new(ErrorCode::InvalidInput, format!("Unknown hook event: {}", e))
+                    .with_hint("Use an event such as pre-tool-use, post-tool-use, stop or session-start")
+            })
+        })
+        .transpose()?;
+    let root = fs::canonicalize(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let plugin = load_plugin(&root).context("Failed to load plugin")?;
+    link_dev(&root, &plugin.manifest.plugin.name, config)?;
+
+    let watcher = PluginWatcher::new(&root)?;
+    println!();
+    println!("{} Watching {}", presenter::arrow(), root.display());
+    println!("{}", "Press Ctrl-C to stop".dimmed());
+
+    dev_cycle(&root, event, payload, config);
+    watcher.skip_pending();
+    loop {
+        let changed = watcher.next_batch()?;
+        let shown: Vec<String> = changed.iter().take(3).map(|p| p.display().to_string()).collect();
+        let more = changed.len().saturating_sub(shown.len());
+        println!();
+        println!(
+            "{} Changed: {}{}",
+            presenter::arrow(),
+            shown.join(", "),
+            if more > 0 { format!(" and {} more", more) } else { String::new() }
+        );
+        dev_cycle(&root, event, payload, config);
+        watcher.skip_pending();
+    }
 }
 
-/// Wrap text to max_width, returning lines
-fn wrap_text(s: &str, max_width: usize) -> Vec<String> {
-    if max_width == 0 {
-        return vec![s.to_string()];
+/// Make sure the installed plugin is a link to `root`, linking it if it isn't installed
+fn link_dev(root: &Path, name: &str, config: &Config) -> Result<()> {
+    let dest = Config::expand_path(&config.paths.plugins).join(name);
+    if dest.symlink_metadata().is_err() {
+        return install_from_path(root, true, false, &root.display().to_string(), None, config);
+    }
+    if dest.is_symlink() && fs::canonicalize(&dest).ok().as_deref() == Some(root) {
+        println!("{} {} is linked to {}", presenter::ok(), name.green(), root.display());
+        return Ok(());
     }
+    Err(CliError::new(
+        ErrorCode::Conflict,
+        format!("Plugin '{}' is already installed from elsewhere", name),
+    )
+    .with_hint(format!("Remove it first: pais plugin remove {}", name))
+    .into())
+}
 
-    let mut lines = Vec::new();
-    let mut current_line = String::new();
-    let mut current_len = 0;
+/// One round of `pais plugin dev`: reload the manifest, check the config, build,
+/// report runtime problems and replay the hook event; failures are printed, not returned
+fn dev_cycle(root: &Path, event: Option<HookEvent>, payload: Option<&Path>, config: &Config) {
+    let plugin = match load_plugin(root) {
+        Ok(plugin) => plugin,
+        Err(e) => {
+            println!("{} plugin.yaml: {:#}", presenter::fail(), e);
+            return;
+        }
+    };
+    let name = &plugin.manifest.plugin.name;
+    println!(
+        "{} Manifest valid ({} v{})",
+        presenter::ok(),
+        name,
+        plugin.manifest.plugin.version
+    );
 
-    for word in s.split_whitespace() {
-        let word_len = word.chars().count();
+    let resolved = match plugin_config::resolve(&plugin.manifest, config.plugins.settings.get(name)) {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            println!("{} {}", presenter::fail(), e.message);
+            if let Some(hint) = e.hint {
+                println!("    {}", hint.dimmed());
+            }
+            return;
+        }
+    };
 
-        if current_len == 0 {
-            current_line = word.to_string();
-            current_len = word_len;
-        } else if current_len + 1 + word_len <= max_width {
-            current_line.push(' ');
-            current_line.push_str(word);
-            current_len += 1 + word_len;
-        } else {
-            lines.push(current_line);
-            current_line = word.to_string();
-            current_len = word_len;
+    if build::plan(&plugin).is_some() {
+        let started = Instant::now();
+        match build::build(&plugin, config.network.offline) {
+            Ok(BuildOutcome::Built) => println!("{} Built in {:.1}s", presenter::ok(), started.elapsed().as_secs_f32()),
+            Ok(BuildOutcome::Cached) => println!("{} Restored from the build cache", presenter::ok()),
+            Ok(BuildOutcome::Nothing) => {}
+            Err(e) => {
+                println!("{} Build failed: {:#}", presenter::fail(), e);
+                return;
+            }
         }
     }
 
-    if !current_line.is_empty() {
-        lines.push(current_line);
+    for diagnosis in health::diagnose(&plugin) {
+        println!("{} {}", presenter::warn(), diagnosis.problem);
+        println!("    {} {}", presenter::arrow(), diagnosis.fix.cyan());
     }
 
-    if lines.is_empty() {
-        lines.push(String::new());
+    let Some(event) = event else {
+        return;
+    };
+    let payload = match payload {
+        Some(file) => match fs::read_to_string(file)
+            .map_err(eyre::Report::from)
+            .and_then(|content| Ok(serde_json::from_str(&content)?))
+        {
+            Ok(payload) => payload,
+            Err(e) => {
+                println!("{} {}: {:#}", presenter::fail(), file.display(), e);
+                return;
+            }
+        },
+        None => plugin_dev::sample_payload(event, &std::env::current_dir().unwrap_or_else(|_| root.to_path_buf())),
+    };
+    let env = HookEnv {
+        default_timeout: Duration::from_secs(config.plugins.hook_timeout_secs),
+        vars: resolved.vars(),
+        secrets: resolved.secrets(),
+        ..Default::default()
+    };
+    let scripts = plugin.manifest.hooks.scripts_for_event(&event.to_string());
+    if scripts.is_empty() {
+        println!("{} No {} hooks in plugin.yaml", presenter::info(), event);
     }
+    for script in scripts {
+        if !executor::applies(script, &payload) {
+            println!("{} {} {}: skipped (matcher)", presenter::info(), event, script.script);
+            continue;
+        }
+        // Run one at a time so a hook that can't start is reported, not just logged
+        let result = match executor::execute_hook(root, &plugin.manifest, script, event, &payload, &env) {
+            Ok(result) => result,
+            Err(e) => {
+                println!("{} {} {}: {:#}", presenter::fail(), event, script.script, e);
+                continue;
+            }
+        };
+        let outcome = HookOutcome::of(std::slice::from_ref(&result));
+        let symbol = match outcome {
+            HookOutcome::Allow => presenter::ok(),
+            HookOutcome::Block => presenter::warn(),
+            HookOutcome::Error | HookOutcome::Timeout => presenter::fail(),
+        };
+        println!(
+            "{} {} {}: {} (exit {})",
+            symbol, event, result.script, outcome, result.exit_code
+        );
+        for line in result.stdout.lines().chain(result.stderr.lines()) {
+            println!("    {}", line.dimmed());
+        }
+    }
+}
 
-    lines
+/// Use the given plugin name, or pick one of the installed plugins interactively
+fn pick_plugin(name: Option<String>, config: &Config) -> Result<String> {
+    pick_name(name, "plugin", || plugin_names(config))
 }
 
+/// Names of all installed plugins
+pub fn plugin_names(config: &Config) -> Result<Vec<String>> {
+    Ok(installed_plugins(config)?
+        .into_iter()
+        .map(|p| p.manifest.plugin.name)
+        .collect())
+}
+
+/// Actions a plugin reports through `describe`, with their descriptions
+pub fn plugin_actions(name: &str, config: &Config) -> Result<Vec<describe::ActionInfo>> {
+    let plugin = find_plugin(name, config)?;
+    Ok(describe::describe_cached(&plugin.path, &plugin.manifest)
+        .map(|d| d.actions)
+        .unwrap_or_default())
+}
+
+/// Plugin info for serialization
+#[derive(Debug, Serialize)]
+struct PluginInfo {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    namespace: Option<String>,
+    version: String,
+    description: String,
+    language: String,
+    path: String,
+    enabled: bool,
+    health: Health,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_hook: Option<LastHook>,
+}
+
+/// A plugin's latest hook run, from the observability events
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct LastHook {
+    at: String,
+    event: String,
+    outcome: HookOutcome,
+    duration_ms: u64,
+}
+
+/// How far back `plugin list` looks for hook runs
+const LAST_HOOK_DAYS: i64 = 7;
+
 fn list(format: OutputFormat, config: &Config) -> Result<()> {
     let plugins_dir = Config::expand_path(&config.paths.plugins);
 
@@ -97,91 +289,90 @@ fn list(format: OutputFormat, config: &Config) -> Result<()> {
         return Ok(());
     }
 
-    let mut plugins = Vec::new();
-
-    // Scan for plugin directories
-    for entry in fs::read_dir(&plugins_dir).context("Failed to read plugins directory")? {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.is_dir() {
-            let manifest_path = path.join("plugin.yaml");
-            if manifest_path.exists() {
-                match load_plugin(&path) {
-                    Ok(plugin) => plugins.push(plugin),
-                    Err(e) => {
-                        log::warn!("Failed to load plugin at {}: {}", path.display(), e);
-                    }
-                }
-            }
-        }
-    }
-
+    let plugins = installed_plugins(config)?;
+    let disabled = DisabledPlugins::load();
+    let history_path = Config::expand_path(&config.paths.history);
+    let mut last_runs = last_plugin_runs(&history_path, LAST_HOOK_DAYS);
+    let output: Vec<PluginInfo> = plugins
+        .iter()
+        .map(|p| PluginInfo {
+            name: p.manifest.plugin.name.clone(),
+            namespace: p.manifest.plugin.namespace.clone(),
+            version: p.manifest.plugin.version.clone(),
+            description: p.manifest.plugin.description.clone(),
+            language: format!("{:?}", p.manifest.plugin.language),
+            path: p.path.display().to_string(),
+            enabled: !disabled.is_disabled(&p.manifest.plugin.name),
+            health: Health::check(p),
+            last_hook: last_runs.remove(&p.manifest.plugin.name).map(|(at, run)| LastHook {
+                at,
+                event: run.event,
+                outcome: run.outcome,
+                duration_ms: run.duration_ms,
+            }),
+        })
+        .collect();
     match format {
-        OutputFormat::Json | OutputFormat::Yaml => {
-            let output: Vec<PluginInfo> = plugins
-                .iter()
-                .map(|p| PluginInfo {
-                    name: p.manifest.plugin.name.clone(),
-                    version: p.manifest.plugin.version.clone(),
-                    description: p.manifest.plugin.description.clone(),
-                    language: format!("{:?}", p.manifest.plugin.language),
-                    path: p.path.display().to_string(),
-                })
-                .collect();
-            match format {
-                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
-                OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&output)?),
-                _ => unreachable!(),
-            }
-        }
+        OutputFormat::Json | OutputFormat::Yaml => match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+            OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&output)?),
+            _ => unreachable!(),
+        },
         OutputFormat::Text => {
             if plugins.is_empty() {
                 println!("{}", "No plugins installed".dimmed());
             } else {
-                let term_width = get_terminal_width();
-
-                // Calculate column widths
-                let name_width = plugins.iter().map(|p| p.manifest.plugin.name.len()).max().unwrap_or(4);
-                let version_width = plugins
-                    .iter()
-                    .map(|p| p.manifest.plugin.version.len() + 1)
-                    .max()
-                    .unwrap_or(7);
-
-                // Description gets remaining space (minus columns and gaps)
-                let fixed_width = name_width + 2 + version_width + 2;
-                let desc_width = term_width.saturating_sub(fixed_width).max(20);
-
-                // Header
-                println!(
-                    "{:<name_width$}  {:<version_width$}  {}",
-                    "NAME".bold(),
-                    "VERSION".bold(),
-                    "DESCRIPTION".bold(),
-                    name_width = name_width,
-                    version_width = version_width,
-                );
-
-                // Plugins
-                let indent = " ".repeat(fixed_width);
-                for plugin in &plugins {
-                    let desc_lines = wrap_text(&plugin.manifest.plugin.description, desc_width);
-                    // First line with name and version
-                    println!(
-                        "{:<name_width$}  {:<version_width$}  {}",
-                        plugin.manifest.plugin.name.green(),
-                        format!("v{}", plugin.manifest.plugin.version).dimmed(),
-                        desc_lines.first().unwrap_or(&String::new()).dimmed(),
-                        name_width = name_width,
-                        version_width = version_width,
-                    );
-                    // Continuation lines indented under description
-                    for line in desc_lines.iter().skip(1) {
-                        println!("{}{}", indent, line.dimmed());
-                    }
+                let mut table = Table::new(&["NAME", "VERSION", "HEALTH", "HOOKS", "LAST HOOK"]);
+                for info in &output {
+                    let problems = info.health.problems();
+                    let last_hook = match &info.last_hook {
+                        Some(last) => {
+                            // Time of day for today's runs, the date for older ones
+                            let at = DateTime::parse_from_rfc3339(&last.at)
+                                .map(|at| at.with_timezone(&Local))
+                                .map(|at| {
+                                    if at.date_naive() == Local::now().date_naive() {
+                                        at.format("%H:%M").to_string()
+                                    } else {
+                                        at.format("%m-%d").to_string()
+                                    }
+                                })
+                                .unwrap_or_default();
+                            let text = format!("{} {} {}", last.outcome, last.event, at);
+                            match last.outcome {
+                                HookOutcome::Allow => text.normal(),
+                                HookOutcome::Block => text.yellow(),
+                                HookOutcome::Error | HookOutcome::Timeout => text.red(),
+                            }
+                        }
+                        None => "-".dimmed(),
+                    };
+                    let name = match &info.namespace {
+                        Some(namespace) => format!("{}/{}", namespace, info.name),
+                        None => info.name.clone(),
+                    };
+                    table.add_row(vec![
+                        if info.enabled {
+                            name.green()
+                        } else {
+                            format!("{} (disabled)", name).bright_black()
+                        },
+                        format!("v{}", info.version).dimmed(),
+                        if problems.is_empty() { "ok".green() } else { problems.join(", ").yellow() },
+                        if info.health.hooks.is_empty() {
+                            "-".dimmed()
+                        } else {
+                            info.health.hooks.join(", ").normal()
+                        },
+                        last_hook,
+                    ]);
                 }
+                table.print();
 
+                for collision in crate::plugin::collisions(&plugins) {
+                    println!();
+                    println!("{} {}; only the first is used", presenter::warn(), collision);
+                }
                 println!();
                 println!("{}", format!("{} plugins", plugins.len()).dimmed());
             }
@@ -191,34 +382,153 @@ fn list(format: OutputFormat, config: &Config) -> Result<()> {
     Ok(())
 }
 
-fn install(source: &str, dev: bool, force: bool, config: &Config) -> Result<()> {
+fn install(source: &str, reference: Option<&str>, dev: bool, force: bool, config: &Config) -> Result<()> {
     println!(
         "{} Installing plugin: {} {}{}",
-        "→".blue(),
+        presenter::arrow(),
         source.cyan(),
         if dev { "(dev mode) ".dimmed().to_string() } else { String::new() },
         if force { "(force) ".dimmed().to_string() } else { String::new() },
     );
 
     let source_path = Path::new(source);
+    if dev && !source_path.exists() {
+        return Err(CliError::new(ErrorCode::InvalidInput, "--dev links a local checkout")
+            .with_hint(format!("Clone {} and pass its path", source))
+            .into());
+    }
+    if dev && pack::is_archive(source_path) {
+        return Err(
+            CliError::new(ErrorCode::InvalidInput, "--dev links a local checkout, not an archive")
+                .with_hint("Unpack it somewhere and pass that directory")
+                .into(),
+        );
+    }
+    let dir = fetch(source, reference, config)?;
+    // Local paths are recorded absolute, so `update` finds them from anywhere
+    let install_source = match fs::canonicalize(source_path) {
+        Ok(path) => path.display().to_string(),
+        Err(_) => source.to_string(),
+    };
+    install_from_path(&dir, dev, force, &install_source, reference, config)
+}
 
-    // Install from local path only
+/// Directory holding the plugin `source` names: a local path, an archive
+/// from `pais plugin pack` (unpacked into the cache), a git URL (at
+/// `reference`, if given), or a registry entry
+fn fetch(source: &str, reference: Option<&str>, config: &Config) -> Result<PathBuf> {
+    let source_path = Path::new(source);
     if source_path.exists() {
-        install_from_path(source_path, dev, force, config)
-    } else {
-        eyre::bail!(
-            "Source not found: {}\n\
-             Install plugins from local paths or git repos.\n\
-             Examples:\n\
-               pais plugin install ./my-plugin\n\
-               pais plugin install ~/repos/scottidler/my-plugin",
-            source
-        );
+        if reference.is_some() {
+            return Err(CliError::new(ErrorCode::InvalidInput, "--ref applies to git sources, not local paths").into());
+        }
+        if pack::is_archive(source_path) {
+            let stem = source_path.file_name().unwrap_or_default().to_string_lossy();
+            let stem = stem.trim_end_matches(".tar.gz").trim_end_matches(".tgz");
+            let dir = registry::cache_dir().join("archives").join(stem);
+            let manifest = pack::unpack(source_path, &dir)?;
+            println!(
+                "  {} Unpacked {} v{} ({} files, checksums verified)",
+                presenter::ok(),
+                manifest.name,
+                manifest.plugin_version,
+                manifest.files.len()
+            );
+            return Ok(dir);
+        }
+        return Ok(source_path.to_path_buf());
+    }
+    if registry::is_git_url(source) {
+        return registry::fetch_git(source, reference, &config.network);
+    }
+
+    let registries = registry::load_all(&config.plugins.registries, &config.network)?;
+    let (found, entry) = registry::resolve(&registries, source).ok_or_else(|| {
+        CliError::not_found("Plugin", source)
+            .with_hint("Pass a local path or git URL, or find a name with `pais plugin search <term>`")
+    })?;
+    let name = source.rsplit('/').next().unwrap_or(source);
+    println!(
+        "  {} Found in registry {}{}",
+        presenter::arrow(),
+        found.name.cyan(),
+        entry.version.as_ref().map(|v| format!(" (v{})", v)).unwrap_or_default()
+    );
+    registry::fetch_source(found, name, entry, reference, &config.network)
+}
+
+/// A plugin found by `pais plugin search`
+#[derive(Serialize)]
+struct SearchHit {
+    name: String,
+    registry: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    description: String,
+    installed: bool,
+}
+
+fn search(term: &str, format: OutputFormat, config: &Config) -> Result<()> {
+    let registries = registry::load_all(&config.plugins.registries, &config.network)?;
+    let installed = plugin_names(config)?;
+
+    let mut hits = Vec::new();
+    for loaded in &registries {
+        for (name, entry) in loaded.registry.search(term) {
+            hits.push(SearchHit {
+                name: name.to_string(),
+                registry: loaded.name.clone(),
+                version: entry.version.clone(),
+                description: entry.description.clone().unwrap_or_default(),
+                installed: installed.iter().any(|n| n == name),
+            });
+        }
+    }
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&hits)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&hits)?),
+        OutputFormat::Text => {
+            if hits.is_empty() {
+                println!("{} No plugins match '{}'", presenter::info(), term);
+                return Ok(());
+            }
+            let mut table = Table::new(&["NAME", "REGISTRY", "VERSION", "DESCRIPTION"]);
+            for hit in &hits {
+                let name = if hit.installed {
+                    format!("{} (installed)", hit.name).green()
+                } else {
+                    hit.name.normal()
+                };
+                table.add_row(vec![
+                    name,
+                    hit.registry.cyan(),
+                    hit.version
+                        .as_ref()
+                        .map(|v| format!("v{}", v))
+                        .unwrap_or_default()
+                        .dimmed(),
+                    presenter::truncate(&hit.description, 60).dimmed(),
+                ]);
+            }
+            table.print();
+            println!();
+            println!("{}", "Install with: pais plugin install <name>".dimmed());
+        }
     }
+    Ok(())
 }
 
-/// Install a plugin from a local path
-fn install_from_path(source_path: &Path, dev: bool, force: bool, config: &Config) -> Result<()> {
+/// Install a plugin from a local path, recording `install_source` (what the
+/// user asked for) so `update` can fetch it again
+fn install_from_path(
+    source_path: &Path,
+    dev: bool,
+    force: bool,
+    install_source: &str,
+    git_ref: Option<&str>,
+    config: &Config,
+) -> Result<()> {
     // Load and validate the plugin
     let plugin = load_plugin(source_path).context("Failed to load plugin from source")?;
     let plugin_name = &plugin.manifest.plugin.name;
@@ -227,6 +537,75 @@ fn install_from_path(source_path: &Path, dev: bool, force: bool, config: &Config
     let plugins_dir = Config::expand_path(&config.paths.plugins);
     let dest_path = plugins_dir.join(plugin_name);
 
+    let problems = new_problems(config, |manifests| {
+        manifests.retain(|m| &m.plugin.name != plugin_name);
+        manifests.push(plugin.manifest.clone());
+    })?;
+    if !problems.is_empty() {
+        if !force {
+            let code = if problems.iter().any(Problem::is_conflict) {
+                ErrorCode::Conflict
+            } else {
+                ErrorCode::NotFound
+            };
+            return Err(dependency_error(
+                code,
+                format!("Plugin '{}' doesn't resolve against the installed plugins", plugin_name),
+                &problems,
+            )
+            .with_hint("Install a provider first (find one with: pais plugin search <contract>), or use --force")
+            .into());
+        }
+        for problem in &problems {
+            println!("  {} {}", presenter::warn(), problem);
+        }
+    }
+
+    if let Some(verifier) = Verifier::from_config(&config.plugins.signing) {
+        match verifier.check_source(source_path, plugin_name)? {
+            Some(warning) => println!("  {} {}", presenter::warn(), warning),
+            None => println!("  {} Signature verified", presenter::ok()),
+        }
+    }
+
+    // Another directory declaring the name would collide with this one
+    let qualified = plugin.manifest.plugin.qualified_name();
+    let installed = installed_plugins(config)?;
+    if let Some(other) = installed
+        .iter()
+        .find(|p| &p.manifest.plugin.name == plugin_name && p.path != dest_path)
+    {
+        return Err(CliError::new(
+            ErrorCode::Conflict,
+            format!(
+                "Plugin name '{}' is already used by {} in {}",
+                plugin_name,
+                other.manifest.plugin.qualified_name(),
+                other.path.display()
+            ),
+        )
+        .with_hint(format!(
+            "Plugin names must be unique; remove it first: pais plugin remove {}",
+            other.path.file_name().unwrap_or_default().to_string_lossy()
+        ))
+        .into());
+    }
+    if let Some(existing) = installed.iter().find(|p| p.path == dest_path)
+        && existing.manifest.plugin.qualified_name() != qualified
+        && !force
+    {
+        return Err(CliError::new(
+            ErrorCode::Conflict,
+            format!(
+                "Installing {} would replace {}",
+                qualified,
+                existing.manifest.plugin.qualified_name()
+            ),
+        )
+        .with_hint("Use --force to replace it")
+        .into());
+    }
+
     // Check if already installed
     if dest_path.exists() {
         if force {
@@ -258,26 +637,68 @@ fn install_from_path(source_path: &Path, dev: bool, force: bool, config: &Config
         }
         println!(
             "  {} Linked {} → {}",
-            "✓".green(),
+            presenter::ok(),
             dest_path.display(),
             source_path.display()
         );
     } else {
         // Copy the plugin directory
         copy_dir_recursive(source_path, &dest_path)?;
-        println!("  {} Installed to {}", "✓".green(), dest_path.display());
+        println!("  {} Installed to {}", presenter::ok(), dest_path.display());
     }
 
     println!(
         "  {} {} v{}",
-        "✓".green(),
+        presenter::ok(),
         plugin_name.green(),
         plugin.manifest.plugin.version
     );
 
+    let manifest = &plugin.manifest.plugin;
+    let authors = (!manifest.authors.is_empty()).then(|| manifest.authors.join(", "));
+    let mut provenance = Provenance::from_dir(source_path).with_declared(authors, manifest.license.clone());
+    provenance.install_source = Some(install_source.to_string());
+    provenance.git_ref = git_ref.map(String::from);
+    state::record(StateEvent::PluginInstalled {
+        name: plugin_name.clone(),
+        version: manifest.version.clone(),
+        source: provenance.source.clone(),
+        dev,
+    });
+    if let Err(e) = provenance::record(ArtifactKind::Plugin, plugin_name, provenance) {
+        log::warn!("Failed to record provenance of plugin '{}': {:#}", plugin_name, e);
+    }
+    write_lockfile(config);
+
     Ok(())
 }
 
+/// Resolution problems a change to the installed manifests would introduce
+fn new_problems(config: &Config, change: impl FnOnce(&mut Vec<PluginManifest>)) -> Result<Vec<Problem>> {
+    let mut manifests: Vec<PluginManifest> = installed_plugins(config)?.into_iter().map(|p| p.manifest).collect();
+    let before = resolve::resolve(&manifests).problems;
+    change(&mut manifests);
+    Ok(resolve::resolve(&manifests)
+        .problems
+        .into_iter()
+        .filter(|p| !before.contains(p))
+        .collect())
+}
+
+fn dependency_error(code: ErrorCode, message: String, problems: &[Problem]) -> CliError {
+    let details: Vec<String> = problems.iter().map(|p| format!("  - {}", p)).collect();
+    CliError::new(code, format!("{}:\n{}", message, details.join("\n")))
+}
+
+/// Rewrite plugins.lock for the installed plugins; failing only warns
+fn write_lockfile(config: &Config) {
+    let mut manager = PluginManager::new(Config::expand_path(&config.paths.plugins));
+    let written = manager.discover().and_then(|_| lockfile::write(&manager));
+    if let Err(e) = written {
+        log::warn!("Failed to write {}: {:#}", lockfile::Lockfile::path().display(), e);
+    }
+}
+
 fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<()> {
     fs::create_dir_all(dst)?;
 
@@ -305,16 +726,41 @@ fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<()> {
 fn remove(name: &str, force: bool, config: &Config) -> Result<()> {
     println!(
         "{} Removing plugin: {} {}",
-        "→".blue(),
+        presenter::arrow(),
         name.cyan(),
         if force { "(force) ".dimmed().to_string() } else { String::new() },
     );
 
+    // A directory name picks out one of several plugins declaring the same name
     let plugins_dir = Config::expand_path(&config.paths.plugins);
-    let plugin_path = plugins_dir.join(name);
-
-    if !plugin_path.exists() {
-        eyre::bail!("Plugin not found: {}", name);
+    let plugin_path = match plugins_dir.join(name) {
+        dir if !name.contains('/') && dir.symlink_metadata().is_ok() => dir,
+        _ => find_plugin(name, config)?.path,
+    };
+    let declared = load_plugin(&plugin_path).map(|p| p.manifest.plugin.name);
+    let name = declared.as_deref().unwrap_or(name);
+    let duplicate = installed_plugins(config)?
+        .iter()
+        .any(|p| p.manifest.plugin.name == name && p.path != plugin_path);
+
+    let problems = if duplicate {
+        Vec::new()
+    } else {
+        new_problems(config, |manifests| manifests.retain(|m| m.plugin.name != name))?
+    };
+    if !problems.is_empty() {
+        if !force {
+            return Err(dependency_error(
+                ErrorCode::Conflict,
+                format!("Other plugins depend on '{}'", name),
+                &problems,
+            )
+            .with_hint("Remove them first, or use --force")
+            .into());
+        }
+        for problem in &problems {
+            println!("  {} {}", presenter::warn(), problem);
+        }
     }
 
     // Check if it's a symlink
@@ -326,46 +772,170 @@ fn remove(name: &str, force: bool, config: &Config) -> Result<()> {
         fs::remove_dir_all(&plugin_path).context("Failed to remove plugin directory")?;
     }
 
-    println!("  {} Removed plugin: {}", "✓".green(), name);
+    // The remaining plugin of the same name keeps its provenance and build
+    if !duplicate {
+        state::record(StateEvent::PluginRemoved { name: name.to_string() });
+        if let Err(e) = provenance::forget(ArtifactKind::Plugin, name) {
+            log::warn!("Failed to forget provenance of plugin '{}': {:#}", name, e);
+        }
+        build::forget(name);
+    }
+    write_lockfile(config);
+
+    println!(
+        "  {} Removed plugin: {} ({})",
+        presenter::ok(),
+        name,
+        plugin_path.display()
+    );
 
     Ok(())
 }
 
-fn update(name: &str, config: &Config) -> Result<()> {
-    println!("{} Updating plugin: {}", "→".blue(), name.cyan());
-
-    // Check if plugin is installed
-    let plugin = match find_plugin(name, config) {
-        Ok(p) => p,
-        Err(_) => {
-            eyre::bail!("Plugin '{}' is not installed", name);
+/// Update one plugin, or every installed plugin for "all"
+fn update(name: &str, reference: Option<&str>, force: bool, config: &Config) -> Result<()> {
+    if name != "all" {
+        return update_plugin(name, reference, force, config);
+    }
+    if reference.is_some() {
+        return Err(CliError::new(ErrorCode::InvalidInput, "--ref applies to a single plugin").into());
+    }
+    let plugins = installed_plugins(config)?;
+    let mut failed = 0;
+    for plugin in &plugins {
+        let name = &plugin.manifest.plugin.name;
+        if let Err(e) = update_plugin(name, None, force, config) {
+            failed += 1;
+            println!("{} {}: {:#}", presenter::fail(), name.red(), e);
         }
-    };
+    }
+    if failed > 0 {
+        eyre::bail!("{} of {} plugins failed to update", failed, plugins.len());
+    }
+    Ok(())
+}
 
-    let current_version = &plugin.manifest.plugin.version;
-    println!("  Current version: {}", current_version.dimmed());
+/// Fetch a plugin again from where it was installed, back up the installed
+/// copy and reinstall; the backup is restored if the reinstall fails
+fn update_plugin(name: &str, reference: Option<&str>, force: bool, config: &Config) -> Result<()> {
+    println!("{} Updating plugin: {}", presenter::arrow(), name.cyan());
+    let plugin = find_plugin(name, config)?;
+    let name = &plugin.manifest.plugin.name;
 
-    // Check if it's a dev install (symlink)
-    let plugins_dir = Config::expand_path(&config.paths.plugins);
-    let plugin_path = plugins_dir.join(name);
-    if plugin_path.symlink_metadata()?.file_type().is_symlink() {
-        println!("  {} Plugin is installed in dev mode (symlink)", "⚠".yellow());
+    if plugin.path.symlink_metadata()?.file_type().is_symlink() {
+        println!("  {} Plugin is installed in dev mode (symlink)", presenter::warn());
         println!("    Update the source directory directly (git pull, etc.)");
         return Ok(());
     }
 
-    // For non-dev plugins, suggest reinstallation from source
-    println!("  {} To update, reinstall from source:", "→".blue());
-    println!("    pais plugin remove {}", name);
-    println!("    pais plugin install /path/to/source");
+    let recorded = commands::provenance::load().get(ArtifactKind::Plugin, name).cloned();
+    let (source, recorded_ref) = match &recorded {
+        Some(p) => match &p.install_source {
+            Some(source) => (source.clone(), p.git_ref.clone()),
+            // Installed before sources were recorded: a git remote or local path still works
+            None if registry::is_git_url(&p.source) || Path::new(&p.source).is_dir() => (p.source.clone(), None),
+            None => (String::new(), None),
+        },
+        None => (String::new(), None),
+    };
+    if source.is_empty() {
+        return Err(CliError::new(
+            ErrorCode::NotFound,
+            format!("No install source recorded for plugin '{}'", name),
+        )
+        .with_hint("Reinstall it once with: pais plugin install <source> --force")
+        .into());
+    }
+    let reference = reference.map(String::from).or(recorded_ref);
+    let dir = fetch(&source, reference.as_deref(), config)?;
+
+    let fetched = load_plugin(&dir).context("Failed to load the fetched plugin")?;
+    if &fetched.manifest.plugin.name != name {
+        return Err(CliError::new(
+            ErrorCode::Conflict,
+            format!(
+                "{} now holds plugin '{}', not '{}'",
+                source, fetched.manifest.plugin.name, name
+            ),
+        )
+        .into());
+    }
+
+    let old_version = &plugin.manifest.plugin.version;
+    let new_version = &fetched.manifest.plugin.version;
+    let old_commit = recorded.as_ref().and_then(|p| p.commit.clone());
+    let new_commit = Provenance::from_dir(&dir).commit;
+    let unchanged = old_version == new_version
+        && match (&old_commit, &new_commit) {
+            (Some(old), Some(new)) => old == new,
+            _ => build::source_hash(&plugin.path)? == build::source_hash(&dir)?,
+        };
+    if unchanged && !force {
+        println!("  {} Already up to date (v{})", presenter::ok(), old_version);
+        return Ok(());
+    }
+    let short = |c: &Option<String>| c.as_deref().map(|c| format!(" ({})", &c[..c.len().min(8)]));
+    println!(
+        "  {} v{}{} → v{}{}",
+        presenter::arrow(),
+        old_version,
+        short(&old_commit).unwrap_or_default(),
+        new_version,
+        short(&new_commit).unwrap_or_default()
+    );
 
+    let backup = backup_plugin(&plugin.path, name, old_version)?;
+    if let Err(e) = install_from_path(&dir, false, false, &source, reference.as_deref(), config) {
+        if plugin.path.exists() {
+            fs::remove_dir_all(&plugin.path).ok();
+        }
+        move_dir(&backup, &plugin.path)?;
+        return Err(e.wrap_err(format!("Update failed; restored v{}", old_version)));
+    }
+    println!("  {} Previous version kept in {}", presenter::ok(), backup.display());
     Ok(())
 }
 
+/// Directory holding copies of plugins replaced by `update`
+fn backups_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("pais")
+        .join("plugin-backups")
+}
+
+/// Backups kept per plugin; older ones are deleted
+const KEEP_BACKUPS: usize = 3;
+
+/// Move the installed copy of a plugin into the backups, pruning old ones
+fn backup_plugin(path: &Path, name: &str, version: &str) -> Result<PathBuf> {
+    let dir = backups_dir().join(name);
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let backup = dir.join(format!("{}-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"), version));
+    move_dir(path, &backup)?;
+
+    let mut backups: Vec<PathBuf> = fs::read_dir(&dir)?.flatten().map(|e| e.path()).collect();
+    backups.sort();
+    for old in backups.iter().rev().skip(KEEP_BACKUPS) {
+        fs::remove_dir_all(old).ok();
+    }
+    Ok(backup)
+}
+
+/// Rename `from` to `to`, copying when they are on different filesystems
+fn move_dir(from: &Path, to: &Path) -> Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    copy_dir_recursive(from, to)?;
+    fs::remove_dir_all(from).with_context(|| format!("Failed to remove {}", from.display()))
+}
+
 fn info(name: &str, config: &Config) -> Result<()> {
     let plugin = find_plugin(name, config)?;
+    let name = plugin.manifest.plugin.name.as_str();
 
-    println!("{}", plugin.manifest.plugin.name.bold());
+    println!("{}", plugin.manifest.plugin.qualified_name().bold());
     println!();
     println!("  {} {}", "Version:".dimmed(), plugin.manifest.plugin.version);
     println!("  {} {}", "Description:".dimmed(), plugin.manifest.plugin.description);
@@ -388,6 +958,29 @@ fn info(name: &str, config: &Config) -> Result<()> {
         println!("  {} {}", "Repository:".dimmed(), repo);
     }
 
+    let authors = &plugin.manifest.plugin.authors;
+    let (provenance, recorded) = commands::provenance::resolve(
+        &commands::provenance::load(),
+        ArtifactKind::Plugin,
+        name,
+        &plugin.path,
+        || {
+            (
+                (!authors.is_empty()).then(|| authors.join(", ")),
+                plugin.manifest.plugin.license.clone(),
+            )
+        },
+    );
+    if let Some(verifier) = Verifier::from_config(&config.plugins.signing) {
+        println!("  {} {}", "Signature:".dimmed(), verifier.verify(&plugin.path));
+    }
+
+    println!();
+    println!("  {}", "Provenance:".cyan());
+    for (label, value) in commands::provenance::details(&provenance, recorded) {
+        println!("    {} {}", format!("{}:", label).dimmed(), value);
+    }
+
     // Show provides/consumes
     if !plugin.manifest.provides.is_empty() {
         println!();
@@ -411,35 +1004,75 @@ fn info(name: &str, config: &Config) -> Result<()> {
 
 /// Find a plugin by name in the plugins directory
 pub fn find_plugin(name: &str, config: &Config) -> Result<crate::plugin::Plugin> {
-    let plugins_dir = Config::expand_path(&config.paths.plugins);
+    let plugins = installed_plugins(config)?;
+    let matching: Vec<crate::plugin::Plugin> = plugins
+        .iter()
+        .filter(|p| p.manifest.plugin.matches(name))
+        .cloned()
+        .collect();
+
+    if matching.len() > 1 {
+        let collision = &crate::plugin::collisions(&matching)[0];
+        let mut qualified: Vec<&str> = collision.plugins.iter().map(|(q, _)| q.as_str()).collect();
+        qualified.sort();
+        qualified.dedup();
+        let hint = if qualified.len() > 1 && !name.contains('/') {
+            format!(
+                "Pick one with a qualified name ({}), and remove or rename the other: hooks and settings go by name alone",
+                qualified.join(", ")
+            )
+        } else {
+            "Remove or rename one of the directories: plugin names must be unique".to_string()
+        };
+        return Err(CliError::new(ErrorCode::Conflict, collision.to_string())
+            .with_hint(hint)
+            .into());
+    }
+    if let Some(plugin) = matching.into_iter().next() {
+        return Ok(plugin);
+    }
 
-    // Try exact path first
-    let plugin_path = plugins_dir.join(name);
-    if plugin_path.exists() {
+    // A directory whose manifest doesn't load: report why
+    let plugin_path = Config::expand_path(&config.paths.plugins).join(name);
+    if plugin_path.join("plugin.yaml").exists() {
         return load_plugin(&plugin_path).context(format!("Failed to load plugin '{}'", name));
     }
 
-    // Scan for matching plugin
-    if plugins_dir.exists() {
-        for entry in fs::read_dir(&plugins_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+    let short = name.rsplit('/').next().unwrap_or(name);
+    let others: Vec<String> = plugins
+        .iter()
+        .filter(|p| p.manifest.plugin.name == short)
+        .map(|p| p.manifest.plugin.qualified_name())
+        .collect();
+    let hint = if others.is_empty() {
+        "List installed plugins with: pais plugin list".to_string()
+    } else {
+        format!("Installed as: {}", others.join(", "))
+    };
+    Err(CliError::not_found("Plugin", name).with_hint(hint).into())
+}
 
-            if !path.is_dir() {
-                continue;
-            }
+/// Load every installed plugin, sorted by name
+pub fn installed_plugins(config: &Config) -> Result<Vec<crate::plugin::Plugin>> {
+    let plugins_dir = Config::expand_path(&config.paths.plugins);
+    let mut plugins = Vec::new();
 
-            let Ok(plugin) = load_plugin(&path) else {
-                continue;
-            };
+    if !plugins_dir.exists() {
+        return Ok(plugins);
+    }
 
-            if plugin.manifest.plugin.name == name {
-                return Ok(plugin);
+    for entry in fs::read_dir(&plugins_dir).context("Failed to read plugins directory")? {
+        let path = entry?.path();
+        if path.is_dir() && path.join("plugin.yaml").exists() {
+            match load_plugin(&path) {
+                Ok(plugin) => plugins.push(plugin),
+                Err(e) => log::warn!("Failed to load plugin from {}: {}", path.display(), e),
             }
         }
     }
 
-    eyre::bail!("Plugin not found: {}", name)
+    plugins.sort_by(|a, b| (&a.manifest.plugin.name, &a.path).cmp(&(&b.manifest.plugin.name, &b.path)));
+    Ok(plugins)
 }
 
 fn new(
@@ -452,10 +1085,18 @@ fn new(
     let output_path = path
         .cloned()
         .unwrap_or_else(|| std::path::PathBuf::from(format!("./{}", name)));
+    let language = language.to_lowercase();
+    let language = language.as_str();
+    if !["python", "rust", "node", "shell"].contains(&language) {
+        eyre::bail!(
+            "Unsupported language: {}. Use 'python', 'rust', 'node' or 'shell'",
+            language
+        );
+    }
 
     println!(
         "{} Creating new {} plugin: {} ({})",
-        "→".blue(),
+        presenter::arrow(),
         plugin_type.cyan(),
         name.green(),
         language.dimmed(),
@@ -491,8 +1132,23 @@ fn new(
             let cargo_toml = generate_cargo_toml(name);
             fs::write(output_path.join("Cargo.toml"), cargo_toml).context("Failed to write Cargo.toml")?;
         }
+        "node" => {
+            let index_ts = generate_node_main(name);
+            fs::write(output_path.join("src").join("index.ts"), index_ts).context("Failed to write index.ts")?;
+
+            let package_json = generate_package_json(name);
+            fs::write(output_path.join("package.json"), package_json).context("Failed to write package.json")?;
+        }
         _ => {
-            eyre::bail!("Unsupported language: {}. Use 'python' or 'rust'", language);
+            let main_sh = generate_shell_main(name);
+            let main_path = output_path.join("src").join("main.sh");
+            fs::write(&main_path, main_sh).context("Failed to write main.sh")?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&main_path, fs::Permissions::from_mode(0o755))
+                    .context("Failed to make main.sh executable")?;
+            }
         }
     }
 
@@ -506,16 +1162,18 @@ fn new(
     let readme = generate_readme(name, plugin_type, language);
     fs::write(output_path.join("README.md"), readme).context("Failed to write README.md")?;
 
-    println!("  {} Created plugin scaffold", "✓".green());
+    println!("  {} Created plugin scaffold", presenter::ok());
     println!();
     println!("  Next steps:");
     println!("    1. cd {}", output_path.display());
     println!("    2. Edit plugin.yaml to configure contracts");
-    if language == "python" {
-        println!("    3. Implement your plugin in src/main.py");
-    } else {
-        println!("    3. Implement your plugin in src/main.rs");
-    }
+    let entry = match language {
+        "python" => "src/main.py",
+        "rust" => "src/main.rs",
+        "node" => "src/index.ts",
+        _ => "src/main.sh",
+    };
+    println!("    3. Implement your plugin in {}", entry);
     println!("    4. pais plugin install --dev {}", output_path.display());
 
     Ok(())
@@ -543,13 +1201,21 @@ consumes: {{}}
 # Plugin configuration schema
 config: {{}}
 
+# Environment for hooks and `pais run` (optional)
+# env:
+#   GITHUB_TOKEN: {{dotenv: GITHUB_TOKEN}}   # environment or ~/.config/pais/.env
+#   API_KEY: {{keyring: my-api-key}}         # OS keyring, service "pais"
+#   API_BASE: {{value: https://api.example.com}}
+
 # Hook subscriptions (scripts to run on events)
 # hooks:
 #   PreToolUse:
-#     - script: hooks/validate.py
-#       matcher: Bash  # optional - only run for specific tool
+#     - script: hooks/validate.{hook_ext}
+#       matcher: Bash  # optional - tool names, as a regex (Edit|Write)
+#       paths: ["src/**/*.py"]  # optional - only for these files
+#       command: "git push"  # optional - only when the command matches
 #   Stop:
-#     - script: hooks/capture.py
+#     - script: hooks/capture.{hook_ext}
 
 # Build configuration
 build:
@@ -558,7 +1224,17 @@ build:
         name = name,
         plugin_type = plugin_type,
         language = language,
-        build_type = if language == "rust" { "cargo" } else { "uv" },
+        hook_ext = match language {
+            "node" => "ts",
+            "shell" => "sh",
+            _ => "py",
+        },
+        build_type = match language {
+            "rust" => "cargo",
+            "node" => "npm",
+            "shell" => "none",
+            _ => "uv",
+        },
     )
 }
 
@@ -571,6 +1247,17 @@ fn generate_python_main(name: &str) -> String {
 import json
 import sys
 
+DESCRIPTION = {{
+    "actions": [
+        {{"name": "greet", "description": "Say hello"}},
+        {{"name": "version", "description": "Print the plugin version"}},
+    ],
+    "hooks": [],
+    "provides": [],
+    "consumes": [],
+    "config": {{}},
+}}
+
 
 def main():
     """Main entry point."""
@@ -581,7 +1268,11 @@ def main():
     action = sys.argv[1]
     args = sys.argv[2:]
 
-    if action == "greet":
+    if action == "describe":
+        # Report what this plugin implements; `pais plugin verify` checks it
+        # against plugin.yaml and `pais run` completes action names from it
+        print(json.dumps(DESCRIPTION))
+    elif action == "greet":
         name = args[0] if args else "World"
         print(json.dumps({{"message": f"Hello, {{name}}!"}}))
     elif action == "version":
@@ -604,6 +1295,19 @@ fn generate_rust_main(name: &str) -> String {
 
 use std::env;
 
+/// Reported by the `describe` action; `pais plugin verify` checks it
+/// against plugin.yaml and `pais run` completes action names from it
+const DESCRIPTION: &str = r#"{{
+  "actions": [
+    {{"name": "greet", "description": "Say hello"}},
+    {{"name": "version", "description": "Print the plugin version"}}
+  ],
+  "hooks": [],
+  "provides": [],
+  "consumes": [],
+  "config": {{}}
+}}"#;
+
 fn main() {{
     let args: Vec<String> = env::args().collect();
 
@@ -616,6 +1320,9 @@ fn main() {{
     let action_args = &args[2..];
 
     match action.as_str() {{
+        "describe" => {{
+            println!("{{}}", DESCRIPTION);
+        }}
         "greet" => {{
             let name = action_args.first().map(|s| s.as_str()).unwrap_or("World");
             println!(r#"{{{{"message": "Hello, {{}}!"}}}}"#, name);
@@ -634,6 +1341,118 @@ fn main() {{
     )
 }
 
+fn generate_node_main(name: &str) -> String {
+    format!(
+        r#"#!/usr/bin/env -S npx tsx
+/**
+ * {name} - A PAIS plugin
+ */
+
+// Reported by the `describe` action; `pais plugin verify` checks it
+// against plugin.yaml and `pais run` completes action names from it
+const DESCRIPTION = {{
+  actions: [
+    {{ name: "greet", description: "Say hello" }},
+    {{ name: "version", description: "Print the plugin version" }},
+  ],
+  hooks: [],
+  provides: [],
+  consumes: [],
+  config: {{}},
+}};
+
+function main(argv: string[]): number {{
+  const [action, ...args] = argv;
+  if (!action) {{
+    console.log(JSON.stringify({{ error: "No action specified" }}));
+    return 1;
+  }}
+
+  switch (action) {{
+    case "describe":
+      console.log(JSON.stringify(DESCRIPTION));
+      return 0;
+    case "greet":
+      console.log(JSON.stringify({{ message: `Hello, ${{args[0] ?? "World"}}!` }}));
+      return 0;
+    case "version":
+      console.log(JSON.stringify({{ version: "0.1.0" }}));
+      return 0;
+    default:
+      console.log(JSON.stringify({{ error: `Unknown action: ${{action}}` }}));
+      return 1;
+  }}
+}}
+
+process.exit(main(process.argv.slice(2)));
+"#,
+        name = name,
+    )
+}
+
+fn generate_package_json(name: &str) -> String {
+    format!(
+        r#"{{
+  "name": "{name}",
+  "version": "0.1.0",
+  "description": "A PAIS plugin",
+  "private": true,
+  "type": "module",
+  "bin": {{
+    "{name}": "src/index.ts"
+  }},
+  "scripts": {{
+    "start": "tsx src/index.ts"
+  }},
+  "devDependencies": {{
+    "@types/node": "^22.0.0",
+    "tsx": "^4.19.0",
+    "typescript": "^5.6.0"
+  }}
+}}
+"#,
+        name = name,
+    )
+}
+
+fn generate_shell_main(name: &str) -> String {
+    format!(
+        r#"#!/bin/sh
+# {name} - A PAIS plugin
+set -eu
+
+# Reported by the `describe` action; `pais plugin verify` checks it
+# against plugin.yaml and `pais run` completes action names from it
+DESCRIPTION='{{"actions":[{{"name":"greet","description":"Say hello"}},{{"name":"version","description":"Print the plugin version"}}],"hooks":[],"provides":[],"consumes":[],"config":{{}}}}'
+
+if [ $# -lt 1 ]; then
+    echo '{{"error": "No action specified"}}'
+    exit 1
+fi
+
+action=$1
+shift
+
+case "$action" in
+    describe)
+        echo "$DESCRIPTION"
+        ;;
+    greet)
+        printf '{{"message": "Hello, %s!"}}\n' "${{1:-World}}"
+        ;;
+    version)
+        echo '{{"version": "0.1.0"}}'
+        ;;
+    *)
+        printf '{{"error": "Unknown action: %s"}}\n' "$action"
+        exit 1
+        ;;
+esac
+"#,
+        name = name,
+    )
+}
+
 fn generate_cargo_toml(name: &str) -> String {
     format!(
         r#"[package]
@@ -734,26 +1553,32 @@ MIT
         name = name,
         plugin_type = plugin_type,
         language = language,
-        dev_instructions = if language == "rust" {
-            "```bash\ncargo build --release\n```"
-        } else {
-            "```bash\nuv sync\n```"
+        dev_instructions = match language {
+            "rust" => "```bash\ncargo build --release\n```",
+            "node" => "```bash\nnpm install\nnpx tsx src/index.ts greet\n```",
+            "shell" => "No build step; edit `src/main.sh` and run it with `sh`.",
+            _ => "```bash\nuv sync\n```",
         },
     )
 }
 
 fn verify(name: &str, format: OutputFormat, config: &Config) -> Result<()> {
     let plugin = find_plugin(name, config)?;
+    let name = plugin.manifest.plugin.name.as_str();
     let spec = &plugin.manifest.verification;
 
     // Check if there are any verification checks defined
     if !has_checks(spec) && spec.guide.is_none() {
         // Fall back to basic verification
-        return verify_basic(name, &plugin, format);
+        return verify_basic(name, &plugin, format, config);
     }
 
     // Run automated verification checks
-    let result = verify_plugin(name, &plugin.path, spec)?;
+    let mut result = verify_plugin(name, &plugin.path, spec)?;
+    add_build_check(&plugin, &mut result.checks);
+    add_config_check(&plugin, config, &mut result.checks);
+    let described = add_describe_checks(&plugin, &mut result.checks);
+    result.finish();
 
     match format {
         OutputFormat::Json => {
@@ -764,6 +1589,9 @@ fn verify(name: &str, format: OutputFormat, config: &Config) -> Result<()> {
         }
         OutputFormat::Text => {
             print_verification_result(&result);
+            if !described {
+                print_describe_note();
+            }
 
             // If there's a guide, mention it
             if let Some(ref guide) = spec.guide {
@@ -784,7 +1612,7 @@ fn verify(name: &str, format: OutputFormat, config: &Config) -> Result<()> {
 }
 
 /// Basic verification when no verification spec is defined
-fn verify_basic(name: &str, plugin: &crate::plugin::Plugin, format: OutputFormat) -> Result<()> {
+fn verify_basic(name: &str, plugin: &crate::plugin::Plugin, format: OutputFormat, config: &Config) -> Result<()> {
     let mut checks = Vec::new();
 
     // Check manifest is valid (already loaded, so this passed)
@@ -825,6 +1653,47 @@ fn verify_basic(name: &str, plugin: &crate::plugin::Plugin, format: OutputFormat
             passed: true,
             message: Some("Mixed language".to_string()),
         },
+        crate::plugin::manifest::PluginLanguage::Node => {
+            let entry = describe::node_entry(&plugin.path);
+            let has_package = plugin.path.join("package.json").exists();
+            let has_node = which::which("node").is_ok();
+            let message = match (&entry, has_package, has_node) {
+                (None, _, _) => "src/index.ts, src/index.js or src/index.mjs not found".to_string(),
+                (Some(_), false, _) => "package.json not found".to_string(),
+                (Some(_), true, false) => "node is not on PATH".to_string(),
+                (Some(path), true, true) => path.strip_prefix(&plugin.path).unwrap_or(path).display().to_string(),
+            };
+            crate::plugin::verify::CheckResult {
+                name: "entry-point".to_string(),
+                passed: entry.is_some() && has_package && has_node,
+                message: Some(message),
+            }
+        }
+        crate::plugin::manifest::PluginLanguage::Shell => {
+            let main_sh = describe::shell_entry(&plugin.path);
+            crate::plugin::verify::CheckResult {
+                name: "entry-point".to_string(),
+                passed: main_sh.exists(),
+                message: if main_sh.exists() {
+                    Some("src/main.sh".to_string())
+                } else {
+                    Some("src/main.sh not found".to_string())
+                },
+            }
+        }
+        crate::plugin::manifest::PluginLanguage::Wasm => {
+            let component = crate::plugin::wasm::component(&plugin.path);
+            let message = match (&component, crate::plugin::wasm::ENABLED) {
+                (None, _) => "plugin.wasm not found".to_string(),
+                (Some(path), true) => path.strip_prefix(&plugin.path).unwrap_or(path).display().to_string(),
+                (Some(_), false) => "pais was built without the wasm feature".to_string(),
+            };
+            crate::plugin::verify::CheckResult {
+                name: "entry-point".to_string(),
+                passed: component.is_some() && crate::plugin::wasm::ENABLED,
+                message: Some(message),
+            }
+        }
     };
     checks.push(entry_point_check);
 
@@ -838,15 +1707,17 @@ fn verify_basic(name: &str, plugin: &crate::plugin::Plugin, format: OutputFormat
         });
     }
 
-    let passed_count = checks.iter().filter(|c| c.passed).count();
-    let all_passed = passed_count == checks.len();
+    add_build_check(plugin, &mut checks);
+    add_config_check(plugin, config, &mut checks);
+    let described = add_describe_checks(plugin, &mut checks);
 
-    let result = crate::plugin::verify::VerificationResult {
+    let mut result = crate::plugin::verify::VerificationResult {
         plugin_name: name.to_string(),
-        passed: all_passed,
+        passed: false,
         checks,
-        summary: format!("{}/{} checks passed", passed_count, passed_count),
+        summary: String::new(),
     };
+    result.finish();
 
     match format {
         OutputFormat::Json => {
@@ -857,6 +1728,9 @@ fn verify_basic(name: &str, plugin: &crate::plugin::Plugin, format: OutputFormat
         }
         OutputFormat::Text => {
             print_verification_result(&result);
+            if !described {
+                print_describe_note();
+            }
             println!();
             println!(
                 "{}",
@@ -865,9 +1739,87 @@ fn verify_basic(name: &str, plugin: &crate::plugin::Plugin, format: OutputFormat
         }
     }
 
+    if !result.passed {
+        std::process::exit(1);
+    }
+
     Ok(())
 }
 
+/// Check that a plugin needing a build has been built from its current sources
+fn add_build_check(plugin: &crate::plugin::Plugin, checks: &mut Vec<crate::plugin::verify::CheckResult>) {
+    if !build::required(plugin) {
+        return;
+    }
+    let name = &plugin.manifest.plugin.name;
+    let (passed, message) = match build::status(plugin) {
+        Ok(build::BuildStatus::Current(marker)) => {
+            (true, format!("built {}", marker.built_at.format("%Y-%m-%d %H:%M")))
+        }
+        Ok(build::BuildStatus::Stale(_)) => (
+            false,
+            format!("sources changed since the last build; run `pais plugin build {}`", name),
+        ),
+        Ok(build::BuildStatus::Missing) => (false, format!("not built; run `pais plugin build {}`", name)),
+        Err(e) => (false, format!("{:#}", e)),
+    };
+    checks.push(crate::plugin::verify::CheckResult {
+        name: "build".to_string(),
+        passed,
+        message: Some(message),
+    });
+}
+
+/// Check the user's settings against the manifest's `config:` schema, if it has one
+fn add_config_check(
+    plugin: &crate::plugin::Plugin,
+    config: &Config,
+    checks: &mut Vec<crate::plugin::verify::CheckResult>,
+) {
+    if plugin.manifest.config.is_empty() && plugin.manifest.env.is_empty() {
+        return;
+    }
+    let resolved = plugin_config::resolve(
+        &plugin.manifest,
+        config.plugins.settings.get(&plugin.manifest.plugin.name),
+    );
+    checks.push(crate::plugin::verify::CheckResult {
+        name: "config".to_string(),
+        passed: resolved.is_ok(),
+        message: resolved.err().map(|e| e.message),
+    });
+}
+
+/// Compare the manifest with the plugin's `describe` answer
+///
+/// Returns false when the plugin doesn't implement `describe` (or isn't
+/// built yet), in which case no checks are added.
+fn add_describe_checks(plugin: &crate::plugin::Plugin, checks: &mut Vec<crate::plugin::verify::CheckResult>) -> bool {
+    match describe::describe(&plugin.path, &plugin.manifest) {
+        Ok(Some(description)) => {
+            checks.extend(describe::check_manifest(&plugin.manifest, &description));
+            true
+        }
+        Ok(None) => false,
+        Err(e) => {
+            checks.push(crate::plugin::verify::CheckResult {
+                name: "describe: handshake".to_string(),
+                passed: false,
+                message: Some(format!("{:#}", e)),
+            });
+            true
+        }
+    }
+}
+
+fn print_describe_note() {
+    println!();
+    println!(
+        "{}",
+        "Note: Plugin does not answer 'describe'; manifest was not checked against the implementation.".dimmed()
+    );
+}
+
 fn install_guide(name: &str, config: &Config) -> Result<()> {
     let plugin = find_plugin(name, config)?;
 
@@ -898,3 +1850,211 @@ fn install_guide(name: &str, config: &Config) -> Result<()> {
 
     Ok(())
 }
+
+fn build(name: Option<&str>, offline: bool, config: &Config) -> Result<()> {
+    let plugins = match name {
+        Some(name) if name != "all" => {
+            let plugin = find_plugin(name, config)?;
+            if build::plan(&plugin).is_none() {
+                println!("{} {} has nothing to build", presenter::info(), name.cyan());
+                return Ok(());
+            }
+            vec![plugin]
+        }
+        _ => installed_plugins(config)?
+            .into_iter()
+            .filter(|p| build::plan(p).is_some())
+            .collect(),
+    };
+    if plugins.is_empty() {
+        println!("{} No plugins to build", presenter::info());
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for plugin in &plugins {
+        let name = &plugin.manifest.plugin.name;
+        match build::build(plugin, offline) {
+            Ok(BuildOutcome::Cached) => {
+                println!("{} {} restored from the build cache", presenter::ok(), name.green())
+            }
+            Ok(BuildOutcome::Built) => println!("{} {} built", presenter::ok(), name.green()),
+            Ok(BuildOutcome::Nothing) => println!("{} {} has nothing to build", presenter::info(), name.cyan()),
+            Err(e) if plugins.len() == 1 => return Err(e),
+            Err(e) => {
+                failed += 1;
+                println!("{} {}: {:#}", presenter::fail(), name.red(), e);
+            }
+        }
+    }
+    if failed > 0 {
+        eyre::bail!("{} of {} plugins failed to build", failed, plugins.len());
+    }
+    Ok(())
+}
+
+/// Sign an installed plugin or a plugin directory, creating the key on first use
+fn sign(target: &str, key: Option<&Path>, config: &Config) -> Result<()> {
+    let dir = Path::new(target);
+    let plugin = if dir.join("plugin.yaml").is_file() {
+        load_plugin(dir).context("Failed to load plugin")?
+    } else {
+        find_plugin(target, config)?
+    };
+    let name = &plugin.manifest.plugin.name;
+
+    let key_path = key.map_or_else(
+        || Config::expand_path(Path::new(&config.plugins.signing.key)),
+        Path::to_path_buf,
+    );
+    let secret = if key_path.exists() {
+        SecretKey::load(&key_path)?
+    } else {
+        let secret = SecretKey::generate()?;
+        secret.save(&key_path)?;
+        println!("{} Created signing key {}", presenter::ok(), key_path.display());
+        println!(
+            "  Share {} so others can trust your plugins",
+            signing::public_key_path(&key_path).display()
+        );
+        secret
+    };
+
+    let written = signing::sign(&plugin.path, name, &secret)?;
+    println!(
+        "{} Signed {} with key {} ({})",
+        presenter::ok(),
+        name.green(),
+        secret.public().id(),
+        written.display()
+    );
+    Ok(())
+}
+
+/// Write a plugin, installed or in a directory, to a distributable archive
+fn pack(target: &str, output: Option<PathBuf>, format: OutputFormat, config: &Config) -> Result<()> {
+    let dir = Path::new(target);
+    let plugin = if dir.join("plugin.yaml").is_file() {
+        load_plugin(dir).context("Failed to load plugin")?
+    } else {
+        find_plugin(target, config)?
+    };
+    let output = output.unwrap_or_else(|| PathBuf::from(pack::file_name(&plugin.manifest)));
+    let summary = pack::pack(&plugin, &output)?;
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&summary)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&summary)?),
+        OutputFormat::Text => {
+            println!(
+                "{} Packed {} v{} ({} files) into {} ({} KB)",
+                presenter::ok(),
+                summary.name.green(),
+                summary.version,
+                summary.files,
+                summary.archive.display(),
+                summary.bytes.div_ceil(1024)
+            );
+            println!(
+                "  Install it with: {}",
+                format!("pais plugin install {}", summary.archive.display()).cyan()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Enable or disable an installed plugin, keeping its files
+fn show_config(name: &str, format: OutputFormat, config: &Config) -> Result<()> {
+    let plugin = find_plugin(name, config)?;
+    let name = &plugin.manifest.plugin.name;
+    let resolved = plugin_config::resolve(&plugin.manifest, config.plugins.settings.get(name))?;
+
+    match format {
+        OutputFormat::Json | OutputFormat::Yaml => {
+            // Secrets stay out of machine-readable output too; env values never serialize
+            let mut resolved = resolved;
+            for setting in resolved.settings.iter_mut().filter(|s| s.secret && s.value.is_some()) {
+                setting.value = Some(serde_json::Value::String("********".to_string()));
+            }
+            if matches!(format, OutputFormat::Json) {
+                println!("{}", serde_json::to_string_pretty(&resolved)?);
+            } else {
+                println!("{}", serde_yaml::to_string(&resolved)?);
+            }
+        }
+        OutputFormat::Text => {
+            if resolved.settings.is_empty() && resolved.env.is_empty() {
+                println!("{}", format!("{} declares no configuration", name).dimmed());
+                return Ok(());
+            }
+            if !resolved.env.is_empty() {
+                let mut table = Table::new(&["ENV", "VALUE", "SOURCE"]);
+                for var in &resolved.env {
+                    let value = if var.source.is_secret() { "********" } else { var.value.as_str() };
+                    table.add_row(vec![var.name.cyan(), value.normal(), var.source.to_string().dimmed()]);
+                }
+                table.print();
+                if resolved.settings.is_empty() {
+                    return Ok(());
+                }
+                println!();
+            }
+            let mut table = Table::new(&["KEY", "VALUE", "SOURCE"]);
+            for setting in &resolved.settings {
+                let value = match &setting.value {
+                    None => "(unset)".dimmed(),
+                    Some(_) if setting.secret => "********".normal(),
+                    Some(serde_json::Value::String(s)) => s.normal(),
+                    Some(other) => other.to_string().normal(),
+                };
+                let source = match (setting.source, &setting.env) {
+                    (Some(plugin_config::Source::Env), Some(env)) => env.clone(),
+                    (Some(source), _) => source.to_string(),
+                    (None, _) => String::new(),
+                };
+                table.add_row(vec![setting.key.cyan(), value, source.dimmed()]);
+            }
+            table.print();
+            println!();
+            println!(
+                "{}",
+                format!(
+                    "Set values under plugins.{}.config in pais.yaml or in {}",
+                    name,
+                    plugin_config::path(name).display()
+                )
+                .dimmed()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn set_enabled(name: &str, enabled: bool, config: &Config) -> Result<()> {
+    let plugin = find_plugin(name, config)?;
+    let name = &plugin.manifest.plugin.name;
+
+    let mut manager = PluginManager::new(Config::expand_path(&config.paths.plugins));
+    manager.discover()?;
+    let before = manager.resolve().problems;
+
+    if !disabled::set(&disabled::path(), name, !enabled)? {
+        let state = if enabled { "enabled" } else { "disabled" };
+        println!("{} {} is already {}", presenter::info(), name.cyan(), state);
+        return Ok(());
+    }
+    if enabled {
+        println!("{} Enabled {}", presenter::ok(), name.green());
+    } else {
+        println!("{} Disabled {}; its files are kept", presenter::ok(), name.cyan());
+    }
+
+    // Consumers of a disabled provider stop resolving too
+    manager.discover()?;
+    for problem in manager.resolve().problems.iter().filter(|p| !before.contains(p)) {
+        println!("  {} {}", presenter::warn(), problem);
+    }
+    write_lockfile(config);
+    Ok(())
+}
@@ -0,0 +1,262 @@
+//! Policy CLI commands
+
+use colored::*;
+use eyre::{Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+use crate::cli::{OutputFormat, PolicyAction};
+use crate::config::Config;
+use crate::hook::HookEvent;
+use crate::policy::{
+    CompiledRule, PROJECT_POLICY_FILE, PolicyEffect, PolicyEngine, PolicyFile, PolicyRule, RuleSource, RuleTrace,
+};
+use crate::presenter;
+use crate::trust::{self, TrustStore};
+
+pub fn run(action: PolicyAction, format: OutputFormat, config: &Config) -> Result<()> {
+    match action {
+        PolicyAction::Eval { payload, event, cwd } => eval(payload.as_ref(), &event, cwd.as_ref(), format, config),
+        PolicyAction::List { cwd } => list(cwd.as_ref(), format, config),
+    }
+}
+
+/// Result of `pais policy eval`
+#[derive(Debug, Serialize)]
+struct EvalOutput {
+    event: String,
+    decision: Option<RuleTrace>,
+    rules: Vec<RuleTrace>,
+}
+
+/// Evaluate all rules against a payload without side effects
+fn eval(
+    payload_path: Option<&PathBuf>,
+    event: &str,
+    cwd: Option<&PathBuf>,
+    format: OutputFormat,
+    config: &Config,
+) -> Result<()> {
+    let hook_event = HookEvent::from_str(event).ok_or_else(|| eyre::eyre!("Unknown hook event: {}", event))?;
+
+    let payload_str = match payload_path {
+        Some(path) => fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?,
+        None => {
+            let mut buffer = String::new();
+            io::stdin()
+                .read_to_string(&mut buffer)
+                .context("Failed to read payload from stdin")?;
+            buffer
+        }
+    };
+    let payload: serde_json::Value = serde_json::from_str(&payload_str).context("Failed to parse payload JSON")?;
+
+    let project_dir = cwd
+        .cloned()
+        .or_else(|| payload.get("cwd").and_then(|v| v.as_str()).map(PathBuf::from));
+
+    // Same as hook dispatch: project rules only count in trusted workspaces
+    let mut engine = PolicyEngine::new(&config.policy.rules);
+    if let Some(ref dir) = project_dir {
+        if trust::resolve(&config.security.trust, &TrustStore::load(), dir).is_trusted() {
+            engine = engine.with_project_overrides(dir);
+        } else {
+            log::warn!(
+                "Ignoring {} in untrusted workspace {}",
+                PROJECT_POLICY_FILE,
+                dir.display()
+            );
+        }
+    }
+
+    let rules = engine.trace(hook_event, &payload);
+    let decision = rules.iter().find(|t| t.applies && t.matched).cloned();
+
+    let output = EvalOutput {
+        event: hook_event.to_string(),
+        decision,
+        rules,
+    };
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&output)?),
+        OutputFormat::Text => print_eval(&output),
+    }
+
+    if output
+        .decision
+        .as_ref()
+        .is_some_and(|d| d.action == PolicyEffect::Block && d.rate_limit.is_none())
+    {
+        std::process::exit(2);
+    }
+
+    Ok(())
+}
+
+fn print_eval(output: &EvalOutput) {
+    println!("{} Policy evaluation for {}", presenter::arrow(), output.event.cyan());
+    println!();
+
+    if output.rules.is_empty() {
+        println!("  {}", "(no policy rules defined)".dimmed());
+        return;
+    }
+
+    for trace in &output.rules {
+        let marker = if !trace.applies {
+            "-".dimmed()
+        } else if trace.error.is_some() {
+            presenter::fail()
+        } else if trace.matched {
+            "●".green()
+        } else {
+            "○".bright_black()
+        };
+
+        println!(
+            "  {} {} [{}] {}",
+            marker,
+            trace.rule.bold(),
+            trace.action,
+            trace.source.dimmed()
+        );
+        if let Some(ref error) = trace.error {
+            println!("      {}", error.red());
+        }
+        if trace.matched
+            && let Some(ref limit) = trace.rate_limit
+        {
+            println!(
+                "      {}",
+                format!(
+                    "rate-limited: fires after {} matches in {}s",
+                    limit.max, limit.window_secs
+                )
+                .dimmed()
+            );
+        }
+    }
+
+    println!();
+    match &output.decision {
+        Some(decision) => {
+            let action = decision.action.to_string();
+            let action = match decision.action {
+                PolicyEffect::Block => action.red(),
+                PolicyEffect::Warn => action.yellow(),
+                PolicyEffect::Allow => action.green(),
+                PolicyEffect::Log => action.dimmed(),
+            };
+            println!("Decision: {} (rule {})", action.bold(), decision.rule.cyan());
+        }
+        None => println!("Decision: {}", "no rule matched".dimmed()),
+    }
+}
+
+/// Rule info for list output
+#[derive(Debug, Serialize)]
+struct RuleInfo {
+    name: String,
+    source: String,
+    events: Vec<String>,
+    when: String,
+    action: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// List rules in evaluation order, including ones that fail to compile
+fn list(cwd: Option<&PathBuf>, format: OutputFormat, config: &Config) -> Result<()> {
+    let project_dir = match cwd {
+        Some(dir) => dir.clone(),
+        None => std::env::current_dir().context("Failed to get current directory")?,
+    };
+
+    let mut rules = Vec::new();
+
+    let project_file = project_dir.join(PROJECT_POLICY_FILE);
+    let ignored = project_file.exists()
+        && !trust::resolve(&config.security.trust, &TrustStore::load(), &project_dir).is_trusted();
+    if project_file.exists() && !ignored {
+        let file = PolicyFile::load(&project_file)?;
+        collect_rules(&mut rules, &file.rules, RuleSource::Project(project_file));
+    }
+    collect_rules(&mut rules, &config.policy.rules, RuleSource::Global);
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&rules)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&rules)?),
+        OutputFormat::Text => {
+            println!("{}", "Policy rules".bold());
+            println!();
+
+            if ignored {
+                println!(
+                    "  {} {} is ignored until {} (untrusted workspace)",
+                    presenter::warn(),
+                    PROJECT_POLICY_FILE.cyan(),
+                    format!("pais trust {}", project_dir.display()).cyan()
+                );
+                println!();
+            }
+
+            if rules.is_empty() {
+                println!("  {}", "(no policy rules defined)".dimmed());
+                println!();
+                println!(
+                    "  Add rules under {} in pais.yaml or in {}",
+                    "policy.rules".cyan(),
+                    PROJECT_POLICY_FILE.cyan()
+                );
+                return Ok(());
+            }
+
+            for rule in &rules {
+                let status = if rule.error.is_some() { presenter::fail() } else { "●".green() };
+                println!(
+                    "  {} {} [{}] {}",
+                    status,
+                    rule.name.bold(),
+                    rule.action,
+                    rule.source.dimmed()
+                );
+                let events = if rule.events.is_empty() { "all".to_string() } else { rule.events.join(", ") };
+                println!("      Events: {}", events.cyan());
+                println!("      When:   {}", rule.when.dimmed());
+                if let Some(ref error) = rule.error {
+                    println!("      {}", error.red());
+                }
+            }
+
+            if !config.policy.enabled {
+                println!();
+                println!(
+                    "  {} Policy evaluation is disabled (policy.enabled: false)",
+                    presenter::warn()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn collect_rules(out: &mut Vec<RuleInfo>, rules: &[PolicyRule], source: RuleSource) {
+    for rule in rules {
+        let error = CompiledRule::compile(rule.clone(), source.clone())
+            .err()
+            .map(|e| format!("{:#}", e));
+        out.push(RuleInfo {
+            name: rule.name.clone(),
+            source: source.to_string(),
+            events: rule.events.clone(),
+            when: rule.when.clone(),
+            action: rule.action.to_string(),
+            error,
+        });
+    }
+}
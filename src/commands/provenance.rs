@@ -0,0 +1,223 @@
+//! Provenance commands
+//!
+//! `pais provenance report` lists every installed skill, plugin, agent and
+//! bundle with where it came from. Items installed before provenance was
+//! recorded (or copied in by hand) are inferred from git and license files
+//! and marked as such. See [`crate::provenance`].
+
+use colored::*;
+use eyre::Result;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::bundle::manager::BundleManager;
+use crate::cli::{OutputFormat, ProvenanceAction};
+use crate::commands::agent::{agent_names, agents_dir};
+use crate::config::Config;
+use crate::plugin::loader::load_plugin;
+use crate::presenter::{self, Table};
+use crate::provenance::{ArtifactKind, Provenance, ProvenanceFile};
+use crate::skill::loader::subdirectories;
+use crate::skill::parser::parse_skill_md;
+
+/// Run a provenance subcommand
+pub fn run(action: ProvenanceAction, format: OutputFormat, config: &Config) -> Result<()> {
+    match action {
+        ProvenanceAction::Report { kind } => report(kind, format, config),
+    }
+}
+
+/// One installed item and where it came from
+#[derive(Debug, Serialize)]
+struct ReportItem {
+    kind: ArtifactKind,
+    name: String,
+    /// False when the provenance was inferred rather than recorded at install
+    recorded: bool,
+    #[serde(flatten)]
+    provenance: Provenance,
+}
+
+/// Recorded provenance of an item, or what can be inferred from its files
+///
+/// Returns whether the provenance was recorded at install time.
+pub fn resolve(
+    file: &ProvenanceFile,
+    kind: ArtifactKind,
+    name: &str,
+    path: &Path,
+    declared: impl FnOnce() -> (Option<String>, Option<String>),
+) -> (Provenance, bool) {
+    match file.get(kind, name) {
+        Some(provenance) => (provenance.clone(), true),
+        None => {
+            let (author, license) = declared();
+            (Provenance::from_dir(path).with_declared(author, license), false)
+        }
+    }
+}
+
+/// Label/value lines for the provenance section of an `info` command
+pub fn details(provenance: &Provenance, recorded: bool) -> Vec<(&'static str, String)> {
+    let mut lines = vec![("Source", provenance.source.clone())];
+    if let Some(commit) = &provenance.commit {
+        lines.push(("Commit", commit.clone()));
+    }
+    if let Some(from) = &provenance.install_source {
+        let reference = provenance.git_ref.as_ref().map(|r| format!(" (ref {})", r));
+        lines.push(("Installed from", format!("{}{}", from, reference.unwrap_or_default())));
+    }
+    lines.push(("Author", provenance.author.clone().unwrap_or_else(|| "unknown".into())));
+    lines.push((
+        "License",
+        provenance.license.clone().unwrap_or_else(|| "unknown".into()),
+    ));
+    if recorded {
+        lines.push(("Installed", provenance.installed.format("%Y-%m-%d %H:%M").to_string()));
+    } else {
+        lines.push(("Installed", "not recorded (inferred from files)".into()));
+    }
+    lines
+}
+
+/// Load the provenance file, warning (not failing) when it's unreadable
+pub fn load() -> ProvenanceFile {
+    ProvenanceFile::load(&ProvenanceFile::path()).unwrap_or_else(|e| {
+        log::warn!("{:#}", e);
+        ProvenanceFile::default()
+    })
+}
+
+/// Every installed item of the requested kinds
+fn collect(kind: Option<ArtifactKind>, config: &Config) -> Result<Vec<ReportItem>> {
+    let file = load();
+    let wanted = |k: ArtifactKind| kind.is_none_or(|kind| kind == k);
+    let mut items = Vec::new();
+    let mut push = |kind: ArtifactKind, name: String, path: PathBuf, declared: (Option<String>, Option<String>)| {
+        let (provenance, recorded) = resolve(&file, kind, &name, &path, || declared);
+        items.push(ReportItem {
+            kind,
+            name,
+            recorded,
+            provenance,
+        });
+    };
+
+    if wanted(ArtifactKind::Skill) {
+        for dir in subdirectories(&Config::expand_path(&config.paths.skills))? {
+            let skill_md = dir.join("SKILL.md");
+            if !skill_md.exists() {
+                continue;
+            }
+            let name = dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let declared = parse_skill_md(&skill_md)
+                .map(|m| (m.author, m.license))
+                .unwrap_or_default();
+            push(ArtifactKind::Skill, name, dir, declared);
+        }
+    }
+
+    if wanted(ArtifactKind::Plugin) {
+        for dir in subdirectories(&Config::expand_path(&config.paths.plugins))? {
+            if !dir.join("plugin.yaml").exists() {
+                continue;
+            }
+            let name = dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let declared = load_plugin(&dir)
+                .map(|p| {
+                    let authors = p.manifest.plugin.authors;
+                    (
+                        (!authors.is_empty()).then(|| authors.join(", ")),
+                        p.manifest.plugin.license,
+                    )
+                })
+                .unwrap_or_default();
+            push(ArtifactKind::Plugin, name, dir, declared);
+        }
+    }
+
+    if wanted(ArtifactKind::Agent) {
+        let dir = agents_dir(config);
+        for name in agent_names(config).unwrap_or_default() {
+            let path = dir.join(format!("{}.yaml", name));
+            push(ArtifactKind::Agent, name, path, (None, None));
+        }
+    }
+
+    if wanted(ArtifactKind::Bundle) {
+        let mut manager = BundleManager::new(
+            Config::pais_dir().join("bundles"),
+            Config::expand_path(&config.paths.plugins),
+        );
+        manager.discover()?;
+        for bundle in manager.list() {
+            let info = &bundle.manifest.bundle;
+            push(
+                ArtifactKind::Bundle,
+                info.name.clone(),
+                bundle.path.clone(),
+                (info.author.clone(), info.license.clone()),
+            );
+        }
+    }
+
+    items.sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| a.name.cmp(&b.name)));
+    Ok(items)
+}
+
+fn report(kind: Option<ArtifactKind>, format: OutputFormat, config: &Config) -> Result<()> {
+    let items = collect(kind, config)?;
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&items)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&items)?),
+        OutputFormat::Text => {
+            if items.is_empty() {
+                println!("{} Nothing installed", presenter::info());
+                return Ok(());
+            }
+
+            let unknown = || "-".dimmed();
+            let mut table = Table::new(&["KIND", "NAME", "SOURCE", "COMMIT", "LICENSE", "AUTHOR"]);
+            for item in &items {
+                let p = &item.provenance;
+                let name = if item.recorded {
+                    item.name.cyan()
+                } else {
+                    format!("{}*", item.name).yellow()
+                };
+                table.add_row(vec![
+                    item.kind.to_string().normal(),
+                    name,
+                    presenter::truncate(&p.source, 50).normal(),
+                    p.commit
+                        .as_deref()
+                        .map(|c| c.chars().take(8).collect::<String>().normal())
+                        .unwrap_or_else(unknown),
+                    p.license
+                        .as_deref()
+                        .map(|l| l.normal())
+                        .unwrap_or_else(|| "unknown".red()),
+                    p.author
+                        .as_deref()
+                        .map(|a| presenter::truncate(a, 30).normal())
+                        .unwrap_or_else(unknown),
+                ]);
+            }
+            table.print();
+
+            let inferred = items.iter().filter(|i| !i.recorded).count();
+            if inferred > 0 {
+                println!();
+                println!(
+                    "{} {} of {} items have no recorded provenance (*); shown values are inferred from their files",
+                    presenter::warn(),
+                    inferred,
+                    items.len()
+                );
+                println!("  Reinstall them with pais to record where they came from");
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,87 @@
+//! Weekly review commands
+//!
+//! Generates the weekly review described in [`crate::history::weekly`] and
+//! stores it as a history entry.
+
+use colored::*;
+use eyre::Result;
+use serde::Serialize;
+
+use crate::cli::{OutputFormat, ReviewAction};
+use crate::clock;
+use crate::config::Config;
+use crate::history::HistoryStore;
+use crate::history::capture::EventCapture;
+use crate::history::categorize::Categories;
+use crate::history::encrypt::Encryption;
+use crate::history::weekly::{self, Week, WeeklyReview};
+use crate::observability::flush_buffered;
+use crate::presenter;
+
+/// Run a review subcommand
+pub fn run(action: ReviewAction, format: OutputFormat, config: &Config) -> Result<()> {
+    match action {
+        ReviewAction::Generate { week, no_save } => generate(week, no_save, format, config),
+    }
+}
+
+#[derive(Serialize)]
+struct GeneratedReview<'a> {
+    #[serde(flatten)]
+    review: &'a WeeklyReview,
+    /// History entry the review was stored as
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entry: Option<String>,
+}
+
+fn generate(weeks_ago: u32, no_save: bool, format: OutputFormat, config: &Config) -> Result<()> {
+    let history_dir = Config::expand_path(&config.paths.history);
+    let categories = Categories::from_config(&config.history);
+    let category = config.history.review.weekly_category.as_str();
+    categories.check(category)?;
+    let store = HistoryStore::new(history_dir.clone())
+        .with_categories(categories)
+        .with_index(config.history.index.enabled)
+        .with_encryption(Encryption::from_config(&config.history.encryption));
+    flush_buffered(&history_dir);
+    let capture = EventCapture::new(history_dir.clone(), true);
+
+    let week = Week::ago(clock::now_local().date_naive(), weeks_ago);
+    let (review, existing) = weekly::generate(&store, &capture, &history_dir, week, category)?;
+
+    let entry = if no_save {
+        None
+    } else {
+        let entry = review.to_entry(category, existing.as_ref());
+        store.store(&entry)?;
+        Some(entry.id)
+    };
+
+    match format {
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&GeneratedReview { review: &review, entry })?
+        ),
+        OutputFormat::Yaml => println!(
+            "{}",
+            serde_yaml::to_string(&GeneratedReview { review: &review, entry })?
+        ),
+        OutputFormat::Text => {
+            println!("# {}\n", review.title());
+            print!("{}", review.to_markdown());
+            println!();
+            if let Some(id) = entry {
+                let verb = if existing.is_some() { "Updated" } else { "Stored" };
+                println!(
+                    "{} {} as {} in {} ({} open action item(s))",
+                    presenter::ok(),
+                    verb,
+                    id.cyan(),
+                    category,
+                    review.open_items()
+                );
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,67 +1,78 @@
 use colored::*;
 use eyre::{Context, Result};
-use std::process::Command;
 
 use crate::commands::plugin::find_plugin;
 use crate::config::Config;
+use crate::error::{CliError, ErrorCode};
+use crate::plugin::build::{self, BuildOutcome};
+use crate::plugin::config as plugin_config;
+use crate::plugin::describe;
+use crate::plugin::disabled::DisabledPlugins;
 use crate::plugin::manifest::PluginLanguage;
+use crate::plugin::wasm::{self, Contracts};
+use crate::plugin::{Plugin, PluginManager};
+use crate::presenter;
+use crate::secrets::redact;
 
 pub fn run(plugin_name: &str, action: &str, args: &[String], config: &Config) -> Result<()> {
     log::info!("Running plugin: {} action: {}", plugin_name, action);
 
     // Find the plugin
     let plugin = find_plugin(plugin_name, config)?;
-
-    // Determine how to execute based on language
-    let output = match plugin.manifest.plugin.language {
-        PluginLanguage::Python => execute_python(&plugin.path, action, args)?,
-        PluginLanguage::Rust => execute_rust(&plugin.path, action, args)?,
-        PluginLanguage::Mixed => {
-            // Try Python first, then Rust
-            let python_main = plugin.path.join("src").join("main.py");
-            if python_main.exists() {
-                execute_python(&plugin.path, action, args)?
-            } else {
-                execute_rust(&plugin.path, action, args)?
-            }
-        }
-    };
-
-    // Print output
-    print!("{}", output);
-
-    Ok(())
-}
-
-fn execute_python(plugin_path: &std::path::Path, action: &str, args: &[String]) -> Result<String> {
-    let main_py = plugin_path.join("src").join("main.py");
-
-    if !main_py.exists() {
-        eyre::bail!("Python main not found: {}", main_py.display());
+    let name = &plugin.manifest.plugin.name;
+    if DisabledPlugins::load().is_disabled(name) {
+        return Err(
+            CliError::new(ErrorCode::InvalidInput, format!("Plugin '{}' is disabled", name))
+                .with_hint(format!("Enable it with: pais plugin enable {}", name))
+                .into(),
+        );
     }
-
-    // Use plugin's venv Python if it exists, otherwise fall back to system python3
-    let venv_python = plugin_path.join(".venv").join("bin").join("python");
-    let python_cmd = if venv_python.exists() {
-        venv_python
-    } else {
-        std::path::PathBuf::from("python3")
-    };
-
-    let mut cmd = Command::new(&python_cmd);
-    cmd.arg(&main_py).arg(action);
-
-    for arg in args {
-        cmd.arg(arg);
+    let resolved = plugin_config::resolve(&plugin.manifest, config.plugins.settings.get(name))?;
+    let (vars, secrets) = (resolved.vars(), resolved.secrets());
+    let language = &plugin.manifest.plugin.language;
+    if let PluginLanguage::Wasm = language {
+        return run_wasm(&plugin, action, args, &vars, &secrets, config);
     }
 
-    cmd.current_dir(plugin_path);
+    // Resolve the entry point, building Rust plugins on first use
+    let mut cmd = match describe::entrypoint(&plugin.path, language) {
+        Some(cmd) => cmd,
+        None if matches!(language, PluginLanguage::Python) => {
+            eyre::bail!(
+                "Python main not found: {}",
+                plugin.path.join("src").join("main.py").display()
+            );
+        }
+        None if matches!(language, PluginLanguage::Node) => {
+            eyre::bail!(
+                "Node entry point not found: expected src/index.ts, src/index.js or src/index.mjs in {}",
+                plugin.path.display()
+            );
+        }
+        None if matches!(language, PluginLanguage::Shell) => {
+            eyre::bail!(
+                "Shell main not found: {}",
+                describe::shell_entry(&plugin.path).display()
+            );
+        }
+        None => {
+            build_rust(&plugin)?;
+            describe::entrypoint(&plugin.path, language)
+                .ok_or_else(|| eyre::eyre!("Rust plugin binary not found after build"))?
+        }
+    };
 
-    let output = cmd.output().context("Failed to execute Python plugin")?;
+    let output = cmd
+        .arg(action)
+        .args(args)
+        .envs(vars)
+        .output()
+        .context("Failed to execute plugin")?;
 
+    // The failure ends up in logs, so it's masked; regular output is the caller's
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = redact(&String::from_utf8_lossy(&output.stderr), &secrets);
+        let stdout = redact(&String::from_utf8_lossy(&output.stdout), &secrets);
         eyre::bail!(
             "Plugin failed with exit code {:?}:\n{}\n{}",
             output.status.code(),
@@ -70,62 +81,43 @@ fn execute_python(plugin_path: &std::path::Path, action: &str, args: &[String])
         );
     }
 
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
-}
-
-fn execute_rust(plugin_path: &std::path::Path, action: &str, args: &[String]) -> Result<String> {
-    // Look for built binary
-    let plugin_name = plugin_path.file_name().and_then(|n| n.to_str()).unwrap_or("plugin");
-
-    // Try release first, then debug
-    let binary_paths = [
-        plugin_path.join("target").join("release").join(plugin_name),
-        plugin_path.join("target").join("debug").join(plugin_name),
-    ];
-
-    let binary = binary_paths.iter().find(|p| p.exists());
-
-    let binary = match binary {
-        Some(b) => b,
-        None => {
-            // Try to build it
-            eprintln!("{} Building Rust plugin: {}", "→".blue(), plugin_name.cyan());
-            let status = Command::new("cargo")
-                .arg("build")
-                .arg("--release")
-                .current_dir(plugin_path)
-                .status()
-                .context("Failed to build Rust plugin")?;
+    // Print output
+    print!("{}", String::from_utf8_lossy(&output.stdout));
 
-            if !status.success() {
-                eyre::bail!("Failed to build Rust plugin");
-            }
+    Ok(())
+}
 
-            &binary_paths[0]
+/// Call a wasm plugin's `call` export in-process
+fn run_wasm(
+    plugin: &Plugin,
+    action: &str,
+    args: &[String],
+    vars: &[(String, String)],
+    secrets: &[String],
+    config: &Config,
+) -> Result<()> {
+    let mut manager =
+        PluginManager::new(Config::expand_path(&config.paths.plugins)).with_settings(config.plugins.settings.clone());
+    manager.discover()?;
+    let contracts = Contracts::from_manager(&manager);
+
+    let output = wasm::call(&plugin.path, &plugin.manifest, action, args, None, vars, &contracts)?;
+    eprint!("{}", redact(&output.stderr, secrets));
+    print!("{}", output.stdout);
+    match output.value {
+        Ok(value) => {
+            print!("{}", value);
+            Ok(())
         }
-    };
-
-    let mut cmd = Command::new(binary);
-    cmd.arg(action);
-
-    for arg in args {
-        cmd.arg(arg);
+        Err(message) => eyre::bail!("Plugin failed: {}", redact(&message, secrets)),
     }
+}
 
-    cmd.current_dir(plugin_path);
-
-    let output = cmd.output().context("Failed to execute Rust plugin")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        eyre::bail!(
-            "Plugin failed with exit code {:?}:\n{}\n{}",
-            output.status.code(),
-            stdout,
-            stderr
-        );
+fn build_rust(plugin: &Plugin) -> Result<()> {
+    let plugin_name = &plugin.manifest.plugin.name;
+    eprintln!("{} Building Rust plugin: {}", presenter::arrow(), plugin_name.cyan());
+    if build::build(plugin, false)? == BuildOutcome::Cached {
+        eprintln!("  Restored from the build cache");
     }
-
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    Ok(())
 }
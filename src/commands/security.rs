@@ -4,64 +4,20 @@ use chrono::Local;
 use colored::*;
 use eyre::Result;
 use serde::Serialize;
-use std::fs;
-use terminal_size::{Width, terminal_size};
 
 use crate::cli::{OutputFormat, SecurityAction as CliSecurityAction};
 use crate::config::Config;
-use crate::hook::security::{SecurityEvent, get_security_summary};
+use crate::hook::security::{SecurityEvent, get_security_summary, read_log};
+use crate::presenter::{self, Role, Table, paint};
 
-pub fn run(action: CliSecurityAction, config: &Config) -> Result<()> {
+pub fn run(action: CliSecurityAction, format: OutputFormat, config: &Config) -> Result<()> {
     match action {
-        CliSecurityAction::Tiers { format } => show_tiers(OutputFormat::resolve(format)),
-        CliSecurityAction::Log { days, format } => show_log(days, OutputFormat::resolve(format), config),
+        CliSecurityAction::Tiers => show_tiers(format),
+        CliSecurityAction::Log { days } => show_log(days, format, config),
         CliSecurityAction::Test { command } => test_command(&command, config),
     }
 }
 
-/// Get terminal width, defaulting to 80 if not available
-fn get_terminal_width() -> usize {
-    terminal_size().map(|(Width(w), _)| w as usize).unwrap_or(80)
-}
-
-/// Wrap text to max_width, returning lines
-fn wrap_text(s: &str, max_width: usize) -> Vec<String> {
-    if max_width == 0 {
-        return vec![s.to_string()];
-    }
-
-    let mut lines = Vec::new();
-    let mut current_line = String::new();
-    let mut current_len = 0;
-
-    for word in s.split_whitespace() {
-        let word_len = word.chars().count();
-
-        if current_len == 0 {
-            current_line = word.to_string();
-            current_len = word_len;
-        } else if current_len + 1 + word_len <= max_width {
-            current_line.push(' ');
-            current_line.push_str(word);
-            current_len += 1 + word_len;
-        } else {
-            lines.push(current_line);
-            current_line = word.to_string();
-            current_len = word_len;
-        }
-    }
-
-    if !current_line.is_empty() {
-        lines.push(current_line);
-    }
-
-    if lines.is_empty() {
-        lines.push(String::new());
-    }
-
-    lines
-}
-
 /// Show security tiers
 fn show_tiers(format: OutputFormat) -> Result<()> {
     let tiers = get_security_summary();
@@ -73,51 +29,17 @@ fn show_tiers(format: OutputFormat) -> Result<()> {
                 return Ok(());
             }
 
-            let term_width = get_terminal_width();
-
-            // Calculate column widths
-            let tier_width = 4; // "Tier" or max 2 digits
-            let action_width = tiers.iter().map(|(_, _, a)| a.len()).max().unwrap_or(6);
-
-            // Description gets remaining space (minus columns and gaps)
-            let fixed_width = tier_width + 2 + action_width + 2;
-            let desc_width = term_width.saturating_sub(fixed_width).max(20);
-
-            // Header
-            println!(
-                "{:<tier_width$}  {:<action_width$}  {}",
-                "TIER".bold(),
-                "ACTION".bold(),
-                "DESCRIPTION".bold(),
-                tier_width = tier_width,
-                action_width = action_width,
-            );
-
-            // Tiers
-            let indent = " ".repeat(fixed_width);
+            let mut table = Table::new(&["TIER", "ACTION", "DESCRIPTION"]);
             for (tier, desc, action) in &tiers {
-                let desc_lines = wrap_text(desc, desc_width);
                 let action_colored = match *action {
-                    "Block" => action.red(),
-                    "Warn" => action.yellow(),
+                    "Block" => paint(Role::Error, action),
+                    "Warn" => paint(Role::Warning, action),
                     "Log" => action.dimmed(),
                     _ => action.normal(),
                 };
-
-                // First line with tier and action
-                println!(
-                    "{:<tier_width$}  {:<action_width$}  {}",
-                    tier.to_string().cyan(),
-                    action_colored,
-                    desc_lines.first().unwrap_or(&String::new()).dimmed(),
-                    tier_width = tier_width,
-                    action_width = action_width,
-                );
-                // Continuation lines indented under description
-                for line in desc_lines.iter().skip(1) {
-                    println!("{}{}", indent, line.dimmed());
-                }
+                table.add_row(vec![tier.to_string().cyan(), action_colored, desc.dimmed()]);
             }
+            table.print();
 
             println!();
             println!("{}", format!("{} tiers", tiers.len()).dimmed());
@@ -164,30 +86,12 @@ fn show_tiers(format: OutputFormat) -> Result<()> {
 /// Show security log
 fn show_log(days: usize, format: OutputFormat, config: &Config) -> Result<()> {
     let history_path = Config::expand_path(&config.paths.history);
-    let security_dir = history_path.join("security");
-
-    let mut events = Vec::new();
 
     // Collect events from the last N days
     let today = Local::now().date_naive();
-    for i in 0..days {
-        let date = today - chrono::Duration::days(i as i64);
-        let month_dir = security_dir.join(date.format("%Y-%m").to_string());
-        let log_file = month_dir.join(format!("{}.jsonl", date.format("%Y-%m-%d")));
-
-        if log_file.exists()
-            && let Ok(content) = fs::read_to_string(&log_file)
-        {
-            for line in content.lines() {
-                if line.trim().is_empty() {
-                    continue;
-                }
-                if let Ok(event) = serde_json::from_str::<SecurityEvent>(line) {
-                    events.push(event);
-                }
-            }
-        }
-    }
+    let mut events: Vec<SecurityEvent> = (0..days)
+        .flat_map(|i| read_log(&history_path, today - chrono::Duration::days(i as i64)))
+        .collect();
 
     // Sort by timestamp (newest first)
     events.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
@@ -240,11 +144,14 @@ fn show_log(days: usize, format: OutputFormat, config: &Config) -> Result<()> {
 }
 
 /// Test a command against security patterns
-fn test_command(command: &str, _config: &Config) -> Result<()> {
-    use crate::hook::security::SecurityValidator;
+fn test_command(command: &str, config: &Config) -> Result<()> {
+    use crate::hook::security::{PatternSet, SecurityValidator};
     use crate::hook::{HookHandler, HookResult};
 
-    let validator = SecurityValidator::new(true);
+    let patterns = PatternSet::load(&Config::expand_path(&config.security.patterns))?;
+    let validator = SecurityValidator::new(true)
+        .with_patterns(patterns)
+        .with_explanations(config.security.explain);
 
     // Create a mock payload
     let payload = serde_json::json!({
@@ -264,12 +171,12 @@ fn test_command(command: &str, _config: &Config) -> Result<()> {
             std::process::exit(2);
         }
         HookResult::Allow => {
-            println!("{} Command allowed", "✓".green());
+            println!("{} Command allowed", presenter::ok());
             println!();
             println!("Command: {}", command.dimmed());
         }
         HookResult::Error { message } => {
-            println!("{} Error: {}", "✗".red(), message);
+            println!("{} Error: {}", presenter::fail(), message);
         }
     }
 
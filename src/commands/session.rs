@@ -44,15 +44,20 @@ use colored::Colorize;
 use eyre::{Context, Result, eyre};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::os::unix::fs as unix_fs;
 use std::os::unix::process::CommandExt;
 use std::path::PathBuf;
 use std::process::Command;
 
+use crate::claude::compat;
 use crate::cli::OutputFormat;
-use crate::config::{Config, McpServerConfig};
+use crate::config::{Config, McpServerConfig, SkillPreference};
+use crate::lock::FileLock;
+use crate::prompt::PROFILE_ENV;
+use crate::skill::cache::content_hash;
+use crate::skill::conflicts;
 use crate::skill::indexer::generate_index;
 
 /// MCP server definition as stored in ~/.mcp.json or similar
@@ -84,14 +89,17 @@ pub fn run(
     skill: Option<Vec<String>>,
     list: bool,
     dry_run: bool,
-    format: Option<OutputFormat>,
+    format: OutputFormat,
     claude_args: Vec<String>,
     config: &Config,
 ) -> Result<()> {
     if list {
-        return list_all(OutputFormat::resolve(format), config);
+        return list_all(format, config);
     }
 
+    // Remember the profile for `pais status --prompt` before names are expanded
+    let profile = profile_name(mcp.as_deref(), &config.mcp.profiles);
+
     // Resolve which MCPs to load (expand profiles, apply defaults)
     let mcp_list = resolve_list(mcp, &config.mcp.profiles);
 
@@ -156,7 +164,7 @@ pub fn run(
     }
 
     // Build and exec claude command
-    launch_claude(temp_path, claude_args)
+    launch_claude(temp_path, profile, claude_args)
 }
 
 /// Result of syncing skill symlinks
@@ -176,22 +184,18 @@ fn get_claude_skills_dir() -> Result<PathBuf> {
 
 /// Find the source path for a skill (checks skills dir, then plugins dir)
 /// Returns None if skill doesn't exist in either location
-fn find_skill_source(name: &str, config: &Config) -> Option<PathBuf> {
-    // Check dedicated skills directory first
-    let skills_dir = Config::expand_path(&config.paths.skills);
-    let skill_path = skills_dir.join(name);
-    if skill_path.exists() && skill_path.join("SKILL.md").exists() {
-        return Some(skill_path);
-    }
-
-    // Check plugins directory (plugins can have SKILL.md too)
-    let plugins_dir = Config::expand_path(&config.paths.plugins);
-    let plugin_path = plugins_dir.join(name);
-    if plugin_path.exists() && plugin_path.join("SKILL.md").exists() {
-        return Some(plugin_path);
-    }
-
-    None
+///
+/// A name in both uses the skill unless `skills.resolve` picks the plugin
+/// (see [`crate::skill::conflicts`]).
+pub fn find_skill_source(name: &str, config: &Config) -> Option<PathBuf> {
+    let skill_path = Config::expand_path(&config.paths.skills).join(name);
+    // Plugins can have SKILL.md too
+    let plugin_path = Config::expand_path(&config.paths.plugins).join(name);
+    let candidates = match conflicts::preference(name, config) {
+        SkillPreference::Skill => [skill_path, plugin_path],
+        SkillPreference::Plugin => [plugin_path, skill_path],
+    };
+    candidates.into_iter().find(|path| path.join("SKILL.md").exists())
 }
 
 /// Get all available skill names from both skills and plugins directories
@@ -263,6 +267,7 @@ fn sync_skill_symlinks(skill_list: &[String], config: &Config) -> Result<SyncRes
 
     // Ensure the directory exists
     fs::create_dir_all(&claude_skills_dir).context("Failed to create ~/.claude/skills/")?;
+    let _lock = FileLock::acquire(&claude_skills_dir)?;
 
     // Get current state
     let current_symlinks = get_current_symlinks(&claude_skills_dir);
@@ -337,7 +342,7 @@ fn resolve_list(input: Option<Vec<String>>, profiles: &IndexMap<String, Vec<Stri
 }
 
 /// Expand names, replacing profile names with their contents
-fn expand_names(names: &[String], profiles: &IndexMap<String, Vec<String>>) -> Vec<String> {
+pub fn expand_names(names: &[String], profiles: &IndexMap<String, Vec<String>>) -> Vec<String> {
     let mut result = Vec::new();
     for name in names {
         if let Some(profile_contents) = profiles.get(name) {
@@ -354,11 +359,42 @@ fn expand_names(names: &[String], profiles: &IndexMap<String, Vec<String>>) -> V
     result
 }
 
+/// Name of the MCP profile(s) a session was launched with
+///
+/// With no `--mcp`, the default (first) profile applies. Otherwise the profile names among
+/// the given names are joined with `+`; plain server names alone mean no profile.
+fn profile_name(input: Option<&[String]>, profiles: &IndexMap<String, Vec<String>>) -> Option<String> {
+    match input {
+        None => profiles.keys().next().cloned(),
+        Some(names) => {
+            let used: Vec<&str> = names
+                .iter()
+                .filter(|n| profiles.contains_key(*n))
+                .map(|n| n.as_str())
+                .collect();
+            if used.is_empty() { None } else { Some(used.join("+")) }
+        }
+    }
+}
+
 /// Get default from first profile (if any)
 fn get_default(profiles: &IndexMap<String, Vec<String>>) -> Vec<String> {
     profiles.values().next().cloned().unwrap_or_default()
 }
 
+/// Names of all MCP servers available to `--mcp`
+pub fn mcp_server_names(config: &Config) -> Vec<String> {
+    load_all_mcp_servers(config).into_keys().collect()
+}
+
+/// All available MCP server definitions by name
+pub fn mcp_servers(config: &Config) -> BTreeMap<String, McpServerConfig> {
+    load_all_mcp_servers(config)
+        .into_iter()
+        .map(|(name, (server, _source))| (name, server))
+        .collect()
+}
+
 /// Load all available MCP servers from sources and config
 fn load_all_mcp_servers(config: &Config) -> HashMap<String, (McpServerConfig, String)> {
     let mut servers: HashMap<String, (McpServerConfig, String)> = HashMap::new();
@@ -401,7 +437,7 @@ fn load_all_mcp_servers(config: &Config) -> HashMap<String, (McpServerConfig, St
 }
 
 /// Build MCP config JSON file with only the requested servers
-fn build_mcp_config(mcp_list: &[String], config: &Config) -> Result<(PathBuf, usize)> {
+pub fn build_mcp_config(mcp_list: &[String], config: &Config) -> Result<(PathBuf, usize)> {
     let all_servers = load_all_mcp_servers(config);
 
     let mut selected_servers: HashMap<String, McpServerConfig> = HashMap::new();
@@ -429,11 +465,14 @@ fn build_mcp_config(mcp_list: &[String], config: &Config) -> Result<(PathBuf, us
         mcp_servers: selected_servers,
     };
 
-    // Write to temp file
-    let temp_dir = std::env::temp_dir();
-    let temp_file = temp_dir.join(format!("pais-mcp-{}.json", std::process::id()));
-
+    // Write to temp file; parallel agent runs may each need a different server set
     let json_content = serde_json::to_string_pretty(&mcp_json).context("Failed to serialize MCP config")?;
+    let temp_dir = std::env::temp_dir();
+    let temp_file = temp_dir.join(format!(
+        "pais-mcp-{}-{}.json",
+        std::process::id(),
+        &content_hash(json_content.as_bytes())[..8]
+    ));
 
     fs::write(&temp_file, &json_content).context("Failed to write temp MCP config file")?;
 
@@ -447,18 +486,17 @@ fn build_mcp_config(mcp_list: &[String], config: &Config) -> Result<(PathBuf, us
 ///
 /// Skill filtering is handled by sync_skill_symlinks() before this is called -
 /// Claude Code loads whatever symlinks exist in ~/.claude/skills/.
-fn launch_claude(mcp_config_path: Option<PathBuf>, extra_args: Vec<String>) -> Result<()> {
+fn launch_claude(mcp_config_path: Option<PathBuf>, profile: Option<String>, extra_args: Vec<String>) -> Result<()> {
     let mut cmd = Command::new("claude");
 
-    // Always use strict mode - only load what we specify
-    cmd.arg("--strict-mcp-config");
-
-    // Add our MCP config if we have one
-    if let Some(ref path) = mcp_config_path {
-        cmd.arg("--mcp-config");
-        cmd.arg(path);
+    // Hooks inherit this and record it for the prompt segment
+    if let Some(ref profile) = profile {
+        cmd.env(PROFILE_ENV, profile);
     }
 
+    // Strict mode (only load what we specify) plus our MCP config, as far as this release supports
+    cmd.args(compat::launch_flags(compat::detect(), mcp_config_path.as_deref()));
+
     // Pass through any extra args
     cmd.args(&extra_args);
 
@@ -673,6 +711,18 @@ fn list_all(format: OutputFormat, config: &Config) -> Result<()> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_profile_name() {
+        let mut profiles = IndexMap::new();
+        profiles.insert("work".to_string(), vec!["github".to_string()]);
+        profiles.insert("home".to_string(), vec![]);
+
+        assert_eq!(profile_name(None, &profiles).as_deref(), Some("work"));
+        let names = ["home".to_string(), "github".to_string(), "work".to_string()];
+        assert_eq!(profile_name(Some(&names), &profiles).as_deref(), Some("home+work"));
+        assert_eq!(profile_name(Some(&["github".to_string()]), &profiles), None);
+    }
+
     #[test]
     fn test_expand_names_direct() {
         let profiles = IndexMap::new();
@@ -1105,6 +1155,22 @@ mod tests {
         assert!(path.to_string_lossy().contains("pais-skills"));
     }
 
+    #[test]
+    fn test_find_skill_source_honours_resolve() {
+        let env = TestEnv::new();
+        env.create_skill("otto");
+        env.create_plugin_skill("otto");
+
+        let mut config = create_test_config(&env);
+        config
+            .skills
+            .resolve
+            .insert("otto".to_string(), SkillPreference::Plugin);
+
+        let path = find_skill_source("otto", &config).unwrap();
+        assert!(path.starts_with(&env.pais_plugins_dir));
+    }
+
     #[test]
     fn test_find_skill_source_not_found() {
         let env = TestEnv::new();
@@ -4,48 +4,118 @@ use colored::*;
 use eyre::{Context, Result};
 use serde::Serialize;
 use std::fs;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
 use std::process::Command;
-use terminal_size::{Width, terminal_size};
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::cli::{OutputFormat, SkillAction};
-use crate::config::Config;
-use crate::skill::indexer::{generate_context_snippet, generate_index, write_index};
+use crate::clock;
+use crate::commands;
+use crate::commands::context::extract_skill_body;
+use crate::config::{CompressionMethod, Config, DistillMethod, ObservabilitySink, SkillPreference};
+use crate::error::{CliError, ErrorCode};
+use crate::history::encrypt::Encryption;
+use crate::history::index::HistoryIndex;
+use crate::history::stats as history_stats;
+use crate::history::{HistoryEntry, HistoryStore};
+use crate::net;
+use crate::observability::emitter::skill_uses;
+use crate::picker::pick_name;
+use crate::plugin::registry;
+use crate::presenter::{self, Table};
+use crate::progress;
+use crate::provenance::{self, ArtifactKind, Provenance};
+use crate::share::{self, Artifact, ShareKind};
+use crate::skill::conflicts;
+use crate::skill::distill;
+use crate::skill::indexer::{SkillIndexEntry, TriggerCollision, generate_context_snippet, generate_index, write_index};
+use crate::skill::install as skill_install;
+use crate::skill::loader::subdirectories;
 use crate::skill::loader::{discover_plugin_skills, discover_simple_skills, load_simple_skill};
-use crate::skill::parser::{SkillMetadata, parse_skill_md};
+use crate::skill::minify;
+use crate::skill::parser::{SkillMetadata, parse_frontmatter, parse_skill_md};
 use crate::skill::scanner::{DiscoveredSkill, scan_for_skills};
+use crate::skill::search as skill_search;
+use crate::skill::subagent;
 use crate::skill::template::generate_skill_template;
+use crate::skill::upgrade as skill_upgrade;
+use crate::skill::usage as skill_usage;
 use crate::skill::workflow::{discover_workflows, load_workflow};
 use crate::skill::{Skill, SkillSource};
+use crate::state::{self, StateEvent};
+use crate::team;
 
 /// Run a skill subcommand
-pub fn run(action: SkillAction, config: &Config) -> Result<()> {
+pub fn run(action: SkillAction, format: OutputFormat, config: &Config) -> Result<()> {
     match action {
-        SkillAction::List { format, simple, plugin } => {
-            list_skills(OutputFormat::resolve(format), simple, plugin, config)
-        }
+        SkillAction::List { simple, plugin } => list_skills(format, simple, plugin, config),
         SkillAction::Add { name, edit } => add_skill(&name, edit, config),
-        SkillAction::Info { name } => show_skill_info(&name, config),
-        SkillAction::Edit { name } => edit_skill(&name, config),
-        SkillAction::Remove { name, force } => remove_skill(&name, force, config),
-        SkillAction::Validate { name } => validate_skill(&name, config),
-        SkillAction::Scan {
-            path,
-            depth,
-            register,
-            format,
-        } => scan_skills(path, depth, register, OutputFormat::resolve(format), config),
-        SkillAction::Index { format } => generate_skill_index(OutputFormat::resolve(format), config),
-        SkillAction::Workflow {
-            skill,
-            workflow,
-            format,
-        } => show_workflow(&skill, workflow.as_deref(), OutputFormat::resolve(format), config),
+        SkillAction::Info { name } => show_skill_info(&pick_skill(name, config)?, config),
+        SkillAction::Edit { name } => edit_skill(&pick_skill(name, config)?, config),
+        SkillAction::Remove { name, force } => remove_skill(&pick_skill(name, config)?, force, config),
+        SkillAction::Validate { name } => validate_skill(&pick_skill(name, config)?, config),
+        SkillAction::Scan { path, depth, register } => scan_skills(path, depth, register, format, config),
+        SkillAction::Index => generate_skill_index(format, config),
+        SkillAction::Lint => lint_skills(format, config),
+        SkillAction::Conflicts => list_conflicts(format, config),
+        SkillAction::Promote { name, force, .. } => promote_skill(&pick_skill(name, config)?, force, config),
+        SkillAction::Minify { name, method, write } => {
+            minify_skill(&pick_skill(name, config)?, method, write, format, config)
+        }
+        SkillAction::Workflow { skill, workflow } => {
+            show_workflow(&pick_skill(skill, config)?, workflow.as_deref(), format, config)
+        }
+        SkillAction::Share { name, public } => share_skill(&pick_skill(name, config)?, public, format, config),
+        SkillAction::Install { source, r#ref, force } => install_skill(&source, r#ref.as_deref(), force, config),
+        SkillAction::Outdated => outdated_skills(format, config),
+        SkillAction::Upgrade {
+            name,
+            r#ref,
+            force,
+            yes,
+        } => upgrade_skills(&pick_skill(name, config)?, r#ref.as_deref(), force, yes, config),
+        SkillAction::Stats { weeks } => skill_stats(weeks, format, config),
+        SkillAction::Search { query, limit } => search_skills(&query, limit, format, config),
+        SkillAction::Distill {
+            from_history,
+            name,
+            category,
+            limit,
+            method,
+            write,
+            force,
+        } => {
+            let opts = DistillOptions {
+                source: &from_history,
+                name: name.as_deref(),
+                categories: &category,
+                limit,
+                method,
+                write,
+                force,
+            };
+            distill_skill(opts, format, config)
+        }
     }
 }
 
+/// Use the given skill name, or pick one of the installed skills interactively
+fn pick_skill(name: Option<String>, config: &Config) -> Result<String> {
+    pick_name(name, "skill", || skill_names(config))
+}
+
+/// Directory names of all simple and plugin skills
+pub fn skill_names(config: &Config) -> Result<Vec<String>> {
+    let skills_dir = Config::expand_path(&config.paths.skills);
+    let plugins_dir = Config::expand_path(&config.paths.plugins);
+    Ok(discover_simple_skills(&skills_dir)?
+        .into_iter()
+        .chain(discover_plugin_skills(&plugins_dir)?)
+        .filter_map(|s| s.path.file_name().map(|n| n.to_string_lossy().to_string()))
+        .collect())
+}
+
 /// Serializable skill info for JSON/YAML output
 #[derive(Serialize)]
 struct SkillInfo {
@@ -62,6 +132,7 @@ impl From<&Skill> for SkillInfo {
             SkillSource::Simple => ("simple".to_string(), None),
             SkillSource::Plugin(name) => ("plugin".to_string(), Some(name.clone())),
             SkillSource::Discovered(path) => ("discovered".to_string(), Some(path.display().to_string())),
+            SkillSource::Team(name) => ("team".to_string(), Some(name.clone())),
         };
 
         Self {
@@ -74,55 +145,13 @@ impl From<&Skill> for SkillInfo {
     }
 }
 
-/// Get terminal width, defaulting to 80 if not available
-fn get_terminal_width() -> usize {
-    terminal_size().map(|(Width(w), _)| w as usize).unwrap_or(80)
-}
-
-/// Wrap text to max_width, returning lines
-fn wrap_text(s: &str, max_width: usize) -> Vec<String> {
-    if max_width == 0 {
-        return vec![s.to_string()];
-    }
-
-    let mut lines = Vec::new();
-    let mut current_line = String::new();
-    let mut current_len = 0;
-
-    for word in s.split_whitespace() {
-        let word_len = word.chars().count();
-
-        if current_len == 0 {
-            current_line = word.to_string();
-            current_len = word_len;
-        } else if current_len + 1 + word_len <= max_width {
-            current_line.push(' ');
-            current_line.push_str(word);
-            current_len += 1 + word_len;
-        } else {
-            lines.push(current_line);
-            current_line = word.to_string();
-            current_len = word_len;
-        }
-    }
-
-    if !current_line.is_empty() {
-        lines.push(current_line);
-    }
-
-    if lines.is_empty() {
-        lines.push(String::new());
-    }
-
-    lines
-}
-
 /// Format source type for display
 fn format_source(skill: &Skill) -> String {
     match &skill.source {
         SkillSource::Simple => "simple".to_string(),
         SkillSource::Plugin(name) => format!("plugin:{}", name),
         SkillSource::Discovered(_) => "discovered".to_string(),
+        SkillSource::Team(name) => format!("team:{}", name),
     }
 }
 
@@ -145,6 +174,12 @@ fn list_skills(format: OutputFormat, only_simple: bool, only_plugin: bool, confi
         all_skills.extend(plugin_skills);
     }
 
+    // Team skills are simple skills; names already listed shadow them
+    if !only_plugin {
+        let team_skills = team::skills(&all_skills);
+        all_skills.extend(team_skills);
+    }
+
     match format {
         OutputFormat::Text => {
             if all_skills.is_empty() {
@@ -152,49 +187,19 @@ fn list_skills(format: OutputFormat, only_simple: bool, only_plugin: bool, confi
                 return Ok(());
             }
 
-            let term_width = get_terminal_width();
-
-            // Calculate column widths
-            let name_width = all_skills.iter().map(|s| s.name.len()).max().unwrap_or(4);
-            let source_width = all_skills.iter().map(|s| format_source(s).len()).max().unwrap_or(6);
-
-            // Description gets remaining space (minus columns and gaps)
-            let fixed_width = name_width + 2 + source_width + 2;
-            let desc_width = term_width.saturating_sub(fixed_width).max(20);
-
-            // Header
-            println!(
-                "{:<name_width$}  {:<source_width$}  {}",
-                "NAME".bold(),
-                "SOURCE".bold(),
-                "DESCRIPTION".bold(),
-                name_width = name_width,
-                source_width = source_width,
-            );
-
-            // Skills
-            let indent = " ".repeat(fixed_width);
+            let mut table = Table::new(&["NAME", "SOURCE", "DESCRIPTION"]);
             for skill in &all_skills {
-                let desc_lines = wrap_text(&skill.description, desc_width);
-                let source = format_source(skill);
-
-                // First line with name and source
-                println!(
-                    "{:<name_width$}  {:<source_width$}  {}",
+                table.add_row(vec![
                     skill.name.green(),
-                    source.dimmed(),
-                    desc_lines.first().unwrap_or(&String::new()).dimmed(),
-                    name_width = name_width,
-                    source_width = source_width,
-                );
-                // Continuation lines indented under description
-                for line in desc_lines.iter().skip(1) {
-                    println!("{}{}", indent, line.dimmed());
-                }
+                    format_source(skill).dimmed(),
+                    skill.description.dimmed(),
+                ]);
             }
+            table.print();
 
             println!();
             println!("{}", format!("{} skills", all_skills.len()).dimmed());
+            warn_unresolved_conflicts(config);
         }
         OutputFormat::Json => {
             let infos: Vec<SkillInfo> = all_skills.iter().map(SkillInfo::from).collect();
@@ -247,50 +252,135 @@ fn add_skill(name: &str, open_editor: bool, config: &Config) -> Result<()> {
     Ok(())
 }
 
-/// Show details for a specific skill
-fn show_skill_info(name: &str, config: &Config) -> Result<()> {
-    let skills_dir = Config::expand_path(&config.paths.skills);
-    let plugins_dir = Config::expand_path(&config.paths.plugins);
+/// Warn about names in both the skills and plugins directories that config doesn't settle
+fn warn_unresolved_conflicts(config: &Config) {
+    let conflicts = match conflicts::find(config) {
+        Ok(conflicts) => conflicts,
+        Err(e) => {
+            log::warn!("Failed to check for shadowed plugins: {:#}", e);
+            return;
+        }
+    };
+    let unresolved: Vec<&str> = conflicts
+        .iter()
+        .filter(|c| !c.resolved)
+        .map(|c| c.name.as_str())
+        .collect();
+    if !unresolved.is_empty() {
+        println!(
+            "{} {} shadow{} a plugin of the same name; see {}",
+            presenter::warn(),
+            unresolved.join(", ").yellow(),
+            if unresolved.len() == 1 { "s" } else { "" },
+            "pais skill conflicts".cyan()
+        );
+    }
+}
 
-    // Check simple skills first
-    let skill_path = skills_dir.join(name);
-    if skill_path.exists() && skill_path.join("SKILL.md").exists() {
-        let skill = load_simple_skill(&skill_path)?;
-        print_skill_details(&skill)?;
-        return Ok(());
+/// List names found in both the skills and plugins directories
+fn list_conflicts(format: OutputFormat, config: &Config) -> Result<()> {
+    let conflicts = conflicts::find(config)?;
+    let stale = conflicts::stale_overrides(config, &conflicts);
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&conflicts)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&conflicts)?),
+        OutputFormat::Text => {
+            if conflicts.is_empty() {
+                println!("{} No skill shadows a plugin", presenter::ok());
+            } else {
+                let mut table = Table::new(&["NAME", "USES", "SHADOWED", "BY"]);
+                for conflict in &conflicts {
+                    let side = match conflict.uses {
+                        SkillPreference::Skill => "skill",
+                        SkillPreference::Plugin => "plugin",
+                    };
+                    table.add_row(vec![
+                        conflict.name.green(),
+                        format!("{} ({})", side, conflict.used_path().display()).normal(),
+                        conflict.shadowed_path().display().to_string().dimmed(),
+                        if conflict.resolved { "skills.resolve".normal() } else { "default".yellow() },
+                    ]);
+                }
+                table.print();
+                if conflicts.iter().any(|c| !c.resolved) {
+                    println!();
+                    println!(
+                        "Pick a side in pais.yaml to silence the warning: {}",
+                        "skills.resolve.<name>: skill | plugin".cyan()
+                    );
+                }
+            }
+            for name in stale {
+                println!(
+                    "{} skills.resolve.{} names no conflict; remove it",
+                    presenter::warn(),
+                    name
+                );
+            }
+        }
     }
+    Ok(())
+}
 
-    // Check plugins
-    let plugin_path = plugins_dir.join(name);
-    if plugin_path.exists() && plugin_path.join("SKILL.md").exists() {
-        let skill = crate::skill::loader::load_plugin_skill(&plugin_path, name)?;
-        print_skill_details(&skill)?;
-        return Ok(());
+/// Write a Claude Code subagent generated from a skill
+fn promote_skill(name: &str, force: bool, config: &Config) -> Result<()> {
+    let skill_dir =
+        commands::session::find_skill_source(name, config).ok_or_else(|| CliError::not_found("Skill", name))?;
+    let agent = subagent::generate(name, &skill_dir)?;
+
+    let agents_dir = Config::claude_agents_dir().ok_or_else(|| eyre::eyre!("Could not determine home directory"))?;
+    let path = agents_dir.join(format!("{}.md", name));
+    if let Ok(existing) = fs::read_to_string(&path)
+        && subagent::source_skill(&existing) != Some(name)
+        && !force
+    {
+        return Err(CliError::new(
+            ErrorCode::Conflict,
+            format!("{} already exists and wasn't generated from this skill", path.display()),
+        )
+        .with_hint("Pass --force to replace it")
+        .into());
     }
 
-    eyre::bail!("Skill '{}' not found", name);
+    fs::create_dir_all(&agents_dir).with_context(|| format!("Failed to create {}", agents_dir.display()))?;
+    fs::write(&path, agent).with_context(|| format!("Failed to write {}", path.display()))?;
+    println!(
+        "{} Promoted {} to a subagent: {}",
+        presenter::ok(),
+        name.green(),
+        path.display()
+    );
+    println!(
+        "  {} `pais sync` regenerates it when the skill changes",
+        presenter::arrow()
+    );
+    Ok(())
 }
 
-/// Edit a skill in $EDITOR
-fn edit_skill(name: &str, config: &Config) -> Result<()> {
-    let skills_dir = Config::expand_path(&config.paths.skills);
-    let plugins_dir = Config::expand_path(&config.paths.plugins);
+/// Show details for a specific skill
+fn show_skill_info(name: &str, config: &Config) -> Result<()> {
+    let path = commands::session::find_skill_source(name, config).ok_or_else(|| CliError::not_found("Skill", name))?;
 
-    // Check simple skills first
-    let skill_path = skills_dir.join(name);
-    let skill_md = skill_path.join("SKILL.md");
-    if skill_md.exists() {
-        return open_in_editor(&skill_md);
+    if path.starts_with(Config::expand_path(&config.paths.plugins)) {
+        let skill = crate::skill::loader::load_plugin_skill(&path, name)?;
+        print_skill_details(&skill)?;
+    } else {
+        let skill = load_simple_skill(&path)?;
+        print_skill_details(&skill)?;
+        print_skill_provenance(name, &path);
     }
+    Ok(())
+}
 
-    // Check plugins
-    let plugin_path = plugins_dir.join(name);
-    let plugin_skill_md = plugin_path.join("SKILL.md");
-    if plugin_skill_md.exists() {
-        return open_in_editor(&plugin_skill_md);
+/// Edit a skill in $EDITOR
+fn edit_skill(name: &str, config: &Config) -> Result<()> {
+    match commands::session::find_skill_source(name, config) {
+        Some(path) => open_in_editor(&path.join("SKILL.md")),
+        None => Err(CliError::not_found("Skill", name)
+            .with_hint(format!("Create it with: pais skill add {}", name))
+            .into()),
     }
-
-    eyre::bail!("Skill '{}' not found.\nCreate it with: pais skill add {}", name, name);
 }
 
 /// Remove a skill
@@ -299,7 +389,11 @@ fn remove_skill(name: &str, force: bool, config: &Config) -> Result<()> {
     let skill_path = skills_dir.join(name);
 
     if !skill_path.exists() {
-        eyre::bail!("Skill '{}' not found in {}", name, skills_dir.display());
+        return Err(CliError::new(
+            ErrorCode::NotFound,
+            format!("Skill '{}' not found in {}", name, skills_dir.display()),
+        )
+        .into());
     }
 
     // Check if it's a plugin skill (can't remove those via skill remove)
@@ -331,6 +425,11 @@ fn remove_skill(name: &str, force: bool, config: &Config) -> Result<()> {
     fs::remove_dir_all(&skill_path)
         .with_context(|| format!("Failed to remove skill directory: {}", skill_path.display()))?;
 
+    state::record(StateEvent::SkillRemoved { name: name.to_string() });
+    if let Err(e) = provenance::forget(ArtifactKind::Skill, name) {
+        log::warn!("Failed to forget provenance of skill '{}': {:#}", name, e);
+    }
+
     println!("Removed skill: {}", name);
     Ok(())
 }
@@ -415,7 +514,7 @@ fn validate_skill(name: &str, config: &Config) -> Result<()> {
                 println!("✓ Skill '{}' (plugin) is valid", name);
                 return Ok(());
             }
-            eyre::bail!("Skill '{}' not found", name);
+            return Err(CliError::not_found("Skill", name).into());
         }
 
         validate_skill_md(&skill_md)?;
@@ -431,7 +530,7 @@ fn validate_skill_md(path: &std::path::Path) -> Result<SkillMetadata> {
 }
 
 /// Open a file in the user's preferred editor
-fn open_in_editor(path: &std::path::Path) -> Result<()> {
+pub fn open_in_editor(path: &std::path::Path) -> Result<()> {
     let editor = std::env::var("EDITOR")
         .or_else(|_| std::env::var("VISUAL"))
         .unwrap_or_else(|_| "vi".to_string());
@@ -467,10 +566,29 @@ fn scan_skills(
         eyre::bail!("Path does not exist: {}", scan_path.display());
     }
 
-    println!("Scanning {} (max depth: {})...", scan_path.display(), depth);
-    println!();
-
-    let skills = scan_for_skills(&scan_path, depth).context("Failed to scan for skills")?;
+    let spinner = progress::spinner(
+        format,
+        format!("Scanning {} (max depth: {})...", scan_path.display(), depth),
+    );
+    let mut visited = 0u64;
+    let skills = scan_for_skills(&scan_path, depth, |dir| {
+        visited += 1;
+        if visited.is_multiple_of(64) {
+            spinner.set_message(format!("Scanning ({} dirs) {}", visited, dir.display()));
+        }
+    })
+    .context("Failed to scan for skills")?;
+    spinner.finish_and_clear();
+
+    if format == OutputFormat::Text {
+        println!(
+            "Scanned {} (max depth: {}, {} dirs)",
+            scan_path.display(),
+            depth,
+            visited
+        );
+        println!();
+    }
 
     if skills.is_empty() {
         println!("No skills found.");
@@ -569,6 +687,7 @@ fn print_skill_details(skill: &Skill) -> Result<()> {
             SkillSource::Simple => "Simple (SKILL.md only)".to_string(),
             SkillSource::Plugin(name) => format!("Plugin ({})", name),
             SkillSource::Discovered(path) => format!("Discovered ({})", path.display()),
+            SkillSource::Team(name) => format!("Team ({})", name),
         }
     );
 
@@ -591,6 +710,26 @@ fn print_skill_details(skill: &Skill) -> Result<()> {
     Ok(())
 }
 
+/// Where a simple skill came from (plugin skills are covered by `pais plugin info`)
+fn print_skill_provenance(name: &str, skill_path: &std::path::Path) {
+    let (provenance, recorded) = commands::provenance::resolve(
+        &commands::provenance::load(),
+        ArtifactKind::Skill,
+        name,
+        skill_path,
+        || {
+            parse_skill_md(&skill_path.join("SKILL.md"))
+                .map(|m| (m.author, m.license))
+                .unwrap_or_default()
+        },
+    );
+    println!();
+    println!("Provenance:");
+    for (label, value) in commands::provenance::details(&provenance, recorded) {
+        println!("  {}: {}", label, value);
+    }
+}
+
 /// Generate skill index for context injection
 fn generate_skill_index(format: OutputFormat, config: &Config) -> Result<()> {
     let skills_dir = Config::expand_path(&config.paths.skills);
@@ -644,6 +783,11 @@ fn generate_skill_index(format: OutputFormat, config: &Config) -> Result<()> {
             println!("  Deferred: {} skill(s)", index.deferred_count);
             println!();
 
+            if !index.collisions.is_empty() {
+                print_collisions(&index.collisions);
+                println!();
+            }
+
             // Also generate context snippet
             let context = generate_context_snippet(&index, &skills_dir);
             let context_path = skills_dir.join("context-snippet.md");
@@ -666,6 +810,475 @@ fn generate_skill_index(format: OutputFormat, config: &Config) -> Result<()> {
     Ok(())
 }
 
+/// A problem found by `pais skill lint`
+#[derive(Serialize)]
+struct LintProblem {
+    skill: String,
+    level: &'static str,
+    message: String,
+}
+
+/// Result of `pais skill lint`
+#[derive(Serialize)]
+struct LintReport {
+    skills: usize,
+    problems: Vec<LintProblem>,
+    collisions: Vec<TriggerCollision>,
+}
+
+/// What `pais skill minify` produced
+#[derive(Serialize)]
+struct Minified {
+    name: String,
+    method: CompressionMethod,
+    tokens_before: usize,
+    tokens_after: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    written: Option<PathBuf>,
+    body: String,
+}
+
+/// Preview (and optionally save) a skill's minified body
+fn minify_skill(
+    name: &str,
+    method: Option<CompressionMethod>,
+    write: bool,
+    format: OutputFormat,
+    config: &Config,
+) -> Result<()> {
+    let skill_dir = [&config.paths.skills, &config.paths.plugins]
+        .iter()
+        .map(|dir| Config::expand_path(dir).join(name))
+        .find(|dir| dir.join("SKILL.md").exists())
+        .ok_or_else(|| CliError::not_found("Skill", name))?;
+    let skill_md = skill_dir.join("SKILL.md");
+    let source = fs::read_to_string(&skill_md).with_context(|| format!("Failed to read {}", skill_md.display()))?;
+    let body = extract_skill_body(&source).ok_or_else(|| {
+        CliError::new(
+            ErrorCode::InvalidInput,
+            format!("{} has no body to minify", skill_md.display()),
+        )
+    })?;
+
+    let compression = &config.skills.compression;
+    let method = method.unwrap_or(compression.method);
+    let minified = match method {
+        CompressionMethod::Rules => minify::prune(&body, compression),
+        CompressionMethod::Llm => {
+            let spinner = progress::spinner(format, format!("Summarizing {}", name));
+            let summary = minify::summarize(name, &body);
+            spinner.finish_and_clear();
+            summary?
+        }
+    };
+
+    let written = if write {
+        let path = skill_dir.join(minify::MIN_FILE);
+        fs::write(&path, minify::render_min_file(&source, &minified))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Some(path)
+    } else {
+        None
+    };
+    let result = Minified {
+        name: name.to_string(),
+        method,
+        tokens_before: minify::estimate_tokens(&body),
+        tokens_after: minify::estimate_tokens(&minified),
+        written,
+        body: minified,
+    };
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&result)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&result)?),
+        OutputFormat::Text => {
+            println!("{}", result.body);
+            println!();
+            println!(
+                "{}",
+                format!(
+                    "~{} -> ~{} tokens ({:?})",
+                    result.tokens_before, result.tokens_after, result.method
+                )
+                .to_lowercase()
+                .dimmed()
+            );
+            match result.written {
+                Some(ref path) => println!("{} Saved {}", presenter::ok(), path.display()),
+                None => println!(
+                    "{} Preview only; {} saves it for context injection",
+                    presenter::info(),
+                    "--write".cyan()
+                ),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// One `pais skill search` result
+#[derive(Serialize)]
+struct SkillSearchResult {
+    name: String,
+    score: f64,
+    matched: Vec<skill_search::Field>,
+    description: String,
+    path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snippet: Option<String>,
+}
+
+/// Rank skills and plugin skills by how well they match `query`
+fn search_skills(query: &str, limit: usize, format: OutputFormat, config: &Config) -> Result<()> {
+    if query.trim().is_empty() {
+        return Err(CliError::new(ErrorCode::InvalidInput, "Nothing to search for")
+            .with_hint("Give a word from the skill's name, triggers or description")
+            .into());
+    }
+
+    // Skills shadow plugin skills of the same name, as in `pais skill list`
+    let mut skills: Vec<(PathBuf, SkillIndexEntry, String)> = Vec::new();
+    for dir in [&config.paths.skills, &config.paths.plugins] {
+        let dir = Config::expand_path(dir);
+        let mut entries: Vec<SkillIndexEntry> = generate_index(&dir)?.skills.into_values().collect();
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        for entry in entries {
+            if skills.iter().any(|(_, known, _)| known.name == entry.name) {
+                continue;
+            }
+            let path = dir.join(&entry.path);
+            let body = fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| extract_skill_body(&content))
+                .unwrap_or_default();
+            skills.push((path, entry, body));
+        }
+    }
+
+    let docs = skills.iter().map(|(_, entry, body)| skill_search::SearchDoc {
+        name: &entry.name,
+        description: &entry.description,
+        triggers: &entry.triggers,
+        body,
+    });
+    let results: Vec<SkillSearchResult> = skill_search::search(query, docs)
+        .into_iter()
+        .take(limit)
+        .filter_map(|hit| {
+            let (path, entry, _) = skills.iter().find(|(_, entry, _)| entry.name == hit.name)?;
+            Some(SkillSearchResult {
+                score: (hit.score * 100.0).round() / 100.0,
+                matched: hit.matched.into_iter().collect(),
+                description: entry.description.clone(),
+                path: path.clone(),
+                snippet: hit.snippet,
+                name: hit.name,
+            })
+        })
+        .collect();
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&results)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&results)?),
+        OutputFormat::Text => {
+            if results.is_empty() {
+                println!("{}", format!("No skills match '{}'", query).dimmed());
+                return Ok(());
+            }
+            let mut table = Table::new(&["NAME", "MATCHED", "DESCRIPTION"]);
+            for result in &results {
+                // A body match is easier to judge by its line than by the description
+                let detail = match (&result.snippet, result.matched.as_slice()) {
+                    (Some(snippet), [skill_search::Field::Body]) => format!("\"{}\"", snippet).italic(),
+                    _ => presenter::truncate(&result.description, 70).normal(),
+                };
+                let matched: Vec<String> = result.matched.iter().map(|field| field.to_string()).collect();
+                table.add_row(vec![result.name.green(), matched.join(", ").dimmed(), detail]);
+            }
+            table.print();
+        }
+    }
+    Ok(())
+}
+
+struct DistillOptions<'a> {
+    /// A full-text query or entry IDs
+    source: &'a str,
+    name: Option<&'a str>,
+    categories: &'a [String],
+    limit: Option<usize>,
+    method: Option<DistillMethod>,
+    write: bool,
+    force: bool,
+}
+
+/// What `pais skill distill` produced
+#[derive(Serialize)]
+struct Distilled {
+    name: String,
+    method: DistillMethod,
+    /// IDs of the entries it was drafted from
+    entries: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    written: Option<PathBuf>,
+    skill: String,
+}
+
+/// Draft a skill from history entries, printing it or saving it with `--write`
+fn distill_skill(opts: DistillOptions, format: OutputFormat, config: &Config) -> Result<()> {
+    let distill = &config.skills.distill;
+    let history_dir = Config::expand_path(&config.paths.history);
+    let store = HistoryStore::new(history_dir.clone())
+        .with_index(config.history.index.enabled)
+        .with_encryption(Encryption::from_config(&config.history.encryption));
+    let limit = opts.limit.unwrap_or(distill.max_entries).max(1);
+
+    let (entries, query) = match history_entries_by_id(&store, opts.source)? {
+        Some(entries) => (entries, None),
+        None => {
+            let categories = if opts.categories.is_empty() { &distill.categories } else { opts.categories };
+            let index = HistoryIndex::new(&history_dir);
+            // Without `history.index.enabled` nothing keeps the index current
+            if !config.history.index.enabled {
+                let pb = progress::spinner(format, "Indexing history entries...");
+                let rebuilt = index.rebuild();
+                pb.finish_and_clear();
+                rebuilt?;
+            }
+            let mut hits = Vec::new();
+            for category in categories {
+                hits.extend(index.search(opts.source, Some(category), limit)?);
+            }
+            hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+            hits.truncate(limit);
+            let mut entries = Vec::new();
+            for hit in hits {
+                let content = store.read(&hit.path)?;
+                entries.push(HistoryEntry::from_markdown(&content, &hit.path)?);
+            }
+            (entries, Some(opts.source))
+        }
+    };
+    if entries.is_empty() {
+        return Err(CliError::new(
+            ErrorCode::NotFound,
+            format!("No history entries match '{}'", opts.source),
+        )
+        .with_hint("Check the query with `pais history search`, or search other categories with --category")
+        .into());
+    }
+
+    let name = opts
+        .name
+        .map(String::from)
+        .unwrap_or_else(|| distill::suggest_name(query, &entries));
+    if !skill_install::is_valid_name(&name) {
+        return Err(CliError::new(ErrorCode::InvalidInput, format!("'{}' can't be a skill name", name)).into());
+    }
+    let skill_dir = Config::expand_path(&config.paths.skills).join(&name);
+    if opts.write && skill_dir.exists() && !opts.force {
+        return Err(
+            CliError::new(ErrorCode::Conflict, format!("Skill '{}' already exists", name))
+                .with_hint("Pick another with --name, or overwrite it with --force")
+                .into(),
+        );
+    }
+
+    let method = opts.method.unwrap_or(distill.method);
+    let skill = match method {
+        DistillMethod::Template => distill::template(&name, &entries),
+        DistillMethod::Llm => {
+            let spinner = progress::spinner(format, format!("Distilling {} entries", entries.len()));
+            let skill = distill::generate(&name, &entries);
+            spinner.finish_and_clear();
+            skill?
+        }
+    };
+
+    let written = if opts.write {
+        fs::create_dir_all(&skill_dir).with_context(|| format!("Failed to create {}", skill_dir.display()))?;
+        let path = skill_dir.join("SKILL.md");
+        fs::write(&path, &skill).with_context(|| format!("Failed to write {}", path.display()))?;
+        Some(path)
+    } else {
+        None
+    };
+    let result = Distilled {
+        name,
+        method,
+        entries: entries.iter().map(|entry| entry.id.clone()).collect(),
+        written,
+        skill,
+    };
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&result)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&result)?),
+        OutputFormat::Text => match result.written {
+            Some(ref path) => {
+                println!(
+                    "{} Drafted skill {} from {}",
+                    presenter::ok(),
+                    result.name.cyan(),
+                    distill::count(result.entries.len())
+                );
+                println!("  Path: {}", path.display());
+                println!();
+                println!("Review it with: pais skill edit {}", result.name);
+            }
+            None => {
+                println!("{}", result.skill.trim_end());
+                println!();
+                println!(
+                    "{} Preview of {} from {}; {} saves it",
+                    presenter::info(),
+                    result.name.cyan(),
+                    distill::count(result.entries.len()),
+                    "--write".cyan()
+                );
+            }
+        },
+    }
+    Ok(())
+}
+
+/// The entries `source` names by ID, or None when it is a query
+///
+/// Words that all look like IDs but name no entry are a query too; once
+/// one of them names an entry, the others must as well.
+fn history_entries_by_id(store: &HistoryStore, source: &str) -> Result<Option<Vec<HistoryEntry>>> {
+    let Some(ids) = distill::ids(source) else {
+        return Ok(None);
+    };
+    let found: Vec<(&str, Option<HistoryEntry>)> = ids
+        .into_iter()
+        .map(|id| store.get(id).map(|entry| (id, entry)))
+        .collect::<Result<_>>()?;
+    if found.iter().all(|(_, entry)| entry.is_none()) {
+        return Ok(None);
+    }
+    found
+        .into_iter()
+        .map(|(id, entry)| entry.ok_or_else(|| CliError::not_found("History entry", id).into()))
+        .collect::<Result<_>>()
+        .map(Some)
+}
+
+/// Check every indexed skill's frontmatter and report overlapping triggers
+fn lint_skills(format: OutputFormat, config: &Config) -> Result<()> {
+    let skills_dir = Config::expand_path(&config.paths.skills);
+
+    let mut skills = 0;
+    let mut problems = Vec::new();
+    if skills_dir.exists() {
+        for path in subdirectories(&skills_dir)? {
+            let skill_md = path.join("SKILL.md");
+            if !skill_md.exists() {
+                continue;
+            }
+            skills += 1;
+            let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let mut problem = |level, message: String| {
+                problems.push(LintProblem {
+                    skill: name.clone(),
+                    level,
+                    message,
+                })
+            };
+            match parse_skill_md(&skill_md) {
+                Err(e) => problem("error", format!("{:#}", e)),
+                Ok(metadata) => {
+                    if metadata.description.trim().is_empty() {
+                        problem("warning", "no description".to_string());
+                    } else if metadata.triggers.is_empty() && !metadata.description.contains("USE WHEN") {
+                        problem(
+                            "warning",
+                            "no `triggers:` and no USE WHEN clause; routing relies on keyword guesses".to_string(),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    let index = generate_index(&skills_dir).context("Failed to generate skill index")?;
+    let report = LintReport {
+        skills,
+        problems,
+        collisions: index.collisions,
+    };
+    let errors = report.problems.iter().filter(|p| p.level == "error").count();
+    let unresolved = report.collisions.iter().filter(|c| !c.is_resolved()).count();
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&report)?),
+        OutputFormat::Text => {
+            for problem in &report.problems {
+                let marker = if problem.level == "error" { presenter::fail() } else { presenter::warn() };
+                println!("{} {}: {}", marker, problem.skill.bold(), problem.message);
+            }
+            if !report.problems.is_empty() {
+                println!();
+            }
+            if !report.collisions.is_empty() {
+                print_collisions(&report.collisions);
+                println!();
+            }
+
+            if errors == 0 && unresolved == 0 {
+                println!(
+                    "{} {} skill(s) checked, {} warning(s)",
+                    presenter::ok(),
+                    report.skills,
+                    report.problems.len()
+                );
+            } else {
+                println!(
+                    "{} {} skill(s) checked: {} error(s), {} unresolved trigger collision(s)",
+                    presenter::fail(),
+                    report.skills,
+                    errors,
+                    unresolved
+                );
+            }
+        }
+    }
+
+    if errors > 0 || unresolved > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Print overlapping triggers with how each is (or could be) disambiguated
+fn print_collisions(collisions: &[TriggerCollision]) {
+    println!("Trigger collisions:");
+    let mut table = Table::new(&["TRIGGER", "SKILLS", "RESOLUTION"]);
+    for collision in collisions {
+        let resolution = match &collision.winner {
+            Some(winner) => format!("priority → {}", winner).green(),
+            None => "unresolved".yellow(),
+        };
+        table.add_row(vec![
+            collision.trigger.cyan(),
+            collision.skills.join(", ").normal(),
+            resolution,
+        ]);
+    }
+    table.print();
+
+    for collision in collisions.iter().filter(|c| !c.is_resolved()) {
+        println!(
+            "  {} {}: {}",
+            presenter::arrow(),
+            collision.trigger.cyan(),
+            collision.suggestion
+        );
+    }
+}
+
 fn truncate_desc(desc: &str, max_len: usize) -> String {
     if desc.len() <= max_len {
         desc.to_string()
@@ -689,7 +1302,7 @@ fn show_workflow(skill_name: &str, workflow: Option<&str>, format: OutputFormat,
         } else if plugin_path.exists() && plugin_path.join("SKILL.md").exists() {
             plugin_path
         } else {
-            eyre::bail!("Skill '{}' not found", skill_name);
+            return Err(CliError::not_found("Skill", skill_name).into());
         }
     };
 
@@ -794,3 +1407,709 @@ fn show_workflow(skill_name: &str, workflow: Option<&str>, format: OutputFormat,
 
     Ok(())
 }
+
+/// Publish a simple skill as a gist
+fn share_skill(name: &str, public: bool, format: OutputFormat, config: &Config) -> Result<()> {
+    let skill_dir = Config::expand_path(&config.paths.skills).join(name);
+    if !skill_dir.join("SKILL.md").exists() {
+        let plugin_dir = Config::expand_path(&config.paths.plugins).join(name);
+        let err = CliError::not_found("Skill", name);
+        return Err(if plugin_dir.join("SKILL.md").exists() {
+            err.with_hint("Plugin skills are shared with their plugin, not as gists")
+        } else {
+            err
+        }
+        .into());
+    }
+    net::ensure_online(&config.network, "Sharing a skill")?;
+
+    let metadata = validate_skill_md(&skill_dir.join("SKILL.md"))?;
+    let artifact = Artifact::from_dir(ShareKind::Skill, name, &skill_dir)?;
+    let token = share::github_token()?;
+
+    let spinner = progress::spinner(format, format!("Uploading {} file(s)", artifact.files.len()));
+    let description = format!("pais skill: {} - {}", name, metadata.description);
+    let shared = share::create_gist(&artifact, &description, public, &token);
+    spinner.finish_and_clear();
+    let shared = shared?;
+
+    print_shared(&shared, format)
+}
+
+/// Print a created gist and how to install it
+pub fn print_shared(shared: &share::Shared, format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(shared)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(shared)?),
+        OutputFormat::Text => {
+            println!("{} Shared: {}", presenter::ok(), shared.url.cyan());
+            println!("  Install with: {}", shared.install);
+        }
+    }
+    Ok(())
+}
+
+/// Install a skill from a shared gist
+fn install_skill(source: &str, reference: Option<&str>, force: bool, config: &Config) -> Result<()> {
+    let Some(id) = share::parse_gist_id(source) else {
+        if registry::is_git_url(source) {
+            return install_from_git(source, reference, force, config);
+        }
+        return Err(
+            CliError::new(ErrorCode::InvalidInput, format!("Not a gist or git URL: {}", source))
+                .with_hint("Install from a URL printed by `pais skill share <name>`, or a git repository URL")
+                .into(),
+        );
+    };
+    if reference.is_some() {
+        return Err(CliError::new(ErrorCode::InvalidInput, "--ref applies to git sources, not gists").into());
+    }
+    net::ensure_online(&config.network, "Installing from a gist")?;
+    let artifact = share::fetch_gist(&id)?;
+
+    if artifact.manifest.kind != ShareKind::Skill {
+        return Err(CliError::new(
+            ErrorCode::InvalidInput,
+            format!(
+                "Gist contains {} '{}', not a skill",
+                artifact.manifest.kind, artifact.manifest.name
+            ),
+        )
+        .with_hint(format!(
+            "Install it with: pais {} install {}",
+            artifact.manifest.kind, source
+        ))
+        .into());
+    }
+
+    let name = &artifact.manifest.name;
+    let skill_dir = Config::expand_path(&config.paths.skills).join(name);
+    if skill_dir.exists() {
+        if !force {
+            return Err(
+                CliError::new(ErrorCode::Conflict, format!("Skill '{}' already exists", name))
+                    .with_hint("Overwrite it with --force")
+                    .into(),
+            );
+        }
+        fs::remove_dir_all(&skill_dir)
+            .with_context(|| format!("Failed to remove existing skill: {}", skill_dir.display()))?;
+    }
+
+    artifact.write_to(&skill_dir)?;
+    let metadata = parse_skill_md(&skill_dir.join("SKILL.md"));
+    if let Err(e) = validate_skill_md(&skill_dir.join("SKILL.md")) {
+        log::warn!("Installed skill '{}' has an invalid SKILL.md: {}", name, e);
+    }
+    state::record(StateEvent::SkillInstalled {
+        name: name.to_string(),
+        source: artifact
+            .origin
+            .as_ref()
+            .map_or_else(|| source.to_string(), |o| o.url.clone()),
+    });
+    if let Some(origin) = &artifact.origin {
+        let (author, license) = metadata.map(|m| (m.author, m.license)).unwrap_or_default();
+        let provenance = Provenance::from_gist(origin).with_declared(author, license);
+        if let Err(e) = provenance::record(ArtifactKind::Skill, name, provenance) {
+            log::warn!("Failed to record provenance of skill '{}': {:#}", name, e);
+        }
+    }
+
+    println!("{} Installed skill: {}", presenter::ok(), name.cyan());
+    println!("  Path: {}", skill_dir.display());
+    println!();
+    println!("Make it available to Claude Code with: pais sync");
+    Ok(())
+}
+
+/// Install the skill, or every skill of the collection, in a git repository
+fn install_from_git(url: &str, reference: Option<&str>, force: bool, config: &Config) -> Result<()> {
+    println!("{} Fetching {}", presenter::arrow(), url.cyan());
+    let checkout = registry::fetch_git(url, reference, &config.network)?;
+    let found = skill_install::find(&checkout, &skill_install::repo_name(url));
+    if found.is_empty() && checkout.join("SKILL.md").is_file() {
+        return Err(
+            CliError::new(ErrorCode::InvalidInput, format!("Can't name the skill in {}", url))
+                .with_hint("Give its SKILL.md a valid name: in the frontmatter")
+                .into(),
+        );
+    }
+    if found.is_empty() {
+        return Err(CliError::new(
+            ErrorCode::NotFound,
+            format!(
+                "No SKILL.md or {}/ collection in {}",
+                skill_install::COLLECTION_DIR,
+                url
+            ),
+        )
+        .with_hint(format!(
+            "A skill repository has SKILL.md at its root, or one directory per skill under {}/",
+            skill_install::COLLECTION_DIR
+        ))
+        .into());
+    }
+
+    // Check every name first, so a collection installs whole or not at all
+    let skills_dir = Config::expand_path(&config.paths.skills);
+    let taken: Vec<&str> = found
+        .iter()
+        .filter(|skill| skills_dir.join(&skill.name).symlink_metadata().is_ok())
+        .map(|skill| skill.name.as_str())
+        .collect();
+    if !taken.is_empty() && !force {
+        return Err(CliError::new(
+            ErrorCode::Conflict,
+            format!("Skills already exist: {}", taken.join(", ")),
+        )
+        .with_hint("Overwrite them with --force")
+        .into());
+    }
+
+    let repo = Provenance::from_dir(&checkout);
+    for skill in &found {
+        let dest = skills_dir.join(&skill.name);
+        skill_install::copy_skill(&skill.dir, &dest)?;
+        record_git_install(&skill.name, &dest, url, reference, skill.subdir.as_deref(), &repo);
+        println!("{} Installed skill: {}", presenter::ok(), skill.name.cyan());
+        println!("  Path: {}", dest.display());
+    }
+    println!();
+    println!("Make it available to Claude Code with: pais sync");
+    Ok(())
+}
+
+/// Validate a skill installed from git and record where it came from
+fn record_git_install(
+    name: &str,
+    dir: &Path,
+    url: &str,
+    reference: Option<&str>,
+    subdir: Option<&str>,
+    repo: &Provenance,
+) {
+    let skill_md = dir.join("SKILL.md");
+    if let Err(e) = validate_skill_md(&skill_md) {
+        log::warn!("Installed skill '{}' has an invalid SKILL.md: {}", name, e);
+    }
+    state::record(StateEvent::SkillInstalled {
+        name: name.to_string(),
+        source: url.to_string(),
+    });
+
+    let (author, license) = parse_skill_md(&skill_md)
+        .map(|m| (m.author, m.license))
+        .unwrap_or_default();
+    // A collection skill's own license file beats the repository's
+    let mut provenance = repo.clone();
+    provenance.license = provenance::detect_license(dir).or(provenance.license);
+    let mut provenance = provenance.with_declared(author, license);
+    provenance.install_source = Some(url.to_string());
+    provenance.git_ref = reference.map(String::from);
+    provenance.subdir = subdir.map(String::from);
+    if let Err(e) = provenance::record(ArtifactKind::Skill, name, provenance) {
+        log::warn!("Failed to record provenance of skill '{}': {:#}", name, e);
+    }
+}
+
+/// Where a skill installed from a remote source can be fetched again
+enum RemoteSource {
+    Git {
+        url: String,
+        reference: Option<String>,
+        subdir: Option<String>,
+    },
+    Gist(String),
+}
+
+impl RemoteSource {
+    /// The source a provenance record points back to, if it's remote
+    fn of(recorded: &Provenance) -> Option<Self> {
+        match &recorded.install_source {
+            Some(url) if registry::is_git_url(url) => Some(Self::Git {
+                url: url.clone(),
+                reference: recorded.git_ref.clone(),
+                subdir: recorded.subdir.clone(),
+            }),
+            _ => share::parse_gist_id(&recorded.source)
+                .filter(|_| recorded.source.contains("gist"))
+                .map(Self::Gist),
+        }
+    }
+}
+
+/// The current copy of a remote skill
+struct RemoteSkill {
+    files: RemoteFiles,
+    version: Option<String>,
+    /// Git commit or gist revision
+    commit: Option<String>,
+    changelog: Option<String>,
+}
+
+enum RemoteFiles {
+    Git {
+        url: String,
+        reference: Option<String>,
+        subdir: Option<String>,
+        checkout: PathBuf,
+        dir: PathBuf,
+        repo: Provenance,
+    },
+    Gist(Artifact),
+}
+
+/// Fetch a skill's source again; `reference` moves a git source to another ref
+fn fetch_remote(source: RemoteSource, reference: Option<&str>, config: &Config) -> Result<RemoteSkill> {
+    match source {
+        RemoteSource::Git {
+            url,
+            reference: recorded,
+            subdir,
+        } => {
+            let reference = reference.map(String::from).or(recorded);
+            let checkout = registry::fetch_git(&url, reference.as_deref(), &config.network)?;
+            let dir = skill_install::locate(&checkout, subdir.as_deref()).ok_or_else(|| {
+                CliError::new(
+                    ErrorCode::NotFound,
+                    format!(
+                        "{} no longer has a skill at {}",
+                        url,
+                        subdir.as_deref().unwrap_or("its root")
+                    ),
+                )
+            })?;
+            let repo = Provenance::from_dir(&checkout);
+            Ok(RemoteSkill {
+                version: parse_skill_md(&dir.join("SKILL.md")).ok().and_then(|m| m.version),
+                commit: repo.commit.clone(),
+                changelog: skill_upgrade::CHANGELOG_FILES
+                    .iter()
+                    .find_map(|file| fs::read_to_string(dir.join(file)).ok()),
+                files: RemoteFiles::Git {
+                    url,
+                    reference,
+                    subdir,
+                    checkout,
+                    dir,
+                    repo,
+                },
+            })
+        }
+        RemoteSource::Gist(id) => {
+            if reference.is_some() {
+                return Err(CliError::new(ErrorCode::InvalidInput, "--ref applies to git sources, not gists").into());
+            }
+            net::ensure_online(&config.network, "Checking a gist")?;
+            let artifact = share::fetch_gist(&id)?;
+            Ok(RemoteSkill {
+                version: artifact
+                    .files
+                    .get("SKILL.md")
+                    .and_then(|content| parse_frontmatter(content).ok())
+                    .and_then(|m| m.version),
+                commit: artifact.origin.as_ref().and_then(|o| o.revision.clone()),
+                changelog: skill_upgrade::CHANGELOG_FILES
+                    .iter()
+                    .find_map(|file| artifact.files.get(*file).cloned()),
+                files: RemoteFiles::Gist(artifact),
+            })
+        }
+    }
+}
+
+/// Skills installed from a git repository or gist, by name
+fn remote_skills(config: &Config) -> Vec<(String, Provenance)> {
+    let skills_dir = Config::expand_path(&config.paths.skills);
+    commands::provenance::load()
+        .items
+        .remove(&ArtifactKind::Skill)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(name, recorded)| RemoteSource::of(recorded).is_some() && skills_dir.join(name).is_dir())
+        .collect()
+}
+
+/// Version (and short commit) for display
+fn describe_version(version: Option<&str>, commit: Option<&str>) -> String {
+    let commit = commit.map(|c| &c[..c.len().min(8)]);
+    match (version, commit) {
+        (Some(version), Some(commit)) => format!("{} ({})", version, commit),
+        (Some(version), None) => version.to_string(),
+        (None, Some(commit)) => commit.to_string(),
+        (None, None) => "unknown".to_string(),
+    }
+}
+
+/// A skill whose source moved on, for `pais skill outdated`
+#[derive(Serialize)]
+struct OutdatedSkill {
+    name: String,
+    installed: Option<String>,
+    latest: Option<String>,
+    status: Option<skill_upgrade::Status>,
+    source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// List skills from remote sources with a newer version or commit upstream
+fn outdated_skills(format: OutputFormat, config: &Config) -> Result<()> {
+    let skills_dir = Config::expand_path(&config.paths.skills);
+    let remote = remote_skills(config);
+    let spinner = progress::spinner(format, "Checking skill sources");
+    let rows: Vec<OutdatedSkill> = remote
+        .iter()
+        .filter_map(|(name, recorded)| {
+            let installed = parse_skill_md(&skills_dir.join(name).join("SKILL.md"))
+                .ok()
+                .and_then(|m| m.version);
+            let source = recorded
+                .install_source
+                .clone()
+                .unwrap_or_else(|| recorded.source.clone());
+            let fetched = RemoteSource::of(recorded)
+                .ok_or_else(|| eyre::eyre!("no remote source"))
+                .and_then(|remote| fetch_remote(remote, None, config));
+            let row = match fetched {
+                Ok(fetched) => {
+                    let status = skill_upgrade::status(
+                        installed.as_deref(),
+                        recorded.commit.as_deref(),
+                        fetched.version.as_deref(),
+                        fetched.commit.as_deref(),
+                    );
+                    if status == skill_upgrade::Status::UpToDate {
+                        return None;
+                    }
+                    OutdatedSkill {
+                        name: name.clone(),
+                        installed: installed.clone(),
+                        latest: fetched.version,
+                        status: Some(status),
+                        source,
+                        error: None,
+                    }
+                    .with_commits(recorded.commit.as_deref(), fetched.commit.as_deref())
+                }
+                Err(e) => OutdatedSkill {
+                    name: name.clone(),
+                    installed,
+                    latest: None,
+                    status: None,
+                    source,
+                    error: Some(format!("{:#}", e)),
+                },
+            };
+            Some(row)
+        })
+        .collect();
+    spinner.finish_and_clear();
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&rows)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&rows)?),
+        OutputFormat::Text => {
+            if remote.is_empty() {
+                println!("{}", "No skills were installed from a git repository or gist".dimmed());
+                return Ok(());
+            }
+            if rows.is_empty() {
+                println!("{} All {} remote skills are up to date", presenter::ok(), remote.len());
+                return Ok(());
+            }
+            let mut table = Table::new(&["NAME", "INSTALLED", "LATEST", "STATUS"]);
+            for row in &rows {
+                let status = match (&row.status, &row.error) {
+                    (Some(status), _) => status.to_string().yellow(),
+                    (None, Some(error)) => format!("failed: {}", error).red(),
+                    (None, None) => "".normal(),
+                };
+                table.add_row(vec![
+                    row.name.cyan(),
+                    row.installed.as_deref().unwrap_or("-").normal(),
+                    row.latest.as_deref().unwrap_or("-").normal(),
+                    status,
+                ]);
+            }
+            table.print();
+            println!();
+            println!("{}", "Upgrade with: pais skill upgrade <name|all>".dimmed());
+        }
+    }
+    Ok(())
+}
+
+impl OutdatedSkill {
+    /// Show commits next to the versions when only the commit moved
+    fn with_commits(mut self, installed: Option<&str>, latest: Option<&str>) -> Self {
+        if self.status == Some(skill_upgrade::Status::Changed) {
+            self.installed = Some(describe_version(self.installed.as_deref(), installed));
+            self.latest = Some(describe_version(self.latest.as_deref(), latest));
+        }
+        self
+    }
+}
+
+/// Upgrade one skill, or every skill from a remote source for "all"
+fn upgrade_skills(name: &str, reference: Option<&str>, force: bool, yes: bool, config: &Config) -> Result<()> {
+    if name != "all" {
+        return upgrade_skill(name, reference, force, yes, config);
+    }
+    if reference.is_some() {
+        return Err(CliError::new(ErrorCode::InvalidInput, "--ref applies to a single skill").into());
+    }
+    let names: Vec<String> = remote_skills(config).into_iter().map(|(name, _)| name).collect();
+    if names.is_empty() {
+        println!(
+            "{} No skills were installed from a git repository or gist",
+            presenter::info()
+        );
+        return Ok(());
+    }
+    let mut failed = 0;
+    for name in &names {
+        if let Err(e) = upgrade_skill(name, None, force, yes, config) {
+            failed += 1;
+            println!("{} {}: {:#}", presenter::fail(), name.red(), e);
+        }
+    }
+    if failed > 0 {
+        eyre::bail!("{} of {} skills failed to upgrade", failed, names.len());
+    }
+    Ok(())
+}
+
+/// Fetch a skill's source again, show what changed and replace the installed copy
+fn upgrade_skill(name: &str, reference: Option<&str>, force: bool, yes: bool, config: &Config) -> Result<()> {
+    println!("{} Upgrading skill: {}", presenter::arrow(), name.cyan());
+    let recorded = commands::provenance::load().get(ArtifactKind::Skill, name).cloned();
+    let Some((recorded, source)) = recorded.and_then(|p| RemoteSource::of(&p).map(|source| (p, source))) else {
+        return Err(CliError::new(
+            ErrorCode::NotFound,
+            format!("Skill '{}' wasn't installed from a git repository or gist", name),
+        )
+        .with_hint("Install it with: pais skill install <git-url|gist-url> --force")
+        .into());
+    };
+    let fetched = fetch_remote(source, reference, config)?;
+    let dest = Config::expand_path(&config.paths.skills).join(name);
+    let installed = parse_skill_md(&dest.join("SKILL.md")).ok().and_then(|m| m.version);
+
+    let status = skill_upgrade::status(
+        installed.as_deref(),
+        recorded.commit.as_deref(),
+        fetched.version.as_deref(),
+        fetched.commit.as_deref(),
+    );
+    let before = describe_version(installed.as_deref(), recorded.commit.as_deref());
+    if status == skill_upgrade::Status::UpToDate && !force {
+        println!("  {} Already up to date: {}", presenter::ok(), before);
+        return Ok(());
+    }
+    println!(
+        "  {} {} → {}",
+        presenter::arrow(),
+        before,
+        describe_version(fetched.version.as_deref(), fetched.commit.as_deref())
+    );
+    print_changes(&fetched, installed.as_deref(), recorded.commit.as_deref());
+
+    if !yes && !confirm(&format!("Overwrite skill '{}' at {}?", name, dest.display()))? {
+        println!("Cancelled.");
+        return Ok(());
+    }
+
+    match fetched.files {
+        RemoteFiles::Git {
+            url,
+            reference,
+            subdir,
+            dir,
+            repo,
+            ..
+        } => {
+            skill_install::copy_skill(&dir, &dest)?;
+            record_git_install(name, &dest, &url, reference.as_deref(), subdir.as_deref(), &repo);
+        }
+        RemoteFiles::Gist(artifact) => {
+            fs::remove_dir_all(&dest).with_context(|| format!("Failed to remove {}", dest.display()))?;
+            artifact.write_to(&dest)?;
+            if let Some(origin) = &artifact.origin {
+                let (author, license) = parse_skill_md(&dest.join("SKILL.md"))
+                    .map(|m| (m.author, m.license))
+                    .unwrap_or_default();
+                let provenance = Provenance::from_gist(origin).with_declared(author, license);
+                if let Err(e) = provenance::record(ArtifactKind::Skill, name, provenance) {
+                    log::warn!("Failed to record provenance of skill '{}': {:#}", name, e);
+                }
+            }
+        }
+    }
+    println!("  {} Upgraded {}", presenter::ok(), dest.display());
+    Ok(())
+}
+
+/// Print the changelog entries since the installed version, or else the git log
+fn print_changes(fetched: &RemoteSkill, installed: Option<&str>, installed_commit: Option<&str>) {
+    let changes = fetched
+        .changelog
+        .as_deref()
+        .and_then(|changelog| skill_upgrade::changelog_since(changelog, installed, fetched.version.as_deref()))
+        .or_else(|| match (&fetched.files, installed_commit, &fetched.commit) {
+            (RemoteFiles::Git { checkout, subdir, .. }, Some(old), Some(new)) if old != new => {
+                let output = Command::new("git")
+                    .args(["log", "--format=- %s (%h)", "-20", &format!("{}..{}", old, new), "--"])
+                    .arg(subdir.as_deref().unwrap_or("."))
+                    .current_dir(checkout)
+                    .output()
+                    .ok()
+                    .filter(|o| o.status.success())?;
+                let log = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                (!log.is_empty()).then_some(log)
+            }
+            _ => None,
+        });
+    match changes {
+        Some(changes) => {
+            println!();
+            for line in changes.lines() {
+                println!("    {}", line);
+            }
+            println!();
+        }
+        None => println!("  {}", "No changelog entries".dimmed()),
+    }
+}
+
+/// Ask a yes/no question on the terminal; no means no
+fn confirm(question: &str) -> Result<bool> {
+    if !std::io::stdin().is_terminal() {
+        return Err(
+            CliError::new(ErrorCode::InvalidInput, "Confirming needs an interactive terminal")
+                .with_hint("Pass --yes to overwrite without asking")
+                .into(),
+        );
+    }
+    print!("{} [y/N] ", question);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim().eq_ignore_ascii_case("y"))
+}
+
+/// `pais skill stats` output
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct SkillStatsReport {
+    weeks: usize,
+    since: chrono::NaiveDate,
+    skills: Vec<skill_usage::SkillStats>,
+    /// Installed skills with no use in the window
+    unused: Vec<UnusedSkill>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct UnusedSkill {
+    name: String,
+    /// An older use on record, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_used: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Per-skill uses over the last `weeks` weeks, from `SkillUsed` events
+fn skill_stats(weeks: usize, format: OutputFormat, config: &Config) -> Result<()> {
+    let weeks = weeks.max(1);
+    let history_path = Config::expand_path(&config.paths.history);
+    let events = skill_uses(&history_path, weeks as i64 * 7);
+    let mut installed = skill_names(config)?;
+    installed.sort();
+    installed.dedup();
+
+    let today = clock::now().date_naive();
+    let uses = events.iter().filter_map(|event| {
+        let when = chrono::DateTime::parse_from_rfc3339(&event.timestamp).ok()?;
+        let skill = event.skill.as_ref()?;
+        Some((when.to_utc(), event.session_id.as_deref(), skill.name.as_str()))
+    });
+    let (used, unused): (Vec<_>, Vec<_>) = skill_usage::stats(uses, &installed, today, weeks)
+        .into_iter()
+        .partition(|row| row.uses > 0);
+    // Skills unused in the window may still have an older use on record
+    let store = skill_usage::UsageStore::load(&skill_usage::usage_path());
+    let report = SkillStatsReport {
+        weeks,
+        since: today - chrono::Duration::days(weeks as i64 * 7 - 1),
+        skills: used,
+        unused: unused
+            .into_iter()
+            .map(|row| UnusedSkill {
+                last_used: store.skills.get(&row.name).map(|usage| usage.last_used),
+                name: row.name,
+            })
+            .collect(),
+    };
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&report)?),
+        OutputFormat::Text => {
+            let file_sink =
+                config.observability.enabled && config.observability.sinks.contains(&ObservabilitySink::File);
+            if !file_sink || !config.skills.usage.track {
+                println!(
+                    "{} Skill uses are only recorded with skills.usage.track and observability's file sink on",
+                    presenter::warn()
+                );
+            }
+            println!(
+                "{} Skill usage since {} ({} weeks)",
+                presenter::symbol("📊", "*").blue(),
+                report.since,
+                report.weeks
+            );
+            println!();
+            if report.skills.is_empty() {
+                println!("  {}", "(no skill used)".dimmed());
+            } else {
+                let mut table = Table::new(&["SKILL", "USES", "SESSIONS", "LAST USED", "PER WEEK"]);
+                for row in &report.skills {
+                    table.add_row(vec![
+                        row.name.cyan(),
+                        row.uses.to_string().normal(),
+                        row.sessions.to_string().normal(),
+                        format_last_used(row.last_used).normal(),
+                        history_stats::sparkline(&row.weeks, presenter::ascii()).green(),
+                    ]);
+                }
+                table.print();
+            }
+            if !report.unused.is_empty() {
+                println!();
+                println!(
+                    "{} {} installed skills unused in {} weeks:",
+                    presenter::info(),
+                    report.unused.len(),
+                    report.weeks
+                );
+                for skill in &report.unused {
+                    let last = match skill.last_used {
+                        Some(when) => format!("last used {}", format_last_used(Some(when))),
+                        None => "never used".to_string(),
+                    };
+                    println!("  {} {}", skill.name.yellow(), format!("({})", last).dimmed());
+                }
+                println!();
+                println!("{}", "Remove one with: pais skill remove <name>".dimmed());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn format_last_used(when: Option<chrono::DateTime<chrono::Utc>>) -> String {
+    when.map(|when| when.with_timezone(&chrono::Local).format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
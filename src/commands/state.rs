@@ -0,0 +1,272 @@
+//! State log commands
+//!
+//! `pais state log` lists what pais changed in its own setup, `show` folds the
+//! log into the setup it describes and `verify` checks the log's hash chain
+//! and compares the result with the disk. See [`crate::state`].
+
+use colored::*;
+use eyre::Result;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fs;
+
+use crate::cli::{OutputFormat, StateAction};
+use crate::commands::agent::agent_names;
+use crate::config::Config;
+use crate::error::{CliError, ErrorCode};
+use crate::presenter::{self, Table};
+use crate::skill::cache::content_hash;
+use crate::skill::loader::subdirectories;
+use crate::state::{ChainError, State, StateLog, StateRecord, verify_chain};
+
+/// Run a state subcommand
+pub fn run(action: StateAction, format: OutputFormat, config: &Config) -> Result<()> {
+    match action {
+        StateAction::Log { event, limit } => log(event.as_deref(), limit, format),
+        StateAction::Show => show(format),
+        StateAction::Verify => verify(format, config),
+    }
+}
+
+fn log(event: Option<&str>, limit: Option<usize>, format: OutputFormat) -> Result<()> {
+    let log = StateLog::open();
+    let mut records: Vec<StateRecord> = log
+        .records()?
+        .into_iter()
+        .filter(|r| event.is_none_or(|e| r.event.kind() == e))
+        .collect();
+    if let Some(limit) = limit {
+        records.drain(..records.len().saturating_sub(limit));
+    }
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&records)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&records)?),
+        OutputFormat::Text => {
+            if records.is_empty() {
+                println!(
+                    "{} No state changes recorded in {}",
+                    presenter::info(),
+                    log.path().display()
+                );
+                return Ok(());
+            }
+            let mut table = Table::new(&["SEQ", "TIME", "EVENT", "DETAILS"]);
+            for record in &records {
+                table.add_row(vec![
+                    record.seq.to_string().dimmed(),
+                    record
+                        .at
+                        .with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string()
+                        .normal(),
+                    record.event.kind().cyan(),
+                    record.event.summary().normal(),
+                ]);
+            }
+            table.print();
+        }
+    }
+    Ok(())
+}
+
+fn show(format: OutputFormat) -> Result<()> {
+    let state = State::replay(&StateLog::open().records()?);
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&state)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&state)?),
+        OutputFormat::Text => {
+            if state.events == 0 {
+                println!("{} No state changes recorded", presenter::info());
+                return Ok(());
+            }
+            let time = |at: &chrono::DateTime<chrono::Utc>| {
+                at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string()
+            };
+
+            if !state.plugins.is_empty() {
+                println!("{}", presenter::banner("Plugins"));
+            }
+            for (name, plugin) in &state.plugins {
+                println!(
+                    "  {} v{}{}  {}  {}",
+                    name.cyan(),
+                    plugin.version,
+                    if plugin.dev { " (dev)".dimmed().to_string() } else { String::new() },
+                    plugin.source.dimmed(),
+                    time(&plugin.installed).dimmed()
+                );
+            }
+            if !state.plugins.is_empty() {
+                println!();
+            }
+            for (title, items) in [
+                ("Skills", &state.skills),
+                ("Agents", &state.agents),
+                ("Bundles", &state.bundles),
+            ] {
+                if items.is_empty() {
+                    continue;
+                }
+                println!("{}", presenter::banner(title));
+                for (name, item) in items {
+                    println!(
+                        "  {}  {}  {}",
+                        name.cyan(),
+                        item.source.dimmed(),
+                        time(&item.installed).dimmed()
+                    );
+                }
+                println!();
+            }
+
+            let never = || "never".dimmed().to_string();
+            println!(
+                "Config version: {}",
+                state.config_version.map(|v| format!("v{}", v)).unwrap_or_else(never)
+            );
+            println!(
+                "Last sync:        {}",
+                state.last_sync.as_ref().map(time).unwrap_or_else(never)
+            );
+            println!(
+                "Last remote sync: {}",
+                state.last_remote_sync.as_ref().map(time).unwrap_or_else(never)
+            );
+            println!("{}", format!("Reconstructed from {} event(s)", state.events).dimmed());
+        }
+    }
+    Ok(())
+}
+
+/// What `verify` found
+#[derive(Debug, Serialize)]
+struct Verification {
+    events: usize,
+    chain: Vec<ChainError>,
+    /// Recorded as installed but gone from disk
+    missing: Vec<String>,
+    /// On disk but never installed through pais (informational)
+    untracked: Vec<String>,
+    /// pais.yaml no longer matches the last `pais config set`
+    config_edited: bool,
+}
+
+/// Names on disk, as `kind/name`
+fn installed(config: &Config) -> Result<BTreeSet<String>> {
+    let mut names = BTreeSet::new();
+    let mut add = |kind: &str, dir: std::path::PathBuf, marker: &str| -> Result<()> {
+        if dir.exists() {
+            for path in subdirectories(&dir)? {
+                if path.join(marker).exists() {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    names.insert(format!("{}/{}", kind, name));
+                }
+            }
+        }
+        Ok(())
+    };
+    add("plugin", Config::expand_path(&config.paths.plugins), "plugin.yaml")?;
+    add("skill", Config::expand_path(&config.paths.skills), "SKILL.md")?;
+    for name in agent_names(config).unwrap_or_default() {
+        names.insert(format!("agent/{}", name));
+    }
+    Ok(names)
+}
+
+fn verify(format: OutputFormat, config: &Config) -> Result<()> {
+    let log = StateLog::open();
+    let lines = log.read()?;
+    let chain = verify_chain(&lines);
+    let records: Vec<StateRecord> = lines.into_iter().filter_map(Result::ok).collect();
+    let state = State::replay(&records);
+
+    let recorded: BTreeSet<String> = state
+        .plugins
+        .keys()
+        .map(|n| format!("plugin/{}", n))
+        .chain(state.skills.keys().map(|n| format!("skill/{}", n)))
+        .chain(state.agents.keys().map(|n| format!("agent/{}", n)))
+        .collect();
+    let on_disk = installed(config)?;
+    let config_edited = state.config_hash.as_ref().is_some_and(|hash| {
+        fs::read(Config::pais_dir().join("pais.yaml")).is_ok_and(|bytes| content_hash(&bytes) != *hash)
+    });
+    let verification = Verification {
+        events: records.len(),
+        chain,
+        missing: recorded.difference(&on_disk).cloned().collect(),
+        untracked: on_disk.difference(&recorded).cloned().collect(),
+        config_edited,
+    };
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&verification)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&verification)?),
+        OutputFormat::Text => print_verification(&verification, &log),
+    }
+
+    if !verification.chain.is_empty() {
+        return Err(CliError::new(
+            ErrorCode::Parse,
+            format!("State log is damaged ({} problem(s))", verification.chain.len()),
+        )
+        .with_hint(format!(
+            "Records after the first problem can't be trusted; inspect {}",
+            log.path().display()
+        ))
+        .into());
+    }
+    if !verification.missing.is_empty() {
+        return Err(CliError::new(
+            ErrorCode::Conflict,
+            format!("{} recorded item(s) are missing from disk", verification.missing.len()),
+        )
+        .with_hint("They were removed outside pais; reinstall or remove them with pais to record it")
+        .into());
+    }
+    Ok(())
+}
+
+fn print_verification(v: &Verification, log: &StateLog) {
+    println!("{} Verifying {}", presenter::arrow(), log.path().display());
+
+    if v.chain.is_empty() {
+        println!("  {} Hash chain intact ({} event(s))", presenter::ok(), v.events);
+    } else {
+        for error in &v.chain {
+            println!("  {} line {}: {}", presenter::fail(), error.line, error.message);
+        }
+    }
+
+    if v.missing.is_empty() {
+        println!("  {} Everything recorded as installed is on disk", presenter::ok());
+    } else {
+        for item in &v.missing {
+            println!(
+                "  {} {} recorded as installed but missing from disk",
+                presenter::fail(),
+                item.cyan()
+            );
+        }
+    }
+
+    if v.config_edited {
+        println!(
+            "  {} pais.yaml was edited outside {} since it was last recorded",
+            presenter::warn(),
+            "pais config set".cyan()
+        );
+    }
+
+    if !v.untracked.is_empty() {
+        println!(
+            "  {} {} item(s) on disk were not installed through pais: {}",
+            presenter::info(),
+            v.untracked.len(),
+            v.untracked.join(", ").dimmed()
+        );
+    }
+}
@@ -1,8 +1,9 @@
 //! System status command
 //!
-//! Shows comprehensive PAIS system health and configuration.
+//! Shows comprehensive PAIS system health and configuration. `--perf` shows
+//! the self-metrics from [`crate::perf`] instead.
 
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, Utc};
 use colored::*;
 use eyre::Result;
 use serde::Serialize;
@@ -13,7 +14,10 @@ use std::path::PathBuf;
 use crate::agent::loader::AgentLoader;
 use crate::cli::OutputFormat;
 use crate::config::Config;
+use crate::history::categorize::RESERVED_DIRS;
+use crate::perf::{self, CommandStats};
 use crate::plugin::PluginManager;
+use crate::presenter::{self, Table};
 use crate::skill::loader::{discover_plugin_skills, discover_simple_skills};
 
 #[derive(Serialize)]
@@ -79,6 +83,41 @@ struct CategoryStats {
     latest: Option<String>,
 }
 
+#[derive(Serialize)]
+struct PerfStatus {
+    log: PathBuf,
+    records: usize,
+    since: Option<DateTime<Utc>>,
+    /// Records from debug builds, which the budgets don't apply to
+    debug_records: usize,
+    commands: Vec<CommandStats>,
+    caches: Vec<CacheStatus>,
+}
+
+#[derive(Serialize)]
+struct CacheStatus {
+    name: String,
+    hits: u64,
+    misses: u64,
+    hit_rate: Option<f64>,
+}
+
+/// Print the cached prompt segment (see `crate::prompt`)
+pub fn prompt(format: OutputFormat) -> Result<()> {
+    let summary = crate::prompt::current_summary();
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string(&summary)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&summary)?),
+        OutputFormat::Text => {
+            let segment = summary.render();
+            if !segment.is_empty() {
+                println!("{}", segment);
+            }
+        }
+    }
+    Ok(())
+}
+
 pub fn run(format: OutputFormat, config: &Config) -> Result<()> {
     let pais_dir = Config::pais_dir();
     let plugins_dir = Config::expand_path(&config.paths.plugins);
@@ -182,6 +221,117 @@ pub fn run(format: OutputFormat, config: &Config) -> Result<()> {
     Ok(())
 }
 
+/// Summarize the self-metrics log
+pub fn perf(format: OutputFormat) -> Result<()> {
+    let log = perf::log_path();
+    let records = perf::read(&log)?;
+    let (commands, caches) = perf::summarize(&records);
+    let status = PerfStatus {
+        records: records.len(),
+        since: records.first().map(|r| r.at),
+        debug_records: records.iter().filter(|r| !r.release).count(),
+        commands,
+        caches: caches
+            .into_iter()
+            .map(|(name, count)| CacheStatus {
+                name,
+                hits: count.hits,
+                misses: count.misses,
+                hit_rate: count.hit_rate(),
+            })
+            .collect(),
+        log,
+    };
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&status)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&status)?),
+        OutputFormat::Text => print_perf(&status),
+    }
+    Ok(())
+}
+
+fn print_perf(status: &PerfStatus) {
+    let Some(since) = status.since else {
+        println!(
+            "{} No timings recorded yet in {} (perf.enabled records every command)",
+            presenter::info(),
+            status.log.display()
+        );
+        return;
+    };
+    println!("{}", "PAIS Performance".bold());
+    println!(
+        "{}",
+        format!(
+            "{} runs since {}",
+            status.records,
+            since.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+        )
+        .dimmed()
+    );
+    println!();
+
+    let mut table = Table::new(&["COMMAND", "RUNS", "MEDIAN", "P95", "MAX", "BUDGET"]);
+    for stats in &status.commands {
+        let budget = match stats.budget_ms {
+            Some(budget) if stats.over_budget() => format!("{}ms", budget).red(),
+            Some(budget) => format!("{}ms", budget).green(),
+            None => "-".dimmed(),
+        };
+        let runs = if stats.failed > 0 {
+            format!("{} ({} failed)", stats.runs, stats.failed).yellow()
+        } else {
+            stats.runs.to_string().normal()
+        };
+        table.add_row(vec![
+            stats.command.cyan(),
+            runs,
+            format!("{:.1}ms", stats.median_ms).normal(),
+            format!("{:.1}ms", stats.p95_ms).normal(),
+            format!("{:.1}ms", stats.max_ms).dimmed(),
+            budget,
+        ]);
+    }
+    table.print();
+
+    if !status.caches.is_empty() {
+        println!();
+        let mut table = Table::new(&["CACHE", "HITS", "MISSES", "HIT RATE"]);
+        for cache in &status.caches {
+            let rate = cache
+                .hit_rate
+                .map(|rate| format!("{:.0}%", rate * 100.0))
+                .unwrap_or_else(|| "-".to_string());
+            table.add_row(vec![
+                cache.name.cyan(),
+                cache.hits.to_string().normal(),
+                cache.misses.to_string().normal(),
+                rate.normal(),
+            ]);
+        }
+        table.print();
+    }
+
+    if status.commands.iter().any(|c| c.over_budget()) {
+        println!();
+        println!(
+            "{} Over budget: compare with `pais bench`, and see docs/performance.md",
+            presenter::warn()
+        );
+    }
+    if status.debug_records > 0 {
+        println!(
+            "{}",
+            format!(
+                "{} run(s) from debug builds: budgets assume a release build",
+                status.debug_records
+            )
+            .yellow()
+        );
+    }
+}
+
 fn gather_history_stats(history_dir: &PathBuf) -> HistoryStatus {
     let mut categories = HashMap::new();
     let mut total_entries = 0;
@@ -195,7 +345,7 @@ fn gather_history_stats(history_dir: &PathBuf) -> HistoryStatus {
                 let name = entry.file_name().to_string_lossy().to_string();
 
                 // Skip raw-events and security (they're JSONL, not markdown)
-                if name == "raw-events" || name == "security" {
+                if RESERVED_DIRS.contains(&name.as_str()) {
                     continue;
                 }
 
@@ -278,7 +428,7 @@ fn print_text_status(status: &Status) {
             let hooks_badge = if plugin.has_hooks { " [hooks]".yellow().to_string() } else { String::new() };
             println!(
                 "  {} {} {} {}{}",
-                "✓".green(),
+                presenter::ok(),
                 plugin.name.green(),
                 format!("v{}", plugin.version).dimmed(),
                 format!("[{}]", plugin.language).dimmed(),
@@ -304,14 +454,14 @@ fn print_text_status(status: &Status) {
             println!("  {} Core tier (always loaded):", "●".yellow());
             for skill in core_skills {
                 let source_badge = format!("[{}]", skill.source).dimmed();
-                println!("    {} {} {}", "✓".green(), skill.name, source_badge);
+                println!("    {} {} {}", presenter::ok(), skill.name, source_badge);
             }
         }
         if !deferred_skills.is_empty() {
             println!("  {} Deferred tier:", "○".dimmed());
             for skill in deferred_skills {
                 let source_badge = format!("[{}]", skill.source).dimmed();
-                println!("    {} {} {}", "✓".green(), skill.name, source_badge);
+                println!("    {} {} {}", presenter::ok(), skill.name, source_badge);
             }
         }
     }
@@ -334,7 +484,7 @@ fn print_text_status(status: &Status) {
             };
             println!(
                 "  {} {} → {} ({})",
-                "✓".green(),
+                presenter::ok(),
                 agent.name.green(),
                 agent.history_category.cyan(),
                 traits_str
@@ -355,7 +505,7 @@ fn print_text_status(status: &Status) {
     if status.observability.enabled {
         println!(
             "  {} Enabled: {}",
-            "✓".green(),
+            presenter::ok(),
             status.observability.sinks.join(", ").cyan()
         );
     } else {
@@ -393,7 +543,7 @@ fn print_text_status(status: &Status) {
 
 fn print_hook_status(name: &str, enabled: bool) {
     if enabled {
-        println!("  {} {}", "✓".green(), name);
+        println!("  {} {}", presenter::ok(), name);
     } else {
         println!("  {} {} {}", "○".dimmed(), name, "(disabled)".dimmed());
     }
@@ -2,27 +2,235 @@
 //!
 //! Syncs PAIS skills to ~/.claude/skills/ using symlinks so Claude Code can discover them.
 //! Also generates ARCHITECTURE.md after sync.
+//!
+//! `pais sync --watch` keeps running and relinks skills as they are added,
+//! renamed or removed, refreshing the skill index along the way.
+//!
+//! `pais sync --target cursor|windsurf|vscode` exports skills and MCP servers
+//! to other editors instead.
+//!
+//! `pais sync remote` synchronizes PAIS state with another machine via a remote.
+//!
+//! With `--dry-run -o json|yaml`, each variant prints its change plan (see
+//! [`crate::plan`]) instead of the colored text.
 
+use colored::*;
 use eyre::{Context, Result};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use crate::architecture;
+use crate::cli::{OutputFormat, SyncAction};
+use crate::commands::session;
 use crate::config::Config;
-use crate::skill::loader::{discover_plugin_skills, discover_simple_skills};
-use crate::skill::parser::has_skill_md;
+use crate::error::{CliError, ErrorCode};
+use crate::lock::FileLock;
+use crate::net;
+use crate::plan::{ChangePlan, Op};
+use crate::presenter;
+use crate::skill::conflicts;
+use crate::skill::indexer::{generate_context_snippet, generate_index, write_index};
+use crate::skill::loader::{discover_plugin_skills, discover_simple_skills, load_plugin_skill, load_simple_skill};
+use crate::skill::parser::{has_skill_md, is_simple_skill};
+use crate::skill::subagent;
+use crate::state::{self, StateEvent};
+use crate::sync::backend::Remote;
+use crate::sync::clean;
+use crate::sync::targets::{self, RuleSkill, Target};
+use crate::sync::watch::{self, SkillWatcher};
+use crate::sync::{self, ChangeAction, SyncRoot};
+use crate::team;
 
 /// Run the sync command
-pub fn run(dry_run: bool, clean: bool, config: &Config) -> Result<()> {
+pub fn run(
+    action: Option<SyncAction>,
+    dry_run: bool,
+    clean: bool,
+    watch: bool,
+    format: OutputFormat,
+    config: &Config,
+) -> Result<()> {
+    if let Some(SyncAction::Remote {
+        remote,
+        history,
+        prefer,
+        dry_run,
+    }) = action
+    {
+        let mut plan = ChangePlan::new(dry_run, format);
+        sync_remote(remote, history, prefer, &mut plan, config)?;
+        return plan.finish();
+    }
+
     let claude_skills_dir = get_claude_skills_dir()?;
 
+    let mut plan = ChangePlan::new(dry_run, format);
     if clean {
-        clean_orphaned_symlinks(&claude_skills_dir, dry_run, config)?;
+        clean_skills(&claude_skills_dir, &mut plan, config)?;
+    } else if watch {
+        watch_skills(&claude_skills_dir, config)?;
     } else {
-        sync_skills(&claude_skills_dir, dry_run, config)?;
+        sync_skills(&claude_skills_dir, &mut plan, config)?;
     }
 
-    Ok(())
+    plan.finish()
+}
+
+/// Export skills and MCP servers to another editor's config files
+pub fn export(
+    target: Target,
+    project: Option<PathBuf>,
+    skill: Option<Vec<String>>,
+    mcp: Option<Vec<String>>,
+    dry_run: bool,
+    format: OutputFormat,
+    config: &Config,
+) -> Result<()> {
+    let mut plan = ChangePlan::new(dry_run, format);
+    let project = match project {
+        Some(dir) => Config::expand_path(&dir),
+        None => std::env::current_dir().context("Failed to get current directory")?,
+    };
+
+    let skills = select_skills(skill, config)?;
+    let servers = select_mcp_servers(mcp, config)?;
+
+    if plan.text() {
+        println!(
+            "{} Exporting to {} ({})",
+            presenter::arrow(),
+            target.to_string().cyan(),
+            project.display()
+        );
+    }
+
+    if let Some(path) = target.rules_path(&project) {
+        let existing = fs::read_to_string(&path).unwrap_or_default();
+        let content = targets::merge_rules(&existing, &targets::render_rules(&skills));
+        write_export(&path, &content, &mut plan)?;
+        if plan.text() {
+            println!("  {} {} skill(s) -> {}", presenter::ok(), skills.len(), path.display());
+        }
+    }
+
+    if let Some(path) = target.mcp_path(&project) {
+        if servers.is_empty() {
+            if plan.text() {
+                println!("  {} No MCP servers to export", presenter::arrow());
+            }
+        } else {
+            let merged = targets::merge_mcp(target, targets::read_json(&path)?, &servers)?;
+            write_export(
+                &path,
+                &format!("{}\n", serde_json::to_string_pretty(&merged)?),
+                &mut plan,
+            )?;
+            if plan.text() {
+                println!(
+                    "  {} {} MCP server(s) -> {}",
+                    presenter::ok(),
+                    servers.len(),
+                    path.display()
+                );
+                let mut env: Vec<&String> = servers.values().flat_map(|s| s.env.keys()).collect();
+                env.sort();
+                env.dedup();
+                if target.references_env() && !env.is_empty() {
+                    println!(
+                        "  {} MCP env values written as ${{env:NAME}} references; export {} where {} runs",
+                        presenter::warn(),
+                        env.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(", "),
+                        target
+                    );
+                }
+            }
+        }
+    }
+
+    if dry_run && plan.text() {
+        println!();
+        println!("{}", "Dry run - nothing written".yellow());
+    }
+
+    plan.finish()
+}
+
+/// Skills to export: the given names/profiles, or every installed skill
+fn select_skills(names: Option<Vec<String>>, config: &Config) -> Result<Vec<RuleSkill>> {
+    let skills_dir = Config::expand_path(&config.paths.skills);
+    let plugins_dir = Config::expand_path(&config.paths.plugins);
+
+    let mut all = discover_simple_skills(&skills_dir).context("Failed to discover simple skills")?;
+    all.extend(discover_plugin_skills(&plugins_dir).context("Failed to discover plugin skills")?);
+    all.extend(team::skills(&all));
+    all.sort_by(|a, b| a.name.cmp(&b.name));
+    all.dedup_by(|a, b| a.name == b.name);
+
+    if let Some(names) = names {
+        let wanted = session::expand_names(&names, &config.skills.profiles);
+        let missing: Vec<&String> = wanted.iter().filter(|n| !all.iter().any(|s| &s.name == *n)).collect();
+        if !missing.is_empty() {
+            return Err(CliError::new(
+                ErrorCode::NotFound,
+                format!(
+                    "Skill(s) not found: {}",
+                    missing.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(", ")
+                ),
+            )
+            .with_hint("List skills with: pais skill list")
+            .into());
+        }
+        all.retain(|s| wanted.contains(&s.name));
+    }
+
+    all.iter()
+        .map(|s| RuleSkill::load(&s.name, &s.description, &s.path))
+        .collect()
+}
+
+/// MCP servers to export: the given names/profiles, or every known server
+fn select_mcp_servers(
+    names: Option<Vec<String>>,
+    config: &Config,
+) -> Result<BTreeMap<String, crate::config::McpServerConfig>> {
+    let mut all = session::mcp_servers(config);
+
+    if let Some(names) = names {
+        let wanted = session::expand_names(&names, &config.mcp.profiles);
+        let missing: Vec<&str> = wanted
+            .iter()
+            .filter(|n| !all.contains_key(*n))
+            .map(|n| n.as_str())
+            .collect();
+        if !missing.is_empty() {
+            return Err(CliError::new(
+                ErrorCode::NotFound,
+                format!("MCP server(s) not found: {}", missing.join(", ")),
+            )
+            .with_hint("List MCP servers with: pais session --list")
+            .into());
+        }
+        all.retain(|name, _| wanted.contains(name));
+    }
+
+    Ok(all)
+}
+
+/// Write an exported file, or record it on a dry run
+fn write_export(path: &Path, content: &str, plan: &mut ChangePlan) -> Result<()> {
+    let op = if path.exists() { Op::Modify } else { Op::Add };
+    plan.record(op, path.display().to_string(), None);
+    if plan.dry_run() {
+        if plan.text() {
+            println!("{} {}", "Would write:".yellow(), path.display());
+        }
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
 }
 
 /// Get the Claude Code skills directory
@@ -30,8 +238,52 @@ fn get_claude_skills_dir() -> Result<PathBuf> {
     Config::claude_skills_dir().ok_or_else(|| eyre::eyre!("Could not determine home directory"))
 }
 
+/// Regenerate subagents promoted from skills, removing those whose skill is gone
+fn sync_subagents(plan: &mut ChangePlan, config: &Config) -> Result<()> {
+    let Some(agents_dir) = Config::claude_agents_dir() else {
+        return Ok(());
+    };
+    for (path, skill) in subagent::promoted(&agents_dir)? {
+        let target = path.display().to_string();
+        let Some(skill_dir) = session::find_skill_source(&skill, config) else {
+            plan.record(Op::Remove, target, Some(format!("skill '{}' is gone", skill)));
+            if plan.dry_run() {
+                if plan.text() {
+                    println!("Would remove subagent: {} (skill is gone)", skill);
+                }
+            } else {
+                fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display()))?;
+                println!("Removed subagent: {} (skill is gone)", skill);
+            }
+            continue;
+        };
+
+        let agent = match subagent::generate(&skill, &skill_dir) {
+            Ok(agent) => agent,
+            Err(e) => {
+                log::warn!("Keeping subagent {} as it is: {:#}", path.display(), e);
+                continue;
+            }
+        };
+        if fs::read_to_string(&path).is_ok_and(|existing| existing == agent) {
+            continue;
+        }
+        plan.record(Op::Modify, target, Some(format!("from skill {}", skill)));
+        if plan.dry_run() {
+            if plan.text() {
+                println!("Would update subagent: {}", skill);
+            }
+        } else {
+            fs::write(&path, agent).with_context(|| format!("Failed to write {}", path.display()))?;
+            println!("Updated subagent: {}", skill);
+        }
+    }
+    Ok(())
+}
+
 /// Sync all PAIS skills to Claude Code
-fn sync_skills(claude_skills_dir: &Path, dry_run: bool, config: &Config) -> Result<()> {
+fn sync_skills(claude_skills_dir: &Path, plan: &mut ChangePlan, config: &Config) -> Result<()> {
+    let dry_run = plan.dry_run();
     let skills_dir = Config::expand_path(&config.paths.skills);
     let plugins_dir = Config::expand_path(&config.paths.plugins);
 
@@ -45,15 +297,25 @@ fn sync_skills(claude_skills_dir: &Path, dry_run: bool, config: &Config) -> Resu
         })?;
     }
 
+    // Session hooks relink skills too; keep them out until the whole pass is done
+    let _lock = if dry_run { None } else { Some(FileLock::acquire(claude_skills_dir)?) };
+
     let mut synced_count = 0;
     let mut skipped_count = 0;
+    let mut personal = Vec::new();
+
+    // A name in both directories links only the side skills.resolve picks
+    let shadowed: Vec<PathBuf> = conflicts::find(config)?
+        .iter()
+        .map(|c| c.shadowed_path().to_path_buf())
+        .collect();
 
     // Sync simple skills
     if skills_dir.exists() {
         let simple_skills = discover_simple_skills(&skills_dir).context("Failed to discover simple skills")?;
 
-        for skill in simple_skills {
-            match sync_skill(&skill.path, &skill.name, claude_skills_dir, dry_run) {
+        for skill in simple_skills.iter().filter(|s| !shadowed.contains(&s.path)) {
+            match sync_skill(&skill.path, &skill.name, claude_skills_dir, plan) {
                 Ok(true) => synced_count += 1,
                 Ok(false) => skipped_count += 1,
                 Err(e) => {
@@ -61,14 +323,15 @@ fn sync_skills(claude_skills_dir: &Path, dry_run: bool, config: &Config) -> Resu
                 }
             }
         }
+        personal.extend(simple_skills);
     }
 
     // Sync plugin skills (only those with SKILL.md)
     if plugins_dir.exists() {
         let plugin_skills = discover_plugin_skills(&plugins_dir).context("Failed to discover plugin skills")?;
 
-        for skill in plugin_skills {
-            match sync_skill(&skill.path, &skill.name, claude_skills_dir, dry_run) {
+        for skill in plugin_skills.iter().filter(|s| !shadowed.contains(&s.path)) {
+            match sync_skill(&skill.path, &skill.name, claude_skills_dir, plan) {
                 Ok(true) => synced_count += 1,
                 Ok(false) => skipped_count += 1,
                 Err(e) => {
@@ -76,6 +339,22 @@ fn sync_skills(claude_skills_dir: &Path, dry_run: bool, config: &Config) -> Resu
                 }
             }
         }
+        personal.extend(plugin_skills);
+    }
+
+    // Sync team skills whose names aren't taken by the ones above
+    for skill in team::skills(&personal) {
+        match sync_skill(&skill.path, &skill.name, claude_skills_dir, plan) {
+            Ok(true) => synced_count += 1,
+            Ok(false) => skipped_count += 1,
+            Err(e) => {
+                log::warn!("Failed to sync team skill '{}': {}", skill.name, e);
+            }
+        }
+    }
+
+    if let Err(e) = sync_subagents(plan, config) {
+        log::warn!("Failed to update subagents generated from skills: {:#}", e);
     }
 
     // Generate ARCHITECTURE.md
@@ -91,12 +370,19 @@ fn sync_skills(claude_skills_dir: &Path, dry_run: bool, config: &Config) -> Resu
     }
 
     // Summary
+    if !plan.text() {
+        return Ok(());
+    }
     println!();
     if dry_run {
         println!("Dry run complete:");
         println!("  Would sync: {} skill(s)", synced_count);
         println!("  Already synced: {} skill(s)", skipped_count);
     } else {
+        state::record(StateEvent::SkillsSynced {
+            linked: synced_count,
+            removed: 0,
+        });
         println!("Sync complete:");
         println!("  Synced: {} skill(s)", synced_count);
         println!("  Already synced: {} skill(s)", skipped_count);
@@ -111,12 +397,105 @@ fn sync_skills(claude_skills_dir: &Path, dry_run: bool, config: &Config) -> Resu
     Ok(())
 }
 
+/// Sync once, then relink skills and refresh the index as skill directories change
+fn watch_skills(claude_skills_dir: &Path, config: &Config) -> Result<()> {
+    let mut plan = ChangePlan::new(false, OutputFormat::Text);
+    sync_skills(claude_skills_dir, &mut plan, config)?;
+
+    let skills_dir = Config::expand_path(&config.paths.skills);
+    let plugins_dir = Config::expand_path(&config.paths.plugins);
+    let watcher = SkillWatcher::new(&[skills_dir.clone(), plugins_dir.clone()])?;
+
+    println!();
+    for root in watcher.roots() {
+        println!("{} Watching {}", presenter::arrow(), root.display());
+    }
+    println!("{}", "Press Ctrl-C to stop".dimmed());
+
+    loop {
+        let dirs = watcher.next_batch()?;
+        let mut index_stale = false;
+        let lock = match FileLock::acquire(claude_skills_dir) {
+            Ok(lock) => lock,
+            Err(e) => {
+                eprintln!("{} Skipping {} change(s): {:#}", presenter::fail(), dirs.len(), e);
+                continue;
+            }
+        };
+
+        for dir in &dirs {
+            let plugin = watch::is_under(dir, &plugins_dir);
+            index_stale |= watch::is_under(dir, &skills_dir);
+            if let Err(e) = resync_skill_dir(dir, plugin, claude_skills_dir, &mut plan) {
+                eprintln!("{} {}: {:#}", presenter::fail(), dir.display(), e);
+            }
+        }
+
+        drop(lock);
+
+        if index_stale && let Err(e) = refresh_index(&skills_dir) {
+            eprintln!("{} Failed to update skill index: {:#}", presenter::fail(), e);
+        }
+    }
+}
+
+/// Bring the link for one skill directory up to date (created, renamed or removed)
+fn resync_skill_dir(dir: &Path, plugin: bool, claude_skills_dir: &Path, plan: &mut ChangePlan) -> Result<()> {
+    // A SKILL.md that fails to parse mid-edit keeps the existing link
+    let skill = if plugin && has_skill_md(dir) {
+        let plugin_name = dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Some(load_plugin_skill(dir, &plugin_name)?)
+    } else if !plugin && is_simple_skill(dir) {
+        Some(load_simple_skill(dir)?)
+    } else {
+        None
+    };
+
+    // Drop links to this directory under any other name (skill renamed or removed)
+    if let Ok(entries) = fs::read_dir(claude_skills_dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let points_here = fs::read_link(entry.path()).is_ok_and(|target| target == dir);
+            if points_here && skill.as_ref().is_none_or(|s| s.name != name) {
+                fs::remove_file(entry.path())
+                    .with_context(|| format!("Failed to remove link: {}", entry.path().display()))?;
+                println!("Unlinked: {}", name);
+            }
+        }
+    }
+
+    if let Some(skill) = skill {
+        sync_skill(dir, &skill.name, claude_skills_dir, plan)?;
+    }
+    Ok(())
+}
+
+/// Rewrite skill-index.yaml, and context-snippet.md when one is in use
+fn refresh_index(skills_dir: &Path) -> Result<()> {
+    let index = generate_index(skills_dir).context("Failed to generate skill index")?;
+    write_index(&index, &skills_dir.join("skill-index.yaml")).context("Failed to write skill index")?;
+
+    let context_path = skills_dir.join("context-snippet.md");
+    if context_path.exists() {
+        fs::write(&context_path, generate_context_snippet(&index, skills_dir))
+            .with_context(|| format!("Failed to write context snippet: {}", context_path.display()))?;
+    }
+
+    println!("Index updated: {} skill(s)", index.total_skills);
+    Ok(())
+}
+
 /// Sync a single skill to Claude Code
-fn sync_skill(source: &Path, name: &str, claude_skills_dir: &Path, dry_run: bool) -> Result<bool> {
+fn sync_skill(source: &Path, name: &str, claude_skills_dir: &Path, plan: &mut ChangePlan) -> Result<bool> {
+    let dry_run = plan.dry_run();
     let target = claude_skills_dir.join(name);
+    let detail = Some(format!("link to {}", source.display()));
 
     // Check if already correctly linked
-    if target.exists() || target.symlink_metadata().is_ok() {
+    if let Ok(metadata) = target.symlink_metadata() {
         if fs::read_link(&target)
             .map(|link_target| link_target == source)
             .unwrap_or(false)
@@ -125,26 +504,35 @@ fn sync_skill(source: &Path, name: &str, claude_skills_dir: &Path, dry_run: bool
             return Ok(false);
         }
 
+        // A wrong link is repointed; a regular file or directory is lost
+        if metadata.file_type().is_symlink() {
+            plan.record(Op::Modify, target.display().to_string(), detail.clone());
+        } else {
+            plan.record(Op::Remove, target.display().to_string(), None);
+            plan.record(Op::Add, target.display().to_string(), detail);
+        }
+
         // Remove existing (wrong link or regular file/dir)
         if dry_run {
-            println!("Would remove existing: {}", target.display());
-        } else if target.is_dir()
-            && !target
-                .symlink_metadata()
-                .map(|m| m.file_type().is_symlink())
-                .unwrap_or(false)
-        {
+            if plan.text() {
+                println!("Would remove existing: {}", target.display());
+            }
+        } else if target.is_dir() && !metadata.file_type().is_symlink() {
             fs::remove_dir_all(&target)
                 .with_context(|| format!("Failed to remove existing directory: {}", target.display()))?;
         } else {
             fs::remove_file(&target)
                 .with_context(|| format!("Failed to remove existing file/link: {}", target.display()))?;
         }
+    } else {
+        plan.record(Op::Add, target.display().to_string(), detail);
     }
 
     // Create symlink
     if dry_run {
-        println!("Would link: {} -> {}", name, source.display());
+        if plan.text() {
+            println!("Would link: {} -> {}", name, source.display());
+        }
     } else {
         #[cfg(unix)]
         {
@@ -168,87 +556,260 @@ fn sync_skill(source: &Path, name: &str, claude_skills_dir: &Path, dry_run: bool
     Ok(true)
 }
 
-/// Remove orphaned symlinks from Claude skills directory
-fn clean_orphaned_symlinks(claude_skills_dir: &Path, dry_run: bool, config: &Config) -> Result<()> {
-    if !claude_skills_dir.exists() {
-        println!(
-            "Claude skills directory does not exist: {}",
-            claude_skills_dir.display()
-        );
-        return Ok(());
-    }
-
+/// Remove orphaned and stale links from the Claude skills directory and
+/// report broken or shadowed skill definitions with how to fix them
+fn clean_skills(claude_skills_dir: &Path, plan: &mut ChangePlan, config: &Config) -> Result<()> {
+    let dry_run = plan.dry_run();
     let skills_dir = Config::expand_path(&config.paths.skills);
     let plugins_dir = Config::expand_path(&config.paths.plugins);
 
-    let mut removed_count = 0;
-    let mut valid_count = 0;
-
-    for entry in fs::read_dir(claude_skills_dir).context("Failed to read Claude skills directory")? {
-        let entry = entry?;
-        let path = entry.path();
-        let name = entry.file_name().to_string_lossy().to_string();
+    let _lock = if dry_run || !claude_skills_dir.exists() {
+        None
+    } else {
+        Some(FileLock::acquire(claude_skills_dir)?)
+    };
 
-        // Check if this is a symlink
-        let metadata = match path.symlink_metadata() {
-            Ok(m) => m,
-            Err(_) => continue,
-        };
+    let report = clean::scan(&clean::Dirs {
+        claude_skills: claude_skills_dir,
+        skills: &skills_dir,
+        plugins: &plugins_dir,
+        teams: &team::teams_dir(),
+    })?;
 
-        if !metadata.file_type().is_symlink() {
-            // Not a symlink, skip
-            valid_count += 1;
+    let mut removed_count = 0;
+    for problem in &report.problems {
+        let Some(ref link) = problem.remove else {
             continue;
-        }
-
-        // Check if the symlink target exists and is a PAIS skill
-        let link_target = match fs::read_link(&path) {
-            Ok(t) => t,
-            Err(_) => {
-                // Can't read link, consider orphaned
-                if dry_run {
-                    println!("Would remove broken symlink: {}", name);
-                } else {
-                    fs::remove_file(&path)?;
-                    println!("Removed broken symlink: {}", name);
-                }
-                removed_count += 1;
-                continue;
-            }
         };
-
-        // Check if target exists
-        if !link_target.exists() {
-            if dry_run {
-                println!("Would remove orphaned symlink: {} -> {}", name, link_target.display());
-            } else {
-                fs::remove_file(&path)?;
-                println!("Removed orphaned symlink: {} -> {}", name, link_target.display());
+        plan.record(
+            Op::Remove,
+            link.display().to_string(),
+            Some(format!("{} symlink: {}", problem.kind, problem.detail)),
+        );
+        if dry_run {
+            if plan.text() {
+                println!(
+                    "Would remove {} symlink: {} ({})",
+                    problem.kind, problem.name, problem.detail
+                );
             }
-            removed_count += 1;
-            continue;
+        } else {
+            fs::remove_file(link).with_context(|| format!("Failed to remove symlink: {}", link.display()))?;
+            println!(
+                "Removed {} symlink: {} ({})",
+                problem.kind, problem.name, problem.detail
+            );
         }
+        removed_count += 1;
+    }
 
-        // Check if target is within our skills or plugins directories
-        let is_pais_skill = link_target.starts_with(&skills_dir) || link_target.starts_with(&plugins_dir);
-
-        if is_pais_skill && has_skill_md(&link_target) {
-            valid_count += 1;
-        } else {
-            // Not a PAIS-managed skill, but target exists - leave it alone
-            valid_count += 1;
+    let remaining: Vec<_> = report.problems.iter().filter(|p| p.remove.is_none()).collect();
+    if !plan.text() {
+        return Ok(());
+    }
+    if !remaining.is_empty() {
+        println!();
+        println!("{}", "Needs attention:".yellow().bold());
+        for problem in &remaining {
+            println!(
+                "  {} {} {} {}",
+                presenter::warn(),
+                problem.kind.to_string().yellow(),
+                problem.name.cyan(),
+                problem.path.display().to_string().dimmed()
+            );
+            println!("      {}", problem.detail);
+            println!("      {} {}", presenter::arrow(), problem.remediation);
         }
     }
 
     println!();
     if dry_run {
         println!("Dry run complete:");
-        println!("  Would remove: {} orphaned symlink(s)", removed_count);
-        println!("  Valid: {} symlink(s)", valid_count);
+        println!("  Would remove: {} orphaned or stale symlink(s)", removed_count);
     } else {
+        state::record(StateEvent::SkillsSynced {
+            linked: 0,
+            removed: removed_count,
+        });
         println!("Clean complete:");
-        println!("  Removed: {} orphaned symlink(s)", removed_count);
-        println!("  Valid: {} symlink(s)", valid_count);
+        println!("  Removed: {} orphaned or stale symlink(s)", removed_count);
+    }
+    println!("  Valid: {} symlink(s)", report.valid);
+    if !remaining.is_empty() {
+        println!("  Needs attention: {} problem(s)", remaining.len());
+    }
+
+    Ok(())
+}
+
+/// Sync state with a remote (git, S3 or SSH)
+fn sync_remote(
+    remote: Option<String>,
+    history: bool,
+    prefer: Option<sync::Prefer>,
+    plan: &mut ChangePlan,
+    config: &Config,
+) -> Result<()> {
+    let dry_run = plan.dry_run();
+    let url = remote.or_else(|| config.sync.remote.clone()).ok_or_else(|| {
+        CliError::new(ErrorCode::Config, "No sync remote configured")
+            .with_hint("Set sync.remote in pais.yaml or pass --remote <url>")
+    })?;
+    let backend = Remote::parse(&url)?;
+    if !net::is_local(&url) {
+        net::ensure_online(&config.network, &format!("Syncing with the {} remote", backend.kind()))?;
+    }
+    let roots = SyncRoot::from_config(config, history || config.sync.include_history);
+
+    let state_dir = sync::state_dir();
+    let mirror = state_dir.join("remote");
+    let base_dir = state_dir.join("base");
+    let conflicts_dir = state_dir.join("conflicts");
+
+    // A different remote means the existing mirror and base no longer apply
+    let remote_file = state_dir.join("remote-url");
+    let previous = fs::read_to_string(&remote_file).unwrap_or_default();
+    if previous.trim() != backend.to_string() && state_dir.exists() {
+        log::info!("Sync remote changed, resetting sync state");
+        fs::remove_dir_all(&state_dir).context("Failed to reset sync state")?;
+    }
+
+    if plan.text() {
+        println!(
+            "{} Syncing with {} remote {}",
+            presenter::arrow(),
+            backend.kind(),
+            backend.to_string().cyan()
+        );
+    }
+
+    backend.fetch(&mirror).context("Failed to fetch remote")?;
+
+    let local = sync::snapshot_local(&roots)?;
+    let remote = sync::snapshot_dir(&mirror, &roots)?;
+    let base = sync::snapshot_dir(&base_dir, &roots)?;
+    let changes = sync::plan(&local, &remote, &base, prefer);
+
+    let mut pulled = 0;
+    let mut pushed = 0;
+    let mut merged = 0;
+    let mut conflicts = Vec::new();
+
+    for change in &changes {
+        let (marker, label, op) = match &change.action {
+            ChangeAction::Unchanged => continue,
+            ChangeAction::Pull => {
+                pulled += 1;
+                let op = if local.contains_key(&change.path) { Op::Modify } else { Op::Add };
+                ("↓".green(), "pull".to_string(), Some(op))
+            }
+            ChangeAction::DeleteLocal => {
+                pulled += 1;
+                ("↓".red(), "delete local".to_string(), Some(Op::Remove))
+            }
+            ChangeAction::Push => {
+                pushed += 1;
+                let op = if remote.contains_key(&change.path) { Op::Modify } else { Op::Add };
+                ("↑".green(), "push".to_string(), Some(op))
+            }
+            ChangeAction::DeleteRemote => {
+                pushed += 1;
+                ("↑".red(), "delete remote".to_string(), Some(Op::Remove))
+            }
+            ChangeAction::Merge => {
+                merged += 1;
+                ("⇅".cyan(), "merged".to_string(), Some(Op::Modify))
+            }
+            ChangeAction::Conflict { reason } => {
+                conflicts.push(change);
+                plan.conflict(change.path.clone());
+                (presenter::fail(), format!("conflict: {}", reason), None)
+            }
+        };
+        if let Some(op) = op {
+            plan.record(op, change.path.clone(), Some(label.clone()));
+        }
+        if plan.text() {
+            println!("  {} {} {}", marker, change.path, format!("({})", label).dimmed());
+        }
+    }
+
+    if !plan.text() {
+        return Ok(());
+    }
+    println!();
+    if dry_run {
+        println!("Dry run complete:");
+        println!("  Would pull: {} file(s)", pulled);
+        println!("  Would push: {} file(s)", pushed);
+        println!("  Would merge: {} file(s)", merged);
+        println!("  Conflicts: {}", conflicts.len());
+        return Ok(());
+    }
+
+    sync::apply(&changes, &roots, &mirror)?;
+
+    if pushed + merged > 0 {
+        let message = format!("pais sync {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"));
+        backend
+            .publish(&mirror, &message)
+            .context("Failed to publish to remote")?;
+    }
+
+    sync::write_snapshot(&base_dir, &sync::next_base(&changes, &base))?;
+    fs::write(&remote_file, backend.to_string()).context("Failed to record sync remote")?;
+
+    if conflicts_dir.exists() {
+        fs::remove_dir_all(&conflicts_dir).context("Failed to clear old conflict copies")?;
+    }
+    for change in &conflicts {
+        if let Some(ref content) = change.content {
+            sync::write_or_remove(&conflicts_dir.join(&change.path), Some(content))?;
+        }
+    }
+
+    state::record(StateEvent::RemoteSynced {
+        remote: backend.to_string(),
+        pulled,
+        pushed,
+        merged,
+        conflicts: conflicts.len(),
+    });
+
+    println!("Sync complete:");
+    println!("  Pulled: {} file(s)", pulled);
+    println!("  Pushed: {} file(s)", pushed);
+    println!("  Merged: {} file(s)", merged);
+    let pulled_skills = changes
+        .iter()
+        .any(|c| matches!(c.action, ChangeAction::Pull | ChangeAction::Merge) && c.path.starts_with("skills/"));
+    if pulled_skills {
+        println!();
+        println!("Run {} to link pulled skills into Claude Code", "pais sync".cyan());
+    }
+
+    if !conflicts.is_empty() {
+        println!();
+        println!(
+            "  {} {} conflict(s) left untouched on both sides",
+            presenter::warn(),
+            conflicts.len()
+        );
+        if conflicts.iter().any(|c| c.content.is_some()) {
+            println!("  Merge attempts with conflict markers: {}", conflicts_dir.display());
+        }
+        println!(
+            "  Fix up the local copies and re-run with {}, or take theirs with {}",
+            "--prefer local".cyan(),
+            "--prefer remote".cyan()
+        );
+        return Err(CliError::new(
+            ErrorCode::Conflict,
+            format!("{} sync conflict(s) need resolution", conflicts.len()),
+        )
+        .with_hint("Re-run with --prefer local or --prefer remote")
+        .into());
     }
 
     Ok(())
@@ -285,7 +846,13 @@ mod tests {
         create_skill(source_temp.path(), "test-skill");
 
         let source = source_temp.path().join("test-skill");
-        let result = sync_skill(&source, "test-skill", target_temp.path(), false).unwrap();
+        let result = sync_skill(
+            &source,
+            "test-skill",
+            target_temp.path(),
+            &mut ChangePlan::new(false, OutputFormat::Text),
+        )
+        .unwrap();
 
         assert!(result);
 
@@ -312,7 +879,13 @@ mod tests {
         std::os::unix::fs::symlink(&source, &target).unwrap();
 
         // Try to sync again
-        let result = sync_skill(&source, "test-skill", target_temp.path(), false).unwrap();
+        let result = sync_skill(
+            &source,
+            "test-skill",
+            target_temp.path(),
+            &mut ChangePlan::new(false, OutputFormat::Text),
+        )
+        .unwrap();
 
         // Should skip (return false)
         assert!(!result);
@@ -326,7 +899,8 @@ mod tests {
         create_skill(source_temp.path(), "test-skill");
 
         let source = source_temp.path().join("test-skill");
-        let result = sync_skill(&source, "test-skill", target_temp.path(), true).unwrap();
+        let mut plan = ChangePlan::new(true, OutputFormat::Json);
+        let result = sync_skill(&source, "test-skill", target_temp.path(), &mut plan).unwrap();
 
         // Dry run should return true (would sync)
         assert!(result);
@@ -334,5 +908,14 @@ mod tests {
         // But no symlink should exist
         let target = target_temp.path().join("test-skill");
         assert!(!target.exists());
+        assert_eq!(plan.changes()[0].op, Op::Add);
+        assert_eq!(plan.changes()[0].target, target.display().to_string());
+
+        // A real directory in the way is a destructive change
+        fs::create_dir_all(&target).unwrap();
+        let mut plan = ChangePlan::new(true, OutputFormat::Json);
+        sync_skill(&source, "test-skill", target_temp.path(), &mut plan).unwrap();
+        assert!(plan.destructive());
+        assert!(target.is_dir());
     }
 }
@@ -0,0 +1,195 @@
+//! Team source commands
+//!
+//! `pais team add` subscribes to a shared git repo, `update` pulls it, `list`
+//! shows what each team provides and `remove` unsubscribes. See [`crate::team`].
+
+use colored::*;
+use eyre::Result;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cli::{OutputFormat, TeamAction};
+use crate::config::{Config, NetworkConfig};
+use crate::net;
+use crate::presenter::{self, Table};
+use crate::progress;
+use crate::skill::loader::discover_team_skills;
+use crate::team::{self, Team};
+
+pub fn run(action: TeamAction, format: OutputFormat, config: &Config) -> Result<()> {
+    match action {
+        TeamAction::Add { url, name } => add(&url, name.as_deref(), format, &config.network),
+        TeamAction::Update { name } => update(name.as_deref(), format, &config.network),
+        TeamAction::List => list(format),
+        TeamAction::Remove { name } => remove(&name),
+    }
+}
+
+/// What a team repo provides
+#[derive(Debug, Serialize)]
+struct TeamInfo {
+    name: String,
+    url: Option<String>,
+    commit: Option<String>,
+    path: PathBuf,
+    skills: usize,
+    agents: usize,
+    bundles: usize,
+}
+
+impl TeamInfo {
+    fn of(team: &Team) -> Self {
+        Self {
+            name: team.name.clone(),
+            url: team.url(),
+            commit: team.commit(),
+            path: team.path.clone(),
+            skills: discover_team_skills(&team.skills_dir(), &team.name).map_or(0, |s| s.len()),
+            agents: count(&team.agents_dir(), |p| p.extension().is_some_and(|e| e == "yaml")),
+            bundles: count(&team.bundles_dir(), |p| p.join("bundle.yaml").exists()),
+        }
+    }
+}
+
+/// Entries of `dir` matching `filter`
+fn count(dir: &Path, filter: impl Fn(&Path) -> bool) -> usize {
+    fs::read_dir(dir)
+        .map(|entries| entries.flatten().filter(|e| filter(&e.path())).count())
+        .unwrap_or(0)
+}
+
+fn short(commit: Option<&str>) -> String {
+    commit
+        .map(|c| c.chars().take(7).collect())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn add(url: &str, name: Option<&str>, format: OutputFormat, network: &NetworkConfig) -> Result<()> {
+    if !net::is_local(url) {
+        net::ensure_online(network, "Cloning a team repo")?;
+    }
+    let spinner = progress::spinner(format, format!("Cloning {}...", url));
+    let team = team::add(url, name);
+    spinner.finish_and_clear();
+    let info = TeamInfo::of(&team?);
+
+    println!(
+        "{} Added team {} at {}",
+        presenter::ok(),
+        info.name.green(),
+        short(info.commit.as_deref())
+    );
+    println!(
+        "  {} skills, {} agents, {} bundles",
+        info.skills, info.agents, info.bundles
+    );
+    if info.skills + info.agents + info.bundles == 0 {
+        println!(
+            "  {} Nothing found; team repos hold skills/<name>/SKILL.md, agents/<name>.yaml and bundles/<name>/bundle.yaml",
+            presenter::warn()
+        );
+    } else if info.skills > 0 {
+        println!("  Run {} to link its skills into Claude Code", "pais sync".cyan());
+    }
+    Ok(())
+}
+
+fn update(name: Option<&str>, format: OutputFormat, network: &NetworkConfig) -> Result<()> {
+    let teams = match name {
+        Some(name) => vec![team::get(name)?],
+        None => team::list(),
+    };
+    if teams.is_empty() {
+        println!(
+            "{} No teams; add one with {}",
+            presenter::info(),
+            "pais team add <git-url>".cyan()
+        );
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for team in &teams {
+        // Offline, teams keep their last fetched checkout
+        if network.offline && !team.url().is_some_and(|url| net::is_local(&url)) {
+            println!(
+                "{} {} skipped while offline (using {})",
+                presenter::info(),
+                team.name,
+                short(team.commit().as_deref())
+            );
+            continue;
+        }
+        let spinner = progress::spinner(format, format!("Updating {}...", team.name));
+        let result = team.update();
+        spinner.finish_and_clear();
+        match result {
+            Ok((before, after)) if before == after => {
+                println!(
+                    "{} {} is up to date ({})",
+                    presenter::ok(),
+                    team.name.green(),
+                    short(after.as_deref())
+                );
+            }
+            Ok((before, after)) => {
+                println!(
+                    "{} {} {} {} {}",
+                    presenter::ok(),
+                    team.name.green(),
+                    short(before.as_deref()).dimmed(),
+                    presenter::arrow(),
+                    short(after.as_deref())
+                );
+            }
+            Err(e) => {
+                failed += 1;
+                println!("{} {}: {:#}", presenter::fail(), team.name.red(), e);
+            }
+        }
+    }
+    if failed > 0 {
+        eyre::bail!("{} of {} teams failed to update", failed, teams.len());
+    }
+    Ok(())
+}
+
+fn list(format: OutputFormat) -> Result<()> {
+    let teams: Vec<TeamInfo> = team::list().iter().map(TeamInfo::of).collect();
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&teams)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&teams)?),
+        OutputFormat::Text => {
+            if teams.is_empty() {
+                println!(
+                    "{} No teams; add one with {}",
+                    presenter::info(),
+                    "pais team add <git-url>".cyan()
+                );
+                return Ok(());
+            }
+            let mut table = Table::new(&["NAME", "URL", "COMMIT", "SKILLS", "AGENTS", "BUNDLES"]);
+            for info in &teams {
+                table.add_row(vec![
+                    info.name.green(),
+                    info.url.clone().unwrap_or_default().cyan(),
+                    short(info.commit.as_deref()).dimmed(),
+                    info.skills.to_string().normal(),
+                    info.agents.to_string().normal(),
+                    info.bundles.to_string().normal(),
+                ]);
+            }
+            table.print();
+        }
+    }
+    Ok(())
+}
+
+fn remove(name: &str) -> Result<()> {
+    let team = team::remove(name)?;
+    println!("{} Removed team {}", presenter::ok(), team.name.green());
+    println!("  Run {} to unlink skills it provided", "pais sync --clean".cyan());
+    Ok(())
+}
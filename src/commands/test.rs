@@ -0,0 +1,127 @@
+//! Test commands for plugins and skills
+
+use eyre::Result;
+
+use crate::cli::{OutputFormat, TestAction};
+use crate::commands::plugin::{find_plugin, installed_plugins};
+use crate::config::Config;
+use crate::error::CliError;
+use crate::skill::Skill;
+use crate::skill::indexer::{SkillIndex, generate_index};
+use crate::skill::loader::discover_all_skills;
+use crate::testing::plugin::run_plugin_tests;
+use crate::testing::skill::{SKILL_TESTS_FILE, load_spec, run_skill_tests};
+use crate::testing::{TestReport, TestSuite, print_report};
+
+pub fn run(action: TestAction, format: OutputFormat, config: &Config) -> Result<()> {
+    let report = match action {
+        TestAction::Plugin { name } => {
+            let mut suites = Vec::new();
+            let mut skipped = Vec::new();
+            test_plugins(name.as_deref(), config, &mut suites, &mut skipped)?;
+            TestReport::new(suites, skipped)
+        }
+        TestAction::Skill { name } => {
+            let mut suites = Vec::new();
+            let mut skipped = Vec::new();
+            test_skills(name.as_deref(), config, &mut suites, &mut skipped)?;
+            TestReport::new(suites, skipped)
+        }
+        TestAction::All => {
+            let mut suites = Vec::new();
+            let mut skipped = Vec::new();
+            test_plugins(None, config, &mut suites, &mut skipped)?;
+            test_skills(None, config, &mut suites, &mut skipped)?;
+            TestReport::new(suites, skipped)
+        }
+    };
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&report)?),
+        OutputFormat::Text => print_report(&report),
+    }
+
+    if !report.passed {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Run plugin tests for one named plugin or every installed plugin
+fn test_plugins(
+    name: Option<&str>,
+    config: &Config,
+    suites: &mut Vec<TestSuite>,
+    skipped: &mut Vec<String>,
+) -> Result<()> {
+    let plugins = match name {
+        Some(name) => vec![find_plugin(name, config)?],
+        None => installed_plugins(config)?,
+    };
+
+    for plugin in &plugins {
+        match run_plugin_tests(plugin)? {
+            Some(suite) => suites.push(suite),
+            None if name.is_some() => eyre::bail!(
+                "Plugin '{}' declares no tests (add test.command to plugin.yaml)",
+                plugin.manifest.plugin.name
+            ),
+            None => skipped.push(format!("plugin {}", plugin.manifest.plugin.name)),
+        }
+    }
+
+    Ok(())
+}
+
+/// Run routing tests for one named skill or every skill
+fn test_skills(
+    name: Option<&str>,
+    config: &Config,
+    suites: &mut Vec<TestSuite>,
+    skipped: &mut Vec<String>,
+) -> Result<()> {
+    let skills_dir = Config::expand_path(&config.paths.skills);
+    let plugins_dir = Config::expand_path(&config.paths.plugins);
+
+    let mut skills: Vec<Skill> = discover_all_skills(&skills_dir, &plugins_dir)?;
+    skills.sort_by(|a, b| a.name.cmp(&b.name));
+
+    if let Some(name) = name {
+        skills.retain(|s| s.name.eq_ignore_ascii_case(name));
+        if skills.is_empty() {
+            return Err(CliError::not_found("Skill", name).into());
+        }
+    }
+
+    // Route against every skill so collisions with other skills surface as failures
+    let index = routing_index(config)?;
+
+    for skill in &skills {
+        match load_spec(&skill.path)? {
+            Some(spec) => suites.push(run_skill_tests(&skill.name, &spec, &index)),
+            None if name.is_some() => eyre::bail!(
+                "Skill '{}' has no {} with example prompts",
+                skill.name,
+                SKILL_TESTS_FILE
+            ),
+            None => skipped.push(format!("skill {}", skill.name)),
+        }
+    }
+
+    Ok(())
+}
+
+/// Index of simple and plugin skills combined
+fn routing_index(config: &Config) -> Result<SkillIndex> {
+    let mut index = generate_index(&Config::expand_path(&config.paths.skills))?;
+    let plugin_index = generate_index(&Config::expand_path(&config.paths.plugins))?;
+
+    for (key, entry) in plugin_index.skills {
+        index.skills.entry(key).or_insert(entry);
+    }
+    index.total_skills = index.skills.len();
+
+    Ok(index)
+}
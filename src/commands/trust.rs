@@ -0,0 +1,158 @@
+//! Workspace trust commands
+//!
+//! `pais trust <path>` trusts a directory tree, `--revoke` withdraws it and a
+//! bare `pais trust` lists the workspaces hooks have seen. See [`crate::trust`].
+
+use chrono::{DateTime, Utc};
+use colored::*;
+use eyre::{Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cli::OutputFormat;
+use crate::config::Config;
+use crate::error::{CliError, ErrorCode};
+use crate::lock::FileLock;
+use crate::presenter::{self, Table};
+use crate::trust::{self, Trust, TrustStore};
+
+pub fn run(path: Option<&Path>, revoke: bool, format: OutputFormat, config: &Config) -> Result<()> {
+    match path {
+        Some(path) if revoke => revoke_trust(path, config),
+        Some(path) => grant(path, config),
+        None => list(format, config),
+    }
+}
+
+/// Resolve `path` to the directory it names
+fn directory(path: &Path) -> Result<PathBuf> {
+    let dir = fs::canonicalize(path)
+        .map_err(|_| CliError::new(ErrorCode::NotFound, format!("No such directory: {}", path.display())))?;
+    if !dir.is_dir() {
+        return Err(CliError::new(ErrorCode::InvalidInput, format!("Not a directory: {}", dir.display())).into());
+    }
+    Ok(dir)
+}
+
+/// Change the store under its lock so hooks recording new workspaces aren't lost
+fn update<T>(change: impl FnOnce(&mut TrustStore) -> T) -> Result<T> {
+    let path = trust::store_path();
+    let _lock = FileLock::acquire(&path)?;
+    let mut store = TrustStore::load_from(&path);
+    let result = change(&mut store);
+    store.save_to(&path).context("Failed to save trust store")?;
+    Ok(result)
+}
+
+fn grant(path: &Path, config: &Config) -> Result<()> {
+    let dir = directory(path)?;
+    update(|store| store.grant(&dir, Utc::now()))?;
+    println!("{} Trusted {} and everything below it", presenter::ok(), dir.display());
+    if !config.security.trust.enabled {
+        println!(
+            "  {} {} is off, so every workspace is already trusted",
+            presenter::info(),
+            "security.trust.enabled".cyan()
+        );
+    }
+    Ok(())
+}
+
+fn revoke_trust(path: &Path, config: &Config) -> Result<()> {
+    let dir = directory(path)?;
+    if !update(|store| store.revoke(&dir))? {
+        return Err(
+            CliError::new(ErrorCode::NotFound, format!("{} was not trusted", dir.display()))
+                .with_hint("Trust is revoked on the directory it was granted to; see `pais trust`")
+                .into(),
+        );
+    }
+    println!("{} Revoked trust for {}", presenter::ok(), dir.display());
+
+    // An ancestor or the config may still cover it
+    if let Trust::Trusted(root) = trust::resolve(&config.security.trust, &TrustStore::load(), &dir) {
+        println!(
+            "  {} Still trusted through {}",
+            presenter::warn(),
+            root.display().to_string().cyan()
+        );
+    }
+    Ok(())
+}
+
+/// One row of `pais trust`
+#[derive(Debug, Serialize)]
+struct WorkspaceRow {
+    path: PathBuf,
+    trusted: bool,
+    /// "store" for `pais trust`, "config" for `security.trust.trusted`
+    source: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    first_seen: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trusted_at: Option<DateTime<Utc>>,
+}
+
+fn list(format: OutputFormat, config: &Config) -> Result<()> {
+    let store = TrustStore::load();
+    let mut rows: Vec<WorkspaceRow> = config
+        .security
+        .trust
+        .trusted
+        .iter()
+        .map(|root| WorkspaceRow {
+            path: Config::expand_path(root),
+            trusted: true,
+            source: "config",
+            first_seen: None,
+            trusted_at: None,
+        })
+        .collect();
+    rows.extend(store.workspaces.iter().map(|(path, workspace)| WorkspaceRow {
+        path: path.clone(),
+        trusted: trust::resolve(&config.security.trust, &store, path).is_trusted(),
+        source: "store",
+        first_seen: Some(workspace.first_seen),
+        trusted_at: workspace.trusted_at,
+    }));
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&rows)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&rows)?),
+        OutputFormat::Text => {
+            if let Ok(cwd) = std::env::current_dir() {
+                let status = match trust::resolve(&config.security.trust, &store, &cwd) {
+                    Trust::Trusted(root) if root == cwd => "trusted".green().to_string(),
+                    Trust::Trusted(root) => format!("{} (through {})", "trusted".green(), root.display()),
+                    Trust::Untrusted => "untrusted".yellow().to_string(),
+                    Trust::Unchecked => format!("{} (trust checks are off)", "trusted".green()),
+                };
+                println!("Current directory: {}", status);
+                println!();
+            }
+
+            if rows.is_empty() {
+                println!("{} No workspaces seen yet", presenter::info());
+                return Ok(());
+            }
+            let time = |at: Option<DateTime<Utc>>| {
+                at.map(|at| at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_default()
+                    .dimmed()
+            };
+            let mut table = Table::new(&["PATH", "STATE", "SOURCE", "FIRST SEEN", "TRUSTED"]);
+            for row in &rows {
+                table.add_row(vec![
+                    row.path.display().to_string().cyan(),
+                    if row.trusted { "trusted".green() } else { "untrusted".yellow() },
+                    row.source.dimmed(),
+                    time(row.first_seen),
+                    time(row.trusted_at),
+                ]);
+            }
+            table.print();
+        }
+    }
+    Ok(())
+}
@@ -1,51 +1,67 @@
 //! Upgrade command
 //!
-//! Run config migrations to upgrade PAIS configuration.
+//! Run config migrations to upgrade PAIS configuration. `--dry-run -o json`
+//! prints the change plan instead (see [`crate::plan`]).
 
 use colored::*;
 use eyre::Result;
 
+use crate::cli::OutputFormat;
 use crate::config::Config;
 use crate::migrate;
+use crate::plan::ChangePlan;
+use crate::presenter;
 
 /// Run the upgrade command
-pub fn run(dry_run: bool, status_only: bool, config: &Config) -> Result<()> {
+pub fn run(dry_run: bool, status_only: bool, format: OutputFormat, config: &Config) -> Result<()> {
     if status_only {
         show_status()?;
         return Ok(());
     }
 
+    let mut plan = ChangePlan::new(dry_run, format);
+    upgrade(&mut plan, config)?;
+    plan.finish()
+}
+
+fn upgrade(plan: &mut ChangePlan, config: &Config) -> Result<()> {
     let (current, target) = migrate::version_info()?;
 
     if current >= target {
-        println!("{} PAIS is up to date (v{})", "✓".green(), target);
+        if plan.text() {
+            println!("{} PAIS is up to date (v{})", presenter::ok(), target);
+        }
         return Ok(());
     }
 
-    println!("{} Upgrading PAIS from v{} to v{}", "🔄".blue(), current, target);
-    println!();
-
     // Show pending migrations
     let pending = migrate::pending_migrations()?;
-    if pending.is_empty() {
-        println!("{} No migrations to apply", "✓".green());
-        return Ok(());
-    }
+    if plan.text() {
+        println!("{} Upgrading PAIS from v{} to v{}", "🔄".blue(), current, target);
+        println!();
+        if pending.is_empty() {
+            println!("{} No migrations to apply", presenter::ok());
+            return Ok(());
+        }
 
-    println!("Pending migrations:");
-    for (from, to, desc) in &pending {
-        println!("  • v{} → v{}: {}", from, to, desc);
+        println!("Pending migrations:");
+        for (from, to, desc) in &pending {
+            println!("  • v{} → v{}: {}", from, to, desc);
+        }
+        println!();
     }
-    println!();
 
     // Run migrations
-    let applied = migrate::run_migrations(config, dry_run)?;
+    let applied = migrate::run_migrations(config, plan)?;
 
+    if !plan.text() {
+        return Ok(());
+    }
     println!();
-    if dry_run {
+    if plan.dry_run() {
         println!("{} Dry run - no changes applied", "📋".blue());
     } else {
-        println!("{} Applied {} migration(s)", "✓".green(), applied.len());
+        println!("{} Applied {} migration(s)", presenter::ok(), applied.len());
         println!();
         println!("Version tags created in ~/.config/pais (git tags)");
     }
@@ -64,11 +80,11 @@ fn show_status() -> Result<()> {
 
     if current < target {
         let pending = migrate::pending_migrations()?;
-        println!("{} {} migration(s) pending", "⚠".yellow(), pending.len());
+        println!("{} {} migration(s) pending", presenter::warn(), pending.len());
         println!();
         println!("Run `pais upgrade` to apply pending migrations.");
     } else {
-        println!("{} Up to date", "✓".green());
+        println!("{} Up to date", presenter::ok());
     }
 
     Ok(())
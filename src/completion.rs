@@ -0,0 +1,156 @@
+//! Dynamic shell completion of names from live state
+//!
+//! The shell script printed by `pais completions <shell>` calls back into
+//! `pais` (with `COMPLETE=<shell>` set) on every TAB, so skill, plugin,
+//! agent, bundle, profile and MCP server names always reflect what is
+//! currently installed. Each completer loads config the same way a normal
+//! run does (`PAIS_CONFIG`, `PAIS_DIR`, defaults) and yields nothing on error,
+//! so a broken config never breaks the shell.
+
+use clap_complete::CompletionCandidate;
+use indexmap::IndexMap;
+
+use crate::commands;
+use crate::config::Config;
+
+/// Environment variable the registration script sets when asking for completions
+pub const COMPLETE_VAR: &str = "COMPLETE";
+
+/// Turn a list of names into sorted, de-duplicated candidates
+fn candidates(names: Vec<String>, help: &str) -> Vec<CompletionCandidate> {
+    let mut names = names;
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .map(|name| CompletionCandidate::new(name).help(Some(help.to_string().into())))
+        .collect()
+}
+
+/// Candidates for each profile, with its members as help text
+fn profile_candidates(profiles: &IndexMap<String, Vec<String>>) -> Vec<CompletionCandidate> {
+    profiles
+        .iter()
+        .map(|(name, members)| {
+            CompletionCandidate::new(name).help(Some(format!("profile: {}", members.join(", ")).into()))
+        })
+        .collect()
+}
+
+/// Load config and list names, swallowing errors
+fn names_from<F>(list: F) -> Vec<String>
+where
+    F: FnOnce(&Config) -> eyre::Result<Vec<String>>,
+{
+    Config::load(None).and_then(|config| list(&config)).unwrap_or_default()
+}
+
+pub fn skills() -> Vec<CompletionCandidate> {
+    candidates(names_from(commands::skill::skill_names), "skill")
+}
+
+pub fn plugins() -> Vec<CompletionCandidate> {
+    candidates(names_from(commands::plugin::plugin_names), "plugin")
+}
+
+pub fn agents() -> Vec<CompletionCandidate> {
+    candidates(names_from(commands::agent::agent_names), "agent")
+}
+
+pub fn bundles() -> Vec<CompletionCandidate> {
+    candidates(names_from(commands::bundle::bundle_names), "bundle")
+}
+
+/// The plugin named on a `pais run <plugin> <action>` command line
+///
+/// The registration script passes the words typed so far after `--`, so
+/// the plugin is the first word after `run` that isn't a flag.
+fn run_plugin(words: &[String]) -> Option<&str> {
+    let run = words.iter().position(|w| w == "run")?;
+    words[run + 1..]
+        .iter()
+        .find(|w| !w.starts_with('-'))
+        .map(String::as_str)
+}
+
+/// Actions the plugin being run reports through `describe`
+pub fn plugin_actions() -> Vec<CompletionCandidate> {
+    let words: Vec<String> = std::env::args().collect();
+    let Some(plugin) = run_plugin(&words) else {
+        return Vec::new();
+    };
+    let Ok(config) = Config::load(None) else {
+        return Vec::new();
+    };
+    commands::plugin::plugin_actions(plugin, &config)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|action| {
+            let help = action.description.unwrap_or_else(|| "action".to_string());
+            CompletionCandidate::new(action.name).help(Some(help.into()))
+        })
+        .collect()
+}
+
+/// Skill names and skill profiles for `pais session --skill`
+pub fn session_skills() -> Vec<CompletionCandidate> {
+    let Ok(config) = Config::load(None) else {
+        return Vec::new();
+    };
+    let mut out = profile_candidates(&config.skills.profiles);
+    out.extend(candidates(
+        commands::skill::skill_names(&config).unwrap_or_default(),
+        "skill",
+    ));
+    out
+}
+
+/// MCP server names and MCP profiles for `pais session --mcp`
+pub fn session_mcps() -> Vec<CompletionCandidate> {
+    let Ok(config) = Config::load(None) else {
+        return Vec::new();
+    };
+    let mut out = profile_candidates(&config.mcp.profiles);
+    out.extend(candidates(commands::session::mcp_server_names(&config), "mcp server"));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(candidates: &[CompletionCandidate]) -> Vec<String> {
+        candidates
+            .iter()
+            .map(|c| c.get_value().to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_candidates_sorted_and_deduped() {
+        let out = candidates(
+            vec!["rust-coder".to_string(), "docker".to_string(), "rust-coder".to_string()],
+            "skill",
+        );
+        assert_eq!(values(&out), vec!["docker", "rust-coder"]);
+    }
+
+    #[test]
+    fn test_run_plugin_from_typed_words() {
+        let words = |line: &str| line.split(' ').map(String::from).collect::<Vec<_>>();
+        assert_eq!(run_plugin(&words("pais -- pais run notes ")), Some("notes"));
+        assert_eq!(run_plugin(&words("pais -- pais -v run -x notes gr")), Some("notes"));
+        assert_eq!(run_plugin(&words("pais -- pais skill list")), None);
+    }
+
+    #[test]
+    fn test_profile_candidates_keep_order_and_describe_members() {
+        let mut profiles = IndexMap::new();
+        profiles.insert("work".to_string(), vec!["github".to_string(), "slack".to_string()]);
+        profiles.insert("minimal".to_string(), vec![]);
+
+        let out = profile_candidates(&profiles);
+        assert_eq!(values(&out), vec!["work", "minimal"]);
+        assert_eq!(out[0].get_help().unwrap().to_string(), "profile: github, slack");
+    }
+}
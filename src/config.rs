@@ -1,10 +1,16 @@
 use eyre::{Context, Result};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::perf;
+use crate::policy::PolicyRule;
+
+/// Origin of `./pais.yaml` among the config file candidates
+const LOCAL_CONFIG: &str = "local config";
+
 /// Log level for RUST_LOG
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "lowercase")]
@@ -32,6 +38,17 @@ impl LogLevel {
     }
 }
 
+/// Format of lines in the log file
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// env_logger's `[timestamp LEVEL module] message`
+    #[default]
+    Text,
+    /// One JSON object per line (timestamp, level, module, message, fields)
+    Json,
+}
+
 /// Main PAIS configuration
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default)]
@@ -39,12 +56,24 @@ pub struct Config {
     /// Log level (can be overridden by RUST_LOG env var)
     #[serde(rename = "log-level")]
     pub log_level: LogLevel,
+    /// Log line format (text or json)
+    #[serde(rename = "log-format")]
+    pub log_format: LogFormat,
     pub paths: PathsConfig,
     pub hooks: HooksConfig,
     pub observability: ObservabilityConfig,
     pub environment: EnvironmentConfig,
     pub mcp: McpConfig,
     pub skills: SkillsConfig,
+    pub plugins: PluginsConfig,
+    pub policy: PolicyConfig,
+    pub sync: SyncConfig,
+    pub publish: PublishConfig,
+    pub network: NetworkConfig,
+    pub perf: PerfConfig,
+    pub security: SecurityConfig,
+    pub ui: UiConfig,
+    pub history: HistoryConfig,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -57,223 +86,1290 @@ pub struct PathsConfig {
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default, rename_all = "kebab-case")]
-pub struct HooksConfig {
-    pub security_enabled: bool,
-    pub history_enabled: bool,
-    pub ui_enabled: bool,
-    pub research_enabled: bool,
+pub struct HooksConfig {
+    pub security_enabled: bool,
+    pub history_enabled: bool,
+    pub ui_enabled: bool,
+    pub research_enabled: bool,
+    pub checkpoint: CheckpointConfig,
+
+    /// Per-handler overrides, by handler name (see [`crate::hook::registry`])
+    pub handlers: BTreeMap<String, HandlerSettings>,
+}
+
+/// Overrides for one built-in hook handler
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct HandlerSettings {
+    /// Run the handler; unset keeps the handler's own default
+    pub enabled: Option<bool>,
+
+    /// Lower runs first; unset keeps the handler's default
+    pub priority: Option<i32>,
+
+    /// Handler-specific options
+    pub settings: serde_json::Map<String, serde_json::Value>,
+}
+
+/// How checkpoints are stored (see [`crate::checkpoint`])
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckpointMode {
+    /// Commit chain under `refs/pais/checkpoints/<session>`
+    #[default]
+    Ref,
+    /// Entries in the stash list (tracked files only)
+    Stash,
+}
+
+/// Automatic git checkpoints of agent edits
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct CheckpointConfig {
+    pub enabled: bool,
+
+    /// File modifications between checkpoints
+    pub every: usize,
+
+    pub mode: CheckpointMode,
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            every: 5,
+            mode: CheckpointMode::default(),
+        }
+    }
+}
+
+/// Observability sink type
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ObservabilitySink {
+    /// Write to JSONL file (default, uses history path)
+    File,
+    /// Print to stdout
+    Stdout,
+    /// Send to HTTP endpoint
+    Http,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ObservabilityConfig {
+    /// Enable observability
+    pub enabled: bool,
+    /// Which sinks to send events to
+    pub sinks: Vec<ObservabilitySink>,
+    /// HTTP endpoint for http sink
+    pub http_endpoint: Option<String>,
+    /// Include event payload in output (can be verbose)
+    pub include_payload: bool,
+    /// Seconds the file sink buffers events before writing them to the day
+    /// logs in one batch (0 writes each event as it comes)
+    pub flush_interval: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let pais_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from(".")).join("pais");
+
+        Self {
+            log_level: LogLevel::default(),
+            log_format: LogFormat::default(),
+            paths: PathsConfig {
+                plugins: pais_dir.join("plugins"),
+                skills: pais_dir.join("skills"),
+                history: pais_dir.join("history"),
+            },
+            hooks: HooksConfig::default(),
+            observability: ObservabilityConfig::default(),
+            environment: EnvironmentConfig::default(),
+            mcp: McpConfig::default(),
+            skills: SkillsConfig::default(),
+            plugins: PluginsConfig::default(),
+            policy: PolicyConfig::default(),
+            sync: SyncConfig::default(),
+            publish: PublishConfig::default(),
+            network: NetworkConfig::default(),
+            perf: PerfConfig::default(),
+            security: SecurityConfig::default(),
+            ui: UiConfig::default(),
+            history: HistoryConfig::default(),
+        }
+    }
+}
+
+impl PathsConfig {
+    /// Copy with `~` and environment variables expanded
+    pub fn expanded(&self) -> Self {
+        Self {
+            plugins: Config::expand_path(&self.plugins),
+            skills: Config::expand_path(&self.skills),
+            history: Config::expand_path(&self.history),
+        }
+    }
+
+    /// Environment variables the paths depend on (`HOME` for `~`, plus any `$VAR`)
+    fn referenced_vars(&self) -> Vec<String> {
+        let mut vars = vec!["HOME".to_string()];
+        for path in [&self.plugins, &self.skills, &self.history] {
+            let path = path.to_string_lossy();
+            for caps in lazy_regex::regex!(r"\$\{?([A-Za-z_][A-Za-z0-9_]*)").captures_iter(&path) {
+                vars.push(caps[1].to_string());
+            }
+        }
+        vars.sort();
+        vars.dedup();
+        vars
+    }
+}
+
+impl Default for PathsConfig {
+    fn default() -> Self {
+        let pais_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from(".")).join("pais");
+
+        Self {
+            plugins: pais_dir.join("plugins"),
+            skills: pais_dir.join("skills"),
+            history: pais_dir.join("history"),
+        }
+    }
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            security_enabled: true,
+            history_enabled: true,
+            ui_enabled: true,
+            research_enabled: true,
+            checkpoint: CheckpointConfig::default(),
+            handlers: BTreeMap::new(),
+        }
+    }
+}
+
+impl Default for ObservabilityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            sinks: vec![ObservabilitySink::File],
+            http_endpoint: None,
+            include_payload: false,
+            flush_interval: 0,
+        }
+    }
+}
+
+/// Environment awareness configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct EnvironmentConfig {
+    /// Directory where repos are cloned (e.g., ~/repos/)
+    /// Repos are expected at: {repos-dir}/{org}/{repo}
+    pub repos_dir: Option<PathBuf>,
+
+    /// Modern tool preferences (e.g., ls -> eza, grep -> rg)
+    pub tool_preferences: HashMap<String, String>,
+
+    /// Custom tools with install info
+    pub tools: HashMap<String, ToolConfig>,
+
+    /// Describe the session's project (languages, package managers, Make
+    /// targets, CI) in the injected context (see [`crate::workspace`])
+    pub detect_project: bool,
+}
+
+impl Default for EnvironmentConfig {
+    fn default() -> Self {
+        Self {
+            repos_dir: None,
+            tool_preferences: HashMap::new(),
+            tools: HashMap::new(),
+            detect_project: true,
+        }
+    }
+}
+
+/// Configuration for a custom tool
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ToolConfig {
+    /// GitHub repository (e.g., "scottidler/otto")
+    pub github: Option<String>,
+
+    /// Description of what the tool does
+    pub description: Option<String>,
+
+    /// Custom install command (defaults to cargo install --git)
+    pub install: Option<String>,
+}
+
+/// MCP (Model Context Protocol) server configuration
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct McpConfig {
+    /// Source files for MCP server definitions
+    /// First one found with the server will be used
+    pub sources: Vec<PathBuf>,
+
+    /// Named profiles mapping to lists of MCP server names
+    /// First profile is the default when no -m flag provided
+    pub profiles: IndexMap<String, Vec<String>>,
+
+    /// Additional MCP server definitions (supplements sources)
+    pub servers: HashMap<String, McpServerConfig>,
+}
+
+/// Configuration for a single MCP server
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct McpServerConfig {
+    /// Command to run the MCP server
+    pub command: String,
+
+    /// Arguments to pass to the command
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// Environment variables for the server
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+/// Plugin installation settings
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct PluginsConfig {
+    /// Indexes `pais plugin search` and `pais plugin install <name>` consult,
+    /// in order (see [`crate::plugin::registry`])
+    pub registries: Vec<RegistryConfig>,
+
+    /// Signing and signature checks (see [`crate::plugin::signing`])
+    pub signing: PluginSigningConfig,
+
+    /// How long a plugin hook script may run before it's killed, unless its
+    /// manifest sets `timeout`
+    pub hook_timeout_secs: u64,
+
+    /// Settings for individual plugins, keyed by plugin name (`plugins.<name>.config`)
+    #[serde(flatten)]
+    pub settings: BTreeMap<String, PluginSettings>,
+}
+
+impl Default for PluginsConfig {
+    fn default() -> Self {
+        Self {
+            registries: Vec::new(),
+            signing: PluginSigningConfig::default(),
+            hook_timeout_secs: 30,
+            settings: BTreeMap::new(),
+        }
+    }
+}
+
+/// Settings for one plugin
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct PluginSettings {
+    /// Values for the plugin's declared `config:` schema (see [`crate::plugin::config`])
+    pub config: BTreeMap<String, serde_json::Value>,
+}
+
+/// What an unsigned or untrusted plugin means
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SignaturePolicy {
+    /// Don't check signatures
+    #[default]
+    Off,
+    /// Install and run the plugin, with a warning
+    Warn,
+    /// Refuse to install the plugin and skip its hooks
+    Enforce,
+}
+
+/// Plugin signing settings
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct PluginSigningConfig {
+    pub policy: SignaturePolicy,
+
+    /// minisign public keys trusted to sign plugins: `.pub` files or their base64 line
+    pub trusted_keys: Vec<String>,
+
+    /// Secret key `pais plugin sign` signs with, created on first use; its
+    /// public key (`<key>.pub`) is trusted too
+    pub key: String,
+}
+
+impl Default for PluginSigningConfig {
+    fn default() -> Self {
+        Self {
+            policy: SignaturePolicy::default(),
+            trusted_keys: Vec::new(),
+            key: "~/.config/pais/keys/plugin-signing.key".to_string(),
+        }
+    }
+}
+
+/// A plugin registry: an `index.yaml` served over HTTP or kept in a git repository
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct RegistryConfig {
+    pub name: String,
+
+    /// URL (or path) of the index when it ends in `.yaml`/`.yml`, else a git repository
+    pub url: String,
+
+    /// Path of the index in a git registry
+    pub index: String,
+}
+
+impl Default for RegistryConfig {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            url: String::new(),
+            index: "index.yaml".to_string(),
+        }
+    }
+}
+
+/// Skills configuration for dynamic skill loading
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct SkillsConfig {
+    /// Named profiles mapping to lists of skill names
+    /// First profile is the default when no -s flag provided
+    pub profiles: IndexMap<String, Vec<String>>,
+
+    /// Skill usage tracking and usage-based context injection
+    pub usage: SkillUsageConfig,
+
+    /// Compressed core skills once the injected context grows too large
+    pub compression: SkillCompressionConfig,
+
+    /// Drafting skills from history entries with `pais skill distill`
+    pub distill: SkillDistillConfig,
+
+    /// Name deferred skills without their tables and let Claude read them
+    /// from `pais mcp serve` (see [`crate::mcp`])
+    pub mcp: bool,
+
+    /// Which copy to use for names found in both the skills and plugins
+    /// directories (see [`crate::skill::conflicts`]); unlisted names use the skill
+    pub resolve: IndexMap<String, SkillPreference>,
+}
+
+/// Side that wins when a skill shadows a plugin of the same name
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SkillPreference {
+    /// The skill in `paths.skills`
+    #[default]
+    Skill,
+    /// The plugin in `paths.plugins`
+    Plugin,
+}
+
+/// Skill usage tracking (see [`crate::skill::usage`])
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct SkillUsageConfig {
+    /// Record skill triggers from PreToolUse and UserPromptSubmit hooks
+    pub track: bool,
+
+    /// Shape `pais context inject` by recent usage
+    pub adaptive_context: bool,
+
+    /// Deferred skills triggered within this many days are expanded
+    pub recent_days: u32,
+
+    /// Deferred skills not triggered for this many days shrink to a one-line mention
+    pub demote_after_days: u32,
+}
+
+impl Default for SkillUsageConfig {
+    fn default() -> Self {
+        Self {
+            track: true,
+            adaptive_context: true,
+            recent_days: 3,
+            demote_after_days: 30,
+        }
+    }
+}
+
+/// How `pais skill minify` shortens a skill
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionMethod {
+    /// Drop listed sections, long code blocks and comments
+    #[default]
+    Rules,
+    /// Ask Claude (`claude -p`) to summarize the skill
+    Llm,
+}
+
+/// Skill compression (see [`crate::skill::minify`])
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct SkillCompressionConfig {
+    /// Estimated tokens `pais context inject` may print before core skills are
+    /// swapped for their minified form (unset: never compress)
+    pub token_ceiling: Option<usize>,
+
+    /// Default method for `pais skill minify`; injection itself never calls Claude
+    pub method: CompressionMethod,
+
+    /// Section headings rule-based pruning drops (case-insensitive)
+    pub drop_sections: Vec<String>,
+
+    /// Fenced code blocks with more lines than this are replaced by a note
+    pub max_code_lines: usize,
+}
+
+impl Default for SkillCompressionConfig {
+    fn default() -> Self {
+        Self {
+            token_ceiling: None,
+            method: CompressionMethod::default(),
+            drop_sections: [
+                "Examples",
+                "Example",
+                "Background",
+                "References",
+                "See Also",
+                "Changelog",
+                "FAQ",
+            ]
+            .map(String::from)
+            .to_vec(),
+            max_code_lines: 12,
+        }
+    }
+}
+
+/// How `pais skill distill` writes a draft
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum DistillMethod {
+    /// Collect each entry's lesson under a generated frontmatter
+    #[default]
+    Template,
+    /// Ask Claude (`claude -p`) to write the skill from the entries
+    Llm,
+}
+
+/// Skill distillation (see [`crate::skill::distill`])
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct SkillDistillConfig {
+    /// Default method for `pais skill distill`
+    pub method: DistillMethod,
+
+    /// History categories searched when no `--category` is given
+    pub categories: Vec<String>,
+
+    /// Most entries a draft is made from
+    pub max_entries: usize,
+}
+
+impl Default for SkillDistillConfig {
+    fn default() -> Self {
+        Self {
+            method: DistillMethod::default(),
+            categories: vec!["learnings".to_string(), "sessions".to_string()],
+            max_entries: 20,
+        }
+    }
+}
+
+/// Policy engine configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct PolicyConfig {
+    /// Enable policy evaluation in hooks
+    pub enabled: bool,
+
+    /// Global policy rules (project `.pais/policy.yaml` rules are evaluated first)
+    pub rules: Vec<PolicyRule>,
+}
+
+impl Default for PolicyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            rules: Vec::new(),
+        }
+    }
+}
+
+/// Multi-machine sync configuration
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct SyncConfig {
+    /// Remote to sync with: a git URL, `s3://bucket/prefix`, or `ssh://host/path`
+    pub remote: Option<String>,
+
+    /// Also sync history (off by default; history can be large)
+    pub include_history: bool,
+}
+
+/// HTTP settings for network-heavy commands (image generation, diagrams)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct NetworkConfig {
+    /// Overall limit for one request, in seconds
+    pub timeout_secs: u64,
+
+    /// Limit for establishing a connection, in seconds
+    pub connect_timeout_secs: u64,
+
+    /// Never reach beyond this machine (also `--offline`): commands that need
+    /// the network fail fast or use a local alternative
+    pub offline: bool,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 120,
+            connect_timeout_secs: 10,
+            offline: false,
+        }
+    }
+}
+
+/// Self-metrics of pais's own commands (see `crate::perf`)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct PerfConfig {
+    /// Record each command's duration and cache hits locally
+    pub enabled: bool,
+}
+
+impl Default for PerfConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// Security hook settings
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct SecurityConfig {
+    /// YAML list of extra patterns checked alongside the built-in tiers (optional)
+    pub patterns: PathBuf,
+
+    /// Which workspaces get project overrides and the normal tiers
+    pub trust: TrustConfig,
+
+    /// Say in block messages what the command would have done and what to
+    /// do instead, so Claude can self-correct
+    pub explain: bool,
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            patterns: dirs::config_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("pais")
+                .join("security-patterns.yaml"),
+            trust: TrustConfig::default(),
+            explain: true,
+        }
+    }
+}
+
+/// Workspace trust (see [`crate::trust`])
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct TrustConfig {
+    /// Treat workspaces nobody ran `pais trust` on as untrusted
+    pub enabled: bool,
+
+    /// Directories trusted along with everything below them, without `pais trust`
+    pub trusted: Vec<PathBuf>,
+}
+
+impl Default for TrustConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            trusted: Vec::new(),
+        }
+    }
+}
+
+/// History storage settings
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct HistoryConfig {
+    /// Secret stripping applied before entries are written
+    pub redaction: RedactionConfig,
+
+    /// Per-project notes carried from one session to the next
+    pub handoff: HandoffConfig,
+
+    /// Categories entries may be stored under; listing any replaces the
+    /// built-in set (see [`crate::history::categorize`])
+    pub categories: Vec<CategoryConfig>,
+
+    /// Category for content no routing rule claims
+    pub default_category: String,
+
+    /// Review queue for auto-captured entries
+    pub review: ReviewConfig,
+
+    /// SQLite index for faster lookups
+    pub index: HistoryIndexConfig,
+
+    /// Embeddings for `pais history similar`
+    pub embeddings: HistoryEmbeddingsConfig,
+
+    /// Model-written titles, summaries and tags for Stop captures
+    pub labeling: HistoryLabelingConfig,
+
+    /// Tool-use timing for `pais history tools`
+    pub tools: HistoryToolsConfig,
+
+    /// Encryption of entry bodies at rest
+    pub encryption: HistoryEncryptionConfig,
+
+    /// Git remote for `pais history sync`
+    pub sync: HistorySyncConfig,
+
+    /// Near-duplicate detection for captured entries
+    pub dedupe: HistoryDedupeConfig,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            redaction: RedactionConfig::default(),
+            handoff: HandoffConfig::default(),
+            categories: crate::history::categorize::builtin_categories(),
+            default_category: "sessions".to_string(),
+            review: ReviewConfig::default(),
+            index: HistoryIndexConfig::default(),
+            embeddings: HistoryEmbeddingsConfig::default(),
+            labeling: HistoryLabelingConfig::default(),
+            tools: HistoryToolsConfig::default(),
+            encryption: HistoryEncryptionConfig::default(),
+            sync: HistorySyncConfig::default(),
+            dedupe: HistoryDedupeConfig::default(),
+        }
+    }
+}
+
+/// A history category
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct CategoryConfig {
+    /// Directory name under the history path
+    pub name: String,
+
+    pub description: String,
+
+    /// Entries older than this are deleted by `pais history prune`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retention_days: Option<u32>,
+
+    /// Frontmatter fields added to every entry stored here
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub frontmatter: IndexMap<String, String>,
+
+    /// Tera template for entry files stored here, inline or a path to a file
+    /// (see [`crate::history::template`]); unset uses the default layout
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+
+    /// When captured content is routed here
+    pub route: CategoryRoute,
+}
+
+/// Content routing rule for a category
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct CategoryRoute {
+    /// Phrases (matched case-insensitively) that suggest this category
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+
+    /// How many distinct keywords must appear
+    pub min_matches: usize,
+
+    /// Regular expressions (case-insensitive); one match routes content here
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<String>,
+
+    /// Agents (or Claude Code subagent types) whose Stop events are stored here
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub agents: Vec<String>,
+}
+
+impl Default for CategoryRoute {
+    fn default() -> Self {
+        Self {
+            keywords: Vec::new(),
+            min_matches: 2,
+            patterns: Vec::new(),
+            agents: Vec::new(),
+        }
+    }
+}
+
+/// Review queue settings (see [`crate::history::review`])
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ReviewConfig {
+    /// Mark entries captured on Stop as unreviewed
+    pub enabled: bool,
+
+    /// Categories whose reviewed entries are promoted into the index
+    pub promote: Vec<String>,
+
+    /// Category `pais review generate` stores weekly reviews in
+    pub weekly_category: String,
+}
+
+impl Default for ReviewConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            promote: vec!["learnings".to_string()],
+            weekly_category: "sessions".to_string(),
+        }
+    }
+}
+
+/// History index settings (see [`crate::history::index`])
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct HistoryIndexConfig {
+    /// Record entries in `index/history.db` and look them up there (and
+    /// skip rebuilding it before each `pais history search`)
+    pub enabled: bool,
+}
+
+/// Tool-use timing settings (see [`crate::history::tools`])
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct HistoryToolsConfig {
+    /// Record each tool call's duration and outcome
+    pub enabled: bool,
+}
+
+impl Default for HistoryToolsConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// History sync settings (see [`crate::history::sync`])
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct HistorySyncConfig {
+    /// Git URL pushed to and pulled from (default: the repo's `origin`)
+    pub remote: Option<String>,
+
+    /// Branch history is kept on
+    pub branch: String,
+}
+
+impl Default for HistorySyncConfig {
+    fn default() -> Self {
+        Self {
+            remote: None,
+            branch: "main".to_string(),
+        }
+    }
+}
+
+/// What happens to a captured entry that nearly duplicates a recent one
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DedupeAction {
+    /// Fold its tags into the existing entry and count it there
+    #[default]
+    Merge,
+    /// Drop it
+    Skip,
+}
+
+/// History near-duplicate settings (see [`crate::history::dedupe`])
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct HistoryDedupeConfig {
+    /// Check Stop captures against recent entries before storing them
+    pub enabled: bool,
+
+    /// Shingle similarity (0-1) at which two entries count as duplicates
+    pub threshold: f64,
+
+    /// Recent entries in the same category each capture is compared with
+    pub window: usize,
+
+    pub action: DedupeAction,
 }
 
-/// Observability sink type
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+impl Default for HistoryDedupeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold: 0.8,
+            window: 20,
+            action: DedupeAction::default(),
+        }
+    }
+}
+
+/// How history entry bodies are encrypted
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "lowercase")]
-pub enum ObservabilitySink {
-    /// Write to JSONL file (default, uses history path)
-    File,
-    /// Print to stdout
-    Stdout,
-    /// Send to HTTP endpoint
-    Http,
+pub enum EncryptionMethod {
+    /// ChaCha20-Poly1305 with a key derived from a passphrase secret
+    #[default]
+    Key,
+    /// The `age` CLI, to the configured recipients
+    Age,
 }
 
+/// History encryption settings (see [`crate::history::encrypt`])
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default, rename_all = "kebab-case")]
-pub struct ObservabilityConfig {
-    /// Enable observability
+pub struct HistoryEncryptionConfig {
+    /// Encrypt the bodies of new entries
     pub enabled: bool,
-    /// Which sinks to send events to
-    pub sinks: Vec<ObservabilitySink>,
-    /// HTTP endpoint for http sink
-    pub http_endpoint: Option<String>,
-    /// Include event payload in output (can be verbose)
-    pub include_payload: bool,
+
+    pub method: EncryptionMethod,
+
+    /// Secret holding the passphrase (method `key`)
+    pub key_secret: String,
+
+    /// age recipients (public keys) entries are encrypted to (method `age`)
+    pub recipients: Vec<String>,
+
+    /// age identity file used to decrypt entries
+    pub identity: String,
 }
 
-impl Default for Config {
+impl Default for HistoryEncryptionConfig {
     fn default() -> Self {
-        let pais_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from(".")).join("pais");
-
         Self {
-            log_level: LogLevel::default(),
-            paths: PathsConfig {
-                plugins: pais_dir.join("plugins"),
-                skills: pais_dir.join("skills"),
-                history: pais_dir.join("history"),
-            },
-            hooks: HooksConfig::default(),
-            observability: ObservabilityConfig::default(),
-            environment: EnvironmentConfig::default(),
-            mcp: McpConfig::default(),
-            skills: SkillsConfig::default(),
+            enabled: false,
+            method: EncryptionMethod::default(),
+            key_secret: "PAIS_HISTORY_KEY".to_string(),
+            recipients: Vec::new(),
+            identity: "~/.config/pais/age-identity.txt".to_string(),
         }
     }
 }
 
-impl Default for PathsConfig {
-    fn default() -> Self {
-        let pais_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from(".")).join("pais");
+/// Where history embeddings come from
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingProvider {
+    /// A local ollama server; nothing leaves the machine
+    #[default]
+    Ollama,
+    OpenAi,
+    Gemini,
+}
+
+/// History embedding settings (see [`crate::history::embed`])
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct HistoryEmbeddingsConfig {
+    pub provider: EmbeddingProvider,
 
+    /// Embedding model (default: nomic-embed-text, text-embedding-3-small or
+    /// text-embedding-004, by provider)
+    pub model: Option<String>,
+
+    /// Ollama server
+    pub ollama_url: String,
+
+    /// Secret holding the API key (default: OPENAI_API_KEY or GOOGLE_API_KEY)
+    pub api_key_secret: Option<String>,
+}
+
+impl Default for HistoryEmbeddingsConfig {
+    fn default() -> Self {
         Self {
-            plugins: pais_dir.join("plugins"),
-            skills: pais_dir.join("skills"),
-            history: pais_dir.join("history"),
+            provider: EmbeddingProvider::default(),
+            model: None,
+            ollama_url: "http://localhost:11434".to_string(),
+            api_key_secret: None,
         }
     }
 }
 
-impl Default for HooksConfig {
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelProvider {
+    #[default]
+    OpenAi,
+    Gemini,
+    /// A local ollama server; nothing leaves the machine
+    Ollama,
+}
+
+/// Capture labeling settings (see [`crate::history::label`])
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct HistoryLabelingConfig {
+    /// Ask a model to title, summarize, tag and categorize each Stop capture
+    pub enabled: bool,
+
+    pub provider: LabelProvider,
+
+    /// Model (default: gpt-4o-mini, gemini-2.0-flash or llama3.2, by provider)
+    pub model: Option<String>,
+
+    /// Ollama server
+    pub ollama_url: String,
+
+    /// Secret holding the API key (default: OPENAI_API_KEY or GOOGLE_API_KEY, as for `pais image`)
+    pub api_key_secret: Option<String>,
+
+    /// Keep the extracted title and tags when the model takes longer than this
+    pub timeout_secs: u64,
+}
+
+impl Default for HistoryLabelingConfig {
     fn default() -> Self {
         Self {
-            security_enabled: true,
-            history_enabled: true,
-            ui_enabled: true,
-            research_enabled: true,
+            enabled: false,
+            provider: LabelProvider::default(),
+            model: None,
+            ollama_url: "http://localhost:11434".to_string(),
+            api_key_secret: None,
+            timeout_secs: 10,
         }
     }
 }
 
-impl Default for ObservabilityConfig {
+/// Session handoff settings (see [`crate::history::handoff`])
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct HandoffConfig {
+    pub enabled: bool,
+
+    /// Handoffs older than this aren't injected
+    pub max_age_days: u32,
+
+    /// Most items kept per section
+    pub max_items: usize,
+}
+
+impl Default for HandoffConfig {
     fn default() -> Self {
         Self {
             enabled: true,
-            sinks: vec![ObservabilitySink::File],
-            http_endpoint: None,
-            include_payload: false,
+            max_age_days: 14,
+            max_items: 5,
         }
     }
 }
 
-/// Environment awareness configuration
-#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+/// Redaction of secrets from history entries (see [`crate::history::redact`])
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default, rename_all = "kebab-case")]
-pub struct EnvironmentConfig {
-    /// Directory where repos are cloned (e.g., ~/repos/)
-    /// Repos are expected at: {repos-dir}/{org}/{repo}
-    pub repos_dir: Option<PathBuf>,
+pub struct RedactionConfig {
+    pub enabled: bool,
 
-    /// Modern tool preferences (e.g., ls -> eza, grep -> rg)
-    pub tool_preferences: HashMap<String, String>,
+    /// Extra patterns (e.g. customer identifiers) on top of the built-in ones
+    pub patterns: Vec<RedactionPattern>,
 
-    /// Custom tools with install info
-    pub tools: HashMap<String, ToolConfig>,
+    /// Also redact long random-looking tokens that no pattern matched
+    pub entropy: bool,
+
+    /// Shannon entropy (bits per character) a token needs to count as random
+    pub min_entropy: f64,
+
+    /// Shortest token the entropy check considers
+    pub min_length: usize,
+
+    /// Regexes for text that is never redacted (checked against each match)
+    pub allow: Vec<String>,
 }
 
-/// Configuration for a custom tool
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            patterns: Vec::new(),
+            entropy: true,
+            min_entropy: 4.0,
+            min_length: 24,
+            allow: Vec::new(),
+        }
+    }
+}
+
+/// A named redaction regex; with a `secret` capture group only that group is replaced
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RedactionPattern {
+    pub name: String,
+    pub regex: String,
+}
+
+/// Terminal output settings (see [`crate::presenter`])
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(default, rename_all = "kebab-case")]
-pub struct ToolConfig {
-    /// GitHub repository (e.g., "scottidler/otto")
-    pub github: Option<String>,
+pub struct UiConfig {
+    /// Colors for status markers and highlights
+    pub theme: Theme,
+    /// Unicode symbols and box drawing, or plain ASCII
+    pub charset: Charset,
+}
 
-    /// Description of what the tool does
-    pub description: Option<String>,
+/// Color theme for terminal output
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum Theme {
+    /// Green/red/yellow/blue
+    #[default]
+    Default,
+    /// Blue/orange/yellow, distinguishable with red-green color blindness
+    Colorblind,
+    /// No colors; bold and dim only
+    Mono,
+}
 
-    /// Custom install command (defaults to cargo install --git)
-    pub install: Option<String>,
+/// Character set for symbols and rules
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum Charset {
+    /// Unicode unless the locale or terminal says otherwise
+    #[default]
+    Auto,
+    Unicode,
+    Ascii,
 }
 
-/// MCP (Model Context Protocol) server configuration
+/// Targets for `pais history publish`
+///
+/// Credentials are never stored here; each target names the secret to look
+/// up (environment first, then `~/.config/pais/.env`).
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(default, rename_all = "kebab-case")]
-pub struct McpConfig {
-    /// Source files for MCP server definitions
-    /// First one found with the server will be used
-    pub sources: Vec<PathBuf>,
+pub struct PublishConfig {
+    pub slack: SlackPublishConfig,
+    pub linear: LinearPublishConfig,
+    pub jira: JiraPublishConfig,
+    pub notion: NotionPublishConfig,
+}
 
-    /// Named profiles mapping to lists of MCP server names
-    /// First profile is the default when no -m flag provided
-    pub profiles: IndexMap<String, Vec<String>>,
+/// Post to a Slack channel through an incoming webhook
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct SlackPublishConfig {
+    /// Secret holding the webhook URL
+    pub webhook_secret: String,
+    /// Message template (default: title, category/date line and summary)
+    pub template: Option<String>,
+}
 
-    /// Additional MCP server definitions (supplements sources)
-    pub servers: HashMap<String, McpServerConfig>,
+impl Default for SlackPublishConfig {
+    fn default() -> Self {
+        Self {
+            webhook_secret: "SLACK_WEBHOOK_URL".to_string(),
+            template: None,
+        }
+    }
 }
 
-/// Configuration for a single MCP server
+/// Create a Linear issue
 #[derive(Debug, Clone, Deserialize, Serialize)]
-#[serde(rename_all = "kebab-case")]
-pub struct McpServerConfig {
-    /// Command to run the MCP server
-    pub command: String,
+#[serde(default, rename_all = "kebab-case")]
+pub struct LinearPublishConfig {
+    /// Team the issue is created in
+    pub team_id: Option<String>,
+    /// Secret holding the API key
+    pub api_key_secret: String,
+    /// Issue description template (default: full entry)
+    pub template: Option<String>,
+}
 
-    /// Arguments to pass to the command
-    #[serde(default)]
-    pub args: Vec<String>,
+impl Default for LinearPublishConfig {
+    fn default() -> Self {
+        Self {
+            team_id: None,
+            api_key_secret: "LINEAR_API_KEY".to_string(),
+            template: None,
+        }
+    }
+}
 
-    /// Environment variables for the server
-    #[serde(default)]
-    pub env: HashMap<String, String>,
+/// Create a Jira issue
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct JiraPublishConfig {
+    /// Site URL, e.g. https://example.atlassian.net
+    pub base_url: Option<String>,
+    /// Project key, e.g. OPS
+    pub project: Option<String>,
+    pub issue_type: String,
+    /// Secret holding the account email
+    pub email_secret: String,
+    /// Secret holding the API token
+    pub token_secret: String,
+    /// Issue description template (default: full entry)
+    pub template: Option<String>,
 }
 
-/// Skills configuration for dynamic skill loading
-#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+impl Default for JiraPublishConfig {
+    fn default() -> Self {
+        Self {
+            base_url: None,
+            project: None,
+            issue_type: "Task".to_string(),
+            email_secret: "JIRA_EMAIL".to_string(),
+            token_secret: "JIRA_API_TOKEN".to_string(),
+            template: None,
+        }
+    }
+}
+
+/// Export entries as pages of a Notion database
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default, rename_all = "kebab-case")]
-pub struct SkillsConfig {
-    /// Named profiles mapping to lists of skill names
-    /// First profile is the default when no -s flag provided
-    pub profiles: IndexMap<String, Vec<String>>,
+pub struct NotionPublishConfig {
+    /// Database used when `--database` is not given
+    pub database: Option<String>,
+    /// Secret holding the integration token
+    pub token_secret: String,
+    /// Database property names entries map to
+    pub title_property: String,
+    pub tags_property: String,
+    pub category_property: String,
+    /// Date property for the entry's creation time (not set unless configured)
+    pub date_property: Option<String>,
+}
+
+impl Default for NotionPublishConfig {
+    fn default() -> Self {
+        Self {
+            database: None,
+            token_secret: "NOTION_TOKEN".to_string(),
+            title_property: "Name".to_string(),
+            tags_property: "Tags".to_string(),
+            category_property: "Category".to_string(),
+            date_property: None,
+        }
+    }
 }
 
 impl Config {
     /// Load configuration with fallback chain
     pub fn load(config_path: Option<&PathBuf>) -> Result<Self> {
+        Self::load_with_source(config_path).map(|(config, _)| config)
+    }
+
+    /// Load configuration through a binary snapshot of the parsed file
+    ///
+    /// For latency-critical paths like `pais hook dispatch`. The snapshot
+    /// holds the config with `paths` already expanded and is reused while the
+    /// source file's size and mtime, the pais version and the environment
+    /// variables the paths reference are unchanged; otherwise the file is
+    /// parsed as usual and the snapshot rewritten.
+    pub fn load_cached(config_path: Option<&PathBuf>) -> Result<Self> {
+        let source = match config_path {
+            Some(path) => Some(path.clone()),
+            None => Self::existing_candidates().map(|(_, path)| path).next(),
+        };
+        let Some(source) = source else {
+            return Self::load(None);
+        };
+        let snapshot_path = snapshot::path_for(&source);
+
+        let cached = snapshot_path.as_deref().and_then(|p| snapshot::read(p, &source));
+        perf::cache("config-snapshot", cached.is_some());
+        if let Some(config) = cached {
+            return Ok(config);
+        }
+
+        let (mut config, loaded) = Self::load_with_source(config_path)?;
+        let vars = config.paths.referenced_vars();
+        config.paths = config.paths.expanded();
+        // A broken first candidate falls through to the next; only cache what the source produced
+        if loaded.as_ref() == Some(&source)
+            && let Some(ref path) = snapshot_path
+            && let Err(e) = snapshot::write(path, &source, &config, &vars)
+        {
+            log::debug!("Failed to write config snapshot: {}", e);
+        }
+        Ok(config)
+    }
+
+    /// Load configuration, also returning the file it came from
+    pub fn load_with_source(config_path: Option<&PathBuf>) -> Result<(Self, Option<PathBuf>)> {
         // If explicit config path provided, try to load it
         if let Some(path) = config_path {
-            return Self::load_from_file(path).context(format!("Failed to load config from {}", path.display()));
+            let config =
+                Self::load_from_file(path).context(format!("Failed to load config from {}", path.display()))?;
+            return Ok((config, Some(path.clone())));
         }
 
-        // Check PAIS_CONFIG env var
-        if let Ok(env_path) = std::env::var("PAIS_CONFIG") {
-            let path = PathBuf::from(env_path);
-            if path.exists() {
-                match Self::load_from_file(&path) {
-                    Ok(config) => return Ok(config),
-                    Err(e) => {
-                        log::warn!("Failed to load config from PAIS_CONFIG: {}", e);
-                    }
+        for (origin, path) in Self::existing_candidates() {
+            match Self::load_from_file(&path) {
+                Ok(config) => return Ok((config, Some(path))),
+                Err(e) => {
+                    log::warn!("Failed to load config from {}: {}", origin, e);
                 }
             }
         }
 
-        // Try PAIS_DIR/pais.yaml
+        // No config file found, use defaults
+        log::info!("No config file found, using defaults");
+        Ok((Self::default(), None))
+    }
+
+    /// Config files to try when none is given explicitly, in priority order
+    fn candidates() -> Vec<(String, PathBuf)> {
+        let mut candidates = Vec::new();
+
+        // PAIS_CONFIG env var
+        if let Ok(env_path) = std::env::var("PAIS_CONFIG") {
+            candidates.push(("PAIS_CONFIG".to_string(), PathBuf::from(env_path)));
+        }
+
+        // PAIS_DIR/pais.yaml
         if let Ok(pais_dir) = std::env::var("PAIS_DIR") {
-            let path = PathBuf::from(pais_dir).join("pais.yaml");
-            if path.exists() {
-                match Self::load_from_file(&path) {
-                    Ok(config) => return Ok(config),
-                    Err(e) => {
-                        log::warn!("Failed to load config from PAIS_DIR: {}", e);
-                    }
-                }
-            }
+            candidates.push(("PAIS_DIR".to_string(), PathBuf::from(pais_dir).join("pais.yaml")));
         }
 
-        // Try ~/.config/pais/pais.yaml
+        // ~/.config/pais/pais.yaml
         if let Some(config_dir) = dirs::config_dir() {
             let path = config_dir.join("pais").join("pais.yaml");
-            if path.exists() {
-                match Self::load_from_file(&path) {
-                    Ok(config) => return Ok(config),
-                    Err(e) => {
-                        log::warn!("Failed to load config from {}: {}", path.display(), e);
-                    }
-                }
-            }
+            candidates.push((path.display().to_string(), path));
         }
 
-        // Try ./pais.yaml (for development)
-        let local_config = PathBuf::from("pais.yaml");
-        if local_config.exists() {
-            match Self::load_from_file(&local_config) {
-                Ok(config) => return Ok(config),
-                Err(e) => {
-                    log::warn!("Failed to load local config: {}", e);
-                }
-            }
-        }
+        // ./pais.yaml (for development)
+        candidates.push((LOCAL_CONFIG.to_string(), PathBuf::from("pais.yaml")));
 
-        // No config file found, use defaults
-        log::info!("No config file found, using defaults");
-        Ok(Self::default())
+        candidates
+    }
+
+    /// Candidates that exist, lazily, in priority order
+    ///
+    /// A cloned repo could ship a `./pais.yaml`, so it only counts in a
+    /// trusted workspace; the trust store is read only when the lookup gets
+    /// that far.
+    fn existing_candidates() -> impl Iterator<Item = (String, PathBuf)> {
+        Self::candidates().into_iter().filter(|(origin, path)| {
+            path.exists()
+                && (origin != LOCAL_CONFIG
+                    || std::env::current_dir().is_ok_and(|cwd| crate::trust::TrustStore::load().is_trusted(&cwd)))
+        })
     }
 
     fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
@@ -307,12 +1403,95 @@ impl Config {
         dirs::home_dir().map(|h| h.join(".claude/skills"))
     }
 
+    /// Get the Claude Code subagents directory (~/.claude/agents)
+    pub fn claude_agents_dir() -> Option<PathBuf> {
+        dirs::home_dir().map(|h| h.join(".claude/agents"))
+    }
+
     /// Get the Claude Code settings file
     pub fn claude_settings_file() -> Option<PathBuf> {
         dirs::home_dir().map(|h| h.join(".claude/settings.json"))
     }
 }
 
+/// Binary snapshots of parsed config files (`~/.cache/pais/config/<hash>.bin`)
+mod snapshot {
+    use eyre::{Context, Result};
+    use serde::{Deserialize, Serialize};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::time::UNIX_EPOCH;
+
+    use super::Config;
+    use crate::skill::cache::content_hash;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Snapshot {
+        version: String,
+        len: u64,
+        modified: u128,
+        env: Vec<(String, Option<String>)>,
+        config: Config,
+    }
+
+    /// Snapshot file for a config source
+    pub fn path_for(source: &Path) -> Option<PathBuf> {
+        let absolute = std::path::absolute(source).ok()?;
+        let name = format!("{}.bin", content_hash(absolute.as_os_str().as_encoded_bytes()));
+        dirs::cache_dir().map(|d| d.join("pais").join("config").join(name))
+    }
+
+    /// Size and mtime of the source file
+    fn stamp(source: &Path) -> Option<(u64, u128)> {
+        let meta = fs::metadata(source).ok()?;
+        let modified = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_nanos();
+        Some((meta.len(), modified))
+    }
+
+    fn env(vars: &[String]) -> Vec<(String, Option<String>)> {
+        vars.iter().map(|v| (v.clone(), std::env::var(v).ok())).collect()
+    }
+
+    /// Cached config for `source`, if the snapshot is still valid
+    pub fn read(path: &Path, source: &Path) -> Option<Config> {
+        let bytes = fs::read(path).ok()?;
+        let snapshot: Snapshot = match rmp_serde::from_slice(&bytes) {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                log::debug!("Ignoring unreadable config snapshot {}: {}", path.display(), e);
+                return None;
+            }
+        };
+        let names: Vec<String> = snapshot.env.iter().map(|(name, _)| name.clone()).collect();
+        let fresh = snapshot.version == env!("CARGO_PKG_VERSION")
+            && stamp(source) == Some((snapshot.len, snapshot.modified))
+            && env(&names) == snapshot.env;
+        fresh.then_some(snapshot.config)
+    }
+
+    /// Store a parsed (and path-expanded) config for `source`
+    pub fn write(path: &Path, source: &Path, config: &Config, vars: &[String]) -> Result<()> {
+        let Some((len, modified)) = stamp(source) else {
+            return Ok(());
+        };
+        let snapshot = Snapshot {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            len,
+            modified,
+            env: env(vars),
+            config: config.clone(),
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let tmp = path.with_extension(format!("bin.{}", std::process::id()));
+        fs::write(&tmp, rmp_serde::to_vec_named(&snapshot)?)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path.display()))?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -507,6 +1686,60 @@ environment:
         assert!(config.environment.tools.contains_key("clone"));
     }
 
+    #[test]
+    fn test_parse_policy_config() {
+        let yaml = r#"
+policy:
+  rules:
+    - name: no-env-edits
+      when: 'tool_input.file_path endswith ".env"'
+      action: block
+"#;
+        let config: Config = serde_yaml::from_str(yaml).expect("Failed to parse policy config");
+        assert!(config.policy.enabled);
+        assert_eq!(config.policy.rules.len(), 1);
+        assert_eq!(config.policy.rules[0].name, "no-env-edits");
+    }
+
+    #[test]
+    fn test_snapshot_round_trip_and_invalidation() {
+        let temp = tempfile::tempdir().unwrap();
+        let source = temp.path().join("pais.yaml");
+        let snapshot_path = temp.path().join("cache").join("config.bin");
+        fs::write(
+            &source,
+            "log-level: debug\nhooks:\n  ui-enabled: true\n  handlers:\n    ui:\n      priority: 5\n      settings:\n        title: [pais, 2]\n",
+        )
+        .unwrap();
+
+        let config = Config::load(Some(&source)).unwrap();
+        snapshot::write(&snapshot_path, &source, &config, &["HOME".to_string()]).unwrap();
+
+        let cached = snapshot::read(&snapshot_path, &source).expect("snapshot should be fresh");
+        assert_eq!(cached.log_level, LogLevel::Debug);
+        assert!(cached.hooks.ui_enabled);
+        assert_eq!(cached.hooks.handlers["ui"].priority, Some(5));
+        assert_eq!(
+            cached.hooks.handlers["ui"].settings["title"],
+            serde_json::json!(["pais", 2])
+        );
+        assert_eq!(cached.paths.history, config.paths.history);
+
+        // Any edit to the source (here: a size change) invalidates the snapshot
+        fs::write(&source, "log-level: trace\n").unwrap();
+        assert!(snapshot::read(&snapshot_path, &source).is_none());
+    }
+
+    #[test]
+    fn test_paths_referenced_vars() {
+        let paths = PathsConfig {
+            plugins: PathBuf::from("$PAIS_HOME/plugins"),
+            skills: PathBuf::from("${XDG_DATA_HOME}/pais/skills"),
+            history: PathBuf::from("~/history"),
+        };
+        assert_eq!(paths.referenced_vars(), vec!["HOME", "PAIS_HOME", "XDG_DATA_HOME"]);
+    }
+
     #[test]
     fn test_log_level_parsing() {
         let yaml = "log-level: trace";
@@ -3,8 +3,8 @@
 //! Contracts define interfaces that plugins can provide or consume.
 //! This enables loose coupling between plugins.
 //!
-//! Note: Contract validation is not yet wired into the plugin loader.
-//! These types are used for manifest parsing but full validation is pending.
+//! Note: Which plugin serves a consumed contract is resolved by name in
+//! `plugin::resolve`; these typed interfaces aren't checked against plugins yet.
 
 #![allow(dead_code)] // Contract validation pending integration
 
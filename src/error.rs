@@ -0,0 +1,182 @@
+//! Machine-readable errors
+//!
+//! Commands return `eyre::Report`s as usual. Failures that scripts may want
+//! to branch on are raised as a `CliError` carrying a stable `code` and an
+//! optional `hint`. In JSON mode `main` prints every error as
+//! `{"error": ..., "code": ..., "hint": ...}` on stderr; errors without a
+//! `CliError` in their chain get a code derived from the underlying cause.
+
+use serde::Serialize;
+use std::fmt;
+
+/// Stable error codes reported in JSON mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// A named plugin, skill, agent, bundle or entry does not exist
+    NotFound,
+    /// Arguments were missing or invalid
+    InvalidInput,
+    /// Configuration is missing or incomplete
+    Config,
+    /// Local state disagrees with a remote and needs manual resolution
+    Conflict,
+    /// A file could not be parsed
+    Parse,
+    /// Filesystem or process I/O failed
+    Io,
+    /// A network operation did not finish in time
+    Timeout,
+    /// The user interrupted the operation
+    Cancelled,
+    /// Another pais process held a lock on the resource for too long
+    Locked,
+    /// The command needs the network and offline mode is on
+    Offline,
+    /// Anything else
+    Error,
+}
+
+/// An error with a stable code and an optional hint for the user
+#[derive(Debug)]
+pub struct CliError {
+    pub code: ErrorCode,
+    pub message: String,
+    pub hint: Option<String>,
+}
+
+impl CliError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    pub fn not_found(kind: &str, name: &str) -> Self {
+        Self::new(ErrorCode::NotFound, format!("{} '{}' not found", kind, name))
+    }
+
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(ref hint) = self.hint {
+            write!(f, "\n{}", hint)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for CliError {}
+
+/// JSON shape of an error printed on stderr
+#[derive(Debug, Serialize)]
+pub struct ErrorReport {
+    pub error: String,
+    pub code: ErrorCode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl ErrorReport {
+    /// Build a report from an error chain
+    ///
+    /// `error` joins the chain's messages outermost first, like eyre's `{:#}`.
+    pub fn from_report(report: &eyre::Report) -> Self {
+        let coded = report.chain().find_map(|e| e.downcast_ref::<CliError>());
+
+        let error = report
+            .chain()
+            .map(|e| match e.downcast_ref::<CliError>() {
+                Some(cli) => cli.message.clone(),
+                None => e.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(": ");
+
+        match coded {
+            Some(cli) => Self {
+                error,
+                code: cli.code,
+                hint: cli.hint.clone(),
+            },
+            None => Self {
+                error,
+                code: classify(report),
+                hint: None,
+            },
+        }
+    }
+}
+
+/// Derive a code from the first recognizable cause in the chain
+fn classify(report: &eyre::Report) -> ErrorCode {
+    for cause in report.chain() {
+        if let Some(io) = cause.downcast_ref::<std::io::Error>() {
+            return match io.kind() {
+                std::io::ErrorKind::NotFound => ErrorCode::NotFound,
+                _ => ErrorCode::Io,
+            };
+        }
+        if cause.is::<serde_yaml::Error>() || cause.is::<serde_json::Error>() {
+            return ErrorCode::Parse;
+        }
+        if cause.downcast_ref::<reqwest::Error>().is_some_and(|e| e.is_timeout()) {
+            return ErrorCode::Timeout;
+        }
+    }
+    ErrorCode::Error
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eyre::WrapErr;
+
+    #[test]
+    fn test_coded_error_keeps_code_and_hint() {
+        let report = Err::<(), _>(
+            CliError::not_found("Skill", "rust-coder").with_hint("Create it with: pais skill add rust-coder"),
+        )
+        .context("Command failed")
+        .unwrap_err();
+
+        let json = ErrorReport::from_report(&report);
+        assert_eq!(json.code, ErrorCode::NotFound);
+        assert_eq!(json.error, "Command failed: Skill 'rust-coder' not found");
+        assert_eq!(json.hint.as_deref(), Some("Create it with: pais skill add rust-coder"));
+    }
+
+    #[test]
+    fn test_uncoded_errors_are_classified() {
+        let io = std::fs::read("/nonexistent/pais.yaml")
+            .context("Failed to read config")
+            .unwrap_err();
+        assert_eq!(ErrorReport::from_report(&io).code, ErrorCode::NotFound);
+
+        let parse = serde_yaml::from_str::<Vec<u32>>("{")
+            .context("Failed to parse")
+            .unwrap_err();
+        assert_eq!(ErrorReport::from_report(&parse).code, ErrorCode::Parse);
+
+        let plain = ErrorReport::from_report(&eyre::eyre!("boom"));
+        assert_eq!(plain.code, ErrorCode::Error);
+        assert!(plain.hint.is_none());
+    }
+
+    #[test]
+    fn test_serialized_shape() {
+        let report = ErrorReport::from_report(&CliError::new(ErrorCode::Config, "No sync remote configured").into());
+        let value = serde_json::to_value(&report).unwrap();
+        assert_eq!(value["code"], "config");
+        assert_eq!(value["error"], "No sync remote configured");
+        assert!(value.get("hint").is_none());
+    }
+}
@@ -0,0 +1,477 @@
+//! Portable history archives
+//!
+//! `pais history pack` writes the whole history directory to one gzipped tar:
+//!
+//! - `manifest.json`: the format name and version, the category schema
+//!   (`history.categories` and `default-category`), and the size and SHA-256
+//!   of every other member;
+//! - `history/…`: entries, the review index, event logs and tool timings at
+//!   their paths under the history directory (sealed entries stay sealed);
+//! - `embeddings.jsonl`: the vectors behind `pais history similar`, keyed by
+//!   entry path, model and text hash.
+//!
+//! The SQLite index records absolute paths, so it is left out: `unpack`
+//! rebuilds it and re-attaches the embeddings. `unpack` checks every member
+//! against the manifest before writing anything, leaves files that differ
+//! locally alone unless forced, and refuses archives from a newer format
+//! version.
+
+use eyre::{Context, Result};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use ring::digest::{SHA256, digest};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::categorize::Categories;
+use super::index::{HistoryIndex, PendingEmbedding};
+use crate::clock;
+use crate::config::{CategoryConfig, HistoryConfig};
+use crate::error::{CliError, ErrorCode};
+use crate::lock::FileLock;
+use crate::share::is_safe_relative;
+
+/// Value of `format` in every manifest
+pub const FORMAT: &str = "pais-history";
+
+/// Bump when the layout changes; older pais refuse newer archives
+pub const VERSION: u32 = 1;
+
+const MANIFEST: &str = "manifest.json";
+const EMBEDDINGS: &str = "embeddings.jsonl";
+const FILES_DIR: &str = "history";
+
+/// Files under `index/` rebuilt on unpack rather than carried
+const REBUILT: &[&str] = &["history.db", "history.db-wal", "history.db-shm", "history.db-journal"];
+
+/// `manifest.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Manifest {
+    pub format: String,
+    pub version: u32,
+    pub created_at: String,
+    pub pais_version: String,
+    pub categories: Vec<CategoryConfig>,
+    pub default_category: String,
+    pub files: Vec<Member>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embeddings: Option<Member>,
+}
+
+/// One archived file: its path (relative to the history directory for `files`) and checksum
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Member {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+impl Member {
+    pub(crate) fn of(path: &str, bytes: &[u8]) -> Self {
+        Self {
+            path: path.to_string(),
+            size: bytes.len() as u64,
+            sha256: sha256(bytes),
+        }
+    }
+
+    pub(crate) fn check(&self, bytes: &[u8]) -> Result<(), CliError> {
+        if bytes.len() as u64 != self.size || sha256(bytes) != self.sha256 {
+            return Err(CliError::new(
+                ErrorCode::Parse,
+                format!("{} is corrupt (checksum mismatch)", self.path),
+            )
+            .with_hint("The archive was damaged in transit; pack it again"));
+        }
+        Ok(())
+    }
+}
+
+/// One line of `embeddings.jsonl`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EmbeddingRecord {
+    path: String,
+    model: String,
+    hash: String,
+    vector: Vec<f32>,
+}
+
+fn sha256(bytes: &[u8]) -> String {
+    digest(&SHA256, bytes)
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Files to pack, by path relative to the history directory
+fn packed_files(history_dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let mut files = Vec::new();
+    let walker = WalkDir::new(history_dir)
+        .sort_by_file_name()
+        .into_iter()
+        // `.git` of `pais history sync`, buffered events and the like
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'));
+    for entry in walker {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let rel = entry.path().strip_prefix(history_dir)?;
+        let rel = rel
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let name = entry.file_name().to_string_lossy();
+        let rebuilt = rel.starts_with("index/") && REBUILT.contains(&name.as_ref());
+        // Half-written entries (`<id>.md.<pid>`)
+        let temporary = name
+            .rsplit_once(".md.")
+            .is_some_and(|(_, pid)| pid.parse::<u32>().is_ok());
+        if !rebuilt && !temporary {
+            files.push((rel, entry.path().to_path_buf()));
+        }
+    }
+    Ok(files)
+}
+
+/// What `pack` wrote
+#[derive(Debug, Serialize)]
+pub struct PackSummary {
+    pub archive: PathBuf,
+    pub files: usize,
+    pub embeddings: usize,
+    pub bytes: u64,
+}
+
+/// Write the history directory to an archive at `output`
+pub fn pack(history_dir: &Path, config: &HistoryConfig, output: &Path) -> Result<PackSummary> {
+    let mut members = Vec::new();
+    let mut contents = Vec::new();
+    for (rel, path) in packed_files(history_dir)? {
+        let bytes = fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        members.push(Member::of(&rel, &bytes));
+        contents.push(bytes);
+    }
+
+    let index = HistoryIndex::new(history_dir);
+    let mut records = Vec::new();
+    if index.exists() {
+        for stored in index.embeddings()? {
+            // Vectors of entries outside this directory can't be re-attached
+            let Ok(rel) = stored.path.strip_prefix(history_dir) else {
+                continue;
+            };
+            records.push(EmbeddingRecord {
+                path: rel.to_string_lossy().replace('\\', "/"),
+                model: stored.model,
+                hash: stored.hash,
+                vector: stored.vector,
+            });
+        }
+    }
+    let mut embeddings = Vec::new();
+    for record in &records {
+        embeddings.extend(serde_json::to_vec(record)?);
+        embeddings.push(b'\n');
+    }
+
+    let manifest = Manifest {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        created_at: clock::now().to_rfc3339(),
+        pais_version: env!("CARGO_PKG_VERSION").to_string(),
+        categories: config.categories.clone(),
+        default_category: config.default_category.clone(),
+        files: members,
+        embeddings: (!records.is_empty()).then(|| Member::of(EMBEDDINGS, &embeddings)),
+    };
+
+    // Written aside and renamed, so a failed pack never leaves a truncated archive
+    let parent = output
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let tmp = tempfile::NamedTempFile::new_in(parent)
+        .with_context(|| format!("Failed to create an archive in {}", parent.display()))?;
+    let mut tar = tar::Builder::new(GzEncoder::new(tmp.as_file(), Compression::default()));
+    let mut append = |path: &str, bytes: &[u8]| -> Result<()> {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(bytes.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(0);
+        tar.append_data(&mut header, path, bytes)
+            .with_context(|| format!("Failed to add {} to the archive", path))
+    };
+    append(MANIFEST, &serde_json::to_vec_pretty(&manifest)?)?;
+    if manifest.embeddings.is_some() {
+        append(EMBEDDINGS, &embeddings)?;
+    }
+    for (member, bytes) in manifest.files.iter().zip(&contents) {
+        append(&format!("{}/{}", FILES_DIR, member.path), bytes)?;
+    }
+    tar.into_inner()?.finish().context("Failed to write the archive")?;
+    tmp.persist(output)
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+
+    Ok(PackSummary {
+        archive: output.to_path_buf(),
+        files: manifest.files.len(),
+        embeddings: records.len(),
+        bytes: fs::metadata(output)?.len(),
+    })
+}
+
+/// A read and verified archive
+#[derive(Debug)]
+pub struct Archive {
+    pub manifest: Manifest,
+    files: Vec<(Member, Vec<u8>)>,
+    embeddings: Vec<EmbeddingRecord>,
+}
+
+fn invalid(archive: &Path, problem: impl std::fmt::Display) -> CliError {
+    CliError::new(
+        ErrorCode::Parse,
+        format!("{} is not a usable history archive: {}", archive.display(), problem),
+    )
+}
+
+impl Archive {
+    /// Read an archive, checking every member against the manifest
+    pub fn read(path: &Path) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        let mut members = BTreeMap::new();
+        let mut tar = tar::Archive::new(GzDecoder::new(file));
+        for entry in tar.entries().map_err(|e| invalid(path, e))? {
+            let mut entry = entry.map_err(|e| invalid(path, e))?;
+            let name = entry
+                .path()
+                .map_err(|e| invalid(path, e))?
+                .to_string_lossy()
+                .to_string();
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes).map_err(|e| invalid(path, e))?;
+            members.insert(name, bytes);
+        }
+
+        let manifest = members
+            .remove(MANIFEST)
+            .ok_or_else(|| invalid(path, "no manifest.json"))?;
+        let manifest: Manifest = serde_json::from_slice(&manifest).map_err(|e| invalid(path, e))?;
+        if manifest.format != FORMAT {
+            return Err(invalid(path, format!("unknown format '{}'", manifest.format)).into());
+        }
+        if manifest.version > VERSION {
+            return Err(invalid(
+                path,
+                format!(
+                    "format version {} is newer than this pais understands ({})",
+                    manifest.version, VERSION
+                ),
+            )
+            .with_hint(format!(
+                "It was packed by pais {}; upgrade to unpack it",
+                manifest.pais_version
+            ))
+            .into());
+        }
+
+        let mut files = Vec::new();
+        for member in &manifest.files {
+            if !is_safe_relative(Path::new(&member.path)) {
+                return Err(invalid(path, format!("unsafe path '{}'", member.path)).into());
+            }
+            let bytes = members
+                .remove(&format!("{}/{}", FILES_DIR, member.path))
+                .ok_or_else(|| invalid(path, format!("{} is missing", member.path)))?;
+            member.check(&bytes)?;
+            files.push((member.clone(), bytes));
+        }
+
+        let mut embeddings = Vec::new();
+        if let Some(member) = &manifest.embeddings {
+            let bytes = members
+                .remove(EMBEDDINGS)
+                .ok_or_else(|| invalid(path, "embeddings.jsonl is missing"))?;
+            member.check(&bytes)?;
+            for line in bytes.split(|b| *b == b'\n').filter(|l| !l.is_empty()) {
+                embeddings.push(serde_json::from_slice(line).map_err(|e| invalid(path, e))?);
+            }
+        }
+
+        Ok(Self {
+            manifest,
+            files,
+            embeddings,
+        })
+    }
+
+    /// Categories in the archive's schema that `declared` lacks
+    pub fn undeclared_categories(&self, declared: &Categories) -> Vec<&CategoryConfig> {
+        self.manifest
+            .categories
+            .iter()
+            .filter(|c| declared.get(&c.name).is_none())
+            .collect()
+    }
+
+    /// Write the archive's files under `history_dir`
+    ///
+    /// Files already there with the same content are skipped, and ones with
+    /// different content are reported as conflicts unless `force` overwrites
+    /// them. The index is then rebuilt and the embeddings re-attached.
+    pub fn unpack(&self, history_dir: &Path, force: bool, dry_run: bool) -> Result<UnpackSummary> {
+        let mut summary = UnpackSummary::default();
+        let _lock = (!dry_run).then(|| FileLock::acquire(history_dir)).transpose()?;
+
+        for (member, bytes) in &self.files {
+            let target = history_dir.join(&member.path);
+            match fs::read(&target) {
+                Ok(existing) if existing == *bytes => {
+                    summary.unchanged += 1;
+                    continue;
+                }
+                Ok(_) if !force => {
+                    summary.conflicts.push(member.path.clone());
+                    continue;
+                }
+                _ => {}
+            }
+            if !dry_run {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+                }
+                fs::write(&target, bytes).with_context(|| format!("Failed to write {}", target.display()))?;
+            }
+            summary.written.push(member.path.clone());
+        }
+
+        summary.embeddings = self.embeddings.len();
+        if dry_run || (summary.written.is_empty() && self.embeddings.is_empty()) {
+            return Ok(summary);
+        }
+
+        let index = HistoryIndex::new(history_dir);
+        index.rebuild()?;
+        for record in &self.embeddings {
+            let pending = PendingEmbedding {
+                path: history_dir.join(&record.path),
+                hash: record.hash.clone(),
+                text: String::new(),
+            };
+            // A vector whose text changed locally is recomputed by the next `history similar`
+            index.store_embedding(&pending, &record.model, &record.vector)?;
+        }
+        Ok(summary)
+    }
+}
+
+/// What `unpack` wrote (or would write)
+#[derive(Debug, Default, Serialize)]
+pub struct UnpackSummary {
+    pub written: Vec<String>,
+    pub unchanged: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<String>,
+    pub embeddings: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::{HistoryEntry, HistoryStore};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_pack_unpack_round_trip() {
+        let source = tempdir().unwrap();
+        let store = HistoryStore::new(source.path().to_path_buf()).with_index(true);
+        let entry = HistoryEntry::new("learnings", "Borrow checker", "Clone less");
+        let path = store.store(&entry).unwrap();
+        fs::create_dir_all(source.path().join("raw-events/2026-03")).unwrap();
+        fs::write(source.path().join("raw-events/2026-03/2026-03-01.jsonl"), "{}\n").unwrap();
+        fs::create_dir_all(source.path().join(".git")).unwrap();
+        fs::write(source.path().join(".git/HEAD"), "ref").unwrap();
+
+        let index = HistoryIndex::new(source.path());
+        let pending = index.pending_embeddings("test/model").unwrap();
+        index.store_embedding(&pending[0], "test/model", &[0.25, -1.5]).unwrap();
+
+        let out = tempdir().unwrap();
+        let archive_path = out.path().join("history.tar.gz");
+        let packed = pack(source.path(), &HistoryConfig::default(), &archive_path).unwrap();
+        assert_eq!(packed.files, 2);
+        assert_eq!(packed.embeddings, 1);
+
+        let target = tempdir().unwrap();
+        let archive = Archive::read(&archive_path).unwrap();
+        assert!(archive.undeclared_categories(&Categories::default()).is_empty());
+        let unpacked = archive.unpack(target.path(), false, false).unwrap();
+        assert_eq!(unpacked.written.len(), 2);
+
+        let rel = path.strip_prefix(source.path()).unwrap();
+        assert_eq!(fs::read(target.path().join(rel)).unwrap(), fs::read(&path).unwrap());
+        assert!(!target.path().join(".git").exists());
+        let restored = HistoryIndex::new(target.path()).embeddings().unwrap();
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[0].vector, [0.25, -1.5]);
+        assert!(
+            HistoryIndex::new(target.path())
+                .pending_embeddings("test/model")
+                .unwrap()
+                .is_empty()
+        );
+
+        // Unpacking again changes nothing; a local edit is a conflict unless forced
+        assert_eq!(archive.unpack(target.path(), false, false).unwrap().unchanged, 2);
+        fs::write(target.path().join(rel), "edited").unwrap();
+        assert_eq!(archive.unpack(target.path(), false, true).unwrap().conflicts.len(), 1);
+        assert_eq!(archive.unpack(target.path(), true, false).unwrap().written.len(), 1);
+    }
+
+    #[test]
+    fn test_corrupt_archive_is_refused() {
+        let temp = tempdir().unwrap();
+        HistoryStore::new(temp.path().join("history"))
+            .store(&HistoryEntry::new("sessions", "T", "Body"))
+            .unwrap();
+        let archive_path = temp.path().join("history.tar.gz");
+        pack(&temp.path().join("history"), &HistoryConfig::default(), &archive_path).unwrap();
+
+        // Swap one member's content for something the manifest doesn't vouch for
+        let mut members = Vec::new();
+        let mut tar = tar::Archive::new(GzDecoder::new(File::open(&archive_path).unwrap()));
+        for entry in tar.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let name = entry.path().unwrap().to_string_lossy().to_string();
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes).unwrap();
+            if name.starts_with(FILES_DIR) {
+                bytes = b"tampered".to_vec();
+            }
+            members.push((name, bytes));
+        }
+        let mut out = tar::Builder::new(GzEncoder::new(
+            File::create(&archive_path).unwrap(),
+            Compression::default(),
+        ));
+        for (name, bytes) in &members {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(bytes.len() as u64);
+            header.set_mode(0o644);
+            out.append_data(&mut header, name, bytes.as_slice()).unwrap();
+        }
+        out.into_inner().unwrap().finish().unwrap();
+
+        let err = Archive::read(&archive_path).unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"), "{}", err);
+    }
+}
@@ -0,0 +1,227 @@
+//! Session activity as calendar events
+//!
+//! Rebuilds sessions from captured raw events (SessionStart to SessionEnd,
+//! falling back to the first/last event seen) and renders them as an
+//! iCalendar (RFC 5545) feed, with the project directory as location.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::capture::CapturedEvent;
+
+/// One Claude session reconstructed from its events
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SessionSpan {
+    pub session_id: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Working directory the session ran in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    /// Number of hook events in the session
+    pub events: usize,
+}
+
+impl SessionSpan {
+    /// Last path component of the working directory
+    pub fn project(&self) -> Option<&str> {
+        self.cwd
+            .as_deref()
+            .map(|cwd| cwd.trim_end_matches('/').rsplit('/').next().unwrap_or(cwd))
+    }
+
+    pub fn duration_minutes(&self) -> i64 {
+        (self.end - self.start).num_minutes()
+    }
+}
+
+/// Group events into sessions, oldest first
+pub fn sessions<I>(events: I) -> Vec<SessionSpan>
+where
+    I: IntoIterator<Item = CapturedEvent>,
+{
+    let mut spans: BTreeMap<String, SessionSpan> = BTreeMap::new();
+    let mut started = BTreeSet::new();
+    let mut ended = BTreeSet::new();
+
+    for event in events {
+        let Some(session_id) = event.session_id.clone() else {
+            continue;
+        };
+        let cwd = event.payload.get("cwd").and_then(|v| v.as_str()).map(String::from);
+        let span = spans.entry(session_id.clone()).or_insert_with(|| SessionSpan {
+            session_id: session_id.clone(),
+            start: event.timestamp,
+            end: event.timestamp,
+            cwd: None,
+            events: 0,
+        });
+
+        span.events += 1;
+        if span.cwd.is_none() {
+            span.cwd = cwd;
+        }
+
+        match event.event.as_str() {
+            "SessionStart" => {
+                span.start = event.timestamp;
+                started.insert(session_id.clone());
+            }
+            "SessionEnd" => {
+                span.end = event.timestamp;
+                ended.insert(session_id.clone());
+            }
+            _ => {
+                // Without explicit markers, widen the span to cover every event
+                if !started.contains(&session_id) && event.timestamp < span.start {
+                    span.start = event.timestamp;
+                }
+                if !ended.contains(&session_id) && event.timestamp > span.end {
+                    span.end = event.timestamp;
+                }
+            }
+        }
+    }
+
+    let mut out: Vec<SessionSpan> = spans.into_values().collect();
+    out.sort_by_key(|s| s.start);
+    out
+}
+
+/// Render sessions as an iCalendar feed
+pub fn to_ics(spans: &[SessionSpan], now: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//PAIS//Session Activity//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+
+    for span in spans {
+        let summary = match span.project() {
+            Some(project) => format!("Claude session: {}", project),
+            None => "Claude session".to_string(),
+        };
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}@pais", escape(&span.session_id)));
+        lines.push(format!("DTSTAMP:{}", ics_time(now)));
+        lines.push(format!("DTSTART:{}", ics_time(span.start)));
+        // Zero-length events are dropped by some calendars
+        let end = if span.end > span.start {
+            span.end
+        } else {
+            span.start + chrono::Duration::minutes(1)
+        };
+        lines.push(format!("DTEND:{}", ics_time(end)));
+        lines.push(format!("SUMMARY:{}", escape(&summary)));
+        if let Some(ref cwd) = span.cwd {
+            lines.push(format!("LOCATION:{}", escape(cwd)));
+        }
+        lines.push(format!(
+            "DESCRIPTION:{}",
+            escape(&format!(
+                "Session {}\n{} min, {} hook events",
+                span.session_id,
+                span.duration_minutes(),
+                span.events
+            ))
+        ));
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(&fold(&line));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+fn ics_time(t: DateTime<Utc>) -> String {
+    t.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape text values (RFC 5545 section 3.3.11)
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Fold lines longer than 75 octets, without splitting characters
+fn fold(line: &str) -> String {
+    let mut out = String::new();
+    let mut len = 0;
+    for c in line.chars() {
+        if len + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            len = 1;
+        }
+        out.push(c);
+        len += c.len_utf8();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn event(name: &str, session: &str, minute: u32) -> CapturedEvent {
+        CapturedEvent {
+            timestamp: Utc.with_ymd_and_hms(2026, 3, 2, 9, minute, 0).unwrap(),
+            local_time: String::new(),
+            event: name.to_string(),
+            session_id: Some(session.to_string()),
+            payload: json!({"cwd": "/home/me/repos/pais"}),
+            duration_ms: None,
+        }
+    }
+
+    #[test]
+    fn test_sessions_from_events() {
+        let spans = sessions(vec![
+            event("PreToolUse", "b", 30),
+            event("SessionStart", "a", 0),
+            event("PreToolUse", "a", 10),
+            event("SessionEnd", "a", 20),
+            event("Stop", "b", 45),
+        ]);
+
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].session_id, "a");
+        assert_eq!(spans[0].duration_minutes(), 20);
+        assert_eq!(spans[0].events, 3);
+        assert_eq!(spans[0].project(), Some("pais"));
+
+        // No start/end markers: span covers the events seen
+        assert_eq!(spans[1].duration_minutes(), 15);
+    }
+
+    #[test]
+    fn test_ics_output() {
+        let spans = sessions(vec![event("SessionStart", "a", 0), event("SessionEnd", "a", 20)]);
+        let now = Utc.with_ymd_and_hms(2026, 3, 3, 0, 0, 0).unwrap();
+        let ics = to_ics(&spans, now);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains("DTSTART:20260302T090000Z\r\n"));
+        assert!(ics.contains("DTEND:20260302T092000Z\r\n"));
+        assert!(ics.contains("SUMMARY:Claude session: pais\r\n"));
+        assert!(ics.contains("LOCATION:/home/me/repos/pais\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn test_escape_and_fold() {
+        assert_eq!(escape("a,b;c\nd"), "a\\,b\\;c\\nd");
+        let folded = fold(&"x".repeat(100));
+        assert_eq!(folded.split("\r\n ").next().unwrap().len(), 75);
+    }
+}
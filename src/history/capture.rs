@@ -12,6 +12,8 @@ use std::fs::{self, OpenOptions};
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
+use super::categorize::Categories;
+use crate::clock;
 use crate::hook::HookEvent;
 
 /// A captured event
@@ -36,8 +38,8 @@ pub struct CapturedEvent {
 impl CapturedEvent {
     /// Create a new captured event
     pub fn new(event: HookEvent, payload: serde_json::Value) -> Self {
-        let now = Utc::now();
-        let local = Local::now();
+        let now = clock::now();
+        let local = now.with_timezone(&Local);
 
         // Extract session_id from payload if present
         let session_id = payload
@@ -77,7 +79,7 @@ impl EventCapture {
 
     /// Get the path to today's event log
     fn today_log_path(&self) -> PathBuf {
-        let now = Local::now();
+        let now = clock::now_local();
         let month_dir = self.base_path.join("raw-events").join(now.format("%Y-%m").to_string());
         month_dir.join(format!("{}.jsonl", now.format("%Y-%m-%d")))
     }
@@ -151,27 +153,6 @@ impl EventCapture {
 
         Ok(events)
     }
-
-    /// Get event counts by type for a date range
-    pub fn stats(&self, days: usize) -> Result<EventStats> {
-        let mut stats = EventStats::default();
-
-        let today = Local::now().date_naive();
-        for i in 0..days {
-            let date = today - chrono::Duration::days(i as i64);
-            let date_str = date.format("%Y-%m-%d").to_string();
-
-            if let Ok(events) = self.read_events(&date_str) {
-                for event in events {
-                    stats.total += 1;
-                    *stats.by_type.entry(event.event).or_insert(0) += 1;
-                }
-            }
-        }
-
-        Ok(stats)
-    }
-
     /// List available event log dates
     pub fn list_dates(&self) -> Result<Vec<String>> {
         let raw_events_dir = self.base_path.join("raw-events");
@@ -207,24 +188,9 @@ impl EventCapture {
     }
 }
 
-/// Event statistics
-#[derive(Debug, Default)]
-pub struct EventStats {
-    pub total: usize,
-    pub by_type: std::collections::HashMap<String, usize>,
-}
-
-/// Initialize the history directory structure
-pub fn init_history_dirs(history_path: &Path) -> Result<()> {
-    let dirs = [
-        "raw-events",
-        "sessions",
-        "learnings",
-        "research",
-        "decisions",
-        "execution",
-    ];
-
+/// Create the raw event directory and one directory per declared category
+pub fn init_history_dirs(history_path: &Path, categories: &Categories) -> Result<()> {
+    let dirs = std::iter::once("raw-events").chain(categories.iter().map(|c| c.name.as_str()));
     for dir in dirs {
         let path = history_path.join(dir);
         fs::create_dir_all(&path).with_context(|| format!("Failed to create history directory: {}", path.display()))?;
@@ -286,13 +252,14 @@ mod tests {
     #[test]
     fn test_init_history_dirs() {
         let temp = TempDir::new().unwrap();
-        init_history_dirs(temp.path()).unwrap();
+        init_history_dirs(temp.path(), &Categories::default()).unwrap();
 
         assert!(temp.path().join("raw-events").exists());
         assert!(temp.path().join("sessions").exists());
         assert!(temp.path().join("learnings").exists());
         assert!(temp.path().join("research").exists());
         assert!(temp.path().join("decisions").exists());
-        assert!(temp.path().join("execution").exists());
+        assert!(temp.path().join("events").exists());
+        assert!(!temp.path().join("execution").exists());
     }
 }
@@ -1,40 +1,39 @@
-//! Content categorization for history entries
+//! History categories and content routing
 //!
-//! Analyzes content to determine if it should be categorized as:
+//! Categories are declared in `history.categories`: a name (the directory
+//! under the history path), a description, an optional retention period,
+//! frontmatter added to each entry, an optional entry template and the
+//! rules that route captured content there: keywords, regex patterns and
+//! agent names. Content goes to the first category, in declared order, with
+//! enough keyword matches or a matching pattern, else to
+//! `history.default-category`. Stop events of an agent listed in a
+//! category's `route.agents` go there whatever their content.
+//!
+//! The built-in set:
+//! - `sessions`: Regular work sessions (the default)
 //! - `learnings`: Problem-solving narratives, debugging discoveries
-//! - `sessions`: Regular work sessions
-//! - `research`: Investigation reports
 //! - `decisions`: Architectural/design decisions
+//! - `research`: Investigation reports
+//! - `events`: Session start/end markers written by the history hook
+//!
+//! A configured list replaces the built-ins, except `events`: the history
+//! hook writes there, so it is added back when the list leaves it out.
+//!
+//! [`HistoryStore`](super::HistoryStore) refuses entries for undeclared
+//! categories, so hooks and agents can't scatter ad-hoc directories.
 
-/// Content category
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Category {
-    Sessions,
-    Learnings,
-    Research,
-    Decisions,
-    #[allow(dead_code)] // Future category for execution logs
-    Execution,
-}
+use regex::{Regex, RegexBuilder};
+use std::collections::HashSet;
 
-impl Category {
-    /// Get the directory name for this category
-    pub fn dir_name(&self) -> &'static str {
-        match self {
-            Category::Sessions => "sessions",
-            Category::Learnings => "learnings",
-            Category::Research => "research",
-            Category::Decisions => "decisions",
-            Category::Execution => "execution",
-        }
-    }
-}
+use super::{HistoryEntry, template};
+use crate::config::{CategoryConfig, CategoryRoute, HistoryConfig};
+use crate::error::{CliError, ErrorCode};
 
-impl std::fmt::Display for Category {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.dir_name())
-    }
-}
+/// Category the history hook writes session start and end markers to
+pub const EVENTS_CATEGORY: &str = "events";
+
+/// Directories under the history path that hold JSONL logs and indexes, not categories
+pub const RESERVED_DIRS: &[&str] = &["raw-events", "security", "index"];
 
 /// Learning indicator patterns
 const LEARNING_INDICATORS: &[&str] = &[
@@ -92,30 +91,191 @@ const DECISION_INDICATORS: &[&str] = &[
     "adopting",
 ];
 
-/// Analyze content and determine its category
-pub fn categorize_content(content: &str) -> Category {
-    let content_lower = content.to_lowercase();
+/// The categories `history.categories` starts with
+pub fn builtin_categories() -> Vec<CategoryConfig> {
+    let category = |name: &str, description: &str, keywords: &[&str]| CategoryConfig {
+        name: name.to_string(),
+        description: description.to_string(),
+        route: CategoryRoute {
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    vec![
+        category(
+            "learnings",
+            "Problem-solving narratives and debugging discoveries",
+            LEARNING_INDICATORS,
+        ),
+        category("decisions", "Architectural and design decisions", DECISION_INDICATORS),
+        category("research", "Investigation reports and comparisons", RESEARCH_INDICATORS),
+        category("sessions", "Regular work sessions", &[]),
+        CategoryConfig {
+            retention_days: Some(90),
+            ..category(EVENTS_CATEGORY, "Session start and end markers", &[])
+        },
+    ]
+}
+
+/// The declared categories
+#[derive(Debug, Clone)]
+pub struct Categories {
+    declared: Vec<CategoryConfig>,
+    /// Compiled `route.patterns`, one list per declared category
+    patterns: Vec<Vec<Regex>>,
+    default: String,
+}
+
+/// Compile a routing pattern, case-insensitively like keywords
+fn compile(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern).case_insensitive(true).build()
+}
 
-    // Count indicator matches
-    let learning_score = count_matches(&content_lower, LEARNING_INDICATORS);
-    let research_score = count_matches(&content_lower, RESEARCH_INDICATORS);
-    let decision_score = count_matches(&content_lower, DECISION_INDICATORS);
+impl Categories {
+    pub fn from_config(config: &HistoryConfig) -> Self {
+        let mut declared = config.categories.clone();
+        if !declared.iter().any(|c| c.name == EVENTS_CATEGORY) {
+            declared.extend(builtin_categories().into_iter().filter(|c| c.name == EVENTS_CATEGORY));
+        }
+        let patterns = declared
+            .iter()
+            .map(|category| {
+                // Broken patterns are reported by `problems` (and `pais doctor`)
+                category.route.patterns.iter().filter_map(|p| compile(p).ok()).collect()
+            })
+            .collect();
+        Self {
+            declared,
+            patterns,
+            default: config.default_category.clone(),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &CategoryConfig> {
+        self.declared.iter()
+    }
 
-    // Require at least 2 matches for specialized categories
-    if learning_score >= 2 {
-        return Category::Learnings;
+    pub fn get(&self, name: &str) -> Option<&CategoryConfig> {
+        self.declared.iter().find(|c| c.name == name)
     }
 
-    if decision_score >= 2 {
-        return Category::Decisions;
+    pub fn default_category(&self) -> &str {
+        &self.default
     }
 
-    if research_score >= 2 {
-        return Category::Research;
+    /// Category for `content`: the first whose keywords match often enough or whose patterns match
+    pub fn route(&self, content: &str) -> &str {
+        let content_lower = content.to_lowercase();
+        self.declared
+            .iter()
+            .zip(&self.patterns)
+            .find(|(c, patterns)| {
+                let keywords: Vec<&str> = c.route.keywords.iter().map(String::as_str).collect();
+                (!keywords.is_empty() && count_matches(&content_lower, &keywords) >= c.route.min_matches.max(1))
+                    || patterns.iter().any(|p| p.is_match(content))
+            })
+            .map_or(self.default.as_str(), |(c, _)| c.name.as_str())
     }
 
-    // Default to sessions
-    Category::Sessions
+    /// The first category listing `agent` in its `route.agents`
+    pub fn for_agent(&self, agent: &str) -> Option<&str> {
+        self.declared
+            .iter()
+            .find(|c| c.route.agents.iter().any(|a| a.eq_ignore_ascii_case(agent)))
+            .map(|c| c.name.as_str())
+    }
+
+    /// Category for an entry with an optional requested category (e.g. an
+    /// agent's `history_category`); undeclared requests fall back to routing
+    pub fn resolve(&self, requested: Option<&str>, content: &str) -> String {
+        match requested {
+            Some(name) if self.get(name).is_some() => name.to_string(),
+            Some(name) => {
+                log::warn!(
+                    "History category '{}' is not declared in history.categories; routing by content instead",
+                    name
+                );
+                self.route(content).to_string()
+            }
+            None => self.route(content).to_string(),
+        }
+    }
+
+    /// Error unless `name` is declared
+    pub fn check(&self, name: &str) -> Result<&CategoryConfig, CliError> {
+        self.get(name).ok_or_else(|| {
+            let names: Vec<&str> = self.declared.iter().map(|c| c.name.as_str()).collect();
+            CliError::new(
+                ErrorCode::Config,
+                format!(
+                    "History category '{}' is not declared (declared: {})",
+                    name,
+                    names.join(", ")
+                ),
+            )
+            .with_hint("Add it under history.categories in pais.yaml")
+        })
+    }
+
+    /// Mistakes in the declared schema
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let mut seen = HashSet::new();
+        for category in &self.declared {
+            let name = &category.name;
+            if name.is_empty() {
+                problems.push("a category has no name".to_string());
+                continue;
+            }
+            if !seen.insert(name.as_str()) {
+                problems.push(format!("category '{}' is declared more than once", name));
+            }
+            if RESERVED_DIRS.contains(&name.as_str()) || name.starts_with('.') || name.contains(['/', '\\']) {
+                problems.push(format!("'{}' can't be used as a category directory name", name));
+            }
+            if let Some(spec) = &category.template {
+                let sample = HistoryEntry::new(name, "Sample entry", "Sample content").with_tag("sample");
+                if let Err(e) = template::apply(spec, &sample) {
+                    problems.push(format!("category '{}' has a broken template: {:#}", name, e));
+                }
+            }
+            if !category.route.keywords.is_empty() && category.route.min_matches > category.route.keywords.len() {
+                problems.push(format!(
+                    "category '{}' needs {} keyword matches but has {} keyword(s), so nothing is routed to it",
+                    name,
+                    category.route.min_matches,
+                    category.route.keywords.len()
+                ));
+            }
+            for pattern in &category.route.patterns {
+                if let Err(e) = compile(pattern) {
+                    problems.push(format!("category '{}' has an invalid route pattern: {}", name, e));
+                }
+            }
+            for agent in &category.route.agents {
+                if self.for_agent(agent) != Some(name.as_str()) {
+                    problems.push(format!(
+                        "agent '{}' is routed to more than one category; '{}' is ignored",
+                        agent, name
+                    ));
+                }
+            }
+        }
+        if self.get(&self.default).is_none() {
+            problems.push(format!(
+                "default-category '{}' is not one of the declared categories",
+                self.default
+            ));
+        }
+        problems
+    }
+}
+
+impl Default for Categories {
+    fn default() -> Self {
+        Self::from_config(&HistoryConfig::default())
+    }
 }
 
 /// Count how many indicator patterns match in the content
@@ -207,29 +367,121 @@ fn truncate(s: &str, max_len: usize) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use indexmap::IndexMap;
 
     #[test]
     fn test_categorize_learning() {
         let content = "After debugging for an hour, I finally figured out the root cause. The problem was in the async handling. Fixed it by adding proper await.";
-        assert_eq!(categorize_content(content), Category::Learnings);
+        assert_eq!(Categories::default().route(content), "learnings");
     }
 
     #[test]
     fn test_categorize_research() {
         let content = "Investigating different approaches. Comparing options between A and B. The trade-offs are complex. Analysis shows option A is better.";
-        assert_eq!(categorize_content(content), Category::Research);
+        assert_eq!(Categories::default().route(content), "research");
     }
 
     #[test]
     fn test_categorize_decision() {
         let content = "Architecture decision: we're going with approach A. The design choice was influenced by scalability. Decided to use the pattern we discussed.";
-        assert_eq!(categorize_content(content), Category::Decisions);
+        assert_eq!(Categories::default().route(content), "decisions");
     }
 
     #[test]
     fn test_categorize_session() {
         let content = "Implemented the new feature. Added tests. Updated documentation.";
-        assert_eq!(categorize_content(content), Category::Sessions);
+        assert_eq!(Categories::default().route(content), "sessions");
+    }
+
+    #[test]
+    fn test_configured_categories() {
+        let config = HistoryConfig {
+            categories: vec![
+                CategoryConfig {
+                    name: "incidents".into(),
+                    frontmatter: IndexMap::from([("severity".to_string(), "unknown".to_string())]),
+                    route: CategoryRoute {
+                        keywords: vec!["outage".into(), "pager".into()],
+                        min_matches: 1,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                CategoryConfig {
+                    name: "notes".into(),
+                    ..Default::default()
+                },
+            ],
+            default_category: "notes".into(),
+            ..Default::default()
+        };
+        let categories = Categories::from_config(&config);
+        assert!(categories.problems().is_empty());
+        assert_eq!(categories.route("The OUTAGE started at noon"), "incidents");
+        assert_eq!(categories.route("Debugged the root cause and fixed it"), "notes");
+        assert_eq!(categories.resolve(Some("learnings"), "pager went off"), "incidents");
+        assert_eq!(categories.resolve(Some("notes"), "pager went off"), "notes");
+        assert!(categories.check("learnings").is_err());
+        assert_eq!(categories.get("incidents").unwrap().frontmatter["severity"], "unknown");
+
+        // The history hook's session markers still have a home, and nothing routes there
+        assert!(categories.check(EVENTS_CATEGORY).is_ok());
+        assert_eq!(categories.get(EVENTS_CATEGORY).unwrap().retention_days, Some(90));
+        assert_eq!(categories.route("Session started"), "notes");
+        let store_dir = tempfile::tempdir().unwrap();
+        let store = crate::history::HistoryStore::new(store_dir.path().to_path_buf()).with_categories(categories);
+        assert!(
+            store
+                .store(&HistoryEntry::new(EVENTS_CATEGORY, "Session started", ""))
+                .is_ok()
+        );
+
+        let broken = Categories::from_config(&HistoryConfig {
+            categories: vec![config.categories[0].clone(), config.categories[0].clone()],
+            default_category: "missing".into(),
+            ..Default::default()
+        });
+        assert_eq!(broken.problems().len(), 2);
+    }
+
+    #[test]
+    fn test_route_patterns_and_agents() {
+        let category = |name: &str, patterns: &[&str], agents: &[&str]| CategoryConfig {
+            name: name.into(),
+            route: CategoryRoute {
+                patterns: patterns.iter().map(|p| p.to_string()).collect(),
+                agents: agents.iter().map(|a| a.to_string()).collect(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let config = HistoryConfig {
+            categories: vec![
+                category("tickets", &[r"\b[A-Z]+-\d+\b"], &["triager"]),
+                category("research", &[], &["Explore", "researcher"]),
+                category("notes", &[], &[]),
+            ],
+            default_category: "notes".into(),
+            ..Default::default()
+        };
+        let categories = Categories::from_config(&config);
+        assert!(categories.problems().is_empty());
+        assert_eq!(categories.route("Closed ops-1234 after the deploy"), "tickets");
+        assert_eq!(categories.route("Nothing to see"), "notes");
+        assert_eq!(categories.for_agent("explore"), Some("research"));
+        assert_eq!(categories.for_agent("triager"), Some("tickets"));
+        assert_eq!(categories.for_agent("general-purpose"), None);
+
+        let broken = Categories::from_config(&HistoryConfig {
+            categories: vec![
+                category("tickets", &["(unclosed"], &["x"]),
+                category("notes", &[], &["X"]),
+            ],
+            default_category: "notes".into(),
+            ..Default::default()
+        });
+        assert_eq!(broken.problems().len(), 2);
+        assert_eq!(broken.route("(unclosed"), "notes");
     }
 
     #[test]
@@ -0,0 +1,229 @@
+//! Near-duplicate detection
+//!
+//! The Stop hook often captures nearly the same summary several times in a
+//! row. Entries are compared as sets of word shingles (every run of three
+//! consecutive words) by Jaccard similarity: the shared shingles over all
+//! shingles. At or above `history.dedupe.threshold`, a new capture is either
+//! merged into the most similar of the recent entries in its category (its
+//! tags are added there and `duplicates:` counts it) or skipped.
+//!
+//! `pais history dedupe` runs the same comparison over entries already stored.
+
+use eyre::{Context, Result};
+use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use super::review::set_frontmatter;
+use super::{HistoryEntry, HistoryStore};
+use crate::config::{DedupeAction, HistoryDedupeConfig};
+
+/// Words per shingle
+const SHINGLE_WORDS: usize = 3;
+
+/// Session start/end markers, alike by design: `pais history dedupe` leaves them
+/// alone unless asked for by name
+pub const SKIPPED_CATEGORIES: &[&str] = &["events"];
+
+/// Frontmatter key counting the captures merged into an entry
+pub const DUPLICATES_KEY: &str = "duplicates";
+
+/// Hashed word shingles of a text, lowercased and ignoring punctuation
+pub fn shingles(text: &str) -> HashSet<u64> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    // Texts shorter than a shingle are compared as one
+    let width = SHINGLE_WORDS.min(words.len().max(1));
+    words
+        .windows(width)
+        .map(|window| {
+            let mut hasher = DefaultHasher::new();
+            window.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect()
+}
+
+/// Jaccard similarity of two shingle sets (0 when both are empty)
+pub fn similarity(a: &HashSet<u64>, b: &HashSet<u64>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+fn entry_shingles(entry: &HistoryEntry) -> HashSet<u64> {
+    shingles(&format!("{}\n{}", entry.title, entry.content))
+}
+
+/// Near-duplicate check applied by [`HistoryStore::store`]
+#[derive(Debug, Clone)]
+pub struct Dedupe {
+    pub threshold: f64,
+    pub window: usize,
+    pub action: DedupeAction,
+}
+
+impl Dedupe {
+    /// `None` when deduplication is disabled
+    pub fn from_config(config: &HistoryDedupeConfig) -> Option<Self> {
+        config.enabled.then_some(Self {
+            threshold: config.threshold,
+            window: config.window,
+            action: config.action,
+        })
+    }
+
+    /// The recent entry in `entry`'s category it most resembles, if that is a duplicate
+    pub fn find(&self, store: &HistoryStore, entry: &HistoryEntry) -> Result<Option<(PathBuf, f64)>> {
+        let new = entry_shingles(entry);
+        let mut best: Option<(PathBuf, f64)> = None;
+        for path in store.paths(Some(&entry.category), None)?.take(self.window) {
+            let path = path?;
+            let existing = HistoryEntry::from_markdown(&store.read(&path)?, &path)?;
+            // Storing an entry again under its own ID replaces it
+            if existing.id == entry.id {
+                continue;
+            }
+            let score = similarity(&new, &entry_shingles(&existing));
+            if score >= self.threshold && best.as_ref().is_none_or(|(_, b)| score > *b) {
+                best = Some((path, score));
+            }
+        }
+        Ok(best)
+    }
+}
+
+/// Fold a duplicate into the entry at `path`: add its tags and count it
+pub fn fold(store: &HistoryStore, path: &Path, duplicate: &HistoryEntry) -> Result<HistoryEntry> {
+    // Frontmatter stays plain in encrypted entries, so edit the file as stored
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let entry = HistoryEntry::from_markdown(&content, path)?;
+
+    let mut tags = entry.tags.clone();
+    for tag in &duplicate.tags {
+        if !tags.contains(tag) {
+            tags.push(tag.clone());
+        }
+    }
+    let count = |e: &HistoryEntry| {
+        e.metadata
+            .get(DUPLICATES_KEY)
+            .and_then(|n| n.parse::<u32>().ok())
+            .unwrap_or(0)
+    };
+    let duplicates = count(&entry) + count(duplicate) + 1;
+
+    let mut content = set_frontmatter(&content, DUPLICATES_KEY, &duplicates.to_string());
+    if !tags.is_empty() {
+        content = set_frontmatter(&content, "tags", &format!("[{}]", tags.join(", ")));
+    }
+    store.write(path, &content)?;
+    HistoryEntry::from_markdown(&content, path)
+}
+
+/// A stored entry that nearly duplicates an older one
+pub struct Duplicate {
+    /// The older entry it would be merged into
+    pub kept: PathBuf,
+    pub kept_id: String,
+    pub path: PathBuf,
+    pub entry: HistoryEntry,
+    pub similarity: f64,
+}
+
+/// Near-duplicates in one category, each compared with the `window` entries kept before it
+pub fn scan(store: &HistoryStore, category: &str, threshold: f64, window: usize) -> Result<Vec<Duplicate>> {
+    let mut paths = store.paths(Some(category), None)?.collect::<Result<Vec<_>>>()?;
+    paths.reverse();
+
+    let mut kept: Vec<(PathBuf, String, HashSet<u64>)> = Vec::new();
+    let mut duplicates = Vec::new();
+    for path in paths {
+        let entry = HistoryEntry::from_markdown(&store.read(&path)?, &path)?;
+        let set = entry_shingles(&entry);
+        let best = kept
+            .iter()
+            .rev()
+            .take(window)
+            .map(|(kept_path, id, kept_set)| (kept_path, id, similarity(&set, kept_set)))
+            .filter(|(_, _, score)| *score >= threshold)
+            .max_by(|a, b| a.2.total_cmp(&b.2));
+        match best {
+            Some((kept_path, id, score)) => duplicates.push(Duplicate {
+                kept: kept_path.clone(),
+                kept_id: id.clone(),
+                path,
+                entry,
+                similarity: score,
+            }),
+            None => kept.push((path, entry.id, set)),
+        }
+    }
+    Ok(duplicates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const SUMMARY: &str = "Fixed the flaky login test by waiting for the session cookie before redirecting";
+
+    #[test]
+    fn test_similarity() {
+        let a = shingles(SUMMARY);
+        let b = shingles(&format!("{}.", SUMMARY.to_uppercase()));
+        assert_eq!(similarity(&a, &b), 1.0);
+
+        let c = shingles(&SUMMARY.replace("login", "signup"));
+        let score = similarity(&a, &c);
+        assert!(score > 0.5 && score < 1.0, "{}", score);
+
+        assert_eq!(similarity(&a, &shingles("Something else entirely")), 0.0);
+        assert_eq!(similarity(&shingles("ok"), &shingles("OK!")), 1.0);
+        assert_eq!(similarity(&shingles(""), &shingles("")), 0.0);
+    }
+
+    #[test]
+    fn test_store_merges_and_scan_finds_duplicates() {
+        let temp = tempdir().unwrap();
+        let plain = HistoryStore::new(temp.path().to_path_buf());
+        let first = HistoryEntry::new("sessions", "Login fix", SUMMARY).with_tag("a");
+        let first_path = plain.store(&first).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let second = HistoryEntry::new("sessions", "Login fix", SUMMARY).with_tag("b");
+        plain.store(&second).unwrap();
+
+        let found = scan(&plain, "sessions", 0.8, 20).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].kept_id, first.id);
+        assert_eq!(found[0].entry.id, second.id);
+
+        let dedupe = Dedupe {
+            threshold: 0.8,
+            window: 20,
+            action: DedupeAction::Merge,
+        };
+        let store = HistoryStore::new(temp.path().to_path_buf()).with_dedupe(Some(dedupe));
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let third = HistoryEntry::new("sessions", "Login fix", SUMMARY).with_tag("c");
+        let merged_into = store.store(&third).unwrap();
+        assert_ne!(merged_into, first_path);
+        assert!(store.get(&third.id).unwrap().is_none());
+
+        let merged = store.get(&second.id).unwrap().unwrap();
+        assert_eq!(merged.tags, ["b", "c"]);
+        assert_eq!(merged.metadata[DUPLICATES_KEY], "1");
+
+        // Different content is stored as usual
+        let other = HistoryEntry::new("sessions", "Other", "Upgraded the build to the new toolchain");
+        assert!(store.store(&other).unwrap().ends_with(format!("{}.md", other.id)));
+    }
+}
@@ -0,0 +1,219 @@
+//! Embeddings for semantic history search
+//!
+//! `pais history similar` ranks entries by how close their meaning is to a
+//! query rather than by shared words. Each entry's title, tags and body are
+//! turned into a vector by the configured provider (`history.embeddings`):
+//! a local ollama server by default, or the OpenAI or Gemini API. Vectors are
+//! kept in the history index next to a hash of the text they came from, so
+//! only new or changed entries are sent again, and switching models starts a
+//! fresh set. Requests go through [`crate::net`] with the `network` timeouts,
+//! so a hung server fails the command instead of stalling it.
+
+use eyre::Result;
+use serde_json::{Value, json};
+
+use crate::config::{EmbeddingProvider, HistoryEmbeddingsConfig, NetworkConfig};
+use crate::error::{CliError, ErrorCode};
+use crate::net;
+use crate::secrets::SecretStore;
+
+const OPENAI_API: &str = "https://api.openai.com/v1/embeddings";
+const GEMINI_API: &str = "https://generativelanguage.googleapis.com/v1beta/models";
+
+/// Characters of an entry sent for embedding; longer bodies are cut
+const MAX_CHARS: usize = 8000;
+
+/// Texts sent per request
+pub const BATCH_SIZE: usize = 32;
+
+/// A configured embedding model
+#[derive(Debug, Clone)]
+pub struct Embedder {
+    provider: EmbeddingProvider,
+    model: String,
+    ollama_url: String,
+    api_key: Option<String>,
+    network: NetworkConfig,
+}
+
+impl Embedder {
+    pub fn new(config: &HistoryEmbeddingsConfig, network: &NetworkConfig, secrets: &SecretStore) -> Result<Self> {
+        let default_model = match config.provider {
+            EmbeddingProvider::Ollama => "nomic-embed-text",
+            EmbeddingProvider::OpenAi => "text-embedding-3-small",
+            EmbeddingProvider::Gemini => "text-embedding-004",
+        };
+        let default_secret = match config.provider {
+            EmbeddingProvider::Ollama => None,
+            EmbeddingProvider::OpenAi => Some("OPENAI_API_KEY"),
+            EmbeddingProvider::Gemini => Some("GOOGLE_API_KEY"),
+        };
+        let api_key = match config.api_key_secret.as_deref().or(default_secret) {
+            Some(secret) if config.provider != EmbeddingProvider::Ollama => Some(secrets.require(secret)?),
+            _ => None,
+        };
+        Ok(Self {
+            provider: config.provider,
+            model: config.model.clone().unwrap_or_else(|| default_model.to_string()),
+            ollama_url: config.ollama_url.trim_end_matches('/').to_string(),
+            api_key,
+            network: network.clone(),
+        })
+    }
+
+    /// Provider and model, as recorded with each vector
+    pub fn id(&self) -> String {
+        let provider = match self.provider {
+            EmbeddingProvider::Ollama => "ollama",
+            EmbeddingProvider::OpenAi => "openai",
+            EmbeddingProvider::Gemini => "gemini",
+        };
+        format!("{}/{}", provider, self.model)
+    }
+
+    /// One vector per text, in order
+    pub fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let key = self.api_key.as_deref().unwrap_or_default();
+        let vectors = match self.provider {
+            EmbeddingProvider::Ollama => {
+                let url = format!("{}/api/embed", self.ollama_url);
+                let body = json!({ "model": self.model, "input": texts });
+                let response = self.post(&url, &[], &body, "ollama embeddings").map_err(|e| {
+                    CliError::new(ErrorCode::Config, format!("Failed to reach ollama at {}: {:#}", url, e))
+                        .with_hint(format!("Start it with `ollama serve` and `ollama pull {}`", self.model))
+                })?;
+                vectors(&response["embeddings"], |v| v)
+            }
+            EmbeddingProvider::OpenAi => {
+                let auth = ("Authorization", format!("Bearer {}", key));
+                let body = json!({ "model": self.model, "input": texts });
+                let response = self.post(OPENAI_API, &[auth], &body, "OpenAI embeddings")?;
+                vectors(&response["data"], |item| &item["embedding"])
+            }
+            EmbeddingProvider::Gemini => {
+                let url = format!("{}/{}:batchEmbedContents", GEMINI_API, self.model);
+                let model = format!("models/{}", self.model);
+                let requests: Vec<Value> = texts
+                    .iter()
+                    .map(|text| json!({ "model": model, "content": { "parts": [{ "text": text }] } }))
+                    .collect();
+                let response = self.post(
+                    &url,
+                    &[("x-goog-api-key", key.to_string())],
+                    &json!({ "requests": requests }),
+                    "Gemini embeddings",
+                )?;
+                vectors(&response["embeddings"], |item| &item["values"])
+            }
+        };
+        match vectors {
+            Some(vectors) if vectors.len() == texts.len() => Ok(vectors),
+            _ => eyre::bail!("{} returned no embeddings for {} texts", self.id(), texts.len()),
+        }
+    }
+
+    fn post(&self, url: &str, headers: &[(&str, String)], body: &Value, what: &str) -> Result<Value> {
+        // A client per call: pooled connections don't outlive block_on's runtime
+        let client = net::client(&self.network)?;
+        let mut request = client.post(url).json(body);
+        for (name, value) in headers {
+            request = request.header(*name, value);
+        }
+        net::block_on(net::json(request, what))
+    }
+}
+
+/// Parse an array of vectors, each found in an item by `vector`
+fn vectors<'a>(items: &'a Value, vector: impl Fn(&'a Value) -> &'a Value) -> Option<Vec<Vec<f32>>> {
+    items
+        .as_array()?
+        .iter()
+        .map(|item| {
+            vector(item)
+                .as_array()?
+                .iter()
+                .map(|x| x.as_f64().map(|x| x as f32))
+                .collect()
+        })
+        .collect()
+}
+
+/// What gets embedded for an entry
+pub fn entry_text(title: &str, tags: &[String], content: &str) -> String {
+    let text = format!("{}\n{}\n\n{}", title, tags.join(", "), content);
+    match text.char_indices().nth(MAX_CHARS) {
+        Some((end, _)) => text[..end].to_string(),
+        None => text,
+    }
+}
+
+/// Cosine similarity, 0 when either vector is all zeros or they differ in length
+pub fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// Little-endian f32s, as stored in the index
+pub fn to_blob(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+pub fn from_blob(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cosine() {
+        assert!((cosine(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine(&[1.0, 0.0], &[0.0, 3.0]).abs() < 1e-6);
+        assert!((cosine(&[1.0, 1.0], &[-1.0, -1.0]) + 1.0).abs() < 1e-6);
+        assert_eq!(cosine(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(cosine(&[1.0], &[1.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn test_blob_round_trip() {
+        let vector = vec![0.25, -1.5, 3.0e-8];
+        assert_eq!(from_blob(&to_blob(&vector)), vector);
+    }
+
+    #[test]
+    fn test_parse_provider_responses() {
+        let ollama = json!({ "embeddings": [[0.1, 0.2], [0.3, 0.4]] });
+        assert_eq!(vectors(&ollama["embeddings"], |v| v).unwrap()[1], vec![0.3, 0.4]);
+
+        let openai = json!({ "data": [{ "embedding": [1.0, 2.0] }] });
+        assert_eq!(
+            vectors(&openai["data"], |i| &i["embedding"]).unwrap(),
+            vec![vec![1.0, 2.0]]
+        );
+
+        let gemini = json!({ "embeddings": [{ "values": [0.5] }] });
+        assert_eq!(
+            vectors(&gemini["embeddings"], |i| &i["values"]).unwrap(),
+            vec![vec![0.5]]
+        );
+
+        assert!(vectors(&json!({ "error": "bad" })["data"], |v| v).is_none());
+    }
+}
@@ -0,0 +1,533 @@
+//! Encryption of history entries at rest
+//!
+//! With `history.encryption.enabled`, the body of each new entry is replaced
+//! by an armored block before it is written. The frontmatter and the title
+//! heading stay readable, so listing, filtering by category or date and the
+//! review queue work without a key; the body is decrypted when an entry is
+//! read through [`super::HistoryStore`].
+//!
+//! Two methods:
+//!
+//! - `key`: ChaCha20-Poly1305 with a key derived (PBKDF2-SHA256) from the
+//!   passphrase in the `key-secret` secret (environment or `.env`, see
+//!   [`crate::secrets`]) and a random per-install salt kept in
+//!   `.encryption-salt` next to the history. Each block names its salt in a
+//!   `Salt:` header, so entries synced from another machine still open
+//! - `age`: the `age` CLI encrypts to `recipients` and decrypts with the
+//!   `identity` file
+//!
+//! Either kind of block is decrypted whatever the configured method, so
+//! switching methods keeps older entries readable. The search index never
+//! sees encrypted bodies (see [`super::index`]).
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use eyre::{Context, Result};
+use ring::aead::{Aad, CHACHA20_POLY1305, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::config::{Config, EncryptionMethod, HistoryEncryptionConfig};
+use crate::error::{CliError, ErrorCode};
+use crate::lock::FileLock;
+use crate::secrets::SecretStore;
+
+const KEY_BEGIN: &str = "-----BEGIN PAIS ENCRYPTED BODY-----";
+const KEY_END: &str = "-----END PAIS ENCRYPTED BODY-----";
+const AGE_BEGIN: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+const AGE_END: &str = "-----END AGE ENCRYPTED FILE-----";
+
+const SALT_LEN: usize = 16;
+const SALT_HEADER: &str = "Salt: ";
+const KDF_ITERATIONS: u32 = 100_000;
+
+/// Per-install salt file, in the history directory
+pub const SALT_FILE: &str = ".encryption-salt";
+
+/// Base64 line width inside a block
+const LINE_WIDTH: usize = 64;
+
+/// Whether the body of an entry file (or a bare body) is one encrypted block
+///
+/// A body that merely quotes a marker is plaintext, and gets sealed.
+pub fn is_sealed(text: &str) -> bool {
+    let (_, body) = split_body(text);
+    sealed_block(body).is_some()
+}
+
+/// Seals and opens entry bodies
+#[derive(Debug, Clone)]
+pub struct Encryption {
+    enabled: bool,
+    method: EncryptionMethod,
+    key_secret: String,
+    recipients: Vec<String>,
+    identity: PathBuf,
+    /// Where the per-install salt lives; without one, a random salt per process
+    salt_file: Option<PathBuf>,
+    /// Salt new blocks are sealed with, loaded on first use
+    salt: Arc<OnceLock<Vec<u8>>>,
+    /// Read from the secret store on first use; shared by clones
+    passphrase: Arc<OnceLock<String>>,
+    iterations: u32,
+    /// Keys derived so far, by salt; shared by clones
+    keys: Arc<Mutex<HashMap<Vec<u8>, [u8; 32]>>>,
+}
+
+impl Encryption {
+    pub fn from_config(config: &HistoryEncryptionConfig) -> Self {
+        Self {
+            enabled: config.enabled,
+            method: config.method,
+            key_secret: config.key_secret.clone(),
+            recipients: config.recipients.clone(),
+            identity: Config::expand_path(Path::new(&config.identity)),
+            salt_file: None,
+            salt: Arc::new(OnceLock::new()),
+            passphrase: Arc::new(OnceLock::new()),
+            iterations: KDF_ITERATIONS,
+            keys: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Seal with the salt in `path`, created on first use
+    pub fn with_salt_file(mut self, path: PathBuf) -> Self {
+        if self.salt_file.as_ref() != Some(&path) {
+            self.salt_file = Some(path);
+            self.salt = Arc::new(OnceLock::new());
+        }
+        self
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Encrypt the body of an entry file (everything after the frontmatter
+    /// and title heading); already sealed or empty bodies are left alone
+    pub fn seal_markdown(&self, markdown: &str) -> Result<String> {
+        let (head, body) = split_body(markdown);
+        if body.trim().is_empty() || sealed_block(body).is_some() {
+            return Ok(markdown.to_string());
+        }
+        let block = match self.method {
+            EncryptionMethod::Key => self.seal_key(body.trim())?,
+            EncryptionMethod::Age => self.seal_age(body.trim())?,
+        };
+        if head.trim().is_empty() {
+            return Ok(format!("{}\n", block));
+        }
+        Ok(format!("{}\n\n{}\n", head.trim_end(), block))
+    }
+
+    /// Replace every encrypted block with its plaintext
+    ///
+    /// One pass over `markdown`: decrypted text is never searched for blocks,
+    /// and markers that don't open a well-formed block are left as they are.
+    pub fn open_markdown(&self, markdown: &str) -> Result<String> {
+        let mut text = String::with_capacity(markdown.len());
+        let mut rest = markdown;
+        while let Some((start, end, age)) = find_block(rest) {
+            let block = &rest[start..end];
+            text.push_str(&rest[..start]);
+            if sealed_block(block).is_none() {
+                // Keep the marker and look for blocks after it
+                let marker = if age { AGE_BEGIN.len() } else { KEY_BEGIN.len() };
+                text.push_str(&rest[start..start + marker]);
+                rest = &rest[start + marker..];
+                continue;
+            }
+            let plain = if age { self.open_age(block)? } else { self.open_key(block)? };
+            text.push_str(plain.trim_end());
+            rest = &rest[end..];
+        }
+        text.push_str(rest);
+        Ok(text)
+    }
+
+    /// The salt new blocks are sealed with
+    fn install_salt(&self) -> Result<Vec<u8>> {
+        if let Some(salt) = self.salt.get() {
+            return Ok(salt.clone());
+        }
+        let salt = match &self.salt_file {
+            Some(path) => load_or_create_salt(path)?,
+            None => random_salt()?,
+        };
+        Ok(self.salt.get_or_init(|| salt).clone())
+    }
+
+    fn cipher(&self, salt: &[u8]) -> Result<LessSafeKey> {
+        let mut keys = self.keys.lock().unwrap_or_else(|e| e.into_inner());
+        let key = match keys.get(salt) {
+            Some(key) => *key,
+            None => {
+                let passphrase = match self.passphrase.get() {
+                    Some(passphrase) => passphrase,
+                    None => {
+                        let passphrase = SecretStore::load().require(&self.key_secret)?;
+                        self.passphrase.get_or_init(|| passphrase)
+                    }
+                };
+                let mut key = [0u8; 32];
+                pbkdf2::derive(
+                    pbkdf2::PBKDF2_HMAC_SHA256,
+                    NonZeroU32::new(self.iterations).expect("non-zero iterations"),
+                    salt,
+                    passphrase.as_bytes(),
+                    &mut key,
+                );
+                *keys.entry(salt.to_vec()).or_insert(key)
+            }
+        };
+        let unbound = UnboundKey::new(&CHACHA20_POLY1305, &key).map_err(|_| eyre::eyre!("Invalid key length"))?;
+        Ok(LessSafeKey::new(unbound))
+    }
+
+    fn seal_key(&self, body: &str) -> Result<String> {
+        let salt = self.install_salt()?;
+        let cipher = self.cipher(&salt)?;
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| eyre::eyre!("No randomness available for a nonce"))?;
+        let mut data = body.as_bytes().to_vec();
+        cipher
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+            .map_err(|_| eyre::eyre!("Failed to encrypt history entry"))?;
+
+        let mut payload = nonce.to_vec();
+        payload.extend(data);
+        let encoded = STANDARD.encode(payload);
+        let mut lines = vec![
+            KEY_BEGIN.to_string(),
+            format!("{}{}", SALT_HEADER, STANDARD.encode(&salt)),
+        ];
+        lines.extend(
+            encoded
+                .as_bytes()
+                .chunks(LINE_WIDTH)
+                .map(|chunk| String::from_utf8_lossy(chunk).into_owned()),
+        );
+        lines.push(KEY_END.to_string());
+        Ok(lines.join("\n"))
+    }
+
+    fn open_key(&self, block: &str) -> Result<String> {
+        let inner = block
+            .trim()
+            .trim_start_matches(KEY_BEGIN)
+            .trim_end_matches(KEY_END)
+            .trim();
+        let (salt, encoded) = inner
+            .strip_prefix(SALT_HEADER)
+            .and_then(|rest| rest.split_once('\n'))
+            .ok_or_else(|| eyre::eyre!("Encrypted history entry has no salt"))?;
+        let salt = STANDARD
+            .decode(salt.trim())
+            .context("Corrupt salt in encrypted history entry")?;
+        let encoded: String = encoded.split_whitespace().collect();
+        let mut payload = STANDARD.decode(encoded).context("Corrupt encrypted history entry")?;
+        if payload.len() < NONCE_LEN {
+            eyre::bail!("Corrupt encrypted history entry");
+        }
+        let mut data = payload.split_off(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(&payload).map_err(|_| eyre::eyre!("Corrupt nonce"))?;
+        let plain = self
+            .cipher(&salt)?
+            .open_in_place(nonce, Aad::empty(), &mut data)
+            .map_err(|_| {
+                CliError::new(ErrorCode::Config, "Failed to decrypt history entry").with_hint(format!(
+                    "Check that {} holds the passphrase it was written with",
+                    self.key_secret
+                ))
+            })?;
+        String::from_utf8(plain.to_vec()).context("Decrypted history entry is not UTF-8")
+    }
+
+    fn seal_age(&self, body: &str) -> Result<String> {
+        if self.recipients.is_empty() {
+            return Err(
+                CliError::new(ErrorCode::Config, "No age recipients to encrypt history to")
+                    .with_hint("Add your public key to history.encryption.recipients")
+                    .into(),
+            );
+        }
+        let mut args = vec!["--armor".to_string()];
+        for recipient in &self.recipients {
+            args.push("--recipient".into());
+            args.push(recipient.clone());
+        }
+        run_age(&args, body)
+    }
+
+    fn open_age(&self, block: &str) -> Result<String> {
+        if !self.identity.is_file() {
+            return Err(CliError::new(
+                ErrorCode::Config,
+                format!("age identity {} not found", self.identity.display()),
+            )
+            .with_hint("Set history.encryption.identity to the key file entries were encrypted for")
+            .into());
+        }
+        let args = vec![
+            "--decrypt".to_string(),
+            "--identity".to_string(),
+            self.identity.display().to_string(),
+        ];
+        run_age(&args, block)
+    }
+}
+
+fn random_salt() -> Result<Vec<u8>> {
+    let mut salt = vec![0u8; SALT_LEN];
+    SystemRandom::new()
+        .fill(&mut salt)
+        .map_err(|_| eyre::eyre!("No randomness available for a salt"))?;
+    Ok(salt)
+}
+
+/// The salt in `path`, written with a fresh one if there is none yet
+fn load_or_create_salt(path: &Path) -> Result<Vec<u8>> {
+    let _lock = FileLock::acquire(path)?;
+    if path.exists() {
+        let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        return STANDARD
+            .decode(content.trim())
+            .with_context(|| format!("Corrupt salt file {}", path.display()));
+    }
+    let salt = random_salt()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(path, format!("{}\n", STANDARD.encode(&salt)))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(salt)
+}
+
+/// Pipe `input` through `age`
+fn run_age(args: &[String], input: &str) -> Result<String> {
+    let mut command = Command::new("age");
+    command.args(args);
+    pipe(command, input)
+}
+
+/// Run `command` with `input` on stdin, returning its stdout
+///
+/// stdin is written on a thread: the command streams its output, so writing
+/// all of a large input before reading any would fill both pipes and hang.
+fn pipe(mut command: Command, input: &str) -> Result<String> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            CliError::new(ErrorCode::Config, format!("Failed to run {}: {}", program, e))
+                .with_hint("Install age (https://age-encryption.org) or use history.encryption.method: key")
+        })?;
+    let writer = child.stdin.take().map(|mut stdin| {
+        let input = input.to_string();
+        std::thread::spawn(move || stdin.write_all(input.as_bytes()))
+    });
+    let output = child
+        .wait_with_output()
+        .with_context(|| format!("Failed to run {}", program))?;
+    if !output.status.success() {
+        eyre::bail!("{} failed: {}", program, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    if let Some(writer) = writer {
+        writer
+            .join()
+            .map_err(|_| eyre::eyre!("Writing to {} panicked", program))?
+            .with_context(|| format!("Failed to write to {}", program))?;
+    }
+    String::from_utf8(output.stdout).with_context(|| format!("{} output is not UTF-8", program))
+}
+
+/// Split an entry file into its frontmatter plus title heading, and the body
+fn split_body(markdown: &str) -> (&str, &str) {
+    let mut offset = 0;
+    if let Some(rest) = markdown.strip_prefix("---\n")
+        && let Some(end) = rest.find("\n---\n")
+    {
+        offset = 4 + end + 5;
+    }
+    let rest = &markdown[offset..];
+    let blank = rest.len() - rest.trim_start_matches('\n').len();
+    if rest[blank..].starts_with("# ") {
+        offset += blank + rest[blank..].find('\n').map(|i| i + 1).unwrap_or(rest.len() - blank);
+    }
+    markdown.split_at(offset)
+}
+
+/// Whether `text`, trimmed, is exactly one encrypted block: `Some(true)` for
+/// age's, `Some(false)` for a key block
+fn sealed_block(text: &str) -> Option<bool> {
+    let text = text.trim();
+    let base64 = |s: &str| {
+        s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '=') || c.is_ascii_whitespace())
+    };
+    let age = text.strip_prefix(AGE_BEGIN).and_then(|rest| rest.strip_suffix(AGE_END));
+    if let Some(inner) = age {
+        return base64(inner).then_some(true);
+    }
+    let inner = text.strip_prefix(KEY_BEGIN)?.strip_suffix(KEY_END)?;
+    let (salt, payload) = inner.trim_start().strip_prefix(SALT_HEADER)?.split_once('\n')?;
+    (base64(salt) && base64(payload) && !payload.trim().is_empty()).then_some(false)
+}
+
+/// Byte range of the first encrypted block, and whether it is age's
+fn find_block(text: &str) -> Option<(usize, usize, bool)> {
+    [(KEY_BEGIN, KEY_END, false), (AGE_BEGIN, AGE_END, true)]
+        .into_iter()
+        .filter_map(|(begin, end, age)| {
+            let start = text.find(begin)?;
+            let stop = text[start..].find(end)? + start + end.len();
+            Some((start, stop, age))
+        })
+        .min_by_key(|(start, _, _)| *start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_passphrase(passphrase: &str) -> Encryption {
+        let mut encryption = Encryption::from_config(&HistoryEncryptionConfig {
+            enabled: true,
+            ..Default::default()
+        });
+        encryption.passphrase.set(passphrase.to_string()).unwrap();
+        encryption.iterations = 1;
+        encryption
+    }
+
+    #[test]
+    fn test_seal_and_open_body() {
+        let encryption = with_passphrase("correct horse");
+        let markdown = "---\nid: a0\ntitle: Deploy\n---\n\n# Deploy\n\nThe staging password is hunter2.\n";
+
+        let sealed = encryption.seal_markdown(markdown).unwrap();
+        assert!(is_sealed(&sealed));
+        assert!(sealed.starts_with("---\nid: a0\ntitle: Deploy\n---\n\n# Deploy\n\n-----BEGIN PAIS"));
+        assert!(!sealed.contains("hunter2"));
+        // Sealing twice is a no-op
+        assert_eq!(encryption.seal_markdown(&sealed).unwrap(), sealed);
+
+        assert_eq!(encryption.open_markdown(&sealed).unwrap(), markdown);
+
+        let err = with_passphrase("wrong").open_markdown(&sealed).unwrap_err();
+        assert!(err.to_string().contains("Failed to decrypt"));
+    }
+
+    #[test]
+    fn test_quoted_markers_are_plaintext() {
+        let encryption = with_passphrase("correct horse");
+        let markdown = format!(
+            "# Notes\n\nThe header looks like {} and the password is hunter2.\n",
+            AGE_BEGIN
+        );
+        assert!(!is_sealed(&markdown));
+        let sealed = encryption.seal_markdown(&markdown).unwrap();
+        assert!(is_sealed(&sealed));
+        assert!(!sealed.contains("hunter2"));
+
+        // The decrypted body isn't searched again for blocks
+        let quoting = format!("# Notes\n\n{}\n{}\n", KEY_BEGIN, KEY_END);
+        let sealed = encryption.seal_markdown(&quoting).unwrap();
+        assert_eq!(encryption.open_markdown(&sealed).unwrap(), quoting);
+        // Nor is a stray marker before a block mistaken for one
+        let stray = format!("{}\n{}", KEY_BEGIN, sealed);
+        assert_eq!(
+            encryption.open_markdown(&stray).unwrap(),
+            format!("{}\n{}", KEY_BEGIN, quoting)
+        );
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        use crate::history::{HistoryEntry, HistoryStore};
+
+        let temp = tempfile::tempdir().unwrap();
+        let store = HistoryStore::new(temp.path().to_path_buf())
+            .with_index(true)
+            .with_encryption(with_passphrase("correct horse"));
+        let path = store
+            .store(&HistoryEntry::new("sessions", "Deploy", "Rotated the staging keys"))
+            .unwrap();
+
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert!(raw.contains("title: Deploy"));
+        assert!(!raw.contains("staging"));
+
+        let recent = store.recent(None, 1).unwrap();
+        assert!(recent[0].content.contains("Rotated the staging keys"));
+        assert_eq!(store.query("staging", None, None, 10).unwrap().len(), 1);
+        assert!(store.read(&path).unwrap().contains("Rotated the staging keys"));
+
+        let index = crate::history::index::HistoryIndex::new(temp.path());
+        assert!(index.search("staging", None, 10).unwrap().is_empty());
+        assert_eq!(index.search("deploy", None, 10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_salt_per_install() {
+        let salt_of = |sealed: &str| {
+            sealed
+                .lines()
+                .find_map(|line| line.strip_prefix(SALT_HEADER))
+                .unwrap()
+                .to_string()
+        };
+        let markdown = "# Deploy\n\nRotated the staging keys\n";
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+
+        let sealed = with_passphrase("pw")
+            .with_salt_file(first.path().join(SALT_FILE))
+            .seal_markdown(markdown)
+            .unwrap();
+        let salt = salt_of(&sealed);
+        assert_eq!(
+            std::fs::read_to_string(first.path().join(SALT_FILE)).unwrap().trim(),
+            salt
+        );
+        // The same install keeps its salt; another gets its own
+        let again = with_passphrase("pw").with_salt_file(first.path().join(SALT_FILE));
+        assert_eq!(salt_of(&again.seal_markdown(markdown).unwrap()), salt);
+        let other = with_passphrase("pw").with_salt_file(second.path().join(SALT_FILE));
+        let foreign = other.seal_markdown(markdown).unwrap();
+        assert_ne!(salt_of(&foreign), salt);
+
+        // Blocks from another install open with the same passphrase
+        assert_eq!(again.open_markdown(&foreign).unwrap(), markdown);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pipe_large_input() {
+        // Far beyond the pipe buffers; writing it all before reading would hang
+        let body = "The staging password is hunter2.\n".repeat(20_000);
+        assert_eq!(pipe(Command::new("cat"), &body).unwrap(), body);
+    }
+
+    #[test]
+    fn test_split_body() {
+        assert_eq!(
+            split_body("---\na: b\n---\n\n# T\n\nbody\n"),
+            ("---\na: b\n---\n\n# T\n", "\nbody\n")
+        );
+        assert_eq!(split_body("plain text"), ("", "plain text"));
+        assert_eq!(split_body("# Only a title"), ("# Only a title", ""));
+    }
+}
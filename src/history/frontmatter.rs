@@ -0,0 +1,225 @@
+//! Entry frontmatter as YAML
+//!
+//! The block between the `---` lines is parsed with serde_yaml, so quoted
+//! strings, multi-line values and nested metadata read back as written. A
+//! block that isn't valid YAML for an entry (an unquoted `title: 2024`, a
+//! stray colon from a hand edit) is read line by line as `key: value` pairs
+//! instead, which is how entries were always parsed.
+//!
+//! [`HistoryEntry::frontmatter`](super::HistoryEntry::frontmatter) quotes a
+//! value only when it wouldn't read back unchanged, so ordinary entries keep
+//! the plain `key: value` look the line-based editors here rely on.
+
+use serde::Deserialize;
+use serde_yaml::Value;
+use std::collections::BTreeMap;
+
+/// The frontmatter fields of an entry
+#[derive(Debug, Default, Deserialize)]
+pub struct Frontmatter {
+    pub id: Option<String>,
+    pub title: Option<String>,
+    pub category: Option<String>,
+    pub created_at: Option<String>,
+    tags: Option<List>,
+    related: Option<List>,
+    #[serde(flatten)]
+    metadata: BTreeMap<String, Value>,
+    /// The block it was read from, for the original text of scalar metadata
+    #[serde(skip)]
+    block: String,
+}
+
+/// `[a, b]`, a YAML list, or a bare comma-separated string
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum List {
+    Items(Vec<String>),
+    Inline(String),
+}
+
+impl List {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            List::Items(items) => items,
+            List::Inline(text) => text
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+        }
+    }
+}
+
+impl Frontmatter {
+    /// Parse a frontmatter block (without the `---` lines)
+    pub fn parse(block: &str) -> Self {
+        let parsed = if block.trim().is_empty() {
+            Self::default()
+        } else {
+            match serde_yaml::from_str::<Self>(block) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    log::debug!("Frontmatter is not valid YAML ({}); reading it line by line", e);
+                    Self::parse_lines(block)
+                }
+            }
+        };
+        Self {
+            block: block.to_string(),
+            ..parsed
+        }
+    }
+
+    /// The old `key: value` reading, one line at a time
+    fn parse_lines(block: &str) -> Self {
+        let mut parsed = Self::default();
+        for line in block.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim().to_string();
+            match key.trim() {
+                "id" => parsed.id = Some(value),
+                "title" => parsed.title = Some(value),
+                "category" => parsed.category = Some(value),
+                "created_at" => parsed.created_at = Some(value),
+                "tags" => parsed.tags = Some(List::Inline(value)),
+                "related" => parsed.related = Some(List::Inline(value)),
+                key => {
+                    parsed.metadata.insert(key.to_string(), Value::String(value));
+                }
+            }
+        }
+        parsed
+    }
+
+    pub fn tags(&mut self) -> Vec<String> {
+        self.tags.take().map(List::into_vec).unwrap_or_default()
+    }
+
+    pub fn related(&mut self) -> Vec<String> {
+        self.related.take().map(List::into_vec).unwrap_or_default()
+    }
+
+    /// Remaining keys as text: numbers and booleans as written, nested values as JSON
+    pub fn metadata(&mut self) -> Vec<(String, String)> {
+        let metadata = std::mem::take(&mut self.metadata);
+        metadata
+            .into_iter()
+            .map(|(key, value)| {
+                let text = match value {
+                    Value::String(s) => s,
+                    Value::Null => String::new(),
+                    Value::Bool(_) | Value::Number(_) => match raw_value(&self.block, &key) {
+                        Some(raw) => raw.to_string(),
+                        None => serde_json::to_string(&value).unwrap_or_default(),
+                    },
+                    other => serde_json::to_string(&other).unwrap_or_default(),
+                };
+                (key, text)
+            })
+            .collect()
+    }
+}
+
+/// The text after `key:` on its line
+fn raw_value<'a>(block: &'a str, key: &str) -> Option<&'a str> {
+    block
+        .lines()
+        .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+        .map(str::trim)
+}
+
+/// `value` as a YAML string: plain when it reads back unchanged, quoted otherwise
+pub fn scalar(value: &str) -> String {
+    if serde_yaml::from_str::<Value>(value).is_ok_and(|parsed| parsed.as_str() == Some(value)) {
+        return value.to_string();
+    }
+    match serde_yaml::to_string(value) {
+        Ok(yaml) => yaml.trim_end_matches('\n').to_string(),
+        Err(_) => value.to_string(),
+    }
+}
+
+/// A metadata value as YAML; numbers, booleans and JSON read back as themselves
+pub fn metadata_scalar(value: &str) -> String {
+    if !value.contains('\n') {
+        match serde_yaml::from_str::<Value>(value) {
+            Ok(Value::Bool(_) | Value::Number(_)) => return value.to_string(),
+            Ok(nested @ (Value::Mapping(_) | Value::Sequence(_)))
+                if serde_json::to_string(&nested).is_ok_and(|json| json == value) =>
+            {
+                return value.to_string();
+            }
+            _ => {}
+        }
+    }
+    scalar(value)
+}
+
+/// Frontmatter lines without `key` (and the indented or `- ` lines continuing its value),
+/// with the index where it was
+pub fn without_key<'a>(block: &'a str, key: &str) -> (Vec<&'a str>, Option<usize>) {
+    let prefix = format!("{}:", key);
+    let mut lines = Vec::new();
+    let mut position = None;
+    let mut skipping = false;
+    for line in block.lines() {
+        if skipping && (line.starts_with([' ', '\t']) || line.starts_with("- ") || line == "-") {
+            continue;
+        }
+        skipping = line.starts_with(&prefix);
+        if skipping {
+            position.get_or_insert(lines.len());
+        } else {
+            lines.push(line);
+        }
+    }
+    (lines, position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_yaml_and_fallback() {
+        let mut yaml = Frontmatter::parse(
+            "id: '0123'\ntitle: \"Fix: quoted # title\"\ntags:\n  - a\n  - b\nnotes: |\n  line one\n  line two\nowner:\n  team: infra\nexit_code: 007\n",
+        );
+        assert_eq!(yaml.id.as_deref(), Some("0123"));
+        assert_eq!(yaml.title.as_deref(), Some("Fix: quoted # title"));
+        assert_eq!(yaml.tags(), ["a", "b"]);
+        let metadata: BTreeMap<_, _> = yaml.metadata().into_iter().collect();
+        assert_eq!(metadata["notes"], "line one\nline two\n");
+        assert_eq!(metadata["owner"], r#"{"team":"infra"}"#);
+        assert_eq!(metadata["exit_code"], "007");
+
+        // An unquoted number where a string belongs: read line by line
+        let mut lines = Frontmatter::parse("id: 0123\ntitle: a: b\ntags: [x, y]\n");
+        assert_eq!(lines.id.as_deref(), Some("0123"));
+        assert_eq!(lines.title.as_deref(), Some("a: b"));
+        assert_eq!(lines.tags(), ["x", "y"]);
+    }
+
+    #[test]
+    fn test_scalar() {
+        assert_eq!(scalar("plain title"), "plain title");
+        assert_eq!(scalar("a: b"), "'a: b'");
+        assert_eq!(scalar("123"), "'123'");
+        assert_eq!(scalar("# heading"), "'# heading'");
+        assert_eq!(metadata_scalar("3"), "3");
+        assert_eq!(metadata_scalar(r#"{"a":1}"#), r#"{"a":1}"#);
+        assert_eq!(metadata_scalar("[a, b]"), "'[a, b]'");
+    }
+
+    #[test]
+    fn test_without_key() {
+        let block = "id: a\ntags:\n  - x\n- y\ntitle: T";
+        assert_eq!(without_key(block, "tags"), (vec!["id: a", "title: T"], Some(1)));
+        assert_eq!(without_key(block, "status"), (block.lines().collect(), None));
+    }
+}
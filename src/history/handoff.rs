@@ -0,0 +1,465 @@
+//! Session handoff notes
+//!
+//! When a session ends, the tail of its transcript is mined for the things
+//! the next session in the same project would otherwise have to rediscover:
+//! questions left open, decisions made, and files still being changed. They
+//! are written to one `handoff.md` per project and injected at the next
+//! SessionStart there, which gives cheap continuity without a memory store.
+//!
+//! A project is the git work tree containing the session's cwd, or the cwd
+//! itself outside git. Handoffs live under `~/.local/share/pais/handoffs/`.
+
+use eyre::{Context, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+use super::transcript::ReverseLines;
+use crate::clock;
+use crate::presenter;
+use crate::skill::cache::content_hash;
+
+/// Transcript lines read from the end when building a handoff
+const MAX_TRANSCRIPT_LINES: usize = 400;
+
+/// Open questions only come from the last few assistant messages; older
+/// ones were most likely answered
+const QUESTION_MESSAGES: usize = 3;
+
+/// Longest item kept, in columns
+const MAX_ITEM_WIDTH: usize = 200;
+
+/// Phrases marking a sentence as a decision
+const DECISION_CUES: &[&str] = &[
+    "decided",
+    "decision",
+    "going with",
+    "went with",
+    "settled on",
+    "opted",
+    "chose",
+    "switched to",
+    "instead of",
+    "we'll use",
+    "will use",
+];
+
+/// Tools whose `file_path` (or `notebook_path`) input is a file being changed
+pub const EDIT_TOOLS: &[&str] = &["Edit", "MultiEdit", "Write", "NotebookEdit"];
+
+/// Where handoffs are stored
+pub fn handoffs_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("pais")
+        .join("handoffs")
+}
+
+/// The project a working directory belongs to
+pub fn project_root(cwd: &Path) -> PathBuf {
+    Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .current_dir(cwd)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| PathBuf::from(String::from_utf8_lossy(&o.stdout).trim()))
+        .filter(|root| !root.as_os_str().is_empty())
+        .unwrap_or_else(|| cwd.to_path_buf())
+}
+
+/// The handoff file for a project root
+pub fn handoff_path(dir: &Path, root: &Path) -> PathBuf {
+    let name = root.file_name().and_then(|n| n.to_str()).unwrap_or("root");
+    let hash = content_hash(root.as_os_str().as_encoded_bytes());
+    dir.join(format!("{}-{}", name, &hash[..8])).join("handoff.md")
+}
+
+/// What the end of a transcript says about the session
+#[derive(Debug, Default)]
+struct TranscriptNotes {
+    /// Assistant message texts, newest first
+    texts: Vec<String>,
+    /// Files passed to edit tools, newest first
+    edited: Vec<PathBuf>,
+}
+
+fn read_transcript(path: &Path) -> TranscriptNotes {
+    let mut notes = TranscriptNotes::default();
+    let lines = match ReverseLines::open(path) {
+        Ok(lines) => lines,
+        Err(e) => {
+            log::debug!("No transcript for handoff at {}: {}", path.display(), e);
+            return notes;
+        }
+    };
+
+    for line in lines.take(MAX_TRANSCRIPT_LINES).flatten() {
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+        if entry.get("type").and_then(|t| t.as_str()) != Some("assistant") {
+            continue;
+        }
+        let Some(blocks) = entry.pointer("/message/content").and_then(|c| c.as_array()) else {
+            continue;
+        };
+
+        let mut text = Vec::new();
+        for block in blocks {
+            match block.get("type").and_then(|t| t.as_str()) {
+                Some("text") => text.extend(block.get("text").and_then(|t| t.as_str())),
+                Some("tool_use")
+                    if block
+                        .get("name")
+                        .and_then(|n| n.as_str())
+                        .is_some_and(|n| EDIT_TOOLS.contains(&n)) =>
+                {
+                    let input = block.get("input");
+                    let file = input
+                        .and_then(|i| i.get("file_path").or_else(|| i.get("notebook_path")))
+                        .and_then(|p| p.as_str());
+                    notes.edited.extend(file.map(PathBuf::from));
+                }
+                _ => {}
+            }
+        }
+        if !text.is_empty() {
+            notes.texts.push(text.join("\n"));
+        }
+    }
+    notes
+}
+
+/// Prose sentences of a message, skipping code blocks and markdown markers
+fn sentences(text: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut in_code = false;
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code || line.is_empty() || line.starts_with('|') {
+            continue;
+        }
+        let line = line
+            .trim_start_matches(['#', '>', '-', '*', '+'])
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .trim_start_matches(['.', ')'])
+            .trim();
+
+        let mut start = 0;
+        let chars: Vec<(usize, char)> = line.char_indices().collect();
+        for (i, &(pos, c)) in chars.iter().enumerate() {
+            let boundary = chars.get(i + 1).is_none_or(|(_, next)| next.is_whitespace());
+            if matches!(c, '.' | '?' | '!') && boundary {
+                let end = pos + c.len_utf8();
+                out.push(line[start..end].trim().replace("**", ""));
+                start = end;
+            }
+        }
+        if start < line.len() {
+            out.push(line[start..].trim().replace("**", ""));
+        }
+    }
+    out.retain(|s| s.chars().count() >= 10);
+    out
+}
+
+/// Keep the first `max` distinct items, shortened
+fn distinct(items: impl IntoIterator<Item = String>, max: usize) -> Vec<String> {
+    let mut seen = HashSet::new();
+    items
+        .into_iter()
+        .filter(|item| seen.insert(item.to_lowercase()))
+        .take(max)
+        .map(|item| presenter::truncate(&item, MAX_ITEM_WIDTH))
+        .collect()
+}
+
+fn open_questions(texts: &[String], max: usize) -> Vec<String> {
+    let candidates = texts.iter().take(QUESTION_MESSAGES).flat_map(|text| {
+        sentences(text)
+            .into_iter()
+            .filter(|s| s.ends_with('?') || s.starts_with("TODO") || s.starts_with("[ ]"))
+    });
+    distinct(candidates, max)
+}
+
+fn decisions(texts: &[String], max: usize) -> Vec<String> {
+    let candidates = texts.iter().flat_map(|text| {
+        sentences(text).into_iter().filter(|s| {
+            let lower = s.to_lowercase();
+            !s.ends_with('?') && DECISION_CUES.iter().any(|cue| lower.contains(cue))
+        })
+    });
+    distinct(candidates, max)
+}
+
+/// Paths `git status` reports as changed under `root`, or `None` outside git
+fn uncommitted(root: &Path) -> Option<Vec<String>> {
+    let output = Command::new("git")
+        .args(["status", "--porcelain", "--untracked-files=all"])
+        .current_dir(root)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.get(3..))
+            .map(|path| path.rsplit(" -> ").next().unwrap_or(path).trim_matches('"').to_string())
+            .collect(),
+    )
+}
+
+/// A file still being changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FileInFlight {
+    path: String,
+    /// Edited during the session that wrote the handoff
+    edited: bool,
+}
+
+/// Files in flight: uncommitted changes (session edits first), or just the
+/// session's edits outside git
+fn files_in_flight(root: &Path, edited: &[PathBuf], dirty: Option<Vec<String>>) -> Vec<FileInFlight> {
+    let relative = |path: &PathBuf| path.strip_prefix(root).unwrap_or(path).display().to_string();
+    let mut seen = HashSet::new();
+    let edited: Vec<String> = edited.iter().map(relative).filter(|p| seen.insert(p.clone())).collect();
+
+    match dirty {
+        Some(dirty) => {
+            let dirty_set: HashSet<&String> = dirty.iter().collect();
+            let mut files: Vec<FileInFlight> = edited
+                .iter()
+                .filter(|path| dirty_set.contains(path))
+                .map(|path| FileInFlight {
+                    path: path.clone(),
+                    edited: true,
+                })
+                .collect();
+            files.extend(
+                dirty
+                    .iter()
+                    .filter(|path| !edited.contains(path))
+                    .map(|path| FileInFlight {
+                        path: path.clone(),
+                        edited: false,
+                    }),
+            );
+            files
+        }
+        None => edited
+            .into_iter()
+            .map(|path| FileInFlight { path, edited: true })
+            .collect(),
+    }
+}
+
+/// Notes for the next session in a project
+#[derive(Debug)]
+pub struct Handoff {
+    pub project: String,
+    pub root: PathBuf,
+    pub session_id: String,
+    open_questions: Vec<String>,
+    decisions: Vec<String>,
+    files: Vec<FileInFlight>,
+    /// Files in flight beyond the ones listed
+    more_files: usize,
+}
+
+impl Handoff {
+    /// Build a handoff from a finished session's transcript and the project's git state
+    pub fn build(session_id: &str, cwd: &Path, transcript: Option<&Path>, max_items: usize) -> Self {
+        let root = project_root(cwd);
+        let notes = transcript.map(read_transcript).unwrap_or_default();
+        let mut files = files_in_flight(&root, &notes.edited, uncommitted(&root));
+        let more_files = files.len().saturating_sub(max_items);
+        files.truncate(max_items);
+
+        Self {
+            project: root
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("project")
+                .to_string(),
+            open_questions: open_questions(&notes.texts, max_items),
+            decisions: decisions(&notes.texts, max_items),
+            files,
+            more_files,
+            session_id: session_id.to_string(),
+            root,
+        }
+    }
+
+    /// Nothing worth handing off
+    pub fn is_empty(&self) -> bool {
+        self.open_questions.is_empty() && self.decisions.is_empty() && self.files.is_empty()
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("# Handoff: {}\n\n", self.project);
+        md.push_str(&format!(
+            "Session `{}` ended {} in `{}`.\n",
+            &self.session_id[..8.min(self.session_id.len())],
+            clock::now_local().format("%Y-%m-%d %H:%M"),
+            self.root.display()
+        ));
+
+        let mut section = |title: &str, items: Vec<String>| {
+            if !items.is_empty() {
+                md.push_str(&format!("\n## {}\n\n", title));
+                for item in items {
+                    md.push_str(&format!("- {}\n", item));
+                }
+            }
+        };
+        section("Open questions", self.open_questions.clone());
+        section("Recent decisions", self.decisions.clone());
+
+        let mut files: Vec<String> = self
+            .files
+            .iter()
+            .map(|f| {
+                if f.edited {
+                    format!("`{}` (edited last session)", f.path)
+                } else {
+                    format!("`{}`", f.path)
+                }
+            })
+            .collect();
+        if self.more_files > 0 {
+            files.push(format!("...and {} more", self.more_files));
+        }
+        section("Files in flight", files);
+        md
+    }
+
+    /// Write the handoff for this project, replacing the previous one
+    pub fn write(&self, dir: &Path, content: &str) -> Result<PathBuf> {
+        let path = handoff_path(dir, &self.root);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let tmp = path.with_extension(format!("md.{}", std::process::id()));
+        fs::write(&tmp, content).context("Failed to write handoff")?;
+        fs::rename(&tmp, &path).context("Failed to write handoff")?;
+        Ok(path)
+    }
+}
+
+/// The project's handoff, unless there is none or it is older than `max_age`
+pub fn load_recent(dir: &Path, cwd: &Path, max_age: Duration) -> Option<String> {
+    let path = handoff_path(dir, &project_root(cwd));
+    let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+    let age = SystemTime::now().duration_since(modified).unwrap_or_default();
+    if age > max_age {
+        log::debug!("Skipping stale handoff {} ({}s old)", path.display(), age.as_secs());
+        return None;
+    }
+    fs::read_to_string(&path)
+        .ok()
+        .filter(|content| !content.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::{NamedTempFile, tempdir};
+
+    fn assistant(blocks: serde_json::Value) -> String {
+        serde_json::json!({"type": "assistant", "message": {"content": blocks}}).to_string()
+    }
+
+    #[test]
+    fn test_questions_and_decisions_from_transcript() {
+        let mut transcript = NamedTempFile::new().unwrap();
+        let lines = [
+            assistant(serde_json::json!([
+                {"type": "text", "text": "Should we keep the old API around? I decided to use a trait instead of an enum."},
+                {"type": "tool_use", "name": "Edit", "input": {"file_path": "/work/app/src/lib.rs"}}
+            ])),
+            r#"{"type":"user","message":{"content":"Sounds good. What about tests?"}}"#.to_string(),
+            assistant(serde_json::json!([
+                {"type": "text", "text": "Done.\n```\nlet x = y?;\n```\n- **Going with** serde for the config format.\n\nDo you want the migration now?"}
+            ])),
+        ];
+        writeln!(transcript, "{}", lines.join("\n")).unwrap();
+
+        let notes = read_transcript(transcript.path());
+        assert_eq!(notes.edited, vec![PathBuf::from("/work/app/src/lib.rs")]);
+        assert_eq!(
+            open_questions(&notes.texts, 5),
+            vec!["Do you want the migration now?", "Should we keep the old API around?"]
+        );
+        assert_eq!(
+            decisions(&notes.texts, 5),
+            vec![
+                "Going with serde for the config format.",
+                "I decided to use a trait instead of an enum."
+            ]
+        );
+    }
+
+    #[test]
+    fn test_files_in_flight_prefer_session_edits() {
+        let root = Path::new("/work/app");
+        let edited = vec![
+            PathBuf::from("/work/app/src/lib.rs"),
+            PathBuf::from("/work/app/README.md"),
+        ];
+
+        let dirty = Some(vec!["Cargo.toml".to_string(), "src/lib.rs".to_string()]);
+        let files = files_in_flight(root, &edited, dirty);
+        assert_eq!(
+            files,
+            vec![
+                FileInFlight {
+                    path: "src/lib.rs".to_string(),
+                    edited: true
+                },
+                FileInFlight {
+                    path: "Cargo.toml".to_string(),
+                    edited: false
+                },
+            ]
+        );
+
+        // Outside git, every edited file is in flight
+        assert_eq!(files_in_flight(root, &edited, None).len(), 2);
+    }
+
+    #[test]
+    fn test_handoff_round_trip_and_age() {
+        let project = tempdir().unwrap();
+        let store = tempdir().unwrap();
+        let handoff = Handoff {
+            project: "app".to_string(),
+            root: project_root(project.path()),
+            session_id: "0123456789abcdef".to_string(),
+            open_questions: vec!["Ship it?".to_string()],
+            decisions: Vec::new(),
+            files: Vec::new(),
+            more_files: 0,
+        };
+        let md = handoff.to_markdown();
+        assert!(md.contains("Session `01234567` ended"));
+        assert!(md.contains("## Open questions\n\n- Ship it?\n"));
+        assert!(!md.contains("## Recent decisions"));
+
+        handoff.write(store.path(), &md).unwrap();
+        let day = Duration::from_secs(86400);
+        assert_eq!(
+            load_recent(store.path(), project.path(), day).as_deref(),
+            Some(md.as_str())
+        );
+        assert_eq!(load_recent(store.path(), project.path(), Duration::ZERO), None);
+    }
+}
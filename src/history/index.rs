@@ -0,0 +1,602 @@
+//! SQLite index of history entries
+//!
+//! With `history.index.enabled`, every entry the store writes is also
+//! recorded in `index/history.db` under the history path (id, category,
+//! title, tags, creation time and file path). `history query`, `recent` and
+//! `show` then read entry paths from the index instead of walking every
+//! category and date directory. The markdown files stay the source of truth:
+//! rows whose file is gone are skipped, and `pais history reindex` rebuilds
+//! the index from disk after entries were added or removed by hand.
+//!
+//! ## Full-text search
+//!
+//! An FTS5 table alongside holds each entry's title, tags and body for
+//! `pais history search`, ranked with BM25 (title matches weigh most, then
+//! tags). Queries are words (all must match; a trailing `*` matches a
+//! prefix), `"quoted phrases"`, `OR`/`NOT`, and the field filters `title:`,
+//! `tag:` and `category:`.
+//!
+//! ## Semantic search
+//!
+//! `pais history similar` stores a vector per entry in an `embeddings` table
+//! (see [`super::embed`]) and ranks entries by cosine similarity to the
+//! query's vector.
+//!
+//! The database records its schema version. A new database, or one written
+//! by an older pais, is filled from disk the first time it is opened.
+
+use chrono::NaiveDate;
+use eyre::{Context, Result};
+use rusqlite::{Connection, params};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::{HistoryEntry, HistoryStore, embed, encrypt};
+use crate::error::{CliError, ErrorCode};
+use crate::skill::cache::content_hash;
+
+/// Database file, relative to the history path
+const INDEX_FILE: &str = "index/history.db";
+
+/// Bumped whenever [`SCHEMA`] changes so existing databases are rebuilt
+const SCHEMA_VERSION: i64 = 2;
+
+const SCHEMA: &str = "
+DROP TABLE IF EXISTS entries;
+DROP TABLE IF EXISTS entries_fts;
+CREATE TABLE entries (
+    path TEXT PRIMARY KEY,
+    id TEXT NOT NULL,
+    category TEXT NOT NULL,
+    title TEXT NOT NULL,
+    tags TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    day TEXT NOT NULL
+);
+CREATE INDEX entries_id ON entries (id);
+CREATE INDEX entries_order ON entries (day, created_at);
+CREATE VIRTUAL TABLE entries_fts USING fts5(
+    title, tags, content, path UNINDEXED,
+    tokenize = 'porter unicode61'
+);
+";
+
+/// Vectors for `pais history similar`; kept across schema rebuilds since
+/// they are costly to recompute, and matched to entries by path and text hash
+const EMBEDDINGS_SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS embeddings (
+    path TEXT NOT NULL,
+    model TEXT NOT NULL,
+    hash TEXT NOT NULL,
+    vector BLOB NOT NULL,
+    PRIMARY KEY (path, model)
+);
+";
+
+/// BM25 weights of the title, tags and content columns
+const RANK: &str = "bm25(entries_fts, 10.0, 5.0, 1.0)";
+
+/// A full-text search match
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub path: PathBuf,
+    /// BM25 relevance; higher is better
+    pub score: f64,
+    /// Matching excerpt with hits wrapped in `[` `]`
+    pub snippet: String,
+}
+
+/// An entry whose text has no current vector
+#[derive(Debug, Clone)]
+pub struct PendingEmbedding {
+    pub path: PathBuf,
+    pub hash: String,
+    pub text: String,
+}
+
+/// A vector stored for an entry, as carried by `pais history pack`
+#[derive(Debug, Clone)]
+pub struct StoredEmbedding {
+    pub path: PathBuf,
+    pub model: String,
+    pub hash: String,
+    pub vector: Vec<f32>,
+}
+
+/// The index database of a history directory
+#[derive(Debug, Clone)]
+pub struct HistoryIndex {
+    history_dir: PathBuf,
+    path: PathBuf,
+}
+
+impl HistoryIndex {
+    pub fn new(history_dir: &Path) -> Self {
+        Self {
+            history_dir: history_dir.to_path_buf(),
+            path: history_dir.join(INDEX_FILE),
+        }
+    }
+
+    /// Location of the database file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the index has been built
+    pub fn exists(&self) -> bool {
+        self.path.is_file()
+    }
+
+    fn connect(&self) -> Result<Connection> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let conn = Connection::open(&self.path).with_context(|| format!("Failed to open {}", self.path.display()))?;
+        // Hooks write entries while CLI commands read
+        conn.busy_timeout(Duration::from_secs(5))?;
+
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version != SCHEMA_VERSION {
+            log::info!("Building history index {}", self.path.display());
+            let tx = conn.unchecked_transaction()?;
+            tx.execute_batch(SCHEMA)
+                .context("Failed to create history index schema")?;
+            tx.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+            fill(&tx, &self.history_dir)?;
+            tx.commit().context("Failed to write history index")?;
+        }
+        conn.execute_batch(EMBEDDINGS_SCHEMA)
+            .context("Failed to create history embeddings table")?;
+        Ok(conn)
+    }
+
+    /// Add or replace the row for the entry stored at `path`
+    pub fn upsert(&self, entry: &HistoryEntry, path: &Path) -> Result<()> {
+        insert(&self.connect()?, entry, path)
+    }
+
+    /// Drop the row for an entry file
+    pub fn remove(&self, path: &Path) -> Result<()> {
+        if !self.exists() {
+            return Ok(());
+        }
+        let conn = self.connect()?;
+        let path = path.to_string_lossy();
+        conn.execute("DELETE FROM entries WHERE path = ?1", params![path])?;
+        conn.execute("DELETE FROM entries_fts WHERE path = ?1", params![path])?;
+        conn.execute("DELETE FROM embeddings WHERE path = ?1", params![path])?;
+        Ok(())
+    }
+
+    /// Entry files, newest first, optionally limited to one category and to
+    /// days on or after `since` (same order as [`HistoryStore::paths`])
+    pub fn paths(&self, category: Option<&str>, since: Option<NaiveDate>) -> Result<Vec<PathBuf>> {
+        let conn = self.connect()?;
+        let mut stmt = conn.prepare(
+            "SELECT path FROM entries
+             WHERE (?1 IS NULL OR category = ?1) AND (?2 IS NULL OR day >= ?2)
+             ORDER BY day DESC, created_at DESC, id DESC",
+        )?;
+        let since = since.map(|d| d.format("%Y-%m-%d").to_string());
+        let paths = stmt
+            .query_map(params![category, since], |row| row.get::<_, String>(0))?
+            .map(|path| path.map(PathBuf::from))
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(paths)
+    }
+
+    /// Newest entry file whose ID starts with `prefix`
+    pub fn find(&self, prefix: &str) -> Result<Option<PathBuf>> {
+        let conn = self.connect()?;
+        let mut stmt = conn.prepare(
+            "SELECT path FROM entries WHERE substr(id, 1, length(?1)) = ?1
+             ORDER BY day DESC, created_at DESC, id DESC",
+        )?;
+        for path in stmt.query_map(params![prefix], |row| row.get::<_, String>(0))? {
+            let path = PathBuf::from(path?);
+            if path.is_file() {
+                return Ok(Some(path));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Full-text search, best match first (see the module docs for the syntax)
+    pub fn search(&self, query: &str, category: Option<&str>, limit: usize) -> Result<Vec<SearchHit>> {
+        let query = SearchQuery::parse(query);
+        if query.fts.is_empty() {
+            return Err(CliError::new(ErrorCode::InvalidInput, "Nothing to search for")
+                .with_hint("Give at least one word or phrase besides category:")
+                .into());
+        }
+        let category = query.category.as_deref().or(category);
+
+        let conn = self.connect()?;
+        let sql = format!(
+            "SELECT f.path, -{rank}, snippet(entries_fts, 2, '[', ']', '…', 16)
+             FROM entries_fts f JOIN entries e ON e.path = f.path
+             WHERE entries_fts MATCH ?1 AND (?2 IS NULL OR e.category = ?2)
+             ORDER BY {rank} LIMIT ?3",
+            rank = RANK
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        // FTS5 reports bad syntax (e.g. `NOT` with nothing before it) when the query runs
+        let hits = stmt
+            .query_map(params![query.fts, category, limit as i64], |row| {
+                Ok(SearchHit {
+                    path: PathBuf::from(row.get::<_, String>(0)?),
+                    score: row.get(1)?,
+                    snippet: row.get(2)?,
+                })
+            })
+            .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+            .map_err(|e| {
+                CliError::new(ErrorCode::InvalidInput, format!("Invalid search query: {}", e))
+                    .with_hint("Quote phrases with \"...\"; filters are title:, tag: and category:")
+            })?;
+        Ok(hits.into_iter().filter(|hit| hit.path.is_file()).collect())
+    }
+
+    /// Entries without a vector from `model` for their current text; vectors
+    /// of entries no longer indexed are dropped
+    pub fn pending_embeddings(&self, model: &str) -> Result<Vec<PendingEmbedding>> {
+        let conn = self.connect()?;
+        conn.execute(
+            "DELETE FROM embeddings WHERE path NOT IN (SELECT path FROM entries)",
+            [],
+        )?;
+        let mut stmt = conn.prepare(
+            "SELECT e.path, e.title, e.tags, f.content, v.hash
+             FROM entries e JOIN entries_fts f ON f.path = e.path
+             LEFT JOIN embeddings v ON v.path = e.path AND v.model = ?1
+             ORDER BY e.day DESC, e.created_at DESC",
+        )?;
+        let rows = stmt.query_map(params![model], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })?;
+
+        let mut pending = Vec::new();
+        for row in rows {
+            let (path, title, tags, content, stored) = row?;
+            let tags: Vec<String> = serde_json::from_str(&tags).unwrap_or_default();
+            let text = embed::entry_text(&title, &tags, &content);
+            let hash = content_hash(text.as_bytes());
+            if stored.as_deref() != Some(hash.as_str()) {
+                pending.push(PendingEmbedding {
+                    path: PathBuf::from(path),
+                    hash,
+                    text,
+                });
+            }
+        }
+        Ok(pending)
+    }
+
+    /// Record the vector `model` produced for a pending entry
+    pub fn store_embedding(&self, pending: &PendingEmbedding, model: &str, vector: &[f32]) -> Result<()> {
+        self.connect()?
+            .execute(
+                "INSERT OR REPLACE INTO embeddings (path, model, hash, vector) VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    pending.path.to_string_lossy(),
+                    model,
+                    pending.hash,
+                    embed::to_blob(vector)
+                ],
+            )
+            .context("Failed to store history embedding")?;
+        Ok(())
+    }
+
+    /// Every stored vector, whatever the model
+    pub fn embeddings(&self) -> Result<Vec<StoredEmbedding>> {
+        let conn = self.connect()?;
+        let mut stmt = conn.prepare("SELECT path, model, hash, vector FROM embeddings ORDER BY path, model")?;
+        let embeddings = stmt
+            .query_map([], |row| {
+                Ok(StoredEmbedding {
+                    path: PathBuf::from(row.get::<_, String>(0)?),
+                    model: row.get(1)?,
+                    hash: row.get(2)?,
+                    vector: embed::from_blob(&row.get::<_, Vec<u8>>(3)?),
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(embeddings)
+    }
+
+    /// Entries closest in meaning to `query`, a vector from `model`, best first
+    pub fn similar(&self, query: &[f32], model: &str, category: Option<&str>, limit: usize) -> Result<Vec<SearchHit>> {
+        let conn = self.connect()?;
+        let mut stmt = conn.prepare(
+            "SELECT v.path, v.vector FROM embeddings v JOIN entries e ON e.path = v.path
+             WHERE v.model = ?1 AND (?2 IS NULL OR e.category = ?2)",
+        )?;
+        let mut hits = stmt
+            .query_map(params![model, category], |row| {
+                let vector: Vec<u8> = row.get(1)?;
+                Ok(SearchHit {
+                    path: PathBuf::from(row.get::<_, String>(0)?),
+                    score: f64::from(embed::cosine(query, &embed::from_blob(&vector))),
+                    snippet: String::new(),
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        hits.retain(|hit| hit.path.is_file());
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(limit);
+        Ok(hits)
+    }
+
+    /// Replace the index with every entry on disk, returning how many were indexed
+    pub fn rebuild(&self) -> Result<usize> {
+        let conn = self.connect()?;
+        let tx = conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM entries", [])?;
+        tx.execute("DELETE FROM entries_fts", [])?;
+        let count = fill(&tx, &self.history_dir)?;
+        tx.commit().context("Failed to write history index")?;
+        Ok(count)
+    }
+}
+
+/// Index every entry on disk
+fn fill(conn: &Connection, history_dir: &Path) -> Result<usize> {
+    let store = HistoryStore::new(history_dir.to_path_buf());
+    let mut count = 0;
+    for path in store.paths(None, None)? {
+        let path = path?;
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        match HistoryEntry::from_markdown(&content, &path) {
+            Ok(entry) => {
+                insert(conn, &entry, &path)?;
+                count += 1;
+            }
+            Err(e) => log::debug!("Not indexing unparseable entry {}: {}", path.display(), e),
+        }
+    }
+    Ok(count)
+}
+
+fn insert(conn: &Connection, entry: &HistoryEntry, path: &Path) -> Result<()> {
+    // The date directory decides which day an entry belongs to, as when walking
+    let day = path
+        .parent()
+        .and_then(Path::file_name)
+        .and_then(|s| s.to_str())
+        .filter(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok())
+        .map(String::from)
+        .unwrap_or_else(|| entry.created_at.format("%Y-%m-%d").to_string());
+    let path = path.to_string_lossy();
+    conn.execute(
+        "INSERT OR REPLACE INTO entries (path, id, category, title, tags, created_at, day)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            path,
+            entry.id,
+            entry.category,
+            entry.title,
+            serde_json::to_string(&entry.tags)?,
+            entry.created_at.timestamp_millis(),
+            day,
+        ],
+    )
+    .context("Failed to update history index")?;
+
+    conn.execute("DELETE FROM entries_fts WHERE path = ?1", params![path])?;
+    // Encrypted bodies stay out of the index; only titles and tags are searchable
+    let content = if encrypt::is_sealed(&entry.content) { "" } else { entry.content.as_str() };
+    conn.execute(
+        "INSERT INTO entries_fts (title, tags, content, path) VALUES (?1, ?2, ?3, ?4)",
+        params![entry.title, entry.tags.join(" "), content, path],
+    )
+    .context("Failed to update history search index")?;
+    Ok(())
+}
+
+/// A `pais history search` query translated for FTS5
+#[derive(Debug, PartialEq)]
+struct SearchQuery {
+    /// FTS5 MATCH expression
+    fts: String,
+    /// From a `category:` filter
+    category: Option<String>,
+}
+
+impl SearchQuery {
+    fn parse(query: &str) -> Self {
+        let mut terms: Vec<String> = Vec::new();
+        let mut category = None;
+        for token in tokenize(query) {
+            if matches!(token.as_str(), "OR" | "AND" | "NOT") {
+                terms.push(token);
+                continue;
+            }
+            let (column, value) = match token.split_once(':') {
+                Some(("title", value)) => (Some("title"), value),
+                Some(("tag" | "tags", value)) => (Some("tags"), value),
+                Some(("category", value)) => {
+                    category = Some(value.trim_matches('"').to_string());
+                    continue;
+                }
+                _ => (None, token.as_str()),
+            };
+            let term = fts_term(value);
+            if term.is_empty() {
+                continue;
+            }
+            terms.push(match column {
+                Some(column) => format!("{} : {}", column, term),
+                None => term,
+            });
+        }
+        // A trailing operator has nothing to apply to
+        while terms.last().is_some_and(|t| matches!(t.as_str(), "OR" | "AND" | "NOT")) {
+            terms.pop();
+        }
+        Self {
+            fts: terms.join(" "),
+            category,
+        }
+    }
+}
+
+/// Split on whitespace, keeping `"quoted phrases"` (also after `field:`) whole
+fn tokenize(query: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in query.chars() {
+        if c == '"' {
+            quoted = !quoted;
+        } else if c.is_whitespace() && !quoted {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// A word or phrase as an FTS5 string, so punctuation isn't read as syntax
+fn fts_term(value: &str) -> String {
+    let (value, prefix) = match value.strip_suffix('*') {
+        Some(value) => (value, "*"),
+        None => (value, ""),
+    };
+    let value = value.replace('"', "");
+    if value.trim().is_empty() {
+        return String::new();
+    }
+    format!("\"{}\"{}", value.trim(), prefix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_index_tracks_store() {
+        let temp = tempdir().unwrap();
+        let store = HistoryStore::new(temp.path().to_path_buf()).with_index(true);
+        let index = HistoryIndex::new(temp.path());
+        let new = store.store(&HistoryEntry::new("learnings", "Indexed", "body")).unwrap();
+
+        // Written behind the store's back: only indexed after a rebuild
+        let plain = HistoryStore::new(temp.path().to_path_buf());
+        let mut entry = HistoryEntry::new("sessions", "By hand", "body");
+        entry.id = format!("{}0", entry.id);
+        let manual = plain.store(&entry).unwrap();
+        assert_eq!(index.paths(None, None).unwrap(), vec![new.clone()]);
+        assert_eq!(index.rebuild().unwrap(), 2);
+        assert_eq!(index.paths(None, None).unwrap(), vec![manual.clone(), new.clone()]);
+        assert_eq!(index.paths(Some("learnings"), None).unwrap(), vec![new.clone()]);
+        assert_eq!(index.find(&entry.id).unwrap(), Some(manual.clone()));
+
+        // Reads go through the index and skip files that are gone
+        fs::remove_file(&manual).unwrap();
+        let titles: Vec<String> = store.recent(None, 10).unwrap().into_iter().map(|e| e.title).collect();
+        assert_eq!(titles, vec!["Indexed"]);
+        assert_eq!(store.query("body", None, None, 10).unwrap().len(), 1);
+
+        store.remove(&new).unwrap();
+        assert!(!new.exists());
+        assert_eq!(index.paths(None, None).unwrap(), vec![manual]);
+        assert!(store.recent(None, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_search_query() {
+        let query = SearchQuery::parse(r#"borrow* "use as_mut" title:fence tag:"rust lang" category:learnings OR"#);
+        assert_eq!(
+            query.fts,
+            r#""borrow"* "use as_mut" title : "fence" tags : "rust lang""#
+        );
+        assert_eq!(query.category.as_deref(), Some("learnings"));
+        assert_eq!(SearchQuery::parse("a NOT b").fts, r#""a" NOT "b""#);
+        assert!(SearchQuery::parse("category:x").fts.is_empty());
+    }
+
+    #[test]
+    fn test_search_ranks_matches() {
+        let temp = tempdir().unwrap();
+        let store = HistoryStore::new(temp.path().to_path_buf()).with_index(true);
+        let body_only = store
+            .store(&HistoryEntry::new(
+                "sessions",
+                "Deploy",
+                "Fixed the cache invalidation bug",
+            ))
+            .unwrap();
+        let titled = store
+            .store(&HistoryEntry::new("learnings", "Cache invalidation", "Purge on deploy").with_tag("ops"))
+            .unwrap();
+        let index = HistoryIndex::new(temp.path());
+
+        let hits = index.search("cache", None, 10).unwrap();
+        assert_eq!(
+            hits.iter().map(|h| h.path.clone()).collect::<Vec<_>>(),
+            vec![titled.clone(), body_only.clone()]
+        );
+        assert!(hits[1].snippet.contains("[cache]"));
+
+        assert_eq!(
+            index.search("\"invalidation bug\"", None, 10).unwrap()[0].path,
+            body_only
+        );
+        assert_eq!(index.search("tag:ops", None, 10).unwrap()[0].path, titled);
+        assert_eq!(index.search("deploy*", Some("learnings"), 10).unwrap().len(), 1);
+        assert!(index.search("title:purge", None, 10).unwrap().is_empty());
+        assert!(index.search("cache category:research", None, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_similar_uses_current_vectors() {
+        let temp = tempdir().unwrap();
+        let store = HistoryStore::new(temp.path().to_path_buf()).with_index(true);
+        let cats = store.store(&HistoryEntry::new("learnings", "Cats", "purr")).unwrap();
+        let dogs = store.store(&HistoryEntry::new("sessions", "Dogs", "bark")).unwrap();
+        let index = HistoryIndex::new(temp.path());
+
+        let pending = index.pending_embeddings("test/model").unwrap();
+        assert_eq!(pending.len(), 2);
+        for item in &pending {
+            let vector = if item.path == cats { [1.0, 0.0] } else { [0.0, 1.0] };
+            index.store_embedding(item, "test/model", &vector).unwrap();
+        }
+        assert!(index.pending_embeddings("test/model").unwrap().is_empty());
+        assert_eq!(index.pending_embeddings("other/model").unwrap().len(), 2);
+
+        let hits = index.similar(&[0.2, 0.9], "test/model", None, 10).unwrap();
+        assert_eq!(
+            hits.iter().map(|h| h.path.clone()).collect::<Vec<_>>(),
+            vec![dogs.clone(), cats.clone()]
+        );
+        let hits = index.similar(&[0.2, 0.9], "test/model", Some("learnings"), 10).unwrap();
+        assert_eq!(hits.len(), 1);
+
+        // Edited entries need a new vector; removed ones drop theirs
+        let mut edited = HistoryEntry::from_markdown(&fs::read_to_string(&dogs).unwrap(), &dogs).unwrap();
+        edited.content = "woof".to_string();
+        store.write(&dogs, &edited.to_markdown()).unwrap();
+        store.remove(&cats).unwrap();
+        let pending = index.pending_embeddings("test/model").unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].path, dogs);
+        assert!(index.similar(&[1.0, 0.0], "test/model", None, 10).unwrap().len() <= 1);
+    }
+}
@@ -0,0 +1,266 @@
+//! Model-written labels for captured sessions
+//!
+//! The Stop hook titles an entry after the first line of the reply and tags
+//! it from a fixed keyword list, which reads generic for most sessions. With
+//! `history.labeling.enabled`, the capture is also sent to a model (OpenAI or
+//! Gemini with the same keys as `pais image`, or a local ollama) for a short
+//! title, a one or two sentence summary, a few tags and one of the declared
+//! categories. Anything going wrong (no key, offline, a timeout, a reply that
+//! isn't the expected JSON) keeps the extracted labels, so capturing never
+//! depends on the model.
+
+use eyre::{Context, Result};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::time::Duration;
+
+use super::categorize::Categories;
+use crate::config::{HistoryLabelingConfig, LabelProvider, NetworkConfig};
+use crate::net;
+use crate::presenter;
+use crate::secrets::SecretStore;
+
+const OPENAI_API: &str = "https://api.openai.com/v1/chat/completions";
+const GEMINI_API: &str = "https://generativelanguage.googleapis.com/v1beta/models";
+
+/// Captures shorter than this keep their extracted labels
+const MIN_CHARS: usize = 200;
+
+/// Characters of a capture sent; longer ones are cut
+const MAX_CHARS: usize = 6000;
+
+const MAX_TITLE: usize = 80;
+const MAX_SUMMARY: usize = 400;
+const MAX_TAGS: usize = 5;
+
+/// What the model suggests for a capture
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Label {
+    pub title: String,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+}
+
+/// A configured labeling model
+#[derive(Debug, Clone)]
+pub struct Labeler {
+    provider: LabelProvider,
+    model: String,
+    ollama_url: String,
+    api_key: Option<String>,
+    timeout: Duration,
+}
+
+impl Labeler {
+    /// The labeler `config` describes, or `None` when labeling is off or can't run
+    pub fn from_config(config: &HistoryLabelingConfig, network: &NetworkConfig, secrets: &SecretStore) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        let ollama_url = config.ollama_url.trim_end_matches('/').to_string();
+        if network.offline && !(config.provider == LabelProvider::Ollama && net::is_local(&ollama_url)) {
+            log::debug!("Offline: keeping extracted titles and tags");
+            return None;
+        }
+        let (default_model, default_secret) = match config.provider {
+            LabelProvider::OpenAi => ("gpt-4o-mini", Some("OPENAI_API_KEY")),
+            LabelProvider::Gemini => ("gemini-2.0-flash", Some("GOOGLE_API_KEY")),
+            LabelProvider::Ollama => ("llama3.2", None),
+        };
+        let api_key = match config.api_key_secret.as_deref().or(default_secret) {
+            Some(secret) if config.provider != LabelProvider::Ollama => match secrets.require(secret) {
+                Ok(key) => Some(key),
+                Err(e) => {
+                    log::warn!("Not labeling captures: {:#}", e);
+                    return None;
+                }
+            },
+            _ => None,
+        };
+        Some(Self {
+            provider: config.provider,
+            model: config.model.clone().unwrap_or_else(|| default_model.to_string()),
+            ollama_url,
+            api_key,
+            timeout: Duration::from_secs(config.timeout_secs.max(1)),
+        })
+    }
+
+    /// Provider and model, as recorded on labeled entries
+    pub fn id(&self) -> String {
+        let provider = match self.provider {
+            LabelProvider::OpenAi => "openai",
+            LabelProvider::Gemini => "gemini",
+            LabelProvider::Ollama => "ollama",
+        };
+        format!("{}/{}", provider, self.model)
+    }
+
+    /// Labels for `content`, or `None` when it is too short to be worth a request
+    pub fn label(&self, content: &str, categories: &Categories) -> Result<Option<Label>> {
+        if content.trim().chars().count() < MIN_CHARS {
+            return Ok(None);
+        }
+        let prompt = prompt(content, categories);
+        let key = self.api_key.as_deref().unwrap_or_default();
+        let reply = match self.provider {
+            LabelProvider::OpenAi => {
+                let body = json!({
+                    "model": self.model,
+                    "messages": [{ "role": "user", "content": prompt }],
+                    "response_format": { "type": "json_object" },
+                });
+                let auth = ("Authorization", format!("Bearer {}", key));
+                let response = self.post(OPENAI_API, &[auth], &body).context("Failed to call OpenAI")?;
+                response["choices"][0]["message"]["content"].as_str().map(String::from)
+            }
+            LabelProvider::Gemini => {
+                let url = format!("{}/{}:generateContent", GEMINI_API, self.model);
+                let body = json!({
+                    "contents": [{ "parts": [{ "text": prompt }] }],
+                    "generationConfig": { "responseMimeType": "application/json" },
+                });
+                let response = self
+                    .post(&url, &[("x-goog-api-key", key.to_string())], &body)
+                    .context("Failed to call Gemini")?;
+                response["candidates"][0]["content"]["parts"][0]["text"]
+                    .as_str()
+                    .map(String::from)
+            }
+            LabelProvider::Ollama => {
+                let url = format!("{}/api/generate", self.ollama_url);
+                let body = json!({ "model": self.model, "prompt": prompt, "stream": false, "format": "json" });
+                let response = self
+                    .post(&url, &[], &body)
+                    .with_context(|| format!("Failed to reach ollama at {}", url))?;
+                response["response"].as_str().map(String::from)
+            }
+        };
+        let reply = reply.ok_or_else(|| eyre::eyre!("{} returned no text", self.id()))?;
+        parse_label(&reply, categories).map(Some)
+    }
+
+    fn post(&self, url: &str, headers: &[(&str, String)], body: &Value) -> Result<Value> {
+        let mut request = ureq::post(url)
+            .config()
+            .timeout_global(Some(self.timeout))
+            .build()
+            .header("Content-Type", "application/json")
+            .header("User-Agent", "pais");
+        for (name, value) in headers {
+            request = request.header(*name, value);
+        }
+        let mut response = request.send(body.to_string().as_bytes())?;
+        let text = response.body_mut().read_to_string()?;
+        serde_json::from_str(&text).context("Failed to parse the model's response")
+    }
+}
+
+fn prompt(content: &str, categories: &Categories) -> String {
+    let content = match content.char_indices().nth(MAX_CHARS) {
+        Some((end, _)) => &content[..end],
+        None => content,
+    };
+    let categories: Vec<String> = categories
+        .iter()
+        .filter(|c| c.name != "events")
+        .map(|c| match c.description.as_str() {
+            "" => format!("- {}", c.name),
+            description => format!("- {}: {}", c.name, description),
+        })
+        .collect();
+    format!(
+        "Label this record of a coding assistant session for a searchable history. Reply with \
+         JSON only: {{\"title\": a specific title of at most 8 words, \"summary\": one or two \
+         sentences on what was done or learned, \"tags\": up to {} short lowercase tags, \
+         \"category\": the best fitting name from the list below}}.\n\nCategories:\n{}\n\n\
+         Session:\n{}",
+        MAX_TAGS,
+        categories.join("\n"),
+        content
+    )
+}
+
+/// Parse and tidy a model reply; a category that isn't declared is dropped
+pub fn parse_label(reply: &str, categories: &Categories) -> Result<Label> {
+    // Models wrap JSON in code fences or prose now and then
+    let start = reply.find('{').unwrap_or(0);
+    let end = reply.rfind('}').map_or(reply.len(), |i| i + 1);
+    let mut label: Label =
+        serde_json::from_str(&reply[start..end.max(start)]).context("The model's reply is not a label")?;
+
+    label.title = presenter::truncate(label.title.lines().next().unwrap_or_default().trim(), MAX_TITLE);
+    if label.title.is_empty() {
+        eyre::bail!("The model's reply has no title");
+    }
+    label.summary = presenter::truncate(
+        &label.summary.split_whitespace().collect::<Vec<_>>().join(" "),
+        MAX_SUMMARY,
+    );
+
+    let mut tags = Vec::new();
+    for tag in &label.tags {
+        let tag: String = tag
+            .trim()
+            .to_lowercase()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join("-")
+            .chars()
+            .filter(|c| c.is_alphanumeric() || matches!(c, '-' | '_'))
+            .collect();
+        if !tag.is_empty() && !tags.contains(&tag) && tags.len() < MAX_TAGS {
+            tags.push(tag);
+        }
+    }
+    label.tags = tags;
+    label.category = label
+        .category
+        .map(|c| c.trim().to_lowercase())
+        .filter(|c| categories.get(c).is_some());
+    Ok(label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_label() {
+        let categories = Categories::default();
+        let reply = "```json\n{\"title\": \"Fix flaky retry test\\nextra\", \"summary\": \"Found a race.\\n  Fixed it.\", \
+                     \"tags\": [\"Testing\", \"race condition\", \"testing\", \"c++\"], \"category\": \"Learnings\"}\n```";
+        let label = parse_label(reply, &categories).unwrap();
+        assert_eq!(label.title, "Fix flaky retry test");
+        assert_eq!(label.summary, "Found a race. Fixed it.");
+        assert_eq!(label.tags, ["testing", "race-condition", "c"]);
+        assert_eq!(label.category.as_deref(), Some("learnings"));
+
+        let undeclared = parse_label(r#"{"title": "T", "category": "misc"}"#, &categories).unwrap();
+        assert_eq!(undeclared.category, None);
+        assert!(parse_label("no json here", &categories).is_err());
+        assert!(parse_label(r#"{"title": " "}"#, &categories).is_err());
+    }
+
+    #[test]
+    fn test_labeler_from_config() {
+        let secrets = SecretStore::default();
+        let mut config = HistoryLabelingConfig::default();
+        let mut network = NetworkConfig::default();
+        assert!(Labeler::from_config(&config, &network, &secrets).is_none());
+
+        config.enabled = true;
+        config.provider = LabelProvider::Ollama;
+        let labeler = Labeler::from_config(&config, &network, &secrets).unwrap();
+        assert_eq!(labeler.id(), "ollama/llama3.2");
+        network.offline = true;
+        assert!(Labeler::from_config(&config, &network, &secrets).is_some());
+
+        config.provider = LabelProvider::OpenAi;
+        assert!(Labeler::from_config(&config, &network, &secrets).is_none());
+    }
+}
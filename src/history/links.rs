@@ -0,0 +1,137 @@
+//! Links between entries
+//!
+//! An entry's `related:` frontmatter lists the IDs of entries it links to,
+//! set by hand or with `pais history link`. Links are one-way in the files;
+//! the other direction (backlinks) is found by scanning the frontmatter of
+//! every entry, which stays plain in encrypted ones. A link to a deleted entry
+//! is kept and shown as missing.
+
+use eyre::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use super::manage::remove_frontmatter;
+use super::review::set_frontmatter;
+use super::{HistoryEntry, HistoryStore};
+use crate::error::{CliError, ErrorCode};
+
+/// An entry `related:` points to; `entry` is `None` when it no longer exists
+#[derive(Debug)]
+pub struct Related {
+    pub id: String,
+    pub entry: Option<HistoryEntry>,
+}
+
+/// The entries linked from and to one entry
+#[derive(Debug)]
+pub struct Links {
+    pub related: Vec<Related>,
+    /// Entries whose `related:` includes this one, newest first
+    pub backlinks: Vec<HistoryEntry>,
+}
+
+/// Add (or with `remove`, drop) a link from the entry file at `path` to `target`
+pub fn link(store: &HistoryStore, path: &Path, target: &str, remove: bool) -> Result<HistoryEntry> {
+    // Frontmatter stays plain in encrypted entries, so edit the file as stored
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let entry = HistoryEntry::from_markdown(&content, path)?;
+    if entry.id == target {
+        return Err(CliError::new(ErrorCode::InvalidInput, "An entry can't link to itself").into());
+    }
+
+    let mut related = entry.related.clone();
+    if remove {
+        related.retain(|id| id != target);
+    } else if !related.iter().any(|id| id == target) {
+        related.push(target.to_string());
+    }
+    if related == entry.related {
+        return Ok(entry);
+    }
+
+    let content = if related.is_empty() {
+        remove_frontmatter(&content, "related")
+    } else {
+        set_frontmatter(&content, "related", &format!("[{}]", related.join(", ")))
+    };
+    store.write(path, &content)?;
+    HistoryEntry::from_markdown(&content, path)
+}
+
+/// Everything `entry` links to and every entry linking to it
+pub fn links(store: &HistoryStore, entry: &HistoryEntry) -> Result<Links> {
+    let mut by_id = HashMap::new();
+    let mut backlinks = Vec::new();
+    for path in store.paths(None, None)? {
+        let path = path?;
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let other = match HistoryEntry::from_markdown(&content, &path) {
+            Ok(other) => other,
+            Err(e) => {
+                log::debug!("Skipping unparseable history entry {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        if other.id == entry.id {
+            continue;
+        }
+        if other.related.contains(&entry.id) {
+            backlinks.push(other.clone());
+        }
+        if entry.related.contains(&other.id) {
+            by_id.insert(other.id.clone(), other);
+        }
+    }
+
+    let related = entry
+        .related
+        .iter()
+        .map(|id| Related {
+            id: id.clone(),
+            entry: by_id.remove(id),
+        })
+        .collect();
+    Ok(Links { related, backlinks })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_links_and_backlinks() {
+        let temp = tempdir().unwrap();
+        let store = HistoryStore::new(temp.path().to_path_buf());
+        let mut decision = HistoryEntry::new("decisions", "Use SQLite", "Fast enough");
+        decision.id = "a1".to_string();
+        let mut session = HistoryEntry::new("sessions", "Index work", "Built the index");
+        session.id = "b2".to_string();
+        let decision_path = store.store(&decision).unwrap();
+        let session_path = store.store(&session).unwrap();
+
+        let linked = link(&store, &session_path, "a1", false).unwrap();
+        assert_eq!(linked.related, ["a1"]);
+        link(&store, &session_path, "gone", false).unwrap();
+        assert!(link(&store, &session_path, "b2", false).is_err());
+
+        let session = store.get("b2").unwrap().unwrap();
+        assert_eq!(session.related, ["a1", "gone"]);
+        let found = links(&store, &session).unwrap();
+        assert_eq!(found.related[0].entry.as_ref().unwrap().title, "Use SQLite");
+        assert!(found.related[1].entry.is_none());
+        assert!(found.backlinks.is_empty());
+
+        let decision =
+            HistoryEntry::from_markdown(&fs::read_to_string(&decision_path).unwrap(), &decision_path).unwrap();
+        let found = links(&store, &decision).unwrap();
+        assert_eq!(found.backlinks.len(), 1);
+        assert_eq!(found.backlinks[0].id, "b2");
+
+        link(&store, &session_path, "a1", true).unwrap();
+        let unlinked = link(&store, &session_path, "gone", true).unwrap();
+        assert!(unlinked.related.is_empty());
+        assert!(!fs::read_to_string(&session_path).unwrap().contains("related:"));
+    }
+}
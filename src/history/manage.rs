@@ -0,0 +1,187 @@
+//! Changing stored entries by hand
+//!
+//! `pais history edit|tag|move|delete` work on one entry at a time. Each keeps
+//! the frontmatter, the file's location (`<category>/<date>/<id>.md`), the
+//! SQLite index and the review index in agreement: a changed `category:` moves
+//! the file, a move or delete drops the day directory it empties, and a
+//! deleted entry leaves `index/learnings.jsonl`.
+
+use eyre::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::frontmatter::without_key;
+use super::review::{self, ReviewIndex};
+use super::{HistoryEntry, HistoryStore};
+use crate::error::{CliError, ErrorCode};
+
+/// Tags to add and remove, from `+tag` / `-tag` arguments (a bare tag adds)
+#[derive(Debug, Default, PartialEq)]
+pub struct TagChanges {
+    pub add: Vec<String>,
+    pub remove: Vec<String>,
+}
+
+impl TagChanges {
+    pub fn parse(args: &[String]) -> Result<Self, CliError> {
+        let mut changes = Self::default();
+        for arg in args {
+            let (list, tag) = match arg.split_at_checked(1) {
+                Some(("-", tag)) => (&mut changes.remove, tag),
+                Some(("+", tag)) => (&mut changes.add, tag),
+                _ => (&mut changes.add, arg.as_str()),
+            };
+            let tag = tag.trim();
+            if tag.is_empty() || tag.contains([',', '[', ']']) {
+                return Err(CliError::new(ErrorCode::InvalidInput, format!("Invalid tag '{}'", arg))
+                    .with_hint("Tags can't be empty or contain commas or brackets"));
+            }
+            list.push(tag.to_string());
+        }
+        Ok(changes)
+    }
+
+    /// `tags` with the changes applied, keeping existing order
+    pub fn apply(&self, tags: &[String]) -> Vec<String> {
+        let mut tags: Vec<String> = tags.iter().filter(|t| !self.remove.contains(t)).cloned().collect();
+        for tag in &self.add {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+        tags
+    }
+}
+
+/// Remove `key` from a markdown file's frontmatter, leaving the rest unchanged
+pub fn remove_frontmatter(content: &str, key: &str) -> String {
+    let Some(rest) = content.strip_prefix("---\n") else {
+        return content.to_string();
+    };
+    let Some(end) = rest.find("\n---") else {
+        return content.to_string();
+    };
+    let (frontmatter, body) = (&rest[..end], &rest[end..]);
+    let (lines, _) = without_key(frontmatter, key);
+    format!("---\n{}{}", lines.join("\n"), body)
+}
+
+/// Add and remove tags on an entry file, returning the entry as now stored
+pub fn retag(store: &HistoryStore, path: &Path, changes: &TagChanges) -> Result<HistoryEntry> {
+    // Frontmatter stays plain in encrypted entries, so edit the file as stored
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let entry = HistoryEntry::from_markdown(&content, path)?;
+    let tags = changes.apply(&entry.tags);
+    if tags == entry.tags {
+        return Ok(entry);
+    }
+
+    let content = if tags.is_empty() {
+        remove_frontmatter(&content, "tags")
+    } else {
+        review::set_frontmatter(&content, "tags", &format!("[{}]", tags.join(", ")))
+    };
+    store.write(path, &content)?;
+    HistoryEntry::from_markdown(&content, path)
+}
+
+/// Move an entry file to another category, keeping a promoted entry's index record
+pub fn move_to(store: &HistoryStore, path: &Path, category: &str) -> Result<PathBuf> {
+    let target = review::recategorize(store, path, category)?;
+    let index = ReviewIndex::new(store.base_path());
+    if index.entries()?.iter().any(|e| e.path == path) {
+        let content = fs::read_to_string(&target).with_context(|| format!("Failed to read {}", target.display()))?;
+        index.promote(&HistoryEntry::from_markdown(&content, &target)?, &target)?;
+    }
+    Ok(target)
+}
+
+/// Replace an entry's content with an edited (plaintext) version
+///
+/// The edit is re-sealed if encryption is enabled, and moved if its
+/// `category:` changed (to a declared one, with `with_categories`). Returns the entry's path afterwards.
+pub fn replace(store: &HistoryStore, path: &Path, edited: &str) -> Result<PathBuf> {
+    let original = HistoryEntry::from_markdown(&store.read(path)?, path)?;
+    let entry = HistoryEntry::from_markdown(edited, path).context("The edited entry is not valid")?;
+    if entry.id != original.id {
+        return Err(CliError::new(
+            ErrorCode::InvalidInput,
+            format!("The entry ID can't be changed ({} → {})", original.id, entry.id),
+        )
+        .into());
+    }
+
+    if entry.category != original.category
+        && let Some(categories) = &store.categories
+    {
+        categories.check(&entry.category)?;
+    }
+
+    store.write(path, &store.seal(edited)?)?;
+    if entry.category != original.category {
+        return move_to(store, path, &entry.category);
+    }
+    Ok(path.to_path_buf())
+}
+
+/// Delete an entry file and every record of it
+pub fn delete(store: &HistoryStore, path: &Path, id: &str) -> Result<()> {
+    store.remove(path)?;
+    ReviewIndex::new(store.base_path()).remove(id)?;
+    if let Some(parent) = path.parent() {
+        fs::remove_dir(parent).ok();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_tag_changes() {
+        let changes = TagChanges::parse(&args(&["+rust", "-old", "cli"])).unwrap();
+        assert_eq!(changes.add, ["rust", "cli"]);
+        assert_eq!(changes.remove, ["old"]);
+        assert_eq!(changes.apply(&args(&["old", "cli", "keep"])), ["cli", "keep", "rust"]);
+
+        assert!(TagChanges::parse(&args(&["+"])).is_err());
+        assert!(TagChanges::parse(&args(&["a,b"])).is_err());
+    }
+
+    #[test]
+    fn test_manage_entry() {
+        let temp = tempdir().unwrap();
+        let store = HistoryStore::new(temp.path().to_path_buf());
+        let entry = HistoryEntry::new("sessions", "Title", "Body").with_tag("old");
+        let path = store.store(&entry).unwrap();
+
+        let tagged = retag(&store, &path, &TagChanges::parse(&args(&["-old", "+new"])).unwrap()).unwrap();
+        assert_eq!(tagged.tags, ["new"]);
+        let untagged = retag(&store, &path, &TagChanges::parse(&args(&["-new"])).unwrap()).unwrap();
+        assert!(untagged.tags.is_empty());
+        assert!(!fs::read_to_string(&path).unwrap().contains("tags:"));
+
+        let edited = store
+            .read(&path)
+            .unwrap()
+            .replace("category: sessions", "category: learnings");
+        let moved = replace(&store, &path, &edited).unwrap();
+        assert!(moved.starts_with(temp.path().join("learnings")));
+        assert!(!path.exists());
+        assert!(!path.parent().unwrap().exists());
+        assert_eq!(store.get(&entry.id).unwrap().unwrap().category, "learnings");
+
+        let renamed = edited.replace(&format!("id: {}", entry.id), "id: other");
+        assert!(replace(&store, &moved, &renamed).is_err());
+
+        delete(&store, &moved, &entry.id).unwrap();
+        assert!(store.find(&entry.id).unwrap().is_none());
+        assert!(!moved.parent().unwrap().exists());
+    }
+}
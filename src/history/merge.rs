@@ -0,0 +1,285 @@
+//! Merging another machine's history directory
+//!
+//! `pais history merge <dir>` brings a copied history tree (a backup, an
+//! rsync from another machine) into this one without git. Entries are
+//! matched by ID, like `pais history sync` does:
+//!
+//! - **same file**: skipped
+//! - **new ID**: copied to the same `<category>/<date>/` directory, unless an
+//!   entry with the same category, date, title and body already exists here
+//!   under another ID (a duplicate from an earlier copy)
+//! - **same ID, different entry** (written in the same millisecond on both
+//!   machines): stored under the next free ID
+//! - **same ID, same entry, different content** (edited on one side): a
+//!   conflict, left alone unless `--prefer` picks a side
+//!
+//! The JSONL day logs under `raw-events/` and `security/` are merged line by
+//! line, so a day logged on both machines keeps the events of both. The
+//! index is left to the store's writes and `pais history reindex`.
+
+use eyre::{Context, Result};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::review::set_frontmatter;
+use super::sync::{Renamed, next_free_id};
+use super::{HistoryEntry, HistoryStore};
+use crate::sync::Prefer;
+
+/// Directories of JSONL logs merged line by line
+const LOG_DIRS: &[&str] = &["raw-events", "security"];
+
+/// What a merge did (or, in a dry run, would do)
+#[derive(Debug, Default, Serialize)]
+pub struct MergeReport {
+    /// Entries copied in, by ID
+    pub added: Vec<String>,
+    /// Entries already here byte for byte
+    pub identical: usize,
+    /// Incoming entries already here under another ID
+    pub duplicates: Vec<String>,
+    /// Incoming entries stored under a new ID because theirs was taken
+    pub renamed: Vec<Renamed>,
+    /// Entries that differ on the two sides and need `--prefer`
+    pub conflicts: Vec<String>,
+    /// Conflicts settled by `--prefer`
+    pub resolved: Vec<String>,
+    /// Log lines appended to `raw-events/` and `security/`
+    pub log_lines: usize,
+    /// Incoming files that couldn't be read as entries
+    pub unreadable: Vec<PathBuf>,
+}
+
+/// An entry file as stored
+struct Stored {
+    path: PathBuf,
+    content: String,
+    entry: HistoryEntry,
+}
+
+/// What makes two entries the same capture, whatever their IDs
+type Fingerprint = (String, String, String, String);
+
+fn fingerprint(entry: &HistoryEntry) -> Fingerprint {
+    (
+        entry.category.clone(),
+        entry.created_at.to_rfc3339(),
+        entry.title.clone(),
+        entry.content.trim().to_string(),
+    )
+}
+
+/// Entry files under `dir`, parsed as stored (encrypted bodies stay sealed)
+fn stored_entries(dir: &Path, unreadable: &mut Vec<PathBuf>) -> Result<Vec<Stored>> {
+    let mut entries = Vec::new();
+    for path in HistoryStore::new(dir.to_path_buf()).paths(None, None)? {
+        let path = path?;
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        match HistoryEntry::from_markdown(&content, &path) {
+            Ok(entry) => entries.push(Stored { path, content, entry }),
+            Err(e) => {
+                log::debug!("Not merging unparseable entry {}: {}", path.display(), e);
+                unreadable.push(path);
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// Merge the history tree at `other` into `store`
+pub fn merge(store: &HistoryStore, other: &Path, prefer: Option<Prefer>, dry_run: bool) -> Result<MergeReport> {
+    let base = store.base_path();
+    let mut report = MergeReport::default();
+
+    let mut local_unreadable = Vec::new();
+    let local = stored_entries(base, &mut local_unreadable)?;
+    let mut fingerprints: HashSet<Fingerprint> = local.iter().map(|s| fingerprint(&s.entry)).collect();
+    let mut by_id: HashMap<String, Stored> = local.into_iter().map(|s| (s.entry.id.clone(), s)).collect();
+    let mut taken: HashSet<String> = by_id.keys().cloned().collect();
+
+    let mut incoming = stored_entries(other, &mut report.unreadable)?;
+    incoming.sort_by(|a, b| a.path.cmp(&b.path));
+
+    for theirs in incoming {
+        let id = theirs.entry.id.clone();
+        let relative = theirs.path.strip_prefix(other).unwrap_or(&theirs.path).to_path_buf();
+
+        let Some(ours) = by_id.get(&id) else {
+            if !fingerprints.insert(fingerprint(&theirs.entry)) {
+                report.duplicates.push(id);
+                continue;
+            }
+            if !dry_run {
+                write(store, &base.join(&relative), &theirs.content)?;
+            }
+            taken.insert(id.clone());
+            report.added.push(id);
+            continue;
+        };
+
+        if ours.content == theirs.content {
+            report.identical += 1;
+        } else if ours.entry.created_at == theirs.entry.created_at && ours.entry.title == theirs.entry.title {
+            match prefer {
+                Some(Prefer::Remote) => {
+                    if !dry_run {
+                        write(store, &ours.path, &theirs.content)?;
+                    }
+                    report.resolved.push(id);
+                }
+                Some(Prefer::Local) => report.resolved.push(id),
+                None => report.conflicts.push(id),
+            }
+        } else if !fingerprints.insert(fingerprint(&theirs.entry)) {
+            // Renamed by an earlier merge
+            report.duplicates.push(id);
+        } else {
+            let new_id = next_free_id(&id, |candidate| taken.contains(candidate));
+            let dest = base.join(&relative).with_file_name(format!("{}.md", new_id));
+            if !dry_run {
+                write(store, &dest, &set_frontmatter(&theirs.content, "id", &new_id))?;
+            }
+            taken.insert(new_id.clone());
+            report.renamed.push(Renamed {
+                from: relative.to_string_lossy().into_owned(),
+                to: dest.strip_prefix(base).unwrap_or(&dest).to_string_lossy().into_owned(),
+            });
+            // Later files with this ID compare against the entry already here
+            by_id.entry(id).or_insert(theirs);
+        }
+    }
+
+    for dir in LOG_DIRS {
+        report.log_lines += merge_logs(&other.join(dir), &base.join(dir), dry_run)?;
+    }
+    Ok(report)
+}
+
+fn write(store: &HistoryStore, path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    store.write(path, content)
+}
+
+/// Append the lines of each `.jsonl` under `from` missing from its counterpart under `to`
+fn merge_logs(from: &Path, to: &Path, dry_run: bool) -> Result<usize> {
+    if !from.is_dir() {
+        return Ok(0);
+    }
+    let mut appended = 0;
+    for file in WalkDir::new(from).into_iter().filter_map(|e| e.ok()) {
+        let path = file.path();
+        let hidden = file.file_name().to_string_lossy().starts_with('.');
+        if !file.file_type().is_file() || hidden || path.extension().is_none_or(|e| e != "jsonl") {
+            continue;
+        }
+        let target = to.join(path.strip_prefix(from).unwrap_or(path));
+        let incoming = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let existing = fs::read_to_string(&target).unwrap_or_default();
+        let present: HashSet<&str> = existing.lines().collect();
+        let missing: Vec<&str> = incoming
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !present.contains(line))
+            .collect();
+        if missing.is_empty() {
+            continue;
+        }
+        appended += missing.len();
+        if dry_run {
+            continue;
+        }
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let mut out = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&target)
+            .with_context(|| format!("Failed to open {}", target.display()))?;
+        let mut text = String::new();
+        if !existing.is_empty() && !existing.ends_with('\n') {
+            text.push('\n');
+        }
+        for line in missing {
+            text.push_str(line);
+            text.push('\n');
+        }
+        out.write_all(text.as_bytes())
+            .with_context(|| format!("Failed to write {}", target.display()))?;
+    }
+    Ok(appended)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn entry(id: &str, title: &str, body: &str) -> HistoryEntry {
+        let mut entry = HistoryEntry::new("sessions", title, body);
+        entry.id = id.to_string();
+        entry
+    }
+
+    #[test]
+    fn test_merge_settles_ids() {
+        let temp = tempdir().unwrap();
+        let here = HistoryStore::new(temp.path().join("here"));
+        let there = HistoryStore::new(temp.path().join("there"));
+
+        let shared = entry("a0", "Shared", "same on both");
+        here.store(&shared).unwrap();
+        there.store(&shared).unwrap();
+        here.store(&entry("b0", "Mine", "written here")).unwrap();
+        there.store(&entry("b0", "Theirs", "written there")).unwrap();
+        there.store(&entry("c0", "New", "only there")).unwrap();
+        let mut copy = entry("d0", "Copied", "copied earlier");
+        copy.created_at = shared.created_at;
+        here.store(&copy).unwrap();
+        copy.id = "d9".to_string();
+        there.store(&copy).unwrap();
+        let mut edited = entry("e0", "Edited", "before");
+        here.store(&edited).unwrap();
+        edited.content = "after".to_string();
+        there.store(&edited).unwrap();
+
+        let day = "raw-events/2026-10/2026-10-18.jsonl";
+        for (store, lines) in [(&here, "{\"n\":1}\n"), (&there, "{\"n\":1}\n{\"n\":2}\n")] {
+            let path = store.base_path().join(day);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, lines).unwrap();
+        }
+
+        let dry = merge(&here, there.base_path(), None, true).unwrap();
+        assert_eq!(dry.added, ["c0"]);
+        assert!(here.get("c0").unwrap().is_none());
+
+        let report = merge(&here, there.base_path(), None, false).unwrap();
+        assert_eq!(report.identical, 1);
+        assert_eq!(report.added, ["c0"]);
+        assert_eq!(report.duplicates, ["d9"]);
+        assert_eq!(report.conflicts, ["e0"]);
+        assert_eq!(report.renamed.len(), 1);
+        assert!(report.renamed[0].to.ends_with("b1.md"));
+        assert_eq!(report.log_lines, 1);
+
+        assert_eq!(here.get("b0").unwrap().unwrap().title, "Mine");
+        assert_eq!(here.get("b1").unwrap().unwrap().title, "Theirs");
+        assert!(here.get("e0").unwrap().unwrap().content.ends_with("before"));
+        let log = fs::read_to_string(here.base_path().join(day)).unwrap();
+        assert_eq!(log, "{\"n\":1}\n{\"n\":2}\n");
+
+        // Merging again only finds what is already here
+        let again = merge(&here, there.base_path(), Some(Prefer::Remote), false).unwrap();
+        assert!(again.added.is_empty() && again.renamed.is_empty() && again.log_lines == 0);
+        assert_eq!(again.duplicates, ["b0", "d9"]);
+        assert_eq!(again.resolved, ["e0"]);
+        assert!(here.get("e0").unwrap().unwrap().content.ends_with("after"));
+    }
+}
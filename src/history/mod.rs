@@ -3,13 +3,73 @@
 //! Stores session data, learnings, decisions, and insights as markdown files.
 //! Structure: ~/.config/pais/history/<category>/<date>/<id>.md
 //!
+//! Frontmatter is YAML, read with a line-by-line fallback for hand edits
+//! that aren't (see [`frontmatter`]).
+//!
 //! ## Event Capture
 //!
 //! Raw hook events are captured to JSONL files for analysis:
 //! `history/raw-events/YYYY-MM/YYYY-MM-DD.jsonl`
+//!
+//! ## Index
+//!
+//! With `history.index.enabled`, lookups read entry paths from a SQLite
+//! index instead of walking the tree (see [`index`]).
+//!
+//! ## Deduplication
+//!
+//! Stores built `with_dedupe` merge or skip entries that nearly duplicate a
+//! recent one in the same category (see [`dedupe`]).
+//!
+//! ## Encryption
+//!
+//! With `history.encryption.enabled`, entry bodies are encrypted on write and
+//! decrypted when read back (see [`encrypt`]).
+//!
+//! ## Links
+//!
+//! An entry's `related: [id, ...]` frontmatter links it to other entries;
+//! `pais history show` lists those and the entries linking back (see [`links`]).
+//!
+//! ## Merging
+//!
+//! `pais history merge <dir>` merges a history directory copied from another
+//! machine, giving colliding IDs the next free one (see [`merge`]).
+//!
+//! ## Watching
+//!
+//! `pais history watch` prints entries as hooks write them (see [`watch`]).
+//!
+//! ## Weekly Reviews
+//!
+//! `pais review generate --week` stores a week's activity, security events,
+//! agent costs and action items as one entry (see [`weekly`]).
 
+pub mod archive;
+pub mod calendar;
 pub mod capture;
 pub mod categorize;
+pub mod dedupe;
+pub mod embed;
+pub mod encrypt;
+pub mod frontmatter;
+pub mod handoff;
+pub mod index;
+pub mod label;
+pub mod links;
+pub mod manage;
+pub mod merge;
+pub mod notion;
+pub mod publish;
+pub mod redact;
+pub mod review;
+pub mod stats;
+pub mod sync;
+pub mod template;
+pub mod tools;
+pub mod transcript;
+pub mod watch;
+pub mod weekly;
 
 use chrono::{DateTime, Local, NaiveDate};
 use eyre::{Context, Result};
@@ -17,6 +77,16 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::clock;
+use crate::config::DedupeAction;
+use crate::lock::FileLock;
+use categorize::Categories;
+use dedupe::Dedupe;
+use encrypt::Encryption;
+use frontmatter::Frontmatter;
+use index::HistoryIndex;
+use redact::Redactor;
+
 /// A history entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryEntry {
@@ -25,6 +95,9 @@ pub struct HistoryEntry {
     pub title: String,
     pub content: String,
     pub tags: Vec<String>,
+    /// IDs of entries this one links to
+    #[serde(default)]
+    pub related: Vec<String>,
     pub created_at: DateTime<Local>,
     pub metadata: std::collections::HashMap<String, String>,
 }
@@ -32,13 +105,15 @@ pub struct HistoryEntry {
 impl HistoryEntry {
     /// Create a new history entry
     pub fn new(category: &str, title: &str, content: &str) -> Self {
+        let now = clock::now();
         Self {
-            id: generate_id(),
+            id: clock::new_id(now),
             category: category.to_string(),
             title: title.to_string(),
             content: content.to_string(),
             tags: Vec::new(),
-            created_at: Local::now(),
+            related: Vec::new(),
+            created_at: now.with_timezone(&Local),
             metadata: std::collections::HashMap::new(),
         }
     }
@@ -55,25 +130,42 @@ impl HistoryEntry {
         self
     }
 
-    /// Convert to markdown format
-    pub fn to_markdown(&self) -> String {
+    /// Frontmatter lines, without the `---` delimiters
+    pub fn frontmatter(&self) -> String {
+        let list = |items: &[String]| {
+            items
+                .iter()
+                .map(|i| frontmatter::scalar(i))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
         let mut md = String::new();
-
-        // Frontmatter
-        md.push_str("---\n");
-        md.push_str(&format!("id: {}\n", self.id));
-        md.push_str(&format!("title: {}\n", self.title));
-        md.push_str(&format!("category: {}\n", self.category));
+        md.push_str(&format!("id: {}\n", frontmatter::scalar(&self.id)));
+        md.push_str(&format!("title: {}\n", frontmatter::scalar(&self.title)));
+        md.push_str(&format!("category: {}\n", frontmatter::scalar(&self.category)));
         md.push_str(&format!(
             "created_at: {}\n",
             self.created_at.format("%Y-%m-%dT%H:%M:%S%z")
         ));
         if !self.tags.is_empty() {
-            md.push_str(&format!("tags: [{}]\n", self.tags.join(", ")));
+            md.push_str(&format!("tags: [{}]\n", list(&self.tags)));
+        }
+        if !self.related.is_empty() {
+            md.push_str(&format!("related: [{}]\n", list(&self.related)));
         }
         for (key, value) in &self.metadata {
-            md.push_str(&format!("{}: {}\n", key, value));
+            md.push_str(&format!("{}: {}\n", key, frontmatter::metadata_scalar(value)));
         }
+        md
+    }
+
+    /// Convert to markdown format
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+
+        // Frontmatter
+        md.push_str("---\n");
+        md.push_str(&self.frontmatter());
         md.push_str("---\n\n");
 
         // Title
@@ -114,41 +206,24 @@ impl HistoryEntry {
             body.push('\n');
         }
 
-        // Parse frontmatter (simple key: value parsing)
-        let mut id = path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("unknown")
-            .to_string();
-        let mut title = String::new();
-        let mut category = String::new();
-        let mut created_at = Local::now();
-        let mut tags = Vec::new();
-        let mut metadata = std::collections::HashMap::new();
-
-        for line in frontmatter.lines() {
-            if let Some((key, value)) = line.split_once(':') {
-                let key = key.trim();
-                let value = value.trim();
-                match key {
-                    "id" => id = value.to_string(),
-                    "title" => title = value.to_string(),
-                    "category" => category = value.to_string(),
-                    "created_at" => {
-                        if let Ok(dt) = DateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%z") {
-                            created_at = dt.with_timezone(&Local);
-                        }
-                    }
-                    "tags" => {
-                        let tag_str = value.trim_start_matches('[').trim_end_matches(']');
-                        tags = tag_str.split(',').map(|s| s.trim().to_string()).collect();
-                    }
-                    _ => {
-                        metadata.insert(key.to_string(), value.to_string());
-                    }
-                }
-            }
-        }
+        let mut fields = Frontmatter::parse(&frontmatter);
+        let id = fields.id.take().unwrap_or_else(|| {
+            path.file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("unknown")
+                .to_string()
+        });
+        let mut title = fields.title.take().unwrap_or_default();
+        let category = fields.category.take().unwrap_or_default();
+        let created_at = fields
+            .created_at
+            .as_deref()
+            .and_then(|value| DateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%z").ok())
+            .map(|dt| dt.with_timezone(&Local))
+            .unwrap_or_else(clock::now_local);
+        let tags = fields.tags();
+        let related = fields.related();
+        let metadata = fields.metadata().into_iter().collect();
 
         // Extract title from body if not in frontmatter
         if title.is_empty() {
@@ -166,6 +241,7 @@ impl HistoryEntry {
             title,
             content: body.trim().to_string(),
             tags,
+            related,
             created_at,
             metadata,
         })
@@ -175,29 +251,175 @@ impl HistoryEntry {
 /// History storage
 pub struct HistoryStore {
     base_path: PathBuf,
+    redactor: Option<Redactor>,
+    categories: Option<Categories>,
+    index: Option<HistoryIndex>,
+    encryption: Option<Encryption>,
+    dedupe: Option<Dedupe>,
 }
 
 impl HistoryStore {
     /// Create a new history store
     pub fn new(base_path: PathBuf) -> Self {
-        Self { base_path }
+        Self {
+            base_path,
+            redactor: None,
+            categories: None,
+            index: None,
+            encryption: None,
+            dedupe: None,
+        }
+    }
+
+    /// Strip secrets from entries before they are written
+    pub fn with_redactor(mut self, redactor: Option<Redactor>) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
+    /// Only store entries in declared categories, adding their frontmatter
+    pub fn with_categories(mut self, categories: Categories) -> Self {
+        self.categories = Some(categories);
+        self
+    }
+
+    /// Keep the SQLite index up to date and read lookups from it
+    pub fn with_index(mut self, enabled: bool) -> Self {
+        self.index = enabled.then(|| HistoryIndex::new(&self.base_path));
+        self
+    }
+
+    /// Encrypt new entry bodies (when enabled) and decrypt sealed ones on read
+    pub fn with_encryption(mut self, encryption: Encryption) -> Self {
+        self.encryption = Some(encryption.with_salt_file(self.base_path.join(encrypt::SALT_FILE)));
+        self
+    }
+
+    /// Merge or skip entries that nearly duplicate a recent one in their category
+    pub fn with_dedupe(mut self, dedupe: Option<Dedupe>) -> Self {
+        self.dedupe = dedupe;
+        self
+    }
+
+    /// Directory the categories live in
+    pub fn base_path(&self) -> &Path {
+        &self.base_path
     }
 
     /// Store an entry
     pub fn store(&self, entry: &HistoryEntry) -> Result<PathBuf> {
+        let mut entry = entry.clone();
+        let mut template = None;
+        if let Some(categories) = &self.categories {
+            let category = categories.check(&entry.category)?;
+            for (key, value) in &category.frontmatter {
+                entry.metadata.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+            template = category.template.as_deref();
+        }
+        if let Some(redactor) = &self.redactor {
+            let count = redactor.redact_entry(&mut entry);
+            if count > 0 {
+                log::info!(entry = entry.id.as_str(); "Redacted {} secret(s) from history entry", count);
+            }
+        }
+
+        if let Some(dedupe) = &self.dedupe {
+            match dedupe.find(self, &entry) {
+                Ok(Some((existing, similarity))) => {
+                    log::info!(
+                        entry = entry.id.as_str();
+                        "Near-duplicate of {} ({:.2}), {:?}",
+                        existing.display(),
+                        similarity,
+                        dedupe.action
+                    );
+                    if dedupe.action == DedupeAction::Merge {
+                        dedupe::fold(self, &existing, &entry)?;
+                    }
+                    return Ok(existing);
+                }
+                Ok(None) => {}
+                // A recent entry that can't be read shouldn't stop this one being stored
+                Err(e) => log::warn!("Skipping the duplicate check: {:#}", e),
+            }
+        }
+
         let date = entry.created_at.format("%Y-%m-%d").to_string();
         let dir = self.base_path.join(&entry.category).join(&date);
         fs::create_dir_all(&dir).context("Failed to create history directory")?;
 
         let filename = format!("{}.md", entry.id);
         let path = dir.join(&filename);
-
-        fs::write(&path, entry.to_markdown()).context("Failed to write history entry")?;
+        let markdown = match template {
+            Some(spec) => template::apply(spec, &entry).unwrap_or_else(|e| {
+                log::warn!(
+                    "The {} template failed ({:#}); using the default layout",
+                    entry.category,
+                    e
+                );
+                entry.to_markdown()
+            }),
+            None => entry.to_markdown(),
+        };
+        self.write(&path, &self.seal(&markdown)?)?;
 
         log::info!("Stored history entry: {}", path.display());
         Ok(path)
     }
 
+    /// Replace an entry file's content
+    pub fn write(&self, path: &Path, content: &str) -> Result<()> {
+        // Hooks and CLI commands store concurrently; write whole files one at a time
+        let _lock = FileLock::acquire(&self.base_path)?;
+        let tmp = path.with_extension(format!("md.{}", std::process::id()));
+        fs::write(&tmp, content).context("Failed to write history entry")?;
+        fs::rename(&tmp, path).context("Failed to write history entry")?;
+
+        if let Some(index) = &self.index {
+            // The file is written; a stale index only costs a reindex
+            let indexed = HistoryEntry::from_markdown(content, path).and_then(|entry| index.upsert(&entry, path));
+            if let Err(e) = indexed {
+                log::warn!("History index is out of date ({:#}); run `pais history reindex`", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Entry markdown as it should be written: body encrypted when enabled
+    pub fn seal(&self, markdown: &str) -> Result<String> {
+        match self.encryption.as_ref().filter(|e| e.enabled()) {
+            Some(encryption) => encryption.seal_markdown(markdown),
+            None => Ok(markdown.to_string()),
+        }
+    }
+
+    /// An entry file's content, with its body decrypted
+    pub fn read(&self, path: &Path) -> Result<String> {
+        let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        match &self.encryption {
+            Some(encryption) if encrypt::is_sealed(&content) => encryption
+                .open_markdown(&content)
+                .with_context(|| format!("Failed to read {}", path.display())),
+            _ => Ok(content),
+        }
+    }
+
+    /// Delete an entry file
+    pub fn remove(&self, path: &Path) -> Result<()> {
+        fs::remove_file(path).with_context(|| format!("Failed to delete {}", path.display()))?;
+        self.unindex(path);
+        Ok(())
+    }
+
+    fn unindex(&self, path: &Path) {
+        if let Some(index) = &self.index
+            && let Err(e) = index.remove(path)
+        {
+            log::warn!("History index is out of date ({:#}); run `pais history reindex`", e);
+        }
+    }
+
     /// List categories
     pub fn categories(&self) -> Result<Vec<String>> {
         if !self.base_path.exists() {
@@ -239,53 +461,164 @@ impl HistoryStore {
         Ok(count)
     }
 
-    /// Get recent entries
-    pub fn recent(&self, category: Option<&str>, limit: usize) -> Result<Vec<HistoryEntry>> {
-        let mut entries = Vec::new();
-        let mut paths = Vec::new();
+    /// Delete entries of `category` from days before `before`, returning
+    /// their paths (nothing is deleted when `dry_run`)
+    pub fn prune(&self, category: &str, before: NaiveDate, dry_run: bool) -> Result<Vec<PathBuf>> {
+        let cat_path = self.base_path.join(category);
+        if !cat_path.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let _lock = (!dry_run).then(|| FileLock::acquire(&self.base_path)).transpose()?;
+        let mut pruned = Vec::new();
+        for date_entry in fs::read_dir(&cat_path)? {
+            let date_path = date_entry?.path();
+            let is_old = date_path
+                .file_name()
+                .and_then(|s| s.to_str())
+                .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+                .is_some_and(|date| date < before);
+            if !is_old || !date_path.is_dir() {
+                continue;
+            }
+            for file in fs::read_dir(&date_path)? {
+                let path = file?.path();
+                if path.extension().is_some_and(|e| e == "md") {
+                    if !dry_run {
+                        fs::remove_file(&path).with_context(|| format!("Failed to delete {}", path.display()))?;
+                        self.unindex(&path);
+                    }
+                    pruned.push(path);
+                }
+            }
+            if !dry_run {
+                // Keeps the day if anything besides entries is left in it
+                fs::remove_dir(&date_path).ok();
+            }
+        }
+        pruned.sort();
+        Ok(pruned)
+    }
 
-        // Collect all entry paths
+    /// Entry files, newest first, optionally limited to one category and to dates on or after `since`.
+    ///
+    /// Only the category directories are listed up front; each date directory is
+    /// read when the iterator reaches it, so taking a few entries stays cheap
+    /// however large the history is.
+    pub fn paths(&self, category: Option<&str>, since: Option<NaiveDate>) -> Result<HistoryPaths> {
         let categories: Vec<String> = match category {
             Some(c) => vec![c.to_string()],
             None => self.categories()?,
         };
 
+        let mut days = Vec::new();
         for cat in categories {
             let cat_path = self.base_path.join(&cat);
-            if !cat_path.exists() {
+            if !cat_path.is_dir() {
                 continue;
             }
 
             for date_entry in fs::read_dir(&cat_path)? {
-                let date_entry = date_entry?;
-                if date_entry.path().is_dir() {
-                    for file_entry in fs::read_dir(date_entry.path())? {
-                        let file_entry = file_entry?;
-                        let path = file_entry.path();
-                        if path.extension().map(|e| e == "md").unwrap_or(false) {
-                            paths.push(path);
-                        }
-                    }
+                let date_path = date_entry?.path();
+                // Entries live in YYYY-MM-DD directories; anything else (e.g. raw-events) is skipped
+                let Some(date) = date_path
+                    .file_name()
+                    .and_then(|s| s.to_str())
+                    .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+                else {
+                    continue;
+                };
+                if since.is_some_and(|since| date < since) || !date_path.is_dir() {
+                    continue;
                 }
+                days.push((date, date_path));
             }
         }
+        days.sort();
 
-        // Sort by modification time (newest first)
-        paths.sort_by(|a, b| {
-            let a_time = fs::metadata(a).and_then(|m| m.modified()).ok();
-            let b_time = fs::metadata(b).and_then(|m| m.modified()).ok();
-            b_time.cmp(&a_time)
-        });
+        Ok(HistoryPaths {
+            days,
+            files: Vec::new(),
+        })
+    }
 
-        // Load entries up to limit
-        for path in paths.into_iter().take(limit) {
-            let content = fs::read_to_string(&path)?;
-            if let Ok(entry) = HistoryEntry::from_markdown(&content, &path) {
-                entries.push(entry);
+    /// Entry files for lookups, newest first: from the index when it is
+    /// enabled and built, else from [`Self::paths`]
+    fn lookup_paths(
+        &self,
+        category: Option<&str>,
+        since: Option<NaiveDate>,
+    ) -> Result<Box<dyn Iterator<Item = Result<PathBuf>>>> {
+        if let Some(index) = self.index.as_ref().filter(|index| index.exists()) {
+            match index.paths(category, since) {
+                // Files removed behind the store's back are skipped until the next reindex
+                Ok(paths) => return Ok(Box::new(paths.into_iter().filter(|path| path.is_file()).map(Ok))),
+                Err(e) => log::warn!("Not using the history index: {:#}", e),
             }
         }
+        Ok(Box::new(self.paths(category, since)?))
+    }
 
-        Ok(entries)
+    /// Entries, newest first, parsed lazily; unparseable files are skipped
+    pub fn entries(
+        &self,
+        category: Option<&str>,
+        since: Option<NaiveDate>,
+    ) -> Result<impl Iterator<Item = Result<HistoryEntry>>> {
+        Ok(self.lookup_paths(category, since)?.filter_map(|path| {
+            let path = match path {
+                Ok(path) => path,
+                Err(e) => return Some(Err(e)),
+            };
+            let content = match self.read(&path) {
+                Ok(content) => content,
+                Err(e) => return Some(Err(e)),
+            };
+            match HistoryEntry::from_markdown(&content, &path) {
+                Ok(entry) => Some(Ok(entry)),
+                Err(e) => {
+                    log::debug!("Skipping unparseable history entry {}: {}", path.display(), e);
+                    None
+                }
+            }
+        }))
+    }
+
+    /// Get recent entries
+    pub fn recent(&self, category: Option<&str>, limit: usize) -> Result<Vec<HistoryEntry>> {
+        self.entries(category, None)?.take(limit).collect()
+    }
+
+    /// Find an entry file by ID or ID prefix (newest match wins)
+    pub fn find(&self, id: &str) -> Result<Option<PathBuf>> {
+        if let Some(index) = self.index.as_ref().filter(|index| index.exists()) {
+            match index.find(id) {
+                Ok(Some(path)) => return Ok(Some(path)),
+                // Not indexed (yet): it may still be on disk
+                Ok(None) => {}
+                Err(e) => log::warn!("Not using the history index: {:#}", e),
+            }
+        }
+        for path in self.paths(None, None)? {
+            let path = path?;
+            if path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .is_some_and(|stem| stem.starts_with(id))
+            {
+                return Ok(Some(path));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Load an entry by ID or ID prefix
+    pub fn get(&self, id: &str) -> Result<Option<HistoryEntry>> {
+        let Some(path) = self.find(id)? else {
+            return Ok(None);
+        };
+        let content = self.read(&path)?;
+        HistoryEntry::from_markdown(&content, &path).map(Some)
     }
 
     /// Query entries with regex
@@ -299,72 +632,73 @@ impl HistoryStore {
         let regex = regex::Regex::new(pattern).context("Invalid regex pattern")?;
         let mut entries = Vec::new();
 
-        let categories: Vec<String> = match category {
-            Some(c) => vec![c.to_string()],
-            None => self.categories()?,
-        };
+        for path in self.lookup_paths(category, since)? {
+            let path = path?;
+            let content = self.read(&path)?;
 
-        for cat in categories {
-            let cat_path = self.base_path.join(&cat);
-            if !cat_path.exists() {
+            if !regex.is_match(&content) {
                 continue;
             }
 
-            for date_entry in fs::read_dir(&cat_path)? {
-                let date_entry = date_entry?;
-                let date_path = date_entry.path();
-
-                if !date_path.is_dir() {
-                    continue;
+            if let Ok(entry) = HistoryEntry::from_markdown(&content, &path) {
+                entries.push(entry);
+                if entries.len() >= limit {
+                    break;
                 }
+            }
+        }
 
-                // Check date filter
-                if let Some(since_date) = since
-                    && let Some(date_str) = date_path.file_name().and_then(|s| s.to_str())
-                    && let Ok(entry_date) = NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
-                    && entry_date < since_date
-                {
-                    continue;
-                }
+        Ok(entries)
+    }
+}
 
-                for file_entry in fs::read_dir(&date_path)? {
-                    let file_entry = file_entry?;
-                    let path = file_entry.path();
+/// Iterator over entry files, newest first (see [`HistoryStore::paths`])
+pub struct HistoryPaths {
+    /// Date directories not yet read, oldest first so `pop` yields the newest
+    days: Vec<(NaiveDate, PathBuf)>,
+    /// Files from the current date (across categories), oldest first
+    files: Vec<PathBuf>,
+}
 
-                    if !path.extension().map(|e| e == "md").unwrap_or(false) {
-                        continue;
-                    }
+impl Iterator for HistoryPaths {
+    type Item = Result<PathBuf>;
 
-                    let content = fs::read_to_string(&path)?;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(path) = self.files.pop() {
+                return Some(Ok(path));
+            }
 
-                    if !regex.is_match(&content) {
-                        continue;
-                    }
+            // Read every category's directory for the next date together so
+            // entries from the same day interleave correctly
+            let (date, dir) = self.days.pop()?;
+            let mut dirs = vec![dir];
+            while self.days.last().is_some_and(|(d, _)| *d == date) {
+                dirs.extend(self.days.pop().map(|(_, dir)| dir));
+            }
 
-                    if let Ok(entry) = HistoryEntry::from_markdown(&content, &path) {
-                        entries.push(entry);
-                        if entries.len() >= limit {
-                            return Ok(entries);
-                        }
+            for dir in dirs {
+                let read = fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()));
+                let entries = match read {
+                    Ok(entries) => entries,
+                    Err(e) => return Some(Err(e)),
+                };
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    if path.extension().is_some_and(|e| e == "md") {
+                        self.files.push(path);
                     }
                 }
             }
+            self.files.sort_by_cached_key(|path| entry_order(path));
         }
-
-        // Sort by date (newest first)
-        entries.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-        Ok(entries)
     }
 }
 
-/// Generate a unique ID for an entry
-fn generate_id() -> String {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_millis())
-        .unwrap_or(0); // Fallback to 0 if system time is before UNIX_EPOCH (shouldn't happen)
-    format!("{:x}", timestamp)
+/// Sort key within a day: IDs are hex millisecond timestamps, so they order by creation
+fn entry_order(path: &Path) -> (u128, String) {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    (u128::from_str_radix(stem, 16).unwrap_or(0), stem.to_string())
 }
 
 #[cfg(test)]
@@ -384,12 +718,107 @@ mod tests {
         assert!(md.contains("This is a test"));
     }
 
+    #[test]
+    fn test_frontmatter_round_trip() {
+        let mut entry = HistoryEntry::new("decisions", "Use SQLite: it's # fast", "Body\n\n---\n\nMore")
+            .with_tag("db")
+            .with_tag("-odd")
+            .with_metadata("cwd", "/src/app")
+            .with_metadata("notes", "first line\nsecond: line")
+            .with_metadata("duplicates", "3")
+            .with_metadata("owner", r#"{"team":"infra"}"#)
+            .with_metadata("quoted", "'single'");
+        entry.id = "19695913800".to_string();
+        entry.related = vec!["abc123".to_string()];
+
+        let parsed = HistoryEntry::from_markdown(&entry.to_markdown(), Path::new("x.md")).unwrap();
+        assert_eq!(parsed.id, entry.id);
+        assert_eq!(parsed.title, entry.title);
+        assert_eq!(parsed.tags, entry.tags);
+        assert_eq!(parsed.related, entry.related);
+        assert_eq!(parsed.metadata, entry.metadata);
+        assert_eq!(parsed.created_at.timestamp(), entry.created_at.timestamp());
+        assert_eq!(parsed.content, format!("# {}\n\n{}", entry.title, entry.content));
+
+        // Hand-written frontmatter that isn't valid YAML still reads
+        let loose = HistoryEntry::from_markdown(
+            "---\nid: 0042\ntitle: a: b\ntags: [x, y]\n---\n\nBody\n",
+            Path::new("x.md"),
+        )
+        .unwrap();
+        assert_eq!(loose.id, "0042");
+        assert_eq!(loose.title, "a: b");
+        assert_eq!(loose.tags, ["x", "y"]);
+    }
+
+    fn store_at(store: &HistoryStore, category: &str, title: &str, date: &str, id: &str) {
+        let mut entry = HistoryEntry::new(category, title, "body");
+        entry.id = id.to_string();
+        entry.created_at = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+            .and_local_timezone(Local)
+            .unwrap();
+        store.store(&entry).unwrap();
+    }
+
+    #[test]
+    fn test_paths_newest_first_across_categories() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = HistoryStore::new(temp.path().to_path_buf());
+        store_at(&store, "learnings", "old", "2026-01-01", "a0");
+        store_at(&store, "sessions", "mid", "2026-02-01", "b0");
+        store_at(&store, "learnings", "new", "2026-03-01", "c0");
+        store_at(&store, "sessions", "newest", "2026-03-01", "c1");
+        fs::create_dir_all(temp.path().join("raw-events").join("2026-03")).unwrap();
+
+        let titles: Vec<String> = store.recent(None, 10).unwrap().into_iter().map(|e| e.title).collect();
+        assert_eq!(titles, vec!["newest", "new", "mid", "old"]);
+
+        // Stops after the limit without reading older days
+        let mut paths = store.paths(None, None).unwrap();
+        paths.next().unwrap().unwrap();
+        assert_eq!(paths.days.len(), 2);
+
+        let since = NaiveDate::from_ymd_opt(2026, 2, 1);
+        assert_eq!(store.paths(Some("sessions"), since).unwrap().count(), 2);
+        assert_eq!(store.query("body", None, since, 2).unwrap().len(), 2);
+        assert_eq!(store.find("b").unwrap().unwrap().file_stem().unwrap(), "b0");
+    }
+
+    #[test]
+    fn test_store_checks_categories_and_prunes() {
+        let temp = tempfile::tempdir().unwrap();
+        let mut categories = crate::config::HistoryConfig::default();
+        categories.categories[0]
+            .frontmatter
+            .insert("kind".into(), "lesson".into());
+        let store = HistoryStore::new(temp.path().to_path_buf()).with_categories(Categories::from_config(&categories));
+
+        let err = store.store(&HistoryEntry::new("adhoc", "t", "b")).unwrap_err();
+        assert!(err.to_string().contains("not declared"));
+        assert!(!temp.path().join("adhoc").exists());
+
+        store_at(&store, "learnings", "old", "2026-01-01", "a0");
+        store_at(&store, "learnings", "new", "2026-03-01", "b0");
+        assert_eq!(store.get("a0").unwrap().unwrap().metadata["kind"], "lesson");
+
+        let before = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
+        assert_eq!(store.prune("learnings", before, true).unwrap().len(), 1);
+        assert_eq!(store.count("learnings").unwrap(), 2);
+        store.prune("learnings", before, false).unwrap();
+        assert_eq!(store.count("learnings").unwrap(), 1);
+        assert!(!temp.path().join("learnings").join("2026-01-01").exists());
+    }
+
     #[test]
     fn test_generate_id() {
-        let id1 = generate_id();
+        let id1 = HistoryEntry::new("sessions", "a", "").id;
         std::thread::sleep(std::time::Duration::from_millis(2));
-        let id2 = generate_id();
+        let entry = HistoryEntry::new("sessions", "b", "");
         assert!(!id1.is_empty());
-        assert_ne!(id1, id2);
+        assert_ne!(id1, entry.id);
+        assert_eq!(entry.id, format!("{:x}", entry.created_at.timestamp_millis()));
     }
 }
@@ -0,0 +1,418 @@
+//! Export history entries to a Notion database
+//!
+//! Each entry becomes a page: the title, tags, category and date become page
+//! properties and the markdown body becomes blocks. A per-database state file
+//! remembers which page each entry went to and a hash of what was sent, so
+//! re-running only creates new entries and rewrites changed ones.
+
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::HistoryEntry;
+use crate::config::NotionPublishConfig;
+
+const NOTION_API: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+
+/// Notion rejects rich text longer than this
+const MAX_TEXT: usize = 2000;
+
+/// Notion accepts at most this many blocks per request
+const MAX_BLOCKS: usize = 100;
+
+/// Where an entry was exported to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedPage {
+    pub page_id: String,
+    pub hash: String,
+}
+
+/// Entry ID -> page for one database
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotionState {
+    #[serde(default)]
+    pub pages: BTreeMap<String, ExportedPage>,
+}
+
+impl NotionState {
+    /// State file for a database
+    pub fn path(database: &str) -> PathBuf {
+        dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("pais")
+            .join("notion")
+            .join(format!("{}.json", database))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// What to do with an entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportAction {
+    Create,
+    /// Rewrite an existing page
+    Update(String),
+    Unchanged,
+}
+
+/// Decide per entry whether it is new, changed or already exported
+pub fn plan<'a>(entries: &'a [HistoryEntry], state: &NotionState) -> Vec<(&'a HistoryEntry, ExportAction)> {
+    entries
+        .iter()
+        .map(|entry| {
+            let action = match state.pages.get(&entry.id) {
+                None => ExportAction::Create,
+                Some(page) if page.hash == entry_hash(entry) => ExportAction::Unchanged,
+                Some(page) => ExportAction::Update(page.page_id.clone()),
+            };
+            (entry, action)
+        })
+        .collect()
+}
+
+/// Stable content hash (FNV-1a) of everything that ends up in Notion
+pub fn entry_hash(entry: &HistoryEntry) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in entry.to_markdown().bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Page properties for an entry
+pub fn properties(entry: &HistoryEntry, config: &NotionPublishConfig) -> Value {
+    let mut props = serde_json::Map::new();
+    props.insert(
+        config.title_property.clone(),
+        json!({ "title": rich_text(&entry.title) }),
+    );
+    // Commas are not allowed in select option names
+    let tags: Vec<Value> = entry
+        .tags
+        .iter()
+        .filter(|t| !t.is_empty())
+        .map(|t| json!({ "name": t.replace(',', " ") }))
+        .collect();
+    props.insert(config.tags_property.clone(), json!({ "multi_select": tags }));
+    props.insert(
+        config.category_property.clone(),
+        json!({ "select": { "name": entry.category } }),
+    );
+    if let Some(ref date) = config.date_property {
+        props.insert(
+            date.clone(),
+            json!({ "date": { "start": entry.created_at.to_rfc3339() } }),
+        );
+    }
+    Value::Object(props)
+}
+
+/// Split text into rich text objects within Notion's length limit
+fn rich_text(text: &str) -> Vec<Value> {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .chunks(MAX_TEXT)
+        .map(|chunk| json!({ "type": "text", "text": { "content": chunk.iter().collect::<String>() } }))
+        .collect()
+}
+
+fn block(kind: &str, text: &str) -> Value {
+    json!({ "object": "block", "type": kind, kind: { "rich_text": rich_text(text) } })
+}
+
+/// Convert markdown to Notion blocks (headings, lists, quotes, code, paragraphs)
+pub fn markdown_to_blocks(markdown: &str) -> Vec<Value> {
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut code: Option<(String, Vec<&str>)> = None;
+
+    let flush = |paragraph: &mut Vec<&str>, blocks: &mut Vec<Value>| {
+        if !paragraph.is_empty() {
+            blocks.push(block("paragraph", &paragraph.join("\n")));
+            paragraph.clear();
+        }
+    };
+
+    for line in markdown.lines() {
+        if let Some((lang, body)) = code.as_mut() {
+            if line.trim_start().starts_with("```") {
+                let language = if lang.is_empty() { "plain text".to_string() } else { lang.clone() };
+                blocks.push(json!({
+                    "object": "block",
+                    "type": "code",
+                    "code": { "rich_text": rich_text(&body.join("\n")), "language": language }
+                }));
+                code = None;
+            } else {
+                body.push(line);
+            }
+            continue;
+        }
+
+        let trimmed = line.trim();
+        if let Some(lang) = trimmed.strip_prefix("```") {
+            flush(&mut paragraph, &mut blocks);
+            code = Some((lang.trim().to_string(), Vec::new()));
+        } else if trimmed.is_empty() {
+            flush(&mut paragraph, &mut blocks);
+        } else if let Some(text) = trimmed.strip_prefix("### ") {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(block("heading_3", text));
+        } else if let Some(text) = trimmed.strip_prefix("## ") {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(block("heading_2", text));
+        } else if let Some(text) = trimmed.strip_prefix("# ") {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(block("heading_1", text));
+        } else if let Some(text) = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(block("bulleted_list_item", text));
+        } else if let Some(text) = numbered_item(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(block("numbered_list_item", text));
+        } else if let Some(text) = trimmed.strip_prefix("> ") {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(block("quote", text));
+        } else {
+            paragraph.push(trimmed);
+        }
+    }
+
+    // An unterminated fence still keeps its content
+    if let Some((_, body)) = code {
+        paragraph.extend(body);
+    }
+    flush(&mut paragraph, &mut blocks);
+    blocks
+}
+
+/// `1. item` -> `item`
+fn numbered_item(line: &str) -> Option<&str> {
+    let (number, rest) = line.split_once(". ")?;
+    (!number.is_empty() && number.chars().all(|c| c.is_ascii_digit())).then_some(rest)
+}
+
+/// Entry content without the `# Title` heading already shown as the page title
+fn body(entry: &HistoryEntry) -> &str {
+    let content = entry.content.trim_start();
+    content
+        .strip_prefix(&format!("# {}", entry.title))
+        .map(str::trim_start)
+        .unwrap_or(content)
+}
+
+/// Thin client for the endpoints the export needs
+pub struct NotionClient {
+    token: String,
+}
+
+impl NotionClient {
+    pub fn new(token: String) -> Self {
+        Self { token }
+    }
+
+    fn request(&self, method: &str, path: &str, body: Option<&Value>) -> Result<Value> {
+        let url = format!("{}{}", NOTION_API, path);
+        let auth = format!("Bearer {}", self.token);
+        let mut response = match (method, body) {
+            ("GET", _) => ureq::get(&url)
+                .header("Authorization", &auth)
+                .header("Notion-Version", NOTION_VERSION)
+                .call(),
+            ("DELETE", _) => ureq::delete(&url)
+                .header("Authorization", &auth)
+                .header("Notion-Version", NOTION_VERSION)
+                .call(),
+            (method, body) => {
+                let body = body.cloned().unwrap_or(Value::Null).to_string();
+                let request = if method == "PATCH" { ureq::patch(&url) } else { ureq::post(&url) };
+                request
+                    .header("Authorization", &auth)
+                    .header("Notion-Version", NOTION_VERSION)
+                    .header("Content-Type", "application/json")
+                    .send(body.as_bytes())
+            }
+        }
+        .with_context(|| format!("Notion {} {} failed", method, path))?;
+
+        let text = response
+            .body_mut()
+            .read_to_string()
+            .context("Failed to read Notion response")?;
+        serde_json::from_str(&text).context("Failed to parse Notion response")
+    }
+
+    /// Create a page for an entry; returns the page ID
+    pub fn create_page(&self, database: &str, entry: &HistoryEntry, config: &NotionPublishConfig) -> Result<String> {
+        let blocks = markdown_to_blocks(body(entry));
+        let mut chunks = blocks.chunks(MAX_BLOCKS);
+        let first: Vec<Value> = chunks.next().map(|c| c.to_vec()).unwrap_or_default();
+
+        let page = self.request(
+            "POST",
+            "/pages",
+            Some(&json!({
+                "parent": { "database_id": database },
+                "properties": properties(entry, config),
+                "children": first,
+            })),
+        )?;
+        let page_id = page["id"]
+            .as_str()
+            .ok_or_else(|| eyre::eyre!("No page ID in Notion response"))?
+            .to_string();
+
+        for chunk in chunks {
+            self.append(&page_id, chunk)?;
+        }
+        Ok(page_id)
+    }
+
+    /// Replace an existing page's properties and content
+    pub fn update_page(&self, page_id: &str, entry: &HistoryEntry, config: &NotionPublishConfig) -> Result<()> {
+        self.request(
+            "PATCH",
+            &format!("/pages/{}", page_id),
+            Some(&json!({ "properties": properties(entry, config) })),
+        )?;
+
+        // Notion has no "replace children": delete the old blocks, then append
+        loop {
+            let children = self.request("GET", &format!("/blocks/{}/children?page_size=100", page_id), None)?;
+            let ids: Vec<&str> = children["results"]
+                .as_array()
+                .map(|r| r.iter().filter_map(|b| b["id"].as_str()).collect())
+                .unwrap_or_default();
+            if ids.is_empty() {
+                break;
+            }
+            for id in ids {
+                self.request("DELETE", &format!("/blocks/{}", id), None)?;
+            }
+        }
+
+        for chunk in markdown_to_blocks(body(entry)).chunks(MAX_BLOCKS) {
+            self.append(page_id, chunk)?;
+        }
+        Ok(())
+    }
+
+    fn append(&self, page_id: &str, blocks: &[Value]) -> Result<()> {
+        self.request(
+            "PATCH",
+            &format!("/blocks/{}/children", page_id),
+            Some(&json!({ "children": blocks })),
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_to_blocks() {
+        let md =
+            "# Title\n\nFirst line\nsecond line\n\n- one\n* two\n1. first\n> quoted\n\n```rust\nfn main() {}\n```\n";
+        let blocks = markdown_to_blocks(md);
+        let kinds: Vec<&str> = blocks.iter().map(|b| b["type"].as_str().unwrap()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "heading_1",
+                "paragraph",
+                "bulleted_list_item",
+                "bulleted_list_item",
+                "numbered_list_item",
+                "quote",
+                "code"
+            ]
+        );
+        assert_eq!(
+            blocks[1]["paragraph"]["rich_text"][0]["text"]["content"],
+            "First line\nsecond line"
+        );
+        assert_eq!(blocks[6]["code"]["language"], "rust");
+    }
+
+    #[test]
+    fn test_long_text_is_split() {
+        let text = "x".repeat(MAX_TEXT + 10);
+        let parts = rich_text(&text);
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[1]["text"]["content"].as_str().unwrap().len(), 10);
+    }
+
+    #[test]
+    fn test_plan_is_incremental() {
+        let a = HistoryEntry::new("learnings", "A", "alpha");
+        let mut b = HistoryEntry::new("learnings", "B", "beta");
+        b.id = "b".to_string();
+        let c = HistoryEntry {
+            id: "c".to_string(),
+            ..HistoryEntry::new("decisions", "C", "gamma")
+        };
+
+        let mut state = NotionState::default();
+        state.pages.insert(
+            b.id.clone(),
+            ExportedPage {
+                page_id: "page-b".to_string(),
+                hash: entry_hash(&b),
+            },
+        );
+        state.pages.insert(
+            c.id.clone(),
+            ExportedPage {
+                page_id: "page-c".to_string(),
+                hash: "stale".to_string(),
+            },
+        );
+
+        let entries = vec![a, b, c];
+        let actions: Vec<ExportAction> = plan(&entries, &state).into_iter().map(|(_, a)| a).collect();
+        assert_eq!(
+            actions,
+            vec![
+                ExportAction::Create,
+                ExportAction::Unchanged,
+                ExportAction::Update("page-c".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_properties_use_configured_names() {
+        let config = NotionPublishConfig {
+            date_property: Some("When".to_string()),
+            ..Default::default()
+        };
+        let entry = HistoryEntry::new("learnings", "Title", "body").with_tag("ops");
+        let props = properties(&entry, &config);
+        assert_eq!(props["Name"]["title"][0]["text"]["content"], "Title");
+        assert_eq!(props["Tags"]["multi_select"][0]["name"], "ops");
+        assert_eq!(props["Category"]["select"]["name"], "learnings");
+        assert!(props["When"]["date"]["start"].is_string());
+    }
+}
@@ -0,0 +1,256 @@
+//! Publish history entries to team tools
+//!
+//! `pais history publish <id> --to slack|linear|jira` renders an entry with a
+//! per-target template and pushes it: a Slack message through an incoming
+//! webhook, or a Linear/Jira issue. Templates use `{placeholders}`:
+//! `{id}`, `{title}`, `{category}`, `{date}`, `{tags}`, `{summary}`, `{content}`.
+
+use eyre::{Context, Result};
+use serde_json::{Value, json};
+
+use super::HistoryEntry;
+use crate::config::PublishConfig;
+use crate::error::{CliError, ErrorCode};
+use crate::secrets::SecretStore;
+
+const SLACK_TEMPLATE: &str = "*{title}*\n_{category} · {date}_\n\n{summary}";
+const LINEAR_TEMPLATE: &str = "{content}\n\n---\n_From PAIS history `{id}` ({category}, {date})_";
+const JIRA_TEMPLATE: &str = "{content}\n\n----\nFrom PAIS history {id} ({category}, {date})";
+
+const LINEAR_API: &str = "https://api.linear.app/graphql";
+const LINEAR_ISSUE_CREATE: &str = "mutation IssueCreate($input: IssueCreateInput!) { issueCreate(input: $input) { success issue { identifier url } } }";
+
+/// Where to publish an entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PublishTarget {
+    Slack,
+    Linear,
+    Jira,
+}
+
+impl std::fmt::Display for PublishTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PublishTarget::Slack => write!(f, "slack"),
+            PublishTarget::Linear => write!(f, "linear"),
+            PublishTarget::Jira => write!(f, "jira"),
+        }
+    }
+}
+
+/// Entry content without the `# Title` heading stored files start with
+fn body(entry: &HistoryEntry) -> &str {
+    let content = entry.content.trim_start();
+    match content.strip_prefix(&format!("# {}", entry.title)) {
+        Some(rest) => rest.trim_start(),
+        None => content,
+    }
+}
+
+/// First paragraph of the body, cut at `max_len` characters
+fn summary(entry: &HistoryEntry, max_len: usize) -> String {
+    let paragraph = body(entry).split("\n\n").next().unwrap_or_default().trim();
+    if paragraph.chars().count() <= max_len {
+        paragraph.to_string()
+    } else {
+        let cut: String = paragraph.chars().take(max_len).collect();
+        format!("{}…", cut.trim_end())
+    }
+}
+
+/// Fill a template's placeholders from an entry
+pub fn render(template: &str, entry: &HistoryEntry) -> String {
+    template
+        .replace("{id}", &entry.id)
+        .replace("{title}", &entry.title)
+        .replace("{category}", &entry.category)
+        .replace("{date}", &entry.created_at.format("%Y-%m-%d").to_string())
+        .replace("{tags}", &entry.tags.join(", "))
+        .replace("{summary}", &summary(entry, 500))
+        .replace("{content}", body(entry))
+}
+
+/// Build the request body for a target (no credentials needed, so dry runs work anywhere)
+pub fn payload(target: PublishTarget, entry: &HistoryEntry, config: &PublishConfig) -> Result<Value> {
+    match target {
+        PublishTarget::Slack => {
+            let template = config.slack.template.as_deref().unwrap_or(SLACK_TEMPLATE);
+            Ok(json!({ "text": render(template, entry) }))
+        }
+        PublishTarget::Linear => {
+            let team_id = required(config.linear.team_id.as_deref(), "publish.linear.team-id")?;
+            let template = config.linear.template.as_deref().unwrap_or(LINEAR_TEMPLATE);
+            Ok(json!({
+                "query": LINEAR_ISSUE_CREATE,
+                "variables": {
+                    "input": {
+                        "teamId": team_id,
+                        "title": entry.title,
+                        "description": render(template, entry),
+                    }
+                }
+            }))
+        }
+        PublishTarget::Jira => {
+            let project = required(config.jira.project.as_deref(), "publish.jira.project")?;
+            let template = config.jira.template.as_deref().unwrap_or(JIRA_TEMPLATE);
+            // Jira labels cannot contain spaces
+            let labels: Vec<String> = entry.tags.iter().map(|t| t.replace(' ', "-")).collect();
+            Ok(json!({
+                "fields": {
+                    "project": { "key": project },
+                    "summary": entry.title,
+                    "description": render(template, entry),
+                    "issuetype": { "name": config.jira.issue_type },
+                    "labels": labels,
+                }
+            }))
+        }
+    }
+}
+
+/// Send a payload; returns a link to what was created, when the target provides one
+pub fn send(
+    target: PublishTarget,
+    body: &Value,
+    config: &PublishConfig,
+    secrets: &SecretStore,
+) -> Result<Option<String>> {
+    match target {
+        PublishTarget::Slack => {
+            let webhook = secrets.require(&config.slack.webhook_secret)?;
+            post(&webhook, &[], body).context("Failed to post to Slack")?;
+            Ok(None)
+        }
+        PublishTarget::Linear => {
+            let api_key = secrets.require(&config.linear.api_key_secret)?;
+            let response = post(LINEAR_API, &[("Authorization", api_key)], body).context("Failed to call Linear")?;
+            if let Some(errors) = response.get("errors") {
+                eyre::bail!("Linear rejected the issue: {}", errors);
+            }
+            Ok(response["data"]["issueCreate"]["issue"]["url"]
+                .as_str()
+                .map(String::from))
+        }
+        PublishTarget::Jira => {
+            let base_url = required(config.jira.base_url.as_deref(), "publish.jira.base-url")?;
+            let base_url = base_url.trim_end_matches('/');
+            let email = secrets.require(&config.jira.email_secret)?;
+            let token = secrets.require(&config.jira.token_secret)?;
+            let auth = format!("Basic {}", base64_encode(format!("{}:{}", email, token).as_bytes()));
+
+            let url = format!("{}/rest/api/2/issue", base_url);
+            let response = post(&url, &[("Authorization", auth)], body).context("Failed to call Jira")?;
+            Ok(response["key"]
+                .as_str()
+                .map(|key| format!("{}/browse/{}", base_url, key)))
+        }
+    }
+}
+
+/// POST JSON and parse the (possibly empty or non-JSON) response
+fn post(url: &str, headers: &[(&str, String)], body: &Value) -> Result<Value> {
+    let mut request = ureq::post(url)
+        .header("Content-Type", "application/json")
+        .header("User-Agent", "pais");
+    for (name, value) in headers {
+        request = request.header(*name, value);
+    }
+    let mut response = request.send(body.to_string().as_bytes())?;
+    let text = response.body_mut().read_to_string().unwrap_or_default();
+    Ok(serde_json::from_str(&text).unwrap_or(Value::Null))
+}
+
+fn required<'a>(value: Option<&'a str>, key: &str) -> Result<&'a str> {
+    value.filter(|v| !v.is_empty()).ok_or_else(|| {
+        CliError::new(ErrorCode::Config, format!("{} is not configured", key))
+            .with_hint("Set it in pais.yaml")
+            .into()
+    })
+}
+
+fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> HistoryEntry {
+        HistoryEntry::new(
+            "learnings",
+            "Cache invalidation",
+            "# Cache invalidation\n\nFlush the CDN after deploys.\n\nDetails follow.",
+        )
+        .with_tag("ops")
+        .with_tag("cdn fix")
+    }
+
+    #[test]
+    fn test_render_placeholders() {
+        let e = entry();
+        let out = render("{title} [{category}] {tags}: {summary} ({id})", &e);
+        assert_eq!(
+            out,
+            format!(
+                "Cache invalidation [learnings] ops, cdn fix: Flush the CDN after deploys. ({})",
+                e.id
+            )
+        );
+        // The stored heading is not repeated in the body
+        assert!(render("{content}", &e).starts_with("Flush the CDN"));
+    }
+
+    #[test]
+    fn test_payload_shapes() {
+        let mut config = PublishConfig::default();
+        let slack = payload(PublishTarget::Slack, &entry(), &config).unwrap();
+        assert!(slack["text"].as_str().unwrap().starts_with("*Cache invalidation*"));
+
+        // Issue trackers need a destination configured
+        assert!(payload(PublishTarget::Linear, &entry(), &config).is_err());
+        assert!(payload(PublishTarget::Jira, &entry(), &config).is_err());
+
+        config.linear.team_id = Some("team-1".to_string());
+        config.jira.project = Some("OPS".to_string());
+        config.jira.template = Some("{summary}".to_string());
+
+        let linear = payload(PublishTarget::Linear, &entry(), &config).unwrap();
+        assert_eq!(linear["variables"]["input"]["teamId"], "team-1");
+        assert!(
+            linear["variables"]["input"]["description"]
+                .as_str()
+                .unwrap()
+                .contains("Flush the CDN")
+        );
+
+        let jira = payload(PublishTarget::Jira, &entry(), &config).unwrap();
+        assert_eq!(jira["fields"]["project"]["key"], "OPS");
+        assert_eq!(jira["fields"]["issuetype"]["name"], "Task");
+        assert_eq!(jira["fields"]["labels"][1], "cdn-fix");
+    }
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"me@example.com:token"), "bWVAZXhhbXBsZS5jb206dG9rZW4=");
+    }
+}
@@ -0,0 +1,284 @@
+//! Review queue for auto-captured entries
+//!
+//! Entries the Stop hooks capture are marked `status: unreviewed` in their
+//! frontmatter. `pais history review` steps through them, oldest first, and
+//! keeps, edits, recategorizes or deletes each one. Kept entries become
+//! `status: reviewed`; those in a `history.review.promote` category (learnings
+//! by default) are also added to `index/learnings.jsonl`, which
+//! `pais history query --reviewed` searches instead of the whole history.
+
+use chrono::{DateTime, Local, NaiveDate};
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::frontmatter::without_key;
+use super::{HistoryEntry, HistoryStore};
+use crate::clock;
+use crate::lock::FileLock;
+
+/// Frontmatter key holding the review status
+pub const STATUS_KEY: &str = "status";
+pub const UNREVIEWED: &str = "unreviewed";
+pub const REVIEWED: &str = "reviewed";
+
+/// Promoted entries, relative to the history path
+const INDEX_FILE: &str = "index/learnings.jsonl";
+
+/// Set `key: value` in a markdown file's frontmatter, adding the key if missing
+///
+/// Works on the text so the rest of the file stays byte-for-byte unchanged.
+pub fn set_frontmatter(content: &str, key: &str, value: &str) -> String {
+    let line = format!("{}: {}", key, value);
+    let Some(rest) = content.strip_prefix("---\n") else {
+        return format!("---\n{}\n---\n\n{}", line, content);
+    };
+    let Some(end) = rest.find("\n---") else {
+        return format!("---\n{}\n---\n\n{}", line, content);
+    };
+    let (frontmatter, body) = (&rest[..end], &rest[end..]);
+
+    let (mut lines, position) = without_key(frontmatter, key);
+    lines.insert(position.unwrap_or(lines.len()), &line);
+    format!("---\n{}{}", lines.join("\n"), body)
+}
+
+/// Whether an entry is waiting in the review queue
+pub fn is_unreviewed(entry: &HistoryEntry) -> bool {
+    entry.metadata.get(STATUS_KEY).is_some_and(|s| s == UNREVIEWED)
+}
+
+/// Unreviewed entry files, oldest first
+pub fn queue(store: &HistoryStore, category: Option<&str>) -> Result<Vec<(PathBuf, HistoryEntry)>> {
+    let mut queue = Vec::new();
+    for path in store.paths(category, None)? {
+        let path = path?;
+        let content = store.read(&path)?;
+        // Cheap check before parsing every entry in the history
+        if !content.contains(UNREVIEWED) {
+            continue;
+        }
+        if let Ok(entry) = HistoryEntry::from_markdown(&content, &path)
+            && is_unreviewed(&entry)
+        {
+            queue.push((path, entry));
+        }
+    }
+    queue.reverse();
+    Ok(queue)
+}
+
+/// Mark an entry file as reviewed, returning the entry as now stored
+pub fn mark_reviewed(store: &HistoryStore, path: &Path) -> Result<HistoryEntry> {
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let content = set_frontmatter(&content, STATUS_KEY, REVIEWED);
+    let content = set_frontmatter(
+        &content,
+        "reviewed_at",
+        &clock::now_local().format("%Y-%m-%dT%H:%M:%S%z").to_string(),
+    );
+    store.write(path, &content)?;
+    HistoryEntry::from_markdown(&content, path)
+}
+
+/// Move an entry file to another category (same date), returning its new path
+pub fn recategorize(store: &HistoryStore, path: &Path, category: &str) -> Result<PathBuf> {
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let date_dir = path.parent().and_then(Path::file_name).unwrap_or_default();
+    let file_name = path.file_name().unwrap_or_default();
+    let dir = store.base_path().join(category).join(date_dir);
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let target = dir.join(file_name);
+    store.write(&target, &set_frontmatter(&content, "category", category))?;
+    store.remove(path)?;
+    // Drop the day directory it left if that was the last entry
+    if let Some(parent) = path.parent() {
+        fs::remove_dir(parent).ok();
+    }
+    Ok(target)
+}
+
+/// A reviewed entry in the index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedEntry {
+    pub id: String,
+    pub category: String,
+    pub title: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub path: PathBuf,
+    pub reviewed_at: DateTime<Local>,
+}
+
+/// Reviewed entries promoted for search (`index/learnings.jsonl`)
+pub struct ReviewIndex {
+    path: PathBuf,
+}
+
+impl ReviewIndex {
+    pub fn new(history_dir: &Path) -> Self {
+        Self {
+            path: history_dir.join(INDEX_FILE),
+        }
+    }
+
+    /// Indexed entries; unreadable lines are skipped
+    pub fn entries(&self) -> Result<Vec<IndexedEntry>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content =
+            fs::read_to_string(&self.path).with_context(|| format!("Failed to read {}", self.path.display()))?;
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    /// Indexed entries whose file matches `pattern`, most recently reviewed first
+    pub fn query(
+        &self,
+        pattern: &str,
+        category: Option<&str>,
+        since: Option<NaiveDate>,
+        limit: usize,
+    ) -> Result<Vec<HistoryEntry>> {
+        let regex = regex::Regex::new(pattern).context("Invalid regex pattern")?;
+        let mut indexed = self.entries()?;
+        indexed.reverse();
+
+        let mut matches = Vec::new();
+        for item in indexed {
+            if category.is_some_and(|c| c != item.category) {
+                continue;
+            }
+            // Entries deleted or moved by hand drop out until they are reviewed again
+            let Ok(content) = fs::read_to_string(&item.path) else {
+                log::debug!("Indexed entry {} is gone: {}", item.id, item.path.display());
+                continue;
+            };
+            if !regex.is_match(&content) {
+                continue;
+            }
+            if let Ok(entry) = HistoryEntry::from_markdown(&content, &item.path)
+                && since.is_none_or(|since| entry.created_at.date_naive() >= since)
+            {
+                matches.push(entry);
+                if matches.len() >= limit {
+                    break;
+                }
+            }
+        }
+        Ok(matches)
+    }
+
+    /// Add or replace an entry
+    pub fn promote(&self, entry: &HistoryEntry, path: &Path) -> Result<()> {
+        let indexed = IndexedEntry {
+            id: entry.id.clone(),
+            category: entry.category.clone(),
+            title: entry.title.clone(),
+            tags: entry.tags.clone(),
+            path: path.to_path_buf(),
+            reviewed_at: clock::now_local(),
+        };
+        self.update(|entries| {
+            entries.retain(|e| e.id != indexed.id);
+            entries.push(indexed);
+        })
+    }
+
+    /// Drop an entry (deleted, or moved out of a promoted category)
+    pub fn remove(&self, id: &str) -> Result<()> {
+        if !self.path.exists() {
+            return Ok(());
+        }
+        self.update(|entries| entries.retain(|e| e.id != id))
+    }
+
+    fn update(&self, change: impl FnOnce(&mut Vec<IndexedEntry>)) -> Result<()> {
+        let _lock = FileLock::acquire(&self.path)?;
+        let mut entries = self.entries()?;
+        change(&mut entries);
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let mut content = String::new();
+        for entry in &entries {
+            content.push_str(&serde_json::to_string(entry)?);
+            content.push('\n');
+        }
+        let tmp = self.path.with_extension(format!("jsonl.{}", std::process::id()));
+        fs::write(&tmp, content).with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &self.path).with_context(|| format!("Failed to replace {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_set_frontmatter() {
+        let content = "---\nid: abc\nstatus: unreviewed\n---\n\n# Title\n\nBody\n";
+        assert_eq!(
+            set_frontmatter(content, STATUS_KEY, REVIEWED),
+            "---\nid: abc\nstatus: reviewed\n---\n\n# Title\n\nBody\n"
+        );
+        assert_eq!(
+            set_frontmatter(content, "category", "learnings"),
+            "---\nid: abc\nstatus: unreviewed\ncategory: learnings\n---\n\n# Title\n\nBody\n"
+        );
+        assert_eq!(
+            set_frontmatter("Body\n", "status", "x"),
+            "---\nstatus: x\n---\n\nBody\n"
+        );
+    }
+
+    #[test]
+    fn test_review_workflow() {
+        let temp = tempdir().unwrap();
+        let store = HistoryStore::new(temp.path().to_path_buf());
+        let index = ReviewIndex::new(temp.path());
+
+        let noisy = HistoryEntry::new("sessions", "Noise", "ok").with_metadata(STATUS_KEY, UNREVIEWED);
+        let noisy_path = store.store(&noisy).unwrap();
+        let mut learning = HistoryEntry::new("sessions", "Learned", "TIL").with_metadata(STATUS_KEY, UNREVIEWED);
+        learning.id = format!("{}0", learning.id);
+        store.store(&learning).unwrap();
+        store
+            .store(&HistoryEntry::new("events", "Session started", ""))
+            .unwrap();
+
+        let queued = queue(&store, None).unwrap();
+        assert_eq!(
+            queued.iter().map(|(_, e)| e.title.as_str()).collect::<Vec<_>>(),
+            ["Noise", "Learned"]
+        );
+
+        let moved = recategorize(&store, &queued[1].0, "learnings").unwrap();
+        assert!(moved.starts_with(temp.path().join("learnings")));
+        let reviewed = mark_reviewed(&store, &moved).unwrap();
+        assert_eq!(reviewed.category, "learnings");
+        assert_eq!(reviewed.metadata[STATUS_KEY], REVIEWED);
+        assert_eq!(reviewed.content, "# Learned\n\nTIL");
+        index.promote(&reviewed, &moved).unwrap();
+        index.promote(&reviewed, &moved).unwrap();
+
+        fs::remove_file(&noisy_path).unwrap();
+        index.remove(&noisy.id).unwrap();
+
+        assert!(queue(&store, None).unwrap().is_empty());
+        let indexed = index.entries().unwrap();
+        assert_eq!(indexed.len(), 1);
+        assert_eq!(indexed[0].path, moved);
+        assert_eq!(index.query("TIL", None, None, 10).unwrap().len(), 1);
+        assert!(index.query("TIL", Some("sessions"), None, 10).unwrap().is_empty());
+        assert!(index.query("ok", None, None, 10).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,306 @@
+//! Activity report behind `pais history stats`
+//!
+//! Counts over the last N days: entries per category bucketed by day (by
+//! several days past a month), the most used tags, the busiest projects (the
+//! last component of an entry's `cwd:` or a session's working directory) and
+//! session lengths rebuilt from raw events as in [`calendar::sessions`].
+
+use chrono::NaiveDate;
+use eyre::{Context, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+
+use super::calendar::{self, SessionSpan};
+use super::capture::{CapturedEvent, EventCapture};
+use super::{HistoryEntry, HistoryStore};
+
+/// Most buckets per category; longer windows group several days per bucket
+const MAX_BUCKETS: usize = 31;
+
+/// Raw hook events by type
+#[derive(Debug, Default, Serialize)]
+pub struct EventCounts {
+    pub total: usize,
+    pub by_type: BTreeMap<String, usize>,
+}
+
+/// Entries stored in one category, per bucket oldest first
+#[derive(Debug, Serialize)]
+pub struct CategoryActivity {
+    pub category: String,
+    pub total: usize,
+    pub buckets: Vec<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ProjectActivity {
+    pub project: String,
+    pub entries: usize,
+    pub sessions: usize,
+    pub minutes: i64,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SessionSummary {
+    pub count: usize,
+    pub average_minutes: f64,
+    pub longest_minutes: i64,
+}
+
+/// Everything `pais history stats` shows
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub days: usize,
+    pub since: NaiveDate,
+    /// Days per bucket in [`CategoryActivity::buckets`]
+    pub bucket_days: usize,
+    pub entries: usize,
+    pub categories: Vec<CategoryActivity>,
+    pub top_tags: Vec<TagCount>,
+    pub projects: Vec<ProjectActivity>,
+    pub sessions: SessionSummary,
+    pub events: EventCounts,
+}
+
+/// Last path component of a working directory
+fn project_name(cwd: &str) -> &str {
+    let cwd = cwd.trim_end_matches('/');
+    cwd.rsplit('/').next().unwrap_or(cwd)
+}
+
+/// Build the report for the `days` days ending `today`, keeping the `top` busiest tags and projects
+pub fn report(
+    store: &HistoryStore,
+    capture: &EventCapture,
+    days: usize,
+    today: NaiveDate,
+    top: usize,
+) -> Result<Report> {
+    let days = days.max(1);
+    let since = today - chrono::Duration::days(days as i64 - 1);
+    let bucket_days = days.div_ceil(MAX_BUCKETS);
+    let bucket_count = days.div_ceil(bucket_days);
+
+    let mut entries = Vec::new();
+    for path in store.paths(None, Some(since))? {
+        let path = path?;
+        // Frontmatter stays plain in encrypted entries, so there is nothing to decrypt
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        match HistoryEntry::from_markdown(&content, &path) {
+            // A window ending in the past leaves out what came after it
+            Ok(entry) if entry.created_at.date_naive() > today => {}
+            Ok(entry) => entries.push(entry),
+            Err(e) => log::warn!("Skipping {}: {}", path.display(), e),
+        }
+    }
+
+    let mut events = Vec::new();
+    for i in (0..days).rev() {
+        let date = today - chrono::Duration::days(i as i64);
+        events.extend(capture.read_events(&date.format("%Y-%m-%d").to_string())?);
+    }
+
+    Ok(build(&entries, events, days, since, bucket_days, bucket_count, top))
+}
+
+fn build(
+    entries: &[HistoryEntry],
+    events: Vec<CapturedEvent>,
+    days: usize,
+    since: NaiveDate,
+    bucket_days: usize,
+    bucket_count: usize,
+    top: usize,
+) -> Report {
+    let mut categories: BTreeMap<&str, CategoryActivity> = BTreeMap::new();
+    let mut tags: HashMap<&str, usize> = HashMap::new();
+    let mut projects: HashMap<String, ProjectActivity> = HashMap::new();
+
+    for entry in entries {
+        let date = entry.created_at.date_naive();
+        let Ok(offset) = usize::try_from((date - since).num_days()) else {
+            continue;
+        };
+        let bucket = (offset / bucket_days).min(bucket_count - 1);
+        let activity = categories.entry(&entry.category).or_insert_with(|| CategoryActivity {
+            category: entry.category.clone(),
+            total: 0,
+            buckets: vec![0; bucket_count],
+        });
+        activity.total += 1;
+        activity.buckets[bucket] += 1;
+
+        for tag in &entry.tags {
+            *tags.entry(tag).or_default() += 1;
+        }
+        if let Some(cwd) = entry.metadata.get("cwd").filter(|cwd| *cwd != "unknown") {
+            let name = project_name(cwd);
+            projects
+                .entry(name.to_string())
+                .or_insert_with(|| ProjectActivity {
+                    project: name.to_string(),
+                    ..Default::default()
+                })
+                .entries += 1;
+        }
+    }
+
+    let mut counts = EventCounts::default();
+    for event in &events {
+        counts.total += 1;
+        *counts.by_type.entry(event.event.clone()).or_default() += 1;
+    }
+
+    let spans = calendar::sessions(events);
+    for span in &spans {
+        if let Some(name) = span.project() {
+            let project = projects.entry(name.to_string()).or_insert_with(|| ProjectActivity {
+                project: name.to_string(),
+                ..Default::default()
+            });
+            project.sessions += 1;
+            project.minutes += span.duration_minutes();
+        }
+    }
+
+    let mut top_tags: Vec<TagCount> = tags
+        .into_iter()
+        .map(|(tag, count)| TagCount {
+            tag: tag.to_string(),
+            count,
+        })
+        .collect();
+    top_tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+    top_tags.truncate(top);
+
+    let mut projects: Vec<ProjectActivity> = projects.into_values().collect();
+    projects.sort_by(|a, b| {
+        (b.sessions, b.entries, b.minutes)
+            .cmp(&(a.sessions, a.entries, a.minutes))
+            .then_with(|| a.project.cmp(&b.project))
+    });
+    projects.truncate(top);
+
+    let mut categories: Vec<CategoryActivity> = categories.into_values().collect();
+    categories.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.category.cmp(&b.category)));
+
+    Report {
+        days,
+        since,
+        bucket_days,
+        entries: categories.iter().map(|c| c.total).sum(),
+        categories,
+        top_tags,
+        projects,
+        sessions: summarize(&spans),
+        events: counts,
+    }
+}
+
+fn summarize(spans: &[SessionSpan]) -> SessionSummary {
+    if spans.is_empty() {
+        return SessionSummary::default();
+    }
+    let minutes: Vec<i64> = spans.iter().map(SessionSpan::duration_minutes).collect();
+    SessionSummary {
+        count: spans.len(),
+        average_minutes: minutes.iter().sum::<i64>() as f64 / spans.len() as f64,
+        longest_minutes: minutes.iter().copied().max().unwrap_or(0),
+    }
+}
+
+/// One character per value, scaled to the largest (a dot for zero)
+pub fn sparkline(values: &[usize], ascii: bool) -> String {
+    let (zero, levels): (char, &[char]) = if ascii {
+        ('.', &[':', '-', '=', '+', '*', '#', '%', '@'])
+    } else {
+        ('·', &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'])
+    };
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(
+            |&v| {
+                if v == 0 { zero } else { levels[(v * levels.len()).div_ceil(max) - 1] }
+            },
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone, Utc};
+    use serde_json::json;
+
+    fn entry(category: &str, date: NaiveDate, tags: &[&str], cwd: Option<&str>) -> HistoryEntry {
+        let mut entry = HistoryEntry::new(category, "Title", "Body");
+        entry.created_at = Local.from_local_datetime(&date.and_hms_opt(12, 0, 0).unwrap()).unwrap();
+        for tag in tags {
+            entry = entry.with_tag(tag);
+        }
+        match cwd {
+            Some(cwd) => entry.with_metadata("cwd", cwd),
+            None => entry,
+        }
+    }
+
+    fn event(name: &str, session: &str, minute: u32) -> CapturedEvent {
+        CapturedEvent {
+            timestamp: Utc.with_ymd_and_hms(2026, 3, 4, 9, minute, 0).unwrap(),
+            local_time: String::new(),
+            event: name.to_string(),
+            session_id: Some(session.to_string()),
+            payload: json!({"cwd": "/src/app"}),
+            duration_ms: None,
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let since = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let day = |n| since + chrono::Duration::days(n);
+        let entries = [
+            entry("sessions", day(0), &["rust", "cli"], Some("/src/app")),
+            entry("sessions", day(2), &["rust"], Some("/src/app/")),
+            entry("learnings", day(2), &["rust"], Some("/src/lib")),
+            entry("sessions", day(-1), &[], None),
+        ];
+        let events = vec![
+            event("SessionStart", "a", 0),
+            event("PreToolUse", "a", 10),
+            event("SessionEnd", "a", 30),
+            event("SessionStart", "b", 40),
+            event("SessionEnd", "b", 50),
+        ];
+
+        let report = build(&entries, events, 3, since, 1, 3, 10);
+        assert_eq!(report.entries, 3);
+        assert_eq!(report.categories[0].category, "sessions");
+        assert_eq!(report.categories[0].buckets, [1, 0, 1]);
+        assert_eq!(report.top_tags[0].tag, "rust");
+        assert_eq!(report.top_tags[0].count, 3);
+        assert_eq!(report.projects[0].project, "app");
+        assert_eq!(report.projects[0].entries, 2);
+        assert_eq!(report.projects[0].sessions, 2);
+        assert_eq!(report.projects[0].minutes, 40);
+        assert_eq!(report.sessions.count, 2);
+        assert_eq!(report.sessions.average_minutes, 20.0);
+        assert_eq!(report.events.total, 5);
+        assert_eq!(report.events.by_type["SessionStart"], 2);
+    }
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[0, 1, 4, 8], false), "·▁▄█");
+        assert_eq!(sparkline(&[2, 0, 2], true), "@.@");
+        assert_eq!(sparkline(&[], true), "");
+    }
+}
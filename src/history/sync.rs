@@ -0,0 +1,423 @@
+//! Git-backed history sync
+//!
+//! `pais history sync` turns the history directory into a git repository,
+//! commits new and edited entries, merges what other machines pushed and
+//! pushes the result, so history follows you across machines.
+//!
+//! Entries are files named by their ID, so entries written on different
+//! machines never touch the same path and merge without conflicts. What git
+//! cannot merge is settled per entry ID:
+//!
+//! - **same ID, different entries** (both machines wrote in the same
+//!   millisecond): the incoming entry gets the next free ID
+//! - **edited on one machine, deleted on the other**: the edited entry is kept
+//! - **edited on both machines**: the merge is aborted unless `--prefer`
+//!   picks a side
+//!
+//! The index, raw events and in-flight writes stay out of the repository;
+//! they are rebuilt or recorded per machine.
+
+use eyre::{Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::error::{CliError, ErrorCode};
+use crate::lock::FileLock;
+use crate::sync::Prefer;
+
+/// Machine-local files that are never committed
+const GITIGNORE: &str = "index/\nraw-events/\n*.md.*\n";
+
+/// What a sync did
+#[derive(Debug, Default, Serialize)]
+pub struct SyncReport {
+    /// Local files committed before merging
+    pub committed: usize,
+    /// Files the merge brought in or changed
+    pub pulled: usize,
+    /// Incoming entries moved to a new ID because theirs was taken
+    pub renamed: Vec<Renamed>,
+    /// Entries kept although the other machine deleted them
+    pub kept: Vec<String>,
+    /// Entries edited on both machines, settled by `--prefer`
+    pub resolved: Vec<String>,
+    pub pushed: bool,
+}
+
+/// An incoming entry stored under a new ID
+#[derive(Debug, Serialize)]
+pub struct Renamed {
+    pub from: String,
+    pub to: String,
+}
+
+/// The history directory as a git repository
+pub struct HistoryRepo {
+    dir: PathBuf,
+    branch: String,
+}
+
+impl HistoryRepo {
+    pub fn new(dir: PathBuf, branch: &str) -> Self {
+        Self {
+            dir,
+            branch: branch.to_string(),
+        }
+    }
+
+    pub fn exists(&self) -> bool {
+        self.dir.join(".git").exists()
+    }
+
+    /// Create the repository and its `.gitignore`; false if it already existed
+    pub fn init(&self) -> Result<bool> {
+        if self.exists() {
+            return Ok(false);
+        }
+        fs::create_dir_all(&self.dir).with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        self.git(&["init", "--quiet", "--initial-branch", &self.branch])?;
+        let ignore = self.dir.join(".gitignore");
+        if !ignore.exists() {
+            fs::write(&ignore, GITIGNORE).with_context(|| format!("Failed to write {}", ignore.display()))?;
+        }
+        Ok(true)
+    }
+
+    /// URL of `origin`, if set
+    pub fn remote(&self) -> Option<String> {
+        self.git(&["remote", "get-url", "origin"])
+            .ok()
+            .filter(|url| !url.is_empty())
+    }
+
+    /// Point `origin` at `url`
+    pub fn set_remote(&self, url: &str) -> Result<()> {
+        match self.remote() {
+            Some(current) if current == url => Ok(()),
+            Some(_) => self.git(&["remote", "set-url", "origin", url]).map(drop),
+            None => self.git(&["remote", "add", "origin", url]).map(drop),
+        }
+    }
+
+    /// Uncommitted changes (new, edited or deleted files)
+    pub fn pending(&self) -> Result<usize> {
+        Ok(self.git(&["status", "--porcelain"])?.lines().count())
+    }
+
+    /// Commit every change, returning how many files it covered
+    pub fn commit(&self, message: &str) -> Result<usize> {
+        // Hooks write entries concurrently; don't commit half a write
+        let _lock = FileLock::acquire(&self.dir)?;
+        self.git(&["add", "-A"])?;
+        let changed = self.pending()?;
+        if changed > 0 {
+            self.git(&["commit", "--quiet", "-m", message])?;
+        }
+        Ok(changed)
+    }
+
+    /// Fetch the remote branch; false if the remote has no commits yet
+    pub fn fetch(&self) -> Result<bool> {
+        if let Err(e) = self.git(&["fetch", "--quiet", "origin", &self.branch]) {
+            // An empty remote has no branch to fetch
+            if self.git(&["ls-remote", "--heads", "origin", &self.branch])?.is_empty() {
+                return Ok(false);
+            }
+            return Err(e);
+        }
+        Ok(true)
+    }
+
+    fn upstream(&self) -> String {
+        format!("origin/{}", self.branch)
+    }
+
+    /// Commits on the remote not merged here, and local commits not pushed
+    pub fn divergence(&self) -> Result<(usize, usize)> {
+        let range = format!("HEAD...{}", self.upstream());
+        let counts = match self.head() {
+            Some(_) => self.git(&["rev-list", "--left-right", "--count", &range])?,
+            None => format!("0\t{}", self.git(&["rev-list", "--count", &self.upstream()])?),
+        };
+        let mut counts = counts.split_whitespace().map(|n| n.parse().unwrap_or(0));
+        let outgoing = counts.next().unwrap_or(0);
+        let incoming = counts.next().unwrap_or(0);
+        Ok((incoming, outgoing))
+    }
+
+    fn head(&self) -> Option<String> {
+        self.git(&["rev-parse", "--verify", "--quiet", "HEAD"]).ok()
+    }
+
+    /// Merge the fetched branch, settling entry conflicts
+    pub fn merge(&self, prefer: Option<Prefer>, report: &mut SyncReport) -> Result<()> {
+        let _lock = FileLock::acquire(&self.dir)?;
+        let upstream = self.upstream();
+        let Some(before) = self.head() else {
+            // Nothing local yet: take the remote as is
+            self.git(&["reset", "--quiet", "--hard", &upstream])?;
+            report.pulled = self.git(&["ls-files"])?.lines().count();
+            return Ok(());
+        };
+
+        let merged = self.git(&[
+            "merge",
+            "--quiet",
+            "--no-edit",
+            "--allow-unrelated-histories",
+            &upstream,
+        ]);
+        if let Err(e) = merged {
+            let conflicted = self.git(&["diff", "--name-only", "--diff-filter=U"])?;
+            if conflicted.is_empty() {
+                self.git(&["merge", "--abort"]).ok();
+                return Err(e);
+            }
+            let unresolved = self.resolve(conflicted.lines(), prefer, report)?;
+            if !unresolved.is_empty() {
+                self.git(&["merge", "--abort"])?;
+                return Err(CliError::new(
+                    ErrorCode::Conflict,
+                    format!(
+                        "{} entr(y/ies) edited on both machines: {}",
+                        unresolved.len(),
+                        unresolved.join(", ")
+                    ),
+                )
+                .with_hint("Rerun with --prefer local or --prefer remote")
+                .into());
+            }
+            self.git(&["commit", "--quiet", "--no-edit"])?;
+        }
+
+        let range = format!("{}..HEAD", before);
+        report.pulled = self.git(&["diff", "--name-only", &range])?.lines().count();
+        Ok(())
+    }
+
+    /// Settle conflicted paths, returning the entries that need `--prefer`
+    fn resolve<'a>(
+        &self,
+        paths: impl Iterator<Item = &'a str>,
+        prefer: Option<Prefer>,
+        report: &mut SyncReport,
+    ) -> Result<Vec<String>> {
+        let mut unresolved = Vec::new();
+        for path in paths {
+            let stages: Vec<char> = self
+                .git(&["ls-files", "--unmerged", "--", path])?
+                .lines()
+                .filter_map(|line| line.split('\t').next()?.chars().last())
+                .collect();
+            let has = |stage: char| stages.contains(&stage);
+            let ours = || self.git_raw(&["show", &format!(":2:{}", path)]);
+            let theirs = || self.git_raw(&["show", &format!(":3:{}", path)]);
+
+            let content = match (has('1'), has('2'), has('3')) {
+                // Written on both machines under the same ID
+                (false, true, true) => {
+                    let incoming = theirs()?;
+                    let to = self.free_path(path)?;
+                    let from_id = entry_id(path);
+                    let to_id = entry_id(&to);
+                    let renamed = incoming.replacen(&format!("id: {}\n", from_id), &format!("id: {}\n", to_id), 1);
+                    self.write(&to, &renamed)?;
+                    report.renamed.push(Renamed {
+                        from: path.to_string(),
+                        to,
+                    });
+                    ours()?
+                }
+                // Deleted on one machine, edited on the other
+                (true, true, false) => {
+                    report.kept.push(path.to_string());
+                    ours()?
+                }
+                (true, false, true) => {
+                    report.kept.push(path.to_string());
+                    theirs()?
+                }
+                _ => match prefer {
+                    Some(Prefer::Local) => ours()?,
+                    Some(Prefer::Remote) => theirs()?,
+                    None => {
+                        unresolved.push(entry_id(path));
+                        continue;
+                    }
+                },
+            };
+            if prefer.is_some() && has('1') && has('2') && has('3') {
+                report.resolved.push(path.to_string());
+            }
+            self.write(path, &content)?;
+        }
+        Ok(unresolved)
+    }
+
+    /// Write a file and stage it
+    fn write(&self, path: &str, content: &str) -> Result<()> {
+        let full = self.dir.join(path);
+        fs::write(&full, content).with_context(|| format!("Failed to write {}", full.display()))?;
+        self.git(&["add", "--", path]).map(drop)
+    }
+
+    /// The path of the next unused ID after `path`'s, in the same directory
+    fn free_path(&self, path: &str) -> Result<String> {
+        let parent = Path::new(path).parent().unwrap_or(Path::new(""));
+        let file = |id: &str| parent.join(format!("{}.md", id));
+        let id = next_free_id(&entry_id(path), |id| self.dir.join(file(id)).exists());
+        Ok(file(&id).to_string_lossy().into_owned())
+    }
+
+    /// Push the branch to `origin`
+    pub fn push(&self) -> Result<()> {
+        let refspec = format!("HEAD:{}", self.branch);
+        self.git(&["push", "--quiet", "origin", &refspec]).map(drop)
+    }
+
+    fn git(&self, args: &[&str]) -> Result<String> {
+        self.git_raw(args).map(|out| out.trim().to_string())
+    }
+
+    /// Run git in the history directory, returning its stdout
+    fn git_raw(&self, args: &[&str]) -> Result<String> {
+        log::debug!("Running: git {}", args.join(" "));
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.dir)
+            .args(args)
+            .output()
+            .context("Failed to run git (is it installed?)")?;
+        if !output.status.success() {
+            eyre::bail!(
+                "git {} failed: {}",
+                args.first().copied().unwrap_or_default(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+/// The first ID after `id` that `taken` rejects
+pub fn next_free_id(id: &str, taken: impl Fn(&str) -> bool) -> String {
+    for n in 1.. {
+        // IDs are hex milliseconds; keep the next one in creation order
+        let candidate = match u128::from_str_radix(id, 16) {
+            Ok(ms) => format!("{:x}", ms + n),
+            Err(_) => format!("{}-{}", id, n),
+        };
+        if !taken(&candidate) {
+            return candidate;
+        }
+    }
+    unreachable!("an unused entry ID exists")
+}
+
+/// Entry ID of an entry file path
+fn entry_id(path: &str) -> String {
+    Path::new(path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(path)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::{HistoryEntry, HistoryStore};
+
+    /// A bare remote and two machines' history directories
+    fn machines() -> (tempfile::TempDir, HistoryRepo, HistoryRepo) {
+        let temp = tempfile::tempdir().unwrap();
+        let remote = temp.path().join("remote.git");
+        Command::new("git")
+            .args(["init", "--quiet", "--bare"])
+            .arg(&remote)
+            .status()
+            .unwrap();
+        let machine = |name: &str| {
+            let repo = HistoryRepo::new(temp.path().join(name), "main");
+            repo.init().unwrap();
+            repo.git(&["config", "user.name", "t"]).unwrap();
+            repo.git(&["config", "user.email", "t@t"]).unwrap();
+            repo.set_remote(&remote.to_string_lossy()).unwrap();
+            repo
+        };
+        let (a, b) = (machine("a"), machine("b"));
+        (temp, a, b)
+    }
+
+    fn sync(repo: &HistoryRepo, prefer: Option<Prefer>) -> Result<SyncReport> {
+        let mut report = SyncReport {
+            committed: repo.commit("sync")?,
+            ..Default::default()
+        };
+        if repo.fetch()? {
+            repo.merge(prefer, &mut report)?;
+        }
+        repo.push()?;
+        Ok(report)
+    }
+
+    fn entry(id: &str, title: &str) -> HistoryEntry {
+        let mut entry = HistoryEntry::new("learnings", title, "body");
+        entry.id = id.to_string();
+        entry
+    }
+
+    #[test]
+    fn test_entries_follow_across_machines() {
+        let (_temp, a, b) = machines();
+        let (store_a, store_b) = (HistoryStore::new(a.dir.clone()), HistoryStore::new(b.dir.clone()));
+
+        store_a.store(&entry("a0", "from a")).unwrap();
+        fs::create_dir_all(a.dir.join("index")).unwrap();
+        fs::write(a.dir.join("index").join("history.db"), "local").unwrap();
+        assert_eq!(sync(&a, None).unwrap().committed, 2); // entry and .gitignore
+
+        store_b.store(&entry("b0", "from b")).unwrap();
+        let report = sync(&b, None).unwrap();
+        assert_eq!(report.pulled, 1);
+        assert!(store_b.get("a0").unwrap().is_some());
+        assert!(!b.dir.join("index").exists());
+
+        sync(&a, None).unwrap();
+        assert_eq!(store_a.get("b0").unwrap().unwrap().title, "from b");
+    }
+
+    #[test]
+    fn test_id_collisions_and_edits_on_both_sides() {
+        let (_temp, a, b) = machines();
+        let (store_a, store_b) = (HistoryStore::new(a.dir.clone()), HistoryStore::new(b.dir.clone()));
+        let path = store_a.store(&entry("c0", "shared")).unwrap();
+        sync(&a, None).unwrap();
+        sync(&b, None).unwrap();
+
+        // Same ID written on both machines
+        store_a.store(&entry("d0", "a's entry")).unwrap();
+        store_b.store(&entry("d0", "b's entry")).unwrap();
+        sync(&a, None).unwrap();
+        let report = sync(&b, None).unwrap();
+        assert_eq!(report.renamed.len(), 1);
+        assert_eq!(store_b.get("d0").unwrap().unwrap().title, "b's entry");
+        assert_eq!(store_b.get("d1").unwrap().unwrap().title, "a's entry");
+
+        // The same entry edited on both machines needs a side
+        sync(&a, None).unwrap();
+        let relative = path.strip_prefix(&a.dir).unwrap();
+        fs::write(a.dir.join(relative), "edited on a\n").unwrap();
+        fs::write(b.dir.join(relative), "edited on b\n").unwrap();
+        sync(&a, None).unwrap();
+        let err = sync(&b, None).unwrap_err();
+        assert!(err.to_string().contains("c0"));
+        assert_eq!(fs::read_to_string(b.dir.join(relative)).unwrap(), "edited on b\n");
+
+        let report = sync(&b, Some(Prefer::Remote)).unwrap();
+        assert_eq!(report.resolved.len(), 1);
+        assert_eq!(fs::read_to_string(b.dir.join(relative)).unwrap(), "edited on a\n");
+    }
+}
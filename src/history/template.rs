@@ -0,0 +1,106 @@
+//! Per-category entry templates
+//!
+//! A category's `template` (inline, or a path to a file) is a
+//! [Tera](https://keats.github.io/tera/docs/) template for the files stored
+//! there, replacing the default layout of [`HistoryEntry::to_markdown`]. It
+//! sees the entry as `id`, `title`, `category`, `created_at`, `date`, `tags`,
+//! `related`, `metadata` and `content`, plus `frontmatter`: the default frontmatter
+//! lines, to keep them and only restyle the body.
+//!
+//! The rendered file must still read back as the same entry, so the template
+//! has to write `id:` and `category:` frontmatter. One that doesn't, or fails
+//! to render, is reported by `pais doctor`, and entries fall back to the
+//! default layout.
+
+use eyre::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use tera::Tera;
+
+use super::HistoryEntry;
+use crate::config::Config;
+use crate::error::{CliError, ErrorCode};
+
+/// Template text from a category's `template`: a file when it names one, else the template itself
+pub fn load(spec: &str) -> Result<String> {
+    if !spec.contains('\n') {
+        let path = Config::expand_path(Path::new(spec.trim()));
+        if path.is_file() {
+            return fs::read_to_string(&path).with_context(|| format!("Failed to read template {}", path.display()));
+        }
+    }
+    Ok(spec.to_string())
+}
+
+/// Render `entry` with a template
+pub fn render(template: &str, entry: &HistoryEntry) -> Result<String> {
+    let mut context = tera::Context::new();
+    context.insert("id", &entry.id);
+    context.insert("title", &entry.title);
+    context.insert("category", &entry.category);
+    context.insert(
+        "created_at",
+        &entry.created_at.format("%Y-%m-%dT%H:%M:%S%z").to_string(),
+    );
+    context.insert("date", &entry.created_at.format("%Y-%m-%d").to_string());
+    context.insert("tags", &entry.tags);
+    context.insert("related", &entry.related);
+    context.insert("metadata", &entry.metadata.iter().collect::<BTreeMap<_, _>>());
+    context.insert("content", &entry.content);
+    context.insert("frontmatter", entry.frontmatter().trim_end());
+
+    // Entries are markdown, not HTML: no autoescaping
+    Tera::one_off(template, &context, false).map_err(|e| {
+        // Tera keeps the useful part (the line and what went wrong) in the source chain
+        let mut message = e.to_string();
+        let mut source = std::error::Error::source(&e);
+        while let Some(cause) = source {
+            message = format!("{}: {}", message, cause);
+            source = cause.source();
+        }
+        eyre::eyre!(message)
+    })
+}
+
+/// Render `entry` with a category's `template`, checking the result reads back as the entry
+pub fn apply(spec: &str, entry: &HistoryEntry) -> Result<String> {
+    let rendered = render(&load(spec)?, entry)?;
+    let parsed = HistoryEntry::from_markdown(&rendered, Path::new(&format!("{}.md", entry.id)))?;
+    if parsed.id != entry.id || parsed.category != entry.category {
+        return Err(CliError::new(
+            ErrorCode::Config,
+            "The template must write the entry's id and category in the frontmatter",
+        )
+        .with_hint("Start the template with `---`, `{{ frontmatter }}` and `---`")
+        .into());
+    }
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEMPLATE: &str = "---\n{{ frontmatter }}\n---\n\n## {{ title | upper }}\n\n{% for tag in tags %}#{{ tag }} {% endfor %}\n\n{{ content }}\n\n_cwd: {{ metadata.cwd | default(value=\"-\") }}_\n";
+
+    #[test]
+    fn test_apply_template() {
+        let entry = HistoryEntry::new("sessions", "Fixed <login>", "Waited for the cookie")
+            .with_tag("auth")
+            .with_tag("tests")
+            .with_metadata("cwd", "/src/app");
+        let rendered = apply(TEMPLATE, &entry).unwrap();
+        assert!(rendered.contains("## FIXED <LOGIN>"));
+        assert!(rendered.contains("#auth #tests"));
+        assert!(rendered.contains("_cwd: /src/app_"));
+
+        let parsed = HistoryEntry::from_markdown(&rendered, Path::new("x.md")).unwrap();
+        assert_eq!(parsed.id, entry.id);
+        assert_eq!(parsed.tags, entry.tags);
+
+        // The entry must survive a read back
+        assert!(apply("# {{ title }}\n", &entry).is_err());
+        assert!(apply("{{ missing }}", &entry).is_err());
+    }
+}
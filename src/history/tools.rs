@@ -0,0 +1,412 @@
+//! Tool-use timing
+//!
+//! Each PreToolUse/PostToolUse pair becomes a row in `index/tools.db` under
+//! the history path: the session, the tool, when it started and finished and
+//! whether it succeeded. Hooks run in separate processes, so the PreToolUse
+//! row is what the PostToolUse hook completes. Pairs are matched by Claude
+//! Code's `tool_use_id`, or for payloads without one by the session's newest
+//! unfinished call of the same tool.
+//!
+//! Durations are measured between the two hooks, so they include the time
+//! Claude Code waited for permission. `pais history tools` shows a session's
+//! calls or, across sessions, the slowest tools and the ones failing most.
+
+use chrono::{DateTime, TimeZone, Utc};
+use eyre::{Context, Result};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Database file, relative to the history path
+const TOOLS_FILE: &str = "index/tools.db";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS tool_uses (
+    session_id TEXT NOT NULL,
+    tool_use_id TEXT NOT NULL,
+    tool TEXT NOT NULL,
+    detail TEXT,
+    started_at INTEGER,
+    ended_at INTEGER,
+    success INTEGER,
+    error TEXT,
+    PRIMARY KEY (session_id, tool_use_id)
+);
+CREATE INDEX IF NOT EXISTS tool_uses_started ON tool_uses (started_at);
+";
+
+/// Longest error message kept
+const MAX_ERROR: usize = 300;
+
+/// One tool call
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolUse {
+    pub session_id: String,
+    pub tool: String,
+    /// Program run or file touched, when the tool has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+    /// `None` until PostToolUse arrives (or when it never did)
+    pub success: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ToolUse {
+    pub fn duration_ms(&self) -> Option<i64> {
+        Some((self.ended_at? - self.started_at?).num_milliseconds())
+    }
+}
+
+/// Totals for one tool
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ToolStats {
+    pub tool: String,
+    pub calls: usize,
+    pub failures: usize,
+    pub avg_ms: u64,
+    pub p95_ms: u64,
+    pub max_ms: u64,
+}
+
+impl ToolStats {
+    pub fn failure_rate(&self) -> f64 {
+        if self.calls == 0 { 0.0 } else { self.failures as f64 / self.calls as f64 }
+    }
+}
+
+/// Order of per-tool totals
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ToolSort {
+    /// Highest average duration first
+    #[default]
+    Slowest,
+    /// Highest failure rate first
+    Failures,
+    /// Most calls first
+    Calls,
+}
+
+impl ToolSort {
+    pub fn sort(self, stats: &mut [ToolStats]) {
+        match self {
+            ToolSort::Slowest => stats.sort_by_key(|s| std::cmp::Reverse(s.avg_ms)),
+            ToolSort::Failures => stats.sort_by(|a, b| {
+                b.failure_rate()
+                    .total_cmp(&a.failure_rate())
+                    .then(b.failures.cmp(&a.failures))
+            }),
+            ToolSort::Calls => stats.sort_by_key(|s| std::cmp::Reverse(s.calls)),
+        }
+    }
+}
+
+/// Whether a PostToolUse payload reports a failure, and its message
+pub fn outcome(payload: &serde_json::Value) -> (bool, Option<String>) {
+    let Some(response) = payload.get("tool_response") else {
+        return (true, None);
+    };
+    let flag = |key: &str| response.get(key).and_then(|v| v.as_bool()) == Some(true);
+    let message = response
+        .get("error")
+        .and_then(|v| v.as_str())
+        .or_else(|| response.get("stderr").and_then(|v| v.as_str()))
+        .map(|m| m.trim().chars().take(MAX_ERROR).collect::<String>())
+        .filter(|m| !m.is_empty());
+
+    if response.get("error").is_some_and(|e| !e.is_null()) || flag("is_error") || flag("isError") {
+        return (false, message);
+    }
+    if flag("interrupted") {
+        return (false, Some("interrupted".to_string()));
+    }
+    let exit_code = response
+        .get("exit_code")
+        .or_else(|| response.get("exitCode"))
+        .and_then(|v| v.as_i64());
+    if exit_code.is_some_and(|code| code != 0) {
+        return (false, message);
+    }
+    (true, None)
+}
+
+/// What a call worked on: the program a Bash command runs, or the file a
+/// file tool reads or writes. Commands aren't kept whole; they can hold secrets.
+pub fn detail(payload: &serde_json::Value) -> Option<String> {
+    let input = payload.get("tool_input")?;
+    if let Some(command) = input.get("command").and_then(|v| v.as_str()) {
+        return command
+            .split_whitespace()
+            .find(|word| !word.contains('='))
+            .map(String::from);
+    }
+    input
+        .get("file_path")
+        .or_else(|| input.get("notebook_path"))
+        .and_then(|v| v.as_str())
+        .map(String::from)
+}
+
+pub struct ToolStore {
+    path: PathBuf,
+}
+
+impl ToolStore {
+    pub fn new(history_dir: &Path) -> Self {
+        Self {
+            path: history_dir.join(TOOLS_FILE),
+        }
+    }
+
+    /// Whether anything has been recorded yet
+    pub fn exists(&self) -> bool {
+        self.path.is_file()
+    }
+
+    fn connect(&self) -> Result<Connection> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let conn = Connection::open(&self.path).with_context(|| format!("Failed to open {}", self.path.display()))?;
+        // Parallel tool calls fire hooks at the same time
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.execute_batch(SCHEMA)
+            .context("Failed to create tool timing schema")?;
+        Ok(conn)
+    }
+
+    /// Record a PreToolUse
+    pub fn start(
+        &self,
+        session: &str,
+        id: Option<&str>,
+        tool: &str,
+        detail: Option<&str>,
+        at: DateTime<Utc>,
+    ) -> Result<()> {
+        let at = at.timestamp_millis();
+        let id = id.map(String::from).unwrap_or_else(|| format!("{}@{}", tool, at));
+        self.connect()?.execute(
+            "INSERT OR REPLACE INTO tool_uses (session_id, tool_use_id, tool, detail, started_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![session, id, tool, detail, at],
+        )?;
+        Ok(())
+    }
+
+    /// Record a PostToolUse, completing its PreToolUse row when there is one
+    pub fn finish(
+        &self,
+        session: &str,
+        id: Option<&str>,
+        tool: &str,
+        at: DateTime<Utc>,
+        (success, error): (bool, Option<String>),
+    ) -> Result<()> {
+        let conn = self.connect()?;
+        let id = match id {
+            Some(id) => Some(id.to_string()),
+            None => conn
+                .query_row(
+                    "SELECT tool_use_id FROM tool_uses
+                     WHERE session_id = ?1 AND tool = ?2 AND ended_at IS NULL
+                     ORDER BY started_at DESC LIMIT 1",
+                    params![session, tool],
+                    |row| row.get(0),
+                )
+                .optional()?,
+        };
+        let at = at.timestamp_millis();
+        let id = id.unwrap_or_else(|| format!("{}@{}", tool, at));
+        conn.execute(
+            "INSERT INTO tool_uses (session_id, tool_use_id, tool, ended_at, success, error)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT (session_id, tool_use_id)
+             DO UPDATE SET ended_at = excluded.ended_at, success = excluded.success, error = excluded.error",
+            params![session, id, tool, at, success, error],
+        )?;
+        Ok(())
+    }
+
+    /// Calls in sessions whose id starts with `session`, oldest first
+    pub fn session(&self, session: &str) -> Result<Vec<ToolUse>> {
+        let conn = self.connect()?;
+        let mut stmt = conn.prepare(
+            "SELECT session_id, tool, detail, started_at, ended_at, success, error FROM tool_uses
+             WHERE substr(session_id, 1, length(?1)) = ?1
+             ORDER BY coalesce(started_at, ended_at)",
+        )?;
+        let uses = stmt
+            .query_map(params![session], |row| {
+                Ok(ToolUse {
+                    session_id: row.get(0)?,
+                    tool: row.get(1)?,
+                    detail: row.get(2)?,
+                    started_at: row.get::<_, Option<i64>>(3)?.and_then(millis),
+                    ended_at: row.get::<_, Option<i64>>(4)?.and_then(millis),
+                    success: row.get(5)?,
+                    error: row.get(6)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(uses)
+    }
+
+    /// Per-tool totals over finished calls that started at or after `since`
+    pub fn stats(&self, since: DateTime<Utc>) -> Result<Vec<ToolStats>> {
+        let conn = self.connect()?;
+        let mut stmt = conn.prepare(
+            "SELECT tool, ended_at - started_at, success FROM tool_uses
+             WHERE started_at >= ?1 AND ended_at IS NOT NULL",
+        )?;
+        let rows = stmt
+            .query_map(params![since.timestamp_millis()], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, bool>(2)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut by_tool: BTreeMap<String, (Vec<u64>, usize)> = BTreeMap::new();
+        for (tool, duration, success) in rows {
+            let (durations, failures) = by_tool.entry(tool).or_default();
+            durations.push(duration.max(0) as u64);
+            if !success {
+                *failures += 1;
+            }
+        }
+        Ok(by_tool
+            .into_iter()
+            .map(|(tool, (mut durations, failures))| {
+                durations.sort_unstable();
+                let calls = durations.len();
+                ToolStats {
+                    tool,
+                    calls,
+                    failures,
+                    avg_ms: durations.iter().sum::<u64>() / calls as u64,
+                    p95_ms: durations[(calls * 95).div_ceil(100).saturating_sub(1)],
+                    max_ms: durations[calls - 1],
+                }
+            })
+            .collect())
+    }
+}
+
+fn millis(ms: i64) -> Option<DateTime<Utc>> {
+    Utc.timestamp_millis_opt(ms).single()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn at(ms: i64) -> DateTime<Utc> {
+        millis(1_700_000_000_000 + ms).unwrap()
+    }
+
+    #[test]
+    fn test_pairs_by_id_and_by_tool() {
+        let temp = tempdir().unwrap();
+        let store = ToolStore::new(temp.path());
+
+        store.start("s1", Some("t1"), "Bash", Some("cargo"), at(0)).unwrap();
+        store.start("s1", Some("t2"), "Read", None, at(10)).unwrap();
+        store.finish("s1", Some("t2"), "Read", at(30), (true, None)).unwrap();
+        store
+            .finish("s1", Some("t1"), "Bash", at(500), (false, Some("boom".into())))
+            .unwrap();
+        // No ids: matched to the newest open call of the tool
+        store.start("s1", None, "Grep", None, at(600)).unwrap();
+        store.finish("s1", None, "Grep", at(650), (true, None)).unwrap();
+        // PostToolUse without a PreToolUse still counts as a call
+        store.finish("s1", Some("t9"), "Write", at(700), (true, None)).unwrap();
+
+        let uses = store.session("s").unwrap();
+        let summary: Vec<_> = uses
+            .iter()
+            .map(|u| (u.tool.as_str(), u.duration_ms(), u.success))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Bash", Some(500), Some(false)),
+                ("Read", Some(20), Some(true)),
+                ("Grep", Some(50), Some(true)),
+                ("Write", None, Some(true)),
+            ]
+        );
+        assert_eq!(uses[0].error.as_deref(), Some("boom"));
+        assert!(store.session("other").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_stats() {
+        let temp = tempdir().unwrap();
+        let store = ToolStore::new(temp.path());
+        for (i, (ms, ok)) in [(100, true), (300, false), (200, true)].iter().enumerate() {
+            let id = i.to_string();
+            store.start("s", Some(&id), "Bash", None, at(0)).unwrap();
+            store.finish("s", Some(&id), "Bash", at(*ms), (*ok, None)).unwrap();
+        }
+        store.start("s", Some("open"), "Bash", None, at(0)).unwrap();
+
+        let stats = store.stats(at(-1)).unwrap();
+        assert_eq!(stats.len(), 1);
+        let bash = &stats[0];
+        assert_eq!((bash.calls, bash.failures), (3, 1));
+        assert_eq!((bash.avg_ms, bash.p95_ms, bash.max_ms), (200, 300, 300));
+        assert!(store.stats(at(1)).unwrap().is_empty());
+
+        let mut stats = vec![
+            ToolStats {
+                tool: "Read".into(),
+                calls: 10,
+                failures: 1,
+                avg_ms: 5,
+                ..Default::default()
+            },
+            ToolStats {
+                tool: "Bash".into(),
+                calls: 4,
+                failures: 2,
+                avg_ms: 900,
+                ..Default::default()
+            },
+        ];
+        ToolSort::Calls.sort(&mut stats);
+        assert_eq!(stats[0].tool, "Read");
+        ToolSort::Failures.sort(&mut stats);
+        assert_eq!(stats[0].tool, "Bash");
+        ToolSort::Slowest.sort(&mut stats);
+        assert_eq!(stats[0].tool, "Bash");
+    }
+
+    #[test]
+    fn test_outcome_and_detail() {
+        assert_eq!(outcome(&json!({ "tool_response": { "stdout": "ok" } })), (true, None));
+        assert_eq!(
+            outcome(&json!({ "tool_response": { "is_error": true, "error": "no such file" } })),
+            (false, Some("no such file".to_string()))
+        );
+        assert_eq!(
+            outcome(&json!({ "tool_response": { "interrupted": true } })),
+            (false, Some("interrupted".to_string()))
+        );
+        assert_eq!(
+            outcome(&json!({ "tool_response": { "exit_code": 1, "stderr": "failed\n" } })),
+            (false, Some("failed".to_string()))
+        );
+
+        let bash = json!({ "tool_input": { "command": "RUST_LOG=debug cargo test --all" } });
+        assert_eq!(detail(&bash).as_deref(), Some("cargo"));
+        let read = json!({ "tool_input": { "file_path": "/src/main.rs" } });
+        assert_eq!(detail(&read).as_deref(), Some("/src/main.rs"));
+        assert_eq!(detail(&json!({ "tool_input": { "pattern": "x" } })), None);
+    }
+}
@@ -0,0 +1,459 @@
+//! Bounded reading of Claude Code transcripts
+//!
+//! Session transcripts are JSONL files that grow to hundreds of megabytes in
+//! long sessions, while hooks only need the last few entries. [`ReverseLines`]
+//! reads a file backwards in fixed-size chunks and yields lines newest first,
+//! so memory stays proportional to the longest line kept rather than the file.
+//! Lines over the size cap (huge tool outputs) are skipped, and a scan budget
+//! stops the walk on pathological files.
+//!
+//! [`recent_exchanges`] uses it to pull the last prompts and replies out of a
+//! project's newest transcript for `pais context recent`, and [`session_info`]
+//! to date old sessions for `pais history import --claude-transcripts`.
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use super::handoff::project_root;
+
+/// Bytes read per step
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Lines longer than this are skipped instead of buffered
+const MAX_LINE: usize = 8 * 1024 * 1024;
+
+/// Stop after scanning this much of the file from the end
+const MAX_SCAN: u64 = 64 * 1024 * 1024;
+
+/// Iterator over a file's lines, last line first
+pub struct ReverseLines<R> {
+    reader: R,
+    /// Offset of the first byte not yet read
+    pos: u64,
+    /// Bytes before this offset are never read
+    floor: u64,
+    /// Unreturned bytes from `pos` up to the last line boundary
+    tail: Vec<u8>,
+    chunk_size: usize,
+    max_line: usize,
+    /// Inside a line that exceeded `max_line`; drop bytes until its start
+    skipping: bool,
+    /// Nothing split off yet (a final newline ends the last line, it doesn't start one)
+    at_end: bool,
+}
+
+impl ReverseLines<File> {
+    /// Open `path` for reading backwards with the default limits
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        Ok(Self::new(file, len, CHUNK_SIZE, MAX_LINE, MAX_SCAN))
+    }
+}
+
+impl<R: Read + Seek> ReverseLines<R> {
+    fn new(reader: R, len: u64, chunk_size: usize, max_line: usize, max_scan: u64) -> Self {
+        Self {
+            reader,
+            pos: len,
+            floor: len.saturating_sub(max_scan),
+            tail: Vec::new(),
+            chunk_size,
+            max_line,
+            skipping: false,
+            at_end: true,
+        }
+    }
+
+    /// Prepend the previous chunk to `tail`
+    fn read_chunk(&mut self) -> io::Result<()> {
+        let start = self.pos.saturating_sub(self.chunk_size as u64).max(self.floor);
+        let mut chunk = vec![0; (self.pos - start) as usize];
+        self.reader.seek(SeekFrom::Start(start))?;
+        self.reader.read_exact(&mut chunk)?;
+        self.pos = start;
+
+        chunk.extend_from_slice(&self.tail);
+        self.tail = chunk;
+        Ok(())
+    }
+}
+
+impl<R: Read + Seek> Iterator for ReverseLines<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(newline) = self.tail.iter().rposition(|&b| b == b'\n') {
+                let line = self.tail.split_off(newline + 1);
+                self.tail.truncate(newline);
+                let first = std::mem::take(&mut self.at_end);
+                if self.skipping {
+                    // The rest of the oversized line; what precedes the newline is a fresh line
+                    self.skipping = false;
+                    continue;
+                }
+                if first && line.is_empty() {
+                    continue;
+                }
+                return Some(Ok(decode(line)));
+            }
+
+            if self.pos <= self.floor {
+                // Reached the start of the file (or the scan budget): the remainder is one line,
+                // unless the budget cut it short
+                let complete = self.floor == 0;
+                let line = std::mem::take(&mut self.tail);
+                if line.is_empty() || self.skipping || !complete {
+                    return None;
+                }
+                return Some(Ok(decode(line)));
+            }
+
+            if self.tail.len() > self.max_line {
+                log::debug!("Skipping transcript line over {} bytes", self.max_line);
+                self.tail.clear();
+                self.skipping = true;
+            }
+
+            if let Err(e) = self.read_chunk() {
+                self.pos = self.floor;
+                self.tail.clear();
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+fn decode(mut line: Vec<u8>) -> String {
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    String::from_utf8(line).unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned())
+}
+
+/// Claude Code's per-project transcript directories
+pub fn projects_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".claude").join("projects"))
+}
+
+/// Directory name Claude Code uses for a working directory's transcripts:
+/// the absolute path with every other character than ASCII letters and
+/// digits replaced by `-`
+pub fn project_key(cwd: &Path) -> String {
+    cwd.to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+/// Transcripts for `cwd` (or, failing that, its git work tree), newest first
+pub fn project_transcripts(projects: &Path, cwd: &Path) -> Vec<PathBuf> {
+    let root = project_root(cwd);
+    for dir in [cwd, root.as_path()] {
+        let Ok(entries) = fs::read_dir(projects.join(project_key(dir))) else {
+            continue;
+        };
+        let mut transcripts: Vec<(SystemTime, PathBuf)> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "jsonl"))
+            .map(|p| {
+                (
+                    fs::metadata(&p)
+                        .and_then(|m| m.modified())
+                        .unwrap_or(SystemTime::UNIX_EPOCH),
+                    p,
+                )
+            })
+            .collect();
+        if !transcripts.is_empty() {
+            transcripts.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+            return transcripts.into_iter().map(|(_, p)| p).collect();
+        }
+    }
+    Vec::new()
+}
+
+/// Every session transcript under `projects`, by path
+pub fn all_transcripts(projects: &Path) -> Vec<PathBuf> {
+    let mut transcripts: Vec<PathBuf> = fs::read_dir(projects)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|project| project.path().is_dir())
+        .flat_map(|project| fs::read_dir(project.path()).into_iter().flatten().flatten())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "jsonl"))
+        .collect();
+    transcripts.sort();
+    transcripts
+}
+
+/// Lines read from the end when looking for a session's id, directory and end
+const MAX_INFO_LINES: usize = 200;
+
+/// Where and when a transcript's session ran
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionInfo {
+    pub session_id: String,
+    pub cwd: Option<String>,
+    /// Timestamp of the last entry
+    pub ended: Option<DateTime<Local>>,
+}
+
+/// Session details from the end of a transcript; the id falls back to the file name
+pub fn session_info(path: &Path) -> io::Result<SessionInfo> {
+    let mut info = SessionInfo {
+        session_id: String::new(),
+        cwd: None,
+        ended: None,
+    };
+    for line in ReverseLines::open(path)?.take(MAX_INFO_LINES) {
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(&line?) else {
+            continue;
+        };
+        let field = |key: &str| entry.get(key).and_then(|v| v.as_str()).map(String::from);
+        if info.session_id.is_empty() {
+            info.session_id = field("sessionId").unwrap_or_default();
+        }
+        info.cwd = info.cwd.or_else(|| field("cwd"));
+        info.ended = info.ended.or_else(|| {
+            field("timestamp")
+                .and_then(|t| DateTime::parse_from_rfc3339(&t).ok())
+                .map(|t| t.with_timezone(&Local))
+        });
+        if !info.session_id.is_empty() && info.cwd.is_some() && info.ended.is_some() {
+            break;
+        }
+    }
+    if info.session_id.is_empty() {
+        info.session_id = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+    }
+    Ok(info)
+}
+
+/// Transcript lines read from the end when collecting exchanges
+const MAX_EXCHANGE_LINES: usize = 5000;
+
+/// One user prompt and Claude's reply to it
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Exchange {
+    pub prompt: String,
+    /// Text Claude wrote in reply, in order
+    pub response: String,
+    /// Tools Claude called, in first-use order
+    pub tools: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+}
+
+/// Text of a user prompt, or `None` for tool results, meta and command output
+fn prompt_text(entry: &serde_json::Value) -> Option<String> {
+    if entry.get("isMeta").and_then(|v| v.as_bool()) == Some(true) {
+        return None;
+    }
+    let text = match entry.pointer("/message/content")? {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(blocks) => {
+            if blocks
+                .iter()
+                .any(|b| b.get("type").and_then(|t| t.as_str()) == Some("tool_result"))
+            {
+                return None;
+            }
+            blocks
+                .iter()
+                .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("text"))
+                .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        _ => return None,
+    };
+    let text = text.trim();
+    // Slash commands and their output are recorded as user messages
+    if text.is_empty() || text.starts_with("<command-") || text.starts_with("<local-command") {
+        return None;
+    }
+    Some(text.to_string())
+}
+
+/// The last `turns` exchanges of a transcript, oldest first. A reply still
+/// in progress (no prompt found for it yet) is dropped when the scan stops.
+pub fn recent_exchanges(path: &Path, turns: usize) -> io::Result<Vec<Exchange>> {
+    let mut exchanges = Vec::new();
+    // Reply blocks seen since the last prompt, newest first
+    let mut texts: Vec<String> = Vec::new();
+    let mut tools: Vec<String> = Vec::new();
+
+    for line in ReverseLines::open(path)?.take(MAX_EXCHANGE_LINES) {
+        if exchanges.len() >= turns {
+            break;
+        }
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(&line?) else {
+            continue;
+        };
+        // Subagent conversations are interleaved in the same file
+        if entry.get("isSidechain").and_then(|v| v.as_bool()) == Some(true) {
+            continue;
+        }
+        match entry.get("type").and_then(|t| t.as_str()) {
+            Some("assistant") => {
+                let Some(blocks) = entry.pointer("/message/content").and_then(|c| c.as_array()) else {
+                    continue;
+                };
+                for block in blocks.iter().rev() {
+                    match block.get("type").and_then(|t| t.as_str()) {
+                        Some("text") => texts.extend(block.get("text").and_then(|t| t.as_str()).map(String::from)),
+                        Some("tool_use") => tools.extend(block.get("name").and_then(|n| n.as_str()).map(String::from)),
+                        _ => {}
+                    }
+                }
+            }
+            Some("user") => {
+                let Some(prompt) = prompt_text(&entry) else {
+                    continue;
+                };
+                texts.reverse();
+                tools.reverse();
+                let mut seen = std::collections::HashSet::new();
+                tools.retain(|t| seen.insert(t.clone()));
+                exchanges.push(Exchange {
+                    prompt,
+                    response: std::mem::take(&mut texts).join("\n\n").trim().to_string(),
+                    tools: std::mem::take(&mut tools),
+                    timestamp: entry.get("timestamp").and_then(|t| t.as_str()).map(String::from),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    exchanges.reverse();
+    Ok(exchanges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn reverse(content: &str, chunk_size: usize, max_line: usize, max_scan: u64) -> Vec<String> {
+        let len = content.len() as u64;
+        ReverseLines::new(
+            Cursor::new(content.as_bytes().to_vec()),
+            len,
+            chunk_size,
+            max_line,
+            max_scan,
+        )
+        .map(|line| line.unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn test_lines_come_newest_first_across_chunks() {
+        let content = "first\nsecond line\r\n\nthird\n";
+        for chunk_size in [1, 3, 7, 1024] {
+            assert_eq!(
+                reverse(content, chunk_size, MAX_LINE, MAX_SCAN),
+                vec!["third", "", "second line", "first"],
+                "chunk size {}",
+                chunk_size
+            );
+        }
+        assert_eq!(reverse("no newline", 4, MAX_LINE, MAX_SCAN), vec!["no newline"]);
+        assert!(reverse("", 4, MAX_LINE, MAX_SCAN).is_empty());
+    }
+
+    #[test]
+    fn test_recent_exchanges() {
+        let transcript = [
+            r#"{"type":"user","message":{"content":"First question"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"First answer"}]}}"#,
+            r#"{"type":"user","isMeta":true,"message":{"content":"Caveat: meta"}}"#,
+            r#"{"type":"user","message":{"content":"<command-name>/clear</command-name>"}}"#,
+            r#"{"type":"user","timestamp":"2026-10-18T10:00:00Z","message":{"content":[{"type":"text","text":"Fix the bug"}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"thinking","thinking":"hmm"},{"type":"text","text":"Looking."}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Read","input":{}}]}}"#,
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","content":"file"}]}}"#,
+            r#"{"type":"assistant","isSidechain":true,"message":{"content":[{"type":"text","text":"subagent"}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Edit","input":{}},{"type":"tool_use","name":"Read","input":{}}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Fixed it."}]}}"#,
+            r#"{"type":"user","message":{"content":"And the tests?"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Running them"}]}}"#,
+        ]
+        .join("\n");
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        fs::write(&path, transcript).unwrap();
+
+        let exchanges = recent_exchanges(&path, 2).unwrap();
+        assert_eq!(exchanges.len(), 2);
+        assert_eq!(exchanges[0].prompt, "Fix the bug");
+        assert_eq!(exchanges[0].response, "Looking.\n\nFixed it.");
+        assert_eq!(exchanges[0].tools, vec!["Read", "Edit"]);
+        assert_eq!(exchanges[0].timestamp.as_deref(), Some("2026-10-18T10:00:00Z"));
+        assert_eq!(exchanges[1].prompt, "And the tests?");
+        assert_eq!(recent_exchanges(&path, 10).unwrap()[0].prompt, "First question");
+
+        assert_eq!(project_key(Path::new("/home/u/my.repo")), "-home-u-my-repo");
+    }
+
+    #[test]
+    fn test_session_info_and_discovery() {
+        let temp = tempfile::tempdir().unwrap();
+        let project = temp.path().join("-home-me-app");
+        fs::create_dir_all(project.join("abc")).unwrap();
+        fs::write(project.join("notes.txt"), "").unwrap();
+        fs::write(project.join("abc").join("nested.jsonl"), "").unwrap();
+        let transcript = project.join("abc.jsonl");
+        fs::write(
+            &transcript,
+            [
+                r#"{"type":"user","sessionId":"s-1","cwd":"/home/me/app","timestamp":"2026-03-01T09:00:00Z"}"#,
+                r#"{"type":"assistant","sessionId":"s-1","timestamp":"2026-03-01T09:30:00Z"}"#,
+                r#"{"type":"summary","summary":"Fixing the build"}"#,
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        assert_eq!(all_transcripts(temp.path()), vec![transcript.clone()]);
+        let info = session_info(&transcript).unwrap();
+        assert_eq!(info.session_id, "s-1");
+        assert_eq!(info.cwd.as_deref(), Some("/home/me/app"));
+        assert_eq!(info.ended.unwrap().to_rfc3339(), {
+            let ended = DateTime::parse_from_rfc3339("2026-03-01T09:30:00Z").unwrap();
+            ended.with_timezone(&Local).to_rfc3339()
+        });
+
+        let bare = project.join("bare.jsonl");
+        fs::write(&bare, "not json\n").unwrap();
+        assert_eq!(
+            session_info(&bare).unwrap(),
+            SessionInfo {
+                session_id: "bare".to_string(),
+                cwd: None,
+                ended: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_oversized_lines_and_scan_budget() {
+        let content = format!("keep\n{}\nlast\n", "x".repeat(100));
+        assert_eq!(reverse(&content, 8, 16, MAX_SCAN), vec!["last", "keep"]);
+
+        // Only the last 10 bytes are scanned; the partial line at the cut is dropped
+        assert_eq!(reverse("aaaa\nbbbb\ncccc\n", 4, MAX_LINE, 10), vec!["cccc"]);
+    }
+}
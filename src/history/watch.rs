@@ -0,0 +1,175 @@
+//! Watch the history directory for new entries
+//!
+//! `pais history watch` shows what the hooks capture during a live session.
+//! Entries are written to a temporary file and renamed into place, so the
+//! watcher waits for a quiet period, then reports the `<category>/<date>/<id>.md`
+//! files it hasn't seen before. Files present when watching started, edits
+//! to an entry already reported, and the JSONL logs under `raw-events/` and
+//! `security/` are not reported.
+
+use chrono::NaiveDate;
+use eyre::{Context, Result};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, channel};
+use std::time::Duration;
+
+use super::HistoryStore;
+use crate::sync::watch::event_paths;
+
+/// Quiet period that ends a batch (a Stop capture can write several entries)
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Recursive watcher over a history directory
+pub struct HistoryWatcher {
+    base: PathBuf,
+    category: Option<String>,
+    /// Entry files that existed at the start or were already reported
+    seen: HashSet<PathBuf>,
+    events: Receiver<notify::Result<notify::Event>>,
+    // Dropping the watcher stops event delivery
+    _watcher: RecommendedWatcher,
+}
+
+impl HistoryWatcher {
+    /// Watch `store`'s directory (created if missing) for new entries, optionally of one category
+    pub fn new(store: &HistoryStore, category: Option<&str>) -> Result<Self> {
+        let base = store.base_path().to_path_buf();
+        fs::create_dir_all(&base).with_context(|| format!("Failed to create {}", base.display()))?;
+
+        let (tx, events) = channel();
+        let mut watcher = notify::recommended_watcher(tx).context("Failed to create file watcher")?;
+        watcher
+            .watch(&base, RecursiveMode::Recursive)
+            .with_context(|| format!("Failed to watch {}", base.display()))?;
+
+        // Listed after the watch starts, so an entry written in between is seen once
+        let seen = store.paths(category, None)?.collect::<Result<HashSet<_>>>()?;
+        Ok(Self {
+            base,
+            category: category.map(String::from),
+            seen,
+            events,
+            _watcher: watcher,
+        })
+    }
+
+    /// Directory being watched
+    pub fn base(&self) -> &Path {
+        &self.base
+    }
+
+    /// Block until new entries are written; returns their paths, sorted
+    pub fn next_batch(&mut self) -> Result<Vec<PathBuf>> {
+        loop {
+            let first = self.events.recv().context("File watcher stopped")?;
+            let mut paths = event_paths(first);
+
+            loop {
+                match self.events.recv_timeout(DEBOUNCE) {
+                    Ok(event) => paths.extend(event_paths(event)),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => eyre::bail!("File watcher stopped"),
+                }
+            }
+
+            paths.sort();
+            paths.dedup();
+            let new: Vec<PathBuf> = paths
+                .into_iter()
+                .filter(|path| is_entry_path(&self.base, path, self.category.as_deref()) && path.is_file())
+                .filter(|path| self.seen.insert(path.clone()))
+                .collect();
+            if !new.is_empty() {
+                return Ok(new);
+            }
+        }
+    }
+}
+
+/// Whether `path` is an entry file under `base`: `<category>/<YYYY-MM-DD>/<id>.md`
+///
+/// Hidden files and the temporary files entries are written through
+/// (`<id>.md.<pid>`) don't count.
+pub fn is_entry_path(base: &Path, path: &Path, category: Option<&str>) -> bool {
+    let Ok(rel) = path.strip_prefix(base) else {
+        return false;
+    };
+    let parts: Vec<&str> = rel
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(name) => name.to_str(),
+            _ => None,
+        })
+        .collect();
+    let [cat, date, file] = parts[..] else {
+        return false;
+    };
+    !cat.starts_with('.')
+        && category.is_none_or(|c| c == cat)
+        && NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok()
+        && !file.starts_with('.')
+        && Path::new(file).extension().is_some_and(|e| e == "md")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::HistoryEntry;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_is_entry_path() {
+        let base = Path::new("/h");
+        assert!(is_entry_path(base, &base.join("sessions/2026-10-18/abc.md"), None));
+        assert!(is_entry_path(
+            base,
+            &base.join("sessions/2026-10-18/abc.md"),
+            Some("sessions")
+        ));
+        assert!(!is_entry_path(
+            base,
+            &base.join("sessions/2026-10-18/abc.md"),
+            Some("learnings")
+        ));
+        assert!(!is_entry_path(
+            base,
+            &base.join("sessions/2026-10-18/abc.md.4242"),
+            None
+        ));
+        assert!(!is_entry_path(base, &base.join("sessions/2026-10-18/.abc.md"), None));
+        assert!(!is_entry_path(
+            base,
+            &base.join("raw-events/2026-10/2026-10-18.jsonl"),
+            None
+        ));
+        assert!(!is_entry_path(base, &base.join("sessions/notes.md"), None));
+        assert!(!is_entry_path(
+            base,
+            Path::new("/elsewhere/sessions/2026-10-18/abc.md"),
+            None
+        ));
+    }
+
+    #[test]
+    fn test_watcher_reports_new_entries() {
+        let temp = tempdir().unwrap();
+        let store = HistoryStore::new(temp.path().join("history"));
+        let old = store
+            .store(&HistoryEntry::new("sessions", "Old", "before watching"))
+            .unwrap();
+
+        let mut watcher = HistoryWatcher::new(&store, Some("sessions")).unwrap();
+        store.write(&old, "edited").unwrap();
+        store
+            .store(&HistoryEntry::new("learnings", "Other", "another category"))
+            .unwrap();
+        let new = store
+            .store(&HistoryEntry::new("sessions", "New", "while watching"))
+            .unwrap();
+
+        assert_eq!(watcher.next_batch().unwrap(), [new]);
+    }
+}
@@ -0,0 +1,500 @@
+//! Weekly reviews
+//!
+//! `pais review generate --week` rolls a Monday-to-Sunday week into one
+//! history entry: the activity report of `pais history stats`, the titles
+//! stored per category, the security events logged, what `pais agent run`
+//! spent (the `cost_usd` it records on its entries) and the action items.
+//!
+//! Action items are the `- [ ]`, `TODO:`, `Action item:` and `Follow up:`
+//! lines of the week's entries. They are written as a checklist, and the
+//! review entries are where they are tracked: items still open in the last
+//! review are carried into the next one, and checking one off in
+//! `pais history edit` drops it from later weeks. Generating a week again
+//! rewrites its review in place, keeping the items already checked.
+
+use chrono::{Datelike, Duration, NaiveDate};
+use eyre::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::capture::EventCapture;
+use super::stats::{self, Report};
+use super::{HistoryEntry, HistoryStore};
+use crate::hook::security::read_log;
+use crate::presenter;
+
+/// Tag marking an entry as a weekly review
+pub const TAG: &str = "weekly-review";
+
+/// Metadata key `pais agent run` records its cost under, in US dollars
+pub const COST_KEY: &str = "cost_usd";
+
+/// Metadata key holding the ISO week a review covers
+const WEEK_KEY: &str = "week";
+
+/// Tag `pais agent run` puts on its entries
+const AGENT_RUN_TAG: &str = "agent-run";
+
+/// Titles listed per category
+const HIGHLIGHTS_PER_CATEGORY: usize = 5;
+
+/// Tags, projects and security descriptions listed
+const TOP: usize = 5;
+
+/// Longest action item kept, in columns
+const MAX_ITEM_WIDTH: usize = 200;
+
+/// Prefixes (lowercase, after any list bullet) that mark a line as an action item
+const ACTION_PREFIXES: &[&str] = &["[ ]", "todo:", "todo ", "action item:", "follow up:", "follow-up:"];
+
+/// A Monday-to-Sunday week
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Week {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl Week {
+    /// The week `weeks_ago` weeks before the one containing `today`
+    pub fn ago(today: NaiveDate, weeks_ago: u32) -> Self {
+        let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+        let start = monday - Duration::weeks(weeks_ago as i64);
+        Self {
+            start,
+            end: start + Duration::days(6),
+        }
+    }
+
+    /// ISO week, like `2026-W42`
+    pub fn label(&self) -> String {
+        self.start.format("%G-W%V").to_string()
+    }
+
+    fn contains(&self, date: NaiveDate) -> bool {
+        (self.start..=self.end).contains(&date)
+    }
+}
+
+/// An entry title listed under its category
+#[derive(Debug, Serialize)]
+pub struct Highlight {
+    pub id: String,
+    pub category: String,
+    pub title: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SecurityCount {
+    pub description: String,
+    pub action: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SecuritySummary {
+    pub events: usize,
+    pub blocked: usize,
+    pub warned: usize,
+    /// Most frequent blocked and warned descriptions
+    pub top: Vec<SecurityCount>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct CostSummary {
+    pub agent_runs: usize,
+    /// Runs that reported a cost
+    pub priced_runs: usize,
+    pub total_usd: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ActionItem {
+    pub text: String,
+    /// Entry the item was found in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    pub done: bool,
+}
+
+impl ActionItem {
+    fn checklist_line(&self) -> String {
+        let mark = if self.done { "x" } else { " " };
+        match &self.source {
+            Some(source) => format!("- [{}] {} (from `{}`)", mark, self.text, source),
+            None => format!("- [{}] {}", mark, self.text),
+        }
+    }
+}
+
+/// Everything in one weekly review
+#[derive(Debug, Serialize)]
+pub struct WeeklyReview {
+    pub week: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub activity: Report,
+    pub highlights: Vec<Highlight>,
+    pub security: SecuritySummary,
+    pub cost: CostSummary,
+    pub action_items: Vec<ActionItem>,
+}
+
+impl WeeklyReview {
+    pub fn title(&self) -> String {
+        format!("Weekly review {}", self.week)
+    }
+
+    pub fn open_items(&self) -> usize {
+        self.action_items.iter().filter(|item| !item.done).count()
+    }
+
+    /// The review as an entry body
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("Week of {} to {}.\n\n## Activity\n\n", self.start, self.end);
+        let activity = &self.activity;
+        if activity.entries == 0 {
+            md.push_str("- No entries\n");
+        } else {
+            let categories: Vec<String> = activity
+                .categories
+                .iter()
+                .map(|c| format!("{} {}", c.category, c.total))
+                .collect();
+            md.push_str(&format!("- {} entries: {}\n", activity.entries, categories.join(", ")));
+        }
+        if activity.sessions.count > 0 {
+            md.push_str(&format!(
+                "- {} sessions averaging {:.0} minutes (longest {})\n",
+                activity.sessions.count, activity.sessions.average_minutes, activity.sessions.longest_minutes
+            ));
+        }
+        if !activity.projects.is_empty() {
+            let projects: Vec<String> = activity
+                .projects
+                .iter()
+                .map(|p| format!("{} ({} sessions, {} entries)", p.project, p.sessions, p.entries))
+                .collect();
+            md.push_str(&format!("- Busiest projects: {}\n", projects.join(", ")));
+        }
+        if !activity.top_tags.is_empty() {
+            let tags: Vec<String> = activity
+                .top_tags
+                .iter()
+                .map(|t| format!("{} ({})", t.tag, t.count))
+                .collect();
+            md.push_str(&format!("- Top tags: {}\n", tags.join(", ")));
+        }
+
+        if !self.highlights.is_empty() {
+            md.push_str("\n## Highlights\n");
+            let mut category = "";
+            for highlight in &self.highlights {
+                if highlight.category != category {
+                    category = &highlight.category;
+                    md.push_str(&format!("\n### {}\n\n", category));
+                }
+                md.push_str(&format!("- {} (`{}`)\n", highlight.title, highlight.id));
+            }
+        }
+
+        md.push_str("\n## Security\n\n");
+        let security = &self.security;
+        if security.events == 0 {
+            md.push_str("- No security events\n");
+        } else {
+            md.push_str(&format!(
+                "- {} security events: {} blocked, {} warned\n",
+                security.events, security.blocked, security.warned
+            ));
+            for count in &security.top {
+                md.push_str(&format!("- {} {}x: {}\n", count.action, count.count, count.description));
+            }
+        }
+
+        md.push_str("\n## Cost\n\n");
+        let cost = &self.cost;
+        if cost.agent_runs == 0 {
+            md.push_str("- No agent runs\n");
+        } else if cost.priced_runs == 0 {
+            md.push_str(&format!(
+                "- {} agent runs, none with a reported cost\n",
+                cost.agent_runs
+            ));
+        } else {
+            md.push_str(&format!(
+                "- {} agent runs, ${:.4} ({} with a reported cost)\n",
+                cost.agent_runs, cost.total_usd, cost.priced_runs
+            ));
+        }
+
+        md.push_str("\n## Action items\n\n");
+        if self.action_items.is_empty() {
+            md.push_str("- None\n");
+        }
+        for item in &self.action_items {
+            md.push_str(&item.checklist_line());
+            md.push('\n');
+        }
+        md
+    }
+
+    /// The review as a history entry in `category`, replacing `existing` (this week's earlier review)
+    pub fn to_entry(&self, category: &str, existing: Option<&HistoryEntry>) -> HistoryEntry {
+        let mut entry = HistoryEntry::new(category, &self.title(), &self.to_markdown())
+            .with_tag(TAG)
+            .with_metadata(WEEK_KEY, &self.week);
+        if let Some(existing) = existing {
+            entry.id = existing.id.clone();
+            entry.created_at = existing.created_at;
+        }
+        entry
+    }
+}
+
+/// Action items in free text: checkbox, TODO, action item and follow-up lines
+pub fn extract_action_items(text: &str) -> Vec<String> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let line = line
+                .strip_prefix("- ")
+                .or_else(|| line.strip_prefix("* "))
+                .unwrap_or(line)
+                .trim_start();
+            let lower = line.to_lowercase();
+            let prefix = ACTION_PREFIXES.iter().find(|prefix| lower.starts_with(*prefix))?;
+            let item = line[prefix.len()..].trim();
+            (!item.is_empty()).then(|| presenter::truncate(item, MAX_ITEM_WIDTH))
+        })
+        .collect()
+}
+
+/// The checklist of a review entry, checked items included
+pub fn checklist(content: &str) -> Vec<ActionItem> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let (done, rest) = if let Some(rest) = line.strip_prefix("- [ ] ") {
+                (false, rest)
+            } else {
+                (
+                    true,
+                    line.strip_prefix("- [x] ").or_else(|| line.strip_prefix("- [X] "))?,
+                )
+            };
+            let (text, source) = match rest.strip_suffix("`)").and_then(|r| r.rsplit_once(" (from `")) {
+                Some((text, source)) => (text, Some(source.to_string())),
+                None => (rest, None),
+            };
+            Some(ActionItem {
+                text: text.trim().to_string(),
+                source,
+                done,
+            })
+        })
+        .collect()
+}
+
+/// Add `item` unless an item with the same text is there; checking one off wins
+fn add_item(items: &mut Vec<ActionItem>, item: ActionItem) {
+    let key = item.text.to_lowercase();
+    match items.iter_mut().find(|i| i.text.to_lowercase() == key) {
+        Some(existing) => existing.done |= item.done,
+        None => items.push(item),
+    }
+}
+
+/// Build the review of `week`, with the review already stored for it (if any)
+pub fn generate(
+    store: &HistoryStore,
+    capture: &EventCapture,
+    history_dir: &Path,
+    week: Week,
+    category: &str,
+) -> Result<(WeeklyReview, Option<HistoryEntry>)> {
+    let label = week.label();
+    let activity = stats::report(store, capture, 7, week.end, TOP)?;
+
+    // Earlier reviews: this week's, and the newest before it for open items
+    let mut existing = None;
+    let mut previous: Option<HistoryEntry> = None;
+    for entry in store.entries(Some(category), None)? {
+        let entry = entry?;
+        if !entry.tags.iter().any(|t| t == TAG) {
+            continue;
+        }
+        match entry.metadata.get(WEEK_KEY) {
+            Some(w) if *w == label => existing = existing.or(Some(entry)),
+            Some(w) if *w < label && previous.as_ref().is_none_or(|p| p.metadata.get(WEEK_KEY) < Some(w)) => {
+                previous = Some(entry)
+            }
+            _ => {}
+        }
+    }
+
+    let mut action_items = Vec::new();
+    if let Some(existing) = &existing {
+        for item in checklist(&existing.content) {
+            add_item(&mut action_items, item);
+        }
+    }
+    if let Some(previous) = &previous {
+        for item in checklist(&previous.content).into_iter().filter(|item| !item.done) {
+            add_item(&mut action_items, item);
+        }
+    }
+
+    let mut highlights: BTreeMap<String, Vec<Highlight>> = BTreeMap::new();
+    let mut cost = CostSummary::default();
+    let mut entries = Vec::new();
+    for entry in store.entries(None, Some(week.start))? {
+        let entry = entry?;
+        if week.contains(entry.created_at.date_naive()) && !entry.tags.iter().any(|t| t == TAG) {
+            entries.push(entry);
+        }
+    }
+    // Oldest first, so items keep the order they came up in
+    entries.sort_by_key(|entry| entry.created_at);
+    for entry in &entries {
+        for text in extract_action_items(&entry.content) {
+            add_item(
+                &mut action_items,
+                ActionItem {
+                    text,
+                    source: Some(entry.id.clone()),
+                    done: false,
+                },
+            );
+        }
+        if entry.tags.iter().any(|t| t == AGENT_RUN_TAG) {
+            cost.agent_runs += 1;
+            if let Some(usd) = entry.metadata.get(COST_KEY).and_then(|c| c.parse::<f64>().ok()) {
+                cost.priced_runs += 1;
+                cost.total_usd += usd;
+            }
+        }
+    }
+    for entry in entries.iter().rev() {
+        let listed = highlights.entry(entry.category.clone()).or_default();
+        if listed.len() < HIGHLIGHTS_PER_CATEGORY {
+            listed.push(Highlight {
+                id: entry.id.clone(),
+                category: entry.category.clone(),
+                title: entry.title.clone(),
+            });
+        }
+    }
+    // Categories in the order of the activity report, busiest first
+    let mut ordered: Vec<Highlight> = Vec::new();
+    for category in &activity.categories {
+        ordered.extend(highlights.remove(&category.category).unwrap_or_default());
+    }
+    ordered.extend(highlights.into_values().flatten());
+
+    let mut security = SecuritySummary::default();
+    let mut counts: BTreeMap<(String, String), usize> = BTreeMap::new();
+    for day in week.start.iter_days().take(7) {
+        for event in read_log(history_dir, day) {
+            security.events += 1;
+            match event.action.as_str() {
+                "Block" => security.blocked += 1,
+                "Warn" => security.warned += 1,
+                _ => continue,
+            }
+            *counts.entry((event.action, event.description)).or_default() += 1;
+        }
+    }
+    security.top = counts
+        .into_iter()
+        .map(|((action, description), count)| SecurityCount {
+            description,
+            action,
+            count,
+        })
+        .collect();
+    security.top.sort_by_key(|count| std::cmp::Reverse(count.count));
+    security.top.truncate(TOP);
+
+    let review = WeeklyReview {
+        week: label,
+        start: week.start,
+        end: week.end,
+        activity,
+        highlights: ordered,
+        security,
+        cost,
+        action_items,
+    };
+    Ok((review, existing))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_week_and_items() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let week = Week::ago(today, 1);
+        assert_eq!(week.start, NaiveDate::from_ymd_opt(2026, 10, 5).unwrap());
+        assert_eq!(week.end, NaiveDate::from_ymd_opt(2026, 10, 11).unwrap());
+        assert_eq!(week.label(), "2026-W41");
+
+        let text =
+            "Notes\n- [ ] Rotate the token\n* TODO: bump deps\nAction item: write docs\n- [x] done already\ntodo\n";
+        assert_eq!(
+            extract_action_items(text),
+            ["Rotate the token", "bump deps", "write docs"]
+        );
+
+        let items = checklist("- [ ] Rotate the token (from `a1`)\n- [x] Write docs\n- plain\n");
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].source.as_deref(), Some("a1"));
+        assert!(!items[0].done && items[1].done);
+        assert_eq!(items[0].checklist_line(), "- [ ] Rotate the token (from `a1`)");
+    }
+
+    #[test]
+    fn test_generate_carries_open_items() {
+        let temp = tempdir().unwrap();
+        let store = HistoryStore::new(temp.path().to_path_buf());
+        let capture = EventCapture::new(temp.path().to_path_buf(), true);
+        let this_week = Week::ago(clock_today(), 0);
+
+        let mut last = HistoryEntry::new("sessions", "Weekly review", "- [ ] Carried over\n- [x] Finished\n")
+            .with_tag(TAG)
+            .with_metadata(WEEK_KEY, &Week::ago(clock_today(), 1).label());
+        last.created_at -= Duration::weeks(1);
+        store.store(&last).unwrap();
+        store
+            .store(
+                &HistoryEntry::new("sessions", "Ran an agent", "TODO: follow the lead")
+                    .with_tag(AGENT_RUN_TAG)
+                    .with_metadata(COST_KEY, "0.0250"),
+            )
+            .unwrap();
+
+        let (review, existing) = generate(&store, &capture, temp.path(), this_week, "sessions").unwrap();
+        assert!(existing.is_none());
+        let texts: Vec<&str> = review.action_items.iter().map(|i| i.text.as_str()).collect();
+        assert_eq!(texts, ["Carried over", "follow the lead"]);
+        assert_eq!((review.cost.agent_runs, review.cost.total_usd), (1, 0.025));
+        assert_eq!(review.highlights.len(), 1);
+
+        // Checking an item off in the stored review keeps it checked on the next run
+        let mut entry = review.to_entry("sessions", None);
+        entry.content = entry.content.replace("- [ ] Carried over", "- [x] Carried over");
+        store.store(&entry).unwrap();
+        let (again, existing) = generate(&store, &capture, temp.path(), this_week, "sessions").unwrap();
+        assert_eq!(existing.map(|e| e.id), Some(entry.id));
+        assert!(again.action_items[0].done);
+        assert_eq!(again.open_items(), 1);
+    }
+
+    fn clock_today() -> NaiveDate {
+        crate::clock::now_local().date_naive()
+    }
+}
@@ -0,0 +1,189 @@
+//! Git checkpoint hook
+//!
+//! Counts file modifications on PostToolUse and snapshots the session's
+//! repository every `hooks.checkpoint.every` edits; SessionEnd snapshots
+//! whatever is left. See [`crate::checkpoint`].
+
+use std::path::{Path, PathBuf};
+
+use super::{HookEvent, HookHandler, HookResult};
+use crate::checkpoint::{self, Pending};
+use crate::config::CheckpointConfig;
+use crate::history::handoff::EDIT_TOOLS;
+
+pub struct CheckpointHandler {
+    config: CheckpointConfig,
+    state: Option<PathBuf>,
+}
+
+impl CheckpointHandler {
+    pub fn new(config: CheckpointConfig) -> Self {
+        Self {
+            config,
+            state: checkpoint::state_path(),
+        }
+    }
+
+    #[cfg(test)]
+    fn with_state(mut self, path: PathBuf) -> Self {
+        self.state = Some(path);
+        self
+    }
+
+    /// Record one edit; returns the pending edits when a checkpoint is due
+    fn record(&self, state: &Path, session: &str, file: Option<String>) -> eyre::Result<Option<Pending>> {
+        let every = self.config.every.max(1);
+        checkpoint::update(state, |s| {
+            let pending = s.sessions.entry(session.to_string()).or_default();
+            pending.edits += 1;
+            if let Some(file) = file
+                && !pending.files.contains(&file)
+            {
+                pending.files.push(file);
+            }
+            (pending.edits >= every).then(|| {
+                pending.taken += 1;
+                let due = pending.clone();
+                pending.edits = 0;
+                pending.files.clear();
+                due
+            })
+        })
+    }
+
+    fn snapshot(&self, root: &Path, session: &str, pending: &Pending) {
+        match checkpoint::create(root, session, pending.taken, &pending.files, self.config.mode) {
+            Ok(Some(commit)) => log::info!(
+                "Checkpoint {} of session {} in {}: {}",
+                pending.taken,
+                session,
+                root.display(),
+                commit
+            ),
+            Ok(None) => log::debug!("No changes to checkpoint in {}", root.display()),
+            Err(e) => log::warn!("Checkpoint failed in {}: {:#}", root.display(), e),
+        }
+    }
+
+    fn on_post_tool_use(&self, state: &Path, session: &str, root: &Path, payload: &serde_json::Value) -> HookResult {
+        let tool = payload.get("tool_name").and_then(|v| v.as_str()).unwrap_or_default();
+        if !EDIT_TOOLS.contains(&tool) {
+            return HookResult::Allow;
+        }
+        let file = payload
+            .get("tool_input")
+            .and_then(|i| i.get("file_path").or_else(|| i.get("notebook_path")))
+            .and_then(|p| p.as_str())
+            .map(|p| {
+                Path::new(p)
+                    .strip_prefix(root)
+                    .map(|rel| rel.to_string_lossy().to_string())
+                    .unwrap_or_else(|_| p.to_string())
+            });
+
+        match self.record(state, session, file) {
+            Ok(Some(pending)) => self.snapshot(root, session, &pending),
+            Ok(None) => {}
+            Err(e) => log::warn!("Failed to update checkpoint state: {:#}", e),
+        }
+        HookResult::Allow
+    }
+
+    fn on_session_end(&self, state: &Path, session: &str, root: &Path) -> HookResult {
+        let pending = checkpoint::update(state, |s| s.sessions.remove(session));
+        match pending {
+            Ok(Some(mut pending)) if pending.edits > 0 => {
+                pending.taken += 1;
+                self.snapshot(root, session, &pending);
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("Failed to update checkpoint state: {:#}", e),
+        }
+        HookResult::Allow
+    }
+}
+
+impl HookHandler for CheckpointHandler {
+    fn name(&self) -> &'static str {
+        "checkpoint"
+    }
+
+    fn handles(&self, event: HookEvent) -> bool {
+        self.config.enabled && matches!(event, HookEvent::PostToolUse | HookEvent::SessionEnd)
+    }
+
+    fn handle(&self, event: HookEvent, payload: &serde_json::Value) -> HookResult {
+        let (Some(state), Some(session), Some(cwd)) = (
+            self.state.as_deref(),
+            payload.get("session_id").and_then(|v| v.as_str()),
+            payload.get("cwd").and_then(|v| v.as_str()),
+        ) else {
+            return HookResult::Allow;
+        };
+        let Some(root) = checkpoint::repo_root(Path::new(cwd)) else {
+            return HookResult::Allow;
+        };
+
+        match event {
+            HookEvent::PostToolUse => self.on_post_tool_use(state, session, &root, payload),
+            HookEvent::SessionEnd => self.on_session_end(state, session, &root),
+            _ => HookResult::Allow,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CheckpointMode;
+    use std::fs;
+    use std::process::Command;
+
+    #[test]
+    fn test_checkpoints_every_n_edits_and_at_session_end() {
+        let repo = tempfile::tempdir().unwrap();
+        let root = repo.path().canonicalize().unwrap();
+        assert!(
+            Command::new("git")
+                .args(["init", "-q"])
+                .current_dir(&root)
+                .status()
+                .unwrap()
+                .success()
+        );
+        let state = tempfile::tempdir().unwrap();
+        let handler = CheckpointHandler::new(CheckpointConfig {
+            enabled: true,
+            every: 2,
+            mode: CheckpointMode::Ref,
+        })
+        .with_state(state.path().join("checkpoints.json"));
+
+        let edit = |name: &str| {
+            let path = root.join(name);
+            fs::write(&path, name).unwrap();
+            let payload = serde_json::json!({
+                "session_id": "sess",
+                "cwd": root,
+                "tool_name": "Write",
+                "tool_input": {"file_path": path},
+            });
+            handler.handle(HookEvent::PostToolUse, &payload);
+        };
+
+        edit("a.txt");
+        assert!(checkpoint::list(&root).unwrap().is_empty());
+        edit("b.txt");
+        edit("c.txt");
+        let checkpoints = checkpoint::list(&root).unwrap();
+        assert_eq!(checkpoints.len(), 1);
+        assert_eq!(checkpoints[0].files, vec!["a.txt", "b.txt"]);
+
+        let end = serde_json::json!({"session_id": "sess", "cwd": root});
+        handler.handle(HookEvent::SessionEnd, &end);
+        let checkpoints = checkpoint::list(&root).unwrap();
+        assert_eq!(checkpoints.len(), 2);
+        assert_eq!(checkpoints[0].number, 2);
+        assert_eq!(checkpoints[0].files, vec!["c.txt"]);
+    }
+}
@@ -0,0 +1,108 @@
+//! Session handoff hooks
+//!
+//! Writes the project's handoff on SessionEnd and prints it on the next
+//! SessionStart, where Claude Code adds hook output to the session context.
+//! See [`crate::history::handoff`].
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::{HookEvent, HookHandler, HookResult};
+use crate::config::HandoffConfig;
+use crate::history::handoff::{self, Handoff};
+use crate::history::redact::Redactor;
+
+/// SessionStart sources that begin a fresh conversation; resumed and
+/// compacted sessions still have their own context
+const FRESH_SOURCES: &[&str] = &["startup", "clear"];
+
+pub struct HandoffHandler {
+    enabled: bool,
+    config: HandoffConfig,
+    dir: PathBuf,
+    redactor: Option<Redactor>,
+}
+
+impl HandoffHandler {
+    pub fn new(enabled: bool, config: HandoffConfig) -> Self {
+        Self {
+            enabled: enabled && config.enabled,
+            config,
+            dir: handoff::handoffs_dir(),
+            redactor: None,
+        }
+    }
+
+    /// Strip secrets from handoffs before they are written
+    pub fn with_redactor(mut self, redactor: Option<Redactor>) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
+    fn on_session_start(&self, payload: &serde_json::Value) -> HookResult {
+        let source = payload.get("source").and_then(|v| v.as_str());
+        if source.is_some_and(|s| !FRESH_SOURCES.contains(&s)) {
+            return HookResult::Allow;
+        }
+        let Some(cwd) = payload.get("cwd").and_then(|v| v.as_str()) else {
+            return HookResult::Allow;
+        };
+
+        let max_age = Duration::from_secs(u64::from(self.config.max_age_days) * 86400);
+        if let Some(content) = handoff::load_recent(&self.dir, Path::new(cwd), max_age) {
+            log::info!("Injecting session handoff for {}", cwd);
+            println!("<system-reminder>");
+            println!("PAIS HANDOFF (notes from the previous session in this project)");
+            println!();
+            println!("{}", content.trim_end());
+            println!("</system-reminder>");
+        }
+        HookResult::Allow
+    }
+
+    fn on_session_end(&self, payload: &serde_json::Value) -> HookResult {
+        let Some(cwd) = payload.get("cwd").and_then(|v| v.as_str()) else {
+            return HookResult::Allow;
+        };
+        let session_id = payload.get("session_id").and_then(|v| v.as_str()).unwrap_or("unknown");
+        let transcript = payload.get("transcript_path").and_then(|v| v.as_str()).map(Path::new);
+
+        let handoff = Handoff::build(session_id, Path::new(cwd), transcript, self.config.max_items);
+        if handoff.is_empty() {
+            log::debug!("Nothing to hand off for {}", handoff.root.display());
+            return HookResult::Allow;
+        }
+
+        let mut content = handoff.to_markdown();
+        if let Some(redactor) = &self.redactor {
+            content = redactor.redact(&content).0;
+        }
+        match handoff.write(&self.dir, &content) {
+            Ok(path) => {
+                log::info!("Wrote session handoff: {}", path.display());
+                HookResult::Allow
+            }
+            Err(e) => HookResult::Error {
+                message: format!("Failed to write handoff: {:#}", e),
+            },
+        }
+    }
+}
+
+impl HookHandler for HandoffHandler {
+    fn name(&self) -> &'static str {
+        "handoff"
+    }
+
+    fn handles(&self, event: HookEvent) -> bool {
+        self.enabled && matches!(event, HookEvent::SessionStart | HookEvent::SessionEnd)
+    }
+
+    fn handle(&self, event: HookEvent, payload: &serde_json::Value) -> HookResult {
+        match event {
+            HookEvent::SessionStart => self.on_session_start(payload),
+            HookEvent::SessionEnd => self.on_session_end(payload),
+            _ => HookResult::Allow,
+        }
+    }
+}
@@ -2,24 +2,43 @@
 //!
 //! Captures session lifecycle events: SessionStart, Stop, SubagentStop, SessionEnd.
 //!
-//! On Stop/SubagentStop, content is analyzed to categorize as:
-//! - Agent's `history_category` if agent detected
-//! - `learnings`: If contains problem-solving narratives
-//! - `sessions`: Default for regular work sessions
+//! On Stop/SubagentStop, the entry's category is:
+//! - The category listing the agent in `route.agents`, if an agent is detected
+//! - Else the agent's `history_category` if its definition declares one and
+//!   the category is declared
+//! - Otherwise the category whose routing keywords or patterns the content
+//!   matches (e.g. `learnings` for problem-solving narratives), falling back
+//!   to `history.default-category`
+//!
+//! With `history.labeling.enabled`, a model writes the title, a summary and
+//! extra tags, and picks the category when no agent did (see
+//! [`crate::history::label`]).
+//!
+//! With `history.review.enabled`, those entries are marked unreviewed for
+//! `pais history review` (see [`crate::history::review`]). With
+//! `history.dedupe.enabled` (the default), a capture that nearly duplicates a
+//! recent entry is merged into it or skipped (see [`crate::history::dedupe`]).
 //!
 //! ## Transcript Reading
 //!
 //! Claude Code provides `transcript_path` in Stop events, pointing to the session's
-//! JSONL file. We read this to extract the actual conversation content.
+//! JSONL file. We read it backwards from the end to extract the actual conversation
+//! content, so long transcripts cost no more than short ones.
 
 #![allow(dead_code)] // with_agents_dir - for testing/custom config
 
-use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use super::{HookEvent, HookHandler, HookResult};
 use crate::agent::loader::AgentLoader;
-use crate::history::categorize::{categorize_content, extract_summary, extract_tags};
+use crate::claude::compat::{self, ResponseSource};
+use crate::history::categorize::{Categories, extract_summary, extract_tags};
+use crate::history::dedupe::Dedupe;
+use crate::history::encrypt::Encryption;
+use crate::history::label::{Label, Labeler};
+use crate::history::redact::Redactor;
+use crate::history::review;
+use crate::history::transcript::{ReverseLines, SessionInfo};
 use crate::history::{HistoryEntry, HistoryStore};
 
 /// History hook handler - captures session lifecycle data
@@ -27,6 +46,13 @@ pub struct HistoryHandler {
     enabled: bool,
     history_path: PathBuf,
     agents_dir: PathBuf,
+    redactor: Option<Redactor>,
+    categories: Categories,
+    review: bool,
+    index: bool,
+    encryption: Option<Encryption>,
+    dedupe: Option<Dedupe>,
+    labeler: Option<Labeler>,
 }
 
 impl HistoryHandler {
@@ -41,6 +67,66 @@ impl HistoryHandler {
             enabled,
             history_path,
             agents_dir,
+            redactor: None,
+            categories: Categories::default(),
+            review: false,
+            index: false,
+            encryption: None,
+            dedupe: None,
+            labeler: None,
+        }
+    }
+
+    /// Queue captured Stop entries for `pais history review`
+    pub fn with_review(mut self, review: bool) -> Self {
+        self.review = review;
+        self
+    }
+
+    /// Record stored entries in the history index
+    pub fn with_index(mut self, index: bool) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Encrypt entry bodies before they are stored
+    pub fn with_encryption(mut self, encryption: Encryption) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
+
+    /// Merge or skip Stop captures that nearly duplicate a recent entry
+    pub fn with_dedupe(mut self, dedupe: Option<Dedupe>) -> Self {
+        self.dedupe = dedupe;
+        self
+    }
+
+    /// Ask a model for the title, summary, tags and category of Stop captures
+    pub fn with_labeler(mut self, labeler: Option<Labeler>) -> Self {
+        self.labeler = labeler;
+        self
+    }
+
+    /// Strip secrets from entries before they are stored
+    pub fn with_redactor(mut self, redactor: Option<Redactor>) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
+    /// Route entries through the configured categories
+    pub fn with_categories(mut self, categories: Categories) -> Self {
+        self.categories = categories;
+        self
+    }
+
+    fn store(&self) -> HistoryStore {
+        let store = HistoryStore::new(self.history_path.clone())
+            .with_redactor(self.redactor.clone())
+            .with_categories(self.categories.clone())
+            .with_index(self.index);
+        match &self.encryption {
+            Some(encryption) => store.with_encryption(encryption.clone()),
+            None => store,
         }
     }
 
@@ -82,7 +168,7 @@ impl HistoryHandler {
             .with_metadata("session_id", session_id)
             .with_metadata("cwd", cwd);
 
-        let store = HistoryStore::new(self.history_path.clone());
+        let store = self.store();
         if let Err(e) = store.store(&entry) {
             log::error!("Failed to log session start: {}", e);
         }
@@ -107,6 +193,24 @@ impl HistoryHandler {
 
     /// Shared logic for Stop and SubagentStop events
     fn capture_stop_event(&self, payload: &serde_json::Value, agent_type: Option<&str>) -> HookResult {
+        let entry = self.stop_entry(payload, agent_type);
+        let store = self.store().with_dedupe(self.dedupe.clone());
+        match store.store(&entry) {
+            Ok(path) => {
+                log::info!("Captured {} to: {}", entry.category, path.display());
+                HookResult::Allow
+            }
+            Err(e) => {
+                log::error!("Failed to capture session: {}", e);
+                HookResult::Error {
+                    message: format!("Failed to store session: {}", e),
+                }
+            }
+        }
+    }
+
+    /// The entry a Stop or SubagentStop payload is captured as
+    fn stop_entry(&self, payload: &serde_json::Value, agent_type: Option<&str>) -> HistoryEntry {
         let session_id = payload.get("session_id").and_then(|v| v.as_str()).unwrap_or("unknown");
 
         let stop_reason = payload
@@ -118,17 +222,31 @@ impl HistoryHandler {
         let summary = build_session_summary(payload);
 
         // Determine category - agent takes precedence over content analysis
-        let (category_name, agent_name) = self.determine_category(agent_type, &summary);
+        let (mut category_name, agent_name) = self.determine_category(agent_type, &summary);
         let extracted_title = extract_summary(&summary, 60);
-        let tags = extract_tags(&summary);
+        let mut tags = extract_tags(&summary);
 
         // Use extracted title or fallback to session ID
-        let title = if extracted_title != "Untitled" && !extracted_title.is_empty() {
+        let mut title = if extracted_title != "Untitled" && !extracted_title.is_empty() {
             extracted_title
         } else {
             format!("Session {}", &session_id[..8.min(session_id.len())])
         };
 
+        // A model's labels replace the extracted ones; its category only overrides content routing
+        let label = self.label(&summary);
+        if let Some(label) = &label {
+            title = label.title.clone();
+            for tag in &label.tags {
+                if !tags.contains(tag) {
+                    tags.push(tag.clone());
+                }
+            }
+            if let Some(category) = label.category.as_ref().filter(|_| agent_name.is_none()) {
+                category_name = category.clone();
+            }
+        }
+
         // Create history entry with determined category
         let mut entry = HistoryEntry::new(&category_name, &title, &summary)
             .with_tag(stop_reason)
@@ -146,18 +264,55 @@ impl HistoryHandler {
         for tag in tags {
             entry = entry.with_tag(&tag);
         }
-
-        let store = HistoryStore::new(self.history_path.clone());
-        match store.store(&entry) {
-            Ok(path) => {
-                log::info!("Captured {} to: {}", category_name, path.display());
-                HookResult::Allow
+        if let (Some(label), Some(labeler)) = (label, &self.labeler) {
+            if !label.summary.is_empty() {
+                entry = entry.with_metadata("summary", &label.summary);
             }
+            entry = entry.with_metadata("labeled_by", &labeler.id());
+        }
+        if self.review {
+            entry = entry.with_metadata(review::STATUS_KEY, review::UNREVIEWED);
+        }
+        entry
+    }
+
+    /// The entry the Stop hook would have captured for an old session,
+    /// dated when it ended; `None` when the transcript holds no reply
+    pub fn transcript_entry(&self, transcript: &Path, session: &SessionInfo) -> Option<HistoryEntry> {
+        extract_response_from_transcript(transcript)?;
+        let payload = serde_json::json!({
+            "session_id": session.session_id,
+            "transcript_path": transcript,
+        });
+        let mut entry = self.stop_entry(&payload, None).with_tag("imported");
+        if let Some(cwd) = &session.cwd {
+            entry = entry.with_metadata("cwd", cwd);
+        }
+        if let Some(ended) = session.ended {
+            // IDs are creation times in milliseconds
+            entry.id = format!("{:x}", ended.timestamp_millis());
+            entry.created_at = ended;
+        }
+        Some(entry)
+    }
+
+    /// Store an entry from [`Self::transcript_entry`]
+    pub fn import(&self, entry: &HistoryEntry) -> eyre::Result<PathBuf> {
+        self.store().with_dedupe(self.dedupe.clone()).store(entry)
+    }
+
+    /// The configured model's labels for `content`, if it gave any
+    fn label(&self, content: &str) -> Option<Label> {
+        let labeler = self.labeler.as_ref()?;
+        match labeler.label(content, &self.categories) {
+            Ok(label) => label,
             Err(e) => {
-                log::error!("Failed to capture session: {}", e);
-                HookResult::Error {
-                    message: format!("Failed to store session: {}", e),
-                }
+                log::warn!(
+                    "Labeling with {} failed ({:#}); keeping the extracted title and tags",
+                    labeler.id(),
+                    e
+                );
+                None
             }
         }
     }
@@ -165,14 +320,19 @@ impl HistoryHandler {
     /// Determine history category from agent or content analysis
     /// Returns (category_name, optional_agent_name)
     fn determine_category(&self, agent_type: Option<&str>, content: &str) -> (String, Option<String>) {
-        // If agent type provided, try to load agent and get its history_category
+        // If agent type provided, use the category routing it in pais.yaml, else its history_category
         if let Some(agent_name) = agent_type {
+            if let Some(category) = self.categories.for_agent(agent_name) {
+                log::info!("Routing agent '{}' to history category: {}", agent_name, category);
+                return (category.to_string(), Some(agent_name.to_string()));
+            }
             let loader = AgentLoader::new(self.agents_dir.clone());
             let agent_path = self.agents_dir.join(format!("{}.yaml", agent_name.to_lowercase()));
 
             if let Ok(agent) = loader.load_agent(&agent_path) {
                 if let Some(category) = agent.history_category {
                     log::info!("Using agent '{}' history category: {}", agent_name, category);
+                    let category = self.categories.resolve(Some(&category), content);
                     return (category, Some(agent_name.to_string()));
                 }
             } else {
@@ -181,8 +341,31 @@ impl HistoryHandler {
         }
 
         // Fall back to content-based categorization
-        let category = categorize_content(content);
-        (category.dir_name().to_string(), None)
+        (self.categories.route(content).to_string(), None)
+    }
+
+    /// The category the Stop hook would give `entry` under the current rules
+    ///
+    /// `None` for entries the rules don't own: ones not captured by a Stop
+    /// hook, `events`, entries kept in the review queue (their category was
+    /// confirmed by hand) and entries a model labeled without an agent route.
+    pub fn reroute(&self, entry: &HistoryEntry) -> Option<String> {
+        let captured = entry.metadata.contains_key("session_id") && entry.category != "events";
+        let reviewed = entry
+            .metadata
+            .get(review::STATUS_KEY)
+            .is_some_and(|s| s == review::REVIEWED);
+        if !captured || reviewed {
+            return None;
+        }
+        // Stored content starts with the title heading; the hook routed the body alone
+        let heading = format!("# {}\n\n", entry.title);
+        let content = entry.content.strip_prefix(&heading).unwrap_or(&entry.content);
+        let (category, agent) = self.determine_category(entry.metadata.get("agent").map(String::as_str), content);
+        if agent.is_none() && entry.metadata.contains_key("labeled_by") {
+            return None;
+        }
+        Some(category)
     }
 
     fn on_session_end(&self, payload: &serde_json::Value) -> HookResult {
@@ -196,7 +379,7 @@ impl HistoryHandler {
             .with_tag("session_end")
             .with_metadata("session_id", session_id);
 
-        let store = HistoryStore::new(self.history_path.clone());
+        let store = self.store();
         if let Err(e) = store.store(&entry) {
             log::error!("Failed to log session end: {}", e);
         }
@@ -232,14 +415,23 @@ impl HookHandler for HistoryHandler {
 /// Extract the last assistant response from a Claude Code transcript file.
 ///
 /// Claude Code provides `transcript_path` in Stop events, pointing to a JSONL file
-/// containing the full conversation. We read backwards to find the last assistant message.
-fn extract_response_from_transcript(transcript_path: &str) -> Option<String> {
-    let content = fs::read_to_string(transcript_path).ok()?;
-    let lines: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
-
-    // Parse backwards to find the last assistant message
-    for line in lines.iter().rev() {
-        if let Ok(entry) = serde_json::from_str::<serde_json::Value>(line)
+/// containing the full conversation. We read backwards in chunks to find the last
+/// assistant message without loading the whole transcript.
+fn extract_response_from_transcript(transcript_path: &Path) -> Option<String> {
+    let lines = ReverseLines::open(transcript_path).ok()?;
+
+    for line in lines {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                log::warn!("Failed to read transcript {}: {}", transcript_path.display(), e);
+                return None;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Ok(entry) = serde_json::from_str::<serde_json::Value>(&line)
             && entry.get("type").and_then(|t| t.as_str()) == Some("assistant")
             && let Some(message) = entry.get("message")
             && let Some(content) = message.get("content")
@@ -292,17 +484,12 @@ fn build_session_summary(payload: &serde_json::Value) -> String {
         summary.push_str(&format!("**Stop reason:** {}\n\n", reason));
     }
 
-    // Try to get response from payload first, then from transcript_path
-    let response = payload
-        .get("response")
-        .and_then(|v| v.as_str())
-        .map(|s| s.to_string())
-        .or_else(|| {
-            payload
-                .get("transcript_path")
-                .and_then(|v| v.as_str())
-                .and_then(extract_response_from_transcript)
-        });
+    // Older releases send the response inline, current ones a transcript to read it from
+    let response = match compat::response_source(payload) {
+        Some(ResponseSource::Inline(text)) => Some(text.to_string()),
+        Some(ResponseSource::Transcript(path)) => extract_response_from_transcript(path),
+        None => None,
+    };
 
     if let Some(response_text) = response {
         summary.push_str("## Final Response\n\n");
@@ -334,7 +521,9 @@ fn build_session_summary(payload: &serde_json::Value) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::HistoryConfig;
     use serde_json::json;
+    use std::fs;
     use std::io::Write;
     use tempfile::{NamedTempFile, tempdir};
 
@@ -640,6 +829,45 @@ mod tests {
     // Original tests (kept for completeness)
     // =========================================================================
 
+    #[test]
+    fn test_transcript_entry_is_dated_when_the_session_ended() {
+        let temp_dir = tempdir().expect("Failed to create temp dir");
+        let handler = HistoryHandler::new(true, temp_dir.path().to_path_buf());
+        let mut temp_file = NamedTempFile::new().expect("Failed to create temp file");
+        temp_file
+            .write_all(br#"{"type":"assistant","message":{"content":[{"type":"text","text":"I discovered the root cause was a stale lock file left by a crashed build."}]}}"#)
+            .expect("Failed to write");
+        let ended = chrono::DateTime::parse_from_rfc3339("2025-11-02T14:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Local);
+        let session = SessionInfo {
+            session_id: "old-session".to_string(),
+            cwd: Some("/src/app".to_string()),
+            ended: Some(ended),
+        };
+
+        let entry = handler.transcript_entry(temp_file.path(), &session).unwrap();
+        assert_eq!(entry.created_at, ended);
+        assert_eq!(entry.id, format!("{:x}", ended.timestamp_millis()));
+        assert_eq!(entry.metadata["session_id"], "old-session");
+        assert_eq!(entry.metadata["cwd"], "/src/app");
+        assert!(entry.tags.contains(&"imported".to_string()));
+        assert!(entry.content.contains("stale lock file"));
+
+        let path = handler.import(&entry).unwrap();
+        assert!(
+            path.starts_with(
+                temp_dir
+                    .path()
+                    .join(&entry.category)
+                    .join(ended.format("%Y-%m-%d").to_string())
+            )
+        );
+
+        let empty = NamedTempFile::new().expect("Failed to create temp file");
+        assert!(handler.transcript_entry(empty.path(), &session).is_none());
+    }
+
     #[test]
     fn test_build_session_summary() {
         let payload = json!({
@@ -693,6 +921,36 @@ mod tests {
         assert!(agent.is_none());
     }
 
+    #[test]
+    fn test_determine_category_from_route_agents() {
+        let temp_dir = tempdir().expect("Failed to create temp dir");
+        let mut config = HistoryConfig::default();
+        config.categories[2].route.agents = vec!["Explore".to_string()];
+        let handler =
+            HistoryHandler::new(true, temp_dir.path().to_path_buf()).with_categories(Categories::from_config(&config));
+
+        // Claude Code's built-in subagents have no definition file, only a route
+        let (category, agent) = handler.determine_category(Some("Explore"), "regular session work");
+        assert_eq!(category, "research");
+        assert_eq!(agent.as_deref(), Some("Explore"));
+    }
+
+    #[test]
+    fn test_reroute_only_rule_placed_entries() {
+        let temp_dir = tempdir().expect("Failed to create temp dir");
+        let handler = HistoryHandler::new(true, temp_dir.path().to_path_buf());
+        let captured = HistoryEntry::new("sessions", "Fix", "debugging the problem and found the root cause")
+            .with_metadata("session_id", "abc123");
+        assert_eq!(handler.reroute(&captured).as_deref(), Some("learnings"));
+
+        let note = HistoryEntry::new("sessions", "Note", "debugging the problem and found the root cause");
+        assert_eq!(handler.reroute(&note), None);
+        let reviewed = captured.clone().with_metadata(review::STATUS_KEY, review::REVIEWED);
+        assert_eq!(handler.reroute(&reviewed), None);
+        let labeled = captured.with_metadata("labeled_by", "ollama/llama3.2");
+        assert_eq!(handler.reroute(&labeled), None);
+    }
+
     #[test]
     fn test_handles_subagent_stop() {
         let temp_dir = tempdir().expect("Failed to create temp dir");
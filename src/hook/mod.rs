@@ -5,11 +5,19 @@
 
 use serde::{Deserialize, Serialize};
 
-pub mod dispatch;
+pub mod checkpoint;
+pub mod handoff;
 pub mod history;
+#[cfg(feature = "notify")]
+pub mod notify;
+pub mod policy;
+pub mod prompt;
+pub mod registry;
 pub mod research;
 pub mod security;
+pub mod tools;
 pub mod ui;
+pub mod usage;
 
 /// Hook event types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
@@ -43,6 +51,11 @@ impl HookEvent {
             _ => None,
         }
     }
+
+    /// Whether Claude Code adds hook output for this event to the conversation
+    pub fn accepts_context(self) -> bool {
+        matches!(self, Self::SessionStart | Self::UserPromptSubmit | Self::PostToolUse)
+    }
 }
 
 impl std::fmt::Display for HookEvent {
@@ -83,6 +96,38 @@ impl HookResult {
     }
 }
 
+/// Render context that plugin hooks asked to inject as the event's stdout
+///
+/// SessionStart and UserPromptSubmit add plain stdout to the conversation, so
+/// each plugin's text goes out as a `<system-reminder>` block alongside what
+/// the built-in handlers print. PostToolUse only reads `additionalContext`
+/// from a `hookSpecificOutput` JSON object. Other events can't add context.
+pub fn context_output(event: HookEvent, context: &[(String, String)]) -> Option<String> {
+    if context.is_empty() || !event.accepts_context() {
+        return None;
+    }
+    let blocks: Vec<String> = context
+        .iter()
+        .map(|(plugin, text)| format!("PAIS PLUGIN CONTEXT (from {})\n\n{}", plugin, text))
+        .collect();
+    if event == HookEvent::PostToolUse {
+        let output = serde_json::json!({
+            "hookSpecificOutput": {
+                "hookEventName": event.to_string(),
+                "additionalContext": blocks.join("\n\n"),
+            }
+        });
+        return Some(output.to_string());
+    }
+    Some(
+        blocks
+            .iter()
+            .map(|block| format!("<system-reminder>\n{}\n</system-reminder>", block))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
 /// A hook handler
 pub trait HookHandler: Send + Sync {
     /// Handler name for logging
@@ -164,4 +209,26 @@ mod tests {
         let parsed: HookEvent = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed, event);
     }
+
+    #[test]
+    fn test_context_output() {
+        let context = vec![("deploys".to_string(), "Deploys are frozen.".to_string())];
+
+        let text = context_output(HookEvent::SessionStart, &context).unwrap();
+        assert!(text.starts_with("<system-reminder>\nPAIS PLUGIN CONTEXT (from deploys)"));
+        assert!(text.contains("Deploys are frozen.\n</system-reminder>"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&context_output(HookEvent::PostToolUse, &context).unwrap()).unwrap();
+        assert_eq!(json["hookSpecificOutput"]["hookEventName"], "PostToolUse");
+        assert!(
+            json["hookSpecificOutput"]["additionalContext"]
+                .as_str()
+                .unwrap()
+                .ends_with("Deploys are frozen.")
+        );
+
+        assert_eq!(context_output(HookEvent::Stop, &context), None);
+        assert_eq!(context_output(HookEvent::SessionStart, &[]), None);
+    }
 }
@@ -0,0 +1,80 @@
+//! Desktop notifications (cargo feature `notify`)
+//!
+//! Forwards Claude Code's Notification events ("Claude needs your
+//! permission", "waiting for input") to the desktop: `notify-send` on Linux,
+//! `osascript` on macOS. `hooks.handlers.notify.settings.command` replaces the
+//! notifier; the message is appended as its last argument.
+
+use serde::Deserialize;
+use std::process::{Command, Stdio};
+
+use super::registry::Registration;
+use super::{HookEvent, HookHandler, HookResult};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct NotifySettings {
+    command: Vec<String>,
+}
+
+pub fn registration() -> Registration {
+    Registration {
+        name: "notify",
+        description: "Shows Claude Code notifications on the desktop",
+        events: &[HookEvent::Notification],
+        priority: 100,
+        enabled: |_| true,
+        build: |_, settings| Box::new(NotifyHandler::new(settings.parse::<NotifySettings>("notify").command)),
+    }
+}
+
+pub struct NotifyHandler {
+    command: Vec<String>,
+}
+
+impl NotifyHandler {
+    fn new(command: Vec<String>) -> Self {
+        let command = if !command.is_empty() {
+            command
+        } else if cfg!(target_os = "macos") {
+            vec!["osascript".into(), "-e".into()]
+        } else {
+            vec!["notify-send".into(), "Claude Code".into()]
+        };
+        Self { command }
+    }
+}
+
+impl HookHandler for NotifyHandler {
+    fn name(&self) -> &'static str {
+        "notify"
+    }
+
+    fn handles(&self, event: HookEvent) -> bool {
+        event == HookEvent::Notification
+    }
+
+    fn handle(&self, _event: HookEvent, payload: &serde_json::Value) -> HookResult {
+        let Some(message) = payload.get("message").and_then(|v| v.as_str()) else {
+            return HookResult::Allow;
+        };
+        let message = if self.command[0] == "osascript" {
+            format!("display notification {:?} with title \"Claude Code\"", message)
+        } else {
+            message.to_string()
+        };
+        // Don't hold up the hook on the notifier
+        match Command::new(&self.command[0])
+            .args(&self.command[1..])
+            .arg(message)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(_) => HookResult::Allow,
+            Err(e) => HookResult::Error {
+                message: format!("Failed to run {}: {}", self.command[0], e),
+            },
+        }
+    }
+}
@@ -0,0 +1,102 @@
+//! Policy hook handler
+//!
+//! Evaluates user-defined policy rules (see `crate::policy`) against every
+//! hook event they apply to.
+
+use std::sync::Arc;
+
+use super::{HookEvent, HookHandler, HookResult};
+use crate::policy::{PolicyEffect, PolicyEngine};
+
+/// Policy hook handler
+pub struct PolicyHandler {
+    enabled: bool,
+    engine: Arc<PolicyEngine>,
+}
+
+impl PolicyHandler {
+    pub fn new(enabled: bool, engine: Arc<PolicyEngine>) -> Self {
+        Self { enabled, engine }
+    }
+}
+
+impl HookHandler for PolicyHandler {
+    fn name(&self) -> &'static str {
+        "policy"
+    }
+
+    fn handles(&self, event: HookEvent) -> bool {
+        self.enabled && self.engine.handles(event)
+    }
+
+    fn handle(&self, event: HookEvent, payload: &serde_json::Value) -> HookResult {
+        let Some(decision) = self.engine.evaluate(event, payload) else {
+            return HookResult::Allow;
+        };
+
+        match decision.effect {
+            PolicyEffect::Block => {
+                let mut message = format!("🚨 BLOCKED [Policy {}]: {}", decision.rule, decision.message);
+                if let Some(suggestion) = decision.suggestion {
+                    message.push_str(&format!("\n  Instead: {}", suggestion));
+                }
+                HookResult::Block { message }
+            }
+            PolicyEffect::Warn => {
+                eprintln!("⚠️  WARNING [Policy {}]: {}", decision.rule, decision.message);
+                HookResult::Allow
+            }
+            PolicyEffect::Log => {
+                log::info!("📝 LOGGED [Policy {}]: {}", decision.rule, decision.message);
+                HookResult::Allow
+            }
+            PolicyEffect::Allow => {
+                log::debug!("Policy '{}' allowed {} ({})", decision.rule, event, decision.source);
+                HookResult::Allow
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::PolicyRule;
+
+    fn engine(action: PolicyEffect) -> Arc<PolicyEngine> {
+        Arc::new(PolicyEngine::new(&[PolicyRule {
+            name: "test".to_string(),
+            events: vec!["PreToolUse".to_string()],
+            when: r#"tool_name == "Write""#.to_string(),
+            action,
+            message: Some("no writes".to_string()),
+            suggestion: Some("write to a scratch file".to_string()),
+            rate_limit: None,
+        }]))
+    }
+
+    #[test]
+    fn test_block_rule_blocks() {
+        let handler = PolicyHandler::new(true, engine(PolicyEffect::Block));
+        let result = handler.handle(HookEvent::PreToolUse, &serde_json::json!({"tool_name": "Write"}));
+        assert!(matches!(result, HookResult::Block { ref message } if message.contains("no writes")));
+        assert!(
+            matches!(result, HookResult::Block { ref message } if message.ends_with("Instead: write to a scratch file"))
+        );
+    }
+
+    #[test]
+    fn test_non_matching_allows() {
+        let handler = PolicyHandler::new(true, engine(PolicyEffect::Block));
+        let result = handler.handle(HookEvent::PreToolUse, &serde_json::json!({"tool_name": "Read"}));
+        assert!(matches!(result, HookResult::Allow));
+    }
+
+    #[test]
+    fn test_handles_only_rule_events() {
+        let handler = PolicyHandler::new(true, engine(PolicyEffect::Block));
+        assert!(handler.handles(HookEvent::PreToolUse));
+        assert!(!handler.handles(HookEvent::Stop));
+        assert!(!PolicyHandler::new(false, engine(PolicyEffect::Block)).handles(HookEvent::PreToolUse));
+    }
+}
@@ -0,0 +1,39 @@
+//! Prompt state hooks
+//!
+//! Tracks running sessions in the cached state read by `pais status --prompt`.
+
+use chrono::Utc;
+
+use super::{HookEvent, HookHandler, HookResult};
+use crate::prompt::{self, PROFILE_ENV};
+
+/// Records SessionStart/SessionEnd in the prompt state file
+pub struct PromptStateHandler;
+
+impl HookHandler for PromptStateHandler {
+    fn name(&self) -> &'static str {
+        "prompt"
+    }
+
+    fn handles(&self, event: HookEvent) -> bool {
+        matches!(event, HookEvent::SessionStart | HookEvent::SessionEnd)
+    }
+
+    fn handle(&self, event: HookEvent, payload: &serde_json::Value) -> HookResult {
+        let Some(session_id) = payload.get("session_id").and_then(|v| v.as_str()) else {
+            return HookResult::Allow;
+        };
+
+        match event {
+            HookEvent::SessionStart => {
+                // Hooks inherit the environment `pais session` gave Claude
+                let profile = std::env::var(PROFILE_ENV).ok().filter(|p| !p.is_empty());
+                prompt::update(|state| state.session_started(session_id, profile, Utc::now().timestamp()));
+            }
+            HookEvent::SessionEnd => prompt::update(|state| state.session_ended(session_id)),
+            _ => {}
+        }
+
+        HookResult::Allow
+    }
+}
@@ -0,0 +1,416 @@
+//! Registry of built-in hook handlers
+//!
+//! Each built-in handler registers a name, the events it handles, a default
+//! priority, whether it is on by default and a factory. `pais hook dispatch`
+//! builds the enabled handlers for the event in priority order (lower first),
+//! and `pais hook list` reads the same table.
+//!
+//! `hooks.handlers.<name>` in the config overrides `enabled` and `priority`
+//! and carries a free-form `settings` section the factory can read with
+//! [`HandlerSettings::parse`]. Optional handlers are compiled in with cargo
+//! features and register themselves in [`Registry::builtin`].
+
+use serde::de::DeserializeOwned;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use super::checkpoint::CheckpointHandler;
+use super::handoff::HandoffHandler;
+use super::history::HistoryHandler;
+use super::policy::PolicyHandler;
+use super::prompt::PromptStateHandler;
+use super::research::ResearchPathValidator;
+use super::security::{PatternSet, SecurityValidator};
+use super::tools::ToolTimingHandler;
+use super::ui::{UiHandler, UiSettings};
+use super::usage::SkillUsageHandler;
+use super::{HookEvent, HookHandler};
+use crate::config::{CheckpointConfig, Config, HandlerSettings};
+use crate::history::categorize::Categories;
+use crate::history::dedupe::Dedupe;
+use crate::history::encrypt::Encryption;
+use crate::history::label::Labeler;
+use crate::history::redact::Redactor;
+use crate::observability::EventEmitter;
+use crate::policy::PolicyEngine;
+use crate::secrets::SecretStore;
+
+const ALL_EVENTS: &[HookEvent] = &[
+    HookEvent::PreToolUse,
+    HookEvent::PostToolUse,
+    HookEvent::Stop,
+    HookEvent::SessionStart,
+    HookEvent::SessionEnd,
+    HookEvent::SubagentStop,
+    HookEvent::Notification,
+    HookEvent::PermissionRequest,
+    HookEvent::UserPromptSubmit,
+    HookEvent::PreCompact,
+];
+
+/// What a factory gets to build its handler for one dispatch
+pub struct HandlerContext<'a> {
+    pub config: &'a Config,
+    pub history_path: PathBuf,
+    pub policy: Arc<PolicyEngine>,
+    /// Whether the payload's workspace is trusted (see [`crate::trust`])
+    pub trusted: bool,
+}
+
+/// A built-in handler
+pub struct Registration {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub events: &'static [HookEvent],
+    /// Lower runs first
+    pub priority: i32,
+    /// Whether it runs when `hooks.handlers.<name>.enabled` is unset
+    pub enabled: fn(&Config) -> bool,
+    pub build: fn(&HandlerContext, &HandlerSettings) -> Box<dyn HookHandler>,
+}
+
+/// A registration with the config's overrides applied
+pub struct Resolved<'a> {
+    pub registration: &'a Registration,
+    pub enabled: bool,
+    pub priority: i32,
+    pub settings: HandlerSettings,
+}
+
+#[derive(Default)]
+pub struct Registry {
+    handlers: Vec<Registration>,
+}
+
+impl HandlerSettings {
+    /// The `settings` section as `T`; unparseable settings fall back to the default
+    pub fn parse<T: DeserializeOwned + Default>(&self, handler: &str) -> T {
+        serde_json::from_value(serde_json::Value::Object(self.settings.clone())).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid settings for hook handler {}: {}", handler, e);
+            T::default()
+        })
+    }
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every handler compiled into this build
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        registry.register(Registration {
+            name: "policy",
+            description: "Applies policy rules (block, warn, or allow) to any event",
+            events: ALL_EVENTS,
+            priority: 10,
+            enabled: |config| config.policy.enabled,
+            build: |ctx, _| Box::new(PolicyHandler::new(true, ctx.policy.clone())),
+        });
+        registry.register(Registration {
+            name: "security",
+            description: "Blocks dangerous commands before execution",
+            events: &[HookEvent::PreToolUse],
+            priority: 20,
+            enabled: |config| config.hooks.security_enabled,
+            build: |ctx, _| {
+                let mut security = SecurityValidator::new(true)
+                    .with_log_path(ctx.history_path.clone())
+                    .with_policy(ctx.policy.clone())
+                    .with_strict(!ctx.trusted)
+                    .with_explanations(ctx.config.security.explain);
+                // A broken patterns file must not disable the built-in tiers
+                match PatternSet::load(&Config::expand_path(&ctx.config.security.patterns)) {
+                    Ok(patterns) => security = security.with_patterns(patterns),
+                    Err(e) => log::warn!("Ignoring user security patterns: {:#}", e),
+                }
+                Box::new(security)
+            },
+        });
+        registry.register(Registration {
+            name: "research",
+            description: "Validates research directory path structure",
+            events: &[HookEvent::PreToolUse],
+            priority: 30,
+            enabled: |config| config.hooks.research_enabled,
+            build: |_, _| Box::new(ResearchPathValidator::new(true)),
+        });
+        registry.register(Registration {
+            name: "history",
+            description: "Captures session lifecycle events",
+            events: &[
+                HookEvent::SessionStart,
+                HookEvent::Stop,
+                HookEvent::SubagentStop,
+                HookEvent::SessionEnd,
+            ],
+            priority: 40,
+            enabled: |config| config.hooks.history_enabled,
+            build: |ctx, _| {
+                let history = &ctx.config.history;
+                Box::new(
+                    HistoryHandler::new(true, ctx.history_path.clone())
+                        .with_redactor(Redactor::from_config(&history.redaction))
+                        .with_categories(Categories::from_config(history))
+                        .with_review(history.review.enabled)
+                        .with_index(history.index.enabled)
+                        .with_encryption(Encryption::from_config(&history.encryption))
+                        .with_dedupe(Dedupe::from_config(&history.dedupe))
+                        .with_labeler(
+                            // Only read secrets when labeling is on
+                            history
+                                .labeling
+                                .enabled
+                                .then(|| {
+                                    Labeler::from_config(&history.labeling, &ctx.config.network, &SecretStore::load())
+                                })
+                                .flatten(),
+                        ),
+                )
+            },
+        });
+        registry.register(Registration {
+            name: "ui",
+            description: "Updates terminal tab title",
+            events: &[HookEvent::UserPromptSubmit],
+            priority: 50,
+            enabled: |config| config.hooks.ui_enabled,
+            build: |_, settings| Box::new(UiHandler::new(true).with_settings(settings.parse::<UiSettings>("ui"))),
+        });
+        registry.register(Registration {
+            name: "prompt",
+            description: "Tracks running sessions for `pais status --prompt`",
+            events: &[HookEvent::SessionStart, HookEvent::SessionEnd],
+            priority: 60,
+            enabled: |_| true,
+            build: |_, _| Box::new(PromptStateHandler),
+        });
+        registry.register(Registration {
+            name: "handoff",
+            description: "Writes a per-project handoff at session end and injects it at the next start",
+            events: &[HookEvent::SessionStart, HookEvent::SessionEnd],
+            priority: 70,
+            enabled: |config| config.hooks.history_enabled && config.history.handoff.enabled,
+            build: |ctx, _| {
+                let history = &ctx.config.history;
+                Box::new(
+                    HandoffHandler::new(true, history.handoff.clone())
+                        .with_redactor(Redactor::from_config(&history.redaction)),
+                )
+            },
+        });
+        registry.register(Registration {
+            name: "checkpoint",
+            description: "Snapshots the repository as a git checkpoint every N file edits",
+            events: &[HookEvent::PostToolUse, HookEvent::SessionEnd],
+            priority: 80,
+            enabled: |config| config.hooks.checkpoint.enabled,
+            build: |ctx, _| {
+                Box::new(CheckpointHandler::new(CheckpointConfig {
+                    enabled: true,
+                    ..ctx.config.hooks.checkpoint.clone()
+                }))
+            },
+        });
+        registry.register(Registration {
+            name: "skill-usage",
+            description: "Records skill triggers for usage-based context injection and `pais skill stats`",
+            events: &[HookEvent::PreToolUse, HookEvent::UserPromptSubmit],
+            priority: 90,
+            enabled: |config| config.skills.usage.track,
+            build: |ctx, _| {
+                let skills_dirs = std::iter::once(Config::expand_path(&ctx.config.paths.skills))
+                    .chain(Config::claude_skills_dir())
+                    .collect();
+                let emitter = EventEmitter::new(ctx.config.observability.clone(), ctx.history_path.clone());
+                Box::new(SkillUsageHandler::new(true, skills_dirs, emitter))
+            },
+        });
+        registry.register(Registration {
+            name: "tool-timing",
+            description: "Times each tool call and records whether it failed",
+            events: &[HookEvent::PreToolUse, HookEvent::PostToolUse],
+            // Last, so calls another handler blocks are never started
+            priority: 95,
+            enabled: |config| config.history.tools.enabled,
+            build: |ctx, _| Box::new(ToolTimingHandler::new(&ctx.history_path)),
+        });
+        #[cfg(feature = "notify")]
+        registry.register(super::notify::registration());
+        registry
+    }
+
+    /// Add a handler; a later registration with the same name replaces the earlier one
+    pub fn register(&mut self, registration: Registration) {
+        self.handlers.retain(|r| r.name != registration.name);
+        self.handlers.push(registration);
+    }
+
+    /// Every handler with the config's overrides, in run order
+    pub fn resolve(&self, config: &Config) -> Vec<Resolved<'_>> {
+        let mut resolved: Vec<Resolved> = self
+            .handlers
+            .iter()
+            .map(|registration| {
+                let settings = config
+                    .hooks
+                    .handlers
+                    .get(registration.name)
+                    .cloned()
+                    .unwrap_or_default();
+                Resolved {
+                    registration,
+                    enabled: settings.enabled.unwrap_or_else(|| (registration.enabled)(config)),
+                    priority: settings.priority.unwrap_or(registration.priority),
+                    settings,
+                }
+            })
+            .collect();
+        resolved.sort_by_key(|r| r.priority);
+        resolved
+    }
+
+    /// The enabled handlers for `event`, built and in run order
+    pub fn build(&self, event: HookEvent, ctx: &HandlerContext) -> Vec<Box<dyn HookHandler>> {
+        for name in ctx.config.hooks.handlers.keys() {
+            if !self.handlers.iter().any(|r| r.name == name) {
+                log::warn!("Ignoring settings for unknown hook handler '{}'", name);
+            }
+        }
+        self.resolve(ctx.config)
+            .into_iter()
+            .filter(|r| r.enabled && r.registration.events.contains(&event))
+            .map(|r| (r.registration.build)(ctx, &r.settings))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hook::HookResult;
+
+    fn context(config: &Config) -> HandlerContext<'_> {
+        HandlerContext {
+            config,
+            history_path: PathBuf::from("/nonexistent/pais-history"),
+            policy: Arc::new(PolicyEngine::new(&config.policy.rules)),
+            trusted: true,
+        }
+    }
+
+    #[test]
+    fn test_overrides_enable_and_reorder() {
+        let mut config = Config::default();
+        config.hooks.ui_enabled = false;
+        config.hooks.handlers.insert(
+            "ui".to_string(),
+            HandlerSettings {
+                enabled: Some(true),
+                ..Default::default()
+            },
+        );
+        config.hooks.handlers.insert(
+            "research".to_string(),
+            HandlerSettings {
+                priority: Some(1),
+                ..Default::default()
+            },
+        );
+        config.hooks.handlers.insert(
+            "security".to_string(),
+            HandlerSettings {
+                enabled: Some(false),
+                ..Default::default()
+            },
+        );
+
+        let registry = Registry::builtin();
+        let resolved = registry.resolve(&config);
+        assert_eq!(resolved[0].registration.name, "research");
+        assert!(resolved.iter().find(|r| r.registration.name == "ui").unwrap().enabled);
+
+        let ctx = context(&config);
+        let names: Vec<&str> = registry
+            .build(HookEvent::PreToolUse, &ctx)
+            .iter()
+            .map(|h| h.name())
+            .collect();
+        assert!(!names.iter().any(|n| n.contains("security")));
+        assert!(
+            registry
+                .build(HookEvent::UserPromptSubmit, &ctx)
+                .iter()
+                .any(|h| h.name() == "ui")
+        );
+    }
+
+    #[test]
+    fn test_registered_events_cover_what_handlers_handle() {
+        let mut config = Config::default();
+        config.hooks.checkpoint.enabled = true;
+        config.history.handoff.enabled = true;
+        let ctx = context(&config);
+        for resolved in Registry::builtin().resolve(&config) {
+            let handler = (resolved.registration.build)(&ctx, &resolved.settings);
+            for &event in ALL_EVENTS {
+                if handler.handles(event) {
+                    assert!(
+                        resolved.registration.events.contains(&event),
+                        "{} handles {} but doesn't register it",
+                        resolved.registration.name,
+                        event
+                    );
+                }
+            }
+        }
+    }
+
+    struct Echo(String);
+
+    impl HookHandler for Echo {
+        fn name(&self) -> &'static str {
+            "echo"
+        }
+
+        fn handles(&self, event: HookEvent) -> bool {
+            event == HookEvent::Stop
+        }
+
+        fn handle(&self, _event: HookEvent, _payload: &serde_json::Value) -> HookResult {
+            HookResult::Block {
+                message: self.0.clone(),
+            }
+        }
+    }
+
+    #[derive(Default, serde::Deserialize)]
+    struct EchoSettings {
+        message: String,
+    }
+
+    #[test]
+    fn test_register_with_settings() {
+        let mut config = Config::default();
+        let mut settings = HandlerSettings::default();
+        settings.settings.insert("message".to_string(), "hi".into());
+        config.hooks.handlers.insert("echo".to_string(), settings);
+
+        let mut registry = Registry::new();
+        registry.register(Registration {
+            name: "echo",
+            description: "Echoes its settings",
+            events: &[HookEvent::Stop],
+            priority: 0,
+            enabled: |_| true,
+            build: |_, settings| Box::new(Echo(settings.parse::<EchoSettings>("echo").message)),
+        });
+
+        let handlers = registry.build(HookEvent::Stop, &context(&config));
+        match handlers[0].handle(HookEvent::Stop, &serde_json::Value::Null) {
+            HookResult::Block { message } => assert_eq!(message, "hi"),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
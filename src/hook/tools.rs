@@ -0,0 +1,53 @@
+//! Tool timing hook
+//!
+//! Records each PreToolUse and completes it on PostToolUse with the outcome,
+//! for `pais history tools`. See [`crate::history::tools`].
+
+use std::path::Path;
+
+use super::{HookEvent, HookHandler, HookResult};
+use crate::clock;
+use crate::history::tools::{self, ToolStore};
+
+pub struct ToolTimingHandler {
+    store: ToolStore,
+}
+
+impl ToolTimingHandler {
+    pub fn new(history_path: &Path) -> Self {
+        Self {
+            store: ToolStore::new(history_path),
+        }
+    }
+}
+
+impl HookHandler for ToolTimingHandler {
+    fn name(&self) -> &'static str {
+        "tool-timing"
+    }
+
+    fn handles(&self, event: HookEvent) -> bool {
+        matches!(event, HookEvent::PreToolUse | HookEvent::PostToolUse)
+    }
+
+    fn handle(&self, event: HookEvent, payload: &serde_json::Value) -> HookResult {
+        let field = |key: &str| payload.get(key).and_then(|v| v.as_str());
+        let (Some(session), Some(tool)) = (field("session_id"), field("tool_name")) else {
+            return HookResult::Allow;
+        };
+        let id = field("tool_use_id");
+        let result = match event {
+            HookEvent::PreToolUse => {
+                self.store
+                    .start(session, id, tool, tools::detail(payload).as_deref(), clock::now())
+            }
+            _ => self
+                .store
+                .finish(session, id, tool, clock::now(), tools::outcome(payload)),
+        };
+        if let Err(e) = result {
+            log::warn!("Failed to record {} timing: {:#}", tool, e);
+        }
+        HookResult::Allow
+    }
+}
@@ -2,16 +2,56 @@
 //!
 //! Handles terminal UI updates like tab titles.
 
+use serde::Deserialize;
+
 use super::{HookEvent, HookHandler, HookResult};
 
+/// `hooks.handlers.ui.settings`
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct UiSettings {
+    /// Put before the task summary; empty for none
+    pub prefix: String,
+    /// Longest summary, in characters, before it is cut with "..."
+    pub max_length: usize,
+}
+
+impl Default for UiSettings {
+    fn default() -> Self {
+        Self {
+            prefix: "🤖".to_string(),
+            max_length: 40,
+        }
+    }
+}
+
 /// UI hook handler - updates terminal tab titles
 pub struct UiHandler {
     enabled: bool,
+    settings: UiSettings,
 }
 
 impl UiHandler {
     pub fn new(enabled: bool) -> Self {
-        Self { enabled }
+        Self {
+            enabled,
+            settings: UiSettings::default(),
+        }
+    }
+
+    pub fn with_settings(mut self, settings: UiSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// The tab title for a prompt
+    fn title(&self, prompt: &str) -> String {
+        let summary = extract_task_summary(prompt, self.settings.max_length);
+        if self.settings.prefix.is_empty() {
+            summary
+        } else {
+            format!("{} {}", self.settings.prefix, summary)
+        }
     }
 
     /// Update terminal tab title based on user prompt
@@ -27,18 +67,18 @@ impl UiHandler {
             return HookResult::Allow;
         }
 
-        // Extract a short summary from the prompt
-        let summary = extract_task_summary(prompt);
+        // Prefix and a short summary of the prompt
+        let title = self.title(prompt);
 
         // Update terminal tab title using OSC escape sequence
         // OSC 0 sets both title and icon name
         // Format: \x1b]0;TITLE\x07
-        print!("\x1b]0;🤖 {}\x07", summary);
+        print!("\x1b]0;{}\x07", title);
 
         // Also set just the title (OSC 2)
-        print!("\x1b]2;🤖 {}\x07", summary);
+        print!("\x1b]2;{}\x07", title);
 
-        log::debug!("Updated tab title to: 🤖 {}", summary);
+        log::debug!("Updated tab title to: {}", title);
 
         HookResult::Allow
     }
@@ -61,10 +101,8 @@ impl HookHandler for UiHandler {
     }
 }
 
-/// Extract a short task summary from user prompt
-fn extract_task_summary(prompt: &str) -> String {
-    const MAX_LEN: usize = 40;
-
+/// Extract a task summary of at most `max_len` characters from user prompt
+fn extract_task_summary(prompt: &str, max_len: usize) -> String {
     // Clean up the prompt
     let cleaned = prompt
         .lines()
@@ -101,8 +139,9 @@ fn extract_task_summary(prompt: &str) -> String {
     };
 
     // Truncate if too long
-    if summary.len() > MAX_LEN {
-        format!("{}...", &summary[..MAX_LEN - 3])
+    if summary.chars().count() > max_len {
+        let kept: String = summary.chars().take(max_len.saturating_sub(3)).collect();
+        format!("{}...", kept)
     } else {
         summary
     }
@@ -114,20 +153,20 @@ mod tests {
 
     #[test]
     fn test_extract_task_summary_simple() {
-        assert_eq!(extract_task_summary("Fix the bug"), "Fix the bug");
+        assert_eq!(extract_task_summary("Fix the bug", 40), "Fix the bug");
     }
 
     #[test]
     fn test_extract_task_summary_strips_prefix() {
-        assert_eq!(extract_task_summary("Please fix the bug"), "Fix the bug");
-        assert_eq!(extract_task_summary("Can you fix this?"), "Fix this?");
-        assert_eq!(extract_task_summary("I need you to refactor"), "Refactor");
+        assert_eq!(extract_task_summary("Please fix the bug", 40), "Fix the bug");
+        assert_eq!(extract_task_summary("Can you fix this?", 40), "Fix this?");
+        assert_eq!(extract_task_summary("I need you to refactor", 40), "Refactor");
     }
 
     #[test]
     fn test_extract_task_summary_truncates() {
         let long_prompt = "Implement a very long feature that does many things and has lots of requirements";
-        let summary = extract_task_summary(long_prompt);
+        let summary = extract_task_summary(long_prompt, 40);
         assert!(summary.len() <= 40);
         assert!(summary.ends_with("..."));
     }
@@ -135,12 +174,25 @@ mod tests {
     #[test]
     fn test_extract_task_summary_multiline() {
         let prompt = "Fix the bug\n\nHere are the details:\n- error 1\n- error 2";
-        assert_eq!(extract_task_summary(prompt), "Fix the bug");
+        assert_eq!(extract_task_summary(prompt, 40), "Fix the bug");
     }
 
     #[test]
     fn test_extract_task_summary_capitalizes() {
-        assert_eq!(extract_task_summary("fix the bug"), "Fix the bug");
+        assert_eq!(extract_task_summary("fix the bug", 40), "Fix the bug");
+    }
+
+    #[test]
+    fn test_title_from_settings() {
+        let prompt = "Please refactor the ümlaut handling";
+        assert_eq!(UiHandler::new(true).title(prompt), "🤖 Refactor the ümlaut handling");
+
+        let settings = UiSettings {
+            prefix: String::new(),
+            max_length: 12,
+        };
+        let handler = UiHandler::new(true).with_settings(settings);
+        assert_eq!(handler.title(prompt), "Refactor ...");
     }
 
     #[test]
@@ -0,0 +1,69 @@
+//! Skill usage hook
+//!
+//! Records skill triggers seen on PreToolUse (the Skill tool, reads of a
+//! skill's files) and UserPromptSubmit (`/skill` prompts) in the usage store
+//! that `pais context inject` budgets by, and emits each one as a `SkillUsed`
+//! observability event for `pais skill stats`. See [`crate::skill::usage`].
+
+use std::path::PathBuf;
+
+use super::{HookEvent, HookHandler, HookResult};
+use crate::clock;
+use crate::observability::EventEmitter;
+use crate::observability::emitter::SkillUse;
+use crate::skill::usage::{self, Trigger};
+
+pub struct SkillUsageHandler {
+    enabled: bool,
+    path: PathBuf,
+    /// pais's skills directory first, then Claude Code's
+    skills_dirs: Vec<PathBuf>,
+    emitter: EventEmitter,
+}
+
+impl SkillUsageHandler {
+    pub fn new(enabled: bool, skills_dirs: Vec<PathBuf>, emitter: EventEmitter) -> Self {
+        Self {
+            enabled,
+            path: usage::usage_path(),
+            skills_dirs,
+            emitter,
+        }
+    }
+
+    fn is_skill(&self, name: &str) -> bool {
+        self.skills_dirs
+            .iter()
+            .any(|dir| dir.join(name).join("SKILL.md").is_file())
+    }
+}
+
+impl HookHandler for SkillUsageHandler {
+    fn name(&self) -> &'static str {
+        "skill-usage"
+    }
+
+    fn handles(&self, event: HookEvent) -> bool {
+        self.enabled && matches!(event, HookEvent::PreToolUse | HookEvent::UserPromptSubmit)
+    }
+
+    fn handle(&self, event: HookEvent, payload: &serde_json::Value) -> HookResult {
+        let triggered = match event {
+            HookEvent::UserPromptSubmit => payload
+                .get("prompt")
+                .and_then(|p| p.as_str())
+                .and_then(|prompt| usage::prompted_skill(prompt, |name| self.is_skill(name)))
+                .map(|name| (name, Trigger::Prompt)),
+            _ => usage::triggered_skill(payload, &self.skills_dirs),
+        };
+        if let Some((skill, via)) = triggered {
+            log::debug!("Skill triggered: {} (via {})", skill, via);
+            if let Err(e) = usage::record(&self.path, &skill, clock::now()) {
+                log::warn!("Failed to record skill usage: {:#}", e);
+            }
+            self.emitter
+                .emit_skill_use(event, payload, SkillUse { name: skill, via });
+        }
+        HookResult::Allow
+    }
+}
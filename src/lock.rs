@@ -0,0 +1,238 @@
+//! Cross-process file locks
+//!
+//! Hooks fire while CLI commands run, so several pais processes can touch
+//! the same state at once. Writers of shared state (skill symlinks, history,
+//! config files) take an exclusive lock on the resource first. Locks live
+//! under `~/.cache/pais/locks/`, one file per resource path, and are released
+//! when the guard is dropped or the process exits, so a crash never leaves a
+//! stale lock behind.
+//!
+//! Waiting is bounded: after `PAIS_LOCK_TIMEOUT` seconds (default 10) the
+//! caller gets a `locked` error naming the process holding the lock.
+
+use eyre::{Context, Result};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::error::{CliError, ErrorCode};
+use crate::skill::cache::content_hash;
+
+/// How long to wait for a lock unless `PAIS_LOCK_TIMEOUT` says otherwise
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Pause after the first failed attempt; doubles up to `MAX_BACKOFF`
+const INITIAL_BACKOFF: Duration = Duration::from_millis(10);
+
+/// Longest pause between attempts
+const MAX_BACKOFF: Duration = Duration::from_millis(200);
+
+/// Directory holding lock files (`~/.cache/pais/locks/`)
+fn lock_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("pais")
+        .join("locks")
+}
+
+/// Wait limit for acquiring a lock
+fn timeout() -> Duration {
+    std::env::var("PAIS_LOCK_TIMEOUT")
+        .ok()
+        .and_then(|s| s.parse::<f64>().ok())
+        .filter(|secs| secs.is_finite() && *secs >= 0.0)
+        .map(Duration::from_secs_f64)
+        .unwrap_or(DEFAULT_TIMEOUT)
+}
+
+/// Exclusive lock on a resource, released on drop
+#[derive(Debug)]
+pub struct FileLock {
+    file: File,
+    path: PathBuf,
+}
+
+impl FileLock {
+    /// Lock `resource`, waiting up to [`timeout`]
+    pub fn acquire(resource: &Path) -> Result<Self> {
+        Self::acquire_in(&lock_dir(), resource, timeout())
+    }
+
+    fn acquire_in(dir: &Path, resource: &Path, timeout: Duration) -> Result<Self> {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create lock directory {}", dir.display()))?;
+        let path = dir.join(lock_name(resource));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .with_context(|| format!("Failed to open lock file {}", path.display()))?;
+
+        let start = Instant::now();
+        let mut backoff = INITIAL_BACKOFF;
+        loop {
+            match file.try_lock() {
+                Ok(()) => break,
+                Err(TryLockError::WouldBlock) => {}
+                Err(TryLockError::Error(e)) => {
+                    return Err(e).with_context(|| format!("Failed to lock {}", path.display()));
+                }
+            }
+
+            let waited = start.elapsed();
+            if waited >= timeout {
+                return Err(locked_error(resource, &path, timeout).into());
+            }
+            if backoff == INITIAL_BACKOFF {
+                log::debug!("Waiting for lock on {}", resource.display());
+            }
+            std::thread::sleep(backoff.min(timeout - waited));
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+
+        let mut lock = Self { file, path };
+        lock.record_holder();
+        log::trace!("Locked {} ({})", resource.display(), lock.path.display());
+        Ok(lock)
+    }
+
+    /// Write our pid into the lock file so a blocked process can name the holder
+    fn record_holder(&mut self) {
+        let written = self
+            .file
+            .set_len(0)
+            .and_then(|_| (&self.file).write_all(std::process::id().to_string().as_bytes()));
+        if let Err(e) = written {
+            log::debug!("Failed to record lock holder in {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        // Closing the file releases the lock too; unlocking first frees waiters sooner
+        let _ = self.file.unlock();
+    }
+}
+
+/// Lock file name: readable prefix plus a hash of the full path
+fn lock_name(resource: &Path) -> String {
+    // Only the parent is canonicalized, so a symlinked directory and its
+    // target share one lock, and the name doesn't change when the resource
+    // itself is created, replaced or renamed away
+    let resolved = match (resource.parent(), resource.file_name()) {
+        (Some(parent), Some(name)) => {
+            let parent = if parent.as_os_str().is_empty() { Path::new(".") } else { parent };
+            parent
+                .canonicalize()
+                .map(|parent| parent.join(name))
+                .unwrap_or_else(|_| resource.to_path_buf())
+        }
+        _ => resource.to_path_buf(),
+    };
+    let stem = resolved
+        .file_name()
+        .map(|n| {
+            n.to_string_lossy()
+                .replace(|c: char| !c.is_ascii_alphanumeric() && c != '-', "_")
+        })
+        .unwrap_or_else(|| "root".to_string());
+    format!(
+        "{}-{}.lock",
+        stem,
+        content_hash(resolved.as_os_str().as_encoded_bytes())
+    )
+}
+
+fn locked_error(resource: &Path, lock_path: &Path, timeout: Duration) -> CliError {
+    let holder = fs::read_to_string(lock_path)
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    let message = match holder {
+        Some(ref pid) => format!(
+            "Timed out after {:.1}s waiting for {} (locked by pid {})",
+            timeout.as_secs_f64(),
+            resource.display(),
+            pid
+        ),
+        None => format!(
+            "Timed out after {:.1}s waiting for {}",
+            timeout.as_secs_f64(),
+            resource.display()
+        ),
+    };
+    CliError::new(ErrorCode::Locked, message)
+        .with_hint("Another pais process is writing it; retry, or raise PAIS_LOCK_TIMEOUT (seconds)")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorReport;
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_second_lock_times_out_until_released() {
+        let temp = tempdir().unwrap();
+        let locks = temp.path().join("locks");
+        let resource = temp.path().join("history");
+
+        let held = FileLock::acquire_in(&locks, &resource, Duration::from_millis(50)).unwrap();
+        let err = FileLock::acquire_in(&locks, &resource, Duration::from_millis(50)).unwrap_err();
+        let report = ErrorReport::from_report(&err);
+        assert_eq!(report.code, ErrorCode::Locked);
+        assert!(report.error.contains(&format!("locked by pid {}", std::process::id())));
+
+        // Other resources are independent
+        FileLock::acquire_in(&locks, &temp.path().join("skills"), Duration::ZERO).unwrap();
+
+        drop(held);
+        FileLock::acquire_in(&locks, &resource, Duration::ZERO).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_lock_name_is_stable_through_symlinked_parents() {
+        let temp = tempdir().unwrap();
+        let real = temp.path().join("real");
+        fs::create_dir_all(&real).unwrap();
+        let link = temp.path().join("link");
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        let before = lock_name(&link.join(".buffer.jsonl"));
+        fs::write(real.join(".buffer.jsonl"), "").unwrap();
+        assert_eq!(lock_name(&link.join(".buffer.jsonl")), before);
+        assert_eq!(lock_name(&real.join(".buffer.jsonl")), before);
+    }
+
+    #[test]
+    fn test_lock_serializes_writers() {
+        let temp = tempdir().unwrap();
+        let locks = Arc::new(temp.path().join("locks"));
+        let counter = Arc::new(temp.path().join("counter"));
+        fs::write(counter.as_ref(), "0").unwrap();
+
+        let workers: Vec<_> = (0..8)
+            .map(|_| {
+                let locks = Arc::clone(&locks);
+                let counter = Arc::clone(&counter);
+                std::thread::spawn(move || {
+                    for _ in 0..10 {
+                        let _lock = FileLock::acquire_in(&locks, &counter, Duration::from_secs(10)).unwrap();
+                        let n: u32 = fs::read_to_string(counter.as_ref()).unwrap().parse().unwrap();
+                        fs::write(counter.as_ref(), (n + 1).to_string()).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(counter.as_ref()).unwrap(), "80");
+    }
+}
@@ -0,0 +1,199 @@
+//! Log file setup and parsing
+//!
+//! Logs are appended to `~/.local/share/pais/logs/pais.log`. With the default
+//! `log-format: text` each line is env_logger's `[timestamp LEVEL module] message`;
+//! with `log-format: json` each line is one object:
+//!
+//! ```json
+//! {"timestamp":"2026-01-05T10:00:00.123Z","level":"INFO","module":"pais::hook","message":"...","fields":{"event":"Stop"}}
+//! ```
+//!
+//! `fields` carries the key-values of structured log calls
+//! (`log::info!(event = name; "Dispatching")`). [`LogRecord::parse`] reads
+//! both formats back for `pais logs tail`.
+
+use chrono::{SecondsFormat, Utc};
+use eyre::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use crate::config::{LogFormat, LogLevel};
+
+/// `[2026-01-05T10:00:00Z INFO  pais::hook] message`
+static TEXT_LINE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\[(\S+)\s+([A-Z]+)\s+([^\]\s]+)\s*\] ?(.*)$").expect("valid regex"));
+
+/// Path of the log file
+pub fn log_file() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("pais")
+        .join("logs")
+        .join("pais.log")
+}
+
+/// Route the `log` macros to the log file
+pub fn init(log_level: &LogLevel, log_format: LogFormat) -> Result<()> {
+    let log_file = log_file();
+    if let Some(log_dir) = log_file.parent() {
+        fs::create_dir_all(log_dir).context("Failed to create log directory")?;
+    }
+
+    // Setup env_logger with file output
+    let target = Box::new(
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_file)
+            .context("Failed to open log file")?,
+    );
+
+    // RUST_LOG env var takes precedence, otherwise use config log_level
+    let mut builder = env_logger::Builder::new();
+
+    if std::env::var("RUST_LOG").is_ok() {
+        // Let env_logger parse RUST_LOG
+        builder.parse_default_env();
+    } else {
+        // Use log level from config
+        builder.filter_level(match log_level {
+            LogLevel::Trace => log::LevelFilter::Trace,
+            LogLevel::Debug => log::LevelFilter::Debug,
+            LogLevel::Info => log::LevelFilter::Info,
+            LogLevel::Warn => log::LevelFilter::Warn,
+            LogLevel::Error => log::LevelFilter::Error,
+            LogLevel::Off => log::LevelFilter::Off,
+        });
+    }
+
+    if log_format == LogFormat::Json {
+        builder.format(|buf, record| {
+            let line = serde_json::to_string(&LogRecord::from_record(record)).map_err(std::io::Error::other)?;
+            writeln!(buf, "{}", line)
+        });
+    }
+
+    builder.target(env_logger::Target::Pipe(target)).init();
+
+    log::info!("Logging initialized, writing to: {}", log_file.display());
+    log::info!(
+        "Log level: {} (from {})",
+        log_level.as_filter(),
+        if std::env::var("RUST_LOG").is_ok() { "RUST_LOG env" } else { "config" }
+    );
+    Ok(())
+}
+
+/// One log entry, as written in JSON format
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogRecord {
+    pub timestamp: String,
+    pub level: String,
+    pub module: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub fields: Map<String, Value>,
+}
+
+impl LogRecord {
+    fn from_record(record: &log::Record) -> Self {
+        let mut fields = Map::new();
+        // Visiting a map never fails
+        let _ = record.key_values().visit(&mut FieldVisitor(&mut fields));
+        Self {
+            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            level: record.level().to_string(),
+            module: record.module_path().unwrap_or(record.target()).to_string(),
+            message: record.args().to_string(),
+            fields,
+        }
+    }
+
+    /// Parse a log line in either format; `None` for continuation lines of a
+    /// multi-line text message
+    pub fn parse(line: &str) -> Option<Self> {
+        if line.starts_with('{') {
+            return serde_json::from_str(line).ok();
+        }
+        let caps = TEXT_LINE.captures(line)?;
+        Some(Self {
+            timestamp: caps[1].to_string(),
+            level: caps[2].to_string(),
+            module: caps[3].to_string(),
+            message: caps[4].to_string(),
+            fields: Map::new(),
+        })
+    }
+
+    /// Parsed level, if it names one
+    pub fn level(&self) -> Option<log::Level> {
+        self.level.parse().ok()
+    }
+}
+
+struct FieldVisitor<'a>(&'a mut Map<String, Value>);
+
+impl<'kvs> log::kv::VisitSource<'kvs> for FieldVisitor<'_> {
+    fn visit_pair(&mut self, key: log::kv::Key<'kvs>, value: log::kv::Value<'kvs>) -> Result<(), log::kv::Error> {
+        let value = if let Some(b) = value.to_bool() {
+            Value::Bool(b)
+        } else if let Some(n) = value.to_i64() {
+            Value::from(n)
+        } else if let Some(n) = value.to_u64() {
+            Value::from(n)
+        } else if let Some(n) = value.to_f64() {
+            Value::from(n)
+        } else {
+            Value::String(value.to_string())
+        };
+        self.0.insert(key.to_string(), value);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_record_carries_fields() {
+        let kvs = [
+            ("event", log::kv::Value::from("Stop")),
+            ("handlers", log::kv::Value::from(3u64)),
+            ("blocked", log::kv::Value::from(false)),
+        ];
+        let args = format_args!("Dispatching hook event");
+        let record = log::Record::builder()
+            .args(args)
+            .level(log::Level::Warn)
+            .module_path(Some("pais::commands::hook"))
+            .key_values(&kvs)
+            .build();
+
+        let line = serde_json::to_string(&LogRecord::from_record(&record)).unwrap();
+        let parsed = LogRecord::parse(&line).unwrap();
+        assert_eq!(parsed.level(), Some(log::Level::Warn));
+        assert_eq!(parsed.module, "pais::commands::hook");
+        assert_eq!(parsed.message, "Dispatching hook event");
+        assert_eq!(parsed.fields["event"], "Stop");
+        assert_eq!(parsed.fields["handlers"], 3);
+        assert_eq!(parsed.fields["blocked"], false);
+    }
+
+    #[test]
+    fn test_parse_text_lines() {
+        let record = LogRecord::parse("[2026-01-05T10:00:00Z INFO  pais::config] Loaded config [v2]").unwrap();
+        assert_eq!(record.timestamp, "2026-01-05T10:00:00Z");
+        assert_eq!(record.level(), Some(log::Level::Info));
+        assert_eq!(record.module, "pais::config");
+        assert_eq!(record.message, "Loaded config [v2]");
+
+        assert!(LogRecord::parse("  continuation of a multi-line message").is_none());
+        assert!(LogRecord::parse("{not json").is_none());
+    }
+}
@@ -1,123 +1,243 @@
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches};
+use clap_complete::CompleteEnv;
 use eyre::{Context, Result};
 use log::info;
-use std::fs;
-use std::path::PathBuf;
+use std::time::Instant;
 
 mod agent;
 mod architecture;
 mod bundle;
+mod checkpoint;
+mod claude;
 mod cli;
+mod clock;
 mod commands;
+mod completion;
 mod config;
 mod contract;
+mod crash;
+mod error;
 mod history;
 mod hook;
+mod lock;
+mod logging;
+mod mcp;
 mod migrate;
+mod net;
 mod observability;
+mod pager;
+mod perf;
+mod picker;
+mod plan;
 mod plugin;
+mod policy;
+mod presenter;
+mod progress;
+mod prompt;
+mod provenance;
+mod secrets;
+mod share;
 mod skill;
-
-use cli::{Cli, Commands};
-use config::{Config, LogLevel};
-
-fn setup_logging(log_level: &LogLevel) -> Result<()> {
-    // Create log directory
-    let log_dir = dirs::data_local_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("pais")
-        .join("logs");
-
-    fs::create_dir_all(&log_dir).context("Failed to create log directory")?;
-
-    let log_file = log_dir.join("pais.log");
-
-    // Setup env_logger with file output
-    let target = Box::new(
-        fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&log_file)
-            .context("Failed to open log file")?,
-    );
-
-    // RUST_LOG env var takes precedence, otherwise use config log_level
-    let mut builder = env_logger::Builder::new();
-
-    if std::env::var("RUST_LOG").is_ok() {
-        // Let env_logger parse RUST_LOG
-        builder.parse_default_env();
-    } else {
-        // Use log level from config
-        builder.filter_level(match log_level {
-            LogLevel::Trace => log::LevelFilter::Trace,
-            LogLevel::Debug => log::LevelFilter::Debug,
-            LogLevel::Info => log::LevelFilter::Info,
-            LogLevel::Warn => log::LevelFilter::Warn,
-            LogLevel::Error => log::LevelFilter::Error,
-            LogLevel::Off => log::LevelFilter::Off,
-        });
+mod state;
+mod sync;
+mod team;
+mod testing;
+mod trust;
+mod workspace;
+
+use cli::{Cli, Commands, FormatArg, HistoryAction, OutputFormat};
+use config::Config;
+use sync::targets::Target;
+
+fn run(cli: Cli, format: OutputFormat, config: Config) -> Result<()> {
+    if cli.format == Some(FormatArg::Ics)
+        && !matches!(
+            cli.command,
+            Commands::History {
+                action: HistoryAction::Calendar { .. }
+            }
+        )
+    {
+        return Err(error::CliError::new(
+            error::ErrorCode::InvalidInput,
+            "--format ics only applies to history calendar",
+        )
+        .with_hint("Use text, json or yaml")
+        .into());
     }
-
-    builder.target(env_logger::Target::Pipe(target)).init();
-
-    info!("Logging initialized, writing to: {}", log_file.display());
-    info!(
-        "Log level: {} (from {})",
-        log_level.as_filter(),
-        if std::env::var("RUST_LOG").is_ok() { "RUST_LOG env" } else { "config" }
-    );
-    Ok(())
-}
-
-fn run(cli: Cli, config: Config) -> Result<()> {
     match cli.command {
         Commands::Init { path, force, no_git } => commands::init::run(path, force, no_git),
         Commands::Doctor => commands::doctor::run(&config),
-        Commands::Plugin { action } => commands::plugin::run(action, &config),
-        Commands::Skill { action } => commands::skill::run(action, &config),
+        Commands::Plugin { action } => commands::plugin::run(action, format, &config),
+        Commands::Skill { action } => commands::skill::run(action, format, &config),
         Commands::Hook { action } => commands::hook::run(action, &config),
-        Commands::History { action } => commands::history::run(action, &config),
-        Commands::Config { action } => commands::config::run(action, &config),
+        Commands::History { action } => commands::history::run(action, format, cli.format, &config),
+        Commands::Review { action } => commands::review::run(action, format, &config),
+        Commands::Config { action } => commands::config::run(action, format, &config),
         Commands::Context { action } => commands::context::run(action, &config),
-        Commands::Security { action } => commands::security::run(action, &config),
-        Commands::Observe { filter, last, payload } => {
-            commands::observe::run(filter.as_deref(), last, payload, &config)
+        Commands::Security { action } => commands::security::run(action, format, &config),
+        Commands::Claude { action } => commands::claude::run(action, format, &config),
+        Commands::Policy { action } => commands::policy::run(action, format, &config),
+        Commands::Observe {
+            filter,
+            last,
+            payload,
+            since,
+            until,
+            speed,
+            no_delay,
+            no_pager,
+        } => {
+            let replay = since.map(|since| commands::observe::Replay {
+                since,
+                until,
+                speed,
+                delay: !no_delay,
+                pager: !no_pager,
+            });
+            commands::observe::run(filter.as_deref(), last, payload, replay, &config)
         }
-        Commands::Agent { action } => commands::agent::run(action, &config),
-        Commands::Bundle { action } => commands::bundle::run(action, &config),
-        Commands::Image { action } => commands::image::run(action, &config),
-        Commands::Diagram { action } => commands::diagram::run(action, &config),
+        Commands::Agent { action } => commands::agent::run(action, format, &config),
+        Commands::Bundle { action } => commands::bundle::run(action, format, &config),
+        Commands::Team { action } => commands::team::run(action, format, &config),
+        Commands::Mcp { action } => commands::mcp::run(action, &config),
+        Commands::Image { action } => commands::image::run(action, format, &config),
+        Commands::Diagram { action } => commands::diagram::run(action, format, &config),
         Commands::Run { plugin, action, args } => commands::run::run(&plugin, &action, &args, &config),
         Commands::Session {
             mcp,
             skill,
             list,
             dry_run,
-            format,
             claude_args,
         } => commands::session::run(mcp, skill, list, dry_run, format, claude_args, &config),
-        Commands::Status { format } => commands::status::run(cli::OutputFormat::resolve(format), &config),
-        Commands::Sync { dry_run, clean } => commands::sync::run(dry_run, clean, &config),
-        Commands::Upgrade { dry_run, status } => commands::upgrade::run(dry_run, status, &config),
-        Commands::Completions { shell } => commands::completions::run(shell),
+        Commands::Status { perf: true, .. } => commands::status::perf(format),
+        Commands::Status { .. } => commands::status::run(format, &config),
+        Commands::Test { action } => commands::test::run(action, format, &config),
+        Commands::Sync {
+            dry_run,
+            clean,
+            watch,
+            target,
+            project,
+            skill,
+            mcp,
+            action,
+        } => {
+            if target == Target::Claude || action.is_some() {
+                commands::sync::run(action, dry_run, clean, watch, format, &config)
+            } else if watch {
+                Err(error::CliError::new(
+                    error::ErrorCode::InvalidInput,
+                    "--watch only applies to Claude Code sync",
+                )
+                .with_hint("Re-run the export after editing, or drop --target")
+                .into())
+            } else {
+                commands::sync::export(target, project, skill, mcp, dry_run, format, &config)
+            }
+        }
+        Commands::Debug { action } => commands::debug::run(action, format),
+        Commands::Checkpoint { action } => commands::checkpoint::run(action, format),
+        Commands::Provenance { action } => commands::provenance::run(action, format, &config),
+        Commands::State { action } => commands::state::run(action, format, &config),
+        Commands::Trust { path, revoke } => commands::trust::run(path.as_deref(), revoke, format, &config),
+        Commands::Logs { action } => commands::logs::run(action, format),
+        Commands::Gc {
+            build_cache,
+            all,
+            dry_run,
+        } => commands::gc::run(build_cache, all, dry_run, format, &config),
+        Commands::Upgrade { dry_run, status } => commands::upgrade::run(dry_run, status, format, &config),
+        Commands::Bench { iterations, check } => commands::bench::run(iterations, check, format, &config),
+        Commands::Completions { shell, static_only } => commands::completions::run(shell, static_only),
     }
 }
 
 fn main() -> Result<()> {
+    let started = Instant::now();
+
+    // Answer shell completion requests (COMPLETE=<shell>) before normal parsing
+    CompleteEnv::with_factory(Cli::command)
+        .var(completion::COMPLETE_VAR)
+        .complete();
+
     // Parse CLI arguments first
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.format(&mut Cli::command()).exit());
+    let command = perf::command_name(&matches);
+    let format = OutputFormat::resolve(cli.format);
+    clock::init(cli.deterministic);
+
+    // Prompt segments run on every shell prompt: skip config loading and logging entirely.
+    // Prompt tools capture stdout, so default to text rather than the usual pipe → json.
+    if let Commands::Status { prompt: true, .. } = cli.command {
+        return commands::status::prompt(
+            cli.format
+                .map_or(OutputFormat::Text, |f| OutputFormat::resolve(Some(f))),
+        );
+    }
+
+    crash::install_panic_hook(cli.config.clone());
+
+    let result = load_and_run(cli, format, &command, started);
+
+    // Unexpected failures leave a diagnostic bundle for bug reports
+    if let Err(ref report) = result
+        && let Some(path) = crash::report_error(report)
+        && format != OutputFormat::Json
+    {
+        eprintln!(
+            "{} Diagnostic bundle written to {} (attach it to a bug report)",
+            presenter::info(),
+            path.display()
+        );
+    }
 
-    // Load configuration (before logging, so log messages in Config::load are silent)
-    let config = Config::load(cli.config.as_ref()).context("Failed to load configuration")?;
+    // Scripts asking for JSON get a parseable error object instead of a report
+    if format == OutputFormat::Json
+        && let Err(ref report) = result
+    {
+        let json = serde_json::to_string(&error::ErrorReport::from_report(report))?;
+        eprintln!("{}", json);
+        std::process::exit(1);
+    }
+
+    result
+}
 
-    // Setup logging with log level from config (or RUST_LOG env var)
-    setup_logging(&config.log_level).context("Failed to setup logging")?;
+fn load_and_run(cli: Cli, format: OutputFormat, command: &str, started: Instant) -> Result<()> {
+    // Load configuration (before logging, so log messages in Config::load are silent).
+    // Hook dispatch runs on every tool call, so it reads a binary snapshot instead of parsing YAML.
+    let mut config = if matches!(
+        cli.command,
+        Commands::Hook {
+            action: cli::HookAction::Dispatch { .. }
+        }
+    ) {
+        Config::load_cached(cli.config.as_ref())
+    } else {
+        Config::load(cli.config.as_ref())
+    }
+    .context("Failed to load configuration")?;
+    config.network.offline |= cli.offline;
+    presenter::init(&config.ui);
+
+    // Setup logging with log level from config (or RUST_LOG env var).
+    // Viewing the log doesn't write to it, so `pais logs tail -f` only shows other processes.
+    if !matches!(cli.command, Commands::Logs { .. }) {
+        logging::init(&config.log_level, config.log_format).context("Failed to setup logging")?;
+    }
 
     info!("Starting pais with config from: {:?}", cli.config);
 
     // Run the command
-    run(cli, config).context("Command failed")?;
+    if config.perf.enabled {
+        perf::start(command, started);
+    }
+    let result = run(cli, format, config);
+    perf::finish(result.is_ok());
+    result.context("Command failed")?;
 
     Ok(())
 }
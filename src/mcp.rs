@@ -0,0 +1,334 @@
+//! Built-in MCP server
+//!
+//! `pais mcp serve` speaks the Model Context Protocol (JSON-RPC 2.0, one
+//! message per line on stdin/stdout) and exposes the skill index:
+//!
+//! - Resource `pais://skills`: every skill's tier, description and triggers
+//! - Resource `pais://skills/<name>`: the body of that skill's SKILL.md
+//! - Prompt `<name>`: the same body, for invoking a skill by hand
+//!
+//! With `skills.mcp: true`, `pais context inject` names deferred skills
+//! without their tables, and Claude reads a skill's resource when a request
+//! matches it. The index is rebuilt on each request (cheap thanks to the
+//! skill cache), so edits show up without restarting the server.
+
+use eyre::{Context, Result};
+use serde_json::{Value, json};
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use crate::commands::context::extract_skill_body;
+use crate::skill::indexer::{SkillIndex, SkillIndexEntry, generate_index};
+
+/// Protocol revision used when the client asks for one we don't know
+const PROTOCOL_VERSION: &str = "2025-06-18";
+const SUPPORTED_VERSIONS: &[&str] = &["2024-11-05", "2025-03-26", "2025-06-18"];
+
+/// URI of the skill index resource; skills live below it
+pub const INDEX_URI: &str = "pais://skills";
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const RESOURCE_NOT_FOUND: i64 = -32002;
+
+/// A JSON-RPC error
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+pub struct Server {
+    skills_dir: PathBuf,
+}
+
+impl Server {
+    pub fn new(skills_dir: PathBuf) -> Self {
+        Self { skills_dir }
+    }
+
+    /// Answer requests from stdin until it closes
+    pub fn serve(&self) -> Result<()> {
+        let stdin = io::stdin();
+        let mut stdout = io::stdout().lock();
+        for line in stdin.lock().lines() {
+            let line = line.context("Failed to read from stdin")?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle_line(&line) {
+                writeln!(stdout, "{}", response).context("Failed to write to stdout")?;
+                stdout.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The response to one message, or `None` for notifications
+    pub fn handle_line(&self, line: &str) -> Option<Value> {
+        let message: Value = match serde_json::from_str(line) {
+            Ok(message) => message,
+            Err(e) => return Some(error_response(Value::Null, RpcError::new(PARSE_ERROR, e.to_string()))),
+        };
+        let id = message.get("id").cloned()?;
+        let method = message.get("method").and_then(|m| m.as_str()).unwrap_or_default();
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        log::debug!("MCP request: {}", method);
+
+        Some(match self.call(method, &params) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(e) => error_response(id, e),
+        })
+    }
+
+    fn call(&self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        match method {
+            "initialize" => Ok(initialize(params)),
+            "ping" => Ok(json!({})),
+            "resources/list" => Ok(json!({ "resources": self.resources()? })),
+            "resources/templates/list" => Ok(json!({
+                "resourceTemplates": [{
+                    "uriTemplate": format!("{}/{{name}}", INDEX_URI),
+                    "name": "skill",
+                    "description": "Instructions of one pais skill (SKILL.md)",
+                    "mimeType": "text/markdown",
+                }]
+            })),
+            "resources/read" => {
+                let uri = param(params, "uri")?;
+                let text = self.read(uri)?;
+                Ok(json!({ "contents": [{ "uri": uri, "mimeType": "text/markdown", "text": text }] }))
+            }
+            "prompts/list" => Ok(json!({ "prompts": self.prompts()? })),
+            "prompts/get" => self.prompt(param(params, "name")?),
+            _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+        }
+    }
+
+    fn index(&self) -> Result<SkillIndex, RpcError> {
+        generate_index(&self.skills_dir).map_err(|e| RpcError::new(-32603, format!("{:#}", e)))
+    }
+
+    fn resources(&self) -> Result<Vec<Value>, RpcError> {
+        let index = self.index()?;
+        let mut resources = vec![json!({
+            "uri": INDEX_URI,
+            "name": "skill-index",
+            "description": "All pais skills with their tier, description and triggers",
+            "mimeType": "text/markdown",
+        })];
+        resources.extend(sorted(&index).into_iter().map(|skill| {
+            json!({
+                "uri": skill_uri(&skill.name),
+                "name": skill.name,
+                "description": skill.description,
+                "mimeType": "text/markdown",
+            })
+        }));
+        Ok(resources)
+    }
+
+    fn read(&self, uri: &str) -> Result<String, RpcError> {
+        let index = self.index()?;
+        if uri == INDEX_URI {
+            return Ok(index_markdown(&index));
+        }
+        let name = uri
+            .strip_prefix(INDEX_URI)
+            .and_then(|rest| rest.strip_prefix('/'))
+            .ok_or_else(|| RpcError::new(RESOURCE_NOT_FOUND, format!("Resource not found: {}", uri)))?;
+        self.body(&index, name)?
+            .ok_or_else(|| RpcError::new(RESOURCE_NOT_FOUND, format!("Resource not found: {}", uri)))
+    }
+
+    fn prompts(&self) -> Result<Vec<Value>, RpcError> {
+        Ok(sorted(&self.index()?)
+            .into_iter()
+            .map(|skill| json!({ "name": skill.name, "description": skill.description }))
+            .collect())
+    }
+
+    fn prompt(&self, name: &str) -> Result<Value, RpcError> {
+        let index = self.index()?;
+        let body = self
+            .body(&index, name)?
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("Unknown skill: {}", name)))?;
+        Ok(json!({
+            "description": index.skills[&name.to_lowercase()].description,
+            "messages": [{ "role": "user", "content": { "type": "text", "text": body } }],
+        }))
+    }
+
+    /// Body of a skill's SKILL.md, `None` for an unknown skill
+    fn body(&self, index: &SkillIndex, name: &str) -> Result<Option<String>, RpcError> {
+        let Some(skill) = index.skills.get(&name.to_lowercase()) else {
+            return Ok(None);
+        };
+        let path = self.skills_dir.join(&skill.path);
+        let content = fs::read_to_string(&path)
+            .map_err(|e| RpcError::new(-32603, format!("Failed to read {}: {}", path.display(), e)))?;
+        Ok(Some(extract_skill_body(&content).unwrap_or(content)))
+    }
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(|v| v.as_str());
+    let version = requested
+        .filter(|v| SUPPORTED_VERSIONS.contains(v))
+        .unwrap_or(PROTOCOL_VERSION);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "resources": {}, "prompts": {} },
+        "serverInfo": { "name": "pais", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": format!(
+            "Read {} for the available skills. When a request matches a skill's triggers, \
+             read {}/<name> and follow it.",
+            INDEX_URI, INDEX_URI
+        ),
+    })
+}
+
+fn param<'a>(params: &'a Value, key: &str) -> Result<&'a str, RpcError> {
+    params
+        .get(key)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("Missing parameter: {}", key)))
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+pub fn skill_uri(name: &str) -> String {
+    format!("{}/{}", INDEX_URI, name)
+}
+
+fn sorted(index: &SkillIndex) -> Vec<&SkillIndexEntry> {
+    let mut skills: Vec<_> = index.skills.values().collect();
+    skills.sort_by(|a, b| a.name.cmp(&b.name));
+    skills
+}
+
+/// The index resource: one section per skill
+fn index_markdown(index: &SkillIndex) -> String {
+    let mut lines = vec!["# Skills".to_string(), String::new()];
+    for skill in sorted(index) {
+        lines.push(format!("## {} ({})", skill.name, skill.tier));
+        lines.push(String::new());
+        lines.push(skill.description.clone());
+        if !skill.triggers.is_empty() {
+            lines.push(String::new());
+            lines.push(format!("Triggers: {}", skill.triggers.join(", ")));
+        }
+        lines.push(format!("Read: `{}`", skill_uri(&skill.name)));
+        lines.push(String::new());
+    }
+    lines.join("\n")
+}
+
+/// Run the server on stdio for the skills in `skills_dir`
+pub fn serve(skills_dir: &Path) -> Result<()> {
+    log::info!("Serving MCP for skills in {}", skills_dir.display());
+    Server::new(skills_dir.to_path_buf()).serve()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn server() -> (tempfile::TempDir, Server) {
+        let temp = tempdir().unwrap();
+        let skill = temp.path().join("deploy");
+        fs::create_dir_all(&skill).unwrap();
+        fs::write(
+            skill.join("SKILL.md"),
+            "---\nname: deploy\ndescription: Ship releases. USE WHEN deploy, release\n---\n\n# Deploy\n\nRun the pipeline.\n",
+        )
+        .unwrap();
+        let server = Server::new(temp.path().to_path_buf());
+        (temp, server)
+    }
+
+    fn request(server: &Server, method: &str, params: Value) -> Value {
+        let line = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }).to_string();
+        server.handle_line(&line).unwrap()
+    }
+
+    #[test]
+    fn test_initialize_and_notifications() {
+        let (_temp, server) = server();
+        let response = request(&server, "initialize", json!({ "protocolVersion": "2024-11-05" }));
+        assert_eq!(response["result"]["protocolVersion"], "2024-11-05");
+        assert!(response["result"]["capabilities"]["resources"].is_object());
+
+        let response = request(&server, "initialize", json!({ "protocolVersion": "1999-01-01" }));
+        assert_eq!(response["result"]["protocolVersion"], PROTOCOL_VERSION);
+
+        assert!(
+            server
+                .handle_line(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#)
+                .is_none()
+        );
+        assert_eq!(server.handle_line("{oops").unwrap()["error"]["code"], PARSE_ERROR);
+        assert_eq!(
+            request(&server, "tools/call", json!({}))["error"]["code"],
+            METHOD_NOT_FOUND
+        );
+    }
+
+    #[test]
+    fn test_skill_resources_and_prompts() {
+        let (_temp, server) = server();
+
+        let resources = request(&server, "resources/list", json!({}));
+        let uris: Vec<&str> = resources["result"]["resources"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["uri"].as_str().unwrap())
+            .collect();
+        assert_eq!(uris, vec!["pais://skills", "pais://skills/deploy"]);
+
+        let index = request(&server, "resources/read", json!({ "uri": "pais://skills" }));
+        let text = index["result"]["contents"][0]["text"].as_str().unwrap();
+        assert!(text.contains("## deploy (deferred)"));
+        assert!(text.contains("Triggers: deploy, release"));
+
+        let body = request(&server, "resources/read", json!({ "uri": "pais://skills/Deploy" }));
+        let text = body["result"]["contents"][0]["text"].as_str().unwrap();
+        assert!(text.contains("Run the pipeline."));
+        assert!(!text.contains("description:"));
+
+        let missing = request(&server, "resources/read", json!({ "uri": "pais://skills/nope" }));
+        assert_eq!(missing["error"]["code"], RESOURCE_NOT_FOUND);
+
+        let prompts = request(&server, "prompts/list", json!({}));
+        assert_eq!(prompts["result"]["prompts"][0]["name"], "deploy");
+        let prompt = request(&server, "prompts/get", json!({ "name": "deploy" }));
+        assert!(
+            prompt["result"]["messages"][0]["content"]["text"]
+                .as_str()
+                .unwrap()
+                .contains("Run the pipeline.")
+        );
+        assert_eq!(
+            request(&server, "prompts/get", json!({}))["error"]["code"],
+            INVALID_PARAMS
+        );
+    }
+}
@@ -8,6 +8,8 @@ use eyre::{Context, Result};
 use std::process::Command;
 
 use crate::config::Config;
+use crate::plan::{ChangePlan, Op};
+use crate::state::{self, StateEvent};
 
 /// Current config version
 pub const CURRENT_VERSION: u32 = 1;
@@ -70,6 +72,9 @@ pub trait Migration {
     fn target_version(&self) -> u32;
     fn description(&self) -> &str;
     fn apply(&self, config: &Config) -> Result<()>;
+
+    /// Record what `apply` would change, for `pais upgrade --dry-run`
+    fn plan(&self, _config: &Config, _plan: &mut ChangePlan) {}
 }
 
 /// Migration from v0 (unversioned) to v1
@@ -119,8 +124,8 @@ pub fn pending_migrations() -> Result<Vec<(u32, u32, String)>> {
     Ok(pending)
 }
 
-/// Run all pending migrations
-pub fn run_migrations(config: &Config, dry_run: bool) -> Result<Vec<String>> {
+/// Run all pending migrations (or record them in the plan on a dry run)
+pub fn run_migrations(config: &Config, plan: &mut ChangePlan) -> Result<Vec<String>> {
     let mut current = get_current_version()?;
     let migrations = get_migrations();
 
@@ -135,13 +140,24 @@ pub fn run_migrations(config: &Config, dry_run: bool) -> Result<Vec<String>> {
                 migration.description()
             );
 
-            if dry_run {
+            if plan.dry_run() {
+                migration.plan(config, plan);
+                plan.record(
+                    Op::Add,
+                    format!("git tag v{}", migration.target_version()),
+                    Some(migration.description().to_string()),
+                );
                 applied.push(format!("[dry-run] {}", desc));
             } else {
                 migration.apply(config)?;
 
                 // Create git tag for this version
                 set_version(migration.target_version(), migration.description())?;
+                state::record(StateEvent::Migrated {
+                    from: migration.source_version(),
+                    to: migration.target_version(),
+                    description: migration.description().to_string(),
+                });
 
                 current = migration.target_version();
                 applied.push(desc);
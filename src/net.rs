@@ -0,0 +1,184 @@
+//! Async HTTP layer for network-heavy commands
+//!
+//! Commands stay synchronous at the edges: they build their work as a future
+//! and hand it to [`block_on`], which runs it on a tokio runtime and aborts it
+//! on Ctrl-C. Inside, requests go through a shared [`reqwest::Client`] with
+//! the configured timeouts, so several calls can be in flight at once
+//! (`tokio::join!`, `futures` streams) instead of queueing on blocking I/O.
+//!
+//! With `--offline` or `network.offline`, commands call [`ensure_online`]
+//! before going out, and services on this machine ([`is_local`]) stay usable.
+
+use eyre::{Context, Result};
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::net::IpAddr;
+use std::time::Duration;
+
+use crate::config::NetworkConfig;
+use crate::error::{CliError, ErrorCode};
+
+/// Run a future to completion, cancelling it on Ctrl-C
+pub fn block_on<F, T>(future: F) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .context("Failed to create tokio runtime")?;
+
+    runtime.block_on(async {
+        tokio::select! {
+            result = future => result,
+            _ = tokio::signal::ctrl_c() => Err(CliError::new(ErrorCode::Cancelled, "Cancelled").into()),
+        }
+    })
+}
+
+/// Fail with a clear message if `future` does not finish within `limit`
+pub async fn deadline<F, T>(limit: Duration, what: &str, future: F) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    match tokio::time::timeout(limit, future).await {
+        Ok(result) => result,
+        Err(_) => Err(CliError::new(
+            ErrorCode::Timeout,
+            format!("{} timed out after {}s", what, limit.as_secs()),
+        )
+        .with_hint("Raise network.timeout-secs in pais.yaml")
+        .into()),
+    }
+}
+
+/// Fail fast when offline mode is on; `what` names the operation
+pub fn ensure_online(config: &NetworkConfig, what: &str) -> Result<()> {
+    if config.offline {
+        return Err(offline_error(what).into());
+    }
+    Ok(())
+}
+
+/// The error for `what` being refused in offline mode, for callers adding their own hint
+pub fn offline_error(what: &str) -> CliError {
+    CliError::new(
+        ErrorCode::Offline,
+        format!("{} needs the network, and offline mode is on", what),
+    )
+    .with_hint("Run it without --offline, or set network.offline: false in pais.yaml")
+}
+
+/// Whether `url` stays on this machine: a loopback host or a filesystem path
+pub fn is_local(url: &str) -> bool {
+    if url.starts_with("file://") {
+        return true;
+    }
+    match reqwest::Url::parse(url) {
+        Ok(parsed) => {
+            let host = parsed.host_str().unwrap_or_default();
+            let host = host.trim_start_matches('[').trim_end_matches(']');
+            host == "localhost" || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+        }
+        // Not a URL: a path, unless it's scp-style `user@host:repo`
+        Err(_) => !url.contains(':'),
+    }
+}
+
+/// HTTP client with the configured timeouts
+pub fn client(config: &NetworkConfig) -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .user_agent(concat!("pais/", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(config.timeout_secs))
+        .connect_timeout(Duration::from_secs(config.connect_timeout_secs))
+        .build()
+        .context("Failed to create HTTP client")
+}
+
+/// Send a request and parse a JSON response, including the body in errors
+pub async fn json<T: DeserializeOwned>(request: reqwest::RequestBuilder, what: &str) -> Result<T> {
+    let response = send(request, what).await?;
+    let body = response
+        .text()
+        .await
+        .map_err(reqwest::Error::without_url)
+        .with_context(|| format!("Failed to read {} response", what))?;
+    serde_json::from_str(&body).with_context(|| format!("Failed to parse {} response", what))
+}
+
+/// Send a request and return the raw response body
+pub async fn bytes(request: reqwest::RequestBuilder, what: &str) -> Result<Vec<u8>> {
+    let response = send(request, what).await?;
+    let body = response
+        .bytes()
+        .await
+        .map_err(reqwest::Error::without_url)
+        .with_context(|| format!("Failed to read {} response", what))?;
+    Ok(body.to_vec())
+}
+
+async fn send(request: reqwest::RequestBuilder, what: &str) -> Result<reqwest::Response> {
+    // Some APIs take keys in the query string; keep URLs out of error messages
+    let response = request
+        .send()
+        .await
+        .map_err(reqwest::Error::without_url)
+        .with_context(|| format!("Failed to call {}", what))?;
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    eyre::bail!("{} returned {}: {}", what, status, body.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_on_returns_result() {
+        let value = block_on(async { Ok(21 * 2) }).unwrap();
+        assert_eq!(value, 42);
+        assert!(block_on(async { Err::<(), _>(eyre::eyre!("boom")) }).is_err());
+    }
+
+    #[test]
+    fn test_offline() {
+        let mut config = NetworkConfig::default();
+        assert!(ensure_online(&config, "Image generation").is_ok());
+        config.offline = true;
+        let err = ensure_online(&config, "Image generation").unwrap_err();
+        assert_eq!(err.downcast_ref::<CliError>().unwrap().code, ErrorCode::Offline);
+
+        for local in [
+            "http://localhost:11434",
+            "http://127.0.0.1:8080/x",
+            "http://[::1]/",
+            "/srv/sync.git",
+            "file:///tmp/r",
+        ] {
+            assert!(is_local(local), "{}", local);
+        }
+        for remote in [
+            "https://mermaid.ink",
+            "git@github.com:me/pais.git",
+            "s3://bucket/prefix",
+            "ssh://host/path",
+        ] {
+            assert!(!is_local(remote), "{}", remote);
+        }
+    }
+
+    #[test]
+    fn test_deadline() {
+        let slow = block_on(deadline(Duration::from_millis(10), "Slow call", async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(())
+        }));
+        assert!(slow.unwrap_err().to_string().contains("Slow call timed out"));
+
+        let fast = block_on(deadline(Duration::from_secs(5), "Fast call", async { Ok("done") }));
+        assert_eq!(fast.unwrap(), "done");
+    }
+}
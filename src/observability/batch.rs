@@ -0,0 +1,288 @@
+//! Batched file sink
+//!
+//! Every hook runs as its own short-lived process, so the plain file sink
+//! opens and appends to the day log once per event. With
+//! `observability.flush-interval` set, events go to `raw-events/.buffer.jsonl`
+//! instead, and the buffer is moved into the day logs at most once per
+//! interval: when an event arrives and the oldest buffered one is older than
+//! that, at the end of each turn (`Stop`) and session, and whenever pais is
+//! about to read the day logs (see [`flush_buffered`]). Until then buffered
+//! events are not in the day logs.
+//!
+//! Buffering an event is one append to a file it already has open; the age of
+//! the batch is the buffer's creation time, read from that open file. Only
+//! where the filesystem doesn't record creation times is the oldest event read
+//! back from the buffer.
+//!
+//! A flush renames the buffer to `.flushing.jsonl` before appending to the day
+//! logs and removes it afterwards. If a process dies in between, the next
+//! flush finishes the batch, skipping lines that already reached their log.
+
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use eyre::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::Event;
+use crate::clock;
+use crate::lock::FileLock;
+
+/// Events waiting to be written to the day logs
+pub const BUFFER_FILE: &str = ".buffer.jsonl";
+
+/// A batch being written; only left behind by an interrupted flush
+const FLUSHING_FILE: &str = ".flushing.jsonl";
+
+/// Move the events the batched sink still holds into the day logs under
+/// `history_path`, so a reader of those logs sees them. Failures are only logged.
+pub fn flush_buffered(history_path: &Path) {
+    if let Err(e) = BatchedFileSink::new(history_path, Duration::ZERO).flush() {
+        log::warn!("Failed to flush buffered events: {}", e);
+    }
+}
+
+/// File sink that buffers events and writes them to the day logs in batches
+pub struct BatchedFileSink {
+    dir: PathBuf,
+    interval: Duration,
+}
+
+impl BatchedFileSink {
+    pub fn new(history_path: &Path, interval: Duration) -> Self {
+        Self {
+            dir: history_path.join("raw-events"),
+            interval,
+        }
+    }
+
+    fn buffer_path(&self) -> PathBuf {
+        self.dir.join(BUFFER_FILE)
+    }
+
+    /// Buffer an event, flushing if the batch is due or `flush` is set
+    pub fn write(&self, event: &Event, flush: bool) -> Result<()> {
+        let buffer = self.buffer_path();
+        let _lock = FileLock::acquire(&buffer)?;
+
+        let line = serde_json::to_string(event).context("Failed to serialize event")?;
+        let open = || OpenOptions::new().create(true).append(true).open(&buffer);
+        let mut file = match open() {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                fs::create_dir_all(&self.dir).context("Failed to create raw-events directory")?;
+                open()
+            }
+            result => result,
+        }
+        .with_context(|| format!("Failed to open {}", buffer.display()))?;
+        writeln!(file, "{}", line).context("Failed to buffer event")?;
+
+        if flush || self.due(&file)? {
+            self.flush_locked()?;
+        }
+        Ok(())
+    }
+
+    /// Write every buffered event to its day log, returning how many were written
+    pub fn flush(&self) -> Result<usize> {
+        let _lock = FileLock::acquire(&self.buffer_path())?;
+        self.flush_locked()
+    }
+
+    /// Whether the batch in `buffer` has waited a full interval
+    fn due(&self, buffer: &fs::File) -> Result<bool> {
+        match buffer.metadata().and_then(|m| m.created()) {
+            Ok(created) => Ok(created.elapsed().unwrap_or_default() >= self.interval),
+            Err(_) => self.oldest_due(),
+        }
+    }
+
+    /// Whether the oldest buffered event has waited a full interval
+    fn oldest_due(&self) -> Result<bool> {
+        let file = fs::File::open(self.buffer_path()).context("Failed to open event buffer")?;
+        let mut first = String::new();
+        BufReader::new(file).read_line(&mut first)?;
+        let oldest = serde_json::from_str::<Event>(&first)
+            .ok()
+            .and_then(|e| DateTime::parse_from_rfc3339(&e.timestamp).ok());
+        Ok(match oldest {
+            Some(oldest) => (clock::now() - oldest.with_timezone(&Utc)).to_std().unwrap_or_default() >= self.interval,
+            // Unreadable, so write it out rather than let it hold the batch back
+            None => true,
+        })
+    }
+
+    fn flush_locked(&self) -> Result<usize> {
+        let flushing = self.dir.join(FLUSHING_FILE);
+        let mut written = 0;
+        if flushing.exists() {
+            log::warn!("Finishing an interrupted flush of {}", flushing.display());
+            written += self.apply(&flushing, true)?;
+        }
+
+        let buffer = self.buffer_path();
+        if buffer.exists() {
+            fs::rename(&buffer, &flushing).context("Failed to move the event buffer aside")?;
+            written += self.apply(&flushing, false)?;
+        }
+        Ok(written)
+    }
+
+    /// Append a batch to the day logs and remove it; `recovering` skips lines already written
+    fn apply(&self, batch: &Path, recovering: bool) -> Result<usize> {
+        let content = fs::read_to_string(batch).with_context(|| format!("Failed to read {}", batch.display()))?;
+        let mut days: BTreeMap<NaiveDate, Vec<&str>> = BTreeMap::new();
+        for line in content.lines().filter(|l| !l.trim().is_empty()) {
+            match serde_json::from_str::<Event>(line).ok().and_then(|e| event_day(&e)) {
+                Some(day) => days.entry(day).or_default().push(line),
+                None => log::warn!("Dropping unreadable buffered event: {}", line),
+            }
+        }
+
+        let mut written = 0;
+        for (day, lines) in days {
+            let month_dir = self.dir.join(day.format("%Y-%m").to_string());
+            fs::create_dir_all(&month_dir)?;
+            let log_path = month_dir.join(format!("{}.jsonl", day.format("%Y-%m-%d")));
+
+            let skip = if recovering {
+                already_written(&fs::read_to_string(&log_path).unwrap_or_default(), &lines)
+            } else {
+                0
+            };
+            let lines = &lines[skip..];
+            if lines.is_empty() {
+                continue;
+            }
+
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&log_path)
+                .with_context(|| format!("Failed to open event log: {}", log_path.display()))?;
+            file.write_all(format!("{}\n", lines.join("\n")).as_bytes())?;
+            file.sync_data()?;
+            written += lines.len();
+        }
+
+        fs::remove_file(batch).with_context(|| format!("Failed to remove {}", batch.display()))?;
+        Ok(written)
+    }
+}
+
+/// Local date of an event, which picks its day log
+fn event_day(event: &Event) -> Option<NaiveDate> {
+    event
+        .local_time
+        .get(..10)
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        .or_else(|| {
+            DateTime::parse_from_rfc3339(&event.timestamp)
+                .ok()
+                .map(|t| t.with_timezone(&Local).date_naive())
+        })
+}
+
+/// How many leading `batch` lines a log already ends with
+fn already_written(log: &str, batch: &[&str]) -> usize {
+    let tail: Vec<&str> = log.lines().rev().take(batch.len()).collect();
+    (1..=tail.len())
+        .rev()
+        .find(|&k| tail[..k].iter().rev().eq(batch[..k].iter()))
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn event(day: &str, n: u32) -> Event {
+        Event {
+            timestamp: format!("{}T10:00:{:02}Z", day, n),
+            local_time: format!("{} 10:00:{:02}", day, n),
+            event_type: "PreToolUse".to_string(),
+            session_id: None,
+            tool_name: Some(format!("tool{}", n)),
+            payload: None,
+            plugin: None,
+            skill: None,
+        }
+    }
+
+    fn log_lines(history: &Path, day: &str) -> Vec<String> {
+        let path = history
+            .join("raw-events")
+            .join(&day[..7])
+            .join(format!("{}.jsonl", day));
+        fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_batches_until_flushed() {
+        let temp = tempdir().unwrap();
+        let sink = BatchedFileSink::new(temp.path(), Duration::from_secs(u32::MAX.into()));
+
+        sink.write(&event("2026-03-01", 1), false).unwrap();
+        sink.write(&event("2026-03-02", 2), false).unwrap();
+        assert!(log_lines(temp.path(), "2026-03-02").is_empty());
+
+        sink.write(&event("2026-03-02", 3), true).unwrap();
+        assert_eq!(log_lines(temp.path(), "2026-03-01").len(), 1);
+        assert_eq!(log_lines(temp.path(), "2026-03-02").len(), 2);
+        assert!(!temp.path().join("raw-events").join(BUFFER_FILE).exists());
+        assert_eq!(sink.flush().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_flushes_once_batch_is_due() {
+        let temp = tempdir().unwrap();
+        let sink = BatchedFileSink::new(temp.path(), Duration::from_millis(200));
+
+        sink.write(&event("2026-03-01", 1), false).unwrap();
+        sink.write(&event("2026-03-01", 2), false).unwrap();
+        assert!(log_lines(temp.path(), "2026-03-01").is_empty());
+
+        std::thread::sleep(Duration::from_millis(250));
+        sink.write(&event("2026-03-01", 3), false).unwrap();
+        assert_eq!(log_lines(temp.path(), "2026-03-01").len(), 3);
+    }
+
+    #[test]
+    fn test_recovers_interrupted_flush() {
+        let temp = tempdir().unwrap();
+        let sink = BatchedFileSink::new(temp.path(), Duration::ZERO);
+        sink.write(&event("2026-03-01", 1), false).unwrap();
+
+        // A flush died after writing the first of three lines
+        let batch: Vec<String> = (2..5)
+            .map(|n| serde_json::to_string(&event("2026-03-01", n)).unwrap())
+            .collect();
+        let log = temp.path().join("raw-events/2026-03/2026-03-01.jsonl");
+        fs::write(
+            &log,
+            format!("{}\n{}\n", fs::read_to_string(&log).unwrap().trim(), batch[0]),
+        )
+        .unwrap();
+        fs::write(temp.path().join("raw-events").join(FLUSHING_FILE), batch.join("\n")).unwrap();
+
+        assert_eq!(sink.flush().unwrap(), 2);
+        let lines = log_lines(temp.path(), "2026-03-01");
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[1..], batch[..]);
+    }
+
+    #[test]
+    fn test_already_written() {
+        assert_eq!(already_written("a\nb\nc\n", &["b", "c", "d"]), 2);
+        assert_eq!(already_written("a\nb\n", &["c"]), 0);
+        assert_eq!(already_written("", &["a"]), 0);
+        assert_eq!(already_written("x\na\nb\n", &["a", "b"]), 2);
+    }
+}
@@ -2,14 +2,21 @@
 
 #![allow(dead_code)] // has_stdout_sink - for observe command deduplication
 
-use chrono::{Local, Utc};
+use chrono::Local;
 use colored::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs::{self, OpenOptions};
 use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
 
+use super::{BatchedFileSink, flush_buffered};
+use crate::clock;
 use crate::config::{ObservabilityConfig, ObservabilitySink};
 use crate::hook::HookEvent;
+use crate::plugin::{HookOutcome, HookRun};
+use crate::skill::usage::Trigger;
 
 /// An observable event
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,13 +36,44 @@ pub struct Event {
     /// Event payload (optional, can be large)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub payload: Option<serde_json::Value>,
+    /// How a plugin's hooks went, on `PluginHook` events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plugin: Option<PluginHookRun>,
+    /// Which skill was used and how, on `SkillUsed` events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skill: Option<SkillUse>,
+}
+
+/// Event type recording one plugin's hooks for a hook event
+pub const PLUGIN_HOOK: &str = "PluginHook";
+
+/// Event type recording that Claude used a skill
+pub const SKILL_USED: &str = "SkillUsed";
+
+/// One plugin's hooks for one hook event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PluginHookRun {
+    pub name: String,
+    /// The hook event the plugin handled
+    pub event: String,
+    pub outcome: HookOutcome,
+    pub duration_ms: u64,
+}
+
+/// One use of a skill
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SkillUse {
+    pub name: String,
+    pub via: Trigger,
 }
 
 impl Event {
     /// Create a new event from a hook event and payload
     pub fn from_hook(hook_event: HookEvent, payload: &serde_json::Value, include_payload: bool) -> Self {
-        let now = Utc::now();
-        let local = Local::now();
+        let now = clock::now();
+        let local = now.with_timezone(&Local);
 
         let session_id = payload
             .get("session_id")
@@ -56,6 +94,33 @@ impl Event {
             session_id,
             tool_name,
             payload: if include_payload { Some(payload.clone()) } else { None },
+            plugin: None,
+            skill: None,
+        }
+    }
+
+    /// A `PluginHook` event for the plugin run `run`, during `hook_event` with `payload`
+    pub fn plugin_hook(hook_event: HookEvent, payload: &serde_json::Value, run: &HookRun) -> Self {
+        Self {
+            event_type: PLUGIN_HOOK.to_string(),
+            payload: None,
+            plugin: Some(PluginHookRun {
+                name: run.plugin.clone(),
+                event: hook_event.to_string(),
+                outcome: run.outcome,
+                duration_ms: run.elapsed.as_millis() as u64,
+            }),
+            ..Self::from_hook(hook_event, payload, false)
+        }
+    }
+
+    /// A `SkillUsed` event for `skill`, seen during `hook_event` with `payload`
+    pub fn skill_used(hook_event: HookEvent, payload: &serde_json::Value, skill: SkillUse) -> Self {
+        Self {
+            event_type: SKILL_USED.to_string(),
+            payload: None,
+            skill: Some(skill),
+            ..Self::from_hook(hook_event, payload, false)
         }
     }
 
@@ -80,6 +145,20 @@ impl Event {
             parts.push(tool.bold().to_string());
         }
 
+        if let Some(ref run) = self.plugin {
+            parts.push(format!(
+                "{} {} {} ({}ms)",
+                run.name.bold(),
+                run.event,
+                run.outcome,
+                run.duration_ms
+            ));
+        }
+
+        if let Some(ref skill) = self.skill {
+            parts.push(format!("{} via {}", skill.name.bold(), skill.via));
+        }
+
         parts.join(" ")
     }
 }
@@ -103,19 +182,48 @@ impl EventEmitter {
         }
 
         let event = Event::from_hook(hook_event, payload, self.config.include_payload);
+        // Turns and sessions end with their events written out
+        self.send(&event, matches!(hook_event, HookEvent::Stop | HookEvent::SessionEnd));
+    }
+
+    /// Emit a `PluginHook` event for each plugin that ran hooks for `hook_event`
+    pub fn emit_plugin_runs(&self, hook_event: HookEvent, payload: &serde_json::Value, runs: &[HookRun]) {
+        if !self.config.enabled {
+            return;
+        }
+        for run in runs {
+            self.send(&Event::plugin_hook(hook_event, payload, run), false);
+        }
+    }
 
+    /// Emit a `SkillUsed` event for `skill`
+    pub fn emit_skill_use(&self, hook_event: HookEvent, payload: &serde_json::Value, skill: SkillUse) {
+        if !self.config.enabled {
+            return;
+        }
+        self.send(&Event::skill_used(hook_event, payload, skill), false);
+    }
+
+    fn send(&self, event: &Event, flush: bool) {
         for sink in &self.config.sinks {
             match sink {
+                ObservabilitySink::File if self.config.flush_interval > 0 => {
+                    let sink =
+                        BatchedFileSink::new(&self.history_path, Duration::from_secs(self.config.flush_interval));
+                    if let Err(e) = sink.write(event, flush) {
+                        log::warn!("Failed to emit to file sink: {}", e);
+                    }
+                }
                 ObservabilitySink::File => {
-                    if let Err(e) = self.emit_to_file(&event) {
+                    if let Err(e) = self.emit_to_file(event) {
                         log::warn!("Failed to emit to file sink: {}", e);
                     }
                 }
                 ObservabilitySink::Stdout => {
-                    self.emit_to_stdout(&event);
+                    self.emit_to_stdout(event);
                 }
                 ObservabilitySink::Http => {
-                    if let Err(e) = self.emit_to_http(&event) {
+                    if let Err(e) = self.emit_to_http(event) {
                         log::warn!("Failed to emit to HTTP sink: {}", e);
                     }
                 }
@@ -125,7 +233,7 @@ impl EventEmitter {
 
     /// Write event to JSONL file
     fn emit_to_file(&self, event: &Event) -> std::io::Result<()> {
-        let now = Local::now();
+        let now = clock::now_local();
         let month_dir = self
             .history_path
             .join("raw-events")
@@ -168,6 +276,55 @@ impl EventEmitter {
     }
 }
 
+/// The latest `PluginHook` run of each plugin, with its timestamp, from the
+/// file sink's day logs for the last `days` days
+pub fn last_plugin_runs(history_path: &Path, days: i64) -> HashMap<String, (String, PluginHookRun)> {
+    let mut last = HashMap::new();
+    // Oldest first, so later runs replace earlier ones
+    for event in logged_events(history_path, days, PLUGIN_HOOK) {
+        if let Some(run) = event.plugin {
+            last.insert(run.name.clone(), (event.timestamp, run));
+        }
+    }
+    last
+}
+
+/// Every `SkillUsed` event in the file sink's day logs for the last `days` days, oldest first
+pub fn skill_uses(history_path: &Path, days: i64) -> Vec<Event> {
+    logged_events(history_path, days, SKILL_USED)
+        .into_iter()
+        .filter(|event| event.skill.is_some())
+        .collect()
+}
+
+/// Events of `event_type` from the day logs for the last `days` days, oldest first,
+/// including any the batched file sink still holds
+fn logged_events(history_path: &Path, days: i64, event_type: &str) -> Vec<Event> {
+    flush_buffered(history_path);
+    let events_dir = history_path.join("raw-events");
+    let today = clock::now_local().date_naive();
+    let mut events = Vec::new();
+    for day in (0..days)
+        .rev()
+        .filter_map(|n| today.checked_sub_signed(chrono::Duration::days(n)))
+    {
+        let log_file = events_dir
+            .join(day.format("%Y-%m").to_string())
+            .join(format!("{}.jsonl", day.format("%Y-%m-%d")));
+        let Ok(content) = fs::read_to_string(&log_file) else {
+            continue;
+        };
+        events.extend(
+            content
+                .lines()
+                .filter(|line| line.contains(event_type))
+                .filter_map(|line| serde_json::from_str::<Event>(line).ok())
+                .filter(|event| event.event_type == event_type),
+        );
+    }
+    events
+}
+
 /// Check if observability is configured for stdout
 /// (useful for `pais observe` command to avoid duplicate output)
 pub fn has_stdout_sink(config: &ObservabilityConfig) -> bool {
@@ -211,6 +368,8 @@ mod tests {
             session_id: Some("abc12345".to_string()),
             tool_name: None,
             payload: None,
+            plugin: None,
+            skill: None,
         };
 
         let display = event.format_display();
@@ -225,10 +384,98 @@ mod tests {
             sinks: vec![ObservabilitySink::Stdout],
             http_endpoint: None,
             include_payload: false,
+            flush_interval: 0,
         };
 
         let emitter = EventEmitter::new(config, std::path::PathBuf::from("/tmp"));
         // Should not panic or do anything
         emitter.emit(HookEvent::SessionStart, &serde_json::json!({}));
     }
+
+    #[test]
+    fn test_last_plugin_runs() {
+        let temp = tempfile::tempdir().unwrap();
+        let config = ObservabilityConfig {
+            enabled: true,
+            sinks: vec![ObservabilitySink::File],
+            http_endpoint: None,
+            include_payload: false,
+            flush_interval: 0,
+        };
+        let emitter = EventEmitter::new(config, temp.path().to_path_buf());
+        let run = |plugin: &str, outcome, ms| HookRun {
+            plugin: plugin.to_string(),
+            outcome,
+            elapsed: Duration::from_millis(ms),
+        };
+        let payload = serde_json::json!({"session_id": "s1", "tool_name": "Bash"});
+        emitter.emit(HookEvent::PreToolUse, &payload);
+        emitter.emit_plugin_runs(
+            HookEvent::PreToolUse,
+            &payload,
+            &[run("guard", HookOutcome::Allow, 5), run("audit", HookOutcome::Error, 7)],
+        );
+        emitter.emit_plugin_runs(HookEvent::Stop, &payload, &[run("guard", HookOutcome::Block, 12)]);
+
+        let last = last_plugin_runs(temp.path(), 2);
+        assert_eq!(last.len(), 2);
+        let (_, guard) = &last["guard"];
+        assert_eq!(
+            (guard.event.as_str(), guard.outcome, guard.duration_ms),
+            ("Stop", HookOutcome::Block, 12)
+        );
+        assert_eq!(last["audit"].1.outcome, HookOutcome::Error);
+    }
+
+    #[test]
+    fn test_skill_uses() {
+        let temp = tempfile::tempdir().unwrap();
+        let config = ObservabilityConfig {
+            enabled: true,
+            sinks: vec![ObservabilitySink::File],
+            http_endpoint: None,
+            include_payload: false,
+            flush_interval: 0,
+        };
+        let emitter = EventEmitter::new(config, temp.path().to_path_buf());
+        let payload = serde_json::json!({"session_id": "s1", "tool_name": "Skill"});
+        emitter.emit(HookEvent::PreToolUse, &payload);
+        let skill = SkillUse {
+            name: "rust".to_string(),
+            via: Trigger::SkillTool,
+        };
+        emitter.emit_skill_use(HookEvent::PreToolUse, &payload, skill.clone());
+
+        let uses = skill_uses(temp.path(), 1);
+        assert_eq!(uses.len(), 1);
+        assert_eq!(uses[0].session_id.as_deref(), Some("s1"));
+        assert_eq!(uses[0].skill, Some(skill));
+    }
+
+    #[test]
+    fn test_skill_uses_include_buffered_events() {
+        let temp = tempfile::tempdir().unwrap();
+        let config = ObservabilityConfig {
+            enabled: true,
+            sinks: vec![ObservabilitySink::File],
+            http_endpoint: None,
+            include_payload: false,
+            flush_interval: 3600,
+        };
+        let emitter = EventEmitter::new(config, temp.path().to_path_buf());
+        let payload = serde_json::json!({"session_id": "s1", "tool_name": "Skill"});
+        let skill = SkillUse {
+            name: "rust".to_string(),
+            via: Trigger::SkillTool,
+        };
+        emitter.emit_skill_use(HookEvent::PreToolUse, &payload, skill);
+        assert!(
+            temp.path()
+                .join("raw-events")
+                .join(crate::observability::batch::BUFFER_FILE)
+                .exists()
+        );
+
+        assert_eq!(skill_uses(temp.path(), 1).len(), 1);
+    }
 }
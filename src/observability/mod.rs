@@ -1,10 +1,12 @@
 //! Observability module for real-time event streaming
 //!
 //! Provides event emission to multiple sinks:
-//! - File (JSONL) - writes to history/raw-events/
+//! - File (JSONL) - writes to history/raw-events/, optionally in batches
 //! - Stdout - prints formatted events
 //! - HTTP - POSTs events to configured endpoint
 
+pub mod batch;
 pub mod emitter;
 
+pub use batch::{BatchedFileSink, flush_buffered};
 pub use emitter::{Event, EventEmitter};
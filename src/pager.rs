@@ -0,0 +1,92 @@
+//! Page long text output through `$PAGER`
+//!
+//! Lines are handed to the pager only when stdout is a terminal and they don't
+//! fit on one screen; otherwise they are printed directly. `PAGER` defaults to
+//! `less -R` so colors survive, and an empty `PAGER` or `cat` disables paging.
+
+use std::io::{ErrorKind, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+use eyre::{Context, Result};
+use terminal_size::{Height, terminal_size};
+
+const DEFAULT_PAGER: &str = "less -R";
+
+/// Pager program and arguments for a `PAGER` value (`None` = unset)
+fn pager_command(pager: Option<&str>) -> Option<Vec<String>> {
+    let words: Vec<String> = pager
+        .unwrap_or(DEFAULT_PAGER)
+        .split_whitespace()
+        .map(String::from)
+        .collect();
+    match words.first().map(String::as_str) {
+        None | Some("cat") => None,
+        Some(_) => Some(words),
+    }
+}
+
+/// Whether `lines` overflow a terminal of `height` rows (leaving one for the prompt)
+fn needs_paging(lines: &[String], height: Option<usize>) -> bool {
+    height.is_some_and(|h| lines.len() >= h.saturating_sub(1))
+}
+
+/// Print `lines`, through the user's pager when they overflow the terminal
+pub fn page(lines: &[String]) -> Result<()> {
+    let height = std::io::stdout()
+        .is_terminal()
+        .then(|| terminal_size().map(|(_, Height(h))| h as usize))
+        .flatten();
+    let command = pager_command(std::env::var("PAGER").ok().as_deref());
+
+    let Some(command) = command.filter(|_| needs_paging(lines, height)) else {
+        for line in lines {
+            println!("{}", line);
+        }
+        return Ok(());
+    };
+
+    let mut child = match Command::new(&command[0])
+        .args(&command[1..])
+        .stdin(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            log::warn!("Pager '{}' failed to start: {}", command.join(" "), e);
+            for line in lines {
+                println!("{}", line);
+            }
+            return Ok(());
+        }
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        for line in lines {
+            // The user quitting the pager early closes the pipe; that's not an error
+            match writeln!(stdin, "{}", line) {
+                Err(e) if e.kind() == ErrorKind::BrokenPipe => break,
+                other => other.context("Failed to write to pager")?,
+            }
+        }
+    }
+    child.wait().context("Failed to wait for pager")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pager_command_and_threshold() {
+        assert_eq!(pager_command(None).unwrap(), vec!["less", "-R"]);
+        assert_eq!(pager_command(Some("most")).unwrap(), vec!["most"]);
+        assert!(pager_command(Some("")).is_none());
+        assert!(pager_command(Some("cat")).is_none());
+
+        let lines: Vec<String> = (0..30).map(|i| i.to_string()).collect();
+        assert!(needs_paging(&lines, Some(24)));
+        assert!(!needs_paging(&lines, Some(50)));
+        assert!(!needs_paging(&lines, None));
+    }
+}
@@ -0,0 +1,319 @@
+//! Self-metrics
+//!
+//! Each pais process appends one line to `~/.local/share/pais/perf.jsonl`
+//! as it exits: the command, how long it took (config loading included) and
+//! the hits and misses of the caches it consulted, counted in-process with
+//! [`cache`]. Nothing leaves the machine. `pais status --perf` summarizes the
+//! records and compares the hot paths to their budgets (see
+//! docs/performance.md), so a slow hook shows up without running `pais bench`.
+//!
+//! The file is trimmed to its newest half once it passes [`MAX_BYTES`]. Set
+//! `perf.enabled: false` to stop recording.
+
+use chrono::{DateTime, Utc};
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::clock;
+
+// Budgets for a release build, in milliseconds (keep docs/performance.md in sync)
+pub const HOOK_DISPATCH_BUDGET_MS: u64 = 50;
+pub const CONTEXT_INJECT_BUDGET_MS: u64 = 100;
+pub const SKILL_INDEX_BUDGET_MS: u64 = 250;
+pub const HISTORY_QUERY_BUDGET_MS: u64 = 150;
+
+/// Size at which the log is trimmed to its newest half
+pub const MAX_BYTES: u64 = 512 * 1024;
+
+/// Cache lookups of this process, by cache name
+static CACHES: Mutex<BTreeMap<&'static str, CacheCount>> = Mutex::new(BTreeMap::new());
+
+/// The command being timed and when the process started
+static CURRENT: OnceLock<(String, Instant)> = OnceLock::new();
+
+/// Where the records live
+pub fn log_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("pais")
+        .join("perf.jsonl")
+}
+
+/// Subcommand path of a parsed command line, like `hook dispatch`
+pub fn command_name(matches: &clap::ArgMatches) -> String {
+    let mut names = Vec::new();
+    let mut current = matches;
+    while let Some((name, sub)) = current.subcommand() {
+        names.push(name);
+        current = sub;
+    }
+    names.join(" ")
+}
+
+/// Budget for the median run of `command`, if it is a hot path
+pub fn budget(command: &str) -> Option<u64> {
+    match command {
+        "hook dispatch" => Some(HOOK_DISPATCH_BUDGET_MS),
+        "context inject" => Some(CONTEXT_INJECT_BUDGET_MS),
+        "skill index" => Some(SKILL_INDEX_BUDGET_MS),
+        "history query" => Some(HISTORY_QUERY_BUDGET_MS),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CacheCount {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheCount {
+    /// Share of lookups that hit, or `None` before the first lookup
+    pub fn hit_rate(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+}
+
+/// Count one lookup in the cache `name`
+pub fn cache(name: &'static str, hit: bool) {
+    let mut caches = CACHES.lock().unwrap_or_else(|e| e.into_inner());
+    let count = caches.entry(name).or_default();
+    if hit {
+        count.hits += 1;
+    } else {
+        count.misses += 1;
+    }
+}
+
+/// One finished pais process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerfRecord {
+    pub at: DateTime<Utc>,
+    /// Subcommand path, like `hook dispatch`
+    pub command: String,
+    pub ms: f64,
+    pub ok: bool,
+    /// Whether this was an optimized build; budgets assume one
+    pub release: bool,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub caches: BTreeMap<String, CacheCount>,
+}
+
+impl PerfRecord {
+    /// A record for this process, with the cache lookups counted so far
+    pub fn new(command: &str, elapsed: Duration, ok: bool) -> Self {
+        let caches = CACHES.lock().unwrap_or_else(|e| e.into_inner());
+        Self {
+            at: clock::now(),
+            command: command.to_string(),
+            ms: millis(elapsed),
+            ok,
+            release: !cfg!(debug_assertions),
+            caches: caches.iter().map(|(name, count)| (name.to_string(), *count)).collect(),
+        }
+    }
+}
+
+/// Time this process as `command`; without a call, [`finish`] records nothing
+pub fn start(command: &str, started: Instant) {
+    let _ = CURRENT.set((command.to_string(), started));
+}
+
+/// Record the command being timed
+pub fn finish(ok: bool) {
+    if let Some((command, started)) = CURRENT.get() {
+        record(&PerfRecord::new(command, started.elapsed(), ok));
+    }
+}
+
+/// Record the command being timed, then exit with `code`
+///
+/// For commands that pick their own exit code, like a hook allowing (0) or
+/// blocking (2) a tool call; either way the run counts as successful.
+pub fn exit(code: i32) -> ! {
+    finish(true);
+    std::process::exit(code)
+}
+
+/// Append `record`, logging instead of failing: metrics must never break a command
+fn record(record: &PerfRecord) {
+    if let Err(e) = append(&log_path(), record) {
+        log::debug!("Failed to record self-metrics: {}", e);
+    }
+}
+
+fn append(path: &Path, record: &PerfRecord) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+    // One short write per line, so concurrent hooks don't interleave
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    file.write_all(line.as_bytes())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    if file.metadata().map(|m| m.len()).unwrap_or(0) > MAX_BYTES {
+        trim(path)?;
+    }
+    Ok(())
+}
+
+/// Keep the newest half of the lines
+fn trim(path: &Path) -> Result<()> {
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let lines: Vec<&str> = content.lines().collect();
+    let kept = lines[lines.len() / 2..].join("\n") + "\n";
+    let tmp = path.with_extension(format!("jsonl.{}", std::process::id()));
+    fs::write(&tmp, kept).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path.display()))
+}
+
+/// Every readable record, oldest first; unparseable lines are skipped
+pub fn read(path: &Path) -> Result<Vec<PerfRecord>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Timings of one command across records
+#[derive(Debug, Serialize)]
+pub struct CommandStats {
+    pub command: String,
+    pub runs: usize,
+    pub failed: usize,
+    pub median_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget_ms: Option<u64>,
+}
+
+impl CommandStats {
+    pub fn over_budget(&self) -> bool {
+        self.budget_ms.is_some_and(|budget| self.median_ms > budget as f64)
+    }
+}
+
+/// Per-command timings (slowest median first) and cache totals
+pub fn summarize(records: &[PerfRecord]) -> (Vec<CommandStats>, BTreeMap<String, CacheCount>) {
+    let mut by_command: BTreeMap<&str, Vec<&PerfRecord>> = BTreeMap::new();
+    let mut caches: BTreeMap<String, CacheCount> = BTreeMap::new();
+    for record in records {
+        by_command.entry(&record.command).or_default().push(record);
+        for (name, count) in &record.caches {
+            let total = caches.entry(name.clone()).or_default();
+            total.hits += count.hits;
+            total.misses += count.misses;
+        }
+    }
+
+    let mut commands: Vec<CommandStats> = by_command
+        .into_iter()
+        .map(|(command, runs)| {
+            let mut samples: Vec<Duration> = runs.iter().map(|r| Duration::from_secs_f64(r.ms / 1000.0)).collect();
+            let (median, p95) = percentiles(&mut samples);
+            CommandStats {
+                command: command.to_string(),
+                runs: runs.len(),
+                failed: runs.iter().filter(|r| !r.ok).count(),
+                median_ms: millis(median),
+                p95_ms: millis(p95),
+                max_ms: millis(samples[samples.len() - 1]),
+                budget_ms: budget(command),
+            }
+        })
+        .collect();
+    commands.sort_by(|a, b| b.median_ms.total_cmp(&a.median_ms));
+    (commands, caches)
+}
+
+/// Median and 95th percentile (nearest rank) of non-empty samples
+pub fn percentiles(samples: &mut [Duration]) -> (Duration, Duration) {
+    samples.sort();
+    let median = samples[samples.len() / 2];
+    let rank = (samples.len() * 95).div_ceil(100).max(1);
+    (median, samples[rank - 1])
+}
+
+/// Milliseconds, rounded to a tenth
+pub fn millis(d: Duration) -> f64 {
+    (d.as_secs_f64() * 10_000.0).round() / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn record(command: &str, ms: f64, caches: &[(&str, u64, u64)]) -> PerfRecord {
+        PerfRecord {
+            at: Utc::now(),
+            command: command.to_string(),
+            ms,
+            ok: true,
+            release: true,
+            caches: caches
+                .iter()
+                .map(|(name, hits, misses)| {
+                    (
+                        name.to_string(),
+                        CacheCount {
+                            hits: *hits,
+                            misses: *misses,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_append_read_and_summarize() {
+        let temp = tempdir().unwrap();
+        let path = temp.path().join("perf.jsonl");
+        for ms in [30.0, 70.0, 80.0] {
+            append(&path, &record("hook dispatch", ms, &[("config-snapshot", 1, 0)])).unwrap();
+        }
+        append(&path, &record("skill list", 20.0, &[("skill-metadata", 3, 1)])).unwrap();
+        fs::write(&path, fs::read_to_string(&path).unwrap() + "not json\n").unwrap();
+
+        let records = read(&path).unwrap();
+        assert_eq!(records.len(), 4);
+        let (commands, caches) = summarize(&records);
+        assert_eq!(commands[0].command, "hook dispatch");
+        assert_eq!(
+            (commands[0].runs, commands[0].median_ms, commands[0].max_ms),
+            (3, 70.0, 80.0)
+        );
+        assert!(commands[0].over_budget());
+        assert!(!commands[1].over_budget());
+        assert_eq!(caches["config-snapshot"].hit_rate(), Some(1.0));
+        assert_eq!(caches["skill-metadata"].hit_rate(), Some(0.75));
+    }
+
+    #[test]
+    fn test_trim_keeps_newest_half() {
+        let temp = tempdir().unwrap();
+        let path = temp.path().join("perf.jsonl");
+        fs::write(&path, "1\n2\n3\n4\n").unwrap();
+        trim(&path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "3\n4\n");
+    }
+}
@@ -0,0 +1,65 @@
+//! Fuzzy interactive pickers for names
+//!
+//! Commands that take a skill/plugin/agent/bundle name accept it as optional;
+//! when omitted on a terminal, the user picks from a fuzzy-searchable list.
+
+use dialoguer::FuzzySelect;
+use dialoguer::theme::ColorfulTheme;
+use eyre::Result;
+use std::io::IsTerminal;
+
+use crate::error::{CliError, ErrorCode};
+
+/// Return `name` if given, otherwise let the user pick one of `candidates`.
+///
+/// `kind` is used in prompts and errors (e.g. "skill", "plugin").
+/// Candidates are only computed when a picker is actually shown.
+pub fn pick_name<F>(name: Option<String>, kind: &str, candidates: F) -> Result<String>
+where
+    F: FnOnce() -> Result<Vec<String>>,
+{
+    if let Some(name) = name {
+        return Ok(name);
+    }
+
+    if !std::io::stdin().is_terminal() || !std::io::stderr().is_terminal() {
+        return Err(
+            CliError::new(ErrorCode::InvalidInput, format!("No {} name given", kind))
+                .with_hint("Pass one, or run interactively to pick from a list")
+                .into(),
+        );
+    }
+
+    let mut items = candidates()?;
+    items.sort();
+    items.dedup();
+
+    if items.is_empty() {
+        eyre::bail!("No {}s found", kind);
+    }
+
+    let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("Select {}", kind))
+        .items(&items)
+        .default(0)
+        .interact_opt()?;
+
+    match selection {
+        Some(index) => Ok(items.swap_remove(index)),
+        None => eyre::bail!("No {} selected", kind),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explicit_name_skips_picker() {
+        let name = pick_name(Some("rust-coder".to_string()), "skill", || {
+            panic!("candidates should not be listed when a name is given")
+        })
+        .unwrap();
+        assert_eq!(name, "rust-coder");
+    }
+}
@@ -0,0 +1,137 @@
+//! Machine-readable change plans for dry runs
+//!
+//! `pais sync --dry-run` and `pais upgrade --dry-run` record each change they
+//! would make as an add, remove or modify of a target. With `-o json|yaml` the
+//! plan is printed instead of the colored text, so scripts can gate on
+//! `destructive` (any removal) before running the real thing.
+
+use eyre::Result;
+use serde::Serialize;
+
+use crate::cli::OutputFormat;
+
+/// What a change does to its target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Op {
+    Add,
+    Remove,
+    Modify,
+}
+
+/// One change a dry run would make
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedChange {
+    pub op: Op,
+    pub target: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Changes collected over a run, printed as JSON/YAML at the end of a dry run
+pub struct ChangePlan {
+    dry_run: bool,
+    format: OutputFormat,
+    changes: Vec<PlannedChange>,
+    conflicts: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct PlanOutput<'a> {
+    destructive: bool,
+    changes: &'a [PlannedChange],
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    conflicts: &'a [String],
+}
+
+impl ChangePlan {
+    pub fn new(dry_run: bool, format: OutputFormat) -> Self {
+        Self {
+            dry_run,
+            format,
+            changes: Vec::new(),
+            conflicts: Vec::new(),
+        }
+    }
+
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Whether to print the colored progress text (everything but a JSON/YAML dry run)
+    pub fn text(&self) -> bool {
+        !self.dry_run || self.format == OutputFormat::Text
+    }
+
+    pub fn record(&mut self, op: Op, target: impl Into<String>, detail: Option<String>) {
+        self.changes.push(PlannedChange {
+            op,
+            target: target.into(),
+            detail,
+        });
+    }
+
+    /// A target that can't be changed without the user deciding how
+    pub fn conflict(&mut self, target: impl Into<String>) {
+        self.conflicts.push(target.into());
+    }
+
+    pub fn changes(&self) -> &[PlannedChange] {
+        &self.changes
+    }
+
+    /// Whether any change removes something
+    pub fn destructive(&self) -> bool {
+        self.changes.iter().any(|c| c.op == Op::Remove)
+    }
+
+    /// Print the plan if this is a JSON/YAML dry run
+    pub fn finish(&self) -> Result<()> {
+        if self.text() {
+            return Ok(());
+        }
+        let output = PlanOutput {
+            destructive: self.destructive(),
+            changes: self.changes(),
+            conflicts: &self.conflicts,
+        };
+        match self.format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+            OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&output)?),
+            OutputFormat::Text => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_plan() {
+        let mut plan = ChangePlan::new(true, OutputFormat::Json);
+        assert!(!plan.text());
+        plan.record(Op::Add, "skills/a", None);
+        plan.record(Op::Modify, "skills/b", Some("relink".into()));
+        assert!(!plan.destructive());
+        plan.record(Op::Remove, "skills/c", None);
+        assert!(plan.destructive());
+
+        let output = PlanOutput {
+            destructive: plan.destructive(),
+            changes: plan.changes(),
+            conflicts: &[],
+        };
+        let json = serde_json::to_value(&output).unwrap();
+        assert_eq!(
+            json["changes"][0],
+            serde_json::json!({"op": "add", "target": "skills/a"})
+        );
+        assert_eq!(json["changes"][1]["detail"], "relink");
+        assert!(json.get("conflicts").is_none());
+
+        assert!(ChangePlan::new(false, OutputFormat::Json).text());
+        assert!(ChangePlan::new(true, OutputFormat::Text).text());
+    }
+}
@@ -0,0 +1,520 @@
+//! Plugin builds and the build cache for compiled plugins
+//!
+//! `pais plugin build` runs each plugin's build type: `cargo build
+//! --release`, `uv sync`, `npm install` (plus `npm run build` when
+//! package.json has one) or the manifest's custom commands. The output goes
+//! to `~/.local/share/pais/builds/<plugin>.log`, and a successful build
+//! leaves a marker there recording the sources it built. Hooks of plugins
+//! that need building (cargo, npm and custom builds) don't run until the
+//! marker exists, and `pais plugin verify` flags a missing or stale one.
+//!
+//! Rust plugins are built with `cargo build --release` on every machine that
+//! installs them. The release binary is cached under
+//! `~/.local/share/pais/build-cache/<plugin>/<key>/`, where the key hashes the
+//! plugin's sources (everything but `target/`, `.git/` and other build
+//! output) together with the `rustc -vV` of the toolchain that built it. A
+//! later build with the same sources and toolchain restores the binary
+//! instead of compiling, and offline builds (`--offline`, `network.offline`) only ever restore.
+
+use chrono::{DateTime, Utc};
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use walkdir::WalkDir;
+
+use super::Plugin;
+use super::describe::rust_binaries;
+use super::manifest::BuildType;
+use crate::error::{CliError, ErrorCode};
+use crate::perf;
+use crate::skill::cache::content_hash;
+
+/// Directories that hold build output or tooling state, not sources
+pub const SKIP_DIRS: &[&str] = &["target", ".git", ".venv", "node_modules", "__pycache__"];
+
+/// How a build was satisfied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildOutcome {
+    /// Restored from the cache
+    Cached,
+    /// Compiled (and, for cargo, now cached)
+    Built,
+    /// The plugin has nothing to build
+    Nothing,
+}
+
+/// Directory holding cached builds
+pub fn cache_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("pais")
+        .join("build-cache")
+}
+
+/// Hash of the plugin's source files: relative paths and contents, in path order
+pub fn source_hash(plugin_path: &Path) -> Result<String> {
+    let mut files = Vec::new();
+    let walker = WalkDir::new(plugin_path)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| !(e.file_type().is_dir() && e.file_name().to_str().is_some_and(|n| SKIP_DIRS.contains(&n))));
+    for entry in walker {
+        let entry = entry.with_context(|| format!("Failed to read {}", plugin_path.display()))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let bytes = fs::read(entry.path()).with_context(|| format!("Failed to read {}", entry.path().display()))?;
+        let relative = entry.path().strip_prefix(plugin_path).unwrap_or(entry.path());
+        files.push(format!("{}:{}", relative.to_string_lossy(), content_hash(&bytes)));
+    }
+    Ok(content_hash(files.join("\n").as_bytes()))
+}
+
+/// `rustc -vV` of the toolchain cargo would use in `plugin_path`
+/// (so a `rust-toolchain.toml` there is honored)
+pub fn toolchain(plugin_path: &Path) -> Result<String> {
+    let output = Command::new("rustc")
+        .arg("-vV")
+        .current_dir(plugin_path)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .ok_or_else(|| {
+            CliError::new(ErrorCode::NotFound, "rustc not found; can't identify the toolchain")
+                .with_hint("Install Rust from https://rustup.rs")
+        })?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Cache key for the plugin's current sources and toolchain
+pub fn cache_key(plugin_path: &Path) -> Result<String> {
+    let sources = source_hash(plugin_path)?;
+    let toolchain = toolchain(plugin_path)?;
+    Ok(content_hash(format!("{}\n{}", sources, toolchain).as_bytes()))
+}
+
+fn plugin_name(plugin_path: &Path) -> &str {
+    plugin_path.file_name().and_then(|n| n.to_str()).unwrap_or("plugin")
+}
+
+/// Cached binary for `key`, if there is one
+fn cached(cache: &Path, plugin_path: &Path, key: &str) -> Option<PathBuf> {
+    let name = plugin_name(plugin_path);
+    Some(cache.join(name).join(key).join(name)).filter(|p| p.is_file())
+}
+
+/// The build a plugin has: its build type, when the files it works on exist
+pub fn plan(plugin: &Plugin) -> Option<BuildType> {
+    let has = |file: &str| plugin.path.join(file).exists();
+    match plugin.manifest.build_type() {
+        BuildType::Cargo => has("Cargo.toml").then_some(BuildType::Cargo),
+        BuildType::Uv => has("pyproject.toml").then_some(BuildType::Uv),
+        BuildType::Npm => has("package.json").then_some(BuildType::Npm),
+        BuildType::Custom => Some(BuildType::Custom),
+        BuildType::Nothing => None,
+    }
+}
+
+/// Whether the plugin's hooks wait for a build (uv syncs by itself on first run)
+pub fn required(plugin: &Plugin) -> bool {
+    matches!(
+        plan(plugin),
+        Some(BuildType::Cargo | BuildType::Npm | BuildType::Custom)
+    )
+}
+
+/// Directory holding build logs and markers
+pub fn builds_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("pais")
+        .join("builds")
+}
+
+/// Output of the last build of plugin `name`
+pub fn log_path(name: &str) -> PathBuf {
+    builds_dir().join(format!("{}.log", name))
+}
+
+/// Record of a plugin's last successful build
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Marker {
+    pub build: BuildType,
+    /// [`source_hash`] of what was built
+    pub sources: String,
+    pub built_at: DateTime<Utc>,
+}
+
+impl Marker {
+    fn path(builds: &Path, name: &str) -> PathBuf {
+        builds.join(format!("{}.json", name))
+    }
+
+    fn load(builds: &Path, name: &str) -> Option<Self> {
+        fs::read(Self::path(builds, name))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+    }
+}
+
+/// Whether a plugin has been built from its current sources
+#[derive(Debug, Clone)]
+pub enum BuildStatus {
+    Current(Marker),
+    /// Built, but the sources changed since
+    Stale(Marker),
+    Missing,
+}
+
+/// Check the plugin's marker against its sources
+pub fn status(plugin: &Plugin) -> Result<BuildStatus> {
+    status_in(&builds_dir(), plugin)
+}
+
+fn status_in(builds: &Path, plugin: &Plugin) -> Result<BuildStatus> {
+    let Some(marker) = Marker::load(builds, &plugin.manifest.plugin.name) else {
+        return Ok(BuildStatus::Missing);
+    };
+    if marker.sources == source_hash(&plugin.path)? {
+        Ok(BuildStatus::Current(marker))
+    } else {
+        Ok(BuildStatus::Stale(marker))
+    }
+}
+
+/// Whether the plugin has a build marker at all; cheap enough for hook dispatch
+pub fn is_built(plugin: &Plugin) -> bool {
+    Marker::path(&builds_dir(), &plugin.manifest.plugin.name).exists()
+}
+
+/// Forget a removed plugin's build log and marker
+pub fn forget(name: &str) {
+    let builds = builds_dir();
+    let _ = fs::remove_file(Marker::path(&builds, name));
+    let _ = fs::remove_file(log_path(name));
+}
+
+/// Run the plugin's build, saving its output to [`log_path`] and, on
+/// success, a marker of the sources it built
+///
+/// Cargo builds go through the build cache; with `offline`, a cache miss is
+/// an error instead of a compile, and uv and npm don't touch the network.
+pub fn build(plugin: &Plugin, offline: bool) -> Result<BuildOutcome> {
+    build_with(&cache_dir(), &builds_dir(), plugin, offline)
+}
+
+fn build_with(cache: &Path, builds: &Path, plugin: &Plugin, offline: bool) -> Result<BuildOutcome> {
+    let Some(build_type) = plan(plugin) else {
+        return Ok(BuildOutcome::Nothing);
+    };
+    let name = &plugin.manifest.plugin.name;
+    let log_file = builds.join(format!("{}.log", name));
+    let mut log = String::new();
+    let result = match build_type {
+        BuildType::Cargo => build_in(cache, &plugin.path, offline, &mut log).inspect(|outcome| {
+            perf::cache("build-cache", *outcome == BuildOutcome::Cached);
+        }),
+        BuildType::Uv => {
+            let mut uv = tool("uv", &plugin.path)?;
+            uv.arg("sync");
+            if offline {
+                uv.arg("--offline");
+            }
+            run_logged(&mut uv, &mut log).map(|_| BuildOutcome::Built)
+        }
+        BuildType::Npm => build_npm(&plugin.path, offline, &mut log).map(|_| BuildOutcome::Built),
+        BuildType::Custom => build_custom(plugin, &mut log).map(|_| BuildOutcome::Built),
+        BuildType::Nothing => Ok(BuildOutcome::Nothing),
+    };
+
+    fs::create_dir_all(builds).with_context(|| format!("Failed to create {}", builds.display()))?;
+    if !log.is_empty() {
+        fs::write(&log_file, &log).with_context(|| format!("Failed to write {}", log_file.display()))?;
+    }
+    let outcome = result.map_err(|e| {
+        if log.is_empty() {
+            return e;
+        }
+        let lines: Vec<&str> = log.lines().collect();
+        let tail = lines[lines.len().saturating_sub(20)..].join("\n");
+        CliError::new(ErrorCode::Error, format!("{:#}\n{}", e, tail))
+            .with_hint(format!("Full output in {}", log_file.display()))
+            .into()
+    })?;
+
+    let marker = Marker {
+        build: build_type,
+        sources: source_hash(&plugin.path)?,
+        built_at: Utc::now(),
+    };
+    let path = Marker::path(builds, name);
+    fs::write(&path, serde_json::to_vec_pretty(&marker)?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(outcome)
+}
+
+/// Command for `program` in `dir`, or an error saying how to get it
+fn tool(program: &str, dir: &Path) -> Result<Command> {
+    which::which(program).map_err(|_| {
+        CliError::new(
+            ErrorCode::NotFound,
+            format!("{} not found; it's needed to build this plugin", program),
+        )
+        .with_hint(format!("Install {} and make sure it's on PATH", program))
+    })?;
+    let mut command = Command::new(program);
+    command.current_dir(dir);
+    Ok(command)
+}
+
+/// Run `command`, appending what it printed to `log`
+fn run_logged(command: &mut Command, log: &mut String) -> Result<()> {
+    let line = format!(
+        "$ {} {}",
+        command.get_program().to_string_lossy(),
+        command
+            .get_args()
+            .map(|a| a.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ")
+    );
+    log.push_str(line.trim_end());
+    log.push('\n');
+    let output = command
+        .output()
+        .with_context(|| format!("Failed to run {}", command.get_program().to_string_lossy()))?;
+    log.push_str(&String::from_utf8_lossy(&output.stdout));
+    log.push_str(&String::from_utf8_lossy(&output.stderr));
+    if !output.status.success() {
+        eyre::bail!(
+            "`{}` failed with {}",
+            line.trim_start_matches("$ ").trim_end(),
+            output.status
+        );
+    }
+    Ok(())
+}
+
+/// `npm install`, then `npm run build` when package.json has a build script
+fn build_npm(plugin_path: &Path, offline: bool, log: &mut String) -> Result<()> {
+    let mut install = tool("npm", plugin_path)?;
+    install.arg("install");
+    if offline {
+        install.arg("--offline");
+    }
+    run_logged(&mut install, log)?;
+
+    let package: serde_json::Value = fs::read(plugin_path.join("package.json"))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default();
+    if package.pointer("/scripts/build").is_some() {
+        run_logged(tool("npm", plugin_path)?.args(["run", "build"]), log)?;
+    }
+    Ok(())
+}
+
+/// The manifest's `install_command`, then its `build_command`, each with `sh -c`
+fn build_custom(plugin: &Plugin, log: &mut String) -> Result<()> {
+    let spec = &plugin.manifest.build;
+    let commands: Vec<&String> = [&spec.install_command, &spec.build_command]
+        .into_iter()
+        .flatten()
+        .collect();
+    if commands.is_empty() {
+        return Err(CliError::new(
+            ErrorCode::Config,
+            format!(
+                "Plugin '{}' has a custom build but no install_command or build_command",
+                plugin.manifest.plugin.name
+            ),
+        )
+        .with_hint("Set build.build_command in plugin.yaml")
+        .into());
+    }
+    for command in commands {
+        run_logged(Command::new("sh").arg("-c").arg(command).current_dir(&plugin.path), log)?;
+    }
+    Ok(())
+}
+
+fn build_in(cache: &Path, plugin_path: &Path, offline: bool, log: &mut String) -> Result<BuildOutcome> {
+    let name = plugin_name(plugin_path);
+    let key = cache_key(plugin_path)?;
+    let release = rust_binaries(plugin_path)[0].clone();
+
+    if let Some(artifact) = cached(cache, plugin_path, &key) {
+        log::debug!("Restoring {} from build cache {}", name, key);
+        if let Some(parent) = release.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        fs::copy(&artifact, &release).with_context(|| format!("Failed to restore {}", release.display()))?;
+        return Ok(BuildOutcome::Cached);
+    }
+    if offline {
+        return Err(CliError::new(
+            ErrorCode::NotFound,
+            format!("No cached build of '{}' for these sources and toolchain", name),
+        )
+        .with_hint(format!(
+            "Run `pais plugin build {}` online (without --offline or network.offline) to compile and cache it",
+            name
+        ))
+        .into());
+    }
+
+    let mut cargo = tool("cargo", plugin_path)?;
+    run_logged(cargo.args(["build", "--release"]), log)
+        .wrap_err_with(|| format!("Failed to build Rust plugin '{}'", name))?;
+
+    // Hash again: the first build writes Cargo.lock
+    let key = cache_key(plugin_path)?;
+    let dir = cache.join(name).join(&key);
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let tmp = dir.join(format!("{}.{}", name, std::process::id()));
+    fs::copy(&release, &tmp).with_context(|| format!("Failed to cache {}", release.display()))?;
+    fs::rename(&tmp, dir.join(name)).with_context(|| format!("Failed to cache {}", release.display()))?;
+    Ok(BuildOutcome::Built)
+}
+
+/// A cached build
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    pub plugin: String,
+    pub key: String,
+    pub path: PathBuf,
+    pub bytes: u64,
+}
+
+/// Every cached build
+pub fn entries(cache: &Path) -> Vec<CacheEntry> {
+    let mut entries = Vec::new();
+    for plugin in fs::read_dir(cache).into_iter().flatten().flatten() {
+        let plugin_name = plugin.file_name().to_string_lossy().to_string();
+        for build in fs::read_dir(plugin.path()).into_iter().flatten().flatten() {
+            let path = build.path();
+            let bytes = WalkDir::new(&path)
+                .into_iter()
+                .flatten()
+                .filter_map(|e| e.metadata().ok())
+                .filter(|m| m.is_file())
+                .map(|m| m.len())
+                .sum();
+            entries.push(CacheEntry {
+                plugin: plugin_name.clone(),
+                key: build.file_name().to_string_lossy().to_string(),
+                path,
+                bytes,
+            });
+        }
+    }
+    entries.sort_by(|a, b| (&a.plugin, &a.key).cmp(&(&b.plugin, &b.key)));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_source_hash_ignores_build_output() {
+        let temp = tempdir().unwrap();
+        let plugin = temp.path().join("demo");
+        fs::create_dir_all(plugin.join("src")).unwrap();
+        fs::write(plugin.join("Cargo.toml"), "[package]\nname = \"demo\"\n").unwrap();
+        fs::write(plugin.join("src/main.rs"), "fn main() {}\n").unwrap();
+        let before = source_hash(&plugin).unwrap();
+
+        fs::create_dir_all(plugin.join("target/release")).unwrap();
+        fs::write(plugin.join("target/release/demo"), "binary").unwrap();
+        fs::create_dir_all(plugin.join(".git")).unwrap();
+        fs::write(plugin.join(".git/HEAD"), "ref").unwrap();
+        assert_eq!(source_hash(&plugin).unwrap(), before);
+
+        fs::write(plugin.join("src/main.rs"), "fn main() { println!(); }\n").unwrap();
+        assert_ne!(source_hash(&plugin).unwrap(), before);
+    }
+
+    fn custom_plugin(dir: &Path, build_command: &str) -> Plugin {
+        fs::create_dir_all(dir).unwrap();
+        let manifest = format!(
+            "plugin:\n  name: demo\n  version: 0.1.0\n  description: test\nbuild:\n  type: custom\n  build_command: \"{}\"\n",
+            build_command
+        );
+        fs::write(dir.join("plugin.yaml"), &manifest).unwrap();
+        Plugin {
+            manifest: crate::plugin::manifest::PluginManifest::from_str(&manifest).unwrap(),
+            path: dir.to_path_buf(),
+            state: crate::plugin::PluginState::Discovered,
+        }
+    }
+
+    #[test]
+    fn test_build_records_marker_and_log() {
+        let temp = tempdir().unwrap();
+        let (cache, builds) = (temp.path().join("cache"), temp.path().join("builds"));
+        let plugin = custom_plugin(
+            &temp.path().join("demo"),
+            "echo compiling; mkdir -p target; touch target/out",
+        );
+        assert!(required(&plugin));
+        assert!(matches!(status_in(&builds, &plugin).unwrap(), BuildStatus::Missing));
+
+        assert_eq!(
+            build_with(&cache, &builds, &plugin, false).unwrap(),
+            BuildOutcome::Built
+        );
+        assert!(
+            fs::read_to_string(builds.join("demo.log"))
+                .unwrap()
+                .contains("compiling")
+        );
+        let BuildStatus::Current(marker) = status_in(&builds, &plugin).unwrap() else {
+            panic!("expected a current build");
+        };
+        assert_eq!(marker.build, BuildType::Custom);
+
+        fs::write(plugin.path.join("extra.txt"), "changed").unwrap();
+        assert!(matches!(status_in(&builds, &plugin).unwrap(), BuildStatus::Stale(_)));
+
+        let broken = custom_plugin(&temp.path().join("broken"), "echo oops >&2; exit 3");
+        let err = build_with(&cache, &temp.path().join("broken-builds"), &broken, false).unwrap_err();
+        let err = err.downcast_ref::<CliError>().unwrap();
+        assert!(err.message.contains("oops"));
+        assert!(err.hint.as_deref().unwrap().contains("demo.log"));
+    }
+
+    #[test]
+    fn test_offline_build_restores_from_cache() {
+        if toolchain(Path::new(".")).is_err() {
+            return;
+        }
+        let temp = tempdir().unwrap();
+        let (cache, plugin) = (temp.path().join("cache"), temp.path().join("demo"));
+        fs::create_dir_all(plugin.join("src")).unwrap();
+        fs::write(plugin.join("src/main.rs"), "fn main() {}\n").unwrap();
+
+        let err = build_in(&cache, &plugin, true, &mut String::new()).unwrap_err();
+        assert!(err.to_string().contains("No cached build"));
+
+        let key = cache_key(&plugin).unwrap();
+        fs::create_dir_all(cache.join("demo").join(&key)).unwrap();
+        fs::write(cache.join("demo").join(&key).join("demo"), "binary").unwrap();
+        assert_eq!(
+            build_in(&cache, &plugin, true, &mut String::new()).unwrap(),
+            BuildOutcome::Cached
+        );
+        assert_eq!(
+            fs::read_to_string(plugin.join("target/release/demo")).unwrap(),
+            "binary"
+        );
+
+        let found = entries(&cache);
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].plugin.as_str(), found[0].bytes), ("demo", 6));
+    }
+}
@@ -0,0 +1,449 @@
+//! Per-plugin configuration
+//!
+//! A manifest's `config:` section declares the settings a plugin accepts.
+//! Values come from, in order of precedence:
+//!
+//! 1. `plugins.<name>.config` in pais.yaml
+//! 2. `~/.config/pais/plugin-config/<name>.yaml`, a flat map of keys to values
+//!    (kept outside the plugin directory so installs and updates leave it alone)
+//! 3. the setting's `env` variable, from the environment or `~/.config/pais/.env`
+//! 4. the setting's `default`
+//!
+//! The result is checked against the declared types before a plugin runs.
+//! Hook scripts and `pais run` get it as JSON in `PAIS_PLUGIN_CONFIG`, and
+//! each setting with an `env` name also under that variable.
+//!
+//! The manifest's `env:` section sets further variables from a secret in the
+//! environment or `.env`, an OS keyring entry, or a literal value. Every one
+//! must resolve; secret values are masked in the output pais captures.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::manifest::{EnvSource, PluginManifest};
+use crate::config::{Config, PluginSettings};
+use crate::error::{CliError, ErrorCode};
+use crate::secrets::{self, SecretStore};
+
+/// Environment variable carrying the resolved config as a JSON object
+pub const ENV_VAR: &str = "PAIS_PLUGIN_CONFIG";
+
+/// Where a setting's value came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Source {
+    PaisYaml,
+    File,
+    Env,
+    Default,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::PaisYaml => "pais.yaml",
+            Self::File => "config file",
+            Self::Env => "environment",
+            Self::Default => "default",
+        })
+    }
+}
+
+/// One declared setting and its resolved value
+#[derive(Debug, Clone, Serialize)]
+pub struct Setting {
+    pub key: String,
+    /// Unset when nothing supplies it and it has no default
+    pub value: Option<Value>,
+    pub source: Option<Source>,
+    pub secret: bool,
+    /// Variable the value is also exported under
+    pub env: Option<String>,
+}
+
+/// One `env:` variable and its resolved value
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvVar {
+    pub name: String,
+    #[serde(with = "serde_yaml::with::singleton_map")]
+    pub source: EnvSource,
+    /// Never serialized, so it can't leak into `--format json`
+    #[serde(skip)]
+    pub value: String,
+}
+
+/// A plugin's resolved configuration, in key order
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PluginConfig {
+    pub settings: Vec<Setting>,
+    /// The manifest's `env:` variables, by name
+    pub env: Vec<EnvVar>,
+}
+
+impl PluginConfig {
+    /// The set values as a JSON object
+    pub fn to_json(&self) -> Value {
+        Value::Object(
+            self.settings
+                .iter()
+                .filter_map(|s| Some((s.key.clone(), s.value.clone()?)))
+                .collect(),
+        )
+    }
+
+    /// Environment variables to run the plugin with
+    pub fn vars(&self) -> Vec<(String, String)> {
+        let mut vars = vec![(ENV_VAR.to_string(), self.to_json().to_string())];
+        for setting in &self.settings {
+            if let (Some(env), Some(value)) = (&setting.env, &setting.value) {
+                let value = match value {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                vars.push((env.clone(), value));
+            }
+        }
+        vars.extend(self.env.iter().map(|var| (var.name.clone(), var.value.clone())));
+        vars
+    }
+
+    /// Values to mask wherever the plugin's output is shown or logged
+    pub fn secrets(&self) -> Vec<String> {
+        let settings = self
+            .settings
+            .iter()
+            .filter(|s| s.secret)
+            .filter_map(|s| match &s.value {
+                Some(Value::String(s)) => Some(s.clone()),
+                Some(Value::Null) | None => None,
+                Some(other) => Some(other.to_string()),
+            });
+        let env = self
+            .env
+            .iter()
+            .filter(|var| var.source.is_secret())
+            .map(|var| var.value.clone());
+        settings.chain(env).collect()
+    }
+}
+
+/// Location of the config file for plugin `name`
+pub fn path(name: &str) -> PathBuf {
+    Config::pais_dir().join("plugin-config").join(format!("{}.yaml", name))
+}
+
+/// Resolve and validate the config for `manifest`, given its pais.yaml settings
+pub fn resolve(manifest: &PluginManifest, configured: Option<&PluginSettings>) -> Result<PluginConfig, CliError> {
+    let name = &manifest.plugin.name;
+    let file = path(name);
+    // Only read .env when some setting can come from it
+    let secrets = if manifest.config.values().any(|spec| spec.env.is_some())
+        || manifest
+            .env
+            .values()
+            .any(|source| matches!(source, EnvSource::Dotenv(_)))
+    {
+        SecretStore::load()
+    } else {
+        SecretStore::default()
+    };
+    resolve_from(manifest, configured, &file, &secrets)
+}
+
+fn resolve_from(
+    manifest: &PluginManifest,
+    configured: Option<&PluginSettings>,
+    file: &Path,
+    secrets: &SecretStore,
+) -> Result<PluginConfig, CliError> {
+    let name = &manifest.plugin.name;
+    let invalid = |problem: String| {
+        CliError::new(
+            ErrorCode::Config,
+            format!("Invalid configuration for plugin '{}': {}", name, problem),
+        )
+        .with_hint(format!(
+            "Set it under plugins.{}.config in pais.yaml or in {}",
+            name,
+            file.display()
+        ))
+    };
+
+    let from_file: BTreeMap<String, Value> = match fs::read_to_string(file) {
+        Ok(content) if content.trim().is_empty() => BTreeMap::new(),
+        Ok(content) => {
+            serde_yaml::from_str(&content).map_err(|e| invalid(format!("failed to parse {}: {}", file.display(), e)))?
+        }
+        Err(_) => BTreeMap::new(),
+    };
+    let from_pais = configured.map(|s| &s.config);
+
+    let unknown: Vec<&String> = from_pais
+        .into_iter()
+        .flat_map(|c| c.keys())
+        .chain(from_file.keys())
+        .filter(|key| !manifest.config.contains_key(*key))
+        .collect();
+    if let Some(key) = unknown.first() {
+        return Err(invalid(format!("unknown setting '{}'", key)));
+    }
+
+    let mut keys: Vec<&String> = manifest.config.keys().collect();
+    keys.sort();
+    let mut settings = Vec::new();
+    for key in keys {
+        let spec = &manifest.config[key];
+        let (value, source) = if let Some(value) = from_pais.and_then(|c| c.get(key)) {
+            (Some(value.clone()), Some(Source::PaisYaml))
+        } else if let Some(value) = from_file.get(key) {
+            (Some(value.clone()), Some(Source::File))
+        } else if let Some(raw) = spec.env.as_deref().and_then(|env| secrets.get(env)) {
+            let value = coerce(&spec.r#type, &raw).ok_or_else(|| {
+                invalid(format!(
+                    "'{}' from {} should be {}, got '{}'",
+                    key,
+                    spec.env.as_deref().unwrap_or_default(),
+                    article(&spec.r#type),
+                    if spec.secret { "***" } else { raw.as_str() }
+                ))
+            })?;
+            (Some(value), Some(Source::Env))
+        } else if let Some(default) = &spec.default {
+            let value =
+                serde_json::to_value(default).map_err(|e| invalid(format!("bad default for '{}': {}", key, e)))?;
+            (Some(value), Some(Source::Default))
+        } else {
+            (None, None)
+        };
+
+        match &value {
+            Some(value) if !matches_type(&spec.r#type, value) => {
+                return Err(invalid(format!(
+                    "'{}' should be {}, got {}",
+                    key,
+                    article(&spec.r#type),
+                    describe(value)
+                )));
+            }
+            None if spec.required => return Err(invalid(format!("'{}' is required", key))),
+            _ => {}
+        }
+
+        settings.push(Setting {
+            key: key.clone(),
+            value,
+            source,
+            secret: spec.secret,
+            env: spec.env.clone(),
+        });
+    }
+
+    let mut names: Vec<&String> = manifest.env.keys().collect();
+    names.sort();
+    let mut env = Vec::new();
+    for var in names {
+        let source = &manifest.env[var];
+        let value = match source {
+            EnvSource::Dotenv(secret) => secrets.get(secret).ok_or_else(|| secrets.missing(secret)),
+            EnvSource::Keyring(account) => secrets::keyring(account),
+            EnvSource::Value(value) => Ok(value.clone()),
+        }
+        .map_err(|e| {
+            let error = CliError::new(
+                ErrorCode::Config,
+                format!("Plugin '{}' can't set {}: {}", name, var, e.message),
+            );
+            match e.hint {
+                Some(hint) => error.with_hint(hint),
+                None => error,
+            }
+        })?;
+        env.push(EnvVar {
+            name: var.clone(),
+            source: source.clone(),
+            value,
+        });
+    }
+    Ok(PluginConfig { settings, env })
+}
+
+/// Whether `value` fits the declared type; undeclared types aren't checked
+fn matches_type(ty: &str, value: &Value) -> bool {
+    match ty {
+        "string" => value.is_string(),
+        "integer" | "int" => value.is_i64() || value.is_u64(),
+        "number" | "float" => value.is_number(),
+        "boolean" | "bool" => value.is_boolean(),
+        "array" | "list" => value.is_array(),
+        "object" | "map" => value.is_object(),
+        _ => true,
+    }
+}
+
+/// Parse an environment variable's text as the declared type
+fn coerce(ty: &str, raw: &str) -> Option<Value> {
+    match ty {
+        "string" => Some(Value::String(raw.to_string())),
+        "integer" | "int" | "number" | "float" | "boolean" | "bool" | "array" | "list" | "object" | "map" => {
+            serde_json::from_str(raw).ok().filter(|v| matches_type(ty, v))
+        }
+        _ => Some(serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))),
+    }
+}
+
+fn article(ty: &str) -> String {
+    match ty.chars().next() {
+        Some('a' | 'e' | 'i' | 'o' | 'u') => format!("an {}", ty),
+        _ => format!("a {}", ty),
+    }
+}
+
+fn describe(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn manifest() -> PluginManifest {
+        serde_yaml::from_str(
+            r#"
+plugin:
+  name: tracker
+  version: 0.1.0
+  description: test
+config:
+  url:
+    type: string
+    required: true
+  limit:
+    type: integer
+    default: 10
+  token:
+    type: string
+    env: PAIS_TEST_TRACKER_TOKEN
+    secret: true
+  verbose:
+    type: boolean
+    env: PAIS_TEST_TRACKER_VERBOSE
+"#,
+        )
+        .unwrap()
+    }
+
+    fn settings(yaml: &str) -> PluginSettings {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_resolve_precedence_and_vars() {
+        let temp = tempdir().unwrap();
+        let file = temp.path().join("tracker.yaml");
+        fs::write(&file, "url: https://file.example\nlimit: 5\n").unwrap();
+        let env = temp.path().join(".env");
+        fs::write(&env, "PAIS_TEST_TRACKER_TOKEN=s3cret\nPAIS_TEST_TRACKER_VERBOSE=true\n").unwrap();
+
+        let configured = settings("config:\n  url: https://pais.example\n");
+        let config = resolve_from(&manifest(), Some(&configured), &file, &SecretStore::from_file(&env)).unwrap();
+        let sources: Vec<_> = config.settings.iter().map(|s| (s.key.as_str(), s.source)).collect();
+        assert_eq!(
+            sources,
+            [
+                ("limit", Some(Source::File)),
+                ("token", Some(Source::Env)),
+                ("url", Some(Source::PaisYaml)),
+                ("verbose", Some(Source::Env)),
+            ]
+        );
+        assert_eq!(
+            config.to_json(),
+            serde_json::json!({"limit": 5, "token": "s3cret", "url": "https://pais.example", "verbose": true})
+        );
+
+        let vars = config.vars();
+        assert_eq!(vars[0].0, ENV_VAR);
+        assert!(vars.contains(&("PAIS_TEST_TRACKER_TOKEN".to_string(), "s3cret".to_string())));
+        assert!(vars.contains(&("PAIS_TEST_TRACKER_VERBOSE".to_string(), "true".to_string())));
+    }
+
+    #[test]
+    fn test_resolve_rejects_invalid_config() {
+        let temp = tempdir().unwrap();
+        let file = temp.path().join("tracker.yaml");
+        let secrets = SecretStore::default();
+        let check = |yaml: &str| {
+            resolve_from(&manifest(), Some(&settings(yaml)), &file, &secrets)
+                .unwrap_err()
+                .message
+        };
+
+        assert!(
+            resolve_from(&manifest(), None, &file, &secrets)
+                .unwrap_err()
+                .message
+                .contains("'url' is required")
+        );
+        assert!(check("config:\n  url: x\n  colour: red\n").contains("unknown setting 'colour'"));
+        assert!(check("config:\n  url: x\n  limit: lots\n").contains("'limit' should be an integer, got a string"));
+
+        let config = resolve_from(&manifest(), Some(&settings("config:\n  url: x\n")), &file, &secrets).unwrap();
+        let limit = config.settings.iter().find(|s| s.key == "limit").unwrap();
+        assert_eq!(
+            (limit.value.clone(), limit.source),
+            (Some(serde_json::json!(10)), Some(Source::Default))
+        );
+        assert!(
+            config
+                .settings
+                .iter()
+                .find(|s| s.key == "token")
+                .unwrap()
+                .value
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_resolve_env_section() {
+        let manifest: PluginManifest = serde_yaml::from_str(
+            r#"
+plugin:
+  name: deployer
+  version: 0.1.0
+  description: test
+env:
+  GITHUB_TOKEN: {dotenv: PAIS_TEST_DEPLOYER_GH}
+  API_BASE: {value: https://api.example.com}
+"#,
+        )
+        .unwrap();
+        let temp = tempdir().unwrap();
+        let file = temp.path().join("deployer.yaml");
+        let env = temp.path().join(".env");
+        fs::write(&env, "PAIS_TEST_DEPLOYER_GH=ghp_s3cret\n").unwrap();
+
+        let config = resolve_from(&manifest, None, &file, &SecretStore::from_file(&env)).unwrap();
+        let vars = config.vars();
+        assert!(vars.contains(&("GITHUB_TOKEN".to_string(), "ghp_s3cret".to_string())));
+        assert!(vars.contains(&("API_BASE".to_string(), "https://api.example.com".to_string())));
+        // Only the secret is masked, and its value never serializes
+        assert_eq!(config.secrets(), ["ghp_s3cret"]);
+        assert!(!serde_json::to_string(&config).unwrap().contains("ghp_s3cret"));
+
+        let err = resolve_from(&manifest, None, &file, &SecretStore::default()).unwrap_err();
+        assert!(err.message.contains("can't set GITHUB_TOKEN"));
+        assert!(err.hint.unwrap().contains("PAIS_TEST_DEPLOYER_GH"));
+    }
+}
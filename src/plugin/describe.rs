@@ -0,0 +1,495 @@
+//! Runtime capability discovery via the `describe` handshake
+//!
+//! A plugin's entry point answers the `describe` action with a JSON
+//! document listing what it actually implements:
+//!
+//! ```json
+//! {
+//!   "actions": [{"name": "greet", "description": "Say hello"}],
+//!   "hooks": ["PreToolUse"],
+//!   "provides": ["memory"],
+//!   "consumes": [],
+//!   "config": {"api-key": {"type": "string", "required": true}}
+//! }
+//! ```
+//!
+//! Every section is optional; an omitted section is not checked. `pais
+//! plugin verify` compares the answer against plugin.yaml, and `pais run`
+//! completes action names from it. Answers are cached until the manifest or
+//! entry point changes, since completion asks on every TAB.
+
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use super::executor::script_command;
+use super::manifest::{PluginLanguage, PluginManifest};
+use super::verify::CheckResult;
+use super::wasm::{self, Contracts};
+use crate::skill::cache::content_hash;
+
+/// Action plugins answer with their description
+pub const DESCRIBE_ACTION: &str = "describe";
+
+/// How long a plugin gets to describe itself
+const DESCRIBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Hook events a manifest can subscribe to
+const HOOK_EVENTS: &[&str] = &[
+    "PreToolUse",
+    "PostToolUse",
+    "Stop",
+    "SessionStart",
+    "SessionEnd",
+    "SubagentStop",
+];
+
+/// What a plugin reports about itself at runtime
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Description {
+    pub actions: Vec<ActionInfo>,
+    pub hooks: Option<Vec<String>>,
+    pub provides: Option<Vec<String>>,
+    pub consumes: Option<Vec<String>>,
+    pub config: Option<BTreeMap<String, ConfigField>>,
+}
+
+/// One action the entry point accepts
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ActionInfo {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// A config key the plugin reads
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ConfigField {
+    pub r#type: Option<String>,
+    pub required: bool,
+}
+
+/// Path of a Rust plugin's binary for each build profile, release first
+pub fn rust_binaries(plugin_path: &Path) -> [PathBuf; 2] {
+    let name = plugin_path.file_name().and_then(|n| n.to_str()).unwrap_or("plugin");
+    [
+        plugin_path.join("target").join("release").join(name),
+        plugin_path.join("target").join("debug").join(name),
+    ]
+}
+
+/// Entry point of a Node plugin: `src/index.ts`, `src/index.js` or `src/index.mjs`
+pub fn node_entry(plugin_path: &Path) -> Option<PathBuf> {
+    ["index.ts", "index.js", "index.mjs"]
+        .iter()
+        .map(|file| plugin_path.join("src").join(file))
+        .find(|path| path.exists())
+}
+
+/// Entry point of a shell plugin
+pub fn shell_entry(plugin_path: &Path) -> PathBuf {
+    plugin_path.join("src").join("main.sh")
+}
+
+/// Command running the plugin's entry point, if it exists
+///
+/// Python plugins run `src/main.py` with the plugin's venv (or `python3`);
+/// Rust plugins run an already built binary. Node and shell plugins run
+/// their entry script as hook scripts do. Nothing is built here. Wasm
+/// plugins have no command; they're called through [`wasm::call`].
+pub fn entrypoint(plugin_path: &Path, language: &PluginLanguage) -> Option<Command> {
+    let main_py = plugin_path.join("src").join("main.py");
+    let python = || {
+        let venv_python = plugin_path.join(".venv").join("bin").join("python");
+        let mut cmd = Command::new(if venv_python.exists() { venv_python } else { PathBuf::from("python3") });
+        cmd.env("PYTHONDONTWRITEBYTECODE", "1").arg(&main_py);
+        cmd
+    };
+    let rust = || {
+        rust_binaries(plugin_path)
+            .into_iter()
+            .find(|p| p.exists())
+            .map(Command::new)
+    };
+
+    let mut cmd = match language {
+        PluginLanguage::Python => main_py.exists().then(python)?,
+        PluginLanguage::Rust => rust()?,
+        PluginLanguage::Mixed if main_py.exists() => python(),
+        PluginLanguage::Mixed => rust()?,
+        PluginLanguage::Node => script_command(plugin_path, &node_entry(plugin_path)?),
+        PluginLanguage::Shell => {
+            let main_sh = shell_entry(plugin_path);
+            main_sh.exists().then(|| script_command(plugin_path, &main_sh))?
+        }
+        PluginLanguage::Wasm => return None,
+    };
+    cmd.current_dir(plugin_path);
+    Some(cmd)
+}
+
+/// The file whose changes invalidate a cached description
+fn entrypoint_file(plugin_path: &Path, language: &PluginLanguage) -> Option<PathBuf> {
+    let main_py = plugin_path.join("src").join("main.py");
+    let rust = || rust_binaries(plugin_path).into_iter().find(|p| p.exists());
+    match language {
+        PluginLanguage::Python => main_py.exists().then_some(main_py),
+        PluginLanguage::Mixed if main_py.exists() => Some(main_py),
+        PluginLanguage::Rust | PluginLanguage::Mixed => rust(),
+        PluginLanguage::Node => node_entry(plugin_path),
+        PluginLanguage::Shell => Some(shell_entry(plugin_path)).filter(|p| p.exists()),
+        PluginLanguage::Wasm => wasm::component(plugin_path),
+    }
+}
+
+/// Ask the plugin to describe itself, bypassing the cache
+///
+/// Returns `Ok(None)` when the entry point doesn't exist yet or doesn't
+/// implement `describe` (non-zero exit); malformed answers and timeouts
+/// are errors.
+pub fn describe(plugin_path: &Path, manifest: &PluginManifest) -> Result<Option<Description>> {
+    if let PluginLanguage::Wasm = manifest.plugin.language {
+        return describe_wasm(plugin_path, manifest);
+    }
+    let Some(mut cmd) = entrypoint(plugin_path, &manifest.plugin.language) else {
+        return Ok(None);
+    };
+    let mut child = cmd
+        .arg(DESCRIBE_ACTION)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to start plugin '{}'", manifest.plugin.name))?;
+
+    // Drain stdout on a thread so a chatty plugin can't fill the pipe and stall
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let reader = std::thread::spawn(move || {
+        let mut out = String::new();
+        stdout.read_to_string(&mut out).map(|_| out)
+    });
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().context("Failed to wait for plugin")? {
+            break status;
+        }
+        if started.elapsed() > DESCRIBE_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            eyre::bail!(
+                "Plugin '{}' did not answer describe within {}s",
+                manifest.plugin.name,
+                DESCRIBE_TIMEOUT.as_secs()
+            );
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+
+    let output = reader
+        .join()
+        .map_err(|_| eyre::eyre!("Failed to read plugin output"))?
+        .context("Failed to read plugin output")?;
+    if !status.success() {
+        log::debug!(
+            "Plugin {} does not implement describe ({})",
+            manifest.plugin.name,
+            status
+        );
+        return Ok(None);
+    }
+
+    serde_json::from_str(output.trim())
+        .map(Some)
+        .with_context(|| format!("Plugin '{}' answered describe with invalid JSON", manifest.plugin.name))
+}
+
+fn describe_wasm(plugin_path: &Path, manifest: &PluginManifest) -> Result<Option<Description>> {
+    if wasm::component(plugin_path).is_none() {
+        return Ok(None);
+    }
+    let output = wasm::call(
+        plugin_path,
+        manifest,
+        DESCRIBE_ACTION,
+        &[],
+        Some(DESCRIBE_TIMEOUT),
+        &[],
+        &Contracts::default(),
+    )?;
+    match output.value {
+        Ok(answer) => serde_json::from_str(answer.trim())
+            .map(Some)
+            .with_context(|| format!("Plugin '{}' answered describe with invalid JSON", manifest.plugin.name)),
+        Err(e) => {
+            log::debug!("Plugin {} does not implement describe ({})", manifest.plugin.name, e);
+            Ok(None)
+        }
+    }
+}
+
+/// A description cached for one plugin
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedDescription {
+    key: String,
+    description: Option<Description>,
+}
+
+fn cache_file(plugin_path: &Path) -> Option<PathBuf> {
+    let name = format!("{}.json", content_hash(plugin_path.as_os_str().as_encoded_bytes()));
+    dirs::cache_dir().map(|d| d.join("pais").join("describe").join(name))
+}
+
+fn mtime(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+/// Cache key: changes whenever the manifest or entry point does
+fn cache_key(plugin_path: &Path, language: &PluginLanguage) -> String {
+    let entry = entrypoint_file(plugin_path, language);
+    format!(
+        "{}:{}:{}",
+        mtime(&plugin_path.join("plugin.yaml")),
+        entry.as_deref().map(Path::to_string_lossy).unwrap_or_default(),
+        entry.as_deref().map(mtime).unwrap_or(0)
+    )
+}
+
+/// Like [`describe`], but reuse the last answer while nothing changed
+///
+/// Errors are logged and treated as "no description", so callers on hot
+/// paths (shell completion) never fail because of a plugin.
+pub fn describe_cached(plugin_path: &Path, manifest: &PluginManifest) -> Option<Description> {
+    let key = cache_key(plugin_path, &manifest.plugin.language);
+    let cache = cache_file(plugin_path);
+    if let Some(cached) = cache
+        .as_ref()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<CachedDescription>(&content).ok())
+        .filter(|c| c.key == key)
+    {
+        return cached.description;
+    }
+
+    let description = describe(plugin_path, manifest).unwrap_or_else(|e| {
+        log::debug!("Describe failed: {:#}", e);
+        None
+    });
+    if let Some(path) = cache {
+        let entry = CachedDescription {
+            key,
+            description: description.clone(),
+        };
+        let written = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, serde_json::to_string(&entry).unwrap_or_default()));
+        if let Err(e) = written {
+            log::debug!("Failed to cache plugin description: {}", e);
+        }
+    }
+    description
+}
+
+/// Compare a declared and a reported set of names
+fn compare_names<'a>(
+    name: &str,
+    declared: impl IntoIterator<Item = &'a str>,
+    reported: impl IntoIterator<Item = &'a str>,
+) -> CheckResult {
+    let declared: BTreeSet<&str> = declared.into_iter().collect();
+    let reported: BTreeSet<&str> = reported.into_iter().collect();
+    let join = |names: Vec<&&str>| names.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(", ");
+
+    let mut problems = Vec::new();
+    let missing: Vec<_> = declared.difference(&reported).collect();
+    if !missing.is_empty() {
+        problems.push(format!(
+            "declared in plugin.yaml but not implemented: {}",
+            join(missing)
+        ));
+    }
+    let undeclared: Vec<_> = reported.difference(&declared).collect();
+    if !undeclared.is_empty() {
+        problems.push(format!(
+            "implemented but not declared in plugin.yaml: {}",
+            join(undeclared)
+        ));
+    }
+
+    CheckResult {
+        name: format!("describe: {}", name),
+        passed: problems.is_empty(),
+        message: if problems.is_empty() {
+            Some(format!("{} match", reported.len()))
+        } else {
+            Some(problems.join("; "))
+        },
+    }
+}
+
+/// Checks that the manifest matches what the plugin reported
+pub fn check_manifest(manifest: &PluginManifest, description: &Description) -> Vec<CheckResult> {
+    let mut checks = vec![CheckResult {
+        name: "describe: actions".to_string(),
+        passed: true,
+        message: Some(
+            description
+                .actions
+                .iter()
+                .map(|a| a.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+    }];
+
+    if let Some(ref hooks) = description.hooks {
+        let declared = HOOK_EVENTS
+            .iter()
+            .copied()
+            .filter(|event| !manifest.hooks.scripts_for_event(event).is_empty());
+        checks.push(compare_names("hooks", declared, hooks.iter().map(String::as_str)));
+    }
+    if let Some(ref provides) = description.provides {
+        checks.push(compare_names(
+            "provides",
+            manifest.provides.keys().map(String::as_str),
+            provides.iter().map(String::as_str),
+        ));
+    }
+    if let Some(ref consumes) = description.consumes {
+        checks.push(compare_names(
+            "consumes",
+            manifest.consumes.keys().map(String::as_str),
+            consumes.iter().map(String::as_str),
+        ));
+    }
+    if let Some(ref config) = description.config {
+        let mut check = compare_names(
+            "config",
+            manifest.config.keys().map(String::as_str),
+            config.keys().map(String::as_str),
+        );
+        // Keys on both sides must also agree on whether they're required
+        let disagreeing: Vec<&str> = config
+            .iter()
+            .filter(|(key, field)| {
+                manifest
+                    .config
+                    .get(*key)
+                    .is_some_and(|spec| spec.required != field.required)
+            })
+            .map(|(key, _)| key.as_str())
+            .collect();
+        if !disagreeing.is_empty() {
+            let problem = format!("required differs for: {}", disagreeing.join(", "));
+            check.message = Some(if check.passed {
+                problem
+            } else {
+                format!("{}; {}", check.message.unwrap_or_default(), problem)
+            });
+            check.passed = false;
+        }
+        checks.push(check);
+    }
+
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn manifest(yaml: &str) -> PluginManifest {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    const MANIFEST: &str = r#"
+plugin:
+  name: notes
+  version: 0.1.0
+  description: test
+  language: python
+provides:
+  memory: {contract: MemoryProvider}
+config:
+  api-key: {type: string, required: true}
+  region: {type: string}
+hooks:
+  Stop:
+    - script: hooks/capture.py
+"#;
+
+    #[test]
+    fn test_check_manifest_reports_both_directions() {
+        let description: Description = serde_json::from_str(
+            r#"{
+                "actions": [{"name": "greet"}],
+                "hooks": ["Stop", "PreToolUse"],
+                "provides": ["memory"],
+                "config": {"api-key": {"type": "string"}, "region": {}}
+            }"#,
+        )
+        .unwrap();
+        let checks = check_manifest(&manifest(MANIFEST), &description);
+        let by_name = |name: &str| checks.iter().find(|c| c.name == format!("describe: {}", name)).unwrap();
+
+        assert!(by_name("actions").passed);
+        assert!(by_name("provides").passed);
+        let hooks = by_name("hooks");
+        assert!(!hooks.passed);
+        assert!(
+            hooks
+                .message
+                .as_deref()
+                .unwrap()
+                .contains("not declared in plugin.yaml: PreToolUse")
+        );
+        let config = by_name("config");
+        assert!(!config.passed);
+        assert!(
+            config
+                .message
+                .as_deref()
+                .unwrap()
+                .contains("required differs for: api-key")
+        );
+        // Sections the plugin didn't report aren't checked
+        assert!(checks.iter().all(|c| c.name != "describe: consumes"));
+    }
+
+    #[test]
+    fn test_describe_runs_entrypoint() {
+        let temp = tempdir().unwrap();
+        let manifest = manifest(MANIFEST);
+        assert_eq!(describe(temp.path(), &manifest).unwrap(), None);
+
+        if which::which("python3").is_err() {
+            return;
+        }
+        fs::create_dir_all(temp.path().join("src")).unwrap();
+        fs::write(
+            temp.path().join("src").join("main.py"),
+            "import json, sys\n\
+             if sys.argv[1] == 'describe':\n    print(json.dumps({'actions': [{'name': 'greet'}]}))\n\
+             else:\n    sys.exit(1)\n",
+        )
+        .unwrap();
+        let description = describe(temp.path(), &manifest).unwrap().unwrap();
+        assert_eq!(description.actions[0].name, "greet");
+        assert_eq!(description.hooks, None);
+    }
+}
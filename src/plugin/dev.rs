@@ -0,0 +1,164 @@
+//! Watch a plugin checkout during development
+//!
+//! `pais plugin dev` links a checkout into the plugins directory, then
+//! re-validates and rebuilds it whenever its sources change. Build output
+//! (`target/`, `node_modules/`, `.venv/`, `dist/`), lockfiles the builds
+//! rewrite and hidden paths don't count as changes, so a rebuild doesn't
+//! trigger the next one.
+
+use eyre::{Context, Result};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde_json::{Value, json};
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, channel};
+use std::time::Duration;
+
+use crate::hook::HookEvent;
+use crate::sync::watch::event_paths;
+
+/// Quiet period that ends a batch (editors often write a file several times)
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Directories builds write into
+const BUILD_DIRS: &[&str] = &["target", "node_modules", ".venv", "dist", "__pycache__"];
+
+/// Files builds rewrite
+const LOCKFILES: &[&str] = &["Cargo.lock", "package-lock.json", "uv.lock"];
+
+/// Recursive watcher over one plugin directory
+pub struct PluginWatcher {
+    root: PathBuf,
+    events: Receiver<notify::Result<notify::Event>>,
+    // Dropping the watcher stops event delivery
+    _watcher: RecommendedWatcher,
+}
+
+impl PluginWatcher {
+    pub fn new(root: &Path) -> Result<Self> {
+        let (tx, events) = channel();
+        let mut watcher = notify::recommended_watcher(tx).context("Failed to create file watcher")?;
+        watcher
+            .watch(root, RecursiveMode::Recursive)
+            .with_context(|| format!("Failed to watch {}", root.display()))?;
+        Ok(Self {
+            root: root.to_path_buf(),
+            events,
+            _watcher: watcher,
+        })
+    }
+
+    /// Block until sources change; returns the changed paths relative to the root, sorted
+    pub fn next_batch(&self) -> Result<Vec<PathBuf>> {
+        loop {
+            let first = self.events.recv().context("File watcher stopped")?;
+            let mut paths = event_paths(first);
+
+            loop {
+                match self.events.recv_timeout(DEBOUNCE) {
+                    Ok(event) => paths.extend(event_paths(event)),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => eyre::bail!("File watcher stopped"),
+                }
+            }
+
+            let mut changed: Vec<PathBuf> = paths
+                .iter()
+                .filter_map(|path| path.strip_prefix(&self.root).ok())
+                .filter(|rel| is_source(rel))
+                .map(Path::to_path_buf)
+                .collect();
+            changed.sort();
+            changed.dedup();
+            if !changed.is_empty() {
+                return Ok(changed);
+            }
+        }
+    }
+
+    /// Drop events already queued, such as those from a build that just ran
+    pub fn skip_pending(&self) {
+        while self.events.try_recv().is_ok() {}
+    }
+}
+
+/// Whether a path relative to the plugin root is a source file, not build output
+pub fn is_source(rel: &Path) -> bool {
+    let names: Vec<&str> = rel
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(name) => name.to_str(),
+            _ => None,
+        })
+        .collect();
+    let Some(file) = names.last() else {
+        return false;
+    };
+    !names
+        .iter()
+        .any(|name| name.starts_with('.') || BUILD_DIRS.contains(name))
+        && !LOCKFILES.contains(file)
+        && !file.ends_with('~')
+}
+
+/// A payload shaped like the ones Claude Code sends for `event`
+pub fn sample_payload(event: HookEvent, cwd: &Path) -> Value {
+    let mut payload = json!({
+        "session_id": "pais-dev",
+        "transcript_path": "",
+        "cwd": cwd.display().to_string(),
+        "hook_event_name": event.to_string(),
+    });
+    let extra = match event {
+        HookEvent::PreToolUse | HookEvent::PermissionRequest => json!({
+            "tool_name": "Bash",
+            "tool_input": {"command": "ls -la", "description": "List files"},
+        }),
+        HookEvent::PostToolUse => json!({
+            "tool_name": "Bash",
+            "tool_input": {"command": "ls -la", "description": "List files"},
+            "tool_response": {"stdout": "", "stderr": "", "interrupted": false},
+        }),
+        HookEvent::UserPromptSubmit => json!({"prompt": "Summarize this repository"}),
+        HookEvent::Notification => json!({"message": "Claude needs your permission to use Bash"}),
+        HookEvent::Stop | HookEvent::SubagentStop => json!({"stop_hook_active": false}),
+        HookEvent::SessionStart => json!({"source": "startup"}),
+        HookEvent::SessionEnd => json!({"reason": "exit"}),
+        HookEvent::PreCompact => json!({"trigger": "manual", "custom_instructions": ""}),
+    };
+    if let (Some(payload), Value::Object(extra)) = (payload.as_object_mut(), extra) {
+        payload.extend(extra);
+    }
+    payload
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_source() {
+        for rel in ["plugin.yaml", "src/main.py", "hooks/guard.sh", "src/lib/util.ts"] {
+            assert!(is_source(Path::new(rel)), "{}", rel);
+        }
+        for rel in [
+            "target/debug/guard",
+            "node_modules/tsx/package.json",
+            ".venv/bin/python",
+            ".git/index",
+            "src/.main.py.swp",
+            "src/main.py~",
+            "Cargo.lock",
+            "package-lock.json",
+        ] {
+            assert!(!is_source(Path::new(rel)), "{}", rel);
+        }
+    }
+
+    #[test]
+    fn test_sample_payload() {
+        let payload = sample_payload(HookEvent::PreToolUse, Path::new("/work"));
+        assert_eq!(payload["hook_event_name"], "PreToolUse");
+        assert_eq!(payload["cwd"], "/work");
+        assert_eq!(payload["tool_name"], "Bash");
+    }
+}
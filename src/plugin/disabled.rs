@@ -0,0 +1,107 @@
+//! Disabled plugins
+//!
+//! `pais plugin disable <name>` silences a plugin without uninstalling it:
+//! its files stay put, but discovery marks it [`PluginState::Disabled`], so
+//! its hooks aren't dispatched, it provides no contracts, and `pais run`
+//! refuses it until `pais plugin enable`. The set lives in
+//! `~/.local/share/pais/disabled-plugins.json`, so pais.yaml is left as
+//! written.
+//!
+//! [`PluginState::Disabled`]: super::PluginState::Disabled
+
+use chrono::{DateTime, Utc};
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::lock::FileLock;
+
+/// Disabled plugins, by name, with when they were disabled
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DisabledPlugins {
+    #[serde(default)]
+    pub plugins: BTreeMap<String, DateTime<Utc>>,
+}
+
+/// Location of the file (`~/.local/share/pais/disabled-plugins.json`)
+pub fn path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("pais")
+        .join("disabled-plugins.json")
+}
+
+impl DisabledPlugins {
+    /// Load the set, treating a missing or unreadable file as empty
+    pub fn load() -> Self {
+        Self::load_from(&path())
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        fs::read(path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write the set atomically
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let tmp = path.with_extension(format!("json.{}", std::process::id()));
+        fs::write(&tmp, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path.display()))?;
+        Ok(())
+    }
+
+    pub fn is_disabled(&self, name: &str) -> bool {
+        self.plugins.contains_key(name)
+    }
+
+    /// When `name` was disabled, if it is
+    pub fn since(&self, name: &str) -> Option<DateTime<Utc>> {
+        self.plugins.get(name).copied()
+    }
+}
+
+/// Disable or re-enable `name` in the file at `path`; returns false if it already was
+pub fn set(path: &Path, name: &str, disabled: bool) -> Result<bool> {
+    let _lock = FileLock::acquire(path)?;
+    let mut set = DisabledPlugins::load_from(path);
+    let changed = if disabled {
+        set.plugins.insert(name.to_string(), Utc::now()).is_none()
+    } else {
+        set.plugins.remove(name).is_some()
+    };
+    if changed {
+        set.save_to(path)?;
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_disable_and_enable() {
+        let temp = tempdir().unwrap();
+        let path = temp.path().join("disabled-plugins.json");
+        assert!(!DisabledPlugins::load_from(&path).is_disabled("noisy"));
+
+        assert!(set(&path, "noisy", true).unwrap());
+        assert!(!set(&path, "noisy", true).unwrap());
+        let loaded = DisabledPlugins::load_from(&path);
+        assert!(loaded.is_disabled("noisy"));
+        assert!(loaded.since("noisy").is_some());
+
+        assert!(set(&path, "noisy", false).unwrap());
+        assert!(!set(&path, "noisy", false).unwrap());
+        assert!(!DisabledPlugins::load_from(&path).is_disabled("noisy"));
+    }
+}
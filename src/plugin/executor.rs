@@ -1,14 +1,75 @@
 //! Plugin hook executor
 //!
-//! Executes plugin scripts when hook events fire.
+//! Executes plugin scripts when hook events fire. A script that runs past
+//! its timeout is killed and reported as an error, so a hung plugin can't
+//! stall Claude.
 
 use eyre::{Context, Result};
-use std::io::Write;
+use regex::Regex;
+use serde_json::Value;
+use std::io::{Read, Write};
 use std::path::Path;
 use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
 
 use crate::hook::{HookEvent, HookResult};
 use crate::plugin::manifest::{HookScript, PluginLanguage, PluginManifest};
+use crate::plugin::wasm::{self, Contracts};
+use crate::secrets::redact;
+
+/// Command running `script`, picking the interpreter by file extension
+///
+/// Python goes through uv when it's installed, TypeScript through the
+/// plugin's own `tsx` (or `npx tsx`), JavaScript through node and `.sh`
+/// through sh; anything else is executed directly.
+pub fn script_command(plugin_path: &Path, script: &Path) -> Command {
+    let mut command = match script.extension().and_then(|e| e.to_str()).unwrap_or("") {
+        "py" => return python(script),
+        "ts" | "mts" | "cts" => {
+            let tsx = plugin_path.join("node_modules").join(".bin").join("tsx");
+            if tsx.exists() {
+                Command::new(tsx)
+            } else {
+                let mut npx = Command::new("npx");
+                npx.args(["--yes", "tsx"]);
+                npx
+            }
+        }
+        "js" | "mjs" | "cjs" => Command::new("node"),
+        "sh" => Command::new("sh"),
+        _ => return Command::new(script),
+    };
+    command.arg(script);
+    command
+}
+
+/// Whether a hook script runs as its own program, and so needs the executable bit
+pub fn runs_directly(language: &PluginLanguage, script: &Path) -> bool {
+    match language {
+        PluginLanguage::Python | PluginLanguage::Wasm => false,
+        PluginLanguage::Rust => true,
+        PluginLanguage::Mixed | PluginLanguage::Node | PluginLanguage::Shell => !matches!(
+            script.extension().and_then(|e| e.to_str()).unwrap_or(""),
+            "py" | "ts" | "mts" | "cts" | "js" | "mjs" | "cjs" | "sh"
+        ),
+    }
+}
+
+/// Run a Python script, with uv when it's installed
+///
+/// No bytecode is written: `__pycache__/` is part of a plugin's signature.
+fn python(script: &Path) -> Command {
+    let mut command = if which::which("uv").is_ok() {
+        let mut uv = Command::new("uv");
+        uv.args(["run", "python"]);
+        uv
+    } else {
+        Command::new("python3")
+    };
+    command.env("PYTHONDONTWRITEBYTECODE", "1").arg(script);
+    command
+}
 
 /// Result of executing a plugin hook
 #[derive(Debug)]
@@ -18,11 +79,35 @@ pub struct PluginHookResult {
     pub exit_code: i32,
     pub stdout: String,
     pub stderr: String,
+    /// Context a wasm hook returned with its decision
+    pub context: Option<String>,
+    /// Set when the hook was killed for running longer than this
+    pub timed_out: Option<Duration>,
 }
 
 impl PluginHookResult {
+    /// This result with `secrets` masked in its output
+    fn redacted(mut self, secrets: &[String]) -> Self {
+        if !secrets.is_empty() {
+            self.stdout = redact(&self.stdout, secrets);
+            self.stderr = redact(&self.stderr, secrets);
+            self.context = self.context.map(|context| redact(&context, secrets));
+        }
+        self
+    }
+
     /// Convert to HookResult based on exit code
     pub fn to_hook_result(&self) -> HookResult {
+        if let Some(timeout) = self.timed_out {
+            return HookResult::Error {
+                message: format!(
+                    "Plugin '{}' ({}) timed out after {}s",
+                    self.plugin_name,
+                    self.script,
+                    timeout.as_secs_f32()
+                ),
+            };
+        }
         match self.exit_code {
             0 => HookResult::Allow,
             2 => HookResult::Block {
@@ -40,6 +125,141 @@ impl PluginHookResult {
             },
         }
     }
+
+    /// Context the hook asked pais to add to the conversation
+    ///
+    /// Hooks opt in by printing a JSON object with a non-empty
+    /// `inject_context` string, e.g. `{"inject_context": "..."}`; wasm
+    /// hooks return it instead.
+    pub fn inject_context(&self) -> Option<String> {
+        if let Some(text) = self.context.as_deref().map(str::trim) {
+            return (!text.is_empty()).then(|| text.to_string());
+        }
+        let value: serde_json::Value = serde_json::from_str(self.stdout.trim()).ok()?;
+        let text = value.get("inject_context")?.as_str()?.trim();
+        (!text.is_empty()).then(|| text.to_string())
+    }
+}
+
+/// How long to wait for a killed script's output before giving up on it
+const KILL_GRACE: Duration = Duration::from_millis(200);
+
+/// What a plugin's hooks run with besides the event payload
+#[derive(Debug, Clone)]
+pub struct HookEnv {
+    /// Plugins wasm hooks can reach through `call-contract`
+    pub contracts: Contracts,
+    /// Timeout for scripts whose manifest doesn't set one
+    pub default_timeout: Duration,
+    /// Extra environment: the plugin's configuration (see [`super::config`])
+    pub vars: Vec<(String, String)>,
+    /// Values masked in the script's output
+    pub secrets: Vec<String>,
+}
+
+impl Default for HookEnv {
+    fn default() -> Self {
+        Self {
+            contracts: Contracts::default(),
+            default_timeout: Duration::from_secs(30),
+            vars: Vec::new(),
+            secrets: Vec::new(),
+        }
+    }
+}
+
+impl HookEnv {
+    /// The manifest's `timeout` for `hook_script`, else the default
+    pub fn timeout_for(&self, hook_script: &HookScript) -> Duration {
+        hook_script.timeout.map_or(self.default_timeout, Duration::from_secs)
+    }
+}
+
+/// Whether `hook_script` should run for the tool call in `payload`
+///
+/// `matcher` is a regex that must match the whole tool name, `paths` are
+/// globs on the file the tool touches (relative to `cwd` when it's inside
+/// it; a glob without `/` matches the file name) and `command` is a regex
+/// searched for in the command. Events without a tool run every hook, as
+/// Claude Code ignores matchers there. A pattern that doesn't compile never
+/// matches.
+pub fn applies(hook_script: &HookScript, payload: &Value) -> bool {
+    let Some(tool_name) = payload.get("tool_name").and_then(Value::as_str) else {
+        return true;
+    };
+    let input = payload.get("tool_input");
+    let field = |key: &str| input.and_then(|i| i.get(key)).and_then(Value::as_str);
+
+    if let Some(matcher) = hook_script.matcher.as_deref().filter(|m| !m.is_empty() && *m != "*") {
+        let Some(re) = compile(hook_script, &format!("^(?:{})$", matcher)) else {
+            return false;
+        };
+        if !re.is_match(tool_name) {
+            return false;
+        }
+    }
+
+    if !hook_script.paths.is_empty() {
+        let Some(path) = ["file_path", "path", "notebook_path"].into_iter().find_map(field) else {
+            return false;
+        };
+        let path = Path::new(path);
+        let rel = match payload.get("cwd").and_then(Value::as_str) {
+            Some(cwd) if !cwd.is_empty() => path.strip_prefix(cwd).unwrap_or(path),
+            _ => path,
+        };
+        let name = rel.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        let rel = rel.to_string_lossy();
+        let hit = hook_script.paths.iter().any(|glob| {
+            let subject = if glob.contains('/') { rel.as_ref() } else { name };
+            compile(hook_script, &glob_regex(glob)).is_some_and(|re| re.is_match(subject))
+        });
+        if !hit {
+            return false;
+        }
+    }
+
+    if let Some(pattern) = &hook_script.command {
+        let Some(command) = field("command") else {
+            return false;
+        };
+        if !compile(hook_script, pattern).is_some_and(|re| re.is_match(command)) {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Compile a matcher pattern, warning when it's invalid
+fn compile(hook_script: &HookScript, pattern: &str) -> Option<Regex> {
+    Regex::new(pattern)
+        .inspect_err(|e| log::warn!("Invalid matcher for hook {}: {}", hook_script.script, e))
+        .ok()
+}
+
+/// Anchored regex for a path glob: `**` spans directories, `*` and `?` don't
+fn glob_regex(glob: &str) -> String {
+    let mut re = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    re.push_str("(?:.*/)?");
+                } else {
+                    re.push_str(".*");
+                }
+            }
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+    re
 }
 
 /// Execute a plugin hook script
@@ -49,8 +269,10 @@ pub fn execute_hook(
     hook_script: &HookScript,
     event: HookEvent,
     payload: &serde_json::Value,
+    env: &HookEnv,
 ) -> Result<PluginHookResult> {
     let script_path = plugin_path.join(&hook_script.script);
+    let timeout = env.timeout_for(hook_script);
 
     if !script_path.exists() {
         return Ok(PluginHookResult {
@@ -59,51 +281,23 @@ pub fn execute_hook(
             exit_code: 1,
             stdout: String::new(),
             stderr: format!("Script not found: {}", script_path.display()),
+            context: None,
+            timed_out: None,
         });
     }
 
-    // Check matcher if specified
-    if let Some(ref matcher) = hook_script.matcher {
-        let tool_name = payload.get("tool_name").and_then(|v| v.as_str()).unwrap_or("");
-        if tool_name != matcher {
-            // Matcher doesn't match, skip this hook
-            return Ok(PluginHookResult {
-                plugin_name: manifest.plugin.name.clone(),
-                script: hook_script.script.clone(),
-                exit_code: 0,
-                stdout: String::new(),
-                stderr: String::new(),
-            });
-        }
-    }
-
     // Determine how to run the script based on plugin language
-    let (program, args) = match manifest.plugin.language {
-        PluginLanguage::Python => {
-            // Try uv first, fall back to python
-            if which::which("uv").is_ok() {
-                ("uv", vec!["run", "python", script_path.to_str().unwrap_or("")])
-            } else {
-                ("python3", vec![script_path.to_str().unwrap_or("")])
-            }
+    let mut command = match manifest.plugin.language {
+        PluginLanguage::Python => python(&script_path),
+        // Rust plugins should be compiled binaries
+        PluginLanguage::Rust => Command::new(&script_path),
+        PluginLanguage::Mixed | PluginLanguage::Node | PluginLanguage::Shell => {
+            script_command(plugin_path, &script_path)
         }
-        PluginLanguage::Rust => {
-            // Rust plugins should be compiled binaries
-            (script_path.to_str().unwrap_or(""), vec![])
-        }
-        PluginLanguage::Mixed => {
-            // Determine by file extension
-            let ext = script_path.extension().and_then(|e| e.to_str()).unwrap_or("");
-            match ext {
-                "py" => {
-                    if which::which("uv").is_ok() {
-                        ("uv", vec!["run", "python", script_path.to_str().unwrap_or("")])
-                    } else {
-                        ("python3", vec![script_path.to_str().unwrap_or("")])
-                    }
-                }
-                _ => (script_path.to_str().unwrap_or(""), vec![]),
-            }
+        PluginLanguage::Wasm => {
+            // Components run in-process
+            return wasm::run_hook(plugin_path, manifest, hook_script, event, payload, env)
+                .map(|result| result.redacted(&env.secrets));
         }
     };
 
@@ -111,38 +305,86 @@ pub fn execute_hook(
     let payload_json = serde_json::to_string(payload).context("Failed to serialize payload")?;
 
     // Spawn process
-    let mut child = Command::new(program)
-        .args(&args)
+    let mut child = command
         .current_dir(plugin_path)
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .env("PAIS_EVENT", event.to_string())
         .env("PAIS_PLUGIN", &manifest.plugin.name)
+        .envs(env.vars.iter().map(|(k, v)| (k, v)))
         .spawn()
         .with_context(|| format!("Failed to spawn plugin script: {}", script_path.display()))?;
 
-    // Write payload to stdin
+    // Write payload to stdin on a thread, so a script that never reads it still times out
     if let Some(mut stdin) = child.stdin.take() {
-        stdin
-            .write_all(payload_json.as_bytes())
-            .context("Failed to write payload to plugin stdin")?;
+        std::thread::spawn(move || {
+            if let Err(e) = stdin.write_all(payload_json.as_bytes()) {
+                log::debug!("Failed to write payload to plugin stdin: {}", e);
+            }
+        });
     }
 
-    // Wait for completion with timeout
-    let output = child.wait_with_output().context("Failed to wait for plugin script")?;
+    // Drain output on threads so a chatty script can't fill the pipes and stall
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
 
-    let exit_code = output.status.code().unwrap_or(1);
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    // Wait for completion, polling more slowly the longer the script runs
+    let deadline = Instant::now() + timeout;
+    let mut poll = Duration::from_millis(1);
+    let status = loop {
+        if let Some(status) = child.try_wait().context("Failed to wait for plugin script")? {
+            break Some(status);
+        }
+        if Instant::now() >= deadline {
+            log::warn!(
+                "Killing plugin '{}' ({}) after {}s",
+                manifest.plugin.name,
+                hook_script.script,
+                timeout.as_secs_f32()
+            );
+            let _ = child.kill();
+            let _ = child.wait();
+            break None;
+        }
+        std::thread::sleep(poll.min(deadline.saturating_duration_since(Instant::now())));
+        poll = (poll * 2).min(Duration::from_millis(20));
+    };
 
+    // Children the script started may hold the pipes open after a kill
+    let grace = if status.is_some() { None } else { Some(KILL_GRACE) };
     Ok(PluginHookResult {
         plugin_name: manifest.plugin.name.clone(),
         script: hook_script.script.clone(),
-        exit_code,
-        stdout,
-        stderr,
-    })
+        exit_code: status.and_then(|s| s.code()).unwrap_or(1),
+        stdout: collect(&stdout, grace),
+        stderr: collect(&stderr, grace),
+        context: None,
+        timed_out: status.is_none().then_some(timeout),
+    }
+    .redacted(&env.secrets))
+}
+
+/// Read a pipe to the end on a thread
+fn drain(pipe: Option<impl Read + Send + 'static>) -> Receiver<Vec<u8>> {
+    let (tx, rx) = mpsc::channel();
+    if let Some(mut pipe) = pipe {
+        std::thread::spawn(move || {
+            let mut bytes = Vec::new();
+            let _ = pipe.read_to_end(&mut bytes);
+            let _ = tx.send(bytes);
+        });
+    }
+    rx
+}
+
+/// What a drained pipe produced, waiting at most `grace` when set
+fn collect(rx: &Receiver<Vec<u8>>, grace: Option<Duration>) -> String {
+    let bytes = match grace {
+        Some(grace) => rx.recv_timeout(grace).ok(),
+        None => rx.recv().ok(),
+    };
+    String::from_utf8_lossy(&bytes.unwrap_or_default()).into_owned()
 }
 
 /// Execute all hooks for a plugin on a given event
@@ -151,13 +393,15 @@ pub fn execute_plugin_hooks(
     manifest: &PluginManifest,
     event: HookEvent,
     payload: &serde_json::Value,
+    env: &HookEnv,
 ) -> Vec<PluginHookResult> {
     let scripts = manifest.hooks.scripts_for_event(&event.to_string());
 
     scripts
         .iter()
+        .filter(|script| applies(script, payload))
         .filter_map(
-            |script| match execute_hook(plugin_path, manifest, script, event, payload) {
+            |script| match execute_hook(plugin_path, manifest, script, event, payload, env) {
                 Ok(result) => Some(result),
                 Err(e) => {
                     log::error!("Failed to execute plugin hook: {}", e);
@@ -225,6 +469,7 @@ sys.exit(0)  # Allow
             &manifest.hooks.pre_tool_use[0],
             HookEvent::PreToolUse,
             &payload,
+            &HookEnv::default(),
         )
         .unwrap();
 
@@ -251,6 +496,7 @@ sys.exit(2)  # Block
             &manifest.hooks.pre_tool_use[0],
             HookEvent::PreToolUse,
             &payload,
+            &HookEnv::default(),
         )
         .unwrap();
 
@@ -258,6 +504,66 @@ sys.exit(2)  # Block
         assert!(matches!(result.to_hook_result(), HookResult::Block { .. }));
     }
 
+    #[test]
+    fn test_execute_hook_timeout() {
+        let temp = tempdir().unwrap();
+        let manifest = create_test_plugin(
+            temp.path(),
+            r#"#!/usr/bin/env python3
+import time
+time.sleep(30)
+"#,
+        );
+
+        let started = Instant::now();
+        let result = execute_hook(
+            temp.path(),
+            &manifest,
+            &manifest.hooks.pre_tool_use[0],
+            HookEvent::PreToolUse,
+            &serde_json::json!({"tool_name": "Bash"}),
+            &HookEnv {
+                default_timeout: Duration::from_millis(300),
+                ..HookEnv::default()
+            },
+        )
+        .unwrap();
+
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(result.timed_out, Some(Duration::from_millis(300)));
+        assert!(
+            matches!(result.to_hook_result(), HookResult::Error { message } if message.contains("timed out after 0.3s"))
+        );
+    }
+
+    #[test]
+    fn test_execute_shell_hook() {
+        let temp = tempdir().unwrap();
+        fs::create_dir_all(temp.path().join("hooks")).unwrap();
+        // Not executable: the .sh extension picks the interpreter
+        fs::write(
+            temp.path().join("hooks").join("guard.sh"),
+            "read -r payload\necho \"blocked $PAIS_EVENT\" >&2\nexit 2\n",
+        )
+        .unwrap();
+        let manifest = PluginManifest::from_str(
+            "plugin:\n  name: guard\n  version: 0.1.0\n  description: test\n  language: shell\nhooks:\n  PreToolUse:\n    - script: hooks/guard.sh\n",
+        )
+        .unwrap();
+
+        let result = execute_hook(
+            temp.path(),
+            &manifest,
+            &manifest.hooks.pre_tool_use[0],
+            HookEvent::PreToolUse,
+            &serde_json::json!({"tool_name": "Bash"}),
+            &HookEnv::default(),
+        )
+        .unwrap();
+        assert_eq!(result.exit_code, 2);
+        assert_eq!(result.stderr.trim(), "blocked PreToolUse");
+    }
+
     #[test]
     fn test_hook_result_conversion() {
         let allow = PluginHookResult {
@@ -266,6 +572,8 @@ sys.exit(2)  # Block
             exit_code: 0,
             stdout: String::new(),
             stderr: String::new(),
+            context: None,
+            timed_out: None,
         };
         assert!(matches!(allow.to_hook_result(), HookResult::Allow));
 
@@ -275,6 +583,8 @@ sys.exit(2)  # Block
             exit_code: 2,
             stdout: String::new(),
             stderr: "Blocked!".to_string(),
+            context: None,
+            timed_out: None,
         };
         assert!(matches!(block.to_hook_result(), HookResult::Block { .. }));
 
@@ -284,7 +594,85 @@ sys.exit(2)  # Block
             exit_code: 1,
             stdout: String::new(),
             stderr: "Error".to_string(),
+            context: None,
+            timed_out: None,
         };
         assert!(matches!(error.to_hook_result(), HookResult::Error { .. }));
     }
+
+    #[test]
+    fn test_inject_context() {
+        let temp = tempdir().unwrap();
+        let manifest = create_test_plugin(
+            temp.path(),
+            r#"#!/usr/bin/env python3
+import json
+print(json.dumps({"inject_context": "  Deploys are frozen until Monday.\n"}))
+"#,
+        );
+
+        let payload = serde_json::json!({"tool_name": "Bash"});
+        let result = execute_hook(
+            temp.path(),
+            &manifest,
+            &manifest.hooks.pre_tool_use[0],
+            HookEvent::PreToolUse,
+            &payload,
+            &HookEnv::default(),
+        )
+        .unwrap();
+
+        assert!(matches!(result.to_hook_result(), HookResult::Allow));
+        assert_eq!(
+            result.inject_context().as_deref(),
+            Some("Deploys are frozen until Monday.")
+        );
+
+        let plain = |stdout: &str| PluginHookResult {
+            plugin_name: "test".to_string(),
+            script: "test.py".to_string(),
+            exit_code: 0,
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+            context: None,
+            timed_out: None,
+        };
+        assert_eq!(plain("just some output\n").inject_context(), None);
+        assert_eq!(plain(r#"{"inject_context": "   "}"#).inject_context(), None);
+        assert_eq!(plain(r#"{"inject_context": 42}"#).inject_context(), None);
+    }
+
+    #[test]
+    fn test_applies() {
+        let hook = |yaml: &str| -> HookScript { serde_yaml::from_str(yaml).unwrap() };
+        let call = |tool: &str, input: serde_json::Value| serde_json::json!({"cwd": "/work", "tool_name": tool, "tool_input": input});
+        let bash = call("Bash", serde_json::json!({"command": "git push --force"}));
+        let edit = call("Edit", serde_json::json!({"file_path": "/work/src/plugin/mod.rs"}));
+
+        let any = hook("script: a.py");
+        assert!(applies(&any, &bash) && applies(&any, &edit));
+        assert!(applies(&hook("{script: a.py, matcher: '*'}"), &edit));
+
+        let writes = hook("{script: a.py, matcher: Edit|Write}");
+        assert!(applies(&writes, &edit) && !applies(&writes, &bash));
+        // The whole name must match
+        assert!(!applies(&hook("{script: a.py, matcher: Edi}"), &edit));
+
+        assert!(applies(&hook("{script: a.py, paths: ['src/**/*.rs']}"), &edit));
+        assert!(applies(&hook("{script: a.py, paths: ['**/*.rs']}"), &edit));
+        assert!(applies(&hook("{script: a.py, paths: ['*.md', 'mod.rs']}"), &edit));
+        assert!(!applies(&hook("{script: a.py, paths: ['src/*.rs']}"), &edit));
+        assert!(!applies(&hook("{script: a.py, paths: ['*.rs']}"), &bash));
+
+        let force = hook("{script: a.py, matcher: Bash, command: 'push.*--force'}");
+        assert!(applies(&force, &bash));
+        assert!(!applies(
+            &force,
+            &call("Bash", serde_json::json!({"command": "git push"}))
+        ));
+
+        // Invalid patterns never match; non-tool events ignore matchers
+        assert!(!applies(&hook("{script: a.py, command: '('}"), &bash));
+        assert!(applies(&writes, &serde_json::json!({"hook_event_name": "Stop"})));
+    }
 }
@@ -0,0 +1,342 @@
+//! Runtime health of installed plugins
+//!
+//! What `pais plugin list` reports beyond the manifest: whether the entry
+//! point and hook scripts exist, whether a plugin that needs building has
+//! been built, and whether the program that runs it is on PATH.
+//! [`diagnose`] goes further for `pais doctor`, pairing each problem with
+//! how to fix it.
+
+use serde::Serialize;
+use std::path::Path;
+
+use super::describe::{self, rust_binaries};
+use super::executor::runs_directly;
+use super::manifest::{BuildType, PluginLanguage};
+use super::{Plugin, build, wasm};
+
+/// The program a plugin runs with
+#[derive(Debug, Clone, Serialize)]
+pub struct Runtime {
+    pub program: String,
+    pub found: bool,
+}
+
+/// Health of one plugin
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Health {
+    pub entry_point: bool,
+    /// Unset when the plugin has nothing that must be built
+    pub built: Option<bool>,
+    /// Unset for compiled plugins, which run on their own
+    pub runtime: Option<Runtime>,
+    /// Hook events the plugin subscribes to
+    pub hooks: Vec<&'static str>,
+    /// Hook scripts the manifest names that don't exist
+    pub missing_scripts: Vec<String>,
+}
+
+impl Health {
+    pub fn check(plugin: &Plugin) -> Self {
+        let path = &plugin.path;
+        let main_py = path.join("src").join("main.py");
+        let cargo = path.join("Cargo.toml");
+        let language = &plugin.manifest.plugin.language;
+        let entry_point = match language {
+            PluginLanguage::Python => main_py.exists(),
+            PluginLanguage::Rust => cargo.exists(),
+            PluginLanguage::Mixed => main_py.exists() || cargo.exists(),
+            PluginLanguage::Node => describe::node_entry(path).is_some(),
+            PluginLanguage::Shell => describe::shell_entry(path).exists(),
+            PluginLanguage::Wasm => wasm::component(path).is_some(),
+        };
+
+        let built = build::required(plugin).then(|| {
+            let artifacts = match build::plan(plugin) {
+                Some(BuildType::Cargo) => rust_binaries(path).iter().any(|p| p.exists()),
+                Some(BuildType::Npm) => path.join("node_modules").is_dir(),
+                _ => true,
+            };
+            artifacts && build::is_built(plugin)
+        });
+
+        let on_path = |program: &str| Runtime {
+            program: program.to_string(),
+            found: which::which(program).is_ok(),
+        };
+        let runtime = match language {
+            PluginLanguage::Python | PluginLanguage::Mixed => {
+                let venv = path.join(".venv").join("bin").join("python");
+                Some(if venv.exists() {
+                    Runtime {
+                        program: ".venv/bin/python".to_string(),
+                        found: true,
+                    }
+                } else {
+                    on_path("python3")
+                })
+            }
+            PluginLanguage::Node => Some(on_path("node")),
+            PluginLanguage::Shell => Some(on_path("sh")),
+            PluginLanguage::Wasm => Some(Runtime {
+                program: "wasm runtime".to_string(),
+                found: wasm::ENABLED,
+            }),
+            PluginLanguage::Rust => None,
+        };
+
+        let hooks = plugin.manifest.hooks.subscribed();
+        let mut missing_scripts: Vec<String> = hooks
+            .iter()
+            .flat_map(|event| plugin.manifest.hooks.scripts_for_event(event))
+            .filter(|script| !matches!(language, PluginLanguage::Wasm) && !path.join(&script.script).exists())
+            .map(|script| script.script.clone())
+            .collect();
+        missing_scripts.sort();
+        missing_scripts.dedup();
+
+        Self {
+            entry_point,
+            built,
+            runtime,
+            hooks,
+            missing_scripts,
+        }
+    }
+
+    /// What's wrong, in a few words each; empty when the plugin is healthy
+    ///
+    /// A missing entry point only counts for plugins without hooks, since
+    /// hook-only plugins don't need one.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if !self.entry_point && self.hooks.is_empty() {
+            problems.push("no entry point".to_string());
+        }
+        if self.built == Some(false) {
+            problems.push("not built".to_string());
+        }
+        if let Some(runtime) = self.runtime.as_ref().filter(|r| !r.found) {
+            problems.push(format!("{} not found", runtime.program));
+        }
+        for script in &self.missing_scripts {
+            problems.push(format!("missing {}", script));
+        }
+        problems
+    }
+}
+
+/// A problem `pais doctor` found with a plugin, and what to do about it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnosis {
+    pub problem: String,
+    pub fix: String,
+}
+
+/// Check what a plugin needs at runtime: the interpreters and build tools
+/// for its language and scripts, the system tools its manifest declares
+/// under `pais.tools`, and the executable bit on hook scripts that run as
+/// programs of their own
+pub fn diagnose(plugin: &Plugin) -> Vec<Diagnosis> {
+    let path = &plugin.path;
+    let name = &plugin.manifest.plugin.name;
+    let language = &plugin.manifest.plugin.language;
+    let health = Health::check(plugin);
+    let mut found = Vec::new();
+
+    // Each need is a list of programs, any one of which will do
+    let mut needs: Vec<(&[&str], &str)> = Vec::new();
+    let mut need = |programs: Option<&'static [&'static str]>, purpose: &'static str| {
+        if let Some(programs) = programs.filter(|p| !needs.iter().any(|(n, _)| n == p)) {
+            needs.push((programs, purpose));
+        }
+    };
+    let python = path.join(".venv").join("bin").join("python").exists();
+    let tsx = path.join("node_modules").join(".bin").join("tsx").exists();
+    let runner = |script: &Path| -> Option<&'static [&'static str]> {
+        match script.extension().and_then(|e| e.to_str()).unwrap_or("") {
+            "py" if !python => Some(&["uv", "python3"]),
+            "js" | "mjs" | "cjs" => Some(&["node"]),
+            "ts" | "mts" | "cts" if !tsx => Some(&["npx"]),
+            "sh" => Some(&["sh"]),
+            _ => None,
+        }
+    };
+
+    match language {
+        PluginLanguage::Python => need(runner(&path.join("src").join("main.py")), "to run it"),
+        PluginLanguage::Mixed if path.join("src").join("main.py").exists() => {
+            need(runner(&path.join("src").join("main.py")), "to run it")
+        }
+        PluginLanguage::Node => {
+            need(Some(&["node"]), "to run it");
+            if let Some(entry) = describe::node_entry(path) {
+                need(runner(&entry), "to run its TypeScript entry point");
+            }
+        }
+        PluginLanguage::Shell => need(Some(&["sh"]), "to run it"),
+        _ => {}
+    }
+    let scripts: Vec<String> = health
+        .hooks
+        .iter()
+        .flat_map(|event| plugin.manifest.hooks.scripts_for_event(event))
+        .map(|script| script.script.clone())
+        .collect();
+    if matches!(
+        language,
+        PluginLanguage::Mixed | PluginLanguage::Node | PluginLanguage::Shell
+    ) {
+        for script in &scripts {
+            need(runner(Path::new(script)), "to run its hooks");
+        }
+    }
+    if health.built == Some(false) {
+        match build::plan(plugin) {
+            Some(BuildType::Cargo) => need(Some(&["cargo"]), "to build it"),
+            Some(BuildType::Npm) => need(Some(&["npm"]), "to install its dependencies"),
+            _ => {}
+        }
+    }
+
+    for (programs, purpose) in needs {
+        if !programs.iter().any(|p| which::which(p).is_ok()) {
+            found.push(Diagnosis {
+                problem: format!("{} not found ({})", programs.join(" or "), purpose),
+                fix: install_hint(programs[0]),
+            });
+        }
+    }
+    for tool in &plugin.manifest.pais.tools {
+        if which::which(tool).is_err() {
+            found.push(Diagnosis {
+                problem: format!("{} not found (declared in plugin.yaml)", tool),
+                fix: install_hint(tool),
+            });
+        }
+    }
+    if matches!(language, PluginLanguage::Wasm) && !wasm::ENABLED {
+        found.push(Diagnosis {
+            problem: "pais was built without WebAssembly support".to_string(),
+            fix: "Rebuild pais with: cargo install --path . --features wasm".to_string(),
+        });
+    }
+
+    if health.built == Some(false) {
+        found.push(Diagnosis {
+            problem: "not built".to_string(),
+            fix: format!("Run: pais plugin build {}", name),
+        });
+    }
+    for script in &health.missing_scripts {
+        found.push(Diagnosis {
+            problem: format!("hook script {} is missing", script),
+            fix: if matches!(language, PluginLanguage::Rust) {
+                format!("Run: pais plugin build {}", name)
+            } else {
+                "Create it or remove it from plugin.yaml".to_string()
+            },
+        });
+    }
+    let mut scripts = scripts;
+    scripts.sort();
+    scripts.dedup();
+    for script in scripts {
+        let file = path.join(&script);
+        if file.exists() && runs_directly(language, &file) && !is_executable(&file) {
+            found.push(Diagnosis {
+                problem: format!("hook script {} is not executable", script),
+                fix: format!("Run: chmod +x {}", file.display()),
+            });
+        }
+    }
+    found
+}
+
+fn install_hint(program: &str) -> String {
+    match program {
+        "uv" | "python3" => "Install uv (https://docs.astral.sh/uv/) or Python 3".to_string(),
+        "node" | "npm" | "npx" => "Install Node.js (https://nodejs.org)".to_string(),
+        "cargo" => "Install Rust (https://rustup.rs)".to_string(),
+        other => format!("Install {} and make sure it's on PATH", other),
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .map(|m| m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::loader::load_plugin;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_health_reports_problems() {
+        let temp = tempdir().unwrap();
+        let path = temp.path().join("guard");
+        fs::create_dir_all(path.join("src")).unwrap();
+        fs::write(
+            path.join("plugin.yaml"),
+            "plugin:\n  name: guard\n  version: 0.1.0\n  description: test\n  language: shell\nhooks:\n  Stop:\n    - script: hooks/stop.sh\n  PreToolUse:\n    - script: hooks/guard.sh\n",
+        )
+        .unwrap();
+        fs::create_dir_all(path.join("hooks")).unwrap();
+        fs::write(path.join("hooks").join("guard.sh"), "exit 0\n").unwrap();
+
+        let health = Health::check(&load_plugin(&path).unwrap());
+        assert!(!health.entry_point);
+        assert_eq!(health.built, None);
+        assert_eq!(health.hooks, ["PreToolUse", "Stop"]);
+        assert_eq!(health.problems(), ["missing hooks/stop.sh"]);
+
+        fs::write(path.join("hooks").join("stop.sh"), "exit 0\n").unwrap();
+        fs::write(path.join("src").join("main.sh"), "echo hi\n").unwrap();
+        let health = Health::check(&load_plugin(&path).unwrap());
+        assert!(health.entry_point);
+        assert!(health.problems().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_diagnose_tools_and_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempdir().unwrap();
+        let path = temp.path().join("tracker");
+        fs::create_dir_all(path.join("hooks")).unwrap();
+        fs::write(
+            path.join("plugin.yaml"),
+            "plugin:\n  name: tracker\n  version: 0.1.0\n  description: test\n  language: shell\npais:\n  tools: [sh, pais-test-no-such-tool]\nhooks:\n  Stop:\n    - script: hooks/stop\n    - script: hooks/notify.sh\n",
+        )
+        .unwrap();
+        fs::write(path.join("hooks").join("stop"), "#!/bin/sh\nexit 0\n").unwrap();
+        fs::write(path.join("hooks").join("notify.sh"), "exit 0\n").unwrap();
+
+        let problems: Vec<String> = diagnose(&load_plugin(&path).unwrap())
+            .into_iter()
+            .map(|d| d.problem)
+            .collect();
+        assert_eq!(
+            problems,
+            [
+                "pais-test-no-such-tool not found (declared in plugin.yaml)",
+                "hook script hooks/stop is not executable",
+            ]
+        );
+
+        fs::set_permissions(path.join("hooks").join("stop"), fs::Permissions::from_mode(0o755)).unwrap();
+        assert_eq!(diagnose(&load_plugin(&path).unwrap()).len(), 1);
+    }
+}
@@ -0,0 +1,185 @@
+//! The plugin lockfile (`~/.config/pais/plugins.lock`)
+//!
+//! Rewritten after every plugin install and removal, it records the resolved
+//! set: each plugin's version and source (from provenance), the contracts it
+//! provides, and which plugin serves each contract it consumes. Plugins are
+//! listed in load order; ones that don't resolve (or are disabled) come
+//! last with the reason.
+
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::resolve::{ContractRef, Resolution};
+use super::{PluginManager, PluginState};
+use crate::config::Config;
+use crate::provenance::{ArtifactKind, ProvenanceFile};
+
+const HEADER: &str = "# Generated by pais from the installed plugins; don't edit by hand.\n";
+
+/// One locked plugin
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LockedPlugin {
+    pub name: String,
+    pub version: String,
+    /// Source URL or path it was installed from, when recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub provides: Vec<String>,
+    /// Provider serving each consumed alias
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub consumes: BTreeMap<String, String>,
+    /// Why the plugin doesn't load, if it doesn't
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unresolved: Vec<String>,
+    /// Turned off with `pais plugin disable`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+}
+
+/// The resolved plugin set
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Lockfile {
+    #[serde(default)]
+    pub plugins: Vec<LockedPlugin>,
+}
+
+impl Lockfile {
+    /// Location of the lockfile (`~/.config/pais/plugins.lock`)
+    pub fn path() -> PathBuf {
+        Config::pais_dir().join("plugins.lock")
+    }
+
+    /// Lock the plugins `manager` has discovered, as `resolution` wired them
+    pub fn build(manager: &PluginManager, resolution: &Resolution, provenance: &ProvenanceFile) -> Self {
+        let mut names: Vec<&str> = resolution.order.iter().map(String::as_str).collect();
+        let mut unresolved: Vec<&str> = manager
+            .plugins
+            .keys()
+            .map(String::as_str)
+            .filter(|name| !resolution.order.iter().any(|n| n == name))
+            .collect();
+        unresolved.sort();
+        names.extend(unresolved);
+
+        let plugins = names
+            .into_iter()
+            .filter_map(|name| manager.get(name))
+            .map(|plugin| {
+                let name = &plugin.manifest.plugin.name;
+                let recorded = provenance.get(ArtifactKind::Plugin, name);
+                let mut provides: Vec<String> = plugin
+                    .manifest
+                    .provides
+                    .values()
+                    .map(|spec| ContractRef::from(spec).to_string())
+                    .collect();
+                provides.sort();
+                provides.dedup();
+                LockedPlugin {
+                    name: name.clone(),
+                    version: plugin.manifest.plugin.version.clone(),
+                    source: recorded.map(|p| p.source.clone()),
+                    commit: recorded.and_then(|p| p.commit.clone()),
+                    provides,
+                    consumes: resolution.wiring.get(name).cloned().unwrap_or_default(),
+                    unresolved: resolution.problems_for(name).map(|p| p.to_string()).collect(),
+                    disabled: plugin.state == PluginState::Disabled,
+                }
+            })
+            .collect();
+        Self { plugins }
+    }
+
+    /// Load the lockfile, empty when it doesn't exist yet
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_yaml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let yaml = serde_yaml::to_string(self).context("Failed to serialize the plugin lockfile")?;
+        fs::write(path, format!("{}{}", HEADER, yaml)).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Resolve the plugins in `manager` and rewrite the lockfile
+pub fn write(manager: &PluginManager) -> Result<Lockfile> {
+    let provenance = ProvenanceFile::load(&ProvenanceFile::path())?;
+    let lockfile = Lockfile::build(manager, &manager.resolve(), &provenance);
+    lockfile.save(&Lockfile::path())?;
+    Ok(lockfile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provenance::Provenance;
+    use tempfile::tempdir;
+
+    fn install(dir: &Path, name: &str, extra: &str) {
+        let plugin = dir.join(name);
+        fs::create_dir_all(&plugin).unwrap();
+        fs::write(
+            plugin.join("plugin.yaml"),
+            format!(
+                "plugin:\n  name: {}\n  version: 0.2.0\n  description: test\n{}",
+                name, extra
+            ),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_lockfile_build_and_round_trip() {
+        let temp = tempdir().unwrap();
+        let plugins = temp.path().join("plugins");
+        install(
+            &plugins,
+            "researcher",
+            "consumes:\n  patterns: {contract: PatternProvider}\n",
+        );
+        install(
+            &plugins,
+            "fabric",
+            "provides:\n  patterns: {contract: PatternProvider, service: fabric}\n",
+        );
+        install(&plugins, "jira", "consumes:\n  tracker: {contract: IssueTracker}\n");
+        let mut manager = PluginManager::new(plugins);
+        manager.discover().unwrap();
+
+        let mut provenance = ProvenanceFile::default();
+        let mut recorded = Provenance::new("https://example.com/fabric.git");
+        recorded.commit = Some("abc123".to_string());
+        provenance
+            .items
+            .entry(ArtifactKind::Plugin)
+            .or_default()
+            .insert("fabric".to_string(), recorded);
+
+        let lockfile = Lockfile::build(&manager, &manager.resolve(), &provenance);
+        let names: Vec<&str> = lockfile.plugins.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["fabric", "researcher", "jira"]);
+        assert_eq!(lockfile.plugins[0].provides, ["PatternProvider[fabric]"]);
+        assert_eq!(lockfile.plugins[0].commit.as_deref(), Some("abc123"));
+        assert_eq!(lockfile.plugins[1].consumes["patterns"], "fabric");
+        assert_eq!(lockfile.plugins[2].unresolved.len(), 1);
+
+        let path = temp.path().join("plugins.lock");
+        lockfile.save(&path).unwrap();
+        assert!(fs::read_to_string(&path).unwrap().starts_with("# Generated"));
+        assert_eq!(Lockfile::load(&path).unwrap(), lockfile);
+    }
+}
@@ -23,6 +23,14 @@ pub struct PluginManifest {
     #[serde(default)]
     pub config: HashMap<String, ConfigSpec>,
 
+    /// Variables set for the plugin's processes, by where their values come from
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        with = "serde_yaml::with::singleton_map_recursive"
+    )]
+    pub env: HashMap<String, EnvSource>,
+
     #[serde(default)]
     pub hooks: HooksSpec,
 
@@ -31,6 +39,17 @@ pub struct PluginManifest {
 
     #[serde(default)]
     pub verification: VerificationSpec,
+
+    #[serde(default)]
+    pub test: TestSpec,
+}
+
+/// Test specification run by `pais test plugin`
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct TestSpec {
+    /// Command that runs the plugin's test suite (run from the plugin directory)
+    #[serde(default)]
+    pub command: Option<String>,
 }
 
 /// Verification specification for plugin installation
@@ -82,6 +101,11 @@ pub struct VerificationCommand {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct PluginInfo {
     pub name: String,
+
+    /// Organization publishing the plugin; `org/name` then names it anywhere a plugin name is accepted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+
     pub version: String,
     pub description: String,
 
@@ -103,6 +127,24 @@ pub struct PluginInfo {
     pub install_guide: Option<String>,
 }
 
+impl PluginInfo {
+    /// `namespace/name`, or just the name when there's no namespace
+    pub fn qualified_name(&self) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("{}/{}", namespace, self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Whether `name` refers to this plugin, by its name or its qualified name
+    pub fn matches(&self, name: &str) -> bool {
+        match name.split_once('/') {
+            Some((namespace, short)) => self.namespace.as_deref() == Some(namespace) && self.name == short,
+            None => self.name == name,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum PluginLanguage {
@@ -110,12 +152,22 @@ pub enum PluginLanguage {
     Python,
     Rust,
     Mixed,
+    /// JavaScript or TypeScript, with a package.json
+    Node,
+    /// POSIX shell scripts
+    Shell,
+    /// A WebAssembly component run in-process (see [`super::wasm`])
+    Wasm,
 }
 
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct PaisRequirements {
     #[serde(default)]
     pub core_version: Option<String>,
+
+    /// System tools the plugin runs (`jq`, `gh`, ...), checked by `pais doctor`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -155,6 +207,35 @@ pub struct ConfigSpec {
     pub secret: bool,
 }
 
+/// Where an `env:` variable's value comes from
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EnvSource {
+    /// A secret from the environment or `~/.config/pais/.env`, by name
+    Dotenv(String),
+    /// A password in the OS keyring, stored for service `pais` under this account
+    Keyring(String),
+    /// A fixed value, written in the manifest
+    Value(String),
+}
+
+impl EnvSource {
+    /// Whether the value is a credential to keep out of output
+    pub fn is_secret(&self) -> bool {
+        !matches!(self, Self::Value(_))
+    }
+}
+
+impl std::fmt::Display for EnvSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Dotenv(name) => write!(f, ".env {}", name),
+            Self::Keyring(account) => write!(f, "keyring {}", account),
+            Self::Value(_) => f.write_str("manifest"),
+        }
+    }
+}
+
 /// Hook configuration - maps event types to scripts
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct HooksSpec {
@@ -189,17 +270,21 @@ pub struct HookScript {
     /// Path to the script (relative to plugin directory)
     pub script: String,
 
-    /// Optional matcher (e.g., "Bash" to only run on Bash tool)
+    /// Tool names to run for, as a regex (`Bash`, `Edit|Write`); all tools when unset or `*`
     #[serde(default)]
     pub matcher: Option<String>,
 
-    /// Optional timeout in seconds (default: 30)
-    #[serde(default = "default_timeout")]
-    pub timeout: u64,
-}
+    /// Globs on the file the tool touches (`src/**/*.rs`, `*.md`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+
+    /// Regex searched for in a Bash tool's command
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
 
-fn default_timeout() -> u64 {
-    30
+    /// Timeout in seconds; defaults to `plugins.hook-timeout-secs` in pais.yaml
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
 }
 
 impl HooksSpec {
@@ -213,6 +298,21 @@ impl HooksSpec {
             || !self.subagent_stop.is_empty()
     }
 
+    /// Events with at least one script, in a fixed order
+    pub fn subscribed(&self) -> Vec<&'static str> {
+        [
+            "SessionStart",
+            "PreToolUse",
+            "PostToolUse",
+            "Stop",
+            "SubagentStop",
+            "SessionEnd",
+        ]
+        .into_iter()
+        .filter(|event| !self.scripts_for_event(event).is_empty())
+        .collect()
+    }
+
     /// Get scripts for a given event type
     pub fn scripts_for_event(&self, event: &str) -> &[HookScript] {
         match event {
@@ -229,42 +329,108 @@ impl HooksSpec {
 
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct BuildSpec {
-    #[serde(default)]
-    pub r#type: BuildType,
+    /// Unset means the language's usual build (see [`PluginManifest::build_type`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<BuildType>,
 
     pub requirements: Option<String>,
     pub install_command: Option<String>,
     pub build_command: Option<String>,
 }
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum BuildType {
     #[default]
     Uv,
     Cargo,
+    Npm,
+    /// Runs `install-command`, then `build-command`
     Custom,
+    /// Nothing to build (shell plugins)
+    #[serde(rename = "none")]
+    Nothing,
 }
 
 impl PluginManifest {
+    /// The declared build type, or the language's usual one: cargo for Rust,
+    /// npm for Node, nothing for shell and wasm, uv otherwise
+    pub fn build_type(&self) -> BuildType {
+        if let Some(build_type) = self.build.r#type {
+            return build_type;
+        }
+        match self.plugin.language {
+            PluginLanguage::Rust => BuildType::Cargo,
+            PluginLanguage::Node => BuildType::Npm,
+            PluginLanguage::Shell | PluginLanguage::Wasm => BuildType::Nothing,
+            PluginLanguage::Python | PluginLanguage::Mixed => BuildType::Uv,
+        }
+    }
+
     /// Load a manifest from a YAML file
     pub fn load<P: AsRef<Path>>(path: P) -> eyre::Result<Self> {
         let content = std::fs::read_to_string(&path)?;
-        let manifest: Self = serde_yaml::from_str(&content)?;
-        Ok(manifest)
+        Self::from_str(&content)
     }
 
     /// Parse a manifest from YAML string
     pub fn from_str(content: &str) -> eyre::Result<Self> {
         let manifest: Self = serde_yaml::from_str(content)?;
+        manifest.check_names()?;
         Ok(manifest)
     }
+
+    /// Names and namespaces are path-like segments; the namespace goes in its own field
+    fn check_names(&self) -> eyre::Result<()> {
+        let segment = |s: &str| {
+            !s.is_empty()
+                && s.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+                && !s.starts_with('.')
+        };
+        let plugin = &self.plugin;
+        if let Some((namespace, _)) = plugin.name.split_once('/') {
+            eyre::bail!(
+                "Invalid plugin name '{}': set `namespace: {}` and leave the name unqualified",
+                plugin.name,
+                namespace
+            );
+        }
+        if !segment(&plugin.name) {
+            eyre::bail!(
+                "Invalid plugin name '{}': use letters, digits, '-', '_' and '.'",
+                plugin.name
+            );
+        }
+        if let Some(namespace) = plugin.namespace.as_deref().filter(|n| !segment(n)) {
+            eyre::bail!(
+                "Invalid plugin namespace '{}': use letters, digits, '-', '_' and '.'",
+                namespace
+            );
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_qualified_names() {
+        let manifest =
+            PluginManifest::from_str("plugin:\n  name: guard\n  namespace: acme\n  version: 0.1.0\n  description: x\n")
+                .unwrap();
+        assert_eq!(manifest.plugin.qualified_name(), "acme/guard");
+        assert!(manifest.plugin.matches("guard"));
+        assert!(manifest.plugin.matches("acme/guard"));
+        assert!(!manifest.plugin.matches("contoso/guard"));
+
+        let err =
+            PluginManifest::from_str("plugin:\n  name: acme/guard\n  version: 0.1.0\n  description: x\n").unwrap_err();
+        assert!(err.to_string().contains("set `namespace: acme`"));
+    }
+
     const MINIMAL_MANIFEST: &str = r#"
 plugin:
   name: test-plugin
@@ -334,7 +500,7 @@ build:
         assert_eq!(manifest.hooks.pre_tool_use[0].matcher, Some("Bash".to_string()));
         assert_eq!(manifest.hooks.stop.len(), 1);
         assert!(manifest.hooks.post_tool_use.is_empty());
-        assert!(matches!(manifest.build.r#type, BuildType::Cargo));
+        assert!(matches!(manifest.build.r#type, Some(BuildType::Cargo)));
     }
 
     #[test]
@@ -10,21 +10,93 @@
 #![allow(dead_code)] // Plugin lifecycle states and methods - for full plugin management
 
 use eyre::{Context, Result};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
+pub mod build;
+pub mod config;
+pub mod describe;
+pub mod dev;
+pub mod disabled;
 pub mod executor;
+pub mod health;
 pub mod loader;
+pub mod lockfile;
 pub mod manifest;
+pub mod pack;
 pub mod registry;
+pub mod resolve;
+pub mod signing;
 pub mod verify;
+pub mod wasm;
 
+use crate::config::PluginSettings;
 use crate::hook::{HookEvent, HookResult};
+use disabled::DisabledPlugins;
 use manifest::PluginManifest;
+use resolve::Resolution;
+use signing::Verifier;
+
+/// What the plugin hooks for one event returned
+#[derive(Debug, Default)]
+pub struct HookOutput {
+    pub results: Vec<HookResult>,
+    /// `(plugin, text)` for each hook that returned `inject_context`
+    pub context: Vec<(String, String)>,
+    /// How each plugin that ran hooks fared, in load order
+    pub runs: Vec<HookRun>,
+}
+
+/// One plugin's hooks for one event
+#[derive(Debug, Clone)]
+pub struct HookRun {
+    pub plugin: String,
+    pub outcome: HookOutcome,
+    pub elapsed: Duration,
+}
+
+/// The combined result of a plugin's hooks for one event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HookOutcome {
+    Allow,
+    Block,
+    Error,
+    Timeout,
+}
+
+impl HookOutcome {
+    /// A block beats a timeout, which beats any other error
+    pub fn of(results: &[executor::PluginHookResult]) -> Self {
+        let outcomes: Vec<HookResult> = results.iter().map(|r| r.to_hook_result()).collect();
+        if outcomes.iter().any(|r| matches!(r, HookResult::Block { .. })) {
+            Self::Block
+        } else if results.iter().any(|r| r.timed_out.is_some()) {
+            Self::Timeout
+        } else if outcomes.iter().any(|r| matches!(r, HookResult::Error { .. })) {
+            Self::Error
+        } else {
+            Self::Allow
+        }
+    }
+}
+
+impl std::fmt::Display for HookOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Allow => "allow",
+            Self::Block => "block",
+            Self::Error => "error",
+            Self::Timeout => "timeout",
+        })
+    }
+}
 
 /// A loaded plugin
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Plugin {
     pub manifest: PluginManifest,
     pub path: PathBuf,
@@ -38,12 +110,67 @@ pub enum PluginState {
     Loaded,
     Initialized,
     Failed(String),
+    /// Turned off with `pais plugin disable`
+    Disabled,
+}
+
+/// Plugin directories declaring the same name; only the first (by path) is used
+#[derive(Debug, Clone, Serialize)]
+pub struct Collision {
+    pub name: String,
+    /// Qualified name and directory of each, the one in use first
+    pub plugins: Vec<(String, PathBuf)>,
+}
+
+impl std::fmt::Display for Collision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let plugins: Vec<String> = self
+            .plugins
+            .iter()
+            .map(|(qualified, path)| format!("{} ({})", path.display(), qualified))
+            .collect();
+        write!(
+            f,
+            "Plugin name '{}' is declared by {}",
+            self.name,
+            plugins.join(" and ")
+        )
+    }
+}
+
+/// Group plugins by name, in path order, keeping the names more than one declares
+pub fn collisions(plugins: &[Plugin]) -> Vec<Collision> {
+    let mut sorted: Vec<&Plugin> = plugins.iter().collect();
+    sorted.sort_by(|a, b| a.path.cmp(&b.path));
+    let mut by_name: BTreeMap<&str, Vec<&Plugin>> = BTreeMap::new();
+    for plugin in sorted {
+        by_name.entry(&plugin.manifest.plugin.name).or_default().push(plugin);
+    }
+    by_name
+        .into_iter()
+        .filter(|(_, plugins)| plugins.len() > 1)
+        .map(|(name, plugins)| Collision {
+            name: name.to_string(),
+            plugins: plugins
+                .iter()
+                .map(|p| (p.manifest.plugin.qualified_name(), p.path.clone()))
+                .collect(),
+        })
+        .collect()
 }
 
 /// Plugin manager responsible for all plugin operations
 pub struct PluginManager {
     pub plugins: HashMap<String, Plugin>,
     pub plugins_dir: PathBuf,
+    /// Names more than one plugin directory declares
+    pub collisions: Vec<Collision>,
+    /// Signature checks hooks must pass, if any
+    verifier: Option<Verifier>,
+    /// Timeout for hook scripts whose manifest doesn't set one
+    hook_timeout: Duration,
+    /// `plugins.<name>` settings from pais.yaml
+    pub settings: BTreeMap<String, PluginSettings>,
 }
 
 impl PluginManager {
@@ -51,23 +178,64 @@ impl PluginManager {
         Self {
             plugins: HashMap::new(),
             plugins_dir,
+            collisions: Vec::new(),
+            verifier: None,
+            hook_timeout: Duration::from_secs(30),
+            settings: BTreeMap::new(),
         }
     }
 
-    /// Discover all plugins in the plugins directory
+    /// Configure plugins from their pais.yaml settings
+    pub fn with_settings(mut self, settings: BTreeMap<String, PluginSettings>) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Give hook scripts without their own `timeout` this long
+    pub fn with_hook_timeout(mut self, timeout: Duration) -> Self {
+        self.hook_timeout = timeout;
+        self
+    }
+
+    /// Check plugin signatures before running hooks, per `verifier`'s policy
+    pub fn with_verifier(mut self, verifier: Option<Verifier>) -> Self {
+        self.verifier = verifier;
+        self
+    }
+
+    /// Discover all plugins in the plugins directory, marking disabled ones
     pub fn discover(&mut self) -> Result<usize> {
+        let count = self.discover_all()?;
+        self.apply_disabled(&DisabledPlugins::load());
+        Ok(count)
+    }
+
+    /// Mark the plugins in `disabled` as [`PluginState::Disabled`]
+    pub fn apply_disabled(&mut self, disabled: &DisabledPlugins) {
+        for (name, plugin) in &mut self.plugins {
+            if disabled.is_disabled(name) {
+                plugin.state = PluginState::Disabled;
+            }
+        }
+    }
+
+    fn discover_all(&mut self) -> Result<usize> {
         self.plugins.clear();
+        self.collisions.clear();
 
         if !self.plugins_dir.exists() {
             return Ok(0);
         }
 
-        let mut count = 0;
-
-        for entry in fs::read_dir(&self.plugins_dir).context("Failed to read plugins directory")? {
-            let entry = entry?;
-            let path = entry.path();
+        let mut found = Vec::new();
+        let mut paths: Vec<PathBuf> = fs::read_dir(&self.plugins_dir)
+            .context("Failed to read plugins directory")?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<std::io::Result<_>>()?;
+        // Sorted, so the same directory wins a name collision every time
+        paths.sort();
 
+        for path in paths {
             if !path.is_dir() {
                 continue;
             }
@@ -78,25 +246,27 @@ impl PluginManager {
             }
 
             match PluginManifest::load(&manifest_path) {
-                Ok(manifest) => {
-                    let name = manifest.plugin.name.clone();
-                    self.plugins.insert(
-                        name,
-                        Plugin {
-                            manifest,
-                            path,
-                            state: PluginState::Discovered,
-                        },
-                    );
-                    count += 1;
-                }
+                Ok(manifest) => found.push(Plugin {
+                    manifest,
+                    path,
+                    state: PluginState::Discovered,
+                }),
                 Err(e) => {
                     log::warn!("Failed to load plugin manifest {}: {}", manifest_path.display(), e);
                 }
             }
         }
 
-        Ok(count)
+        self.collisions = collisions(&found);
+        for collision in &self.collisions {
+            log::error!("{}; only the first is used", collision);
+        }
+        for plugin in found {
+            self.plugins
+                .entry(plugin.manifest.plugin.name.clone())
+                .or_insert(plugin);
+        }
+        Ok(self.plugins.len())
     }
 
     /// Load all discovered plugins
@@ -109,50 +279,161 @@ impl PluginManager {
         Ok(())
     }
 
-    /// Get a plugin by name
+    /// Get a plugin by name or qualified name (`namespace/name`)
     pub fn get(&self, name: &str) -> Option<&Plugin> {
-        self.plugins.get(name)
+        match name.split_once('/') {
+            Some((_, short)) => self.plugins.get(short).filter(|p| p.manifest.plugin.matches(name)),
+            None => self.plugins.get(name),
+        }
     }
 
-    /// Get all plugins with hooks for a given event
+    /// Resolve provides/consumes between the discovered plugins that aren't disabled
+    pub fn resolve(&self) -> Resolution {
+        resolve::resolve(
+            self.plugins
+                .values()
+                .filter(|p| p.state != PluginState::Disabled)
+                .map(|p| &p.manifest),
+        )
+    }
+
+    /// Get the plugins with hooks for a given event, providers before their consumers
+    ///
+    /// Plugins whose dependencies don't resolve, or that fail an enforced
+    /// signature check, are skipped with a warning.
     pub fn plugins_for_event(&self, event: HookEvent) -> Vec<&Plugin> {
-        self.plugins
-            .values()
-            .filter(|p| !p.manifest.hooks.scripts_for_event(&event.to_string()).is_empty())
+        let resolution = self.resolve();
+        let event = event.to_string();
+        for problem in &resolution.problems {
+            log::warn!("Plugin not loaded: {}", problem);
+        }
+        resolution
+            .order
+            .iter()
+            .filter_map(|name| self.plugins.get(name))
+            .filter(|p| !p.manifest.hooks.scripts_for_event(&event).is_empty())
+            .filter(|p| {
+                match self
+                    .verifier
+                    .as_ref()
+                    .map(|v| v.check(&p.path, &p.manifest.plugin.name))
+                {
+                    Some(Ok(Some(warning))) => {
+                        log::warn!("{}", warning);
+                        true
+                    }
+                    Some(Err(e)) => {
+                        log::warn!("Skipping hooks: {}", e.message);
+                        false
+                    }
+                    _ => true,
+                }
+            })
+            .filter(|p| {
+                let built = !build::required(p) || build::is_built(p);
+                if !built {
+                    log::warn!(
+                        "Skipping hooks: plugin '{}' isn't built; run `pais plugin build {}`",
+                        p.manifest.plugin.name,
+                        p.manifest.plugin.name
+                    );
+                }
+                built
+            })
             .collect()
     }
 
     /// Execute all plugin hooks for an event
-    pub fn execute_hooks(&self, event: HookEvent, payload: &serde_json::Value) -> Vec<HookResult> {
-        let mut results = Vec::new();
-
-        for plugin in self.plugins_for_event(event) {
-            let hook_results = executor::execute_plugin_hooks(&plugin.path, &plugin.manifest, event, payload);
-
-            for result in hook_results {
-                let hook_result = result.to_hook_result();
-
-                // Log non-trivial results
-                match &hook_result {
-                    HookResult::Block { message } => {
-                        log::warn!("Plugin '{}' blocked: {}", result.plugin_name, message);
+    ///
+    /// Plugins that don't consume each other's contracts run in parallel;
+    /// results come back in load order either way.
+    pub fn execute_hooks(&self, event: HookEvent, payload: &serde_json::Value) -> HookOutput {
+        let mut output = HookOutput::default();
+        let contracts = wasm::Contracts::from_manager(self);
+        let plugins = self.plugins_for_event(event);
+        let names: Vec<&str> = plugins.iter().map(|p| p.manifest.plugin.name.as_str()).collect();
+
+        // A plugin whose config doesn't validate is skipped rather than run half-configured
+        let envs: Vec<Option<executor::HookEnv>> = plugins
+            .iter()
+            .map(
+                |plugin| match config::resolve(&plugin.manifest, self.settings.get(&plugin.manifest.plugin.name)) {
+                    Ok(config) => Some(executor::HookEnv {
+                        contracts: contracts.clone(),
+                        default_timeout: self.hook_timeout,
+                        vars: config.vars(),
+                        secrets: config.secrets(),
+                    }),
+                    Err(e) => {
+                        log::warn!("Skipping hooks: {}", e.message);
+                        None
                     }
-                    HookResult::Error { message } => {
-                        log::error!("Plugin '{}' error: {}", result.plugin_name, message);
+                },
+            )
+            .collect();
+
+        let mut results: Vec<Vec<executor::PluginHookResult>> = plugins.iter().map(|_| Vec::new()).collect();
+        for wave in waves(&names, &self.resolve().wiring) {
+            // A thread per plugin: hooks mostly wait on their scripts, not the CPU
+            std::thread::scope(|scope| {
+                let running: Vec<_> = wave
+                    .into_iter()
+                    .filter_map(|i| {
+                        let plugin = plugins[i];
+                        let env = envs[i].as_ref()?;
+                        let handle = scope.spawn(move || {
+                            let started = Instant::now();
+                            let results =
+                                executor::execute_plugin_hooks(&plugin.path, &plugin.manifest, event, payload, env);
+                            (results, started.elapsed())
+                        });
+                        Some((i, handle))
+                    })
+                    .collect();
+                for (i, handle) in running {
+                    match handle.join() {
+                        Ok((hook_results, elapsed)) => {
+                            output.runs.push(HookRun {
+                                plugin: names[i].to_string(),
+                                outcome: HookOutcome::of(&hook_results),
+                                elapsed,
+                            });
+                            results[i] = hook_results;
+                        }
+                        Err(_) => log::error!("Plugin '{}' hook thread panicked", names[i]),
                     }
-                    HookResult::Allow => {}
                 }
+            });
+        }
+        output
+            .runs
+            .sort_by_key(|run| names.iter().position(|name| *name == run.plugin));
+
+        for result in results.into_iter().flatten() {
+            let hook_result = result.to_hook_result();
 
-                // Print any stdout from the plugin
-                if !result.stdout.is_empty() {
-                    print!("{}", result.stdout);
+            // Log non-trivial results
+            match &hook_result {
+                HookResult::Block { message } => {
+                    log::warn!("Plugin '{}' blocked: {}", result.plugin_name, message);
                 }
+                HookResult::Error { message } => {
+                    log::error!("Plugin '{}' error: {}", result.plugin_name, message);
+                }
+                HookResult::Allow => {}
+            }
 
-                results.push(hook_result);
+            // Context requests are emitted by the dispatcher; print any other stdout
+            if let Some(text) = result.inject_context() {
+                output.context.push((result.plugin_name.clone(), text));
+            } else if !result.stdout.is_empty() {
+                print!("{}", result.stdout);
             }
+
+            output.results.push(hook_result);
         }
 
-        results
+        output
     }
 
     /// List all plugins
@@ -177,6 +458,31 @@ impl PluginManager {
     }
 }
 
+/// Group plugins (given in load order) into waves that can run at once
+///
+/// Each plugin lands in the wave after the last of its providers among
+/// `names`; returns indexes into `names`.
+fn waves(names: &[&str], wiring: &BTreeMap<String, BTreeMap<String, String>>) -> Vec<Vec<usize>> {
+    let mut level: HashMap<&str, usize> = HashMap::new();
+    let mut waves: Vec<Vec<usize>> = Vec::new();
+    for (i, name) in names.iter().enumerate() {
+        let wave = wiring
+            .get(*name)
+            .into_iter()
+            .flat_map(|wired| wired.values())
+            .filter_map(|provider| level.get(provider.as_str()))
+            .map(|l| l + 1)
+            .max()
+            .unwrap_or(0);
+        level.insert(name, wave);
+        if waves.len() <= wave {
+            waves.resize_with(wave + 1, Vec::new);
+        }
+        waves[wave].push(i);
+    }
+    waves
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -198,6 +504,27 @@ plugin:
         fs::write(plugin_dir.join("plugin.yaml"), manifest).unwrap();
     }
 
+    #[test]
+    fn test_hook_waves() {
+        let wired = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(alias, provider)| (alias.to_string(), provider.to_string()))
+                .collect()
+        };
+        let wiring = BTreeMap::from([
+            (
+                "researcher".to_string(),
+                wired(&[("patterns", "fabric"), ("memory", "memory")]),
+            ),
+            ("reviewer".to_string(), wired(&[("research", "researcher")])),
+            ("tagger".to_string(), wired(&[("memory", "memory")])),
+        ]);
+        // `memory` has no hooks for the event, so its consumers don't wait on it
+        let names = ["alone", "fabric", "researcher", "reviewer", "tagger"];
+        assert_eq!(waves(&names, &wiring), [vec![0, 1, 4], vec![2], vec![3]]);
+    }
+
     #[test]
     fn test_discover_plugins() {
         let temp = tempdir().unwrap();
@@ -212,6 +539,35 @@ plugin:
         assert!(manager.has("plugin-b"));
     }
 
+    #[test]
+    fn test_discover_detects_name_collisions() {
+        let temp = tempdir().unwrap();
+        for (dir, namespace) in [("guard", "contoso"), ("acme-guard", "acme")] {
+            fs::create_dir_all(temp.path().join(dir)).unwrap();
+            fs::write(
+                temp.path().join(dir).join("plugin.yaml"),
+                format!(
+                    "plugin:\n  name: guard\n  namespace: {}\n  version: 0.1.0\n  description: test\n",
+                    namespace
+                ),
+            )
+            .unwrap();
+        }
+
+        let mut manager = PluginManager::new(temp.path().to_path_buf());
+        assert_eq!(manager.discover().unwrap(), 1);
+        assert_eq!(manager.collisions.len(), 1);
+        let collision = &manager.collisions[0];
+        assert_eq!(collision.name, "guard");
+        assert_eq!(collision.plugins[0].0, "acme/guard");
+        assert_eq!(collision.plugins[1].0, "contoso/guard");
+
+        // The first by path is the one used, under either name
+        assert_eq!(manager.get("guard").unwrap().path, temp.path().join("acme-guard"));
+        assert!(manager.get("acme/guard").is_some());
+        assert!(manager.get("contoso/guard").is_none());
+    }
+
     #[test]
     fn test_discover_empty_directory() {
         let temp = tempdir().unwrap();
@@ -222,6 +578,24 @@ plugin:
         assert_eq!(count, 0);
     }
 
+    #[test]
+    fn test_disabled_plugins_are_not_resolved() {
+        let temp = tempdir().unwrap();
+        create_test_plugin(temp.path(), "plugin-a");
+        create_test_plugin(temp.path(), "plugin-b");
+
+        let mut manager = PluginManager::new(temp.path().to_path_buf());
+        manager.discover_all().unwrap();
+        let mut disabled = DisabledPlugins::default();
+        disabled.plugins.insert("plugin-b".to_string(), chrono::Utc::now());
+        manager.apply_disabled(&disabled);
+        manager.load_all().unwrap();
+
+        assert!(manager.has("plugin-b"));
+        assert_eq!(manager.get("plugin-b").unwrap().state, PluginState::Disabled);
+        assert_eq!(manager.resolve().order, ["plugin-a"]);
+    }
+
     #[test]
     fn test_remove_plugin() {
         let temp = tempdir().unwrap();
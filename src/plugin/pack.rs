@@ -0,0 +1,354 @@
+//! Distributable plugin archives
+//!
+//! `pais plugin pack` writes a plugin to one gzipped tar,
+//! `<name>-<version>.tar.gz`:
+//!
+//! - `manifest.json`: the format name and version, the plugin's name,
+//!   namespace and version, and the size and SHA-256 of every other member;
+//! - `plugin/…`: plugin.yaml, SKILL.md, sources and `plugin.sig` at their
+//!   paths under the plugin directory, keeping executable bits.
+//!
+//! Build output and tooling state (`target/`, `node_modules/`, `.venv/`,
+//! `.git/`) stay out, so the archive holds exactly the files a signature
+//! covers and a signed plugin still verifies once installed. `pais plugin
+//! install` takes an archive wherever it takes a path; every member is
+//! checked against the manifest before anything is unpacked.
+
+use eyre::{Context, Result};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::Plugin;
+use super::manifest::PluginManifest;
+use super::signing::UNSIGNED_DIRS;
+use crate::clock;
+use crate::error::{CliError, ErrorCode};
+use crate::history::archive::Member;
+use crate::share::is_safe_relative;
+
+/// Value of `format` in every manifest
+pub const FORMAT: &str = "pais-plugin";
+
+/// Bump when the layout changes; older pais refuse newer archives
+pub const VERSION: u32 = 1;
+
+const MANIFEST: &str = "manifest.json";
+const FILES_DIR: &str = "plugin";
+
+/// `manifest.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Manifest {
+    pub format: String,
+    pub version: u32,
+    pub created_at: String,
+    pub pais_version: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    pub plugin_version: String,
+    pub files: Vec<PackedFile>,
+}
+
+/// One archived file, by path relative to the plugin directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackedFile {
+    #[serde(flatten)]
+    pub member: Member,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub executable: bool,
+}
+
+/// What `pack` wrote
+#[derive(Debug, Serialize)]
+pub struct PackSummary {
+    pub archive: PathBuf,
+    pub name: String,
+    pub version: String,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// Whether `path` is a plugin archive rather than a plugin directory
+pub fn is_archive(path: &Path) -> bool {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.is_file() && (name.ends_with(".tar.gz") || name.ends_with(".tgz"))
+}
+
+/// `<name>-<version>.tar.gz`, with the namespace in front when there is one
+pub fn file_name(manifest: &PluginManifest) -> String {
+    let plugin = &manifest.plugin;
+    match &plugin.namespace {
+        Some(namespace) => format!("{}-{}-{}.tar.gz", namespace, plugin.name, plugin.version),
+        None => format!("{}-{}.tar.gz", plugin.name, plugin.version),
+    }
+}
+
+/// Files to pack, by path relative to the plugin directory
+fn packed_files(plugin_dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let mut files = Vec::new();
+    let walker = WalkDir::new(plugin_dir)
+        .follow_links(true)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_str().unwrap_or_default();
+            e.depth() == 0 || !(e.file_type().is_dir() && UNSIGNED_DIRS.contains(&name))
+        });
+    for entry in walker {
+        let entry = entry.with_context(|| format!("Failed to read {}", plugin_dir.display()))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let rel = entry
+            .path()
+            .strip_prefix(plugin_dir)?
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        files.push((rel, entry.path().to_path_buf()));
+    }
+    Ok(files)
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata().is_ok_and(|m| m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> bool {
+    false
+}
+
+/// Write `plugin` to the archive at `output`
+pub fn pack(plugin: &Plugin, output: &Path) -> Result<PackSummary> {
+    let info = &plugin.manifest.plugin;
+    let mut files = Vec::new();
+    let mut contents = Vec::new();
+    for (rel, path) in packed_files(&plugin.path)? {
+        let bytes = fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        files.push(PackedFile {
+            member: Member::of(&rel, &bytes),
+            executable: is_executable(&path),
+        });
+        contents.push(bytes);
+    }
+    let manifest = Manifest {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        created_at: clock::now().to_rfc3339(),
+        pais_version: env!("CARGO_PKG_VERSION").to_string(),
+        name: info.name.clone(),
+        namespace: info.namespace.clone(),
+        plugin_version: info.version.clone(),
+        files,
+    };
+
+    // Written aside and renamed, so a failed pack never leaves a truncated archive
+    let parent = output
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let tmp = tempfile::NamedTempFile::new_in(parent)
+        .with_context(|| format!("Failed to create an archive in {}", parent.display()))?;
+    let mut tar = tar::Builder::new(GzEncoder::new(tmp.as_file(), Compression::default()));
+    let mut append = |path: &str, bytes: &[u8], mode: u32| -> Result<()> {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(bytes.len() as u64);
+        header.set_mode(mode);
+        header.set_mtime(0);
+        tar.append_data(&mut header, path, bytes)
+            .with_context(|| format!("Failed to add {} to the archive", path))
+    };
+    append(MANIFEST, &serde_json::to_vec_pretty(&manifest)?, 0o644)?;
+    for (file, bytes) in manifest.files.iter().zip(&contents) {
+        let mode = if file.executable { 0o755 } else { 0o644 };
+        append(&format!("{}/{}", FILES_DIR, file.member.path), bytes, mode)?;
+    }
+    tar.into_inner()?.finish().context("Failed to write the archive")?;
+    tmp.persist(output)
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+
+    Ok(PackSummary {
+        archive: output.to_path_buf(),
+        name: info.qualified_name(),
+        version: info.version.clone(),
+        files: manifest.files.len(),
+        bytes: fs::metadata(output)?.len(),
+    })
+}
+
+fn invalid(archive: &Path, problem: impl std::fmt::Display) -> CliError {
+    CliError::new(
+        ErrorCode::Parse,
+        format!("{} is not a usable plugin archive: {}", archive.display(), problem),
+    )
+}
+
+/// Check the archive at `path` and unpack its plugin into `dest`, replacing what's there
+pub fn unpack(path: &Path, dest: &Path) -> Result<Manifest> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut members = BTreeMap::new();
+    let mut tar = tar::Archive::new(GzDecoder::new(file));
+    for entry in tar.entries().map_err(|e| invalid(path, e))? {
+        let mut entry = entry.map_err(|e| invalid(path, e))?;
+        let name = entry
+            .path()
+            .map_err(|e| invalid(path, e))?
+            .to_string_lossy()
+            .to_string();
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes).map_err(|e| invalid(path, e))?;
+        members.insert(name, bytes);
+    }
+
+    let manifest = members
+        .remove(MANIFEST)
+        .ok_or_else(|| invalid(path, "no manifest.json"))?;
+    let manifest: Manifest = serde_json::from_slice(&manifest).map_err(|e| invalid(path, e))?;
+    if manifest.format != FORMAT {
+        return Err(invalid(path, format!("unknown format '{}'", manifest.format)).into());
+    }
+    if manifest.version > VERSION {
+        return Err(invalid(
+            path,
+            format!(
+                "format version {} is newer than this pais understands ({})",
+                manifest.version, VERSION
+            ),
+        )
+        .with_hint(format!(
+            "It was packed by pais {}; upgrade to install it",
+            manifest.pais_version
+        ))
+        .into());
+    }
+
+    let mut files = Vec::new();
+    for file in &manifest.files {
+        let rel = &file.member.path;
+        if !is_safe_relative(Path::new(rel)) {
+            return Err(invalid(path, format!("unsafe path '{}'", rel)).into());
+        }
+        let bytes = members
+            .remove(&format!("{}/{}", FILES_DIR, rel))
+            .ok_or_else(|| invalid(path, format!("{} is missing", rel)))?;
+        file.member.check(&bytes)?;
+        files.push((file, bytes));
+    }
+    if !files.iter().any(|(file, _)| file.member.path == "plugin.yaml") {
+        return Err(invalid(path, "no plugin.yaml").into());
+    }
+
+    if dest.exists() {
+        fs::remove_dir_all(dest).with_context(|| format!("Failed to clear {}", dest.display()))?;
+    }
+    for (file, bytes) in files {
+        let target = dest.join(&file.member.path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        fs::write(&target, bytes).with_context(|| format!("Failed to write {}", target.display()))?;
+        #[cfg(unix)]
+        if file.executable {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&target, fs::Permissions::from_mode(0o755))?;
+        }
+    }
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::loader::load_plugin;
+    use tempfile::tempdir;
+
+    fn plugin(dir: &Path) -> Plugin {
+        fs::create_dir_all(dir.join("hooks")).unwrap();
+        fs::create_dir_all(dir.join("target").join("release")).unwrap();
+        fs::write(
+            dir.join("plugin.yaml"),
+            "plugin:\n  name: guard\n  namespace: acme\n  version: 1.2.0\n  description: test\n  language: shell\n",
+        )
+        .unwrap();
+        fs::write(dir.join("SKILL.md"), "# Guard\n").unwrap();
+        fs::write(dir.join("hooks").join("check"), "#!/bin/sh\nexit 0\n").unwrap();
+        fs::write(dir.join("target").join("release").join("guard"), "binary").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(dir.join("hooks").join("check"), fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        load_plugin(dir).unwrap()
+    }
+
+    #[test]
+    fn test_pack_and_unpack() {
+        let temp = tempdir().unwrap();
+        let plugin = plugin(&temp.path().join("src"));
+        let archive = temp.path().join(file_name(&plugin.manifest));
+        assert!(archive.ends_with("acme-guard-1.2.0.tar.gz"));
+
+        let summary = pack(&plugin, &archive).unwrap();
+        assert_eq!((summary.name.as_str(), summary.files), ("acme/guard", 3));
+        assert!(is_archive(&archive));
+
+        let dest = temp.path().join("unpacked");
+        let manifest = unpack(&archive, &dest).unwrap();
+        assert_eq!(
+            (manifest.name.as_str(), manifest.plugin_version.as_str()),
+            ("guard", "1.2.0")
+        );
+        assert_eq!(fs::read_to_string(dest.join("SKILL.md")).unwrap(), "# Guard\n");
+        assert!(!dest.join("target").exists());
+        #[cfg(unix)]
+        assert!(is_executable(&dest.join("hooks").join("check")));
+        assert!(!is_executable(&dest.join("SKILL.md")));
+    }
+
+    #[test]
+    fn test_unpack_rejects_tampered_archive() {
+        let temp = tempdir().unwrap();
+        let plugin = plugin(&temp.path().join("src"));
+        let archive = temp.path().join("guard.tar.gz");
+        pack(&plugin, &archive).unwrap();
+
+        // Repack with one member changed but the manifest left alone
+        let mut members = Vec::new();
+        let mut tar = tar::Archive::new(GzDecoder::new(File::open(&archive).unwrap()));
+        for entry in tar.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let name = entry.path().unwrap().to_string_lossy().to_string();
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes).unwrap();
+            if name == "plugin/SKILL.md" {
+                bytes = b"# Tampered\n".to_vec();
+            }
+            members.push((name, bytes));
+        }
+        let mut out = tar::Builder::new(GzEncoder::new(File::create(&archive).unwrap(), Compression::default()));
+        for (name, bytes) in members {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(bytes.len() as u64);
+            header.set_mode(0o644);
+            out.append_data(&mut header, name, bytes.as_slice()).unwrap();
+        }
+        out.into_inner().unwrap().finish().unwrap();
+
+        let dest = temp.path().join("unpacked");
+        let err = unpack(&archive, &dest).unwrap_err();
+        assert!(err.to_string().contains("SKILL.md is corrupt"), "{}", err);
+        assert!(!dest.exists());
+    }
+}
@@ -1,19 +1,50 @@
-//! Plugin registry management
+//! Plugin registries
 //!
-//! Registries are sources of plugin metadata for discovery.
-
-#![allow(dead_code)] // Registry types - for future remote registry support
+//! A registry is an `index.yaml` naming plugins and where to get them,
+//! listed under `plugins.registries` in pais.yaml. It is either served as a
+//! file (an `https://` URL or a local path ending in `.yaml`) or kept in a git
+//! repository (any other URL), in which case plugin sources may be paths
+//! inside that repository:
+//!
+//! ```yaml
+//! plugins:
+//!   hello-world:
+//!     source: https://github.com/someone/hello-world.git
+//!     version: 0.1.0
+//!     description: Greets people
+//!     keywords: [example]
+//!   deploy:
+//!     source: plugins/deploy      # in the registry's own repository
+//! ```
+//!
+//! Indexes and git sources are cached under `~/.cache/pais/registries/`. A
+//! registry that can't be fetched (or offline mode) falls back to its cached
+//! copy with a warning.
 
+use eyre::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::{NetworkConfig, RegistryConfig};
+use crate::error::{CliError, ErrorCode};
+use crate::net;
+use crate::sync::backend::Remote;
 
 /// A plugin registry entry
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct RegistryEntry {
+    /// Git URL, or a path relative to the registry (its repository or the index's directory)
     pub source: String,
     pub version: Option<String>,
     pub description: Option<String>,
 
+    /// Directory of the plugin within `source`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
     #[serde(default)]
     pub provides: Vec<String>,
 
@@ -21,23 +52,20 @@ pub struct RegistryEntry {
     pub keywords: Vec<String>,
 }
 
-/// A plugin registry
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// A registry index
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct Registry {
     #[serde(default)]
-    pub plugins: HashMap<String, RegistryEntry>,
+    pub plugins: BTreeMap<String, RegistryEntry>,
 }
 
 impl Registry {
-    /// Load a registry from a TOML file or URL
-    pub fn load(source: &str) -> eyre::Result<Self> {
-        // TODO: Handle URL sources (git repos, HTTP)
-        let content = std::fs::read_to_string(source)?;
-        let registry: Self = serde_yaml::from_str(&content)?;
-        Ok(registry)
+    /// Parse an `index.yaml`
+    pub fn parse(content: &str) -> Result<Self> {
+        serde_yaml::from_str(content).context("Invalid registry index")
     }
 
-    /// Search for plugins matching a query
+    /// Plugins whose name, description, keywords or provided contracts contain `query`, by name
     pub fn search(&self, query: &str) -> Vec<(&str, &RegistryEntry)> {
         let query_lower = query.to_lowercase();
         self.plugins
@@ -50,8 +78,307 @@ impl Registry {
                         .map(|d| d.to_lowercase().contains(&query_lower))
                         .unwrap_or(false)
                     || entry.keywords.iter().any(|k| k.to_lowercase().contains(&query_lower))
+                    || entry.provides.iter().any(|c| c.to_lowercase().contains(&query_lower))
             })
             .map(|(name, entry)| (name.as_str(), entry))
             .collect()
     }
 }
+
+/// A configured registry with its index
+#[derive(Debug)]
+pub struct LoadedRegistry {
+    pub name: String,
+    pub registry: Registry,
+    /// Directory relative sources resolve against, if the registry has one
+    base: Option<PathBuf>,
+}
+
+/// Directory holding cached indexes, registry checkouts and plugin sources
+pub fn cache_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("pais")
+        .join("registries")
+}
+
+/// Whether a registry URL names the index file itself rather than a git repository
+fn is_index_file(url: &str) -> bool {
+    url.ends_with(".yaml") || url.ends_with(".yml")
+}
+
+/// Whether a plugin source is a git URL rather than a path
+pub fn is_git_url(source: &str) -> bool {
+    source.contains("://") || source.starts_with("git@") || source.ends_with(".git")
+}
+
+/// Load every configured registry; one that can't be loaded is skipped with a warning
+pub fn load_all(configs: &[RegistryConfig], network: &NetworkConfig) -> Result<Vec<LoadedRegistry>> {
+    if configs.is_empty() {
+        return Err(CliError::new(ErrorCode::Config, "No plugin registries are configured")
+            .with_hint("Add one under plugins.registries in pais.yaml (name and url)")
+            .into());
+    }
+    let mut loaded = Vec::new();
+    for config in configs {
+        match load(config, network, &cache_dir()) {
+            Ok(registry) => loaded.push(registry),
+            Err(e) => log::warn!("Skipping registry '{}': {:#}", config.name, e),
+        }
+    }
+    Ok(loaded)
+}
+
+/// Load one registry, refreshing its cached copy unless offline
+pub fn load(config: &RegistryConfig, network: &NetworkConfig, cache: &Path) -> Result<LoadedRegistry> {
+    if config.name.is_empty() || config.url.is_empty() {
+        return Err(CliError::new(ErrorCode::Config, "A plugin registry needs a name and a url").into());
+    }
+    let url = config.url.trim();
+    let local = net::is_local(url);
+
+    if is_index_file(url) && local {
+        let path = PathBuf::from(shellexpand::tilde(url.trim_start_matches("file://")).as_ref());
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        return Ok(LoadedRegistry {
+            name: config.name.clone(),
+            registry: Registry::parse(&content)?,
+            base: path.parent().map(Path::to_path_buf),
+        });
+    }
+
+    if is_index_file(url) {
+        let cached = cache.join(format!("{}.yaml", config.name));
+        let content = if network.offline {
+            let what = format!("Fetching registry '{}'", config.name);
+            fs::read_to_string(&cached).map_err(|_| net::offline_error(&what))?
+        } else {
+            match fetch_index(url, network) {
+                Ok(content) => {
+                    fs::create_dir_all(cache).with_context(|| format!("Failed to create {}", cache.display()))?;
+                    fs::write(&cached, &content).with_context(|| format!("Failed to write {}", cached.display()))?;
+                    content
+                }
+                Err(e) => match fs::read_to_string(&cached) {
+                    Ok(content) => {
+                        log::warn!("Using the cached copy of registry '{}': {:#}", config.name, e);
+                        content
+                    }
+                    Err(_) => return Err(e.wrap_err(format!("Failed to fetch registry '{}'", config.name))),
+                },
+            }
+        };
+        return Ok(LoadedRegistry {
+            name: config.name.clone(),
+            registry: Registry::parse(&content)?,
+            base: None,
+        });
+    }
+
+    let checkout = cache.join(&config.name);
+    if network.offline && !local {
+        if !checkout.join(".git").exists() {
+            return Err(net::offline_error(&format!("Fetching registry '{}'", config.name)).into());
+        }
+        log::debug!("Offline: using the cached checkout of registry '{}'", config.name);
+    } else if let Err(e) = (Remote::Git { url: url.to_string() }).fetch(&checkout) {
+        if !checkout.join(".git").exists() {
+            return Err(e.wrap_err(format!("Failed to fetch registry '{}'", config.name)));
+        }
+        log::warn!("Using the cached copy of registry '{}': {:#}", config.name, e);
+    }
+    let index = checkout.join(&config.index);
+    let content = fs::read_to_string(&index).with_context(|| format!("Failed to read {}", index.display()))?;
+    Ok(LoadedRegistry {
+        name: config.name.clone(),
+        registry: Registry::parse(&content)?,
+        base: Some(checkout),
+    })
+}
+
+fn fetch_index(url: &str, network: &NetworkConfig) -> Result<String> {
+    let client = net::client(network)?;
+    let body = net::block_on(net::bytes(client.get(url), "registry index"))?;
+    String::from_utf8(body).context("Registry index is not UTF-8")
+}
+
+/// The registry entry for `name`, or `registry/name`, from the first registry listing it
+pub fn resolve<'a>(registries: &'a [LoadedRegistry], name: &str) -> Option<(&'a LoadedRegistry, &'a RegistryEntry)> {
+    let (registry, plugin) = match name.split_once('/') {
+        Some((registry, plugin)) => (Some(registry), plugin),
+        None => (None, name),
+    };
+    registries
+        .iter()
+        .filter(|r| registry.is_none_or(|wanted| r.name == wanted))
+        .find_map(|r| r.registry.plugins.get(plugin).map(|entry| (r, entry)))
+}
+
+/// Clone or update the git repository at `url`, returning its checkout
+///
+/// With `reference` (a branch, tag or commit), the checkout is a separate
+/// clone detached at that ref.
+pub fn fetch_git(url: &str, reference: Option<&str>, network: &NetworkConfig) -> Result<PathBuf> {
+    if !net::is_local(url) {
+        net::ensure_online(network, &format!("Fetching {}", url))?;
+    }
+    // One checkout per repository (and ref), named after its host and path
+    let trimmed = url.trim_end_matches('/').trim_end_matches(".git");
+    let trimmed = trimmed.split_once("://").map_or(trimmed, |(_, rest)| rest);
+    let mut name = trimmed.trim_start_matches("git@").to_string();
+    if let Some(reference) = reference {
+        name = format!("{}@{}", name, reference);
+    }
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '@' { c } else { '-' })
+        .collect();
+    let checkout = cache_dir().join("sources").join(name.trim_matches('-'));
+
+    let Some(reference) = reference else {
+        (Remote::Git { url: url.to_string() })
+            .fetch(&checkout)
+            .with_context(|| format!("Failed to fetch {}", url))?;
+        return Ok(checkout);
+    };
+    let fetched = if checkout.join(".git").exists() {
+        git(&checkout, &["fetch", "--quiet", "--tags", "--force", "origin"]).map(drop)
+    } else {
+        (Remote::Git { url: url.to_string() }).fetch(&checkout)
+    };
+    fetched.with_context(|| format!("Failed to fetch {}", url))?;
+    // A branch follows the remote; a tag or commit is taken as is
+    let branch = format!("origin/{}", reference);
+    let target = if git(&checkout, &["rev-parse", "--verify", "--quiet", &branch]).is_ok() {
+        branch
+    } else {
+        reference.to_string()
+    };
+    git(&checkout, &["checkout", "--quiet", "--force", "--detach", &target]).map_err(|e| {
+        CliError::new(
+            ErrorCode::NotFound,
+            format!("No ref '{}' in {} ({:#})", reference, url, e),
+        )
+        .with_hint("Pass a branch, tag or commit of that repository with --ref")
+    })?;
+    Ok(checkout)
+}
+
+/// Run git in `dir`, returning its trimmed stdout
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("Failed to run git (is it installed?)")?;
+    if !output.status.success() {
+        eyre::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Directory to install `entry` from, cloning or updating a git source first
+///
+/// `reference` pins a git source to a branch, tag or commit; it can't apply
+/// to a path inside the registry's own repository.
+pub fn fetch_source(
+    registry: &LoadedRegistry,
+    name: &str,
+    entry: &RegistryEntry,
+    reference: Option<&str>,
+    network: &NetworkConfig,
+) -> Result<PathBuf> {
+    let root = if is_git_url(&entry.source) {
+        fetch_git(&entry.source, reference, network)?
+    } else if reference.is_some() {
+        return Err(CliError::new(
+            ErrorCode::InvalidInput,
+            format!(
+                "'{}' is kept in registry '{}', so it has no ref of its own",
+                name, registry.name
+            ),
+        )
+        .into());
+    } else {
+        let base = registry.base.as_ref().ok_or_else(|| {
+            CliError::new(
+                ErrorCode::Config,
+                format!(
+                    "'{}' in registry '{}' has a relative source, but the registry is served over HTTP",
+                    name, registry.name
+                ),
+            )
+        })?;
+        base.join(&entry.source)
+    };
+    let dir = match &entry.path {
+        Some(path) => root.join(path),
+        None => root,
+    };
+    if !dir.join("plugin.yaml").exists() {
+        return Err(CliError::new(
+            ErrorCode::NotFound,
+            format!("No plugin.yaml for '{}' at {}", name, dir.display()),
+        )
+        .into());
+    }
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_local_registry_search_and_resolve() {
+        let temp = tempdir().unwrap();
+        let plugin = temp.path().join("plugins").join("deploy");
+        fs::create_dir_all(&plugin).unwrap();
+        fs::write(plugin.join("plugin.yaml"), "plugin:\n  name: deploy\n").unwrap();
+        let index = temp.path().join("index.yaml");
+        fs::write(
+            &index,
+            "plugins:\n  deploy:\n    source: plugins/deploy\n    description: Ship builds\n    provides: [DeployTarget]\n  \
+             hello:\n    source: https://example.com/hello.git\n    keywords: [example, greet]\n",
+        )
+        .unwrap();
+
+        let config = RegistryConfig {
+            name: "team".to_string(),
+            url: index.display().to_string(),
+            ..Default::default()
+        };
+        let network = NetworkConfig {
+            offline: true,
+            ..Default::default()
+        };
+        let registries = vec![load(&config, &network, &temp.path().join("cache")).unwrap()];
+
+        let hits: Vec<&str> = registries[0].registry.search("GREET").iter().map(|(n, _)| *n).collect();
+        assert_eq!(hits, ["hello"]);
+        assert_eq!(registries[0].registry.search("ship").len(), 1);
+        assert_eq!(registries[0].registry.search("deploytarget").len(), 1);
+
+        let (registry, entry) = resolve(&registries, "team/deploy").unwrap();
+        assert_eq!(fetch_source(registry, "deploy", entry, None, &network).unwrap(), plugin);
+        assert!(fetch_source(registry, "deploy", entry, Some("v1"), &network).is_err());
+        assert!(resolve(&registries, "other/deploy").is_none());
+
+        // Git sources need the network
+        let (registry, entry) = resolve(&registries, "hello").unwrap();
+        assert!(fetch_source(registry, "hello", entry, None, &network).is_err());
+    }
+
+    #[test]
+    fn test_is_git_url() {
+        assert!(is_git_url("https://github.com/someone/plugin"));
+        assert!(is_git_url("git@github.com:someone/plugin.git"));
+        assert!(!is_git_url("plugins/deploy"));
+    }
+}
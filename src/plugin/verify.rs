@@ -10,6 +10,8 @@ use std::process::Command;
 
 use super::manifest::{VerificationCommand, VerificationSpec};
 
+use crate::presenter;
+
 /// Result of a single verification check
 #[derive(Debug, Clone, Serialize)]
 pub struct CheckResult {
@@ -27,6 +29,15 @@ pub struct VerificationResult {
     pub summary: String,
 }
 
+impl VerificationResult {
+    /// Recompute `passed` and `summary` after checks were added
+    pub fn finish(&mut self) {
+        let passed_count = self.checks.iter().filter(|c| c.passed).count();
+        self.passed = passed_count == self.checks.len();
+        self.summary = format!("{}/{} checks passed", passed_count, self.checks.len());
+    }
+}
+
 /// Run verification for a plugin
 pub fn verify_plugin(plugin_name: &str, plugin_path: &Path, spec: &VerificationSpec) -> Result<VerificationResult> {
     let mut checks = Vec::new();
@@ -124,10 +135,15 @@ pub fn print_verification_result(result: &VerificationResult) {
     // Group checks by type
     let file_checks: Vec<_> = result.checks.iter().filter(|c| c.name.starts_with("file:")).collect();
     let env_checks: Vec<_> = result.checks.iter().filter(|c| c.name.starts_with("env:")).collect();
+    let describe_checks: Vec<_> = result
+        .checks
+        .iter()
+        .filter(|c| c.name.starts_with("describe:"))
+        .collect();
     let cmd_checks: Vec<_> = result
         .checks
         .iter()
-        .filter(|c| !c.name.starts_with("file:") && !c.name.starts_with("env:"))
+        .filter(|c| !c.name.starts_with("file:") && !c.name.starts_with("env:") && !c.name.starts_with("describe:"))
         .collect();
 
     if !file_checks.is_empty() {
@@ -154,6 +170,14 @@ pub fn print_verification_result(result: &VerificationResult) {
         println!();
     }
 
+    if !describe_checks.is_empty() {
+        println!("{}:", "Runtime Checks".bold());
+        for check in describe_checks {
+            print_check(check);
+        }
+        println!();
+    }
+
     // Summary
     if result.passed {
         println!("Result: {} ({})", "PASSED".green().bold(), result.summary.dimmed());
@@ -163,8 +187,12 @@ pub fn print_verification_result(result: &VerificationResult) {
 }
 
 fn print_check(check: &CheckResult) {
-    let icon = if check.passed { "✓".green() } else { "✗".red() };
-    let name = check.name.trim_start_matches("file: ").trim_start_matches("env: ");
+    let icon = if check.passed { presenter::ok() } else { presenter::fail() };
+    let name = check
+        .name
+        .trim_start_matches("file: ")
+        .trim_start_matches("env: ")
+        .trim_start_matches("describe: ");
 
     if let Some(ref msg) = check.message {
         println!("  {} {} - {}", icon, name, msg.dimmed());
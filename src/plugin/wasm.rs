@@ -0,0 +1,562 @@
+//! WebAssembly component plugins
+//!
+//! A plugin with `language: wasm` ships a component built against
+//! `wit/plugin.wit` and runs in-process under wasmtime instead of as a
+//! python/uv or native subprocess. Hook scripts name the component file
+//! (`script: plugin.wasm`); pais calls its `on-hook` export with a typed
+//! payload, and its `call` export for `pais run` actions, `describe`, and
+//! contract calls. Components get WASI without preopened directories,
+//! environment, or network, and are interrupted when they overrun their
+//! timeout. The host API lets them log and call the plugins wired to the
+//! contracts they consume.
+//!
+//! The runtime is behind the `wasm` cargo feature; without it, running a
+//! wasm plugin fails with a hint to rebuild pais.
+
+use eyre::Result;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::executor::{HookEnv, PluginHookResult};
+use super::manifest::{HookScript, PluginLanguage, PluginManifest};
+use super::{Plugin, PluginManager, config};
+use crate::config::PluginSettings;
+use crate::error::{CliError, ErrorCode};
+use crate::hook::HookEvent;
+use crate::secrets::redact;
+
+/// How long a contract call may run
+const CONTRACT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Whether this build of pais can run wasm plugins
+pub const ENABLED: bool = cfg!(feature = "wasm");
+
+/// Where a plugin's component may be, in order of preference
+///
+/// `plugin.wasm` at the plugin root, else what `cargo build --target
+/// wasm32-wasip2 --release` produces for a crate named after the plugin.
+pub fn components(plugin_path: &Path) -> [PathBuf; 2] {
+    let name = plugin_path.file_name().and_then(|n| n.to_str()).unwrap_or("plugin");
+    [
+        plugin_path.join("plugin.wasm"),
+        plugin_path
+            .join("target")
+            .join("wasm32-wasip2")
+            .join("release")
+            .join(format!("{}.wasm", name.replace('-', "_"))),
+    ]
+}
+
+/// The plugin's component, if it has been built
+pub fn component(plugin_path: &Path) -> Option<PathBuf> {
+    components(plugin_path).into_iter().find(|p| p.exists())
+}
+
+/// The plugins a wasm plugin can reach through `call-contract`
+///
+/// Cheap to clone: each wasm instance holds its own copy.
+#[derive(Debug, Default, Clone)]
+pub struct Contracts {
+    plugins: Arc<HashMap<String, Plugin>>,
+    /// For each plugin, the provider wired to each consumed alias
+    wiring: Arc<BTreeMap<String, BTreeMap<String, String>>>,
+    /// Configuration from pais.yaml, for the providers
+    settings: Arc<BTreeMap<String, PluginSettings>>,
+}
+
+impl Contracts {
+    /// Snapshot the resolved plugins in `manager`
+    pub fn from_manager(manager: &PluginManager) -> Self {
+        let resolution = manager.resolve();
+        let plugins = resolution
+            .order
+            .iter()
+            .filter_map(|name| manager.get(name).map(|p| (name.clone(), p.clone())))
+            .collect();
+        Self {
+            plugins: Arc::new(plugins),
+            wiring: Arc::new(resolution.wiring),
+            settings: Arc::new(manager.settings.clone()),
+        }
+    }
+
+    /// The plugin serving `alias` for `consumer`
+    fn provider(&self, consumer: &str, alias: &str) -> Option<&Plugin> {
+        self.wiring
+            .get(consumer)
+            .and_then(|wired| wired.get(alias))
+            .and_then(|name| self.plugins.get(name))
+    }
+}
+
+/// What a component's `call` export produced
+#[derive(Debug)]
+pub struct CallOutput {
+    /// The returned value: `Ok` output or `Err` message
+    pub value: std::result::Result<String, String>,
+    /// What it wrote to WASI stdout and stderr
+    pub stdout: String,
+    pub stderr: String,
+}
+
+fn not_enabled(plugin: &str) -> CliError {
+    CliError::new(
+        ErrorCode::InvalidInput,
+        format!(
+            "Plugin '{}' is a WebAssembly plugin, but this pais was built without wasm support",
+            plugin
+        ),
+    )
+    .with_hint("Rebuild pais with: cargo install --path . --features wasm")
+}
+
+/// Run one hook script (a component) for `event`
+pub fn run_hook(
+    plugin_path: &Path,
+    manifest: &PluginManifest,
+    hook_script: &HookScript,
+    event: HookEvent,
+    payload: &serde_json::Value,
+    env: &HookEnv,
+) -> Result<PluginHookResult> {
+    #[cfg(feature = "wasm")]
+    {
+        let component = plugin_path.join(&hook_script.script);
+        runtime::run_hook(&manifest.plugin.name, &component, hook_script, event, payload, env)
+    }
+    #[cfg(not(feature = "wasm"))]
+    {
+        let _ = (plugin_path, hook_script, event, payload, env);
+        Err(not_enabled(&manifest.plugin.name).into())
+    }
+}
+
+/// Call the plugin's `call` export with `action` and `args`
+///
+/// `vars` become the component's (otherwise empty) WASI environment.
+pub fn call(
+    plugin_path: &Path,
+    manifest: &PluginManifest,
+    action: &str,
+    args: &[String],
+    timeout: Option<Duration>,
+    vars: &[(String, String)],
+    contracts: &Contracts,
+) -> Result<CallOutput> {
+    let name = &manifest.plugin.name;
+    let Some(path) = component(plugin_path) else {
+        return Err(
+            CliError::new(ErrorCode::NotFound, format!("Plugin '{}' has no built component", name))
+                .with_hint("Build it to plugin.wasm, or with: cargo build --target wasm32-wasip2 --release")
+                .into(),
+        );
+    };
+    #[cfg(feature = "wasm")]
+    {
+        runtime::call(name, &path, action, args, timeout, vars, contracts)
+    }
+    #[cfg(not(feature = "wasm"))]
+    {
+        let _ = (path, action, args, timeout, vars, contracts);
+        Err(not_enabled(name).into())
+    }
+}
+
+/// Serve a contract call from `consumer` to the plugin wired to `alias`
+///
+/// Wasm providers are called in-process; others run their entry point
+/// with the method and arguments, answering on stdout.
+fn call_contract(
+    contracts: &Contracts,
+    consumer: &str,
+    alias: &str,
+    method: &str,
+    args: &[String],
+) -> std::result::Result<String, String> {
+    let provider = contracts
+        .provider(consumer, alias)
+        .ok_or_else(|| format!("No plugin is wired to '{}' for {}", alias, consumer))?;
+    let name = &provider.manifest.plugin.name;
+    let resolved = config::resolve(&provider.manifest, contracts.settings.get(name)).map_err(|e| e.message)?;
+    let vars = resolved.vars();
+    if matches!(provider.manifest.plugin.language, PluginLanguage::Wasm) {
+        return call(
+            &provider.path,
+            &provider.manifest,
+            method,
+            args,
+            Some(CONTRACT_TIMEOUT),
+            &vars,
+            contracts,
+        )
+        .map_err(|e| format!("{:#}", e))?
+        .value;
+    }
+
+    let mut cmd = super::describe::entrypoint(&provider.path, &provider.manifest.plugin.language)
+        .ok_or_else(|| format!("Plugin '{}' has no runnable entry point", name))?;
+    let output = cmd
+        .arg(method)
+        .args(args)
+        .envs(vars)
+        .output()
+        .map_err(|e| format!("Failed to run plugin '{}': {}", name, e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(format!(
+            "Plugin '{}' failed ({}): {}",
+            name,
+            output.status,
+            redact(String::from_utf8_lossy(&output.stderr).trim(), &resolved.secrets())
+        ))
+    }
+}
+
+#[cfg(feature = "wasm")]
+mod runtime {
+    use eyre::{Context, Result};
+    use once_cell::sync::Lazy;
+    use std::path::Path;
+    use std::time::Duration;
+    use wasmtime::component::{Component, Linker, ResourceTable};
+    use wasmtime::{Config as EngineConfig, Engine, Store, Trap};
+    use wasmtime_wasi::pipe::MemoryOutputPipe;
+    use wasmtime_wasi::{IoView, WasiCtx, WasiCtxBuilder, WasiView};
+
+    use super::{CallOutput, Contracts};
+    use crate::hook::HookEvent;
+    use crate::plugin::executor::{HookEnv, PluginHookResult};
+    use crate::plugin::manifest::HookScript;
+
+    mod bindings {
+        wasmtime::component::bindgen!({
+            path: "wit/plugin.wit",
+            world: "plugin",
+        });
+    }
+
+    use bindings::Plugin as Bindings;
+    use bindings::pais::plugin::host::{self, Level};
+    use bindings::pais::plugin::types::{self, HookDecision, HookPayload};
+
+    /// How often the epoch advances; timeouts are counted in ticks
+    const TICK: Duration = Duration::from_millis(10);
+
+    /// Most output kept from a component's stdout or stderr
+    const OUTPUT_LIMIT: usize = 1 << 20;
+
+    /// One engine per process, with a thread advancing its epoch
+    static ENGINE: Lazy<Engine> = Lazy::new(|| {
+        let mut config = EngineConfig::new();
+        config.wasm_component_model(true).epoch_interruption(true);
+        let engine = Engine::new(&config).expect("wasm engine configuration is valid");
+        let ticker = engine.clone();
+        std::thread::spawn(move || {
+            loop {
+                std::thread::sleep(TICK);
+                ticker.increment_epoch();
+            }
+        });
+        engine
+    });
+
+    /// Per-call host state
+    struct State {
+        plugin: String,
+        contracts: Contracts,
+        wasi: WasiCtx,
+        table: ResourceTable,
+    }
+
+    impl IoView for State {
+        fn table(&mut self) -> &mut ResourceTable {
+            &mut self.table
+        }
+    }
+
+    impl WasiView for State {
+        fn ctx(&mut self) -> &mut WasiCtx {
+            &mut self.wasi
+        }
+    }
+
+    impl types::Host for State {}
+
+    impl host::Host for State {
+        fn log(&mut self, level: Level, message: String) {
+            let level = match level {
+                Level::Debug => log::Level::Debug,
+                Level::Info => log::Level::Info,
+                Level::Warn => log::Level::Warn,
+                Level::Error => log::Level::Error,
+            };
+            log::log!(level, "[{}] {}", self.plugin, message);
+        }
+
+        fn call_contract(
+            &mut self,
+            alias: String,
+            method: String,
+            args: Vec<String>,
+        ) -> std::result::Result<String, String> {
+            super::call_contract(&self.contracts, &self.plugin, &alias, &method, &args)
+        }
+    }
+
+    /// A component instantiated in a fresh, sandboxed store
+    struct Instance {
+        store: Store<State>,
+        bindings: Bindings,
+        stdout: MemoryOutputPipe,
+        stderr: MemoryOutputPipe,
+        name: String,
+        timeout: Option<Duration>,
+    }
+
+    impl Instance {
+        fn new(
+            name: &str,
+            component: &Path,
+            timeout: Option<Duration>,
+            vars: &[(String, String)],
+            contracts: &Contracts,
+        ) -> Result<Self> {
+            let name = name.to_string();
+            let engine = &*ENGINE;
+            let component = Component::from_file(engine, component)
+                .map_err(|e| eyre::eyre!("Failed to load component {}: {:#}", component.display(), e))?;
+
+            let mut linker = Linker::new(engine);
+            wasmtime_wasi::add_to_linker_sync(&mut linker)
+                .and_then(|_| Bindings::add_to_linker(&mut linker, |state: &mut State| state))
+                .map_err(|e| eyre::eyre!("Failed to set up the wasm host: {:#}", e))?;
+
+            let stdout = MemoryOutputPipe::new(OUTPUT_LIMIT);
+            let stderr = MemoryOutputPipe::new(OUTPUT_LIMIT);
+            let wasi = WasiCtxBuilder::new()
+                .stdout(stdout.clone())
+                .stderr(stderr.clone())
+                .envs(vars)
+                .build();
+            let mut store = Store::new(
+                engine,
+                State {
+                    plugin: name.clone(),
+                    contracts: contracts.clone(),
+                    wasi,
+                    table: ResourceTable::new(),
+                },
+            );
+            store.set_epoch_deadline(ticks(timeout));
+            let bindings = Bindings::instantiate(&mut store, &component, &linker)
+                .map_err(|e| eyre::eyre!("Failed to instantiate plugin '{}': {:#}", name, e))?;
+            Ok(Self {
+                store,
+                bindings,
+                stdout,
+                stderr,
+                name,
+                timeout,
+            })
+        }
+
+        /// Explain a failed export call, naming timeouts
+        fn failure(&self, error: wasmtime::Error) -> eyre::Report {
+            match (error.downcast_ref::<Trap>(), self.timeout) {
+                (Some(Trap::Interrupt), Some(timeout)) => {
+                    eyre::eyre!("Plugin '{}' timed out after {}s", self.name, timeout.as_secs_f32())
+                }
+                _ => eyre::eyre!("Plugin '{}' failed: {:#}", self.name, error),
+            }
+        }
+
+        fn output(&self) -> (String, String) {
+            (
+                String::from_utf8_lossy(&self.stdout.contents()).into_owned(),
+                String::from_utf8_lossy(&self.stderr.contents()).into_owned(),
+            )
+        }
+    }
+
+    /// Epoch ticks until the deadline; without a timeout, one the ticker won't reach
+    fn ticks(timeout: Option<Duration>) -> u64 {
+        timeout.map_or(u32::MAX.into(), |t| (t.as_millis() / TICK.as_millis()).max(1) as u64)
+    }
+
+    pub(super) fn run_hook(
+        name: &str,
+        component: &Path,
+        hook_script: &HookScript,
+        event: HookEvent,
+        payload: &serde_json::Value,
+        env: &HookEnv,
+    ) -> Result<PluginHookResult> {
+        let text = |key: &str| payload.get(key).and_then(|v| v.as_str()).map(String::from);
+        let hook_payload = HookPayload {
+            event: event.to_string(),
+            session_id: text("session_id"),
+            tool_name: text("tool_name"),
+            json: serde_json::to_string(payload).context("Failed to serialize payload")?,
+        };
+        let timeout = env.timeout_for(hook_script);
+        let mut instance = Instance::new(name, component, Some(timeout), &env.vars, &env.contracts)?;
+        let decision = match instance.bindings.call_on_hook(&mut instance.store, &hook_payload) {
+            Ok(decision) => decision,
+            Err(e) if e.downcast_ref::<Trap>() == Some(&Trap::Interrupt) => {
+                let (stdout, stderr) = instance.output();
+                return Ok(PluginHookResult {
+                    plugin_name: instance.name,
+                    script: hook_script.script.clone(),
+                    exit_code: 1,
+                    stdout,
+                    stderr,
+                    context: None,
+                    timed_out: Some(timeout),
+                });
+            }
+            Err(e) => return Err(instance.failure(e)),
+        };
+        let (stdout, mut stderr) = instance.output();
+
+        let (exit_code, context) = match decision {
+            HookDecision::Allow => (0, None),
+            HookDecision::Block(message) => {
+                stderr = message;
+                (2, None)
+            }
+            HookDecision::InjectContext(text) => (0, Some(text)),
+        };
+        Ok(PluginHookResult {
+            plugin_name: instance.name,
+            script: hook_script.script.clone(),
+            exit_code,
+            stdout,
+            stderr,
+            context,
+            timed_out: None,
+        })
+    }
+
+    pub(super) fn call(
+        name: &str,
+        component: &Path,
+        action: &str,
+        args: &[String],
+        timeout: Option<Duration>,
+        vars: &[(String, String)],
+        contracts: &Contracts,
+    ) -> Result<CallOutput> {
+        let mut instance = Instance::new(name, component, timeout, vars, contracts)?;
+        let value = instance
+            .bindings
+            .call_call(&mut instance.store, action, args)
+            .map_err(|e| instance.failure(e))?;
+        let (stdout, stderr) = instance.output();
+        Ok(CallOutput { value, stdout, stderr })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_component_locations() {
+        let temp = tempdir().unwrap();
+        let plugin = temp.path().join("tool-guard");
+        let built = plugin.join("target/wasm32-wasip2/release/tool_guard.wasm");
+        assert_eq!(components(&plugin)[1], built);
+        assert_eq!(component(&plugin), None);
+
+        fs::create_dir_all(built.parent().unwrap()).unwrap();
+        fs::write(&built, b"").unwrap();
+        assert_eq!(component(&plugin), Some(built));
+        fs::write(plugin.join("plugin.wasm"), b"").unwrap();
+        assert_eq!(component(&plugin), Some(plugin.join("plugin.wasm")));
+    }
+
+    /// Blocks tools, answers "pong" to calls, and spins on a call to "spin"
+    #[cfg(feature = "wasm")]
+    const GUARD: &str = r#"
+(component
+  (core module $m
+    (memory (export "memory") 1)
+    (global $next (mut i32) (i32.const 4096))
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+      (local $p i32)
+      (local.set $p (i32.and (i32.add (global.get $next) (i32.const 7)) (i32.const -8)))
+      (global.set $next (i32.add (local.get $p) (local.get 3)))
+      (local.get $p))
+    (data (i32.const 16) "pong")
+    (data (i32.const 32) "No tools today")
+    ;; payload: event (0-1), session-id (2-4), tool-name (5-7), json (8-9)
+    (func (export "on-hook") (param i32 i32 i32 i32 i32 i32 i32 i32 i32 i32) (result i32)
+      (i32.store8 (i32.const 80) (local.get 5))
+      (i32.store (i32.const 84) (i32.const 32))
+      (i32.store (i32.const 88) (i32.const 14))
+      (i32.const 80))
+    (func (export "call") (param i32 i32 i32 i32) (result i32)
+      (if (i32.eq (local.get 1) (i32.const 4))
+        (then (if (i32.eq (i32.load (local.get 0)) (i32.const 0x6e697073))
+          (then (loop $spin (br $spin))))))
+      (i32.store8 (i32.const 64) (i32.const 0))
+      (i32.store (i32.const 68) (i32.const 16))
+      (i32.store (i32.const 72) (i32.const 4))
+      (i32.const 64)))
+  (core instance $i (instantiate $m))
+  (type $payload' (record
+    (field "event" string)
+    (field "session-id" (option string))
+    (field "tool-name" (option string))
+    (field "json" string)))
+  (export $payload "hook-payload" (type $payload'))
+  (type $decision' (variant (case "allow") (case "block" string) (case "inject-context" string)))
+  (export $decision "hook-decision" (type $decision'))
+  (func (export "on-hook") (param "payload" $payload) (result $decision)
+    (canon lift (core func $i "on-hook") (memory $i "memory") (realloc (func $i "realloc"))))
+  (func (export "call") (param "action" string) (param "args" (list string)) (result (result string (error string)))
+    (canon lift (core func $i "call") (memory $i "memory") (realloc (func $i "realloc")))))
+"#;
+
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn test_component_hooks_and_calls() {
+        use crate::hook::HookResult;
+
+        let temp = tempdir().unwrap();
+        fs::write(temp.path().join("plugin.wasm"), wat::parse_str(GUARD).unwrap()).unwrap();
+        let manifest = PluginManifest::from_str(
+            "plugin:\n  name: guard\n  version: 0.1.0\n  description: test\n  language: wasm\n\
+             hooks:\n  PreToolUse:\n    - script: plugin.wasm\n",
+        )
+        .unwrap();
+        let script = &manifest.hooks.pre_tool_use[0];
+        let contracts = Contracts::default();
+
+        let env = HookEnv::default();
+        let hook = |payload| run_hook(temp.path(), &manifest, script, HookEvent::PreToolUse, &payload, &env);
+        let blocked = hook(serde_json::json!({"tool_name": "Bash"})).unwrap();
+        assert!(matches!(blocked.to_hook_result(), HookResult::Block { message } if message == "No tools today"));
+        let allowed = hook(serde_json::json!({})).unwrap();
+        assert!(matches!(allowed.to_hook_result(), HookResult::Allow));
+
+        let output = call(temp.path(), &manifest, "ping", &[], None, &[], &contracts).unwrap();
+        assert_eq!(output.value, Ok("pong".to_string()));
+
+        let spin = call(
+            temp.path(),
+            &manifest,
+            "spin",
+            &[],
+            Some(Duration::from_millis(50)),
+            &[],
+            &contracts,
+        );
+        assert!(spin.unwrap_err().to_string().contains("timed out"));
+    }
+}
@@ -0,0 +1,516 @@
+//! Policy expression language
+//!
+//! A small expression language evaluated against hook payloads.
+//!
+//! # Syntax
+//!
+//! ```text
+//! tool_name == "Bash" && tool_input.command matches "git\s+push"
+//! event in ["PreToolUse", "PostToolUse"] && !(cwd startswith "/tmp")
+//! tool_input.file_path endswith ".env" || tool_input.command contains "secret"
+//! ```
+//!
+//! - Paths: dotted field access into the payload (`tool_input.command`)
+//! - Literals: `"string"`, `'string'`, numbers, `true`, `false`, `null`, `[list]`
+//! - Comparison: `==`, `!=`, `<`, `<=`, `>`, `>=`
+//! - String operators: `matches` (regex), `contains`, `startswith`, `endswith`.
+//!   A regex written as a literal is compiled when the expression is parsed,
+//!   so an invalid one fails there rather than on every evaluation
+//! - Membership: `in` (value in list, or substring in string)
+//! - Boolean: `&&`, `||`, `!`, parentheses
+
+use eyre::{Result, bail};
+use serde_json::Value;
+
+/// A parsed policy expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Literal(Value),
+    Path(Vec<String>),
+    List(Vec<Expr>),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare(Box<Expr>, Op, Box<Expr>),
+    /// `matches` against a literal regex, compiled at parse time
+    Matches(Box<Expr>, Pattern),
+}
+
+/// A compiled regex, compared by its source
+#[derive(Debug, Clone)]
+pub struct Pattern(regex::Regex);
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+/// Binary comparison operators
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Matches,
+    Contains,
+    StartsWith,
+    EndsWith,
+    In,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Str(String),
+    Num(f64),
+    Ident(String),
+    Op(Op),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+}
+
+impl Expr {
+    /// Parse an expression from source text
+    pub fn parse(source: &str) -> Result<Self> {
+        let tokens = tokenize(source)?;
+        if tokens.is_empty() {
+            bail!("Empty expression");
+        }
+
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.parse_or()?;
+
+        if parser.pos < parser.tokens.len() {
+            bail!("Unexpected token {:?} in expression", parser.tokens[parser.pos]);
+        }
+
+        Ok(expr)
+    }
+
+    /// Evaluate the expression to a boolean against a context value
+    pub fn matches(&self, context: &Value) -> Result<bool> {
+        Ok(truthy(&self.eval(context)?))
+    }
+
+    /// Evaluate the expression to a value
+    pub fn eval(&self, context: &Value) -> Result<Value> {
+        match self {
+            Expr::Literal(v) => Ok(v.clone()),
+            Expr::Path(parts) => Ok(lookup(context, parts).cloned().unwrap_or(Value::Null)),
+            Expr::List(items) => Ok(Value::Array(
+                items.iter().map(|e| e.eval(context)).collect::<Result<Vec<_>>>()?,
+            )),
+            Expr::Not(inner) => Ok(Value::Bool(!inner.matches(context)?)),
+            Expr::And(lhs, rhs) => Ok(Value::Bool(lhs.matches(context)? && rhs.matches(context)?)),
+            Expr::Or(lhs, rhs) => Ok(Value::Bool(lhs.matches(context)? || rhs.matches(context)?)),
+            Expr::Compare(lhs, op, rhs) => {
+                let l = lhs.eval(context)?;
+                let r = rhs.eval(context)?;
+                Ok(Value::Bool(compare(&l, *op, &r)?))
+            }
+            Expr::Matches(lhs, pattern) => Ok(Value::Bool(pattern.0.is_match(&as_text(&lhs.eval(context)?)))),
+        }
+    }
+}
+
+/// Look up a dotted path in a JSON value
+fn lookup<'a>(value: &'a Value, parts: &[String]) -> Option<&'a Value> {
+    let mut current = value;
+    for part in parts {
+        current = match current {
+            Value::Object(map) => map.get(part)?,
+            Value::Array(items) => items.get(part.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(current)
+}
+
+/// Truthiness of a value: false, null, 0, "" and [] are false
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().map(|f| f != 0.0).unwrap_or(false),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+    }
+}
+
+/// Render a value as a string for string operators
+fn as_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn compare(l: &Value, op: Op, r: &Value) -> Result<bool> {
+    Ok(match op {
+        Op::Eq => values_equal(l, r),
+        Op::Ne => !values_equal(l, r),
+        Op::Lt | Op::Le | Op::Gt | Op::Ge => {
+            let ordering = match (l.as_f64(), r.as_f64()) {
+                (Some(a), Some(b)) => a.partial_cmp(&b),
+                _ => Some(as_text(l).cmp(&as_text(r))),
+            };
+            let Some(ordering) = ordering else {
+                return Ok(false);
+            };
+            match op {
+                Op::Lt => ordering.is_lt(),
+                Op::Le => ordering.is_le(),
+                Op::Gt => ordering.is_gt(),
+                _ => ordering.is_ge(),
+            }
+        }
+        // Only reached when the pattern isn't a literal (see `Expr::Matches`)
+        Op::Matches => compile_regex(&as_text(r))?.is_match(&as_text(l)),
+        Op::Contains => match l {
+            Value::Array(items) => items.iter().any(|item| values_equal(item, r)),
+            _ => as_text(l).contains(&as_text(r)),
+        },
+        Op::StartsWith => as_text(l).starts_with(&as_text(r)),
+        Op::EndsWith => as_text(l).ends_with(&as_text(r)),
+        Op::In => match r {
+            Value::Array(items) => items.iter().any(|item| values_equal(l, item)),
+            _ => as_text(r).contains(&as_text(l)),
+        },
+    })
+}
+
+fn compile_regex(pattern: &str) -> Result<regex::Regex> {
+    regex::Regex::new(pattern).map_err(|e| eyre::eyre!("Invalid regex '{}': {}", pattern, e))
+}
+
+fn values_equal(l: &Value, r: &Value) -> bool {
+    match (l.as_f64(), r.as_f64()) {
+        (Some(a), Some(b)) => a == b,
+        _ => l == r,
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '[' => {
+                tokens.push(Token::LBracket);
+                i += 1;
+            }
+            ']' => {
+                tokens.push(Token::RBracket);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            '"' | '\'' => {
+                let quote = c;
+                let mut s = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => bail!("Unterminated string literal"),
+                        Some(&ch) if ch == quote => {
+                            i += 1;
+                            break;
+                        }
+                        Some('\\') => {
+                            // Keep unknown escapes verbatim so regexes like "\s" survive
+                            match chars.get(i + 1) {
+                                Some(&next) if next == quote || next == '\\' => s.push(next),
+                                Some('n') => s.push('\n'),
+                                Some('t') => s.push('\t'),
+                                Some(&next) => {
+                                    s.push('\\');
+                                    s.push(next);
+                                }
+                                None => bail!("Unterminated string literal"),
+                            }
+                            i += 2;
+                        }
+                        Some(&ch) => {
+                            s.push(ch);
+                            i += 1;
+                        }
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+            '=' | '!' | '<' | '>' => {
+                let next = chars.get(i + 1).copied();
+                let (token, len) = match (c, next) {
+                    ('=', Some('=')) => (Token::Op(Op::Eq), 2),
+                    ('!', Some('=')) => (Token::Op(Op::Ne), 2),
+                    ('<', Some('=')) => (Token::Op(Op::Le), 2),
+                    ('>', Some('=')) => (Token::Op(Op::Ge), 2),
+                    ('<', _) => (Token::Op(Op::Lt), 1),
+                    ('>', _) => (Token::Op(Op::Gt), 1),
+                    ('!', _) => (Token::Not, 1),
+                    _ => bail!("Unexpected '=' at position {} (use '==')", i),
+                };
+                tokens.push(token);
+                i += len;
+            }
+            '&' | '|' => {
+                if chars.get(i + 1) != Some(&c) {
+                    bail!("Unexpected '{}' at position {} (use '{}{}')", c, i, c, c);
+                }
+                tokens.push(if c == '&' { Token::And } else { Token::Or });
+                i += 2;
+            }
+            c if c.is_ascii_digit() || (c == '-' && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit())) => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let num = text
+                    .parse::<f64>()
+                    .map_err(|_| eyre::eyre!("Invalid number: {}", text))?;
+                tokens.push(Token::Num(num));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '-' | '.')) {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                let token = match word.as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    "matches" => Token::Op(Op::Matches),
+                    "contains" => Token::Op(Op::Contains),
+                    "startswith" => Token::Op(Op::StartsWith),
+                    "endswith" => Token::Op(Op::EndsWith),
+                    "in" => Token::Op(Op::In),
+                    _ => Token::Ident(word),
+                };
+                tokens.push(token);
+            }
+            _ => bail!("Unexpected character '{}' at position {}", c, i),
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<()> {
+        match self.next() {
+            Some(ref t) if *t == expected => Ok(()),
+            Some(t) => bail!("Expected {:?}, found {:?}", expected, t),
+            None => bail!("Expected {:?}, found end of expression", expected),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr> {
+        let mut lhs = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            let rhs = self.parse_and()?;
+            lhs = Expr::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr> {
+        let mut lhs = self.parse_not()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            let rhs = self.parse_not()?;
+            lhs = Expr::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr> {
+        if self.peek() == Some(&Token::Not) {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_compare()
+    }
+
+    fn parse_compare(&mut self) -> Result<Expr> {
+        let lhs = self.parse_primary()?;
+        if let Some(Token::Op(op)) = self.peek().cloned() {
+            self.pos += 1;
+            let rhs = self.parse_primary()?;
+            if let (Op::Matches, Expr::Literal(Value::String(pattern))) = (op, &rhs) {
+                return Ok(Expr::Matches(Box::new(lhs), Pattern(compile_regex(pattern)?)));
+            }
+            return Ok(Expr::Compare(Box::new(lhs), op, Box::new(rhs)));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr> {
+        match self.next() {
+            Some(Token::Str(s)) => Ok(Expr::Literal(Value::String(s))),
+            Some(Token::Num(n)) => Ok(Expr::Literal(
+                serde_json::Number::from_f64(n)
+                    .map(Value::Number)
+                    .unwrap_or(Value::Null),
+            )),
+            Some(Token::Ident(word)) => Ok(match word.as_str() {
+                "true" => Expr::Literal(Value::Bool(true)),
+                "false" => Expr::Literal(Value::Bool(false)),
+                "null" => Expr::Literal(Value::Null),
+                _ => Expr::Path(word.split('.').map(String::from).collect()),
+            }),
+            Some(Token::LParen) => {
+                let expr = self.parse_or()?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            Some(Token::LBracket) => {
+                let mut items = Vec::new();
+                if self.peek() == Some(&Token::RBracket) {
+                    self.pos += 1;
+                    return Ok(Expr::List(items));
+                }
+                loop {
+                    items.push(self.parse_primary()?);
+                    match self.next() {
+                        Some(Token::Comma) => continue,
+                        Some(Token::RBracket) => break,
+                        other => bail!("Expected ',' or ']' in list, found {:?}", other),
+                    }
+                }
+                Ok(Expr::List(items))
+            }
+            Some(t) => bail!("Unexpected token {:?}", t),
+            None => bail!("Unexpected end of expression"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn eval(source: &str, context: &Value) -> bool {
+        Expr::parse(source).unwrap().matches(context).unwrap()
+    }
+
+    #[test]
+    fn test_equality_and_paths() {
+        let ctx = json!({"tool_name": "Bash", "tool_input": {"command": "ls -la"}});
+        assert!(eval(r#"tool_name == "Bash""#, &ctx));
+        assert!(eval(r#"tool_input.command != "pwd""#, &ctx));
+        assert!(!eval(r#"tool_name == 'Edit'"#, &ctx));
+    }
+
+    #[test]
+    fn test_missing_path_is_null() {
+        let ctx = json!({});
+        assert!(eval("missing.field == null", &ctx));
+        assert!(!eval("missing.field", &ctx));
+    }
+
+    #[test]
+    fn test_regex_keeps_escapes() {
+        let ctx = json!({"tool_input": {"command": "git   push --force"}});
+        assert!(eval(r#"tool_input.command matches "git\s+push""#, &ctx));
+    }
+
+    #[test]
+    fn test_boolean_operators_and_precedence() {
+        let ctx = json!({"a": 1, "b": 0, "c": "x"});
+        assert!(eval("a == 1 || b == 1 && c == 'y'", &ctx));
+        assert!(!eval("(a == 1 || b == 1) && c == 'y'", &ctx));
+        assert!(eval("!b", &ctx));
+        assert!(eval("not (a < 1)", &ctx));
+    }
+
+    #[test]
+    fn test_string_operators() {
+        let ctx = json!({"path": "/home/me/project/.env"});
+        assert!(eval("path endswith '.env'", &ctx));
+        assert!(eval("path startswith '/home'", &ctx));
+        assert!(eval("path contains 'project'", &ctx));
+    }
+
+    #[test]
+    fn test_in_list() {
+        let ctx = json!({"event": "PreToolUse", "count": 3});
+        assert!(eval(r#"event in ["PreToolUse", "PostToolUse"]"#, &ctx));
+        assert!(!eval(r#"event in []"#, &ctx));
+        assert!(eval("count in [1, 2, 3]", &ctx));
+    }
+
+    #[test]
+    fn test_numeric_comparison() {
+        let ctx = json!({"size": 2048});
+        assert!(eval("size > 1024", &ctx));
+        assert!(eval("size >= 2048", &ctx));
+        assert!(!eval("size < -1", &ctx));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Expr::parse("").is_err());
+        assert!(Expr::parse("a = 1").is_err());
+        assert!(Expr::parse("a == 'unterminated").is_err());
+        assert!(Expr::parse("(a == 1").is_err());
+        assert!(Expr::parse("a == 1 b").is_err());
+    }
+
+    #[test]
+    fn test_literal_regex_compiles_at_parse() {
+        assert!(matches!(Expr::parse("a matches 'x+'").unwrap(), Expr::Matches(..)));
+        assert!(Expr::parse("a matches '('").is_err());
+    }
+
+    #[test]
+    fn test_invalid_regex_from_payload_is_eval_error() {
+        let expr = Expr::parse("a matches b").unwrap();
+        assert!(expr.matches(&json!({"a": "x", "b": "("})).is_err());
+        assert!(expr.matches(&json!({"a": "x", "b": "x"})).unwrap());
+    }
+}
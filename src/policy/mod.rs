@@ -0,0 +1,576 @@
+//! Policy-as-code engine
+//!
+//! Policies are rules written in a small expression language (see [`expr`])
+//! and evaluated against hook payloads. The same engine backs:
+//!
+//! - **Hook policies**: block/warn/log any hook event (`PolicyHandler`)
+//! - **Security overrides**: `allow` rules silence the built-in security
+//!   tiers that warn or log; tiers that block can't be overridden
+//! - **Rate limiting**: rules with `rate-limit` only fire once a match has
+//!   been seen more than `max` times within `window-secs`
+//!
+//! Rules come from `policy.rules` in pais.yaml and from per-project
+//! `.pais/policy.yaml` files (found via the payload's `cwd`). Project rules
+//! are evaluated first, so they can override global rules.
+//!
+//! ```yaml
+//! policy:
+//!   rules:
+//!     - name: no-force-push-main
+//!       events: [PreToolUse]
+//!       when: 'tool_name == "Bash" && tool_input.command matches "git push.*--force.*main"'
+//!       action: block
+//!       message: Force pushing to main is not allowed
+//!       suggestion: Push to a branch and open a pull request
+//!     - name: edit-storm
+//!       when: 'tool_name in ["Edit", "Write"]'
+//!       action: warn
+//!       rate-limit:
+//!         max: 50
+//!         window-secs: 60
+//! ```
+
+pub mod expr;
+
+use chrono::Utc;
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::hook::HookEvent;
+use crate::lock::FileLock;
+use expr::Expr;
+
+/// Per-project policy file, relative to the project root
+pub const PROJECT_POLICY_FILE: &str = ".pais/policy.yaml";
+
+/// What to do when a rule matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyEffect {
+    /// Explicitly allow (silences warn and log security tiers)
+    Allow,
+    /// Block the action (exit code 2)
+    Block,
+    /// Warn but allow
+    Warn,
+    /// Log silently and allow
+    Log,
+}
+
+impl fmt::Display for PolicyEffect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyEffect::Allow => write!(f, "allow"),
+            PolicyEffect::Block => write!(f, "block"),
+            PolicyEffect::Warn => write!(f, "warn"),
+            PolicyEffect::Log => write!(f, "log"),
+        }
+    }
+}
+
+/// Rate limit for a rule
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RateLimit {
+    /// Matches allowed within the window before the rule fires
+    pub max: u32,
+    /// Window length in seconds
+    pub window_secs: u64,
+}
+
+/// A single policy rule
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PolicyRule {
+    /// Unique rule name
+    pub name: String,
+
+    /// Events this rule applies to (empty = all events)
+    #[serde(default)]
+    pub events: Vec<String>,
+
+    /// Expression evaluated against the payload
+    pub when: String,
+
+    /// Effect when the expression matches
+    pub action: PolicyEffect,
+
+    /// Message shown when the rule fires
+    #[serde(default)]
+    pub message: Option<String>,
+
+    /// What to do instead, added to the block message so Claude can change
+    /// course rather than retry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+
+    /// Optional rate limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// A policy file (`.pais/policy.yaml`)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct PolicyFile {
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+}
+
+impl PolicyFile {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_yaml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+}
+
+/// Where a rule was loaded from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleSource {
+    /// pais.yaml `policy.rules`
+    Global,
+    /// A project's `.pais/policy.yaml`
+    Project(PathBuf),
+}
+
+impl fmt::Display for RuleSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleSource::Global => write!(f, "global"),
+            RuleSource::Project(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// A rule with its compiled expression
+#[derive(Debug, Clone)]
+pub struct CompiledRule {
+    pub rule: PolicyRule,
+    pub expr: Expr,
+    pub events: Vec<HookEvent>,
+    pub source: RuleSource,
+}
+
+impl CompiledRule {
+    pub fn compile(rule: PolicyRule, source: RuleSource) -> Result<Self> {
+        let expr = Expr::parse(&rule.when).with_context(|| format!("Invalid expression in rule '{}'", rule.name))?;
+
+        let mut events = Vec::new();
+        for name in &rule.events {
+            match HookEvent::from_str(name) {
+                Some(event) => events.push(event),
+                None => eyre::bail!("Unknown event '{}' in rule '{}'", name, rule.name),
+            }
+        }
+
+        Ok(Self {
+            rule,
+            expr,
+            events,
+            source,
+        })
+    }
+
+    /// Check whether this rule applies to an event
+    pub fn applies_to(&self, event: HookEvent) -> bool {
+        self.events.is_empty() || self.events.contains(&event)
+    }
+}
+
+/// Decision produced by the engine
+#[derive(Debug, Clone)]
+pub struct PolicyDecision {
+    pub rule: String,
+    pub effect: PolicyEffect,
+    pub message: String,
+    pub suggestion: Option<String>,
+    pub source: RuleSource,
+}
+
+/// Outcome of evaluating a single rule (for `pais policy eval`)
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleTrace {
+    pub rule: String,
+    pub source: String,
+    pub action: PolicyEffect,
+    pub applies: bool,
+    pub matched: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimit>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Rate limiter state persisted between hook invocations
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct RateState {
+    /// Rule name -> unix timestamps (seconds) of recent matches
+    #[serde(default)]
+    hits: HashMap<String, Vec<i64>>,
+}
+
+/// The policy engine
+#[derive(Debug, Default)]
+pub struct PolicyEngine {
+    rules: Vec<CompiledRule>,
+    state_path: Option<PathBuf>,
+}
+
+impl PolicyEngine {
+    /// Build an engine from global rules, skipping (and logging) invalid ones
+    pub fn new(rules: &[PolicyRule]) -> Self {
+        let mut engine = Self::default();
+        engine.add_rules(rules, RuleSource::Global);
+        engine
+    }
+
+    /// Prepend rules from `<cwd>/.pais/policy.yaml`, if present
+    pub fn with_project_overrides(mut self, cwd: &Path) -> Self {
+        let path = cwd.join(PROJECT_POLICY_FILE);
+        if !path.exists() {
+            return self;
+        }
+
+        match PolicyFile::load(&path) {
+            Ok(file) => {
+                let global = std::mem::take(&mut self.rules);
+                self.add_rules(&file.rules, RuleSource::Project(path));
+                self.rules.extend(global);
+            }
+            Err(e) => log::warn!("Failed to load project policy: {:#}", e),
+        }
+        self
+    }
+
+    /// Persist rate limit counters under this directory
+    pub fn with_state_dir(mut self, dir: &Path) -> Self {
+        self.state_path = Some(dir.join("policy").join("rate-limits.json"));
+        self
+    }
+
+    fn add_rules(&mut self, rules: &[PolicyRule], source: RuleSource) {
+        for rule in rules {
+            match CompiledRule::compile(rule.clone(), source.clone()) {
+                Ok(compiled) => self.rules.push(compiled),
+                Err(e) => log::warn!("Skipping policy rule: {:#}", e),
+            }
+        }
+    }
+
+    /// Check whether any rule applies to an event
+    pub fn handles(&self, event: HookEvent) -> bool {
+        self.rules.iter().any(|r| r.applies_to(event))
+    }
+
+    /// Evaluate rules in order; the first matching rule decides.
+    ///
+    /// Rate-limited rules only decide once their limit is exceeded. Their
+    /// counters are updated under a lock, since hooks of concurrent sessions
+    /// count into the same file.
+    pub fn evaluate(&self, event: HookEvent, payload: &serde_json::Value) -> Option<PolicyDecision> {
+        let context = build_context(event, payload);
+        let limited = self
+            .rules
+            .iter()
+            .any(|r| r.applies_to(event) && r.rule.rate_limit.is_some());
+        let _lock = match self.state_path {
+            Some(ref path) if limited => match FileLock::acquire(path) {
+                Ok(lock) => Some(lock),
+                Err(e) => {
+                    log::warn!("Failed to lock policy state: {:#}", e);
+                    None
+                }
+            },
+            _ => None,
+        };
+        let mut state = if limited { self.load_state() } else { RateState::default() };
+        let now = Utc::now().timestamp();
+        let mut decision = None;
+
+        for compiled in self.rules.iter().filter(|r| r.applies_to(event)) {
+            match compiled.expr.matches(&context) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    log::warn!("Policy rule '{}' failed: {}", compiled.rule.name, e);
+                    continue;
+                }
+            }
+
+            if let Some(ref limit) = compiled.rule.rate_limit {
+                let hits = state.hits.entry(compiled.rule.name.clone()).or_default();
+                hits.retain(|t| now - t < limit.window_secs as i64);
+                hits.push(now);
+                if hits.len() <= limit.max as usize {
+                    continue;
+                }
+            }
+
+            decision = Some(PolicyDecision {
+                rule: compiled.rule.name.clone(),
+                effect: compiled.rule.action,
+                message: compiled
+                    .rule
+                    .message
+                    .clone()
+                    .unwrap_or_else(|| format!("Policy '{}' matched", compiled.rule.name)),
+                suggestion: compiled.rule.suggestion.clone(),
+                source: compiled.source.clone(),
+            });
+            break;
+        }
+
+        if limited {
+            self.save_state(&state);
+        }
+        decision
+    }
+
+    /// Check whether an explicit `allow` rule matches (ignores rate limits, records nothing)
+    pub fn is_allowed(&self, event: HookEvent, payload: &serde_json::Value) -> bool {
+        let context = build_context(event, payload);
+        self.rules
+            .iter()
+            .filter(|r| r.applies_to(event) && r.rule.rate_limit.is_none())
+            .find(|r| r.expr.matches(&context).unwrap_or(false))
+            .is_some_and(|r| r.rule.action == PolicyEffect::Allow)
+    }
+
+    /// Evaluate every rule without side effects (for testing policies)
+    pub fn trace(&self, event: HookEvent, payload: &serde_json::Value) -> Vec<RuleTrace> {
+        let context = build_context(event, payload);
+        self.rules
+            .iter()
+            .map(|r| {
+                let applies = r.applies_to(event);
+                let (matched, error) = if applies {
+                    match r.expr.matches(&context) {
+                        Ok(m) => (m, None),
+                        Err(e) => (false, Some(e.to_string())),
+                    }
+                } else {
+                    (false, None)
+                };
+                RuleTrace {
+                    rule: r.rule.name.clone(),
+                    source: r.source.to_string(),
+                    action: r.rule.action,
+                    applies,
+                    matched,
+                    rate_limit: r.rule.rate_limit.clone(),
+                    error,
+                }
+            })
+            .collect()
+    }
+
+    fn load_state(&self) -> RateState {
+        let Some(ref path) = self.state_path else {
+            return RateState::default();
+        };
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_state(&self, state: &RateState) {
+        let Some(ref path) = self.state_path else {
+            return;
+        };
+        if state.hits.is_empty() && !path.exists() {
+            return;
+        }
+        if let Some(parent) = path.parent()
+            && let Err(e) = fs::create_dir_all(parent)
+        {
+            log::warn!("Failed to create policy state directory: {}", e);
+            return;
+        }
+        match serde_json::to_string(state) {
+            Ok(json) => {
+                if let Err(e) = fs::write(path, json) {
+                    log::warn!("Failed to write policy state: {}", e);
+                }
+            }
+            Err(e) => log::warn!("Failed to serialize policy state: {}", e),
+        }
+    }
+}
+
+/// Build the evaluation context: the payload plus an `event` field
+fn build_context(event: HookEvent, payload: &serde_json::Value) -> serde_json::Value {
+    let mut context = match payload {
+        serde_json::Value::Object(map) => serde_json::Value::Object(map.clone()),
+        other => serde_json::json!({ "payload": other }),
+    };
+    if let Some(map) = context.as_object_mut() {
+        map.entry("event")
+            .or_insert_with(|| serde_json::Value::String(event.to_string()));
+    }
+    context
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn rule(name: &str, when: &str, action: PolicyEffect) -> PolicyRule {
+        PolicyRule {
+            name: name.to_string(),
+            events: Vec::new(),
+            when: when.to_string(),
+            action,
+            message: None,
+            suggestion: None,
+            rate_limit: None,
+        }
+    }
+
+    fn bash(command: &str) -> serde_json::Value {
+        json!({"tool_name": "Bash", "tool_input": {"command": command}})
+    }
+
+    #[test]
+    fn test_first_match_wins() {
+        let engine = PolicyEngine::new(&[
+            rule("allow-ls", r#"tool_input.command == "ls""#, PolicyEffect::Allow),
+            rule("block-bash", r#"tool_name == "Bash""#, PolicyEffect::Block),
+        ]);
+
+        let decision = engine.evaluate(HookEvent::PreToolUse, &bash("ls")).unwrap();
+        assert_eq!(decision.rule, "allow-ls");
+        assert_eq!(decision.effect, PolicyEffect::Allow);
+
+        let decision = engine.evaluate(HookEvent::PreToolUse, &bash("pwd")).unwrap();
+        assert_eq!(decision.effect, PolicyEffect::Block);
+    }
+
+    #[test]
+    fn test_event_filter_and_context() {
+        let mut r = rule("stop-only", r#"event == "Stop""#, PolicyEffect::Log);
+        r.events = vec!["stop".to_string()];
+        let engine = PolicyEngine::new(&[r]);
+
+        assert!(engine.handles(HookEvent::Stop));
+        assert!(!engine.handles(HookEvent::PreToolUse));
+        assert!(engine.evaluate(HookEvent::Stop, &json!({})).is_some());
+        assert!(engine.evaluate(HookEvent::PreToolUse, &json!({})).is_none());
+    }
+
+    #[test]
+    fn test_invalid_rules_are_skipped() {
+        let mut bad_event = rule("bad-event", "true", PolicyEffect::Block);
+        bad_event.events = vec!["NotAnEvent".to_string()];
+        let engine = PolicyEngine::new(&[
+            rule("bad-expr", "a = 1", PolicyEffect::Block),
+            rule("bad-regex", "tool_input.command matches '(git'", PolicyEffect::Block),
+            bad_event,
+        ]);
+        assert!(engine.rules.is_empty());
+    }
+
+    #[test]
+    fn test_rate_limit_fires_after_max() {
+        let temp = TempDir::new().unwrap();
+        let mut r = rule("edits", r#"tool_name == "Edit""#, PolicyEffect::Warn);
+        r.rate_limit = Some(RateLimit {
+            max: 2,
+            window_secs: 60,
+        });
+        let engine = PolicyEngine::new(&[r]).with_state_dir(temp.path());
+        let payload = json!({"tool_name": "Edit"});
+
+        assert!(engine.evaluate(HookEvent::PreToolUse, &payload).is_none());
+        assert!(engine.evaluate(HookEvent::PreToolUse, &payload).is_none());
+        let decision = engine.evaluate(HookEvent::PreToolUse, &payload).unwrap();
+        assert_eq!(decision.effect, PolicyEffect::Warn);
+        assert!(temp.path().join("policy").join("rate-limits.json").exists());
+    }
+
+    #[test]
+    fn test_concurrent_rate_limit_counts_every_match() {
+        let temp = TempDir::new().unwrap();
+        let mut r = rule("edits", r#"tool_name == "Edit""#, PolicyEffect::Warn);
+        r.rate_limit = Some(RateLimit {
+            max: 1000,
+            window_secs: 600,
+        });
+        let engine = PolicyEngine::new(&[r]).with_state_dir(temp.path());
+        let payload = json!({"tool_name": "Edit"});
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..10 {
+                        engine.evaluate(HookEvent::PreToolUse, &payload);
+                    }
+                });
+            }
+        });
+        assert_eq!(engine.load_state().hits["edits"].len(), 80);
+    }
+
+    #[test]
+    fn test_project_overrides_take_precedence() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join(".pais")).unwrap();
+        fs::write(
+            temp.path().join(PROJECT_POLICY_FILE),
+            "rules:\n  - name: allow-force\n    when: 'tool_input.command contains \"--force\"'\n    action: allow\n",
+        )
+        .unwrap();
+
+        let engine = PolicyEngine::new(&[rule(
+            "block-force",
+            r#"tool_input.command contains "--force""#,
+            PolicyEffect::Block,
+        )])
+        .with_project_overrides(temp.path());
+
+        assert_eq!(engine.rules.len(), 2);
+        assert!(matches!(engine.rules[0].source, RuleSource::Project(_)));
+        assert!(engine.is_allowed(HookEvent::PreToolUse, &bash("git push --force")));
+    }
+
+    #[test]
+    fn test_trace_has_no_side_effects() {
+        let temp = TempDir::new().unwrap();
+        let mut r = rule("limited", "true", PolicyEffect::Block);
+        r.rate_limit = Some(RateLimit {
+            max: 0,
+            window_secs: 60,
+        });
+        let engine = PolicyEngine::new(&[r]).with_state_dir(temp.path());
+
+        let traces = engine.trace(HookEvent::PreToolUse, &json!({}));
+        assert_eq!(traces.len(), 1);
+        assert!(traces[0].matched);
+        assert!(!temp.path().join("policy").exists());
+    }
+
+    #[test]
+    fn test_parse_policy_file_kebab_case() {
+        let yaml = r#"
+rules:
+  - name: edits
+    events: [PreToolUse]
+    when: 'tool_name == "Edit"'
+    action: warn
+    message: Slow down
+    rate-limit:
+      max: 10
+      window-secs: 30
+"#;
+        let file: PolicyFile = serde_yaml::from_str(yaml).unwrap();
+        let limit = file.rules[0].rate_limit.as_ref().unwrap();
+        assert_eq!(limit.max, 10);
+        assert_eq!(limit.window_secs, 30);
+        assert_eq!(file.rules[0].action, PolicyEffect::Warn);
+    }
+}
@@ -0,0 +1,356 @@
+//! Terminal output: themes, symbols, rules and tables
+//!
+//! Commands print status markers, section rules and tables through this
+//! module instead of hardcoding colors and Unicode, so output follows the
+//! `ui:` config section:
+//!
+//! - `theme`: `default`, `colorblind` or `mono`. `NO_COLOR` (honored by the
+//!   `colored` crate) turns color off whatever the theme.
+//! - `charset`: `unicode`, `ascii`, or `auto`, which falls back to ASCII when
+//!   the locale isn't UTF-8 or the terminal is the Linux console.
+//!
+//! Widths are measured in terminal columns rather than bytes or chars, so
+//! tables stay aligned around wide (CJK, emoji) text.
+
+use colored::*;
+use std::sync::OnceLock;
+use terminal_size::{Width, terminal_size};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::config::{Charset, Theme, UiConfig};
+
+/// Width of section rules, matching the context injection banners
+const RULE_WIDTH: usize = 59;
+
+/// Narrowest a wrapped table column gets, however small the terminal
+const MIN_WRAP_WIDTH: usize = 20;
+
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy)]
+struct Settings {
+    theme: Theme,
+    ascii: bool,
+}
+
+impl Settings {
+    fn resolve(ui: &UiConfig) -> Self {
+        let ascii = match ui.charset {
+            Charset::Ascii => true,
+            Charset::Unicode => false,
+            Charset::Auto => !unicode_terminal(|var| std::env::var(var).ok()),
+        };
+        Self { theme: ui.theme, ascii }
+    }
+}
+
+/// Apply the `ui:` config; output before this uses the defaults
+pub fn init(ui: &UiConfig) {
+    let _ = SETTINGS.set(Settings::resolve(ui));
+}
+
+fn settings() -> Settings {
+    *SETTINGS.get_or_init(|| Settings::resolve(&UiConfig::default()))
+}
+
+/// Whether the environment can display Unicode symbols
+fn unicode_terminal(var: impl Fn(&str) -> Option<String>) -> bool {
+    if var("TERM").is_some_and(|term| term == "linux" || term == "dumb") {
+        return false;
+    }
+    // The first locale variable that is set decides, as in setlocale(3)
+    for name in ["LC_ALL", "LC_CTYPE", "LANG"] {
+        if let Some(value) = var(name).filter(|v| !v.is_empty()) {
+            let value = value.to_lowercase();
+            return value.contains("utf-8") || value.contains("utf8");
+        }
+    }
+    true
+}
+
+/// Whether output is limited to ASCII
+pub fn ascii() -> bool {
+    settings().ascii
+}
+
+/// Pick the Unicode or ASCII form of a symbol
+pub fn symbol(unicode: &'static str, ascii: &'static str) -> &'static str {
+    if settings().ascii { ascii } else { unicode }
+}
+
+/// Meaning of highlighted text, mapped to a color by the theme
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Success,
+    Error,
+    Warning,
+    Info,
+}
+
+/// Style `text` for `role` in the configured theme
+pub fn paint(role: Role, text: &str) -> ColoredString {
+    paint_with(settings().theme, role, text)
+}
+
+fn paint_with(theme: Theme, role: Role, text: &str) -> ColoredString {
+    match (theme, role) {
+        (Theme::Default, Role::Success) => text.green(),
+        (Theme::Default, Role::Error) => text.red(),
+        (Theme::Default, Role::Warning) => text.yellow(),
+        (Theme::Default, Role::Info) => text.blue(),
+        (Theme::Colorblind, Role::Success) => text.blue(),
+        (Theme::Colorblind, Role::Error) => text.magenta().bold(),
+        (Theme::Colorblind, Role::Warning) => text.yellow(),
+        (Theme::Colorblind, Role::Info) => text.cyan(),
+        (Theme::Mono, Role::Error | Role::Warning) => text.bold(),
+        (Theme::Mono, Role::Success | Role::Info) => text.normal(),
+    }
+}
+
+/// Success marker (✓)
+pub fn ok() -> ColoredString {
+    paint(Role::Success, symbol("✓", "ok"))
+}
+
+/// Failure marker (✗)
+pub fn fail() -> ColoredString {
+    paint(Role::Error, symbol("✗", "x"))
+}
+
+/// Warning marker (⚠)
+pub fn warn() -> ColoredString {
+    paint(Role::Warning, symbol("⚠", "!"))
+}
+
+/// Informational marker (ℹ)
+pub fn info() -> ColoredString {
+    paint(Role::Info, symbol("ℹ", "i"))
+}
+
+/// Step or action marker (→)
+pub fn arrow() -> ColoredString {
+    paint(Role::Info, symbol("→", "->"))
+}
+
+/// A heavy horizontal rule
+pub fn rule() -> String {
+    symbol("═", "=").repeat(RULE_WIDTH)
+}
+
+/// A title centered between two rules
+pub fn banner(title: &str) -> String {
+    let pad = RULE_WIDTH.saturating_sub(display_width(title)) / 2;
+    format!("{}\n{}{}\n{}", rule(), " ".repeat(pad), title, rule())
+}
+
+/// Terminal width in columns, 80 when not a terminal
+pub fn terminal_width() -> usize {
+    terminal_size().map(|(Width(w), _)| w as usize).unwrap_or(80)
+}
+
+/// Columns `s` occupies in a terminal
+pub fn display_width(s: &str) -> usize {
+    UnicodeWidthStr::width(s)
+}
+
+/// Cut `s` to at most `max` columns, marking the cut with an ellipsis
+pub fn truncate(s: &str, max: usize) -> String {
+    if display_width(s) <= max {
+        return s.to_string();
+    }
+    let ellipsis = symbol("…", "...");
+    let budget = max.saturating_sub(display_width(ellipsis));
+    let mut out = String::new();
+    let mut used = 0;
+    for c in s.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w > budget {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    out.push_str(ellipsis);
+    out
+}
+
+/// Word-wrap `s` to lines of at most `max_width` columns
+///
+/// Words longer than a line are kept whole on a line of their own.
+pub fn wrap(s: &str, max_width: usize) -> Vec<String> {
+    if max_width == 0 {
+        return vec![s.to_string()];
+    }
+
+    let mut lines = Vec::new();
+    let mut current_line = String::new();
+    let mut current_width = 0;
+
+    for word in s.split_whitespace() {
+        let word_width = display_width(word);
+
+        if current_width == 0 {
+            current_line = word.to_string();
+            current_width = word_width;
+        } else if current_width + 1 + word_width <= max_width {
+            current_line.push(' ');
+            current_line.push_str(word);
+            current_width += 1 + word_width;
+        } else {
+            lines.push(std::mem::take(&mut current_line));
+            current_line = word.to_string();
+            current_width = word_width;
+        }
+    }
+
+    if !current_line.is_empty() || lines.is_empty() {
+        lines.push(current_line);
+    }
+
+    lines
+}
+
+/// Left-align a styled cell in `width` columns
+fn pad(cell: &ColoredString, width: usize) -> String {
+    let fill = width.saturating_sub(display_width(&cell.input));
+    format!("{}{}", cell, " ".repeat(fill))
+}
+
+/// A table whose last column wraps to the terminal width
+///
+/// Cells carry their own styling; alignment uses the unstyled text.
+#[derive(Debug)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<ColoredString>>,
+}
+
+impl Table {
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Add a row with one cell per header
+    pub fn add_row(&mut self, cells: Vec<ColoredString>) {
+        debug_assert_eq!(cells.len(), self.headers.len(), "row width must match the headers");
+        self.rows.push(cells);
+    }
+
+    /// Lines of the table laid out for a terminal `width` columns wide
+    pub fn render(&self, width: usize) -> Vec<String> {
+        let fixed = self.headers.len().saturating_sub(1);
+        let widths: Vec<usize> = (0..fixed)
+            .map(|col| {
+                self.rows
+                    .iter()
+                    .map(|row| display_width(&row[col].input))
+                    .chain([display_width(&self.headers[col])])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let fixed_width: usize = widths.iter().map(|w| w + 2).sum();
+        let wrap_width = width.saturating_sub(fixed_width).max(MIN_WRAP_WIDTH);
+        let indent = " ".repeat(fixed_width);
+
+        let mut lines = Vec::with_capacity(self.rows.len() + 1);
+        let header: Vec<ColoredString> = self.headers.iter().map(|h| h.as_str().bold()).collect();
+        lines.push(self.line(&header[..fixed], &widths, &header[fixed].to_string()));
+
+        for row in &self.rows {
+            let last = &row[fixed];
+            let wrapped = wrap(&last.input, wrap_width);
+            let restyle = |text: &str| {
+                let mut styled = last.clone();
+                styled.input = text.to_string();
+                styled.to_string()
+            };
+            lines.push(self.line(&row[..fixed], &widths, &restyle(&wrapped[0])));
+            for continuation in &wrapped[1..] {
+                lines.push(format!("{}{}", indent, restyle(continuation)));
+            }
+        }
+        lines
+    }
+
+    fn line(&self, cells: &[ColoredString], widths: &[usize], last: &str) -> String {
+        let mut line: String = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, &width)| format!("{}  ", pad(cell, width)))
+            .collect();
+        line.push_str(last);
+        line
+    }
+
+    /// Print the table sized to the terminal
+    pub fn print(&self) {
+        for line in self.render(terminal_width()) {
+            println!("{}", line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: Vec<(String, String)> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        move |name| vars.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone())
+    }
+
+    #[test]
+    fn test_unicode_terminal_detection() {
+        assert!(unicode_terminal(env(&[])));
+        assert!(unicode_terminal(env(&[("LANG", "en_US.UTF-8")])));
+        assert!(!unicode_terminal(env(&[("LANG", "C")])));
+        // LC_ALL overrides LANG; empty values are skipped
+        assert!(!unicode_terminal(env(&[("LC_ALL", "POSIX"), ("LANG", "en_US.UTF-8")])));
+        assert!(unicode_terminal(env(&[("LC_ALL", ""), ("LANG", "de_DE.utf8")])));
+        assert!(!unicode_terminal(env(&[("TERM", "linux"), ("LANG", "en_US.UTF-8")])));
+    }
+
+    #[test]
+    fn test_wrap_and_truncate_measure_columns() {
+        // Each CJK character is two columns wide
+        assert_eq!(wrap("日本語 テキスト 表示", 10), vec!["日本語", "テキスト", "表示"]);
+        assert_eq!(wrap("one two three", 7), vec!["one two", "three"]);
+        assert_eq!(wrap("", 10), vec![""]);
+
+        assert_eq!(truncate("short", 10), "short");
+        let cut = truncate("日本語テキスト", 9);
+        assert!(display_width(&cut) <= 9, "{}", cut);
+        assert!(cut.starts_with("日本語"));
+    }
+
+    #[test]
+    fn test_table_aligns_wide_text() {
+        colored::control::set_override(false);
+        let mut table = Table::new(&["NAME", "SOURCE", "DESCRIPTION"]);
+        table.add_row(vec!["日本".normal(), "simple".normal(), "first skill".normal()]);
+        table.add_row(vec![
+            "writer".normal(),
+            "plugin:x".normal(),
+            "a description long enough to wrap onto more lines".normal(),
+        ]);
+        let lines = table.render(40);
+
+        assert_eq!(lines[0], "NAME    SOURCE    DESCRIPTION");
+        assert_eq!(lines[1], "日本    simple    first skill");
+        assert!(lines[2].starts_with("writer  plugin:x  a description"));
+        // Continuations are indented under the description column
+        assert!(lines[3].starts_with(&" ".repeat(18)));
+        assert!(lines.iter().skip(2).all(|l| display_width(l) <= 40 || !l.contains(' ')));
+    }
+
+    #[test]
+    fn test_mono_theme_has_no_colors() {
+        assert!(paint_with(Theme::Mono, Role::Success, "ok").fgcolor.is_none());
+        assert!(paint_with(Theme::Mono, Role::Error, "x").fgcolor.is_none());
+        assert!(paint_with(Theme::Colorblind, Role::Success, "ok").fgcolor.is_some());
+    }
+}
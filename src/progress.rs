@@ -0,0 +1,63 @@
+//! Progress bars and spinners for long operations
+//!
+//! Drawn on stderr, and only when stderr is a terminal and the output format
+//! is text. Otherwise the returned bar is hidden, so JSON/YAML on stdout and
+//! redirected output stay clean. Print through [`ProgressBar::suspend`] while
+//! a bar is active so lines are not garbled (and still print when hidden).
+
+use indicatif::{ProgressBar, ProgressStyle};
+use std::borrow::Cow;
+use std::io::IsTerminal;
+use std::time::Duration;
+
+use crate::cli::OutputFormat;
+
+const TICK: Duration = Duration::from_millis(100);
+
+/// Whether progress should be drawn for this output format
+pub fn enabled(format: OutputFormat) -> bool {
+    format == OutputFormat::Text && std::io::stderr().is_terminal()
+}
+
+/// Spinner for work of unknown length
+pub fn spinner(format: OutputFormat, message: impl Into<Cow<'static, str>>) -> ProgressBar {
+    if !enabled(format) {
+        return ProgressBar::hidden();
+    }
+
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(ProgressStyle::with_template("{spinner:.cyan} {msg} {elapsed:.dim}").expect("valid template"));
+    pb.set_message(message);
+    pb.enable_steady_tick(TICK);
+    pb
+}
+
+/// Bar for `len` discrete steps
+pub fn bar(format: OutputFormat, len: u64, message: impl Into<Cow<'static, str>>) -> ProgressBar {
+    if !enabled(format) {
+        return ProgressBar::hidden();
+    }
+
+    let pb = ProgressBar::new(len);
+    pb.set_style(
+        ProgressStyle::with_template("{spinner:.cyan} [{bar:30.cyan/blue}] {pos}/{len} {msg}")
+            .expect("valid template")
+            .progress_chars("=> "),
+    );
+    pb.set_message(message);
+    pb.enable_steady_tick(TICK);
+    pb
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_machine_formats_hide_progress() {
+        assert!(!enabled(OutputFormat::Json));
+        assert!(!enabled(OutputFormat::Yaml));
+        assert!(bar(OutputFormat::Json, 3, "work").is_hidden());
+        assert!(spinner(OutputFormat::Yaml, "work").is_hidden());
+    }
+}
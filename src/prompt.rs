@@ -0,0 +1,223 @@
+//! Cached state for shell prompt segments
+//!
+//! `pais status --prompt` runs on every shell prompt, so it must not load
+//! config, scan skills or read logs. Instead, hook handlers keep a tiny JSON
+//! file up to date as sessions start/end and actions get blocked, and the
+//! prompt command only reads and formats that file.
+//!
+//! ```toml
+//! # starship.toml
+//! [custom.pais]
+//! command = "pais status --prompt"
+//! when = true
+//! ```
+
+use chrono::{Local, NaiveDate, Utc};
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::lock::FileLock;
+
+/// Environment variable `pais session` sets for the Claude process it launches
+pub const PROFILE_ENV: &str = "PAIS_PROFILE";
+
+/// Sessions without a SessionEnd after this long are assumed dead
+const STALE_SESSION_SECS: i64 = 24 * 60 * 60;
+
+/// A running Claude session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionEntry {
+    /// Unix timestamp of SessionStart
+    pub started: i64,
+    /// Profile `pais session` launched with, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
+/// Everything a prompt segment needs, persisted between hook invocations
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PromptState {
+    #[serde(default)]
+    pub sessions: BTreeMap<String, SessionEntry>,
+    /// Day the block counter belongs to
+    #[serde(default)]
+    pub blocks_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub blocks: u32,
+}
+
+/// Summary printed by `pais status --prompt`
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct PromptSummary {
+    pub profile: Option<String>,
+    pub sessions: usize,
+    pub blocks_today: u32,
+}
+
+/// Location of the state file (`~/.cache/pais/prompt.json`)
+pub fn state_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|d| d.join("pais").join("prompt.json"))
+}
+
+impl PromptState {
+    /// Load state, treating a missing or unreadable file as empty
+    pub fn load(path: &Path) -> Self {
+        fs::read(path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write state atomically so a concurrent prompt never sees a partial file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let tmp = path.with_extension(format!("json.{}", std::process::id()));
+        fs::write(&tmp, serde_json::to_vec(self)?).with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path.display()))?;
+        Ok(())
+    }
+
+    pub fn session_started(&mut self, session_id: &str, profile: Option<String>, now: i64) {
+        self.sessions.retain(|_, s| now - s.started < STALE_SESSION_SECS);
+        self.sessions
+            .insert(session_id.to_string(), SessionEntry { started: now, profile });
+    }
+
+    pub fn session_ended(&mut self, session_id: &str) {
+        self.sessions.remove(session_id);
+    }
+
+    pub fn record_block(&mut self, today: NaiveDate) {
+        if self.blocks_date != Some(today) {
+            self.blocks_date = Some(today);
+            self.blocks = 0;
+        }
+        self.blocks += 1;
+    }
+
+    pub fn summary(&self, now: i64, today: NaiveDate) -> PromptSummary {
+        let live: Vec<&SessionEntry> = self
+            .sessions
+            .values()
+            .filter(|s| now - s.started < STALE_SESSION_SECS)
+            .collect();
+
+        // The most recently started session decides the active profile
+        let profile = live.iter().max_by_key(|s| s.started).and_then(|s| s.profile.clone());
+
+        PromptSummary {
+            profile,
+            sessions: live.len(),
+            blocks_today: if self.blocks_date == Some(today) { self.blocks } else { 0 },
+        }
+    }
+}
+
+impl PromptSummary {
+    /// Compact prompt segment; empty when nothing is running and nothing was blocked
+    pub fn render(&self) -> String {
+        let mut parts = Vec::new();
+        if self.sessions > 0 {
+            match self.profile {
+                Some(ref profile) => parts.push(format!("{} ●{}", profile, self.sessions)),
+                None => parts.push(format!("●{}", self.sessions)),
+            }
+        }
+        if self.blocks_today > 0 {
+            parts.push(format!("✗{}", self.blocks_today));
+        }
+        parts.join(" ")
+    }
+}
+
+/// Read the cached summary for right now
+pub fn current_summary() -> PromptSummary {
+    let state = state_path().map(|p| PromptState::load(&p)).unwrap_or_default();
+    state.summary(Utc::now().timestamp(), Local::now().date_naive())
+}
+
+/// Apply a change to the state file, logging (not failing) on error
+///
+/// Used from hooks, which must never fail because a prompt cache could not be written.
+pub fn update<F>(change: F)
+where
+    F: FnOnce(&mut PromptState),
+{
+    let Some(path) = state_path() else {
+        return;
+    };
+    // Hooks of concurrent sessions update it too
+    let _lock = match FileLock::acquire(&path) {
+        Ok(lock) => lock,
+        Err(e) => {
+            log::warn!("Failed to update prompt state: {:#}", e);
+            return;
+        }
+    };
+    let mut state = PromptState::load(&path);
+    change(&mut state);
+    if let Err(e) = state.save(&path) {
+        log::warn!("Failed to update prompt state: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 1, d).unwrap()
+    }
+
+    #[test]
+    fn test_idle_renders_empty() {
+        let state = PromptState::default();
+        assert_eq!(state.summary(0, day(1)).render(), "");
+    }
+
+    #[test]
+    fn test_sessions_and_profile() {
+        let mut state = PromptState::default();
+        state.session_started("a", Some("work".to_string()), 100);
+        state.session_started("b", Some("minimal".to_string()), 200);
+        assert_eq!(state.summary(300, day(1)).render(), "minimal ●2");
+
+        state.session_ended("b");
+        assert_eq!(state.summary(300, day(1)).render(), "work ●1");
+
+        // Sessions that never ended stop counting once stale
+        assert_eq!(state.summary(100 + STALE_SESSION_SECS, day(1)).sessions, 0);
+    }
+
+    #[test]
+    fn test_blocks_reset_daily() {
+        let mut state = PromptState::default();
+        state.record_block(day(1));
+        state.record_block(day(1));
+        assert_eq!(state.summary(0, day(1)).render(), "✗2");
+        assert_eq!(state.summary(0, day(2)).blocks_today, 0);
+
+        state.record_block(day(2));
+        assert_eq!(state.blocks, 1);
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let temp = tempdir().unwrap();
+        let path = temp.path().join("nested").join("prompt.json");
+        assert!(PromptState::load(&path).sessions.is_empty());
+
+        let mut state = PromptState::default();
+        state.session_started("a", None, 10);
+        state.save(&path).unwrap();
+
+        let loaded = PromptState::load(&path);
+        assert_eq!(loaded.summary(20, day(1)).render(), "●1");
+    }
+}
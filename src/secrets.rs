@@ -0,0 +1,188 @@
+//! Secret lookup
+//!
+//! Secrets come from the environment first, then from `~/.config/pais/.env`
+//! (`KEY=value` lines, git-ignored by `pais init`). Config files only ever
+//! name a secret, never contain it. Plugins may also read passwords from the
+//! OS keyring, through `security` on macOS and `secret-tool` elsewhere.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::config::Config;
+use crate::error::{CliError, ErrorCode};
+
+/// Secrets from the environment and a dotenv file
+#[derive(Debug, Default)]
+pub struct SecretStore {
+    file: HashMap<String, String>,
+    path: Option<PathBuf>,
+}
+
+impl SecretStore {
+    /// Load `.env` from the PAIS directory
+    pub fn load() -> Self {
+        Self::from_file(&Config::pais_dir().join(".env"))
+    }
+
+    /// Load a specific dotenv file; a missing file is empty
+    pub fn from_file(path: &Path) -> Self {
+        let file = fs::read_to_string(path).map(|c| parse_dotenv(&c)).unwrap_or_default();
+        Self {
+            file,
+            path: Some(path.to_path_buf()),
+        }
+    }
+
+    /// Look up a secret, environment first
+    pub fn get(&self, name: &str) -> Option<String> {
+        std::env::var(name)
+            .ok()
+            .or_else(|| self.file.get(name).cloned())
+            .filter(|v| !v.is_empty())
+    }
+
+    /// Look up a secret that must exist
+    pub fn require(&self, name: &str) -> eyre::Result<String> {
+        self.get(name).ok_or_else(|| self.missing(name).into())
+    }
+
+    /// The error for a secret that isn't set
+    pub fn missing(&self, name: &str) -> CliError {
+        let location = self
+            .path
+            .as_ref()
+            .map(|p| format!(" or add it to {}", p.display()))
+            .unwrap_or_default();
+        CliError::new(ErrorCode::Config, format!("Secret {} is not set", name))
+            .with_hint(format!("Export {}{}", name, location))
+    }
+}
+
+/// Keyring service pais entries are stored under
+pub const KEYRING_SERVICE: &str = "pais";
+
+/// Shorter secrets aren't masked; they'd match all over ordinary output
+const MIN_REDACTED_LEN: usize = 4;
+
+/// Read the password stored for `account` in the OS keyring
+pub fn keyring(account: &str) -> Result<String, CliError> {
+    let (program, args) = if cfg!(target_os = "macos") {
+        (
+            "security",
+            vec!["find-generic-password", "-s", KEYRING_SERVICE, "-a", account, "-w"],
+        )
+    } else {
+        (
+            "secret-tool",
+            vec!["lookup", "service", KEYRING_SERVICE, "account", account],
+        )
+    };
+    let output = Command::new(program).args(args).stdin(Stdio::null()).output();
+    let missing = || {
+        CliError::new(
+            ErrorCode::Config,
+            format!("Keyring has no '{}' entry for {}", KEYRING_SERVICE, account),
+        )
+        .with_hint(format!("Store it with: {}", keyring_store_command(account)))
+    };
+    match output {
+        Ok(output) if output.status.success() => {
+            let password = String::from_utf8_lossy(&output.stdout)
+                .trim_end_matches(['\r', '\n'])
+                .to_string();
+            if password.is_empty() { Err(missing()) } else { Ok(password) }
+        }
+        Ok(_) => Err(missing()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(CliError::new(
+            ErrorCode::Config,
+            format!("Can't read keyring entry {}: {} is not installed", account, program),
+        )
+        .with_hint("Install libsecret-tools (secret-tool), or use a dotenv source instead")),
+        Err(e) => Err(CliError::new(
+            ErrorCode::Io,
+            format!("Failed to run {} for keyring entry {}: {}", program, account, e),
+        )),
+    }
+}
+
+/// Command that stores a password for `account` in the OS keyring
+pub fn keyring_store_command(account: &str) -> String {
+    if cfg!(target_os = "macos") {
+        format!("security add-generic-password -s {} -a {} -w", KEYRING_SERVICE, account)
+    } else {
+        format!(
+            "secret-tool store --label='{0} {1}' service {0} account {1}",
+            KEYRING_SERVICE, account
+        )
+    }
+}
+
+/// `text` with each of `secrets` replaced by `********`
+pub fn redact(text: &str, secrets: &[String]) -> String {
+    let mut secrets: Vec<&String> = secrets.iter().filter(|s| s.len() >= MIN_REDACTED_LEN).collect();
+    // Longest first, so a secret containing another is masked whole
+    secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+    secrets.into_iter().fold(text.to_string(), |text, secret| {
+        text.replace(secret.as_str(), "********")
+    })
+}
+
+/// Parse `KEY=value` lines, allowing comments, `export` and quoted values
+fn parse_dotenv(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+                .unwrap_or(value);
+            Some((key.trim().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_dotenv() {
+        let vars = parse_dotenv("# comment\nexport A=1\nB = \"two words\"\nC='x=y'\n\nnot a pair\n");
+        assert_eq!(vars.get("A").unwrap(), "1");
+        assert_eq!(vars.get("B").unwrap(), "two words");
+        assert_eq!(vars.get("C").unwrap(), "x=y");
+        assert_eq!(vars.len(), 3);
+    }
+
+    #[test]
+    fn test_require_reports_missing_secret() {
+        let temp = tempdir().unwrap();
+        let path = temp.path().join(".env");
+        fs::write(&path, "PAIS_TEST_SECRET_FROM_FILE=abc\n").unwrap();
+
+        let store = SecretStore::from_file(&path);
+        assert_eq!(store.get("PAIS_TEST_SECRET_FROM_FILE").unwrap(), "abc");
+
+        let err = store.require("PAIS_TEST_SECRET_MISSING").unwrap_err();
+        assert!(err.to_string().contains("PAIS_TEST_SECRET_MISSING"));
+    }
+
+    #[test]
+    fn test_redact() {
+        let secrets = vec!["ghp_abc123".to_string(), "ghp_abc123xyz".to_string(), "no".to_string()];
+        assert_eq!(
+            redact("token=ghp_abc123xyz, old ghp_abc123; no change", &secrets),
+            "token=********, old ********; no change"
+        );
+        assert_eq!(redact("plain", &[]), "plain");
+    }
+}
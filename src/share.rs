@@ -0,0 +1,379 @@
+//! Share skills and agents as GitHub gists
+//!
+//! `pais skill share` / `pais agent share` upload the artifact's files to a
+//! gist together with a small `pais-share.yaml` manifest. The gist URL is the
+//! install URL: `pais skill install <url>` / `pais agent install <url>` fetch
+//! the gist, check the manifest and write the files back in place.
+//!
+//! Gists are flat, so nested paths (`workflows/deploy.md`) are stored under
+//! flattened names and the manifest maps them back.
+
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path};
+use std::process::Command;
+use walkdir::WalkDir;
+
+use crate::error::{CliError, ErrorCode};
+
+/// Name of the manifest file inside a shared gist
+pub const MANIFEST_FILE: &str = "pais-share.yaml";
+
+const GIST_API: &str = "https://api.github.com/gists";
+
+/// What a gist contains
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ShareKind {
+    Skill,
+    Agent,
+}
+
+impl std::fmt::Display for ShareKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShareKind::Skill => write!(f, "skill"),
+            ShareKind::Agent => write!(f, "agent"),
+        }
+    }
+}
+
+/// `pais-share.yaml`: what was shared and where each gist file belongs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ShareManifest {
+    pub kind: ShareKind,
+    pub name: String,
+    /// Gist file name -> path relative to the install location
+    pub files: BTreeMap<String, String>,
+}
+
+/// A shared skill or agent: manifest plus file contents keyed by relative path
+#[derive(Debug, Clone)]
+pub struct Artifact {
+    pub manifest: ShareManifest,
+    pub files: BTreeMap<String, String>,
+    /// Where a fetched artifact came from (`None` for local ones)
+    pub origin: Option<GistOrigin>,
+}
+
+/// The gist an artifact was fetched from
+#[derive(Debug, Clone)]
+pub struct GistOrigin {
+    pub url: String,
+    /// Gist revision (commit) that was fetched
+    pub revision: Option<String>,
+    pub owner: Option<String>,
+}
+
+/// A created gist
+#[derive(Debug, Clone, Serialize)]
+pub struct Shared {
+    pub id: String,
+    pub url: String,
+    /// Command that installs the shared artifact
+    pub install: String,
+}
+
+impl Artifact {
+    /// Collect the text files under a skill directory (hidden files skipped)
+    pub fn from_dir(kind: ShareKind, name: &str, dir: &Path) -> Result<Self> {
+        let mut files = BTreeMap::new();
+        let walker = WalkDir::new(dir)
+            .follow_links(true)
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'));
+        for entry in walker {
+            let entry = entry.with_context(|| format!("Failed to walk {}", dir.display()))?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let rel = entry.path().strip_prefix(dir).unwrap_or(entry.path());
+            let rel = rel.to_string_lossy().replace('\\', "/");
+            match fs::read_to_string(entry.path()) {
+                Ok(content) => {
+                    files.insert(rel, content);
+                }
+                Err(_) => log::warn!("Skipping non-text file: {}", entry.path().display()),
+            }
+        }
+        Ok(Self::new(kind, name, files))
+    }
+
+    /// A single-file artifact such as an agent YAML
+    pub fn from_file(kind: ShareKind, name: &str, path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| format!("{}.yaml", name));
+        Ok(Self::new(kind, name, BTreeMap::from([(file_name, content)])))
+    }
+
+    fn new(kind: ShareKind, name: &str, files: BTreeMap<String, String>) -> Self {
+        let mapping = files.keys().map(|rel| (gist_file_name(rel), rel.clone())).collect();
+        Self {
+            manifest: ShareManifest {
+                kind,
+                name: name.to_string(),
+                files: mapping,
+            },
+            files,
+            origin: None,
+        }
+    }
+
+    /// Gist files (including the manifest) keyed by gist file name
+    pub fn gist_files(&self) -> Result<BTreeMap<String, String>> {
+        let mut out: BTreeMap<String, String> = self
+            .manifest
+            .files
+            .iter()
+            .filter_map(|(gist_name, rel)| self.files.get(rel).map(|c| (gist_name.clone(), c.clone())))
+            .collect();
+        out.insert(MANIFEST_FILE.to_string(), serde_yaml::to_string(&self.manifest)?);
+        Ok(out)
+    }
+
+    /// Rebuild an artifact from gist files, rejecting paths that escape the install location
+    pub fn from_gist_files(mut gist_files: BTreeMap<String, String>) -> Result<Self> {
+        let manifest_yaml = gist_files.remove(MANIFEST_FILE).ok_or_else(|| {
+            CliError::new(
+                ErrorCode::InvalidInput,
+                format!("Gist has no {}; it was not shared with pais", MANIFEST_FILE),
+            )
+        })?;
+        let manifest: ShareManifest =
+            serde_yaml::from_str(&manifest_yaml).with_context(|| format!("Failed to parse {}", MANIFEST_FILE))?;
+
+        if !is_safe_relative(Path::new(&manifest.name)) || manifest.name.contains('/') {
+            eyre::bail!("Invalid {} name in gist: {}", manifest.kind, manifest.name);
+        }
+
+        let mut files = BTreeMap::new();
+        for (gist_name, rel) in &manifest.files {
+            if !is_safe_relative(Path::new(rel)) {
+                eyre::bail!(
+                    "Refusing to install file outside the {} directory: {}",
+                    manifest.kind,
+                    rel
+                );
+            }
+            let content = gist_files
+                .remove(gist_name)
+                .ok_or_else(|| eyre::eyre!("Gist is missing file listed in manifest: {}", gist_name))?;
+            files.insert(rel.clone(), content);
+        }
+
+        Ok(Self {
+            manifest,
+            files,
+            origin: None,
+        })
+    }
+
+    /// Write all files under `dir`
+    pub fn write_to(&self, dir: &Path) -> Result<()> {
+        for (rel, content) in &self.files {
+            let path = dir.join(rel);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
+            fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Flatten a relative path into a gist file name
+fn gist_file_name(rel: &str) -> String {
+    rel.replace('/', "__")
+}
+
+/// A relative path with no `..`, root or prefix components
+pub(crate) fn is_safe_relative(path: &Path) -> bool {
+    !path.as_os_str().is_empty() && path.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// Extract a gist ID from a gist URL, `gist:<id>` or a bare ID
+pub fn parse_gist_id(source: &str) -> Option<String> {
+    let source = source.trim().trim_end_matches('/');
+    let id = if let Some(id) = source.strip_prefix("gist:") {
+        id
+    } else if source.contains("gist.github.com/") || source.contains("api.github.com/gists/") {
+        source.rsplit('/').next()?
+    } else {
+        source
+    };
+    let id = id.split(['#', '?']).next()?.trim_end_matches(".git");
+    (!id.is_empty() && id.chars().all(|c| c.is_ascii_hexdigit())).then(|| id.to_string())
+}
+
+/// GitHub token from `GITHUB_TOKEN`/`GH_TOKEN`, falling back to `gh auth token`
+pub fn github_token() -> Result<String> {
+    for var in ["GITHUB_TOKEN", "GH_TOKEN"] {
+        if let Ok(token) = std::env::var(var)
+            && !token.trim().is_empty()
+        {
+            return Ok(token.trim().to_string());
+        }
+    }
+
+    if let Ok(output) = Command::new("gh").args(["auth", "token"]).output()
+        && output.status.success()
+    {
+        let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !token.is_empty() {
+            return Ok(token);
+        }
+    }
+
+    Err(CliError::new(ErrorCode::Config, "No GitHub credentials found")
+        .with_hint("Run 'gh auth login' or set GITHUB_TOKEN (needs the gist scope)")
+        .into())
+}
+
+/// Upload an artifact as a new gist
+pub fn create_gist(artifact: &Artifact, description: &str, public: bool, token: &str) -> Result<Shared> {
+    let files: serde_json::Map<String, serde_json::Value> = artifact
+        .gist_files()?
+        .into_iter()
+        .map(|(name, content)| (name, serde_json::json!({ "content": content })))
+        .collect();
+    let body = serde_json::json!({
+        "description": description,
+        "public": public,
+        "files": files,
+    });
+
+    let mut response = ureq::post(GIST_API)
+        .header("Authorization", &format!("Bearer {}", token))
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", "pais")
+        .send(body.to_string().as_bytes())
+        .context("Failed to create gist")?;
+    let response_body = response
+        .body_mut()
+        .read_to_string()
+        .context("Failed to read GitHub response")?;
+    let gist: serde_json::Value = serde_json::from_str(&response_body).context("Failed to parse GitHub response")?;
+
+    let id = gist["id"]
+        .as_str()
+        .ok_or_else(|| eyre::eyre!("No gist ID in response"))?;
+    let url = gist["html_url"].as_str().unwrap_or_default();
+    Ok(Shared {
+        id: id.to_string(),
+        url: url.to_string(),
+        install: format!("pais {} install {}", artifact.manifest.kind, url),
+    })
+}
+
+/// Download a shared gist
+pub fn fetch_gist(id: &str) -> Result<Artifact> {
+    let mut request = ureq::get(&format!("{}/{}", GIST_API, id))
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", "pais");
+    // Public and secret gists are readable anonymously; a token only raises the rate limit
+    let token = github_token().ok();
+    if let Some(ref token) = token {
+        request = request.header("Authorization", &format!("Bearer {}", token));
+    }
+    let response_body = request
+        .call()
+        .with_context(|| format!("Failed to fetch gist {}", id))?
+        .body_mut()
+        .read_to_string()
+        .context("Failed to read GitHub response")?;
+    let gist: serde_json::Value = serde_json::from_str(&response_body).context("Failed to parse GitHub response")?;
+
+    let mut files = BTreeMap::new();
+    if let Some(entries) = gist["files"].as_object() {
+        for (name, file) in entries {
+            // The API truncates large files; fetch those from their raw URL
+            let content = if file["truncated"].as_bool().unwrap_or(false) {
+                let raw_url = file["raw_url"].as_str().unwrap_or_default();
+                ureq::get(raw_url)
+                    .call()
+                    .with_context(|| format!("Failed to download {}", name))?
+                    .body_mut()
+                    .read_to_string()?
+            } else {
+                file["content"].as_str().unwrap_or_default().to_string()
+            };
+            files.insert(name.clone(), content);
+        }
+    }
+
+    let mut artifact = Artifact::from_gist_files(files)?;
+    artifact.origin = Some(GistOrigin {
+        url: gist["html_url"]
+            .as_str()
+            .map(String::from)
+            .unwrap_or_else(|| format!("https://gist.github.com/{}", id)),
+        revision: gist["history"][0]["version"].as_str().map(String::from),
+        owner: gist["owner"]["login"].as_str().map(String::from),
+    });
+    Ok(artifact)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_gist_id() {
+        let id = "aa5a315d61ae9438b18d";
+        assert_eq!(
+            parse_gist_id(&format!("https://gist.github.com/someone/{}", id)).unwrap(),
+            id
+        );
+        assert_eq!(parse_gist_id(&format!("https://gist.github.com/{}/", id)).unwrap(), id);
+        assert_eq!(parse_gist_id(&format!("gist:{}", id)).unwrap(), id);
+        assert_eq!(parse_gist_id(id).unwrap(), id);
+        assert!(parse_gist_id("https://github.com/owner/repo").is_none());
+        assert!(parse_gist_id("./local/path").is_none());
+    }
+
+    #[test]
+    fn test_roundtrip_through_gist_files() {
+        let temp = tempdir().unwrap();
+        let src = temp.path().join("rust");
+        fs::create_dir_all(src.join("workflows")).unwrap();
+        fs::create_dir_all(src.join(".git")).unwrap();
+        fs::write(src.join("SKILL.md"), "---\nname: rust\n---\n").unwrap();
+        fs::write(src.join("workflows").join("build.md"), "cargo build").unwrap();
+        fs::write(src.join(".git").join("HEAD"), "ref").unwrap();
+
+        let artifact = Artifact::from_dir(ShareKind::Skill, "rust", &src).unwrap();
+        let gist_files = artifact.gist_files().unwrap();
+        assert!(gist_files.contains_key("workflows__build.md"));
+        assert!(gist_files.contains_key(MANIFEST_FILE));
+        assert!(!gist_files.keys().any(|k| k.contains("HEAD")));
+
+        let restored = Artifact::from_gist_files(gist_files).unwrap();
+        assert_eq!(restored.manifest.kind, ShareKind::Skill);
+        let dest = temp.path().join("installed");
+        restored.write_to(&dest).unwrap();
+        assert_eq!(
+            fs::read_to_string(dest.join("workflows").join("build.md")).unwrap(),
+            "cargo build"
+        );
+    }
+
+    #[test]
+    fn test_rejects_escaping_paths() {
+        let manifest = "kind: skill\nname: evil\nfiles:\n  x: ../../.bashrc\n";
+        let files = BTreeMap::from([
+            (MANIFEST_FILE.to_string(), manifest.to_string()),
+            ("x".to_string(), "rm -rf ~".to_string()),
+        ]);
+        assert!(Artifact::from_gist_files(files).is_err());
+
+        let files = BTreeMap::from([("SKILL.md".to_string(), String::new())]);
+        assert!(Artifact::from_gist_files(files).is_err());
+    }
+}
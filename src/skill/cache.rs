@@ -0,0 +1,229 @@
+//! Parsed SKILL.md cache
+//!
+//! Frontmatter parsing dominates discovery once there are dozens of skills.
+//! Parsed metadata is kept in `~/.cache/pais/skill-cache.json`, keyed by the
+//! SKILL.md path and a hash of its content, so unchanged files are read but
+//! never re-parsed. The cache is safe to share between rayon workers.
+
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::parser::{SkillMetadata, parse_frontmatter};
+use crate::perf;
+
+/// Bump when `SkillMetadata` changes shape so old entries are dropped
+const CACHE_VERSION: u32 = 4;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedSkill {
+    hash: String,
+    metadata: SkillMetadata,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    skills: BTreeMap<PathBuf, CachedSkill>,
+}
+
+/// Location of the cache file (`~/.cache/pais/skill-cache.json`)
+pub fn cache_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|d| d.join("pais").join("skill-cache.json"))
+}
+
+/// Content-hash cache of parsed skill metadata
+#[derive(Debug, Default)]
+pub struct SkillCache {
+    path: Option<PathBuf>,
+    state: Mutex<CacheState>,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    skills: BTreeMap<PathBuf, CachedSkill>,
+    dirty: bool,
+    hits: usize,
+    misses: usize,
+}
+
+impl SkillCache {
+    /// Open the user cache; an unreadable or outdated file starts empty
+    pub fn open() -> Self {
+        Self::load(cache_path())
+    }
+
+    /// Load a cache from a specific file, or an in-memory cache when `None`
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let skills = path
+            .as_deref()
+            .and_then(|p| fs::read(p).ok())
+            .and_then(|bytes| serde_json::from_slice::<CacheFile>(&bytes).ok())
+            .filter(|file| file.version == CACHE_VERSION)
+            .map(|file| file.skills)
+            .unwrap_or_default();
+
+        Self {
+            path,
+            state: Mutex::new(CacheState {
+                skills,
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Parse a SKILL.md, reusing the cached metadata when its content is unchanged
+    pub fn parse(&self, skill_md: &Path) -> Result<SkillMetadata> {
+        let content = fs::read_to_string(skill_md)
+            .with_context(|| format!("Failed to read SKILL.md at {}", skill_md.display()))?;
+        let hash = content_hash(content.as_bytes());
+
+        {
+            let mut state = self.lock();
+            if let Some(cached) = state.skills.get(skill_md).filter(|c| c.hash == hash) {
+                let metadata = cached.metadata.clone();
+                state.hits += 1;
+                perf::cache("skill-metadata", true);
+                return Ok(metadata);
+            }
+        }
+
+        // Parse outside the lock so workers don't serialize on each other
+        let metadata = parse_frontmatter(&content)
+            .with_context(|| format!("Failed to parse frontmatter in {}", skill_md.display()))?;
+
+        let mut state = self.lock();
+        state.misses += 1;
+        perf::cache("skill-metadata", false);
+        state.dirty = true;
+        state.skills.insert(
+            skill_md.to_path_buf(),
+            CachedSkill {
+                hash,
+                metadata: metadata.clone(),
+            },
+        );
+        Ok(metadata)
+    }
+
+    /// (hits, misses) since the cache was opened
+    pub fn stats(&self) -> (usize, usize) {
+        let state = self.lock();
+        (state.hits, state.misses)
+    }
+
+    /// Write the cache back if anything changed, dropping entries for deleted files
+    pub fn save(&self) -> Result<()> {
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+        let mut state = self.lock();
+        if !state.dirty {
+            return Ok(());
+        }
+        state.skills.retain(|skill_md, _| skill_md.exists());
+
+        let file = CacheFile {
+            version: CACHE_VERSION,
+            skills: state.skills.clone(),
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        // Concurrent hooks may save at once; rename keeps the file whole
+        let tmp = path.with_extension(format!("json.{}", std::process::id()));
+        fs::write(&tmp, serde_json::to_vec(&file)?).with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path.display()))?;
+        state.dirty = false;
+        Ok(())
+    }
+
+    /// Save, logging instead of failing: the cache is only an optimization
+    pub fn save_quietly(&self) {
+        if let Err(e) = self.save() {
+            log::debug!("Failed to save skill cache: {}", e);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        // A panicking worker can't leave the map half-updated, so poison is harmless
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Stable content hash (FNV-1a)
+pub fn content_hash(bytes: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write_skill(dir: &Path, name: &str, description: &str) -> PathBuf {
+        let skill_md = dir.join(name).join("SKILL.md");
+        fs::create_dir_all(skill_md.parent().unwrap()).unwrap();
+        fs::write(
+            &skill_md,
+            format!("---\nname: {}\ndescription: {}\n---\n# {}\n", name, description, name),
+        )
+        .unwrap();
+        skill_md
+    }
+
+    #[test]
+    fn test_unchanged_skill_is_not_reparsed() {
+        let temp = tempdir().unwrap();
+        let cache_file = temp.path().join("cache.json");
+        let skill_md = write_skill(temp.path(), "rust", "Rust patterns");
+
+        let cache = SkillCache::load(Some(cache_file.clone()));
+        assert_eq!(cache.parse(&skill_md).unwrap().description, "Rust patterns");
+        assert_eq!(cache.stats(), (0, 1));
+        cache.save().unwrap();
+
+        let cache = SkillCache::load(Some(cache_file.clone()));
+        assert_eq!(cache.parse(&skill_md).unwrap().name, "rust");
+        assert_eq!(cache.stats(), (1, 0));
+
+        // Edited content invalidates the entry
+        write_skill(temp.path(), "rust", "Idiomatic Rust");
+        assert_eq!(cache.parse(&skill_md).unwrap().description, "Idiomatic Rust");
+        assert_eq!(cache.stats(), (1, 1));
+    }
+
+    #[test]
+    fn test_save_prunes_deleted_skills() {
+        let temp = tempdir().unwrap();
+        let cache_file = temp.path().join("cache.json");
+        let keep = write_skill(temp.path(), "keep", "Kept");
+        let gone = write_skill(temp.path(), "gone", "Deleted");
+
+        let cache = SkillCache::load(Some(cache_file.clone()));
+        cache.parse(&keep).unwrap();
+        cache.parse(&gone).unwrap();
+        fs::remove_file(&gone).unwrap();
+        cache.save().unwrap();
+
+        let file: CacheFile = serde_json::from_slice(&fs::read(&cache_file).unwrap()).unwrap();
+        assert_eq!(file.skills.len(), 1);
+        assert!(file.skills.contains_key(&keep));
+    }
+
+    #[test]
+    fn test_in_memory_cache_does_not_write() {
+        let cache = SkillCache::load(None);
+        cache.save().unwrap();
+        assert_eq!(content_hash(b"abc"), content_hash(b"abc"));
+        assert_ne!(content_hash(b"abc"), content_hash(b"abd"));
+    }
+}
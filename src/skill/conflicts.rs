@@ -0,0 +1,135 @@
+//! Skills that shadow plugins
+//!
+//! A name can exist both as a skill (`paths.skills/<name>/SKILL.md`) and as a
+//! plugin with a SKILL.md (`paths.plugins/<name>/SKILL.md`). Only one of them
+//! is linked into `~/.claude/skills/`: the skill, unless
+//! `skills.resolve.<name>` picks the plugin. `pais skill list` and `pais
+//! doctor` warn about conflicts nobody has resolved in config, and `pais skill
+//! conflicts` lists them all.
+
+use eyre::Result;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use super::loader::subdirectories;
+use crate::config::{Config, SkillPreference};
+
+/// A name with both a skill and a plugin SKILL.md
+#[derive(Debug, Clone, Serialize)]
+pub struct Conflict {
+    pub name: String,
+    pub skill: PathBuf,
+    pub plugin: PathBuf,
+    /// The side in use
+    pub uses: SkillPreference,
+    /// Whether `skills.resolve` picks the side (rather than the default)
+    pub resolved: bool,
+}
+
+impl Conflict {
+    /// Directory of the copy in use
+    pub fn used_path(&self) -> &Path {
+        match self.uses {
+            SkillPreference::Skill => &self.skill,
+            SkillPreference::Plugin => &self.plugin,
+        }
+    }
+
+    /// Directory of the copy hidden by the other
+    pub fn shadowed_path(&self) -> &Path {
+        match self.uses {
+            SkillPreference::Skill => &self.plugin,
+            SkillPreference::Plugin => &self.skill,
+        }
+    }
+}
+
+/// Which side a name resolves to when it exists on both
+pub fn preference(name: &str, config: &Config) -> SkillPreference {
+    config.skills.resolve.get(name).copied().unwrap_or_default()
+}
+
+/// Every name found in both the skills and plugins directories, sorted
+pub fn find(config: &Config) -> Result<Vec<Conflict>> {
+    let skills_dir = Config::expand_path(&config.paths.skills);
+    let plugins_dir = Config::expand_path(&config.paths.plugins);
+    if !skills_dir.is_dir() || !plugins_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut conflicts = Vec::new();
+    for skill in subdirectories(&skills_dir)? {
+        let Some(name) = skill.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let plugin = plugins_dir.join(name);
+        if skill.join("SKILL.md").exists() && plugin.join("SKILL.md").exists() {
+            conflicts.push(Conflict {
+                name: name.to_string(),
+                uses: preference(name, config),
+                resolved: config.skills.resolve.contains_key(name),
+                skill,
+                plugin,
+            });
+        }
+    }
+    Ok(conflicts)
+}
+
+/// `skills.resolve` names that no longer exist on both sides
+pub fn stale_overrides<'a>(config: &'a Config, conflicts: &[Conflict]) -> Vec<&'a str> {
+    config
+        .skills
+        .resolve
+        .keys()
+        .filter(|name| !conflicts.iter().any(|c| &c.name == *name))
+        .map(String::as_str)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_find_conflicts() {
+        let temp = tempdir().unwrap();
+        let mut config = Config::default();
+        config.paths.skills = temp.path().join("skills");
+        config.paths.plugins = temp.path().join("plugins");
+        for dir in [
+            "skills/otto",
+            "skills/solo",
+            "plugins/otto",
+            "plugins/fabric",
+            "plugins/solo",
+        ] {
+            fs::create_dir_all(temp.path().join(dir)).unwrap();
+        }
+        for file in ["skills/otto", "skills/solo", "plugins/otto", "plugins/fabric"] {
+            fs::write(temp.path().join(file).join("SKILL.md"), "---\nname: x\n---\n").unwrap();
+        }
+
+        let conflicts = find(&config).unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].name, "otto");
+        assert_eq!(conflicts[0].uses, SkillPreference::Skill);
+        assert!(!conflicts[0].resolved);
+        assert!(conflicts[0].used_path().ends_with("skills/otto"));
+
+        config
+            .skills
+            .resolve
+            .insert("otto".to_string(), SkillPreference::Plugin);
+        config
+            .skills
+            .resolve
+            .insert("gone".to_string(), SkillPreference::Plugin);
+        let conflicts = find(&config).unwrap();
+        assert!(conflicts[0].resolved);
+        assert!(conflicts[0].used_path().ends_with("plugins/otto"));
+        assert_eq!(stale_overrides(&config, &conflicts), ["gone"]);
+    }
+}
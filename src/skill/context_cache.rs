@@ -0,0 +1,195 @@
+//! Cache for the SessionStart context injection
+//!
+//! `pais context inject` runs on every session start. Its output only changes
+//! when a skill file, the relevant config or the skill filter changes, so the
+//! rendered pieces are stored under `~/.cache/pais/context/<key>.json`, where
+//! the key fingerprints all of those inputs. Any edit produces a new key;
+//! entries older than a day are ignored and pruned so tool availability
+//! checks are eventually refreshed too.
+
+use eyre::{Context, Result};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use walkdir::WalkDir;
+
+use super::cache::content_hash;
+
+/// Entries older than this are treated as stale
+const MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Deep enough for `<skill>/workflows/<file>.md`
+const FINGERPRINT_DEPTH: usize = 3;
+
+/// Directory holding cached injections (`~/.cache/pais/context/`)
+pub fn cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|d| d.join("pais").join("context"))
+}
+
+/// Builds a cache key from everything the cached output depends on
+#[derive(Debug, Default)]
+pub struct CacheKey {
+    input: String,
+}
+
+impl CacheKey {
+    pub fn new() -> Self {
+        let mut key = Self::default();
+        key.add("version", env!("CARGO_PKG_VERSION"));
+        key
+    }
+
+    /// Add a named value to the key
+    pub fn add(&mut self, name: &str, value: impl std::fmt::Display) -> &mut Self {
+        self.input.push_str(&format!("{}={}\n", name, value));
+        self
+    }
+
+    /// Add the path, size and mtime of one file (or that it is missing)
+    pub fn add_file(&mut self, path: &Path) -> &mut Self {
+        let stamp = fs::metadata(path)
+            .map(|meta| format!("{}\t{}", meta.len(), mtime_nanos(&meta)))
+            .unwrap_or_else(|_| "missing".to_string());
+        self.add("file", format!("{}\t{}", path.display(), stamp))
+    }
+
+    /// Add the path, size and mtime of every file under `dir` (hidden entries skipped)
+    pub fn add_dir(&mut self, dir: &Path) -> &mut Self {
+        self.add("dir", dir.display());
+        let walker = WalkDir::new(dir)
+            .max_depth(FINGERPRINT_DEPTH)
+            .follow_links(true)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'));
+
+        for entry in walker.flatten() {
+            // Directory mtimes would also move for ignored entries; the file list covers adds and removes
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if !meta.is_file() {
+                continue;
+            }
+            let mtime = mtime_nanos(&meta);
+            let rel = entry.path().strip_prefix(dir).unwrap_or(entry.path());
+            self.input
+                .push_str(&format!("{}\t{}\t{}\n", rel.display(), meta.len(), mtime));
+        }
+        self
+    }
+
+    /// Final key, safe to use as a file name
+    pub fn finish(&self) -> String {
+        content_hash(self.input.as_bytes())
+    }
+}
+
+fn mtime_nanos(meta: &fs::Metadata) -> u128 {
+    meta.modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos())
+        .unwrap_or_default()
+}
+
+/// Load a cached value for `key`, if present and fresh
+pub fn load<T: DeserializeOwned>(dir: &Path, key: &str) -> Option<T> {
+    let path = dir.join(format!("{}.json", key));
+    let age = fs::metadata(&path).ok()?.modified().ok()?.elapsed().unwrap_or_default();
+    if age > MAX_AGE {
+        log::debug!("Context cache entry is stale: {}", path.display());
+        return None;
+    }
+    let value = fs::read(&path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok());
+    if value.is_none() {
+        log::debug!("Ignoring unreadable context cache entry: {}", path.display());
+    }
+    value
+}
+
+/// Store a value under `key`, pruning stale entries
+pub fn store<T: Serialize>(dir: &Path, key: &str, value: &T) -> Result<()> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    prune(dir, SystemTime::now());
+
+    let path = dir.join(format!("{}.json", key));
+    let tmp = path.with_extension(format!("json.{}", std::process::id()));
+    fs::write(&tmp, serde_json::to_vec(value)?).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, &path).with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(())
+}
+
+/// Remove every cached entry
+pub fn clear(dir: &Path) -> Result<usize> {
+    let mut removed = 0;
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(0);
+    };
+    for entry in entries.flatten() {
+        fs::remove_file(entry.path()).with_context(|| format!("Failed to remove {}", entry.path().display()))?;
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+fn prune(dir: &Path, now: SystemTime) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let stale = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > MAX_AGE);
+        if stale {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_key_changes_with_skill_files() {
+        let temp = tempdir().unwrap();
+        let skill = temp.path().join("rust");
+        fs::create_dir_all(&skill).unwrap();
+        fs::write(skill.join("SKILL.md"), "v1").unwrap();
+
+        let key = |filter: &str| CacheKey::new().add_dir(temp.path()).add("filter", filter).finish();
+        let before = key("");
+        assert_eq!(before, key(""));
+        assert_ne!(before, key("rust"));
+
+        // Size changes are caught even when mtime resolution is coarse
+        fs::write(skill.join("SKILL.md"), "version two").unwrap();
+        assert_ne!(before, key(""));
+
+        // Hidden entries are not part of the fingerprint
+        let after = key("");
+        fs::write(temp.path().join(".DS_Store"), "x").unwrap();
+        assert_eq!(after, key(""));
+    }
+
+    #[test]
+    fn test_store_and_load() {
+        let temp = tempdir().unwrap();
+        let dir = temp.path().join("context");
+
+        assert_eq!(load::<Vec<String>>(&dir, "abc"), None);
+        store(&dir, "abc", &vec!["snippet".to_string()]).unwrap();
+        assert_eq!(load::<Vec<String>>(&dir, "abc"), Some(vec!["snippet".to_string()]));
+        assert_eq!(clear(&dir).unwrap(), 1);
+        assert_eq!(load::<Vec<String>>(&dir, "abc"), None);
+    }
+}
@@ -0,0 +1,285 @@
+//! Draft skills from history entries
+//!
+//! `pais skill distill --from-history <query|ids>` gathers the entries a
+//! full-text query matches in `skills.distill.categories` (learnings and
+//! sessions by default), or the entries named by ID, and turns them into a
+//! draft SKILL.md. The template method lists each entry's lesson under a
+//! frontmatter whose triggers are the entries' most common tags; the llm
+//! method has `claude -p` write the skill from the entries. Both end with a
+//! Sources section naming the entries, so the draft can be traced back.
+
+use eyre::Result;
+use std::collections::HashMap;
+use std::process::Command;
+
+use crate::agent::runner;
+use crate::history::HistoryEntry;
+use crate::presenter;
+
+/// Most triggers a template draft gets
+const MAX_TRIGGERS: usize = 5;
+
+/// Characters of an entry a template lesson keeps
+const MAX_LESSON: usize = 400;
+
+/// Characters of an entry sent to Claude; longer ones are cut
+const MAX_ENTRY_CHARS: usize = 4000;
+
+/// Words of a query or title a suggested name keeps
+const NAME_WORDS: usize = 4;
+
+/// The IDs `input` lists (comma or space separated), if every word could be one
+///
+/// Entry IDs are lowercase hex; whether they name entries is up to the caller.
+pub fn ids(input: &str) -> Option<Vec<&str>> {
+    let ids: Vec<&str> = input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let is_id = |word: &&str| word.len() >= 6 && word.chars().all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c));
+    (!ids.is_empty() && ids.iter().all(is_id)).then_some(ids)
+}
+
+/// A skill name from a query's words, else the first entry's title
+pub fn suggest_name(query: Option<&str>, entries: &[HistoryEntry]) -> String {
+    let words = |text: &str| -> Vec<String> {
+        text.split_whitespace()
+            // Field filters and operators say nothing about the topic
+            .filter(|word| !word.contains(':') && !matches!(*word, "OR" | "NOT" | "AND"))
+            .map(|word| {
+                word.chars()
+                    .filter(|c| c.is_alphanumeric() || *c == '-')
+                    .collect::<String>()
+                    .to_lowercase()
+            })
+            .filter(|word| !word.is_empty())
+            .take(NAME_WORDS)
+            .collect()
+    };
+    let mut name = query.map(words).unwrap_or_default();
+    if name.is_empty() {
+        name = entries.first().map(|entry| words(&entry.title)).unwrap_or_default();
+    }
+    match name.join("-").trim_matches('-') {
+        "" => "distilled".to_string(),
+        name => name.to_string(),
+    }
+}
+
+/// A draft SKILL.md listing each entry's lesson
+pub fn template(name: &str, entries: &[HistoryEntry]) -> String {
+    let mut lessons = String::new();
+    for entry in entries {
+        lessons.push_str(&format!("### {}\n\n{}\n\n", entry.title.trim(), lesson(&entry.content)));
+    }
+    format!(
+        "{}\n# {}\n\nDistilled from {}. Review it: keep what still holds, \
+         turn it into instructions, and drop the rest.\n\n## USE WHEN\n\n- [When should Claude \
+         activate this skill?]\n\n## LESSONS\n\n{}{}",
+        frontmatter(name, entries),
+        title(name),
+        count(entries.len()),
+        lessons,
+        sources(entries)
+    )
+}
+
+/// Ask Claude to write the skill from the entries
+pub fn generate(name: &str, entries: &[HistoryEntry]) -> Result<String> {
+    let mut cmd = Command::new("claude");
+    cmd.arg("-p")
+        .arg(prompt(name, entries))
+        .args(["--output-format", "json"]);
+    let (result, ok) = runner::execute(cmd)?;
+    if !ok || result.result.trim().is_empty() {
+        eyre::bail!("Claude could not distill '{}': {}", name, result.result.trim());
+    }
+    Ok(finish_reply(name, entries, &result.result))
+}
+
+fn prompt(name: &str, entries: &[HistoryEntry]) -> String {
+    let records: Vec<String> = entries
+        .iter()
+        .map(|entry| {
+            let content = match entry.content.char_indices().nth(MAX_ENTRY_CHARS) {
+                Some((end, _)) => &entry.content[..end],
+                None => &entry.content,
+            };
+            format!(
+                "<entry id=\"{}\" category=\"{}\" date=\"{}\" title=\"{}\">\n{}\n</entry>",
+                entry.id,
+                entry.category,
+                entry.created_at.format("%Y-%m-%d"),
+                entry.title,
+                content.trim()
+            )
+        })
+        .collect();
+    format!(
+        "Below are records of past coding assistant sessions. Write a reusable Claude Code skill \
+         named '{}' that codifies the lessons they share. Reply with the SKILL.md only: YAML \
+         frontmatter with name, a one-sentence description, version: 0.1.0 and a few short \
+         triggers, then a markdown body with a USE WHEN section and concrete, imperative \
+         instructions. Leave out anything specific to a single session and don't list the \
+         sources.\n\n{}",
+        name,
+        records.join("\n\n")
+    )
+}
+
+/// Tidy Claude's reply into a SKILL.md with the sources appended
+fn finish_reply(name: &str, entries: &[HistoryEntry], reply: &str) -> String {
+    let mut text = reply.trim();
+    // Models wrap markdown in code fences now and then
+    if let Some(fenced) = text.strip_prefix("```") {
+        let fenced = fenced.split_once('\n').map_or("", |(_, rest)| rest);
+        text = fenced.trim_end().strip_suffix("```").unwrap_or(fenced).trim();
+    }
+    let body = if text.starts_with("---") {
+        text.to_string()
+    } else {
+        format!("{}\n{}", frontmatter(name, entries), text)
+    };
+    format!("{}\n\n{}", body.trim_end(), sources(entries))
+}
+
+fn frontmatter(name: &str, entries: &[HistoryEntry]) -> String {
+    let triggers = common_tags(entries);
+    let triggers = if triggers.is_empty() {
+        " []".to_string()
+    } else {
+        triggers.iter().map(|tag| format!("\n  - {}", tag)).collect()
+    };
+    format!(
+        "---\nname: {}\ndescription: \"Lessons from past sessions about {}\"\nversion: 0.1.0\ntriggers:{}\n---\n",
+        name,
+        title(name).to_lowercase(),
+        triggers
+    )
+}
+
+/// The entries' most common tags, most common first
+fn common_tags(entries: &[HistoryEntry]) -> Vec<String> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for tag in entries.iter().flat_map(|entry| &entry.tags) {
+        *counts.entry(tag.as_str()).or_default() += 1;
+    }
+    let mut tags: Vec<(&str, usize)> = counts.into_iter().collect();
+    tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    tags.into_iter()
+        .take(MAX_TRIGGERS)
+        .map(|(tag, _)| tag.to_string())
+        .collect()
+}
+
+/// The first paragraph of an entry that isn't a heading
+fn lesson(content: &str) -> String {
+    let paragraph = content
+        .split("\n\n")
+        .map(str::trim)
+        .find(|p| !p.is_empty() && !p.starts_with('#'))
+        .unwrap_or_default();
+    presenter::truncate(&paragraph.split_whitespace().collect::<Vec<_>>().join(" "), MAX_LESSON)
+}
+
+fn sources(entries: &[HistoryEntry]) -> String {
+    let lines: Vec<String> = entries
+        .iter()
+        .map(|entry| {
+            format!(
+                "- {} {} `{}`: {}",
+                entry.created_at.format("%Y-%m-%d"),
+                entry.category,
+                entry.id,
+                entry.title.trim()
+            )
+        })
+        .collect();
+    format!("## SOURCES\n\n{}\n", lines.join("\n"))
+}
+
+/// "1 history entry", "3 history entries"
+pub fn count(entries: usize) -> String {
+    match entries {
+        1 => "1 history entry".to_string(),
+        n => format!("{} history entries", n),
+    }
+}
+
+fn title(name: &str) -> String {
+    name.split(['-', '_'])
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                None => String::new(),
+                Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skill::parser::parse_frontmatter;
+
+    fn entry(title: &str, content: &str, tags: &[&str]) -> HistoryEntry {
+        let mut entry = HistoryEntry::new("learnings", title, content);
+        entry.tags = tags.iter().map(|t| t.to_string()).collect();
+        entry
+    }
+
+    #[test]
+    fn test_ids() {
+        assert_eq!(
+            ids("1a14d4a97a6, 1a14d2a1256"),
+            Some(vec!["1a14d4a97a6", "1a14d2a1256"])
+        );
+        assert_eq!(ids("borrow checker"), None);
+        assert_eq!(ids("cafe"), None);
+        assert_eq!(ids(""), None);
+    }
+
+    #[test]
+    fn test_suggest_name() {
+        let entries = [entry("Retry flaky CI jobs", "", &[])];
+        assert_eq!(
+            suggest_name(Some("tag:rust borrow checker"), &entries),
+            "borrow-checker"
+        );
+        assert_eq!(suggest_name(None, &entries), "retry-flaky-ci-jobs");
+        assert_eq!(suggest_name(Some("category:learnings"), &[]), "distilled");
+    }
+
+    #[test]
+    fn test_template() {
+        let entries = [
+            entry(
+                "Async deadlock",
+                "# Notes\n\nHolding a mutex guard across an await\ndeadlocked the runtime.\n\nMore detail.",
+                &["rust", "async"],
+            ),
+            entry("Clone less", "Borrow instead of cloning in hot loops.", &["rust"]),
+        ];
+        let draft = template("rust-async", &entries);
+        let metadata = parse_frontmatter(&draft).unwrap();
+        assert_eq!(metadata.name, "rust-async");
+        assert_eq!(metadata.triggers, ["rust", "async"]);
+        assert!(
+            draft.contains("### Async deadlock\n\nHolding a mutex guard across an await deadlocked the runtime.\n")
+        );
+        assert!(draft.contains(&format!("`{}`: Clone less", entries[1].id)));
+    }
+
+    #[test]
+    fn test_finish_reply() {
+        let entries = [entry("Clone less", "Borrow.", &[])];
+        let fenced = "```markdown\n---\nname: perf\ndescription: x\n---\n# Perf\n```";
+        let skill = finish_reply("perf", &entries, fenced);
+        assert!(skill.starts_with("---\nname: perf\n"));
+        assert!(skill.contains("# Perf\n\n## SOURCES"));
+        let bare = finish_reply("perf", &entries, "# Perf\nBody");
+        assert_eq!(parse_frontmatter(&bare).unwrap().name, "perf");
+    }
+}
@@ -9,12 +9,15 @@
 //! skill routing context into Claude's system prompt.
 
 use eyre::{Context, Result};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
 use std::path::Path;
 
-use super::parser::{SkillTier, parse_skill_md};
+use super::cache::SkillCache;
+use super::loader::subdirectories;
+use super::parser::SkillTier;
 use super::workflow::{WorkflowRoute, discover_workflows};
 
 /// A skill entry in the index
@@ -30,6 +33,12 @@ pub struct SkillIndexEntry {
     pub triggers: Vec<String>,
     /// Loading tier
     pub tier: SkillTier,
+    /// Installed version, from the `version:` frontmatter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Routing priority from frontmatter; breaks ties between overlapping triggers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
     /// Available workflows for this skill
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub workflows: Vec<WorkflowRoute>,
@@ -48,6 +57,190 @@ pub struct SkillIndex {
     pub deferred_count: usize,
     /// Skills by name (lowercase)
     pub skills: HashMap<String, SkillIndexEntry>,
+    /// Triggers claimed by more than one skill
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub collisions: Vec<TriggerCollision>,
+}
+
+/// A trigger that more than one skill claims
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerCollision {
+    /// Normalized trigger (lowercase, singular)
+    pub trigger: String,
+    /// Skills claiming the trigger, highest priority first
+    pub skills: Vec<String>,
+    /// Skill that wins routing through a unique highest `priority`, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub winner: Option<String>,
+    /// How to disambiguate the skills
+    pub suggestion: String,
+}
+
+impl TriggerCollision {
+    /// A collision is resolved when one skill has a strictly higher priority
+    pub fn is_resolved(&self) -> bool {
+        self.winner.is_some()
+    }
+}
+
+/// A skill (and optionally one of its workflows) a prompt would route to
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteMatch {
+    /// Skill name
+    pub skill: String,
+    /// Number of triggers found in the prompt
+    pub score: usize,
+    /// Best-matching workflow intent, if the skill has workflows
+    pub workflow: Option<String>,
+}
+
+impl SkillIndex {
+    /// Rank skills by how many of their triggers appear in `prompt`.
+    ///
+    /// This approximates the trigger-based routing described in the context
+    /// snippet so routing can be tested deterministically. Skills with no
+    /// matching triggers are omitted; ties are ordered by `priority`, then name.
+    pub fn route(&self, prompt: &str) -> Vec<RouteMatch> {
+        let prompt_lower = prompt.to_lowercase();
+        let words = prompt_words(&prompt_lower);
+
+        let mut matches: Vec<RouteMatch> = self
+            .skills
+            .values()
+            .filter_map(|entry| {
+                let score = entry
+                    .triggers
+                    .iter()
+                    .filter(|t| trigger_matches(&t.to_lowercase(), &prompt_lower, &words))
+                    .count();
+                (score > 0).then(|| RouteMatch {
+                    skill: entry.name.clone(),
+                    score,
+                    workflow: best_workflow(&entry.workflows, &words),
+                })
+            })
+            .collect();
+
+        let priority = |skill: &str| {
+            self.skills
+                .get(&skill.to_lowercase())
+                .and_then(|e| e.priority)
+                .unwrap_or_default()
+        };
+        matches.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| priority(&b.skill).cmp(&priority(&a.skill)))
+                .then_with(|| a.skill.cmp(&b.skill))
+        });
+        matches
+    }
+}
+
+/// Normalize a trigger for comparison, with the same plural handling as prompt words
+fn normalize_trigger(trigger: &str) -> String {
+    let lower = trigger.trim().to_lowercase();
+    if lower.contains(' ') { lower } else { prompt_words(&lower).join("-") }
+}
+
+/// Find triggers claimed by more than one skill.
+///
+/// A collision is resolved when one of the skills sets a strictly higher
+/// `priority` than the rest; otherwise routing falls back to name order and
+/// the suggestion names a more specific phrase for each skill.
+pub fn find_collisions<'a>(skills: impl IntoIterator<Item = &'a SkillIndexEntry>) -> Vec<TriggerCollision> {
+    let mut claims: BTreeMap<String, Vec<&SkillIndexEntry>> = BTreeMap::new();
+    for entry in skills {
+        let mut seen = BTreeSet::new();
+        for trigger in &entry.triggers {
+            let normalized = normalize_trigger(trigger);
+            if !normalized.is_empty() && seen.insert(normalized.clone()) {
+                claims.entry(normalized).or_default().push(entry);
+            }
+        }
+    }
+
+    claims
+        .into_iter()
+        .filter(|(_, entries)| entries.len() > 1)
+        .map(|(trigger, mut entries)| {
+            entries.sort_by(|a, b| {
+                b.priority
+                    .unwrap_or_default()
+                    .cmp(&a.priority.unwrap_or_default())
+                    .then_with(|| a.name.cmp(&b.name))
+            });
+            let top = entries[0].priority.unwrap_or_default();
+            let winner = (entries[1].priority.unwrap_or_default() < top).then(|| entries[0].name.clone());
+            let suggestion = match &winner {
+                Some(name) => format!("'{}' routes to {} (priority {})", trigger, name, top),
+                None => format!(
+                    "use a more specific trigger ({}) or set `priority:` on the preferred skill",
+                    entries
+                        .iter()
+                        .map(|e| format!("{}: '{}'", e.name, specific_trigger(&e.name, &trigger)))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            };
+            TriggerCollision {
+                trigger,
+                skills: entries.iter().map(|e| e.name.clone()).collect(),
+                winner,
+                suggestion,
+            }
+        })
+        .collect()
+}
+
+/// Qualify a shared trigger with the first part of the skill name that it doesn't already contain
+fn specific_trigger(skill: &str, trigger: &str) -> String {
+    skill
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .find(|part| part.len() > 1 && !trigger.split(' ').any(|w| w == *part))
+        .map(|part| format!("{} {}", part, trigger))
+        .unwrap_or_else(|| format!("{} {}", skill.to_lowercase(), trigger))
+}
+
+/// Normalized words of a prompt, with the same plural handling as `extract_triggers`
+pub(crate) fn prompt_words(prompt_lower: &str) -> Vec<String> {
+    prompt_lower
+        .split(|c: char| !c.is_alphanumeric() && c != '-')
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            if w.ends_with('s') && w.len() > 3 {
+                w[..w.len() - 1].to_string()
+            } else {
+                w.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Single-word triggers match whole words; multi-word triggers match as a phrase
+pub(crate) fn trigger_matches(trigger: &str, prompt_lower: &str, words: &[String]) -> bool {
+    if trigger.contains(' ') {
+        prompt_lower.contains(trigger)
+    } else {
+        words.iter().any(|w| w == trigger || w == trigger.trim_end_matches('s'))
+    }
+}
+
+/// The workflow whose intent shares the most words with the prompt
+fn best_workflow(workflows: &[WorkflowRoute], words: &[String]) -> Option<String> {
+    workflows
+        .iter()
+        .map(|route| {
+            let overlap = prompt_words(&route.intent.to_lowercase())
+                .iter()
+                .filter(|w| w.len() > 2 && words.contains(w))
+                .count();
+            (overlap, route)
+        })
+        .filter(|(overlap, _)| *overlap > 0)
+        .max_by_key(|(overlap, _)| *overlap)
+        .map(|(_, route)| route.intent.clone())
 }
 
 /// Skills that should always be loaded at session start (override frontmatter tier)
@@ -162,6 +355,7 @@ pub fn generate_index(skills_dir: &Path) -> Result<SkillIndex> {
         core_count: 0,
         deferred_count: 0,
         skills: HashMap::new(),
+        collisions: Vec::new(),
     };
 
     if !skills_dir.exists() {
@@ -169,98 +363,23 @@ pub fn generate_index(skills_dir: &Path) -> Result<SkillIndex> {
         return Ok(index);
     }
 
-    for entry in fs::read_dir(skills_dir)
-        .with_context(|| format!("Failed to read skills directory: {}", skills_dir.display()))?
-    {
-        let entry = entry?;
-        let path = entry.path();
+    let dirs = subdirectories(skills_dir)
+        .with_context(|| format!("Failed to read skills directory: {}", skills_dir.display()))?;
+    let cache = SkillCache::open();
 
-        if !path.is_dir() {
-            continue;
-        }
-
-        let skill_md = path.join("SKILL.md");
-        if !skill_md.exists() {
-            log::trace!("No SKILL.md in: {}", path.display());
-            continue;
-        }
+    let entries: Vec<SkillIndexEntry> = dirs.par_iter().filter_map(|path| index_skill(path, &cache)).collect();
+    cache.save_quietly();
 
-        // Parse the skill
-        match parse_skill_md(&skill_md) {
-            Ok(metadata) => {
-                let name_lower = metadata.name.to_lowercase();
-
-                // Tier is determined by:
-                // 1. Force-core list (always core regardless of frontmatter)
-                // 2. Frontmatter tier field
-                let tier = if FORCE_CORE_SKILLS.contains(&name_lower.as_str()) {
-                    SkillTier::Core
-                } else {
-                    metadata.tier
-                };
-
-                let relative_path = path
-                    .file_name()
-                    .map(|n| format!("{}/SKILL.md", n.to_string_lossy()))
-                    .unwrap_or_default();
-
-                // Use frontmatter triggers if present, otherwise extract from description
-                let triggers = if !metadata.triggers.is_empty() {
-                    log::debug!(
-                        "Indexed skill: {} (tier={:?}, triggers from frontmatter=[{}])",
-                        metadata.name,
-                        tier,
-                        metadata.triggers.join(", ")
-                    );
-                    metadata.triggers.clone()
-                } else {
-                    let extracted = extract_triggers(&metadata.description);
-                    log::debug!(
-                        "Indexed skill: {} (tier={:?}, triggers extracted=[{}])",
-                        metadata.name,
-                        tier,
-                        extracted.join(", ")
-                    );
-                    extracted
-                };
-
-                // Discover workflows for this skill
-                let workflows = discover_workflows(&path).map(|w| w.routes).unwrap_or_default();
-                if !workflows.is_empty() {
-                    log::debug!(
-                        "  {} workflows: [{}]",
-                        workflows.len(),
-                        workflows
-                            .iter()
-                            .map(|w| &w.intent)
-                            .cloned()
-                            .collect::<Vec<_>>()
-                            .join(", ")
-                    );
-                }
-
-                let entry = SkillIndexEntry {
-                    name: metadata.name.clone(),
-                    path: relative_path,
-                    description: metadata.description.clone(),
-                    triggers,
-                    tier,
-                    workflows,
-                };
-
-                if tier.is_core() {
-                    index.core_count += 1;
-                } else {
-                    index.deferred_count += 1;
-                }
-                index.total_skills += 1;
-                index.skills.insert(name_lower, entry);
-            }
-            Err(e) => {
-                log::warn!("Failed to parse skill at {}: {}", skill_md.display(), e);
-            }
+    for entry in entries {
+        if entry.tier.is_core() {
+            index.core_count += 1;
+        } else {
+            index.deferred_count += 1;
         }
+        index.total_skills += 1;
+        index.skills.insert(entry.name.to_lowercase(), entry);
     }
+    index.collisions = find_collisions(index.skills.values());
 
     log::debug!(
         "Skill index complete: {} total ({} core, {} deferred)",
@@ -272,6 +391,88 @@ pub fn generate_index(skills_dir: &Path) -> Result<SkillIndex> {
     Ok(index)
 }
 
+/// Build the index entry for one skill directory, if it holds a valid SKILL.md
+fn index_skill(path: &Path, cache: &SkillCache) -> Option<SkillIndexEntry> {
+    let skill_md = path.join("SKILL.md");
+    if !skill_md.exists() {
+        log::trace!("No SKILL.md in: {}", path.display());
+        return None;
+    }
+
+    // Parse the skill
+    let metadata = match cache.parse(&skill_md) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            log::warn!("Failed to parse skill at {}: {}", skill_md.display(), e);
+            return None;
+        }
+    };
+
+    let name_lower = metadata.name.to_lowercase();
+
+    // Tier is determined by:
+    // 1. Force-core list (always core regardless of frontmatter)
+    // 2. Frontmatter tier field
+    let tier = if FORCE_CORE_SKILLS.contains(&name_lower.as_str()) {
+        SkillTier::Core
+    } else {
+        metadata.tier
+    };
+
+    let relative_path = path
+        .file_name()
+        .map(|n| format!("{}/SKILL.md", n.to_string_lossy()))
+        .unwrap_or_default();
+
+    // Use frontmatter triggers if present, otherwise extract from description
+    let triggers = if !metadata.triggers.is_empty() {
+        log::debug!(
+            "Indexed skill: {} (tier={:?}, triggers from frontmatter=[{}])",
+            metadata.name,
+            tier,
+            metadata.triggers.join(", ")
+        );
+        metadata.triggers.clone()
+    } else {
+        let extracted = extract_triggers(&metadata.description);
+        log::debug!(
+            "Indexed skill: {} (tier={:?}, triggers extracted=[{}])",
+            metadata.name,
+            tier,
+            extracted.join(", ")
+        );
+        extracted
+    };
+
+    // Discover workflows for this skill
+    let workflows = discover_workflows(path).map(|w| w.routes).unwrap_or_default();
+    if !workflows.is_empty() {
+        log::debug!(
+            "  {} workflows: [{}]",
+            workflows.len(),
+            workflows
+                .iter()
+                .map(|w| &w.intent)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    let entry = SkillIndexEntry {
+        name: metadata.name.clone(),
+        path: relative_path,
+        description: metadata.description.clone(),
+        triggers,
+        tier,
+        version: metadata.version.clone(),
+        priority: metadata.priority,
+        workflows,
+    };
+
+    Some(entry)
+}
+
 /// Write the index to a file
 pub fn write_index(index: &SkillIndex, output_path: &Path) -> Result<()> {
     let yaml = serde_yaml::to_string(index).context("Failed to serialize skill index")?;
@@ -352,6 +553,15 @@ pub fn generate_context_snippet(index: &SkillIndex, skills_dir: &Path) -> String
     lines.push("2. Follow the skill's instructions and conventions".to_string());
     lines.push("3. No need to ask for permission - the skill is pre-approved".to_string());
 
+    let preferred: Vec<_> = index
+        .collisions
+        .iter()
+        .filter_map(|c| c.winner.as_ref().map(|w| format!("`{}` → {}", c.trigger, w)))
+        .collect();
+    if !preferred.is_empty() {
+        lines.push(format!("4. When triggers overlap, prefer: {}", preferred.join(", ")));
+    }
+
     lines.join("\n")
 }
 
@@ -376,6 +586,91 @@ mod tests {
         assert!(triggers.contains(&"pip".to_string()));
     }
 
+    fn entry(name: &str, triggers: &[&str], workflows: &[&str]) -> SkillIndexEntry {
+        SkillIndexEntry {
+            name: name.to_string(),
+            path: format!("{}/SKILL.md", name),
+            description: String::new(),
+            triggers: triggers.iter().map(|t| t.to_string()).collect(),
+            tier: SkillTier::Deferred,
+            version: None,
+            priority: None,
+            workflows: workflows
+                .iter()
+                .map(|intent| WorkflowRoute {
+                    intent: intent.to_string(),
+                    workflow: format!("workflows/{}.md", intent.replace(' ', "-")),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_route_ranks_by_trigger_hits() {
+        let mut index = SkillIndex {
+            generated: String::new(),
+            total_skills: 2,
+            core_count: 0,
+            deferred_count: 2,
+            skills: HashMap::new(),
+            collisions: Vec::new(),
+        };
+        index.skills.insert(
+            "rust-coder".to_string(),
+            entry(
+                "rust-coder",
+                &["rust", "cargo", "cli"],
+                &["new cli project", "error handling"],
+            ),
+        );
+        index.skills.insert(
+            "python-coder".to_string(),
+            entry("python-coder", &["python", "cli"], &[]),
+        );
+
+        let routes = index.route("Create a new Rust CLI with cargo");
+        assert_eq!(routes[0].skill, "rust-coder");
+        assert_eq!(routes[0].score, 3);
+        assert_eq!(routes[0].workflow.as_deref(), Some("new cli project"));
+        assert_eq!(routes[1].skill, "python-coder");
+
+        assert!(index.route("What's the weather like?").is_empty());
+    }
+
+    #[test]
+    fn test_find_collisions_and_priority() {
+        let mut github = entry("github-pr", &["PR", "github"], &[]);
+        let gitlab = entry("gitlab-mr", &["pr", "merge request"], &[]);
+        let deploy = entry("deploy", &["deploy"], &[]);
+
+        let collisions = find_collisions([&github, &gitlab, &deploy]);
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0].trigger, "pr");
+        assert_eq!(collisions[0].skills, vec!["github-pr", "gitlab-mr"]);
+        assert!(!collisions[0].is_resolved());
+        assert!(collisions[0].suggestion.contains("github-pr: 'github pr'"));
+        assert!(collisions[0].suggestion.contains("gitlab-mr: 'gitlab pr'"));
+
+        github.priority = Some(10);
+        let collisions = find_collisions([&gitlab, &github]);
+        assert_eq!(collisions[0].winner.as_deref(), Some("github-pr"));
+        assert_eq!(collisions[0].skills[0], "github-pr");
+
+        let mut index = SkillIndex {
+            generated: String::new(),
+            total_skills: 2,
+            core_count: 0,
+            deferred_count: 2,
+            skills: HashMap::new(),
+            collisions,
+        };
+        index.skills.insert("github-pr".to_string(), github);
+        index.skills.insert("gitlab-mr".to_string(), gitlab);
+        let routes = index.route("review this pr");
+        assert_eq!(routes[0].skill, "github-pr");
+        assert!(generate_context_snippet(&index, Path::new("/skills")).contains("prefer: `pr` → github-pr"));
+    }
+
     #[test]
     fn test_extract_triggers_empty() {
         let desc = "A simple skill with no triggers.";
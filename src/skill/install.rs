@@ -0,0 +1,229 @@
+//! Install skills from git repositories
+//!
+//! A repository holds either one skill (`SKILL.md` at its root) or a
+//! collection (`skills/<name>/SKILL.md`). Each skill is copied into the
+//! skills directory without the repository's hidden files or symlinks (a
+//! link could point anywhere on this machine), and its URL, ref and
+//! directory are recorded in provenance so `pais skill update` can fetch it
+//! again.
+
+use eyre::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::parser::parse_skill_md;
+
+/// Directory holding a repository's skill collection
+pub const COLLECTION_DIR: &str = "skills";
+
+/// One skill found in a checkout
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceSkill {
+    pub name: String,
+    pub dir: PathBuf,
+    /// Directory within the repository; unset for a skill at its root
+    pub subdir: Option<String>,
+}
+
+/// The skills a checkout holds
+///
+/// A root skill is named by its frontmatter, else by `repo_name`; collection
+/// skills by their directory. A root skill with neither a valid name is left
+/// out, like collection directories with invalid names.
+pub fn find(checkout: &Path, repo_name: &str) -> Vec<SourceSkill> {
+    if checkout.join("SKILL.md").is_file() {
+        let name = parse_skill_md(&checkout.join("SKILL.md"))
+            .ok()
+            .map(|m| m.name)
+            .filter(|name| is_valid_name(name))
+            .or_else(|| Some(repo_name.to_string()).filter(|name| is_valid_name(name)));
+        return name
+            .map(|name| SourceSkill {
+                name,
+                dir: checkout.to_path_buf(),
+                subdir: None,
+            })
+            .into_iter()
+            .collect();
+    }
+
+    let Ok(entries) = fs::read_dir(checkout.join(COLLECTION_DIR)) else {
+        return Vec::new();
+    };
+    let mut skills: Vec<SourceSkill> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .filter(|entry| entry.path().join("SKILL.md").is_file())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            is_valid_name(&name).then(|| SourceSkill {
+                subdir: Some(format!("{}/{}", COLLECTION_DIR, name)),
+                dir: entry.path(),
+                name,
+            })
+        })
+        .collect();
+    skills.sort_by(|a, b| a.name.cmp(&b.name));
+    skills
+}
+
+/// The skill at `subdir` of a checkout (its root when unset)
+pub fn locate(checkout: &Path, subdir: Option<&str>) -> Option<PathBuf> {
+    let dir = subdir.map_or_else(|| checkout.to_path_buf(), |subdir| checkout.join(subdir));
+    dir.join("SKILL.md").is_file().then_some(dir)
+}
+
+/// The name a repository URL suggests: its last path segment without `.git`
+pub fn repo_name(url: &str) -> String {
+    url.trim_end_matches('/')
+        .trim_end_matches(".git")
+        .rsplit(['/', ':'])
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Whether `name` can be a directory in the skills directory
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('.') && !name.contains(['/', '\\'])
+}
+
+/// Replace `dest` with the files of `src`, leaving out hidden files and
+/// directories, and symlinks
+///
+/// The copy is staged next to `dest`, so a failure leaves the old skill in place.
+pub fn copy_skill(src: &Path, dest: &Path) -> Result<()> {
+    let name = dest.file_name().and_then(|n| n.to_str()).unwrap_or("skill");
+    let staging = dest.with_file_name(format!(".{}.new", name));
+    if staging.exists() {
+        fs::remove_dir_all(&staging).with_context(|| format!("Failed to remove {}", staging.display()))?;
+    }
+    copy_visible(src, &staging).with_context(|| format!("Failed to copy skill from {}", src.display()))?;
+
+    if dest.symlink_metadata().is_ok_and(|m| m.file_type().is_symlink()) {
+        fs::remove_file(dest).with_context(|| format!("Failed to remove {}", dest.display()))?;
+    } else if dest.exists() {
+        fs::remove_dir_all(dest).with_context(|| format!("Failed to remove {}", dest.display()))?;
+    }
+    fs::rename(&staging, dest).with_context(|| format!("Failed to move skill into {}", dest.display()))
+}
+
+fn copy_visible(src: &Path, dst: &Path) -> Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            log::warn!("Skipping symlink {}", path.display());
+        } else if file_type.is_dir() {
+            copy_visible(&path, &dst.join(entry.file_name()))?;
+        } else {
+            fs::copy(&path, dst.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn skill(dir: &Path, frontmatter_name: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(
+            dir.join("SKILL.md"),
+            format!("---\nname: {}\ndescription: test\n---\nBody\n", frontmatter_name),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_find_root_skill_and_collection() {
+        let temp = tempdir().unwrap();
+        let single = temp.path().join("single");
+        skill(&single, "rust-coder");
+        assert_eq!(
+            find(&single, "single"),
+            [SourceSkill {
+                name: "rust-coder".to_string(),
+                dir: single.clone(),
+                subdir: None,
+            }]
+        );
+
+        let collection = temp.path().join("collection");
+        skill(&collection.join("skills").join("writing"), "writing");
+        skill(&collection.join("skills").join("coding"), "whatever");
+        fs::create_dir_all(collection.join("skills").join("notes")).unwrap();
+        let found = find(&collection, "collection");
+        let names: Vec<_> = found.iter().map(|s| (s.name.as_str(), s.subdir.as_deref())).collect();
+        assert_eq!(
+            names,
+            [("coding", Some("skills/coding")), ("writing", Some("skills/writing"))]
+        );
+        assert_eq!(locate(&collection, Some("skills/coding")), Some(found[0].dir.clone()));
+        assert!(find(temp.path(), "empty").is_empty());
+
+        // No frontmatter name and no usable repository name: nothing to install
+        let unnamed = temp.path().join("unnamed");
+        fs::create_dir_all(&unnamed).unwrap();
+        fs::write(unnamed.join("SKILL.md"), "# No frontmatter\n").unwrap();
+        assert_eq!(find(&unnamed, "tool")[0].name, "tool");
+        for name in ["", ".", ".."] {
+            assert!(find(&unnamed, name).is_empty(), "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn test_repo_name() {
+        assert_eq!(repo_name("https://github.com/acme/skills.git"), "skills");
+        assert_eq!(repo_name("git@github.com:acme/rust-coder"), "rust-coder");
+        assert_eq!(repo_name("git@host:tool.git"), "tool");
+    }
+
+    #[test]
+    fn test_copy_skill_replaces_and_skips_hidden() {
+        let temp = tempdir().unwrap();
+        let src = temp.path().join("src");
+        skill(&src, "demo");
+        fs::create_dir_all(src.join(".git")).unwrap();
+        fs::write(src.join(".git").join("HEAD"), "ref").unwrap();
+        fs::create_dir_all(src.join("workflows")).unwrap();
+        fs::write(src.join("workflows").join("build.md"), "steps").unwrap();
+
+        let dest = temp.path().join("skills").join("demo");
+        fs::create_dir_all(&dest).unwrap();
+        fs::write(dest.join("stale.md"), "old").unwrap();
+
+        copy_skill(&src, &dest).unwrap();
+        assert!(dest.join("SKILL.md").exists());
+        assert!(dest.join("workflows").join("build.md").exists());
+        assert!(!dest.join(".git").exists());
+        assert!(!dest.join("stale.md").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_skill_skips_symlinks() {
+        let temp = tempdir().unwrap();
+        let secret = temp.path().join("id_ed25519");
+        fs::write(&secret, "PRIVATE KEY").unwrap();
+        let src = temp.path().join("src");
+        skill(&src, "demo");
+        std::os::unix::fs::symlink(&secret, src.join("notes.md")).unwrap();
+        std::os::unix::fs::symlink(temp.path(), src.join("home")).unwrap();
+        std::os::unix::fs::symlink(".", src.join("loop")).unwrap();
+
+        let dest = temp.path().join("skills").join("demo");
+        copy_skill(&src, &dest).unwrap();
+        assert!(dest.join("SKILL.md").exists());
+        for link in ["notes.md", "home", "loop"] {
+            assert!(dest.join(link).symlink_metadata().is_err(), "{}", link);
+        }
+    }
+}
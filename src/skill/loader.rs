@@ -4,23 +4,33 @@
 //! - ~/.config/pais/skills/ (simple skills)
 //! - ~/.config/pais/plugins/ (plugin skills)
 //! - .pais/ directories in repos (discovered skills)
+//! - skills/ in subscribed team repos (team skills)
+//!
+//! Skill directories are loaded in parallel; parsed frontmatter is reused
+//! from the [`SkillCache`] when a SKILL.md has not changed.
 
 use eyre::{Context, Result};
+use rayon::prelude::*;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use super::parser::{has_skill_md, is_simple_skill, parse_skill_md};
+use super::cache::SkillCache;
+use super::parser::{has_skill_md, is_simple_skill};
 use super::{Skill, SkillSource};
 
 /// Load a simple skill from a directory containing SKILL.md
 pub fn load_simple_skill(path: &Path) -> Result<Skill> {
+    load_simple_skill_with(path, &SkillCache::load(None))
+}
+
+fn load_simple_skill_with(path: &Path, cache: &SkillCache) -> Result<Skill> {
     let skill_md = path.join("SKILL.md");
 
     if !skill_md.exists() {
         eyre::bail!("No SKILL.md found in {}", path.display());
     }
 
-    let metadata = parse_skill_md(&skill_md)?;
+    let metadata = cache.parse(&skill_md)?;
 
     Ok(Skill {
         name: metadata.name,
@@ -32,13 +42,17 @@ pub fn load_simple_skill(path: &Path) -> Result<Skill> {
 
 /// Load a skill that's part of a plugin
 pub fn load_plugin_skill(path: &Path, plugin_name: &str) -> Result<Skill> {
+    load_plugin_skill_with(path, plugin_name, &SkillCache::load(None))
+}
+
+fn load_plugin_skill_with(path: &Path, plugin_name: &str, cache: &SkillCache) -> Result<Skill> {
     let skill_md = path.join("SKILL.md");
 
     if !skill_md.exists() {
         eyre::bail!("No SKILL.md found in plugin {}", plugin_name);
     }
 
-    let metadata = parse_skill_md(&skill_md)?;
+    let metadata = cache.parse(&skill_md)?;
 
     Ok(Skill {
         name: metadata.name,
@@ -48,65 +62,89 @@ pub fn load_plugin_skill(path: &Path, plugin_name: &str) -> Result<Skill> {
     })
 }
 
+/// Subdirectories of `dir`, sorted so parallel discovery stays deterministic
+pub(crate) fn subdirectories(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut dirs = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read directory: {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            dirs.push(path);
+        }
+    }
+    dirs.sort();
+    Ok(dirs)
+}
+
 /// Discover all simple skills in a directory
 pub fn discover_simple_skills(skills_dir: &Path) -> Result<Vec<Skill>> {
-    let mut skills = Vec::new();
-
     if !skills_dir.exists() {
-        return Ok(skills);
+        return Ok(Vec::new());
     }
 
-    for entry in fs::read_dir(skills_dir)
-        .with_context(|| format!("Failed to read skills directory: {}", skills_dir.display()))?
-    {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.is_dir() && is_simple_skill(&path) {
-            match load_simple_skill(&path) {
-                Ok(skill) => skills.push(skill),
-                Err(e) => {
-                    log::warn!("Failed to load skill from {}: {}", path.display(), e);
-                }
+    let dirs = subdirectories(skills_dir)
+        .with_context(|| format!("Failed to read skills directory: {}", skills_dir.display()))?;
+    let cache = SkillCache::open();
+
+    let skills = dirs
+        .par_iter()
+        .filter(|path| is_simple_skill(path))
+        .filter_map(|path| match load_simple_skill_with(path, &cache) {
+            Ok(skill) => Some(skill),
+            Err(e) => {
+                log::warn!("Failed to load skill from {}: {}", path.display(), e);
+                None
             }
-        }
-    }
+        })
+        .collect();
 
+    cache.save_quietly();
     Ok(skills)
 }
 
 /// Discover skills from plugins directory
 pub fn discover_plugin_skills(plugins_dir: &Path) -> Result<Vec<Skill>> {
-    let mut skills = Vec::new();
-
     if !plugins_dir.exists() {
-        return Ok(skills);
+        return Ok(Vec::new());
     }
 
-    for entry in fs::read_dir(plugins_dir)
-        .with_context(|| format!("Failed to read plugins directory: {}", plugins_dir.display()))?
-    {
-        let entry = entry?;
-        let path = entry.path();
+    let dirs = subdirectories(plugins_dir)
+        .with_context(|| format!("Failed to read plugins directory: {}", plugins_dir.display()))?;
+    let cache = SkillCache::open();
 
-        if path.is_dir() && has_skill_md(&path) {
+    let skills = dirs
+        .par_iter()
+        .filter(|path| has_skill_md(path))
+        .filter_map(|path| {
             let plugin_name = path
                 .file_name()
                 .map(|n| n.to_string_lossy().to_string())
                 .unwrap_or_default();
 
-            match load_plugin_skill(&path, &plugin_name) {
-                Ok(skill) => skills.push(skill),
+            match load_plugin_skill_with(path, &plugin_name, &cache) {
+                Ok(skill) => Some(skill),
                 Err(e) => {
                     log::warn!("Failed to load skill from plugin {}: {}", plugin_name, e);
+                    None
                 }
             }
-        }
-    }
+        })
+        .collect();
 
+    cache.save_quietly();
     Ok(skills)
 }
 
+/// Discover the skills of a team repo
+pub fn discover_team_skills(skills_dir: &Path, team: &str) -> Result<Vec<Skill>> {
+    Ok(discover_simple_skills(skills_dir)?
+        .into_iter()
+        .map(|skill| Skill {
+            source: SkillSource::Team(team.to_string()),
+            ..skill
+        })
+        .collect())
+}
+
 /// Discover all skills (both simple and plugin)
 pub fn discover_all_skills(skills_dir: &Path, plugins_dir: &Path) -> Result<Vec<Skill>> {
     let mut all_skills = discover_simple_skills(skills_dir)?;
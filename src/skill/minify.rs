@@ -0,0 +1,259 @@
+//! Compressed skill bodies for context injection
+//!
+//! Core skills are injected in full at session start. Once the whole
+//! injection would exceed `skills.compression.token-ceiling`, `pais context
+//! inject` swaps the largest core skills for their minified form until it
+//! fits. The minified form is `SKILL.min.md` next to the skill when it was
+//! written for the current SKILL.md (`pais skill minify <name> --write`, which
+//! can also ask Claude to summarize); otherwise rule-based pruning runs on the
+//! fly. Injection never calls Claude, so session start stays fast.
+//!
+//! Rule-based pruning drops sections whose heading is listed in
+//! `drop-sections` (with their subsections), HTML comments and fenced code
+//! blocks longer than `max-code-lines`, then squeezes blank lines.
+
+use eyre::Result;
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+
+use crate::agent::runner;
+use crate::config::SkillCompressionConfig;
+use crate::skill::cache::content_hash;
+
+/// Minified body next to SKILL.md
+pub const MIN_FILE: &str = "SKILL.min.md";
+
+/// First line of SKILL.min.md, naming the SKILL.md content it was made from
+const SOURCE_MARKER: &str = "<!-- pais:minified-from ";
+
+/// Rough token count (about four characters per token)
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Shorten a skill body with the rules from `config`
+pub fn prune(body: &str, config: &SkillCompressionConfig) -> String {
+    static COMMENT: OnceLock<Regex> = OnceLock::new();
+    let comment = COMMENT.get_or_init(|| Regex::new(r"(?s)<!--.*?-->").expect("valid regex"));
+    let body = comment.replace_all(body, "");
+
+    let mut out: Vec<String> = Vec::new();
+    // Heading level of the section being dropped
+    let mut dropping: Option<usize> = None;
+    let mut fence: Option<(String, Vec<&str>)> = None;
+
+    for line in body.lines() {
+        let trimmed = line.trim_start();
+        if let Some((marker, block)) = fence.as_mut() {
+            block.push(line);
+            if trimmed.starts_with(marker.as_str()) {
+                let (_, block) = fence.take().unwrap_or_default();
+                if dropping.is_none() {
+                    let code_lines = block.len().saturating_sub(2);
+                    if code_lines > config.max_code_lines {
+                        out.push(format!("*({}-line code block omitted; see SKILL.md)*", code_lines));
+                    } else {
+                        out.extend(block.iter().map(|l| l.to_string()));
+                    }
+                }
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some((trimmed[..3].to_string(), vec![line]));
+            continue;
+        }
+        if let Some(level) = heading_level(trimmed) {
+            if dropping.is_some_and(|dropped| level > dropped) {
+                continue;
+            }
+            let title = trimmed[level..].trim();
+            dropping = config
+                .drop_sections
+                .iter()
+                .any(|s| s.eq_ignore_ascii_case(title))
+                .then_some(level);
+        }
+        if dropping.is_none() {
+            out.push(line.trim_end().to_string());
+        }
+    }
+    // An unclosed fence runs to the end of the body; keep it as written
+    if let Some((_, block)) = fence
+        && dropping.is_none()
+    {
+        out.extend(block.iter().map(|l| l.to_string()));
+    }
+
+    let mut squeezed: Vec<String> = Vec::new();
+    for line in out {
+        if line.is_empty() && squeezed.last().is_none_or(|l| l.is_empty()) {
+            continue;
+        }
+        squeezed.push(line);
+    }
+    squeezed.join("\n").trim().to_string()
+}
+
+/// `#`-count of a markdown heading line
+fn heading_level(line: &str) -> Option<usize> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    (1..=6)
+        .contains(&level)
+        .then_some(level)
+        .filter(|_| line[level..].starts_with(' '))
+}
+
+/// Ask Claude to condense a skill body
+pub fn summarize(name: &str, body: &str) -> Result<String> {
+    let prompt = format!(
+        "Condense the instructions of the skill '{}' below for an AI coding assistant that \
+         already knows general practice. Keep every rule, command, path, name and constraint; \
+         drop examples, rationale and repetition. Aim for a third of the length. Reply with the \
+         condensed markdown only.\n\n{}",
+        name, body
+    );
+    let mut cmd = Command::new("claude");
+    cmd.arg("-p").arg(prompt).args(["--output-format", "json"]);
+    let (result, ok) = runner::execute(cmd)?;
+    if !ok || result.result.trim().is_empty() {
+        eyre::bail!("Claude could not summarize '{}': {}", name, result.result.trim());
+    }
+    Ok(result.result.trim().to_string())
+}
+
+/// SKILL.min.md content for `body`, minified from `source` (the full SKILL.md)
+pub fn render_min_file(source: &str, body: &str) -> String {
+    format!(
+        "{}{} -->\n{}\n",
+        SOURCE_MARKER,
+        content_hash(source.as_bytes()),
+        body.trim()
+    )
+}
+
+/// The saved minified body, if it was made from the current `source`
+pub fn read_min_file(skill_dir: &Path, source: &str) -> Option<String> {
+    let path = skill_dir.join(MIN_FILE);
+    let content = fs::read_to_string(&path).ok()?;
+    let (first, body) = content.split_once('\n')?;
+    let hash = first.strip_prefix(SOURCE_MARKER)?.strip_suffix(" -->")?;
+    if hash != content_hash(source.as_bytes()) {
+        log::debug!("Ignoring stale {}", path.display());
+        return None;
+    }
+    Some(body.trim().to_string())
+}
+
+/// Swap core skill bodies for minified ones, largest first, until the
+/// injection fits the ceiling
+///
+/// `fixed` is the estimated size of everything else in the injection.
+/// Returns the names of the skills that were compressed.
+pub fn fit_to_ceiling(
+    skills_dir: &Path,
+    core_skills: &mut [(String, String)],
+    fixed: usize,
+    config: &SkillCompressionConfig,
+) -> Vec<String> {
+    let Some(ceiling) = config.token_ceiling else {
+        return Vec::new();
+    };
+    let mut total = fixed + core_skills.iter().map(|(_, body)| estimate_tokens(body)).sum::<usize>();
+    let mut order: Vec<usize> = (0..core_skills.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(estimate_tokens(&core_skills[i].1)));
+
+    let mut compressed = Vec::new();
+    for i in order {
+        if total <= ceiling {
+            break;
+        }
+        let (name, body) = &core_skills[i];
+        let skill_dir = skills_dir.join(name);
+        let skill_md = skill_dir.join("SKILL.md");
+        let minified = fs::read_to_string(&skill_md)
+            .ok()
+            .and_then(|source| read_min_file(&skill_dir, &source))
+            .unwrap_or_else(|| prune(body, config));
+        let minified = format!(
+            "{}\n\n*Condensed to fit the context budget; read {} for the full skill.*",
+            minified,
+            skill_md.display()
+        );
+        let (before, after) = (estimate_tokens(body), estimate_tokens(&minified));
+        if after >= before {
+            continue;
+        }
+        total -= before - after;
+        compressed.push(name.clone());
+        core_skills[i].1 = minified;
+    }
+    if total > ceiling {
+        log::warn!(
+            "Skill context is still ~{} tokens after compression (ceiling {})",
+            total,
+            ceiling
+        );
+    }
+    compressed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const BODY: &str = "# Deploy\n\nAlways run `make check` first.\n\n<!-- internal note -->\n\n\n## Examples\n\nLong example.\n\n### Nested example\n\nMore.\n\n## Rules\n\n```bash\nmake deploy\n```\n\n```python\n1\n2\n3\n4\n```\n";
+
+    fn config() -> SkillCompressionConfig {
+        SkillCompressionConfig {
+            max_code_lines: 3,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_prune() {
+        let pruned = prune(BODY, &config());
+        assert_eq!(
+            pruned,
+            "# Deploy\n\nAlways run `make check` first.\n\n## Rules\n\n```bash\nmake deploy\n```\n\n*(4-line code block omitted; see SKILL.md)*"
+        );
+        assert!(estimate_tokens(&pruned) < estimate_tokens(BODY));
+    }
+
+    #[test]
+    fn test_min_file_and_ceiling() {
+        let temp = tempdir().unwrap();
+        let dir = temp.path().join("deploy");
+        fs::create_dir_all(&dir).unwrap();
+        // Big enough that pruning pays for the pointer to the full skill
+        let body = format!("{}\n## Background\n\n{}", BODY, "Why we deploy this way. ".repeat(40));
+        let source = format!("---\nname: deploy\n---\n{}", body);
+        fs::write(dir.join("SKILL.md"), &source).unwrap();
+
+        // No ceiling: nothing changes
+        let mut skills = vec![("deploy".to_string(), body.clone())];
+        assert!(fit_to_ceiling(temp.path(), &mut skills, 0, &config()).is_empty());
+
+        // Over the ceiling without SKILL.min.md: rule-based pruning
+        let limited = SkillCompressionConfig {
+            token_ceiling: Some(10),
+            ..config()
+        };
+        assert_eq!(fit_to_ceiling(temp.path(), &mut skills, 0, &limited), vec!["deploy"]);
+        assert!(skills[0].1.starts_with("# Deploy\n\nAlways run"));
+        assert!(skills[0].1.contains("for the full skill"));
+
+        // A current SKILL.min.md wins; a stale one is ignored
+        fs::write(dir.join(MIN_FILE), render_min_file(&source, "Run make check.")).unwrap();
+        assert_eq!(read_min_file(&dir, &source).as_deref(), Some("Run make check."));
+        let mut skills = vec![("deploy".to_string(), body.clone())];
+        fit_to_ceiling(temp.path(), &mut skills, 0, &limited);
+        assert!(skills[0].1.starts_with("Run make check.\n"));
+        assert_eq!(read_min_file(&dir, "changed"), None);
+    }
+}
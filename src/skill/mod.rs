@@ -14,11 +14,21 @@
 
 use std::path::PathBuf;
 
+pub mod cache;
+pub mod conflicts;
+pub mod context_cache;
+pub mod distill;
 pub mod indexer;
+pub mod install;
 pub mod loader;
+pub mod minify;
 pub mod parser;
 pub mod scanner;
+pub mod search;
+pub mod subagent;
 pub mod template;
+pub mod upgrade;
+pub mod usage;
 pub mod workflow;
 
 /// A skill that teaches Claude how to do something
@@ -43,6 +53,8 @@ pub enum SkillSource {
     Plugin(String),
     /// Discovered via scan (from .pais/ in a repo)
     Discovered(PathBuf),
+    /// From a subscribed team repo (see [`crate::team`])
+    Team(String),
 }
 
 impl Skill {
@@ -90,6 +102,11 @@ impl Skill {
     pub fn is_discovered(&self) -> bool {
         matches!(self.source, SkillSource::Discovered(_))
     }
+
+    /// Check if this comes from a team repo
+    pub fn is_team(&self) -> bool {
+        matches!(self.source, SkillSource::Team(_))
+    }
 }
 
 #[cfg(test)]
@@ -122,7 +122,7 @@ impl FromStr for SkillTier {
 }
 
 /// Metadata extracted from SKILL.md frontmatter
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SkillMetadata {
     /// Skill name
     pub name: String,
@@ -141,6 +141,46 @@ pub struct SkillMetadata {
     /// Explicit trigger phrases from frontmatter
     #[serde(default)]
     pub triggers: Vec<String>,
+    /// Routing priority when triggers overlap with another skill (higher wins)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+    /// Who wrote the skill, recorded as provenance on install
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// License the skill is distributed under (SPDX identifier)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// Tools the skill may use (`allowed-tools`: a list or a comma-separated string)
+    #[serde(
+        default,
+        rename = "allowed-tools",
+        deserialize_with = "tool_list",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub allowed_tools: Vec<String>,
+}
+
+/// `allowed-tools` as Claude Code writes it (`Read, Grep`) or as a YAML list
+fn tool_list<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Tools {
+        List(Vec<String>),
+        Inline(String),
+    }
+
+    Ok(match Tools::deserialize(deserializer)? {
+        Tools::List(tools) => tools,
+        Tools::Inline(tools) => tools
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(String::from)
+            .collect(),
+    })
 }
 
 /// Parse SKILL.md and extract frontmatter metadata
@@ -151,7 +191,7 @@ pub fn parse_skill_md(path: &Path) -> Result<SkillMetadata> {
 }
 
 /// Parse YAML frontmatter from markdown content
-fn parse_frontmatter(content: &str) -> Result<SkillMetadata> {
+pub(crate) fn parse_frontmatter(content: &str) -> Result<SkillMetadata> {
     // Check for frontmatter delimiter
     let content = content.trim();
     if !content.starts_with("---") {
@@ -215,6 +255,20 @@ Working with infrastructure
         assert_eq!(metadata.tags, vec!["infrastructure", "cloud"]);
     }
 
+    #[test]
+    fn test_parse_allowed_tools() {
+        let inline = parse_frontmatter("---\nname: a\nallowed-tools: Read, Grep,Bash(git:*)\n---\n").unwrap();
+        assert_eq!(inline.allowed_tools, ["Read", "Grep", "Bash(git:*)"]);
+        let list = parse_frontmatter("---\nname: a\nallowed-tools:\n  - Read\n---\n").unwrap();
+        assert_eq!(list.allowed_tools, ["Read"]);
+        assert!(
+            parse_frontmatter("---\nname: a\n---\n")
+                .unwrap()
+                .allowed_tools
+                .is_empty()
+        );
+    }
+
     #[test]
     fn test_parse_frontmatter_minimal() {
         let content = r#"---
@@ -22,8 +22,11 @@ pub struct DiscoveredSkill {
     pub repo_path: PathBuf,
 }
 
-/// Scan a directory for .pais/SKILL.md files
-pub fn scan_for_skills(root: &Path, max_depth: usize) -> Result<Vec<DiscoveredSkill>> {
+/// Scan a directory for .pais/SKILL.md files, calling `on_dir` for each directory visited
+pub fn scan_for_skills<F>(root: &Path, max_depth: usize, mut on_dir: F) -> Result<Vec<DiscoveredSkill>>
+where
+    F: FnMut(&Path),
+{
     let mut found = Vec::new();
 
     if !root.exists() {
@@ -46,6 +49,9 @@ pub fn scan_for_skills(root: &Path, max_depth: usize) -> Result<Vec<DiscoveredSk
         };
 
         let path = entry.path();
+        if entry.file_type().is_dir() {
+            on_dir(path);
+        }
 
         // Look for .pais directories
         if entry.file_type().is_dir() && path.file_name().map(|n| n == ".pais").unwrap_or(false) {
@@ -137,7 +143,7 @@ mod tests {
         fs::create_dir_all(&repo).unwrap();
         create_pais_skill(&repo, "my-tool", "A cool tool");
 
-        let skills = scan_for_skills(temp.path(), 4).unwrap();
+        let skills = scan_for_skills(temp.path(), 4, |_| {}).unwrap();
         assert_eq!(skills.len(), 1);
         assert_eq!(skills[0].name, "my-tool");
         assert_eq!(skills[0].description, "A cool tool");
@@ -155,7 +161,7 @@ mod tests {
             create_pais_skill(&repo, name, &format!("{} description", name));
         }
 
-        let skills = scan_for_skills(temp.path(), 4).unwrap();
+        let skills = scan_for_skills(temp.path(), 4, |_| {}).unwrap();
         assert_eq!(skills.len(), 3);
     }
 
@@ -173,7 +179,7 @@ mod tests {
         fs::create_dir_all(&normal).unwrap();
         create_pais_skill(&normal, "normal", "Should be found");
 
-        let skills = scan_for_skills(temp.path(), 4).unwrap();
+        let skills = scan_for_skills(temp.path(), 4, |_| {}).unwrap();
         assert_eq!(skills.len(), 1);
         assert_eq!(skills[0].name, "normal");
     }
@@ -187,7 +193,7 @@ mod tests {
         fs::create_dir_all(&node_modules).unwrap();
         create_pais_skill(&node_modules, "dep", "Should not be found");
 
-        let skills = scan_for_skills(temp.path(), 4).unwrap();
+        let skills = scan_for_skills(temp.path(), 4, |_| {}).unwrap();
         assert!(skills.is_empty());
     }
 
@@ -201,17 +207,17 @@ mod tests {
         create_pais_skill(&deep, "deep", "Too deep");
 
         // With max_depth=4, shouldn't find it (5 levels deep)
-        let skills = scan_for_skills(temp.path(), 4).unwrap();
+        let skills = scan_for_skills(temp.path(), 4, |_| {}).unwrap();
         assert!(skills.is_empty());
 
         // With max_depth=7, should find it
-        let skills = scan_for_skills(temp.path(), 7).unwrap();
+        let skills = scan_for_skills(temp.path(), 7, |_| {}).unwrap();
         assert_eq!(skills.len(), 1);
     }
 
     #[test]
     fn test_scan_nonexistent_directory() {
-        let skills = scan_for_skills(Path::new("/nonexistent/path"), 4).unwrap();
+        let skills = scan_for_skills(Path::new("/nonexistent/path"), 4, |_| {}).unwrap();
         assert!(skills.is_empty());
     }
 
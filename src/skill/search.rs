@@ -0,0 +1,312 @@
+//! Fuzzy skill search
+//!
+//! `pais skill search <query>` scores each skill against every word of the
+//! query in four fields, weighted name > triggers > description > body. A
+//! query word matches a field word exactly (plurals aside), as a prefix,
+//! inside it, or within an edit or two of it, so typos still find the skill.
+//! Against the name it may also match as an abbreviation: the name's first
+//! letter, then the rest in order (`prr` for `pr-review`). Body words only count on an exact or prefix
+//! match, so long skills don't match everything. A trigger the query
+//! contains, as prompt routing would see it, adds a bonus. Skills matching
+//! more of the query's words rank higher.
+
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+use super::indexer::{prompt_words, trigger_matches};
+use crate::presenter;
+
+const NAME_WEIGHT: f64 = 10.0;
+const TRIGGER_WEIGHT: f64 = 6.0;
+const DESCRIPTION_WEIGHT: f64 = 3.0;
+const BODY_WEIGHT: f64 = 1.0;
+
+/// Weakest word match a body word counts with (a prefix)
+const BODY_MIN_MATCH: f64 = 0.8;
+
+/// Characters of a body line shown as a snippet
+const SNIPPET_CHARS: usize = 80;
+
+/// Where a skill matched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Field {
+    Name,
+    Trigger,
+    Description,
+    Body,
+}
+
+impl std::fmt::Display for Field {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Field::Name => "name",
+            Field::Trigger => "trigger",
+            Field::Description => "description",
+            Field::Body => "body",
+        })
+    }
+}
+
+/// The searchable text of one skill
+pub struct SearchDoc<'a> {
+    pub name: &'a str,
+    pub description: &'a str,
+    pub triggers: &'a [String],
+    pub body: &'a str,
+}
+
+/// A skill the query matched
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchHit {
+    pub name: String,
+    /// Higher is better
+    pub score: f64,
+    pub matched: BTreeSet<Field>,
+    /// The first body line with a matching word, when the body matched
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+}
+
+/// Skills matching `query`, best first (ties by name)
+pub fn search<'a>(query: &str, docs: impl IntoIterator<Item = SearchDoc<'a>>) -> Vec<SearchHit> {
+    let mut hits: Vec<SearchHit> = docs.into_iter().filter_map(|doc| score(query, &doc)).collect();
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.name.cmp(&b.name)));
+    hits
+}
+
+/// How well `doc` matches `query`, or None when no word matches
+pub fn score(query: &str, doc: &SearchDoc) -> Option<SearchHit> {
+    let query_lower = query.trim().to_lowercase();
+    let mut terms = words(&query_lower);
+    terms.dedup();
+    if terms.is_empty() {
+        return None;
+    }
+
+    let name = doc.name.to_lowercase();
+    let name_words = words(&name);
+    let name_letters: String = name.chars().filter(|c| c.is_alphanumeric()).collect();
+    let triggers: Vec<String> = doc.triggers.iter().map(|t| t.to_lowercase()).collect();
+    let trigger_words: Vec<Vec<String>> = triggers.iter().map(|t| words(t)).collect();
+    let description_words = words(&doc.description.to_lowercase());
+    let body_words: BTreeSet<String> = words(&doc.body.to_lowercase()).into_iter().collect();
+
+    let mut total = 0.0;
+    let mut matched_terms = 0;
+    let mut matched = BTreeSet::new();
+    let mut body_terms = Vec::new();
+    for term in &terms {
+        let abbreviation = if term.len() >= 2 && is_abbreviation(term, &name_letters) { 0.4 } else { 0.0 };
+        let body = best_match(term, &body_words);
+        let fields = [
+            (
+                Field::Name,
+                NAME_WEIGHT * best_match(term, &name_words).max(abbreviation),
+            ),
+            (
+                Field::Trigger,
+                TRIGGER_WEIGHT
+                    * trigger_words
+                        .iter()
+                        .map(|words| best_match(term, words))
+                        .fold(0.0, f64::max),
+            ),
+            (
+                Field::Description,
+                DESCRIPTION_WEIGHT * best_match(term, &description_words),
+            ),
+            (
+                Field::Body,
+                if body >= BODY_MIN_MATCH { BODY_WEIGHT * body } else { 0.0 },
+            ),
+        ];
+        let best = fields.iter().map(|(_, score)| *score).fold(0.0, f64::max);
+        if best > 0.0 {
+            matched_terms += 1;
+            total += best;
+        }
+        for (field, score) in fields {
+            if score > 0.0 {
+                matched.insert(field);
+                if field == Field::Body {
+                    body_terms.push(term.as_str());
+                }
+            }
+        }
+    }
+
+    // Whole-query bonuses: the exact name, and triggers routing would pick
+    let query_words = prompt_words(&query_lower);
+    if name == query_lower || name == terms.join("-") {
+        total += NAME_WEIGHT;
+    }
+    for trigger in &triggers {
+        if trigger_matches(trigger, &query_lower, &query_words) {
+            total += TRIGGER_WEIGHT;
+            matched.insert(Field::Trigger);
+        }
+    }
+    if total == 0.0 {
+        return None;
+    }
+
+    Some(SearchHit {
+        name: doc.name.to_string(),
+        score: total * matched_terms.max(1) as f64 / terms.len() as f64,
+        snippet: snippet(doc.body, &body_terms),
+        matched,
+    })
+}
+
+/// Lowercase words of `text`, split at anything not alphanumeric
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// The best [`word_match`] of `term` among `words`
+fn best_match<'a>(term: &str, words: impl IntoIterator<Item = &'a String>) -> f64 {
+    words.into_iter().map(|word| word_match(term, word)).fold(0.0, f64::max)
+}
+
+/// How closely `term` matches `word`, from 1 (the same word) down to 0
+fn word_match(term: &str, word: &str) -> f64 {
+    let singular = |w: &str| {
+        if w.len() > 3 {
+            w.strip_suffix('s').unwrap_or(w).to_string()
+        } else {
+            w.to_string()
+        }
+    };
+    if word == term || singular(word) == singular(term) {
+        1.0
+    } else if term.len() >= 2 && word.starts_with(term) {
+        0.8
+    } else if term.len() >= 3 && word.contains(term) {
+        0.6
+    } else if term.chars().count() >= 4 && edit_distance(term, word) <= if term.len() >= 8 { 2 } else { 1 } {
+        0.5
+    } else {
+        0.0
+    }
+}
+
+/// Whether `term` abbreviates `text`: same first letter, and the rest in order
+fn is_abbreviation(term: &str, text: &str) -> bool {
+    let mut letters = text.chars();
+    term.chars().next() == letters.next() && term.chars().skip(1).all(|c| letters.any(|l| l == c))
+}
+
+/// Edits (insertions, deletions, substitutions and swaps of neighbours) between `a` and `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// The first non-empty body line mentioning one of `terms`
+fn snippet(body: &str, terms: &[&str]) -> Option<String> {
+    if terms.is_empty() {
+        return None;
+    }
+    body.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .find(|line| {
+            let line = line.to_lowercase();
+            terms.iter().any(|term| line.contains(term))
+        })
+        .map(|line| presenter::truncate(line.trim_start_matches(['#', '-', '*', ' ']), SNIPPET_CHARS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc<'a>(name: &'a str, description: &'a str, triggers: &'a [String], body: &'a str) -> SearchDoc<'a> {
+        SearchDoc {
+            name,
+            description,
+            triggers,
+            body,
+        }
+    }
+
+    #[test]
+    fn test_word_match() {
+        assert_eq!(word_match("deploy", "deploys"), 1.0);
+        assert_eq!(word_match("kube", "kubernetes"), 0.8);
+        assert_eq!(word_match("ploy", "deploy"), 0.6);
+        assert_eq!(word_match("kubernets", "kubernetes"), 0.5);
+        assert_eq!(word_match("rust", "dust"), 0.5);
+        assert_eq!(word_match("go", "rust"), 0.0);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("dokcer", "docker"), 1);
+        assert!(is_abbreviation("prr", "prreview"));
+        assert!(!is_abbreviation("rr", "prreview"));
+    }
+
+    #[test]
+    fn test_search_ranks_fields() {
+        let review_triggers = ["pull request".to_string(), "code review".to_string()];
+        let docker_triggers = ["container".to_string()];
+        let docs = [
+            doc(
+                "pr-review",
+                "Review GitHub pull requests",
+                &review_triggers,
+                "Check the diff.",
+            ),
+            doc(
+                "docker",
+                "Build and run containers",
+                &docker_triggers,
+                "Use multi-stage builds.\n\n## Review\nReview the Dockerfile.",
+            ),
+            doc("writing", "Prose style", &[], "Short sentences."),
+        ];
+
+        let hits = search(
+            "review",
+            docs.iter().map(|d| doc(d.name, d.description, d.triggers, d.body)),
+        );
+        let names: Vec<_> = hits.iter().map(|h| h.name.as_str()).collect();
+        assert_eq!(names, ["pr-review", "docker"]);
+        assert!(hits[0].matched.contains(&Field::Name));
+        assert_eq!(hits[1].matched, BTreeSet::from([Field::Body]));
+        assert_eq!(hits[1].snippet.as_deref(), Some("Review"));
+
+        // A typo, an abbreviation and a trigger phrase
+        let best = |query| {
+            search(
+                query,
+                docs.iter().map(|d| doc(d.name, d.description, d.triggers, d.body)),
+            )
+        };
+        assert_eq!(best("dokcer")[0].name, "docker");
+        assert_eq!(best("prr")[0].name, "pr-review");
+        let trigger = best("open a pull request");
+        assert_eq!(trigger[0].name, "pr-review");
+        assert!(trigger[0].matched.contains(&Field::Trigger));
+        assert!(best("kotlin").is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+//! Claude Code subagents generated from skills
+//!
+//! `pais skill promote <name> --to-subagent` writes `~/.claude/agents/<name>.md`
+//! with the skill's description, its `allowed-tools` as the agent's `tools`
+//! (all tools when unset) and its body as the system prompt. The first
+//! frontmatter line is a comment naming the skill; `pais sync` regenerates
+//! every agent file carrying one and removes those whose skill is gone, so
+//! edits belong in the skill.
+
+use eyre::{Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::parser::parse_frontmatter;
+use crate::commands::context::extract_skill_body;
+use crate::error::{CliError, ErrorCode};
+
+/// Start of the comment that marks an agent file as generated from a skill
+const MARKER: &str = "# pais-skill: ";
+
+#[derive(Serialize)]
+struct Frontmatter<'a> {
+    name: &'a str,
+    description: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<String>,
+}
+
+/// Agent definition for the skill `name`, from its SKILL.md content
+pub fn render(name: &str, skill_md: &str) -> Result<String> {
+    let metadata = parse_frontmatter(skill_md)?;
+    let body = extract_skill_body(skill_md).ok_or_else(|| {
+        CliError::new(
+            ErrorCode::InvalidInput,
+            format!("Skill '{}' has no body to use as the system prompt", name),
+        )
+    })?;
+    let frontmatter = serde_yaml::to_string(&Frontmatter {
+        name,
+        description: &metadata.description,
+        tools: (!metadata.allowed_tools.is_empty()).then(|| metadata.allowed_tools.join(", ")),
+    })?;
+    Ok(format!(
+        "---\n{}{} (generated; edit the skill, then run `pais sync`)\n{}---\n\n{}\n",
+        MARKER, name, frontmatter, body
+    ))
+}
+
+/// Agent definition for the skill in `skill_dir`
+pub fn generate(name: &str, skill_dir: &Path) -> Result<String> {
+    let skill_md = skill_dir.join("SKILL.md");
+    let content = fs::read_to_string(&skill_md).with_context(|| format!("Failed to read {}", skill_md.display()))?;
+    render(name, &content).with_context(|| format!("Failed to turn {} into a subagent", skill_md.display()))
+}
+
+/// The skill an agent file was generated from, if it was
+pub fn source_skill(agent_md: &str) -> Option<&str> {
+    agent_md
+        .strip_prefix("---\n")?
+        .lines()
+        .next()?
+        .strip_prefix(MARKER)?
+        .split_whitespace()
+        .next()
+}
+
+/// Agent files in `agents_dir` generated from skills, with the skill each came from
+pub fn promoted(agents_dir: &Path) -> Result<Vec<(PathBuf, String)>> {
+    if !agents_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut found = Vec::new();
+    for entry in fs::read_dir(agents_dir).with_context(|| format!("Failed to read {}", agents_dir.display()))? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|e| e != "md") {
+            continue;
+        }
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        if let Some(skill) = source_skill(&content) {
+            found.push((path.clone(), skill.to_string()));
+        }
+    }
+    found.sort();
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SKILL: &str = "---\nname: reviewer\ndescription: \"Review diffs: style and bugs\"\nallowed-tools: Read, Grep\n---\n\n# Reviewer\n\nRead the diff.\n";
+
+    #[test]
+    fn test_render_subagent() {
+        let agent = render("reviewer", SKILL).unwrap();
+        assert!(agent.starts_with("---\n# pais-skill: reviewer "));
+        assert!(agent.contains("\nname: reviewer\n"));
+        assert!(agent.contains("tools: Read, Grep\n"));
+        assert!(agent.ends_with("---\n\n# Reviewer\n\nRead the diff.\n"));
+        assert_eq!(source_skill(&agent), Some("reviewer"));
+
+        let parsed = parse_frontmatter(&agent).unwrap();
+        assert_eq!(parsed.description, "Review diffs: style and bugs");
+
+        let no_tools = render("r", "---\nname: r\n---\n\nBody\n").unwrap();
+        assert!(!no_tools.contains("tools:"));
+        assert!(render("r", "---\nname: r\n---\n").is_err());
+        assert_eq!(source_skill("---\nname: hand-written\n---\n"), None);
+    }
+}
@@ -21,6 +21,7 @@ pub fn generate_skill_template(name: &str) -> String {
         r#"---
 name: {name}
 description: "Brief description of what this skill does"
+version: 0.1.0
 allowed-tools: Bash({name}:*)
 tags: []
 ---
@@ -0,0 +1,150 @@
+//! Version checks for skills installed from remote sources
+//!
+//! A skill's `version:` frontmatter and its source commit tell whether the
+//! copy in its git repository or gist moved on. `pais skill outdated` lists
+//! what did; `pais skill upgrade` shows the changelog entries in between
+//! before overwriting the installed copy.
+
+use serde::Serialize;
+use std::cmp::Ordering;
+
+/// Changelog files looked for in a skill directory
+pub const CHANGELOG_FILES: &[&str] = &["CHANGELOG.md", "CHANGES.md"];
+
+/// How a remote copy of a skill relates to the installed one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Status {
+    UpToDate,
+    /// The remote declares a higher `version:`
+    NewVersion,
+    /// Same version, but the source commit or revision moved
+    Changed,
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::UpToDate => "up to date",
+            Self::NewVersion => "new version",
+            Self::Changed => "changed",
+        })
+    }
+}
+
+/// Compare the installed version and commit with the remote ones
+pub fn status(
+    installed_version: Option<&str>,
+    installed_commit: Option<&str>,
+    remote_version: Option<&str>,
+    remote_commit: Option<&str>,
+) -> Status {
+    if let (Some(installed), Some(remote)) = (installed_version, remote_version)
+        && compare(remote, installed) == Ordering::Greater
+    {
+        return Status::NewVersion;
+    }
+    match (installed_commit, remote_commit) {
+        (Some(installed), Some(remote)) if installed != remote => Status::Changed,
+        // Nothing recorded to compare against: a version bump is the only signal left
+        (None, Some(_)) if installed_version != remote_version => Status::Changed,
+        _ => Status::UpToDate,
+    }
+}
+
+/// Order two versions by their numeric dot-separated parts (`v1.10` > `1.9.3`)
+///
+/// Missing parts count as zero; a version with a non-numeric part is
+/// compared as text.
+pub fn compare(a: &str, b: &str) -> Ordering {
+    match (numeric_parts(a), numeric_parts(b)) {
+        (Some(a), Some(b)) => {
+            let len = a.len().max(b.len());
+            let pad = |v: &[u64]| (0..len).map(|i| v.get(i).copied().unwrap_or(0)).collect::<Vec<_>>();
+            pad(&a).cmp(&pad(&b))
+        }
+        _ => a.cmp(b),
+    }
+}
+
+fn numeric_parts(version: &str) -> Option<Vec<u64>> {
+    let core = version.trim().trim_start_matches('v').split(['-', '+']).next()?;
+    core.split('.').map(|part| part.parse().ok()).collect()
+}
+
+/// The changelog sections for versions after `installed`, up to and including `latest`
+///
+/// Sections start at `## ` headings naming a version (`## 1.2.0`,
+/// `## [1.2.0] - 2026-01-05`). With no installed version, every section up to
+/// `latest` is kept. Returns None when no section qualifies.
+pub fn changelog_since(changelog: &str, installed: Option<&str>, latest: Option<&str>) -> Option<String> {
+    let mut kept = Vec::new();
+    let mut keeping = false;
+    for line in changelog.lines() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            keeping = match heading_version(heading) {
+                Some(version) => {
+                    installed.is_none_or(|installed| compare(&version, installed) == Ordering::Greater)
+                        && latest.is_none_or(|latest| compare(&version, latest) != Ordering::Greater)
+                }
+                // "Unreleased" and other headings without a version
+                None => false,
+            };
+        } else if line.starts_with("# ") {
+            keeping = false;
+        }
+        if keeping {
+            kept.push(line);
+        }
+    }
+    let text = kept.join("\n").trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// The version a changelog heading names
+fn heading_version(heading: &str) -> Option<String> {
+    heading
+        .split(|c: char| c.is_whitespace() || c == '[' || c == ']')
+        .find(|word| numeric_parts(word).is_some())
+        .map(|word| word.trim_start_matches('v').to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare() {
+        assert_eq!(compare("1.10.0", "1.9.3"), Ordering::Greater);
+        assert_eq!(compare("v2", "2.0.0"), Ordering::Equal);
+        assert_eq!(compare("1.2.0-beta", "1.2.1"), Ordering::Less);
+        assert_eq!(compare("draft", "1.0"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_status() {
+        assert_eq!(
+            status(Some("1.0"), Some("a"), Some("1.1"), Some("b")),
+            Status::NewVersion
+        );
+        assert_eq!(status(Some("1.0"), Some("a"), Some("1.0"), Some("b")), Status::Changed);
+        assert_eq!(status(Some("1.0"), Some("a"), Some("1.0"), Some("a")), Status::UpToDate);
+        assert_eq!(status(None, None, None, Some("b")), Status::UpToDate);
+        assert_eq!(status(Some("1.0"), None, Some("1.0.1"), None), Status::NewVersion);
+    }
+
+    #[test]
+    fn test_changelog_since() {
+        let changelog = "# Changelog\n\n## Unreleased\n- wip\n\n## [1.2.0] - 2026-05-01\n- Added review workflow\n\n## 1.1.0\n- Shorter triggers\n\n## 1.0.0\n- First release\n";
+        assert_eq!(
+            changelog_since(changelog, Some("1.0.0"), Some("1.2.0")).unwrap(),
+            "## [1.2.0] - 2026-05-01\n- Added review workflow\n\n## 1.1.0\n- Shorter triggers"
+        );
+        assert_eq!(changelog_since(changelog, Some("1.1.0"), Some("1.1.5")), None);
+        assert!(
+            changelog_since(changelog, None, Some("1.0.0"))
+                .unwrap()
+                .contains("First release")
+        );
+    }
+}
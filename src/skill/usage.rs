@@ -0,0 +1,346 @@
+//! Skill usage store
+//!
+//! The skill-usage hook records each time Claude triggers a skill (through the
+//! Skill tool, by reading one of its files, or by a `/skill` prompt) in
+//! `~/.local/share/pais/skill-usage.json`. `pais context inject` uses it to
+//! spend the context budget on skills that are actually in use: recently used
+//! deferred skills are expanded, long-unused ones shrink to a mention.
+//!
+//! Each trigger is also a `SkillUsed` event in the observability stream;
+//! `pais skill stats` counts those per skill and week.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::SkillUsageConfig;
+use crate::lock::FileLock;
+
+/// Where usage is stored
+pub fn usage_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("pais")
+        .join("skill-usage.json")
+}
+
+/// Usage of one skill
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SkillUsage {
+    pub count: u64,
+    pub last_used: DateTime<Utc>,
+}
+
+/// How much context a deferred skill gets, by how recently it was used
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum UsageTier {
+    /// Used within `recent-days`: full description and triggers
+    Recent,
+    /// A regular table row
+    Normal,
+    /// Unused for `demote-after-days`: a one-line mention
+    Stale,
+}
+
+impl std::fmt::Display for UsageTier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UsageTier::Recent => write!(f, "recent"),
+            UsageTier::Normal => write!(f, "normal"),
+            UsageTier::Stale => write!(f, "stale"),
+        }
+    }
+}
+
+/// All recorded skill usage
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct UsageStore {
+    /// When tracking started; skills never used only count as stale once
+    /// tracking has run for `demote-after-days`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub skills: BTreeMap<String, SkillUsage>,
+}
+
+impl UsageStore {
+    /// Load the store, empty when missing or unreadable
+    pub fn load(path: &Path) -> Self {
+        fs::read(path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let json = serde_json::to_vec_pretty(self).context("Failed to serialize skill usage")?;
+        fs::write(path, json).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Count one use of `skill` at `when`
+    pub fn record(&mut self, skill: &str, when: DateTime<Utc>) {
+        self.since.get_or_insert(when);
+        let usage = self.skills.entry(skill.to_string()).or_insert(SkillUsage {
+            count: 0,
+            last_used: when,
+        });
+        usage.count += 1;
+        usage.last_used = usage.last_used.max(when);
+    }
+
+    /// Tier of `skill` at `now`. Without any recorded usage every skill is
+    /// `Normal`, so a fresh install injects exactly what it did before.
+    pub fn tier(&self, skill: &str, now: DateTime<Utc>, config: &SkillUsageConfig) -> UsageTier {
+        if self.since.is_none() {
+            return UsageTier::Normal;
+        }
+        let recent = now - Duration::days(config.recent_days.into());
+        let stale = now - Duration::days(config.demote_after_days.into());
+        match self.skills.get(skill) {
+            Some(usage) if usage.last_used >= recent => UsageTier::Recent,
+            Some(usage) if usage.last_used < stale => UsageTier::Stale,
+            Some(_) => UsageTier::Normal,
+            None => self.unused_tier(now, config),
+        }
+    }
+
+    /// Tier of every skill that was never used
+    pub fn unused_tier(&self, now: DateTime<Utc>, config: &SkillUsageConfig) -> UsageTier {
+        match self.since {
+            Some(since) if since < now - Duration::days(config.demote_after_days.into()) => UsageTier::Stale,
+            _ => UsageTier::Normal,
+        }
+    }
+}
+
+/// Record one use under the store's lock
+pub fn record(path: &Path, skill: &str, when: DateTime<Utc>) -> Result<()> {
+    let _lock = FileLock::acquire(path)?;
+    let mut store = UsageStore::load(path);
+    store.record(skill, when);
+    store.save(path)
+}
+
+/// How Claude came to use a skill
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Trigger {
+    /// A call to the Skill tool
+    SkillTool,
+    /// A Read of the skill's SKILL.md or another of its files
+    Read,
+    /// A prompt starting with `/<skill>`
+    Prompt,
+}
+
+impl std::fmt::Display for Trigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Trigger::SkillTool => "skill-tool",
+            Trigger::Read => "read",
+            Trigger::Prompt => "prompt",
+        })
+    }
+}
+
+/// The skill a PreToolUse payload triggers, if any: the Skill tool, a Read of
+/// some `<skill>/SKILL.md`, or a Read of any file in a skill under one of `skills_dirs`
+pub fn triggered_skill(payload: &serde_json::Value, skills_dirs: &[PathBuf]) -> Option<(String, Trigger)> {
+    let input = payload.get("tool_input")?;
+    match payload.get("tool_name")?.as_str()? {
+        "Skill" => {
+            let name = input.get("skill").or_else(|| input.get("command"))?.as_str()?;
+            Some((skill_name(name)?, Trigger::SkillTool))
+        }
+        "Read" => {
+            let path = Path::new(input.get("file_path")?.as_str()?);
+            // Files at the top of a skills directory (a README) belong to no skill
+            let in_skill = skills_dirs
+                .iter()
+                .filter_map(|dir| path.strip_prefix(dir).ok())
+                .find(|rel| rel.components().count() > 1);
+            let name = match in_skill {
+                Some(rel) => rel.components().next()?.as_os_str(),
+                None if path.file_name()? == "SKILL.md" => path.parent()?.file_name()?,
+                None => return None,
+            };
+            Some((name.to_string_lossy().to_string(), Trigger::Read))
+        }
+        _ => None,
+    }
+}
+
+/// The skill a prompt invokes with a leading `/<skill>`, if `is_skill` knows it
+///
+/// Built-in slash commands such as `/clear` are not skills, so only names
+/// `is_skill` accepts count.
+pub fn prompted_skill(prompt: &str, is_skill: impl Fn(&str) -> bool) -> Option<String> {
+    let command = prompt.trim_start().strip_prefix('/')?.split_whitespace().next()?;
+    skill_name(command).filter(|name| is_skill(name))
+}
+
+/// A skill name without its leading slash or `plugin:` namespace
+fn skill_name(name: &str) -> Option<String> {
+    let name = name.trim().trim_start_matches('/');
+    // Plugin skills are namespaced as `plugin:skill`
+    let name = name.rsplit(':').next().unwrap_or(name);
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// One skill's uses over a window, as `pais skill stats` shows them
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SkillStats {
+    pub name: String,
+    pub uses: usize,
+    /// Distinct sessions the skill was used in
+    pub sessions: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<DateTime<Utc>>,
+    /// Uses per week, oldest first
+    pub weeks: Vec<usize>,
+}
+
+/// Count `uses` (when, session, skill) per skill over the `weeks` weeks ending `today`
+///
+/// Every name in `installed` gets a row, so skills nobody used show up with
+/// zero uses. Rows are ordered by uses, most first.
+pub fn stats<'a>(
+    uses: impl IntoIterator<Item = (DateTime<Utc>, Option<&'a str>, &'a str)>,
+    installed: &[String],
+    today: NaiveDate,
+    weeks: usize,
+) -> Vec<SkillStats> {
+    let since = today - Duration::days(weeks as i64 * 7 - 1);
+    let new_row = |name: &str| SkillStats {
+        name: name.to_string(),
+        uses: 0,
+        sessions: 0,
+        last_used: None,
+        weeks: vec![0; weeks],
+    };
+    let mut rows: BTreeMap<String, SkillStats> = installed.iter().map(|name| (name.clone(), new_row(name))).collect();
+    let mut sessions: BTreeSet<(&str, &str)> = BTreeSet::new();
+    for (when, session, skill) in uses {
+        let Ok(offset) = usize::try_from((when.date_naive() - since).num_days()) else {
+            continue;
+        };
+        if offset >= weeks * 7 {
+            continue;
+        }
+        let row = rows.entry(skill.to_string()).or_insert_with(|| new_row(skill));
+        row.uses += 1;
+        row.weeks[offset / 7] += 1;
+        row.last_used = row.last_used.max(Some(when));
+        if let Some(session) = session
+            && sessions.insert((skill, session))
+        {
+            row.sessions += 1;
+        }
+    }
+    let mut rows: Vec<SkillStats> = rows.into_values().collect();
+    rows.sort_by(|a, b| b.uses.cmp(&a.uses).then_with(|| a.name.cmp(&b.name)));
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Datelike;
+    use serde_json::json;
+
+    #[test]
+    fn test_triggered_skill() {
+        let skills_dirs = [PathBuf::from("/home/u/.config/pais/skills")];
+        let triggered = |payload| triggered_skill(&payload, &skills_dirs);
+        let skill = json!({"tool_name": "Skill", "tool_input": {"skill": "github:pr-review"}});
+        assert_eq!(triggered(skill), Some(("pr-review".to_string(), Trigger::SkillTool)));
+        let command = json!({"tool_name": "Skill", "tool_input": {"command": "rust"}});
+        assert_eq!(triggered(command), Some(("rust".to_string(), Trigger::SkillTool)));
+        let read = json!({"tool_name": "Read", "tool_input": {"file_path": "/home/u/.claude/skills/deploy/SKILL.md"}});
+        assert_eq!(triggered(read), Some(("deploy".to_string(), Trigger::Read)));
+        let workflow = json!({
+            "tool_name": "Read",
+            "tool_input": {"file_path": "/home/u/.config/pais/skills/deploy/workflows/rollback.md"}
+        });
+        assert_eq!(triggered(workflow), Some(("deploy".to_string(), Trigger::Read)));
+        let other = json!({"tool_name": "Read", "tool_input": {"file_path": "/src/main.rs"}});
+        assert_eq!(triggered(other), None);
+        let readme = json!({"tool_name": "Read", "tool_input": {"file_path": "/home/u/.config/pais/skills/README.md"}});
+        assert_eq!(triggered(readme), None);
+    }
+
+    #[test]
+    fn test_prompted_skill() {
+        let is_skill = |name: &str| name == "deploy";
+        assert_eq!(prompted_skill("/deploy staging", is_skill).as_deref(), Some("deploy"));
+        assert_eq!(prompted_skill("  /ops:deploy", is_skill).as_deref(), Some("deploy"));
+        assert_eq!(prompted_skill("/clear", is_skill), None);
+        assert_eq!(prompted_skill("please deploy", is_skill), None);
+    }
+
+    #[test]
+    fn test_stats() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 14).unwrap();
+        let at = |day: u32| today.with_day(day).unwrap().and_hms_opt(12, 0, 0).unwrap().and_utc();
+        let uses = [
+            (at(14), Some("s1"), "rust"),
+            (at(14), Some("s1"), "rust"),
+            (at(2), Some("s2"), "rust"),
+            (at(10), None, "deploy"),
+            // Before the two-week window
+            (at(1) - Duration::days(1), Some("s3"), "rust"),
+        ];
+        let installed = ["rust".to_string(), "writing".to_string()];
+        let rows = stats(uses, &installed, today, 2);
+        let summary: Vec<_> = rows
+            .iter()
+            .map(|r| (r.name.as_str(), r.uses, r.sessions, r.weeks.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("rust", 3, 2, vec![1, 2]),
+                ("deploy", 1, 0, vec![0, 1]),
+                ("writing", 0, 0, vec![0, 0]),
+            ]
+        );
+        assert_eq!(rows[0].last_used, Some(at(14)));
+        assert_eq!(rows[2].last_used, None);
+    }
+
+    #[test]
+    fn test_tiers() {
+        let config = SkillUsageConfig::default();
+        let now = Utc::now();
+        let mut store = UsageStore::default();
+        assert_eq!(store.tier("rust", now, &config), UsageTier::Normal);
+
+        store.record("old", now - Duration::days(60));
+        store.record("rust", now - Duration::days(1));
+        store.record("rust", now - Duration::days(10));
+        store.record("mid", now - Duration::days(10));
+
+        assert_eq!(store.skills["rust"].count, 2);
+        assert_eq!(store.skills["rust"].last_used, now - Duration::days(1));
+        assert_eq!(store.tier("rust", now, &config), UsageTier::Recent);
+        assert_eq!(store.tier("mid", now, &config), UsageTier::Normal);
+        assert_eq!(store.tier("old", now, &config), UsageTier::Stale);
+        // Tracking started 60 days ago, so never-used skills are stale too
+        assert_eq!(store.tier("never", now, &config), UsageTier::Stale);
+
+        let young = UsageStore {
+            since: Some(now - Duration::days(5)),
+            ..Default::default()
+        };
+        assert_eq!(young.tier("never", now, &config), UsageTier::Normal);
+    }
+}
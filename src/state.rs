@@ -0,0 +1,449 @@
+//! Append-only log of what pais changed in its own setup
+//!
+//! Installs, removals, syncs, config changes and migrations append one JSON
+//! line to `~/.local/share/pais/state.jsonl`. Each record carries the hash of
+//! the one before it, so truncation or hand edits are caught by
+//! `pais state verify`. Folding the log from the start reconstructs what pais
+//! believes is installed, which `verify` compares against the disk.
+//!
+//! The hash is the same FNV-1a used for cache keys: it detects accidents,
+//! not tampering.
+
+use chrono::{DateTime, Utc};
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::lock::FileLock;
+use crate::skill::cache::content_hash;
+
+/// Hash that precedes the first record
+const GENESIS: &str = "0000000000000000";
+
+/// Where the log lives
+pub fn log_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("pais")
+        .join("state.jsonl")
+}
+
+/// Something pais did to its setup
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum StateEvent {
+    #[serde(rename_all = "kebab-case")]
+    PluginInstalled {
+        name: String,
+        version: String,
+        source: String,
+        dev: bool,
+    },
+    PluginRemoved {
+        name: String,
+    },
+    SkillInstalled {
+        name: String,
+        source: String,
+    },
+    SkillRemoved {
+        name: String,
+    },
+    AgentInstalled {
+        name: String,
+        source: String,
+    },
+    BundleInstalled {
+        name: String,
+        plugins: Vec<String>,
+    },
+    /// `pais sync` / `pais sync --clean`
+    SkillsSynced {
+        linked: usize,
+        removed: usize,
+    },
+    RemoteSynced {
+        remote: String,
+        pulled: usize,
+        pushed: usize,
+        merged: usize,
+        conflicts: usize,
+    },
+    /// `digest` is the content hash of pais.yaml after the change
+    ConfigChanged {
+        key: String,
+        digest: String,
+    },
+    Migrated {
+        from: u32,
+        to: u32,
+        description: String,
+    },
+}
+
+impl StateEvent {
+    /// Short name, as used by `pais state log --event`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            StateEvent::PluginInstalled { .. } => "plugin-installed",
+            StateEvent::PluginRemoved { .. } => "plugin-removed",
+            StateEvent::SkillInstalled { .. } => "skill-installed",
+            StateEvent::SkillRemoved { .. } => "skill-removed",
+            StateEvent::AgentInstalled { .. } => "agent-installed",
+            StateEvent::BundleInstalled { .. } => "bundle-installed",
+            StateEvent::SkillsSynced { .. } => "skills-synced",
+            StateEvent::RemoteSynced { .. } => "remote-synced",
+            StateEvent::ConfigChanged { .. } => "config-changed",
+            StateEvent::Migrated { .. } => "migrated",
+        }
+    }
+
+    /// One-line description for `pais state log`
+    pub fn summary(&self) -> String {
+        match self {
+            StateEvent::PluginInstalled {
+                name,
+                version,
+                source,
+                dev,
+            } => format!(
+                "{} v{} from {}{}",
+                name,
+                version,
+                source,
+                if *dev { " (dev link)" } else { "" }
+            ),
+            StateEvent::PluginRemoved { name } | StateEvent::SkillRemoved { name } => name.clone(),
+            StateEvent::SkillInstalled { name, source } | StateEvent::AgentInstalled { name, source } => {
+                format!("{} from {}", name, source)
+            }
+            StateEvent::BundleInstalled { name, plugins } => format!("{} ({})", name, plugins.join(", ")),
+            StateEvent::SkillsSynced { linked, removed } => format!("{} linked, {} removed", linked, removed),
+            StateEvent::RemoteSynced {
+                remote,
+                pulled,
+                pushed,
+                merged,
+                conflicts,
+            } => format!(
+                "{}: {} pulled, {} pushed, {} merged, {} conflicts",
+                remote, pulled, pushed, merged, conflicts
+            ),
+            StateEvent::ConfigChanged { key, .. } => key.clone(),
+            StateEvent::Migrated { from, to, description } => format!("v{} → v{}: {}", from, to, description),
+        }
+    }
+}
+
+/// One line of the log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateRecord {
+    pub seq: u64,
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event: StateEvent,
+    /// Hash of the previous record
+    pub prev: String,
+    pub hash: String,
+}
+
+impl StateRecord {
+    fn compute_hash(seq: u64, at: &DateTime<Utc>, event: &StateEvent, prev: &str) -> String {
+        let event = serde_json::to_string(event).unwrap_or_default();
+        content_hash(format!("{}|{}|{}|{}", seq, at.to_rfc3339(), event, prev).as_bytes())
+    }
+
+    fn new(seq: u64, at: DateTime<Utc>, event: StateEvent, prev: String) -> Self {
+        let hash = Self::compute_hash(seq, &at, &event, &prev);
+        Self {
+            seq,
+            at,
+            event,
+            prev,
+            hash,
+        }
+    }
+
+    fn is_intact(&self) -> bool {
+        self.hash == Self::compute_hash(self.seq, &self.at, &self.event, &self.prev)
+    }
+}
+
+/// The log file
+pub struct StateLog {
+    path: PathBuf,
+}
+
+impl StateLog {
+    pub fn open() -> Self {
+        Self::at(log_path())
+    }
+
+    pub fn at(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read every line; unparseable lines are returned as errors in place
+    pub fn read(&self) -> Result<Vec<Result<StateRecord, String>>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content =
+            fs::read_to_string(&self.path).with_context(|| format!("Failed to read {}", self.path.display()))?;
+        Ok(content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(|e| e.to_string()))
+            .collect())
+    }
+
+    /// Parsed records, skipping unreadable lines
+    pub fn records(&self) -> Result<Vec<StateRecord>> {
+        Ok(self.read()?.into_iter().filter_map(Result::ok).collect())
+    }
+
+    /// Append an event, chained to the last record
+    pub fn append(&self, event: StateEvent) -> Result<StateRecord> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let _lock = FileLock::acquire(&self.path)?;
+        let last = self.records()?.pop();
+        let (seq, prev) = match last {
+            Some(last) => (last.seq + 1, last.hash),
+            None => (1, GENESIS.to_string()),
+        };
+        let record = StateRecord::new(seq, Utc::now(), event, prev);
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open {}", self.path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(&record)?)
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+        Ok(record)
+    }
+}
+
+/// Append an event to the user's log. State tracking never fails the
+/// command that made the change; problems are logged instead.
+pub fn record(event: StateEvent) {
+    if let Err(e) = StateLog::open().append(event) {
+        log::warn!("Failed to record state change: {:#}", e);
+    }
+}
+
+/// A plugin as the log last saw it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PluginState {
+    pub version: String,
+    pub source: String,
+    pub dev: bool,
+    pub installed: DateTime<Utc>,
+}
+
+/// An installed skill, agent or bundle
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ItemState {
+    pub source: String,
+    pub installed: DateTime<Utc>,
+}
+
+/// What the log says the setup looks like
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct State {
+    pub plugins: BTreeMap<String, PluginState>,
+    pub skills: BTreeMap<String, ItemState>,
+    pub agents: BTreeMap<String, ItemState>,
+    pub bundles: BTreeMap<String, ItemState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_version: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_sync: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_remote_sync: Option<DateTime<Utc>>,
+    /// Records folded in
+    pub events: usize,
+}
+
+impl State {
+    /// Replay records in order
+    pub fn replay<'a>(records: impl IntoIterator<Item = &'a StateRecord>) -> Self {
+        let mut state = Self::default();
+        for record in records {
+            state.apply(record);
+        }
+        state
+    }
+
+    fn apply(&mut self, record: &StateRecord) {
+        let at = record.at;
+        let item = |source: &str| ItemState {
+            source: source.to_string(),
+            installed: at,
+        };
+        match &record.event {
+            StateEvent::PluginInstalled {
+                name,
+                version,
+                source,
+                dev,
+            } => {
+                self.plugins.insert(
+                    name.clone(),
+                    PluginState {
+                        version: version.clone(),
+                        source: source.clone(),
+                        dev: *dev,
+                        installed: at,
+                    },
+                );
+            }
+            StateEvent::PluginRemoved { name } => {
+                self.plugins.remove(name);
+            }
+            StateEvent::SkillInstalled { name, source } => {
+                self.skills.insert(name.clone(), item(source));
+            }
+            StateEvent::SkillRemoved { name } => {
+                self.skills.remove(name);
+            }
+            StateEvent::AgentInstalled { name, source } => {
+                self.agents.insert(name.clone(), item(source));
+            }
+            StateEvent::BundleInstalled { name, .. } => {
+                self.bundles.insert(name.clone(), item("bundles"));
+            }
+            StateEvent::SkillsSynced { .. } => self.last_sync = Some(at),
+            StateEvent::RemoteSynced { .. } => self.last_remote_sync = Some(at),
+            StateEvent::ConfigChanged { digest, .. } => self.config_hash = Some(digest.clone()),
+            StateEvent::Migrated { to, .. } => self.config_version = Some(*to),
+        }
+        self.events += 1;
+    }
+}
+
+/// A break in the hash chain
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChainError {
+    /// 1-based line number
+    pub line: usize,
+    pub message: String,
+}
+
+/// Check that every line parses, sequence numbers increase by one and each
+/// record's hash matches its content and its successor's `prev`
+pub fn verify_chain(lines: &[Result<StateRecord, String>]) -> Vec<ChainError> {
+    let mut errors = Vec::new();
+    let mut prev: Option<&StateRecord> = None;
+    for (i, line) in lines.iter().enumerate() {
+        let error = |message: String| ChainError { line: i + 1, message };
+        let record = match line {
+            Ok(record) => record,
+            Err(e) => {
+                errors.push(error(format!("unreadable record: {}", e)));
+                continue;
+            }
+        };
+        if !record.is_intact() {
+            errors.push(error(format!(
+                "record {} was modified after it was written",
+                record.seq
+            )));
+        }
+        let (expected_seq, expected_prev) = match prev {
+            Some(p) => (p.seq + 1, p.hash.as_str()),
+            None => (1, GENESIS),
+        };
+        if record.seq != expected_seq {
+            errors.push(error(format!(
+                "expected record {}, found {} (records missing or reordered)",
+                expected_seq, record.seq
+            )));
+        } else if record.prev != expected_prev {
+            errors.push(error(format!(
+                "record {} does not follow the record before it",
+                record.seq
+            )));
+        }
+        prev = Some(record);
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn plugin(name: &str) -> StateEvent {
+        StateEvent::PluginInstalled {
+            name: name.to_string(),
+            version: "1.0.0".into(),
+            source: format!("/src/{}", name),
+            dev: false,
+        }
+    }
+
+    #[test]
+    fn test_append_and_replay() {
+        let dir = tempdir().unwrap();
+        let log = StateLog::at(dir.path().join("state.jsonl"));
+        log.append(plugin("alpha")).unwrap();
+        log.append(plugin("beta")).unwrap();
+        log.append(StateEvent::PluginRemoved { name: "alpha".into() }).unwrap();
+        log.append(StateEvent::SkillsSynced { linked: 3, removed: 0 }).unwrap();
+
+        let lines = log.read().unwrap();
+        assert!(verify_chain(&lines).is_empty());
+        let records = log.records().unwrap();
+        assert_eq!(records.iter().map(|r| r.seq).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!(records[0].prev, GENESIS);
+        assert_eq!(records[1].prev, records[0].hash);
+
+        let state = State::replay(&records);
+        assert_eq!(state.plugins.keys().collect::<Vec<_>>(), vec!["beta"]);
+        assert_eq!(state.last_sync, Some(records[3].at));
+        assert_eq!(state.events, 4);
+
+        let line = fs::read_to_string(log.path()).unwrap();
+        assert!(line.starts_with(r#"{"seq":1,"at":"#));
+        assert!(line.contains(r#""event":"plugin-installed","name":"alpha""#));
+    }
+
+    #[test]
+    fn test_verify_chain_detects_edits_and_gaps() {
+        let dir = tempdir().unwrap();
+        let log = StateLog::at(dir.path().join("state.jsonl"));
+        for name in ["a", "b", "c"] {
+            log.append(plugin(name)).unwrap();
+        }
+
+        // Hand edit the second record's version
+        let content = fs::read_to_string(log.path()).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        let edited = lines[1].replace("1.0.0", "9.9.9");
+        fs::write(log.path(), format!("{}\n{}\n{}\n", lines[0], edited, lines[2])).unwrap();
+        let errors = verify_chain(&log.read().unwrap());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 2);
+        assert!(errors[0].message.contains("modified"));
+
+        // Drop the middle record
+        fs::write(log.path(), format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+        let errors = verify_chain(&log.read().unwrap());
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("missing"));
+    }
+}
@@ -0,0 +1,232 @@
+//! Remote storage backends for `pais sync remote`
+//!
+//! Each backend mirrors the remote into a local working directory (`fetch`)
+//! and pushes that directory back (`publish`). Backends shell out to the
+//! usual tools (`git`, `aws`, `ssh`/`rsync`) so existing credentials apply.
+
+use eyre::{Context, Result};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// A sync remote parsed from a URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Remote {
+    /// Git repository (any URL git understands)
+    Git { url: String },
+    /// S3 bucket prefix (`s3://bucket/prefix`)
+    S3 { url: String },
+    /// SSH target (`ssh://[user@]host/path`), transferred with rsync
+    Ssh { host: String, path: String },
+}
+
+impl Remote {
+    /// Parse a remote URL.
+    ///
+    /// `s3://` selects S3 and `ssh://` selects rsync over SSH; anything else
+    /// (https, git@host:repo.git, local paths) is treated as a git remote.
+    pub fn parse(url: &str) -> Result<Self> {
+        let url = url.trim();
+        if url.is_empty() {
+            eyre::bail!("Sync remote URL is empty");
+        }
+
+        if url.starts_with("s3://") {
+            return Ok(Remote::S3 { url: url.to_string() });
+        }
+
+        if let Some(rest) = url.strip_prefix("ssh://") {
+            let (host, path) = rest
+                .split_once('/')
+                .ok_or_else(|| eyre::eyre!("SSH remote must include a path: {}", url))?;
+            if host.is_empty() || path.is_empty() {
+                eyre::bail!("Invalid SSH remote: {}", url);
+            }
+            // ssh and rsync would read it as an option
+            if host.starts_with('-') {
+                eyre::bail!("SSH remote host must not start with '-': {}", url);
+            }
+            return Ok(Remote::Ssh {
+                host: host.to_string(),
+                path: format!("/{}", path),
+            });
+        }
+
+        Ok(Remote::Git { url: url.to_string() })
+    }
+
+    /// Short backend name for display
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Remote::Git { .. } => "git",
+            Remote::S3 { .. } => "s3",
+            Remote::Ssh { .. } => "ssh",
+        }
+    }
+
+    /// Mirror the remote into `dir`
+    pub fn fetch(&self, dir: &Path) -> Result<()> {
+        match self {
+            Remote::Git { url } => git_fetch(url, dir),
+            Remote::S3 { url } => {
+                fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+                run("aws", &["s3", "sync", url, &dir.to_string_lossy(), "--delete"])
+            }
+            Remote::Ssh { host, path } => {
+                fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+                // The remote shell parses the command line, so the path goes in quoted
+                run("ssh", &["--", host, &format!("mkdir -p -- {}", shell_quote(path))])?;
+                run(
+                    "rsync",
+                    &[
+                        "-az",
+                        "--delete",
+                        &format!("{}:{}/", host, path),
+                        &format!("{}/", dir.display()),
+                    ],
+                )
+            }
+        }
+    }
+
+    /// Push the contents of `dir` to the remote
+    pub fn publish(&self, dir: &Path, message: &str) -> Result<()> {
+        match self {
+            Remote::Git { .. } => git_publish(dir, message),
+            Remote::S3 { url } => run("aws", &["s3", "sync", &dir.to_string_lossy(), url, "--delete"]),
+            Remote::Ssh { host, path } => run(
+                "rsync",
+                &[
+                    "-az",
+                    "--delete",
+                    &format!("{}/", dir.display()),
+                    &format!("{}:{}/", host, path),
+                ],
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for Remote {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Remote::Git { url } | Remote::S3 { url } => write!(f, "{}", url),
+            Remote::Ssh { host, path } => write!(f, "ssh://{}{}", host, path),
+        }
+    }
+}
+
+/// Clone or update the git working copy, discarding any local edits
+fn git_fetch(url: &str, dir: &Path) -> Result<()> {
+    if !dir.join(".git").exists() {
+        if let Some(parent) = dir.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        return run("git", &["clone", "--quiet", url, &dir.to_string_lossy()]);
+    }
+
+    let dir_str = dir.to_string_lossy();
+    run("git", &["-C", &dir_str, "fetch", "--quiet", "origin"])?;
+
+    let branch = git_output(dir, &["symbolic-ref", "--short", "HEAD"])?;
+    let upstream = format!("origin/{}", branch);
+
+    // A freshly created remote has no commits yet; nothing to reset to
+    if git_output(dir, &["rev-parse", "--verify", "--quiet", &upstream]).is_ok() {
+        run("git", &["-C", &dir_str, "reset", "--quiet", "--hard", &upstream])?;
+    }
+    run("git", &["-C", &dir_str, "clean", "-fdq"])
+}
+
+/// Commit everything in the working copy and push it
+fn git_publish(dir: &Path, message: &str) -> Result<()> {
+    let dir_str = dir.to_string_lossy();
+    run("git", &["-C", &dir_str, "add", "-A"])?;
+
+    if git_output(dir, &["status", "--porcelain"])?.is_empty() {
+        log::debug!("Nothing to commit in {}", dir.display());
+        return Ok(());
+    }
+
+    run("git", &["-C", &dir_str, "commit", "--quiet", "-m", message])?;
+    run("git", &["-C", &dir_str, "push", "--quiet", "origin", "HEAD"])
+}
+
+/// Run a git command in `dir` and return its trimmed stdout
+fn git_output(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+
+    if !output.status.success() {
+        eyre::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Quote `s` as one word for a POSIX shell
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// Run an external command, failing with its stderr on non-zero exit
+fn run(program: &str, args: &[&str]) -> Result<()> {
+    log::debug!("Running: {} {}", program, args.join(" "));
+
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to run {} (is it installed?)", program))?;
+
+    if !output.status.success() {
+        eyre::bail!(
+            "{} {} failed: {}",
+            program,
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_remotes() {
+        assert_eq!(
+            Remote::parse("s3://bucket/pais").unwrap(),
+            Remote::S3 {
+                url: "s3://bucket/pais".to_string()
+            }
+        );
+        assert_eq!(
+            Remote::parse("ssh://me@box/home/me/pais").unwrap(),
+            Remote::Ssh {
+                host: "me@box".to_string(),
+                path: "/home/me/pais".to_string()
+            }
+        );
+        assert_eq!(Remote::parse("git@github.com:me/pais-state.git").unwrap().kind(), "git");
+        assert!(Remote::parse("ssh://host-only").is_err());
+        assert!(Remote::parse("ssh://-oProxyCommand=sh/tmp").is_err());
+        assert!(Remote::parse("").is_err());
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("/home/me/pais"), "'/home/me/pais'");
+        assert_eq!(shell_quote("/tmp/$(rm -rf ~)"), "'/tmp/$(rm -rf ~)'");
+        assert_eq!(shell_quote("/it's"), r"'/it'\''s'");
+    }
+}
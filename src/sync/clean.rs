@@ -0,0 +1,297 @@
+//! Problems `pais sync --clean` looks for
+//!
+//! Besides links in `~/.claude/skills` whose target is gone, a clean pass
+//! flags links whose target no longer holds a SKILL.md, skills whose
+//! frontmatter fails to parse (they are silently skipped by sync), and skill
+//! names defined more than once across the skills and plugins directories,
+//! where only one of the definitions ends up linked.
+
+use eyre::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::skill::loader::subdirectories;
+use crate::skill::parser::{has_skill_md, is_simple_skill, parse_skill_md};
+
+/// What is wrong
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProblemKind {
+    /// Link that can't be read or whose target is gone
+    Orphaned,
+    /// Link whose target exists but has no SKILL.md
+    Stale,
+    /// SKILL.md whose frontmatter fails to parse
+    Unparseable,
+    /// Skill name defined in more than one place
+    Shadowed,
+}
+
+impl std::fmt::Display for ProblemKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProblemKind::Orphaned => write!(f, "orphaned"),
+            ProblemKind::Stale => write!(f, "stale"),
+            ProblemKind::Unparseable => write!(f, "unparseable"),
+            ProblemKind::Shadowed => write!(f, "shadowed"),
+        }
+    }
+}
+
+/// One problem and how to fix it
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub kind: ProblemKind,
+    /// Link or skill name
+    pub name: String,
+    pub path: PathBuf,
+    pub detail: String,
+    pub remediation: String,
+    /// Link `pais sync --clean` removes itself
+    pub remove: Option<PathBuf>,
+}
+
+/// Where to look
+pub struct Dirs<'a> {
+    pub claude_skills: &'a Path,
+    pub skills: &'a Path,
+    pub plugins: &'a Path,
+    /// Team clones (see [`crate::team`])
+    pub teams: &'a Path,
+}
+
+impl Dirs<'_> {
+    fn is_managed(&self, target: &Path) -> bool {
+        target.starts_with(self.skills) || target.starts_with(self.plugins) || target.starts_with(self.teams)
+    }
+}
+
+/// Everything a clean pass found
+#[derive(Debug, Default)]
+pub struct Report {
+    pub problems: Vec<Problem>,
+    /// Links that are fine
+    pub valid: usize,
+}
+
+/// Check the links in `~/.claude/skills` and the skill definitions they come from
+pub fn scan(dirs: &Dirs) -> Result<Report> {
+    let mut report = Report::default();
+    if dirs.claude_skills.exists() {
+        scan_links(dirs, &mut report)?;
+    }
+    scan_definitions(dirs, &mut report)?;
+    report
+        .problems
+        .sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| a.name.cmp(&b.name)));
+    Ok(report)
+}
+
+fn scan_links(dirs: &Dirs, report: &mut Report) -> Result<()> {
+    for entry in fs::read_dir(dirs.claude_skills).context("Failed to read Claude skills directory")? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !path.symlink_metadata().is_ok_and(|m| m.file_type().is_symlink()) {
+            report.valid += 1;
+            continue;
+        }
+
+        let Ok(link) = fs::read_link(&path) else {
+            report.problems.push(Problem {
+                kind: ProblemKind::Orphaned,
+                name,
+                path: path.clone(),
+                detail: "link can't be read".into(),
+                remediation: "removed by --clean".into(),
+                remove: Some(path),
+            });
+            continue;
+        };
+        // Relative links resolve against the directory holding them
+        let target = dirs.claude_skills.join(&link);
+
+        if !target.exists() {
+            report.problems.push(Problem {
+                kind: ProblemKind::Orphaned,
+                name,
+                path: path.clone(),
+                detail: format!("target {} is gone", link.display()),
+                remediation: "removed by --clean".into(),
+                remove: Some(path),
+            });
+        } else if !has_skill_md(&target) {
+            // Only links pais created are removed; others are reported
+            let managed = dirs.is_managed(&target);
+            report.problems.push(Problem {
+                kind: ProblemKind::Stale,
+                name,
+                path: path.clone(),
+                detail: format!("{} has no SKILL.md", target.display()),
+                remediation: if managed {
+                    "removed by --clean".into()
+                } else {
+                    format!("not managed by pais; remove it with: rm {}", path.display())
+                },
+                remove: managed.then_some(path),
+            });
+        } else {
+            report.valid += 1;
+        }
+    }
+    Ok(())
+}
+
+/// A SKILL.md directory in the skills or plugins directory
+struct Definition {
+    dir: PathBuf,
+    plugin: bool,
+}
+
+/// Skill directories in the order `pais sync` links them (later ones win)
+fn definitions(dirs: &Dirs) -> Result<Vec<Definition>> {
+    let mut found = Vec::new();
+    if dirs.skills.exists() {
+        for dir in subdirectories(dirs.skills)? {
+            if is_simple_skill(&dir) {
+                found.push(Definition { dir, plugin: false });
+            }
+        }
+    }
+    if dirs.plugins.exists() {
+        for dir in subdirectories(dirs.plugins)? {
+            if has_skill_md(&dir) {
+                found.push(Definition { dir, plugin: true });
+            }
+        }
+    }
+    Ok(found)
+}
+
+fn scan_definitions(dirs: &Dirs, report: &mut Report) -> Result<()> {
+    let mut by_name: BTreeMap<String, Vec<Definition>> = BTreeMap::new();
+    for definition in definitions(dirs)? {
+        let dir_name = definition
+            .dir
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let skill_md = definition.dir.join("SKILL.md");
+        match parse_skill_md(&skill_md) {
+            Ok(metadata) => by_name.entry(metadata.name).or_default().push(definition),
+            Err(e) => report.problems.push(Problem {
+                kind: ProblemKind::Unparseable,
+                name: dir_name.clone(),
+                path: skill_md,
+                detail: e.root_cause().to_string(),
+                remediation: format!(
+                    "fix the frontmatter, then check it with: pais skill validate {}",
+                    dir_name
+                ),
+                remove: None,
+            }),
+        }
+    }
+
+    for (name, definitions) in by_name.into_iter().filter(|(_, d)| d.len() > 1) {
+        // Sync links each definition in turn, so the last one is what Claude sees
+        let linked = fs::read_link(dirs.claude_skills.join(&name)).ok();
+        let winner = definitions
+            .iter()
+            .position(|d| linked.as_deref() == Some(d.dir.as_path()))
+            .unwrap_or(definitions.len() - 1);
+        let describe = |d: &Definition| format!("{} ({})", d.dir.display(), if d.plugin { "plugin" } else { "skill" });
+        let shadowed: Vec<String> = definitions
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != winner)
+            .map(|(_, d)| describe(d))
+            .collect();
+        report.problems.push(Problem {
+            kind: ProblemKind::Shadowed,
+            name: name.clone(),
+            path: definitions[winner].dir.clone(),
+            detail: format!("shadows {}", shadowed.join(", ")),
+            remediation: format!(
+                "rename one (frontmatter `name:`) or remove the duplicate; only {} is linked",
+                describe(&definitions[winner])
+            ),
+            remove: None,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn skill(dir: &Path, name: &str, frontmatter_name: &str) -> PathBuf {
+        let skill_dir = dir.join(name);
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join("SKILL.md"),
+            format!("---\nname: {}\ndescription: Test\n---\n", frontmatter_name),
+        )
+        .unwrap();
+        skill_dir
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_scan_finds_each_problem() {
+        use std::os::unix::fs::symlink;
+
+        let temp = TempDir::new().unwrap();
+        let (claude, skills, plugins) = (
+            temp.path().join("claude"),
+            temp.path().join("skills"),
+            temp.path().join("plugins"),
+        );
+        fs::create_dir_all(&claude).unwrap();
+
+        let good = skill(&skills, "good", "good");
+        symlink(&good, claude.join("good")).unwrap();
+        symlink(skills.join("removed"), claude.join("removed")).unwrap();
+        let emptied = skills.join("emptied");
+        fs::create_dir_all(&emptied).unwrap();
+        symlink(&emptied, claude.join("emptied")).unwrap();
+        let foreign = temp.path().join("foreign");
+        fs::create_dir_all(&foreign).unwrap();
+        symlink(&foreign, claude.join("foreign")).unwrap();
+
+        let broken = skills.join("broken");
+        fs::create_dir_all(&broken).unwrap();
+        fs::write(broken.join("SKILL.md"), "---\nname: [unclosed\n---\n").unwrap();
+
+        skill(&skills, "deploy", "deploy");
+        let plugin_deploy = skill(&plugins, "deploy-plugin", "deploy");
+
+        let report = scan(&Dirs {
+            claude_skills: &claude,
+            skills: &skills,
+            plugins: &plugins,
+            teams: &temp.path().join("teams"),
+        })
+        .unwrap();
+
+        assert_eq!(report.valid, 1);
+        let kinds: Vec<(ProblemKind, &str)> = report.problems.iter().map(|p| (p.kind, p.name.as_str())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (ProblemKind::Orphaned, "removed"),
+                (ProblemKind::Stale, "emptied"),
+                (ProblemKind::Stale, "foreign"),
+                (ProblemKind::Unparseable, "broken"),
+                (ProblemKind::Shadowed, "deploy"),
+            ]
+        );
+        let stale: Vec<bool> = report.problems[1..3].iter().map(|p| p.remove.is_some()).collect();
+        assert_eq!(stale, vec![true, false]);
+        // The plugin is synced last, so it wins
+        assert_eq!(report.problems[4].path, plugin_deploy);
+    }
+}
@@ -0,0 +1,165 @@
+//! Line-based three-way merge
+//!
+//! A small diff3 implementation used to reconcile text files (YAML, markdown)
+//! that changed on both this machine and the remote since the last sync.
+
+/// Refuse to diff inputs whose LCS table would exceed this many cells
+const MAX_DIFF_CELLS: usize = 16_000_000;
+
+/// Outcome of a three-way merge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeResult {
+    /// Merged text (contains conflict markers if `conflicts > 0`)
+    pub text: String,
+    /// Number of conflicting hunks
+    pub conflicts: usize,
+}
+
+impl MergeResult {
+    pub fn is_clean(&self) -> bool {
+        self.conflicts == 0
+    }
+}
+
+/// Merge `local` and `remote`, both derived from `base`.
+///
+/// Returns `None` if the inputs are too large to diff.
+pub fn merge3(base: &str, local: &str, remote: &str) -> Option<MergeResult> {
+    let base_lines: Vec<&str> = base.split_inclusive('\n').collect();
+    let local_lines: Vec<&str> = local.split_inclusive('\n').collect();
+    let remote_lines: Vec<&str> = remote.split_inclusive('\n').collect();
+
+    let to_local = match_lines(&base_lines, &local_lines)?;
+    let to_remote = match_lines(&base_lines, &remote_lines)?;
+
+    let mut text = String::new();
+    let mut conflicts = 0;
+    let (mut i, mut a, mut b) = (0, 0, 0);
+
+    loop {
+        // Next base line that is unchanged on both sides
+        let sync = (i..base_lines.len()).find_map(|j| match (to_local[j], to_remote[j]) {
+            (Some(la), Some(rb)) => Some((j, la, rb)),
+            _ => None,
+        });
+        let (j, la, rb) = sync.unwrap_or((base_lines.len(), local_lines.len(), remote_lines.len()));
+
+        let base_chunk = &base_lines[i..j];
+        let local_chunk = &local_lines[a..la];
+        let remote_chunk = &remote_lines[b..rb];
+
+        if local_chunk == base_chunk || local_chunk == remote_chunk {
+            text.extend(remote_chunk.iter().copied());
+        } else if remote_chunk == base_chunk {
+            text.extend(local_chunk.iter().copied());
+        } else {
+            conflicts += 1;
+            text.push_str("<<<<<<< local\n");
+            push_chunk(&mut text, local_chunk);
+            text.push_str("=======\n");
+            push_chunk(&mut text, remote_chunk);
+            text.push_str(">>>>>>> remote\n");
+        }
+
+        if sync.is_none() {
+            break;
+        }
+        text.push_str(base_lines[j]);
+        i = j + 1;
+        a = la + 1;
+        b = rb + 1;
+    }
+
+    Some(MergeResult { text, conflicts })
+}
+
+/// Append a chunk inside conflict markers, making sure it ends with a newline
+fn push_chunk(text: &mut String, chunk: &[&str]) {
+    text.extend(chunk.iter().copied());
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+}
+
+/// For each line of `base`, the index of the matching line in `other` (via LCS)
+fn match_lines(base: &[&str], other: &[&str]) -> Option<Vec<Option<usize>>> {
+    let (n, m) = (base.len(), other.len());
+    if n.saturating_mul(m) > MAX_DIFF_CELLS {
+        return None;
+    }
+
+    // lcs[i][j] = LCS length of base[i..] and other[j..]
+    let width = m + 1;
+    let mut lcs = vec![0u32; (n + 1) * width];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i * width + j] = if base[i] == other[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut matches = vec![None; n];
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if base[i] == other[j] {
+            matches[i] = Some(j);
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    Some(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_non_overlapping_edits_merge_cleanly() {
+        let base = "a\nb\nc\nd\n";
+        let local = "A\nb\nc\nd\n";
+        let remote = "a\nb\nc\nD\n";
+        let result = merge3(base, local, remote).unwrap();
+        assert!(result.is_clean());
+        assert_eq!(result.text, "A\nb\nc\nD\n");
+    }
+
+    #[test]
+    fn test_identical_edits_merge_cleanly() {
+        let result = merge3("a\n", "b\n", "b\n").unwrap();
+        assert!(result.is_clean());
+        assert_eq!(result.text, "b\n");
+    }
+
+    #[test]
+    fn test_insertions_on_both_sides() {
+        let base = "one\ntwo\n";
+        let local = "zero\none\ntwo\n";
+        let remote = "one\ntwo\nthree\n";
+        let result = merge3(base, local, remote).unwrap();
+        assert!(result.is_clean());
+        assert_eq!(result.text, "zero\none\ntwo\nthree\n");
+    }
+
+    #[test]
+    fn test_conflicting_edits_produce_markers() {
+        let result = merge3("key: 1\n", "key: 2\n", "key: 3\n").unwrap();
+        assert_eq!(result.conflicts, 1);
+        assert_eq!(result.text, "<<<<<<< local\nkey: 2\n=======\nkey: 3\n>>>>>>> remote\n");
+    }
+
+    #[test]
+    fn test_missing_trailing_newline() {
+        let result = merge3("a\nb", "a\nb\nc", "a\nb").unwrap();
+        assert!(result.is_clean());
+        assert_eq!(result.text, "a\nb\nc");
+    }
+}
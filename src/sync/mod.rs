@@ -0,0 +1,441 @@
+//! Multi-machine state sync
+//!
+//! Synchronizes skills, agents, the config file and (optionally) history with
+//! a remote so the same setup follows you across machines.
+//!
+//! Three trees are compared per file:
+//! - **local**: the files on this machine
+//! - **remote**: a mirror of the remote, refreshed before each sync
+//! - **base**: a snapshot of what both sides agreed on after the last sync
+//!
+//! A file changed on only one side is copied to the other. A file changed on
+//! both sides is three-way merged when it is YAML or markdown; otherwise (or
+//! when the merge conflicts) it is reported and left untouched until resolved.
+
+pub mod backend;
+pub mod clean;
+pub mod merge;
+pub mod targets;
+pub mod watch;
+
+use eyre::{Context, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::config::Config;
+
+/// File contents keyed by their path relative to the sync root (e.g. `skills/rust/SKILL.md`)
+pub type Snapshot = BTreeMap<String, Vec<u8>>;
+
+/// Extensions eligible for three-way merge
+const MERGEABLE_EXTENSIONS: &[&str] = &["yaml", "yml", "md", "markdown"];
+
+/// A local location mirrored under a top-level name on the remote
+#[derive(Debug, Clone)]
+pub struct SyncRoot {
+    /// Name on the remote (`skills`, `agents`, `pais.yaml`, `history`)
+    pub name: &'static str,
+    /// Local file or directory
+    pub local: PathBuf,
+}
+
+impl SyncRoot {
+    /// Roots to sync for the given config
+    pub fn from_config(config: &Config, include_history: bool) -> Vec<SyncRoot> {
+        let skills = Config::expand_path(&config.paths.skills);
+        let agents = skills.parent().unwrap_or(&skills).join("agents");
+
+        let mut roots = vec![
+            SyncRoot {
+                name: "skills",
+                local: skills,
+            },
+            SyncRoot {
+                name: "agents",
+                local: agents,
+            },
+            SyncRoot {
+                name: "pais.yaml",
+                local: Config::pais_dir().join("pais.yaml"),
+            },
+        ];
+
+        if include_history {
+            roots.push(SyncRoot {
+                name: "history",
+                local: Config::expand_path(&config.paths.history),
+            });
+        }
+
+        roots
+    }
+}
+
+/// Directory holding the remote mirror and merge base
+pub fn state_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("pais")
+        .join("sync")
+}
+
+/// Map a relative sync path back to its location on this machine
+pub fn local_path(roots: &[SyncRoot], rel: &str) -> Option<PathBuf> {
+    roots.iter().find_map(|root| {
+        if rel == root.name {
+            Some(root.local.clone())
+        } else {
+            rel.strip_prefix(root.name)
+                .and_then(|rest| rest.strip_prefix('/'))
+                .map(|rest| root.local.join(rest))
+        }
+    })
+}
+
+/// Read every file under the local roots
+pub fn snapshot_local(roots: &[SyncRoot]) -> Result<Snapshot> {
+    let mut snapshot = Snapshot::new();
+    for root in roots {
+        collect(&root.local, root.name, &mut snapshot)?;
+    }
+    Ok(snapshot)
+}
+
+/// Read every file under `dir` that belongs to one of the roots.
+///
+/// Anything else in the remote (a README, other tooling) is left alone.
+pub fn snapshot_dir(dir: &Path, roots: &[SyncRoot]) -> Result<Snapshot> {
+    let mut snapshot = Snapshot::new();
+    for root in roots {
+        collect(&dir.join(root.name), root.name, &mut snapshot)?;
+    }
+    Ok(snapshot)
+}
+
+fn collect(path: &Path, name: &str, snapshot: &mut Snapshot) -> Result<()> {
+    if path.is_file() {
+        let content = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        snapshot.insert(name.to_string(), content);
+        return Ok(());
+    }
+    if !path.is_dir() {
+        return Ok(());
+    }
+
+    let walker = WalkDir::new(path).into_iter().filter_entry(|e| e.file_name() != ".git");
+    for entry in walker {
+        let entry = entry.with_context(|| format!("Failed to walk {}", path.display()))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let rel = entry.path().strip_prefix(path).unwrap_or(entry.path());
+        let key = format!("{}/{}", name, rel.to_string_lossy().replace('\\', "/"));
+        let content = fs::read(entry.path()).with_context(|| format!("Failed to read {}", entry.path().display()))?;
+        snapshot.insert(key, content);
+    }
+
+    Ok(())
+}
+
+/// Which side wins when both changed and cannot be merged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Prefer {
+    Local,
+    Remote,
+}
+
+/// What happens to a single file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeAction {
+    /// Same on both sides
+    Unchanged,
+    /// Copy remote version to this machine
+    Pull,
+    /// Copy local version to the remote
+    Push,
+    /// Deleted remotely; delete here
+    DeleteLocal,
+    /// Deleted here; delete remotely
+    DeleteRemote,
+    /// Changed on both sides and merged cleanly
+    Merge,
+    /// Changed on both sides and needs manual resolution
+    Conflict { reason: String },
+}
+
+/// A planned change for one path
+#[derive(Debug, Clone)]
+pub struct SyncChange {
+    pub path: String,
+    pub action: ChangeAction,
+    /// Content both sides will have afterwards (`None` = deleted).
+    /// For conflicts, a marked-up copy for the user to inspect, if available.
+    pub content: Option<Vec<u8>>,
+}
+
+/// Decide what to do with every path present in any of the three snapshots
+pub fn plan(local: &Snapshot, remote: &Snapshot, base: &Snapshot, prefer: Option<Prefer>) -> Vec<SyncChange> {
+    let paths: BTreeSet<&String> = local.keys().chain(remote.keys()).chain(base.keys()).collect();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let (l, r, b) = (local.get(path), remote.get(path), base.get(path));
+            let (action, content) = decide(path, l, r, b, prefer);
+            SyncChange {
+                path: path.clone(),
+                action,
+                content,
+            }
+        })
+        .collect()
+}
+
+fn decide(
+    path: &str,
+    l: Option<&Vec<u8>>,
+    r: Option<&Vec<u8>>,
+    b: Option<&Vec<u8>>,
+    prefer: Option<Prefer>,
+) -> (ChangeAction, Option<Vec<u8>>) {
+    let take_local = || match l {
+        Some(c) => (ChangeAction::Push, Some(c.clone())),
+        None => (ChangeAction::DeleteRemote, None),
+    };
+    let take_remote = || match r {
+        Some(c) => (ChangeAction::Pull, Some(c.clone())),
+        None => (ChangeAction::DeleteLocal, None),
+    };
+
+    if l == r {
+        return (ChangeAction::Unchanged, l.cloned());
+    }
+    if l == b {
+        return take_remote();
+    }
+    if r == b {
+        return take_local();
+    }
+
+    // Changed on both sides
+    let reason = match (l, r) {
+        (Some(l), Some(r)) if is_mergeable(path) => match merge_text(path, b, l, r) {
+            Ok(merged) => return (ChangeAction::Merge, Some(merged)),
+            Err((reason, marked)) => {
+                if prefer.is_none() {
+                    return (ChangeAction::Conflict { reason }, marked);
+                }
+                reason
+            }
+        },
+        (Some(_), Some(_)) => "modified on both sides".to_string(),
+        _ => "modified on one side, deleted on the other".to_string(),
+    };
+
+    match prefer {
+        Some(Prefer::Local) => take_local(),
+        Some(Prefer::Remote) => take_remote(),
+        None => (ChangeAction::Conflict { reason }, None),
+    }
+}
+
+fn is_mergeable(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| MERGEABLE_EXTENSIONS.contains(&ext))
+}
+
+/// Three-way merge text content; on failure returns the reason and a marked-up copy
+fn merge_text(path: &str, b: Option<&Vec<u8>>, l: &[u8], r: &[u8]) -> Result<Vec<u8>, (String, Option<Vec<u8>>)> {
+    let base = b.map(|b| String::from_utf8_lossy(b).into_owned()).unwrap_or_default();
+    let (Ok(local), Ok(remote)) = (std::str::from_utf8(l), std::str::from_utf8(r)) else {
+        return Err(("not valid UTF-8".to_string(), None));
+    };
+
+    let Some(result) = merge::merge3(&base, local, remote) else {
+        return Err(("too large to merge".to_string(), None));
+    };
+
+    if !result.is_clean() {
+        return Err((
+            format!("{} conflicting hunk(s)", result.conflicts),
+            Some(result.text.into_bytes()),
+        ));
+    }
+
+    let is_yaml = path.ends_with(".yaml") || path.ends_with(".yml");
+    if is_yaml && let Err(e) = serde_yaml::from_str::<serde_yaml::Value>(&result.text) {
+        return Err((format!("merged YAML is invalid: {}", e), Some(result.text.into_bytes())));
+    }
+
+    Ok(result.text.into_bytes())
+}
+
+/// Write `content` to `path` (creating parents), or remove it when `None`
+pub fn write_or_remove(path: &Path, content: Option<&[u8]>) -> Result<()> {
+    match content {
+        Some(content) => {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
+            fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+        }
+        None => {
+            if path.exists() {
+                fs::remove_file(path).with_context(|| format!("Failed to remove {}", path.display()))?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Apply a plan to the local roots and the remote mirror
+pub fn apply(changes: &[SyncChange], roots: &[SyncRoot], mirror: &Path) -> Result<()> {
+    for change in changes {
+        let content = change.content.as_deref();
+        let local = local_path(roots, &change.path);
+
+        match change.action {
+            ChangeAction::Unchanged | ChangeAction::Conflict { .. } => {}
+            ChangeAction::Pull | ChangeAction::DeleteLocal => {
+                if let Some(local) = local {
+                    write_or_remove(&local, content)?;
+                }
+            }
+            ChangeAction::Push | ChangeAction::DeleteRemote => write_or_remove(&mirror.join(&change.path), content)?,
+            ChangeAction::Merge => {
+                if let Some(local) = local {
+                    write_or_remove(&local, content)?;
+                }
+                write_or_remove(&mirror.join(&change.path), content)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Compute the new merge base: agreed content for resolved paths, the old base for conflicts
+pub fn next_base(changes: &[SyncChange], old_base: &Snapshot) -> Snapshot {
+    changes
+        .iter()
+        .filter_map(|change| match change.action {
+            ChangeAction::Conflict { .. } => old_base.get(&change.path).map(|c| (change.path.clone(), c.clone())),
+            _ => change.content.clone().map(|c| (change.path.clone(), c)),
+        })
+        .collect()
+}
+
+/// Replace the contents of `dir` with `snapshot`
+pub fn write_snapshot(dir: &Path, snapshot: &Snapshot) -> Result<()> {
+    if dir.exists() {
+        fs::remove_dir_all(dir).with_context(|| format!("Failed to clear {}", dir.display()))?;
+    }
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    for (path, content) in snapshot {
+        write_or_remove(&dir.join(path), Some(content))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn snap(entries: &[(&str, &str)]) -> Snapshot {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.as_bytes().to_vec()))
+            .collect()
+    }
+
+    fn action_for<'a>(changes: &'a [SyncChange], path: &str) -> &'a ChangeAction {
+        &changes.iter().find(|c| c.path == path).unwrap().action
+    }
+
+    #[test]
+    fn test_one_sided_changes() {
+        let base = snap(&[("a.md", "1"), ("b.md", "1"), ("c.md", "1"), ("d.md", "1")]);
+        let local = snap(&[("a.md", "2"), ("b.md", "1"), ("d.md", "1"), ("new.md", "x")]);
+        let remote = snap(&[("a.md", "1"), ("b.md", "2"), ("c.md", "1")]);
+
+        let changes = plan(&local, &remote, &base, None);
+        assert_eq!(action_for(&changes, "a.md"), &ChangeAction::Push);
+        assert_eq!(action_for(&changes, "b.md"), &ChangeAction::Pull);
+        assert_eq!(action_for(&changes, "c.md"), &ChangeAction::DeleteRemote);
+        assert_eq!(action_for(&changes, "d.md"), &ChangeAction::DeleteLocal);
+        assert_eq!(action_for(&changes, "new.md"), &ChangeAction::Push);
+    }
+
+    #[test]
+    fn test_both_changed_markdown_merges() {
+        let base = snap(&[("skills/x/SKILL.md", "a\nb\nc\n")]);
+        let local = snap(&[("skills/x/SKILL.md", "A\nb\nc\n")]);
+        let remote = snap(&[("skills/x/SKILL.md", "a\nb\nC\n")]);
+
+        let changes = plan(&local, &remote, &base, None);
+        assert_eq!(changes[0].action, ChangeAction::Merge);
+        assert_eq!(changes[0].content.as_deref(), Some("A\nb\nC\n".as_bytes()));
+    }
+
+    #[test]
+    fn test_conflict_and_prefer() {
+        let base = snap(&[("pais.yaml", "log-level: info\n"), ("bin.dat", "0")]);
+        let local = snap(&[("pais.yaml", "log-level: debug\n"), ("bin.dat", "1")]);
+        let remote = snap(&[("pais.yaml", "log-level: warn\n"), ("bin.dat", "2")]);
+
+        let changes = plan(&local, &remote, &base, None);
+        assert!(matches!(
+            action_for(&changes, "pais.yaml"),
+            ChangeAction::Conflict { .. }
+        ));
+        assert!(matches!(action_for(&changes, "bin.dat"), ChangeAction::Conflict { .. }));
+
+        // Conflicts keep the previous base
+        let next = next_base(&changes, &base);
+        assert_eq!(next.get("pais.yaml"), base.get("pais.yaml"));
+
+        let changes = plan(&local, &remote, &base, Some(Prefer::Remote));
+        assert_eq!(action_for(&changes, "pais.yaml"), &ChangeAction::Pull);
+        assert_eq!(action_for(&changes, "bin.dat"), &ChangeAction::Pull);
+    }
+
+    #[test]
+    fn test_apply_and_snapshot_roundtrip() {
+        let temp = TempDir::new().unwrap();
+        let roots = vec![
+            SyncRoot {
+                name: "skills",
+                local: temp.path().join("local/skills"),
+            },
+            SyncRoot {
+                name: "pais.yaml",
+                local: temp.path().join("local/pais.yaml"),
+            },
+        ];
+        let mirror = temp.path().join("mirror");
+
+        fs::create_dir_all(temp.path().join("local")).unwrap();
+        fs::write(temp.path().join("local/pais.yaml"), "a: 1\n").unwrap();
+
+        let local = snapshot_local(&roots).unwrap();
+        assert_eq!(local.len(), 1);
+        let remote = snap(&[("skills/x/SKILL.md", "# x\n")]);
+        let changes = plan(&local, &remote, &Snapshot::new(), None);
+        apply(&changes, &roots, &mirror).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(temp.path().join("local/skills/x/SKILL.md")).unwrap(),
+            "# x\n"
+        );
+        assert_eq!(fs::read_to_string(mirror.join("pais.yaml")).unwrap(), "a: 1\n");
+
+        let local_after = snapshot_local(&roots).unwrap();
+        assert_eq!(local_after.len(), 2);
+        assert!(local_after.contains_key("skills/x/SKILL.md"));
+        assert_eq!(snapshot_dir(&mirror, &roots).unwrap().len(), 1);
+    }
+}
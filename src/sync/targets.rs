@@ -0,0 +1,290 @@
+//! Export skills and MCP servers to other editors
+//!
+//! `pais sync --target <editor>` renders the same skills Claude Code gets as
+//! the editor's rules file, and the MCP servers as its MCP config:
+//!
+//! | Target   | Rules                             | MCP config                             |
+//! |----------|-----------------------------------|----------------------------------------|
+//! | cursor   | `.cursorrules`                    | `.cursor/mcp.json`                     |
+//! | windsurf | `.windsurfrules`                  | `~/.codeium/windsurf/mcp_config.json`  |
+//! | vscode   | `.github/copilot-instructions.md` | `.vscode/mcp.json`                     |
+//!
+//! Rules are written between marker comments so hand-written rules in the
+//! same file survive a re-sync. MCP entries are merged by server name, leaving
+//! servers PAIS doesn't know about untouched.
+//!
+//! Cursor's and VS Code's MCP configs live in the project and usually get
+//! committed, so a server's `env` values (tokens, mostly) are written as
+//! `${env:NAME}` references the editor resolves from its own environment.
+//! Windsurf's global config keeps the values.
+
+use eyre::{Context, Result};
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::McpServerConfig;
+
+/// Start of the PAIS-managed block in a rules file
+pub const RULES_BEGIN: &str = "<!-- pais:begin (generated by `pais sync`, edits inside will be overwritten) -->";
+
+/// End of the PAIS-managed block in a rules file
+pub const RULES_END: &str = "<!-- pais:end -->";
+
+/// Where `pais sync` writes skills
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum Target {
+    /// Symlink skills into ~/.claude/skills/
+    #[default]
+    Claude,
+    Cursor,
+    Windsurf,
+    Vscode,
+}
+
+impl std::fmt::Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Target::Claude => write!(f, "claude"),
+            Target::Cursor => write!(f, "cursor"),
+            Target::Windsurf => write!(f, "windsurf"),
+            Target::Vscode => write!(f, "vscode"),
+        }
+    }
+}
+
+impl Target {
+    /// Rules file for this editor, relative to the project directory
+    pub fn rules_path(&self, project: &Path) -> Option<PathBuf> {
+        match self {
+            Target::Claude => None,
+            Target::Cursor => Some(project.join(".cursorrules")),
+            Target::Windsurf => Some(project.join(".windsurfrules")),
+            Target::Vscode => Some(project.join(".github").join("copilot-instructions.md")),
+        }
+    }
+
+    /// MCP config file for this editor (Windsurf only reads a global one)
+    pub fn mcp_path(&self, project: &Path) -> Option<PathBuf> {
+        match self {
+            Target::Claude => None,
+            Target::Cursor => Some(project.join(".cursor").join("mcp.json")),
+            Target::Windsurf => dirs::home_dir().map(|h| h.join(".codeium").join("windsurf").join("mcp_config.json")),
+            Target::Vscode => Some(project.join(".vscode").join("mcp.json")),
+        }
+    }
+
+    /// Top-level key holding the server map
+    fn mcp_key(&self) -> &'static str {
+        match self {
+            Target::Vscode => "servers",
+            _ => "mcpServers",
+        }
+    }
+
+    /// A single server entry in this editor's format
+    fn mcp_entry(&self, server: &McpServerConfig) -> Value {
+        let mut entry = Map::new();
+        if *self == Target::Vscode {
+            entry.insert("type".to_string(), json!("stdio"));
+        }
+        entry.insert("command".to_string(), json!(server.command));
+        if !server.args.is_empty() {
+            entry.insert("args".to_string(), json!(server.args));
+        }
+        if !server.env.is_empty() {
+            let env: BTreeMap<&String, String> = server
+                .env
+                .iter()
+                .map(|(name, value)| match self {
+                    Target::Windsurf => (name, value.clone()),
+                    _ => (name, format!("${{env:{}}}", name)),
+                })
+                .collect();
+            entry.insert("env".to_string(), json!(env));
+        }
+        Value::Object(entry)
+    }
+
+    /// Whether MCP env values are written as `${env:NAME}` references
+    pub fn references_env(&self) -> bool {
+        matches!(self, Target::Cursor | Target::Vscode)
+    }
+}
+
+/// A skill as rendered into a rules file
+#[derive(Debug, Clone)]
+pub struct RuleSkill {
+    pub name: String,
+    pub description: String,
+    /// SKILL.md content without frontmatter
+    pub body: String,
+}
+
+impl RuleSkill {
+    /// Read a skill directory's SKILL.md
+    pub fn load(name: &str, description: &str, dir: &Path) -> Result<Self> {
+        let path = dir.join("SKILL.md");
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        Ok(Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            body: strip_frontmatter(&content).trim().to_string(),
+        })
+    }
+}
+
+/// Drop a leading `---` YAML block from markdown
+fn strip_frontmatter(content: &str) -> &str {
+    let trimmed = content.trim_start();
+    let Some(rest) = trimmed.strip_prefix("---") else {
+        return content;
+    };
+    match rest.find("\n---") {
+        Some(end) => {
+            let after = &rest[end + 4..];
+            after.split_once('\n').map(|(_, body)| body).unwrap_or("")
+        }
+        None => content,
+    }
+}
+
+/// Render skills as the managed block of a rules file
+pub fn render_rules(skills: &[RuleSkill]) -> String {
+    let mut out = String::new();
+    out.push_str(RULES_BEGIN);
+    out.push_str("\n\n");
+    for skill in skills {
+        out.push_str(&format!("## {}\n\n", skill.name));
+        if !skill.description.is_empty() {
+            out.push_str(&format!("> {}\n\n", skill.description));
+        }
+        if !skill.body.is_empty() {
+            out.push_str(&skill.body);
+            out.push_str("\n\n");
+        }
+    }
+    out.push_str(RULES_END);
+    out.push('\n');
+    out
+}
+
+/// Replace the managed block in an existing rules file, or append it
+pub fn merge_rules(existing: &str, block: &str) -> String {
+    if let Some(start) = existing.find(RULES_BEGIN)
+        && let Some(end) = existing[start..].find(RULES_END)
+    {
+        let end = start + end + RULES_END.len();
+        let tail = existing[end..].strip_prefix('\n').unwrap_or(&existing[end..]);
+        return format!("{}{}{}", &existing[..start], block, tail);
+    }
+
+    if existing.trim().is_empty() {
+        block.to_string()
+    } else {
+        format!("{}\n\n{}", existing.trim_end(), block)
+    }
+}
+
+/// Merge servers into an existing MCP config, keeping unrelated keys and servers
+pub fn merge_mcp(target: Target, existing: Value, servers: &BTreeMap<String, McpServerConfig>) -> Result<Value> {
+    let mut root = match existing {
+        Value::Object(map) => map,
+        Value::Null => Map::new(),
+        _ => eyre::bail!("MCP config is not a JSON object"),
+    };
+
+    let entry = root
+        .entry(target.mcp_key())
+        .or_insert_with(|| Value::Object(Map::new()));
+    let Value::Object(map) = entry else {
+        eyre::bail!("\"{}\" in MCP config is not an object", target.mcp_key());
+    };
+    for (name, server) in servers {
+        map.insert(name.clone(), target.mcp_entry(server));
+    }
+
+    Ok(Value::Object(root))
+}
+
+/// Read a JSON file, treating a missing file as null
+pub fn read_json(path: &Path) -> Result<Value> {
+    if !path.exists() {
+        return Ok(Value::Null);
+    }
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    if content.trim().is_empty() {
+        return Ok(Value::Null);
+    }
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn skill(name: &str) -> RuleSkill {
+        RuleSkill {
+            name: name.to_string(),
+            description: format!("{} things", name),
+            body: format!("Use {} well.", name),
+        }
+    }
+
+    #[test]
+    fn test_strip_frontmatter() {
+        let content = "---\nname: rust\ndescription: Rust\n---\n# Rust\n\nBody\n";
+        assert_eq!(strip_frontmatter(content), "# Rust\n\nBody\n");
+        assert_eq!(strip_frontmatter("# No frontmatter"), "# No frontmatter");
+    }
+
+    #[test]
+    fn test_merge_rules_preserves_hand_written_text() {
+        let first = merge_rules("Always use tabs.\n", &render_rules(&[skill("rust")]));
+        assert!(first.starts_with("Always use tabs.\n\n"));
+        assert!(first.contains("## rust"));
+
+        // Re-sync replaces only the managed block
+        let second = merge_rules(&format!("{}\nFooter\n", first), &render_rules(&[skill("docker")]));
+        assert!(second.starts_with("Always use tabs."));
+        assert!(second.contains("## docker"));
+        assert!(!second.contains("## rust"));
+        assert!(second.ends_with("Footer\n"));
+        assert_eq!(second.matches(RULES_BEGIN).count(), 1);
+    }
+
+    #[test]
+    fn test_merge_mcp_formats() {
+        let mut servers = BTreeMap::new();
+        servers.insert(
+            "github".to_string(),
+            McpServerConfig {
+                command: "npx".to_string(),
+                args: vec!["-y".to_string(), "server-github".to_string()],
+                env: HashMap::from([("TOKEN".to_string(), "ghp_secret".to_string())]),
+            },
+        );
+
+        let existing = json!({"mcpServers": {"mine": {"command": "mine"}}});
+        let cursor = merge_mcp(Target::Cursor, existing, &servers).unwrap();
+        assert_eq!(cursor["mcpServers"]["mine"]["command"], "mine");
+        assert_eq!(cursor["mcpServers"]["github"]["args"][1], "server-github");
+        assert_eq!(cursor["mcpServers"]["github"]["env"]["TOKEN"], "${env:TOKEN}");
+
+        let vscode = merge_mcp(Target::Vscode, Value::Null, &servers).unwrap();
+        assert_eq!(vscode["servers"]["github"]["type"], "stdio");
+        assert_eq!(vscode["servers"]["github"]["env"]["TOKEN"], "${env:TOKEN}");
+        assert!(vscode.get("mcpServers").is_none());
+
+        // Project files never see a literal env value
+        for config in [&cursor, &vscode] {
+            assert!(!serde_json::to_string(config).unwrap().contains("ghp_secret"));
+        }
+        let windsurf = merge_mcp(Target::Windsurf, Value::Null, &servers).unwrap();
+        assert_eq!(windsurf["mcpServers"]["github"]["env"]["TOKEN"], "ghp_secret");
+
+        assert!(merge_mcp(Target::Cursor, json!([]), &servers).is_err());
+    }
+}
@@ -0,0 +1,188 @@
+//! Watch skill directories for changes
+//!
+//! `pais sync --watch` keeps `~/.claude/skills` and the skill index current
+//! while skills are being edited. File events are debounced into batches and
+//! reduced to the set of skill directories they touch, so only those skills
+//! are relinked.
+
+use eyre::{Context, Result};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, channel};
+use std::time::Duration;
+
+/// Quiet period that ends a batch (editors often write a file several times)
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Recursive watcher over several root directories
+pub struct SkillWatcher {
+    roots: Vec<PathBuf>,
+    events: Receiver<notify::Result<notify::Event>>,
+    // Dropping the watcher stops event delivery
+    _watcher: RecommendedWatcher,
+}
+
+impl SkillWatcher {
+    /// Watch every root that exists; missing roots are skipped
+    pub fn new(roots: &[PathBuf]) -> Result<Self> {
+        let (tx, events) = channel();
+        let mut watcher = notify::recommended_watcher(tx).context("Failed to create file watcher")?;
+
+        let mut watched = Vec::new();
+        for root in roots {
+            if !root.is_dir() {
+                log::debug!("Not watching missing directory: {}", root.display());
+                continue;
+            }
+            watcher
+                .watch(root, RecursiveMode::Recursive)
+                .with_context(|| format!("Failed to watch {}", root.display()))?;
+            watched.push(root.clone());
+        }
+
+        if watched.is_empty() {
+            eyre::bail!("None of the skill directories exist, nothing to watch");
+        }
+
+        Ok(Self {
+            roots: watched,
+            events,
+            _watcher: watcher,
+        })
+    }
+
+    /// Directories being watched
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// Block until a batch of changes arrives; returns the skill directories it touched
+    pub fn next_batch(&self) -> Result<BTreeSet<PathBuf>> {
+        loop {
+            let first = self.events.recv().context("File watcher stopped")?;
+            let mut paths = event_paths(first);
+
+            loop {
+                match self.events.recv_timeout(DEBOUNCE) {
+                    Ok(event) => paths.extend(event_paths(event)),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => eyre::bail!("File watcher stopped"),
+                }
+            }
+
+            let dirs = affected_skill_dirs(&self.roots, &paths);
+            if !dirs.is_empty() {
+                return Ok(dirs);
+            }
+        }
+    }
+}
+
+/// Paths an event touched; access events and watcher errors (logged) touch none
+pub fn event_paths(event: notify::Result<notify::Event>) -> Vec<PathBuf> {
+    match event {
+        Ok(event) if !event.kind.is_access() => event.paths,
+        Ok(_) => Vec::new(),
+        Err(e) => {
+            log::warn!("File watcher error: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Map changed paths to the top-level skill directories under `roots` they belong to.
+///
+/// Hidden paths (editor swap files, `.git`) are ignored, as are existing
+/// files directly in a root (the generated `skill-index.yaml` and
+/// `context-snippet.md`). A removed top-level entry is still reported so
+/// its link can be cleaned up.
+pub fn affected_skill_dirs(roots: &[PathBuf], paths: &[PathBuf]) -> BTreeSet<PathBuf> {
+    let mut dirs = BTreeSet::new();
+
+    for path in paths {
+        let Some(root) = roots.iter().find(|root| path.starts_with(root)) else {
+            continue;
+        };
+        let Ok(rel) = path.strip_prefix(root) else {
+            continue;
+        };
+
+        let hidden = rel
+            .components()
+            .any(|c| matches!(c, Component::Normal(name) if name.to_string_lossy().starts_with('.')));
+        let Some(Component::Normal(first)) = rel.components().next() else {
+            continue;
+        };
+        if hidden {
+            continue;
+        }
+
+        let dir = root.join(first);
+        let root_level_file = rel.components().count() == 1 && dir.is_file();
+        if !root_level_file {
+            dirs.insert(dir);
+        }
+    }
+
+    dirs
+}
+
+/// Whether `dir` lives directly under `root`
+pub fn is_under(dir: &Path, root: &Path) -> bool {
+    dir.parent() == Some(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_affected_skill_dirs() {
+        let temp = tempdir().unwrap();
+        let skills = temp.path().join("skills");
+        let plugins = temp.path().join("plugins");
+        fs::create_dir_all(skills.join("rust").join("workflows")).unwrap();
+        fs::create_dir_all(&plugins).unwrap();
+        fs::write(skills.join("skill-index.yaml"), "").unwrap();
+
+        let roots = vec![skills.clone(), plugins.clone()];
+        let paths = vec![
+            skills.join("rust").join("SKILL.md"),
+            skills.join("rust").join("workflows").join("build.md"),
+            skills.join("rust").join(".SKILL.md.swp"),
+            skills.join("skill-index.yaml"),
+            skills.join("deleted-skill"),
+            plugins.join("deploy").join("SKILL.md"),
+            temp.path().join("elsewhere.md"),
+        ];
+
+        let dirs: Vec<PathBuf> = affected_skill_dirs(&roots, &paths).into_iter().collect();
+        assert_eq!(
+            dirs,
+            vec![
+                plugins.join("deploy"),
+                skills.join("deleted-skill"),
+                skills.join("rust")
+            ]
+        );
+        assert!(is_under(&skills.join("rust"), &skills));
+        assert!(!is_under(&skills.join("rust"), &plugins));
+    }
+
+    #[test]
+    fn test_watcher_reports_new_skill() {
+        let temp = tempdir().unwrap();
+        let skills = temp.path().join("skills");
+        fs::create_dir_all(&skills).unwrap();
+
+        let watcher = SkillWatcher::new(std::slice::from_ref(&skills)).unwrap();
+        fs::create_dir_all(skills.join("new-skill")).unwrap();
+        fs::write(skills.join("new-skill").join("SKILL.md"), "---\nname: new-skill\n---\n").unwrap();
+
+        let batch = watcher.next_batch().unwrap();
+        assert!(batch.contains(&skills.join("new-skill")));
+    }
+}
@@ -0,0 +1,233 @@
+//! Team sources: shared, read-only git repos of skills, agents and bundles
+//!
+//! `pais team add <git-url>` clones a repo into
+//! `~/.local/share/pais/teams/<name>/` and `pais team update` pulls it,
+//! discarding anything edited locally. A team repo holds any of:
+//!
+//! ```text
+//! skills/<name>/SKILL.md
+//! agents/<name>.yaml
+//! bundles/<name>/bundle.yaml
+//! ```
+//!
+//! Their contents join discovery next to your own, labelled `team:<name>`.
+//! Personal items always win: a team item whose name you already use is
+//! hidden. Teams are listed in name order, so the first one providing a
+//! name wins among teams.
+
+use eyre::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::error::{CliError, ErrorCode};
+use crate::skill::Skill;
+use crate::skill::loader::discover_team_skills;
+use crate::sync::backend::Remote;
+
+/// A subscribed team repo
+#[derive(Debug, Clone)]
+pub struct Team {
+    pub name: String,
+    /// Local clone
+    pub path: PathBuf,
+}
+
+impl Team {
+    pub fn skills_dir(&self) -> PathBuf {
+        self.path.join("skills")
+    }
+
+    pub fn agents_dir(&self) -> PathBuf {
+        self.path.join("agents")
+    }
+
+    pub fn bundles_dir(&self) -> PathBuf {
+        self.path.join("bundles")
+    }
+
+    /// URL the clone was made from
+    pub fn url(&self) -> Option<String> {
+        git_output(&self.path, &["remote", "get-url", "origin"])
+    }
+
+    /// Checked-out commit
+    pub fn commit(&self) -> Option<String> {
+        git_output(&self.path, &["rev-parse", "HEAD"])
+    }
+
+    /// Pull the latest commit, returning the commit before and after
+    pub fn update(&self) -> Result<(Option<String>, Option<String>)> {
+        let url = self
+            .url()
+            .ok_or_else(|| eyre::eyre!("{} has no origin remote", self.path.display()))?;
+        let before = self.commit();
+        Remote::Git { url }
+            .fetch(&self.path)
+            .with_context(|| format!("Failed to update team '{}'", self.name))?;
+        Ok((before, self.commit()))
+    }
+}
+
+/// Directory holding team clones
+pub fn teams_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("pais")
+        .join("teams")
+}
+
+/// Subscribed teams, by name
+pub fn list() -> Vec<Team> {
+    list_in(&teams_dir())
+}
+
+fn list_in(dir: &Path) -> Vec<Team> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut teams: Vec<Team> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.join(".git").exists())
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?.to_string();
+            Some(Team { name, path })
+        })
+        .collect();
+    teams.sort_by(|a, b| a.name.cmp(&b.name));
+    teams
+}
+
+/// Look up a subscribed team
+pub fn get(name: &str) -> Result<Team> {
+    list().into_iter().find(|team| team.name == name).ok_or_else(|| {
+        CliError::not_found("Team", name)
+            .with_hint("See `pais team list`")
+            .into()
+    })
+}
+
+/// Default team name for a repo URL: its last path segment without `.git`
+pub fn name_from_url(url: &str) -> Option<String> {
+    let name = url
+        .trim_end_matches('/')
+        .rsplit(['/', ':'])
+        .next()?
+        .trim_end_matches(".git");
+    let valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c));
+    (valid && !name.starts_with('.')).then(|| name.to_string())
+}
+
+/// Clone a team repo
+pub fn add(url: &str, name: Option<&str>) -> Result<Team> {
+    let name = match name {
+        Some(name) => name.to_string(),
+        None => name_from_url(url).ok_or_else(|| {
+            CliError::new(ErrorCode::InvalidInput, format!("Can't name a team after {}", url)).with_hint("Pass --name")
+        })?,
+    };
+    let path = teams_dir().join(&name);
+    if path.exists() {
+        return Err(
+            CliError::new(ErrorCode::Conflict, format!("Team '{}' already exists", name))
+                .with_hint("Use `pais team update` to pull it, or pick another --name")
+                .into(),
+        );
+    }
+
+    let remote = Remote::Git { url: url.to_string() };
+    if let Err(e) = remote.fetch(&path) {
+        fs::remove_dir_all(&path).ok();
+        return Err(e.wrap_err(format!("Failed to clone {}", url)));
+    }
+    Ok(Team { name, path })
+}
+
+/// Unsubscribe from a team, deleting its clone
+pub fn remove(name: &str) -> Result<Team> {
+    let team = get(name)?;
+    fs::remove_dir_all(&team.path).with_context(|| format!("Failed to remove {}", team.path.display()))?;
+    Ok(team)
+}
+
+/// Skills from every team, minus names taken by `personal` or an earlier team
+pub fn skills(personal: &[Skill]) -> Vec<Skill> {
+    let mut taken: Vec<String> = personal.iter().map(|s| s.name.clone()).collect();
+    let mut skills = Vec::new();
+    for team in list() {
+        let found = match discover_team_skills(&team.skills_dir(), &team.name) {
+            Ok(found) => found,
+            Err(e) => {
+                log::warn!("Failed to read skills of team '{}': {}", team.name, e);
+                continue;
+            }
+        };
+        for skill in found {
+            if taken.contains(&skill.name) {
+                log::debug!("Team '{}' skill '{}' is shadowed", team.name, skill.name);
+                continue;
+            }
+            taken.push(skill.name.clone());
+            skills.push(skill);
+        }
+    }
+    skills
+}
+
+/// Agent directories of every team, as (team, directory)
+pub fn agent_dirs() -> Vec<(String, PathBuf)> {
+    list()
+        .into_iter()
+        .map(|team| (team.name.clone(), team.agents_dir()))
+        .collect()
+}
+
+/// Bundle directories of every team, as (team, directory)
+pub fn bundle_dirs() -> Vec<(String, PathBuf)> {
+    list()
+        .into_iter()
+        .map(|team| (team.name.clone(), team.bundles_dir()))
+        .collect()
+}
+
+/// Trimmed stdout of a git command in `dir`, if it succeeded
+fn git_output(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_name_from_url() {
+        assert_eq!(
+            name_from_url("https://github.com/acme/skills.git").as_deref(),
+            Some("skills")
+        );
+        assert_eq!(
+            name_from_url("git@github.com:acme/team-pais").as_deref(),
+            Some("team-pais")
+        );
+        assert_eq!(name_from_url("/srv/git/shared/").as_deref(), Some("shared"));
+        assert_eq!(name_from_url("https://example.com/"), Some("example.com".to_string()));
+        assert_eq!(name_from_url("..git"), None);
+    }
+
+    #[test]
+    fn test_list_only_git_clones() {
+        let temp = tempdir().unwrap();
+        fs::create_dir_all(temp.path().join("zeta/.git")).unwrap();
+        fs::create_dir_all(temp.path().join("alpha/.git")).unwrap();
+        fs::create_dir_all(temp.path().join("half-cloned")).unwrap();
+        let names: Vec<String> = list_in(temp.path()).into_iter().map(|t| t.name).collect();
+        assert_eq!(names, vec!["alpha", "zeta"]);
+    }
+}
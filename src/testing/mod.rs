@@ -0,0 +1,163 @@
+//! Test runner for plugins and skills
+//!
+//! Plugins declare a test command in `plugin.yaml`:
+//! ```yaml
+//! test:
+//!   command: uv run pytest -q
+//! ```
+//!
+//! Skills ship example prompts with their expected routing in `tests.yaml`
+//! next to `SKILL.md`:
+//! ```yaml
+//! examples:
+//!   - prompt: scaffold a new rust cli
+//!     workflow: new CLI project   # optional: expected workflow intent
+//!   - prompt: containerize the python service
+//!     expect: docker              # should route to another skill
+//!   - prompt: what's for lunch
+//!     expect: none                # should not route to any skill
+//! ```
+//!
+//! Both produce the same `TestSuite` report so results can be consumed uniformly (e.g. in CI).
+
+pub mod plugin;
+pub mod skill;
+
+use colored::*;
+use serde::Serialize;
+
+use crate::presenter;
+
+/// Kind of thing under test
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SuiteKind {
+    Plugin,
+    Skill,
+}
+
+impl std::fmt::Display for SuiteKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SuiteKind::Plugin => write!(f, "plugin"),
+            SuiteKind::Skill => write!(f, "skill"),
+        }
+    }
+}
+
+/// Result of a single test case
+#[derive(Debug, Clone, Serialize)]
+pub struct TestCase {
+    pub name: String,
+    pub passed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// All test cases for one plugin or skill
+#[derive(Debug, Clone, Serialize)]
+pub struct TestSuite {
+    pub kind: SuiteKind,
+    pub name: String,
+    pub cases: Vec<TestCase>,
+}
+
+impl TestSuite {
+    pub fn failed_count(&self) -> usize {
+        self.cases.iter().filter(|c| !c.passed).count()
+    }
+}
+
+/// Aggregate report across suites
+#[derive(Debug, Serialize)]
+pub struct TestReport {
+    pub passed: bool,
+    pub total: usize,
+    pub failed: usize,
+    pub suites: Vec<TestSuite>,
+    /// Plugins/skills that were found but define no tests
+    pub skipped: Vec<String>,
+}
+
+impl TestReport {
+    pub fn new(suites: Vec<TestSuite>, skipped: Vec<String>) -> Self {
+        let total = suites.iter().map(|s| s.cases.len()).sum();
+        let failed = suites.iter().map(|s| s.failed_count()).sum();
+        Self {
+            passed: failed == 0,
+            total,
+            failed,
+            suites,
+            skipped,
+        }
+    }
+}
+
+/// Print a report to the terminal
+pub fn print_report(report: &TestReport) {
+    for suite in &report.suites {
+        println!("{} {}", suite.kind.to_string().dimmed(), suite.name.cyan().bold());
+        for case in &suite.cases {
+            let icon = if case.passed { presenter::ok() } else { presenter::fail() };
+            let timing = format!("({}ms)", case.duration_ms).dimmed();
+            match case.message {
+                Some(ref msg) if !case.passed => println!("  {} {} {}\n      {}", icon, case.name, timing, msg.red()),
+                Some(ref msg) => println!("  {} {} {} {}", icon, case.name, timing, msg.dimmed()),
+                None => println!("  {} {} {}", icon, case.name, timing),
+            }
+        }
+        println!();
+    }
+
+    if !report.skipped.is_empty() {
+        println!(
+            "{} {}",
+            "No tests defined:".dimmed(),
+            report.skipped.join(", ").dimmed()
+        );
+        println!();
+    }
+
+    let summary = format!("{}/{} passed", report.total - report.failed, report.total);
+    if report.passed {
+        println!("Result: {} ({})", "PASSED".green().bold(), summary.dimmed());
+    } else {
+        println!("Result: {} ({})", "FAILED".red().bold(), summary.dimmed());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn case(passed: bool) -> TestCase {
+        TestCase {
+            name: "case".to_string(),
+            passed,
+            message: None,
+            duration_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_report_counts() {
+        let suites = vec![
+            TestSuite {
+                kind: SuiteKind::Plugin,
+                name: "a".to_string(),
+                cases: vec![case(true)],
+            },
+            TestSuite {
+                kind: SuiteKind::Skill,
+                name: "b".to_string(),
+                cases: vec![case(true), case(false)],
+            },
+        ];
+        let report = TestReport::new(suites, vec![]);
+        assert!(!report.passed);
+        assert_eq!(report.total, 3);
+        assert_eq!(report.failed, 1);
+        assert_eq!(report.suites[0].failed_count(), 0);
+    }
+}
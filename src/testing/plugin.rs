@@ -0,0 +1,95 @@
+//! Run a plugin's declared test command
+
+use eyre::{Context, Result};
+use std::process::Command;
+use std::time::Instant;
+
+use super::{SuiteKind, TestCase, TestSuite};
+use crate::plugin::Plugin;
+
+/// Lines of output kept in the failure message
+const OUTPUT_TAIL_LINES: usize = 20;
+
+/// Run the plugin's test command, or return `None` if it declares none
+pub fn run_plugin_tests(plugin: &Plugin) -> Result<Option<TestSuite>> {
+    let Some(ref command) = plugin.manifest.test.command else {
+        return Ok(None);
+    };
+
+    let start = Instant::now();
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(&plugin.path)
+        .env("PAIS_PLUGIN_DIR", &plugin.path)
+        .output()
+        .with_context(|| format!("Failed to execute test command: {}", command))?;
+    let duration_ms = start.elapsed().as_millis() as u64;
+
+    let passed = output.status.success();
+    let message = if passed {
+        None
+    } else {
+        let mut combined = String::from_utf8_lossy(&output.stdout).to_string();
+        combined.push_str(&String::from_utf8_lossy(&output.stderr));
+        let lines: Vec<&str> = combined.lines().collect();
+        let tail = lines[lines.len().saturating_sub(OUTPUT_TAIL_LINES)..].join("\n      ");
+        Some(format!(
+            "Exit code {}{}",
+            output.status.code().unwrap_or(-1),
+            if tail.is_empty() { String::new() } else { format!("\n      {}", tail) }
+        ))
+    };
+
+    Ok(Some(TestSuite {
+        kind: SuiteKind::Plugin,
+        name: plugin.manifest.plugin.name.clone(),
+        cases: vec![TestCase {
+            name: command.clone(),
+            passed,
+            message,
+            duration_ms,
+        }],
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::loader::load_plugin;
+    use tempfile::tempdir;
+
+    fn plugin_with_test(command: Option<&str>) -> (tempfile::TempDir, Plugin) {
+        let temp = tempdir().unwrap();
+        let mut manifest = "plugin:\n  name: demo\n  version: 0.1.0\n  description: Demo\n".to_string();
+        if let Some(command) = command {
+            manifest.push_str(&format!("test:\n  command: \"{}\"\n", command));
+        }
+        std::fs::write(temp.path().join("plugin.yaml"), manifest).unwrap();
+        let plugin = load_plugin(temp.path()).unwrap();
+        (temp, plugin)
+    }
+
+    #[test]
+    fn test_no_command_is_skipped() {
+        let (_temp, plugin) = plugin_with_test(None);
+        assert!(run_plugin_tests(&plugin).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_command_runs_in_plugin_dir() {
+        let (_temp, plugin) = plugin_with_test(Some("test -f plugin.yaml"));
+        let suite = run_plugin_tests(&plugin).unwrap().unwrap();
+        assert_eq!(suite.failed_count(), 0);
+    }
+
+    #[test]
+    fn test_failing_command_reports_output() {
+        let (_temp, plugin) = plugin_with_test(Some("echo boom; exit 3"));
+        let suite = run_plugin_tests(&plugin).unwrap().unwrap();
+        assert_eq!(suite.failed_count(), 1);
+        let message = suite.cases[0].message.as_deref().unwrap();
+        assert!(message.contains("Exit code 3"));
+        assert!(message.contains("boom"));
+    }
+}
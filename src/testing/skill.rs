@@ -0,0 +1,194 @@
+//! Check that a skill's example prompts route where they should
+
+use eyre::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+use super::{SuiteKind, TestCase, TestSuite};
+use crate::skill::indexer::SkillIndex;
+
+/// Test spec file shipped alongside SKILL.md
+pub const SKILL_TESTS_FILE: &str = "tests.yaml";
+
+/// Expected routing meaning "no skill should match"
+const EXPECT_NONE: &str = "none";
+
+/// Contents of a skill's tests.yaml
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct SkillTestSpec {
+    pub examples: Vec<RoutingExample>,
+}
+
+/// An example prompt and where it should route
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RoutingExample {
+    pub prompt: String,
+    /// Expected skill (defaults to the skill under test; `none` for no match)
+    #[serde(default)]
+    pub expect: Option<String>,
+    /// Expected workflow intent within the routed skill
+    #[serde(default)]
+    pub workflow: Option<String>,
+}
+
+/// Load `tests.yaml` from a skill directory, if present
+pub fn load_spec(skill_dir: &Path) -> Result<Option<SkillTestSpec>> {
+    let path = skill_dir.join(SKILL_TESTS_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let spec = serde_yaml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(Some(spec))
+}
+
+/// Route each example prompt against the index and compare with expectations
+pub fn run_skill_tests(skill_name: &str, spec: &SkillTestSpec, index: &SkillIndex) -> TestSuite {
+    let cases = spec
+        .examples
+        .iter()
+        .map(|example| {
+            let start = Instant::now();
+            let (passed, message) = check_example(skill_name, example, index);
+            TestCase {
+                name: format!("\"{}\"", example.prompt),
+                passed,
+                message: Some(message),
+                duration_ms: start.elapsed().as_millis() as u64,
+            }
+        })
+        .collect();
+
+    TestSuite {
+        kind: SuiteKind::Skill,
+        name: skill_name.to_string(),
+        cases,
+    }
+}
+
+fn check_example(skill_name: &str, example: &RoutingExample, index: &SkillIndex) -> (bool, String) {
+    let expected = example.expect.as_deref().unwrap_or(skill_name);
+    let routes = index.route(&example.prompt);
+
+    let Some(top) = routes.first() else {
+        return if expected.eq_ignore_ascii_case(EXPECT_NONE) {
+            (true, "→ no skill".to_string())
+        } else {
+            (false, format!("expected {}, but no skill matched", expected))
+        };
+    };
+
+    if expected.eq_ignore_ascii_case(EXPECT_NONE) {
+        return (false, format!("expected no skill, routed to {}", top.skill));
+    }
+
+    if !top.skill.eq_ignore_ascii_case(expected) {
+        return (
+            false,
+            format!("expected {}, routed to {} (score {})", expected, top.skill, top.score),
+        );
+    }
+
+    let tied: Vec<&str> = routes[1..]
+        .iter()
+        .filter(|r| r.score == top.score)
+        .map(|r| r.skill.as_str())
+        .collect();
+    if !tied.is_empty() {
+        return (
+            false,
+            format!(
+                "ambiguous: {} tied with {} (score {})",
+                top.skill,
+                tied.join(", "),
+                top.score
+            ),
+        );
+    }
+
+    if let Some(ref workflow) = example.workflow {
+        match top.workflow {
+            Some(ref actual) if actual.eq_ignore_ascii_case(workflow) => {}
+            Some(ref actual) => return (false, format!("expected workflow '{}', got '{}'", workflow, actual)),
+            None => return (false, format!("expected workflow '{}', none matched", workflow)),
+        }
+    }
+
+    let workflow = top.workflow.as_ref().map(|w| format!(" / {}", w)).unwrap_or_default();
+    (true, format!("→ {}{}", top.skill, workflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skill::indexer::generate_index;
+    use tempfile::tempdir;
+
+    fn write_skill(dir: &Path, name: &str, description: &str) {
+        let skill_dir = dir.join(name);
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join("SKILL.md"),
+            format!("---\nname: {}\ndescription: {}\n---\n# {}\n", name, description, name),
+        )
+        .unwrap();
+    }
+
+    fn example(prompt: &str, expect: Option<&str>) -> RoutingExample {
+        RoutingExample {
+            prompt: prompt.to_string(),
+            expect: expect.map(String::from),
+            workflow: None,
+        }
+    }
+
+    #[test]
+    fn test_routing_expectations() {
+        let temp = tempdir().unwrap();
+        write_skill(
+            temp.path(),
+            "rust-coder",
+            "Write Rust code. USE WHEN building with cargo.",
+        );
+        write_skill(
+            temp.path(),
+            "docker-helper",
+            "Containers. USE WHEN working with docker.",
+        );
+        let index = generate_index(temp.path()).unwrap();
+
+        let spec = SkillTestSpec {
+            examples: vec![
+                example("fix my cargo build for this rust crate", None),
+                example("what's for lunch", Some("none")),
+                example("write a Dockerfile for docker compose", None),
+            ],
+        };
+
+        let suite = run_skill_tests("rust-coder", &spec, &index);
+        assert!(suite.cases[0].passed, "{:?}", suite.cases[0].message);
+        assert!(suite.cases[1].passed);
+        assert!(!suite.cases[2].passed);
+        assert!(suite.cases[2].message.as_deref().unwrap().contains("docker-helper"));
+    }
+
+    #[test]
+    fn test_load_spec() {
+        let temp = tempdir().unwrap();
+        assert!(load_spec(temp.path()).unwrap().is_none());
+
+        fs::write(
+            temp.path().join(SKILL_TESTS_FILE),
+            "examples:\n  - prompt: hello\n    expect: none\n",
+        )
+        .unwrap();
+        let spec = load_spec(temp.path()).unwrap().unwrap();
+        assert_eq!(spec.examples.len(), 1);
+        assert_eq!(spec.examples[0].expect.as_deref(), Some("none"));
+    }
+}
@@ -0,0 +1,285 @@
+//! Workspace trust
+//!
+//! Claude Code runs hooks from whatever directory a session was started in,
+//! including freshly cloned repos. A repo can ship a `.pais/policy.yaml`
+//! whose `allow` rules silence the security tiers that warn, or a `pais.yaml` that
+//! the development config lookup would pick up. To keep a hostile checkout
+//! from reconfiguring pais, workspaces start out untrusted:
+//!
+//! - project policy files are ignored
+//! - `./pais.yaml` is not considered as a config file
+//! - security tiers are escalated one step (warn blocks, log warns)
+//!
+//! The first hook that runs in a new directory records it in
+//! `~/.local/share/pais/trust.json`, which keeps the most recently seen
+//! [`MAX_UNTRUSTED`] untrusted directories. `pais trust <path>` marks a directory
+//! (and everything below it) as trusted; `security.trust.trusted` in
+//! pais.yaml does the same for whole trees such as `~/src`. The config file
+//! lookup runs before any config is loaded, so it only consults the store,
+//! and only when no other config file exists.
+
+use chrono::{DateTime, Utc};
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::{Config, TrustConfig};
+use crate::lock::FileLock;
+
+/// Untrusted workspaces the store remembers; older ones are forgotten
+pub const MAX_UNTRUSTED: usize = 200;
+
+/// A directory hooks have run in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workspace {
+    pub first_seen: DateTime<Utc>,
+    /// Set by `pais trust`; absent while untrusted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trusted_at: Option<DateTime<Utc>>,
+}
+
+/// Trust decision for one directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Trust {
+    /// Trusted through this directory (itself or an ancestor)
+    Trusted(PathBuf),
+    Untrusted,
+    /// `security.trust.enabled` is off
+    Unchecked,
+}
+
+impl Trust {
+    pub fn is_trusted(&self) -> bool {
+        !matches!(self, Trust::Untrusted)
+    }
+}
+
+/// Every workspace pais has seen, keyed by canonical path
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrustStore {
+    #[serde(default)]
+    pub workspaces: BTreeMap<PathBuf, Workspace>,
+}
+
+/// Location of the trust store (`~/.local/share/pais/trust.json`)
+pub fn store_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("pais")
+        .join("trust.json")
+}
+
+/// Canonical form of `path`, or the path itself if it can't be resolved
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+impl TrustStore {
+    /// Load the store, treating a missing or unreadable file as empty
+    pub fn load() -> Self {
+        Self::load_from(&store_path())
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        fs::read(path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write the store atomically
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let tmp = path.with_extension(format!("json.{}", std::process::id()));
+        fs::write(&tmp, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path.display()))?;
+        Ok(())
+    }
+
+    /// How `path` is trusted by the store alone
+    pub fn trust(&self, path: &Path) -> Trust {
+        let path = canonical(path);
+        path.ancestors()
+            .find(|dir| self.workspaces.get(*dir).is_some_and(|w| w.trusted_at.is_some()))
+            .map_or(Trust::Untrusted, |dir| Trust::Trusted(dir.to_path_buf()))
+    }
+
+    pub fn is_trusted(&self, path: &Path) -> bool {
+        self.trust(path).is_trusted()
+    }
+
+    /// Record `path` as seen; returns true if it wasn't known before
+    ///
+    /// Beyond [`MAX_UNTRUSTED`] untrusted workspaces, the oldest are dropped.
+    pub fn observe(&mut self, path: &Path, now: DateTime<Utc>) -> bool {
+        let mut new = false;
+        self.workspaces.entry(canonical(path)).or_insert_with(|| {
+            new = true;
+            Workspace {
+                first_seen: now,
+                trusted_at: None,
+            }
+        });
+        if new {
+            self.forget_oldest_untrusted();
+        }
+        new
+    }
+
+    fn forget_oldest_untrusted(&mut self) {
+        let mut untrusted: Vec<(DateTime<Utc>, PathBuf)> = self
+            .workspaces
+            .iter()
+            .filter(|(_, w)| w.trusted_at.is_none())
+            .map(|(path, w)| (w.first_seen, path.clone()))
+            .collect();
+        if untrusted.len() <= MAX_UNTRUSTED {
+            return;
+        }
+        untrusted.sort();
+        for (_, path) in &untrusted[..untrusted.len() - MAX_UNTRUSTED] {
+            self.workspaces.remove(path);
+        }
+    }
+
+    /// Mark `path` and everything below it as trusted
+    pub fn grant(&mut self, path: &Path, now: DateTime<Utc>) {
+        let workspace = self.workspaces.entry(canonical(path)).or_insert(Workspace {
+            first_seen: now,
+            trusted_at: None,
+        });
+        workspace.trusted_at.get_or_insert(now);
+    }
+
+    /// Withdraw trust granted to exactly `path`; returns false if it had none
+    pub fn revoke(&mut self, path: &Path) -> bool {
+        self.workspaces
+            .get_mut(&canonical(path))
+            .and_then(|w| w.trusted_at.take())
+            .is_some()
+    }
+}
+
+/// How `path` is trusted, taking the config into account
+pub fn resolve(config: &TrustConfig, store: &TrustStore, path: &Path) -> Trust {
+    if !config.enabled {
+        return Trust::Unchecked;
+    }
+    let canonical_path = canonical(path);
+    let configured = config
+        .trusted
+        .iter()
+        .map(|root| canonical(&Config::expand_path(root)))
+        .find(|root| canonical_path.starts_with(root));
+    match configured {
+        Some(root) => Trust::Trusted(root),
+        None => store.trust(path),
+    }
+}
+
+/// Check the workspace a hook runs in, recording it the first time it's seen
+///
+/// Returns the decision and whether the workspace was new. Failing to write
+/// the store only logs a warning; the workspace is still treated as untrusted.
+pub fn check_workspace(config: &TrustConfig, cwd: &Path) -> (Trust, bool) {
+    let path = store_path();
+    let store = TrustStore::load_from(&path);
+    let trust = resolve(config, &store, cwd);
+    if trust.is_trusted() || store.workspaces.contains_key(&canonical(cwd)) {
+        return (trust, false);
+    }
+
+    let _lock = match FileLock::acquire(&path) {
+        Ok(lock) => lock,
+        Err(e) => {
+            log::warn!("Failed to lock trust store: {}", e);
+            return (trust, false);
+        }
+    };
+    // Reload under the lock so concurrent hooks don't drop each other's entries
+    let mut store = TrustStore::load_from(&path);
+    let new = store.observe(cwd, Utc::now());
+    if new && let Err(e) = store.save_to(&path) {
+        log::warn!("Failed to record workspace {}: {}", cwd.display(), e);
+    }
+    (trust, new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_trust_covers_subdirectories() {
+        let temp = tempdir().unwrap();
+        let repo = temp.path().join("repo");
+        let nested = repo.join("src");
+        fs::create_dir_all(&nested).unwrap();
+        let now = Utc::now();
+
+        let mut store = TrustStore::default();
+        assert!(store.observe(&nested, now));
+        assert!(!store.observe(&nested, now));
+        assert_eq!(store.trust(&nested), Trust::Untrusted);
+
+        store.grant(&repo, now);
+        assert_eq!(store.trust(&nested), Trust::Trusted(canonical(&repo)));
+        assert!(!store.is_trusted(temp.path()));
+
+        assert!(store.revoke(&repo));
+        assert!(!store.revoke(&repo));
+        assert!(!store.is_trusted(&nested));
+
+        let path = temp.path().join("trust.json");
+        store.grant(&nested, now);
+        store.save_to(&path).unwrap();
+        let loaded = TrustStore::load_from(&path);
+        assert!(loaded.is_trusted(&nested));
+        assert_eq!(loaded.workspaces.len(), 2);
+    }
+
+    #[test]
+    fn test_untrusted_workspaces_are_bounded() {
+        let start = Utc::now();
+        let mut store = TrustStore::default();
+        store.grant(Path::new("/trusted"), start);
+        for i in 0..MAX_UNTRUSTED + 5 {
+            let seen = start + chrono::Duration::seconds(i as i64 + 1);
+            store.observe(&PathBuf::from(format!("/seen/{}", i)), seen);
+        }
+
+        assert_eq!(store.workspaces.len(), MAX_UNTRUSTED + 1);
+        assert!(store.is_trusted(Path::new("/trusted")));
+        assert!(!store.workspaces.contains_key(Path::new("/seen/4")));
+        assert!(store.workspaces.contains_key(Path::new("/seen/5")));
+    }
+
+    #[test]
+    fn test_resolve_with_config() {
+        let temp = tempdir().unwrap();
+        let repo = temp.path().join("src").join("repo");
+        fs::create_dir_all(&repo).unwrap();
+        let store = TrustStore::default();
+
+        let config = TrustConfig::default();
+        assert_eq!(resolve(&config, &store, &repo), Trust::Untrusted);
+
+        let config = TrustConfig {
+            enabled: true,
+            trusted: vec![temp.path().join("src")],
+        };
+        assert!(resolve(&config, &store, &repo).is_trusted());
+
+        let config = TrustConfig {
+            enabled: false,
+            trusted: Vec::new(),
+        };
+        assert_eq!(resolve(&config, &store, &repo), Trust::Unchecked);
+    }
+}
@@ -0,0 +1,365 @@
+//! Per-project environment detection
+//!
+//! `pais context inject` describes the project a session starts in: its
+//! languages, package managers, Make/just targets, npm scripts and CI
+//! system, detected from marker files at the repository root. The block is
+//! cached with the context injection (see [`crate::skill::context_cache`]),
+//! keyed on the root and the size and mtime of its top-level files and CI
+//! definitions, so editing the Makefile or adding a lockfile refreshes it.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::perf;
+use crate::skill::context_cache::{self, CacheKey};
+
+/// Marker file → language
+const LANGUAGES: &[(&str, &str)] = &[
+    ("Cargo.toml", "Rust"),
+    ("go.mod", "Go"),
+    ("pyproject.toml", "Python"),
+    ("setup.py", "Python"),
+    ("requirements.txt", "Python"),
+    ("tsconfig.json", "TypeScript"),
+    ("package.json", "JavaScript"),
+    ("deno.json", "TypeScript"),
+    ("Gemfile", "Ruby"),
+    ("pom.xml", "Java"),
+    ("build.gradle", "Java"),
+    ("build.gradle.kts", "Kotlin"),
+    ("composer.json", "PHP"),
+    ("mix.exs", "Elixir"),
+    ("Package.swift", "Swift"),
+    ("CMakeLists.txt", "C/C++"),
+];
+
+/// Marker file → package manager; lockfiles come before the manifests they lock
+const PACKAGE_MANAGERS: &[(&str, &str)] = &[
+    ("Cargo.toml", "cargo"),
+    ("go.mod", "go"),
+    ("uv.lock", "uv"),
+    ("poetry.lock", "poetry"),
+    ("Pipfile", "pipenv"),
+    ("requirements.txt", "pip"),
+    ("pnpm-lock.yaml", "pnpm"),
+    ("yarn.lock", "yarn"),
+    ("bun.lock", "bun"),
+    ("bun.lockb", "bun"),
+    ("package-lock.json", "npm"),
+    ("Gemfile", "bundler"),
+    ("pom.xml", "maven"),
+    ("build.gradle", "gradle"),
+    ("build.gradle.kts", "gradle"),
+    ("composer.json", "composer"),
+    ("mix.exs", "mix"),
+];
+
+/// Marker path → CI system
+const CI_SYSTEMS: &[(&str, &str)] = &[
+    (".github/workflows", "GitHub Actions"),
+    (".gitlab-ci.yml", "GitLab CI"),
+    (".circleci", "CircleCI"),
+    ("Jenkinsfile", "Jenkins"),
+    (".travis.yml", "Travis CI"),
+    ("azure-pipelines.yml", "Azure Pipelines"),
+    ("bitbucket-pipelines.yml", "Bitbucket Pipelines"),
+    (".buildkite", "Buildkite"),
+];
+
+/// Most targets or scripts listed per runner
+const MAX_TARGETS: usize = 12;
+
+/// What was detected about a project
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectEnv {
+    pub root: PathBuf,
+    pub languages: Vec<String>,
+    pub package_managers: Vec<String>,
+    pub make_targets: Vec<String>,
+    pub just_recipes: Vec<String>,
+    pub npm_scripts: Vec<String>,
+    pub ci: Vec<String>,
+    /// Workflow files, for GitHub Actions
+    pub workflows: Vec<String>,
+}
+
+impl ProjectEnv {
+    /// Inspect the marker files at `root`
+    pub fn detect(root: &Path) -> Self {
+        let exists = |marker: &str| root.join(marker).exists();
+        let matching = |table: &[(&str, &str)]| {
+            let mut found: Vec<String> = Vec::new();
+            for (marker, name) in table {
+                if exists(marker) && !found.iter().any(|f| f == name) {
+                    found.push(name.to_string());
+                }
+            }
+            found
+        };
+
+        let mut languages = matching(LANGUAGES);
+        // TypeScript projects also have a package.json
+        if languages.iter().any(|l| l == "TypeScript") {
+            languages.retain(|l| l != "JavaScript");
+        }
+        let mut package_managers = matching(PACKAGE_MANAGERS);
+        let node_managers = ["pnpm", "yarn", "bun", "npm"];
+        if exists("package.json") && !package_managers.iter().any(|m| node_managers.contains(&m.as_str())) {
+            package_managers.push("npm".to_string());
+        }
+
+        let workflows = list_dir(&root.join(".github").join("workflows"))
+            .into_iter()
+            .filter(|name| name.ends_with(".yml") || name.ends_with(".yaml"))
+            .collect();
+
+        Self {
+            root: root.to_path_buf(),
+            languages,
+            package_managers,
+            make_targets: make_targets(root),
+            just_recipes: just_recipes(root),
+            npm_scripts: npm_scripts(root),
+            ci: matching(CI_SYSTEMS),
+            workflows,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.languages.is_empty()
+            && self.package_managers.is_empty()
+            && self.make_targets.is_empty()
+            && self.just_recipes.is_empty()
+            && self.npm_scripts.is_empty()
+            && self.ci.is_empty()
+    }
+
+    /// The compact block for the injected context
+    pub fn render(&self) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+        let name = self
+            .root
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.root.display().to_string());
+        let mut lines = vec![
+            format!("## Project: {} (`{}`)", name, self.root.display()),
+            String::new(),
+        ];
+
+        let mut item = |label: &str, values: &[String], suffix: String| {
+            if !values.is_empty() {
+                lines.push(format!("- {}: {}{}", label, list(values), suffix));
+            }
+        };
+        item("Languages", &self.languages, String::new());
+        item("Package managers", &self.package_managers, String::new());
+        item("Make targets", &self.make_targets, " (`make <target>`)".to_string());
+        item("just recipes", &self.just_recipes, " (`just <recipe>`)".to_string());
+        let runner = self
+            .package_managers
+            .iter()
+            .find(|m| ["pnpm", "yarn", "bun"].contains(&m.as_str()))
+            .map(String::as_str)
+            .unwrap_or("npm");
+        item(
+            "npm scripts",
+            &self.npm_scripts,
+            format!(" (`{} run <script>`)", runner),
+        );
+        let workflows = if self.workflows.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", self.workflows.join(", "))
+        };
+        item("CI", &self.ci, workflows);
+
+        Some(lines.join("\n"))
+    }
+}
+
+/// The project block for `dir` (its git work tree, else `dir` itself), cached
+pub fn project_context(dir: &Path, no_cache: bool) -> Option<String> {
+    let root = crate::checkpoint::repo_root(dir).unwrap_or_else(|| dir.to_path_buf());
+    let cache_dir = context_cache::cache_dir().filter(|_| !no_cache);
+    let key = cache_key(&root);
+    if let Some(dir) = &cache_dir {
+        let cached = context_cache::load::<ProjectEnv>(dir, &key);
+        perf::cache("project-env", cached.is_some());
+        if let Some(env) = cached {
+            log::debug!("Using cached project environment for {}", root.display());
+            return env.render();
+        }
+    }
+
+    let env = ProjectEnv::detect(&root);
+    if let Some(dir) = &cache_dir
+        && let Err(e) = context_cache::store(dir, &key, &env)
+    {
+        log::debug!("Failed to cache project environment: {}", e);
+    }
+    env.render()
+}
+
+/// Fingerprint of everything detection reads
+fn cache_key(root: &Path) -> String {
+    let mut key = CacheKey::new();
+    key.add("project", root.display());
+    for dir in [root.to_path_buf(), root.join(".github").join("workflows")] {
+        for name in list_dir(&dir) {
+            let path = dir.join(&name);
+            // Directory mtimes move with every build; only their names matter here
+            if path.is_dir() {
+                key.add("dir", name);
+            } else {
+                key.add_file(&path);
+            }
+        }
+    }
+    for (marker, _) in CI_SYSTEMS {
+        key.add_file(&root.join(marker));
+    }
+    key.finish()
+}
+
+/// Sorted names of the entries in `dir`
+fn list_dir(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.file_name().to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+fn list(values: &[String]) -> String {
+    let mut shown = values.iter().take(MAX_TARGETS).cloned().collect::<Vec<_>>().join(", ");
+    if values.len() > MAX_TARGETS {
+        shown.push_str(&format!(" and {} more", values.len() - MAX_TARGETS));
+    }
+    shown
+}
+
+/// Rule targets of the root Makefile, in file order
+fn make_targets(root: &Path) -> Vec<String> {
+    let Some(content) = ["Makefile", "makefile", "GNUmakefile"]
+        .iter()
+        .find_map(|name| fs::read_to_string(root.join(name)).ok())
+    else {
+        return Vec::new();
+    };
+    let mut targets: Vec<String> = Vec::new();
+    for line in content.lines() {
+        if line.starts_with(['\t', ' ', '#', '.']) {
+            continue;
+        }
+        let Some((names, rest)) = line.split_once(':') else {
+            continue;
+        };
+        // `VAR := value` and `VAR ::= value` are assignments
+        if rest.starts_with('=') || rest.starts_with(":=") || names.contains(['=', '$', '%']) {
+            continue;
+        }
+        for name in names.split_whitespace() {
+            if !targets.iter().any(|t| t == name) {
+                targets.push(name.to_string());
+            }
+        }
+    }
+    targets
+}
+
+/// Recipes of the root justfile, in file order
+fn just_recipes(root: &Path) -> Vec<String> {
+    let Some(content) = ["justfile", "Justfile", ".justfile"]
+        .iter()
+        .find_map(|name| fs::read_to_string(root.join(name)).ok())
+    else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .filter(|line| !line.starts_with([' ', '\t', '#', '@', '[']) && !line.contains(":="))
+        .filter_map(|line| line.split_once(':'))
+        .filter_map(|(head, _)| head.split_whitespace().next())
+        .filter(|name| name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_'))
+        .map(String::from)
+        .collect()
+}
+
+/// Script names from package.json
+fn npm_scripts(root: &Path) -> Vec<String> {
+    fs::read_to_string(root.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|json| {
+            json.get("scripts")
+                .and_then(|s| s.as_object())
+                .map(|scripts| scripts.keys().cloned().collect())
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_project() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        fs::write(root.join("Cargo.toml"), "[package]\n").unwrap();
+        fs::write(
+            root.join("package.json"),
+            r#"{"scripts": {"dev": "vite", "build": "vite build"}}"#,
+        )
+        .unwrap();
+        fs::write(root.join("tsconfig.json"), "{}").unwrap();
+        fs::write(root.join("pnpm-lock.yaml"), "").unwrap();
+        fs::write(
+            root.join("Makefile"),
+            "CARGO := cargo\n.PHONY: build test\nbuild test: deps\n\t$(CARGO) build\n%.o: %.c\n\tcc\nlint:\n",
+        )
+        .unwrap();
+        fs::create_dir_all(root.join(".github").join("workflows")).unwrap();
+        fs::write(root.join(".github").join("workflows").join("ci.yml"), "on: push\n").unwrap();
+
+        let env = ProjectEnv::detect(root);
+        assert_eq!(env.languages, vec!["Rust", "TypeScript"]);
+        assert_eq!(env.package_managers, vec!["cargo", "pnpm"]);
+        assert_eq!(env.make_targets, vec!["build", "test", "lint"]);
+        assert_eq!(env.npm_scripts, vec!["dev", "build"]);
+        assert_eq!(env.ci, vec!["GitHub Actions"]);
+
+        let block = env.render().unwrap();
+        assert!(block.contains("- Make targets: build, test, lint (`make <target>`)"));
+        assert!(block.contains("- npm scripts: dev, build (`pnpm run <script>`)"));
+        assert!(block.contains("- CI: GitHub Actions (ci.yml)"));
+
+        assert!(ProjectEnv::detect(&root.join(".github")).render().is_none());
+    }
+
+    #[test]
+    fn test_cache_key_follows_marker_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        fs::write(root.join("Makefile"), "build:\n").unwrap();
+        let before = cache_key(root);
+        assert_eq!(cache_key(root), before);
+
+        fs::write(root.join("justfile"), "test:\n    cargo test\n").unwrap();
+        let with_just = cache_key(root);
+        assert_ne!(with_just, before);
+        assert_eq!(ProjectEnv::detect(root).just_recipes, vec!["test"]);
+
+        fs::write(root.join("Makefile"), "build:\nrelease:\n").unwrap();
+        assert_ne!(cache_key(root), with_just);
+    }
+}